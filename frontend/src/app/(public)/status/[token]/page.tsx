@@ -0,0 +1,130 @@
+'use client';
+
+import { useParams } from 'next/navigation';
+import { useQuery } from '@tanstack/react-query';
+import { apiFetch } from '@/lib/api';
+import { CandidateStatusPage } from '@/types/api';
+import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
+import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
+import { format } from 'date-fns';
+
+const APPLICATION_STATUS: Record<string, string> = {
+    active: 'Активна',
+    withdrawn: 'Отозвана',
+};
+
+const ATTEMPT_STATUS: Record<string, string> = {
+    completed: 'Завершён',
+    timeout: 'Время вышло',
+    escaped: 'Прерван',
+    needs_review: 'На проверке',
+};
+
+export default function CandidateStatusPageView() {
+    const params = useParams();
+    const token = params.token as string;
+    const { data, isLoading, error } = useQuery({
+        queryKey: ['candidate-status', token],
+        queryFn: () =>
+            apiFetch<CandidateStatusPage>(`/api/public/candidates/${encodeURIComponent(token)}/status`),
+        enabled: !!token,
+    });
+
+    if (isLoading) {
+        return <div className="container mx-auto py-10 text-center text-muted-foreground">Загрузка...</div>;
+    }
+    if (error || !data) {
+        return (
+            <div className="container mx-auto py-10 text-center text-destructive">
+                Ссылка недействительна или была отозвана.
+            </div>
+        );
+    }
+
+    return (
+        <div className="container mx-auto py-10 px-4 max-w-xl space-y-6">
+            <div className="text-center space-y-2">
+                <h1 className="text-2xl font-bold tracking-tight">{data.name}</h1>
+                <Badge className="text-sm">{data.status_label}</Badge>
+            </div>
+
+            {data.test_invites.length > 0 && (
+                <Card>
+                    <CardHeader>
+                        <CardTitle>Тесты</CardTitle>
+                    </CardHeader>
+                    <CardContent className="space-y-4">
+                        {data.test_invites.map((invite) => (
+                            <div key={invite.test_url} className="flex items-center justify-between gap-4">
+                                <div>
+                                    <div className="font-medium">{invite.test_title}</div>
+                                    <div className="text-sm text-muted-foreground">
+                                        До {format(new Date(invite.expires_at), 'dd.MM.yyyy HH:mm')}
+                                    </div>
+                                </div>
+                                <Button asChild size="sm">
+                                    <a href={invite.test_url}>Начать</a>
+                                </Button>
+                            </div>
+                        ))}
+                    </CardContent>
+                </Card>
+            )}
+
+            <Card>
+                <CardHeader>
+                    <CardTitle>Отклики</CardTitle>
+                </CardHeader>
+                <CardContent className="space-y-3">
+                    {data.applications.length === 0 ? (
+                        <div className="text-sm text-muted-foreground">Откликов пока нет.</div>
+                    ) : (
+                        data.applications.map((app, i) => (
+                            <div key={i} className="flex items-center justify-between gap-4">
+                                <div>
+                                    <div className="font-medium">{app.vacancy_title}</div>
+                                    {app.applied_at && (
+                                        <div className="text-sm text-muted-foreground">
+                                            {format(new Date(app.applied_at), 'dd.MM.yyyy')}
+                                        </div>
+                                    )}
+                                </div>
+                                <Badge variant="secondary">{APPLICATION_STATUS[app.status] ?? app.status}</Badge>
+                            </div>
+                        ))
+                    )}
+                </CardContent>
+            </Card>
+
+            {data.results.length > 0 && (
+                <Card>
+                    <CardHeader>
+                        <CardTitle>Результаты</CardTitle>
+                    </CardHeader>
+                    <CardContent className="space-y-3">
+                        {data.results.map((result, i) => (
+                            <div key={i} className="flex items-center justify-between gap-4">
+                                <div>
+                                    <div className="font-medium">{result.test_title}</div>
+                                    <div className="text-sm text-muted-foreground">
+                                        {ATTEMPT_STATUS[result.status] ?? result.status}
+                                        {result.completed_at &&
+                                            ` · ${format(new Date(result.completed_at), 'dd.MM.yyyy')}`}
+                                    </div>
+                                </div>
+                                {result.passed !== null && (
+                                    <Badge variant={result.passed ? 'default' : 'destructive'}>
+                                        {result.passed ? 'Пройден' : 'Не пройден'}
+                                        {result.percentage !== null &&
+                                            ` · ${Math.round(Number(result.percentage))}%`}
+                                    </Badge>
+                                )}
+                            </div>
+                        ))}
+                    </CardContent>
+                </Card>
+            )}
+        </div>
+    );
+}
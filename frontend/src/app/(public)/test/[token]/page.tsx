@@ -19,10 +19,11 @@ import { ModeToggle } from '@/components/mode-toggle';
 // Types (should actully be in generic types file)
 interface Question {
     id: number;
-    type: 'multiple_choice' | 'short_answer' | 'code';
+    type: 'multiple_choice' | 'short_answer' | 'code' | 'fill_in_blank';
     question: string;
     options?: string[];
     min_words?: number;
+    blanked_sentence?: string;
 }
 
 interface TestData {
@@ -35,6 +36,7 @@ interface TestData {
         test_type?: string;
         presentation_themes?: string[];
         presentation_extra_info?: string;
+        declaration_text?: string;
     };
     attempt: {
         id: string;
@@ -43,10 +45,12 @@ interface TestData {
         candidate_name: string;
         candidate_external_id?: string;
     };
+    requires_declaration?: boolean;
 }
 
 interface StartResponse {
     attempt_id: string;
+    access_token: string;
     status: string;
     started_at: string;
     expires_at: string;
@@ -61,7 +65,8 @@ export default function TestPage() {
     const params = useParams();
     const router = useRouter();
     const searchParams = useSearchParams();
-    const token = params.token as string;
+    // Starting the test swaps the link token for a new one; everything after uses that.
+    const [token, setToken] = useState(params.token as string);
     const autostart = searchParams.get('autostart') === 'true';
 
     // State
@@ -71,6 +76,7 @@ export default function TestPage() {
     const [answers, setAnswers] = useState<Record<number, any>>({});
     const [timeLeftSeconds, setTimeLeftSeconds] = useState<number | null>(null);
     const [isSubmitting, setIsSubmitting] = useState(false);
+    const [declarationAccepted, setDeclarationAccepted] = useState(false);
 
     // Presentation State
     const [presentationLink, setPresentationLink] = useState('');
@@ -91,8 +97,13 @@ export default function TestPage() {
 
     // Start Test Mutation
     const startMutation = useMutation({
-        mutationFn: () => apiFetch<StartResponse>(`/api/public/tests/${token}/start`, { method: 'POST' }),
+        mutationFn: () => apiFetch<StartResponse>(`/api/public/tests/${token}/start`, {
+            method: 'POST',
+            body: JSON.stringify({ accept_declaration: declarationAccepted }),
+        }),
         onSuccess: (data) => {
+            setToken(data.access_token);
+            window.history.replaceState(null, '', `/test/${data.access_token}`);
             setStarted(true);
             setQuestions(data.questions || []);
             // Initialize timer
@@ -292,7 +303,7 @@ export default function TestPage() {
 
     // Auto-start if already active OR if autostart query param is set (Task 15: skip intro when coming from modal)
     useEffect(() => {
-        if (!testData || started || startMutation.isPending) return;
+        if (!testData || started || startMutation.isPending || testData.requires_declaration) return;
 
         const shouldAutostart =
             testData.attempt.status === 'in_progress' || // Resume in-progress tests
@@ -477,7 +488,7 @@ export default function TestPage() {
         return `${mins.toString().padStart(2, '0')}:${secs.toString().padStart(2, '0')}`;
     };
 
-    if (isTestLoading || (started && questions.length === 0 && testData?.test.test_type !== 'presentation') || (autostart && !started && !testError)) {
+    if (isTestLoading || (started && questions.length === 0 && testData?.test.test_type !== 'presentation') || (autostart && !started && !testError && !testData?.requires_declaration)) {
         return (
             <div className="flex h-screen w-full items-center justify-center bg-muted/20">
                 <div className="text-center space-y-4">
@@ -572,12 +583,33 @@ export default function TestPage() {
                             </div>
                         </div>
 
+                        {testData.requires_declaration && (
+                            <div className="space-y-3 rounded-lg border p-6 bg-card">
+                                <h3 className="font-semibold flex items-center gap-2">
+                                    <ShieldAlert className="h-5 w-5 text-amber-600" />
+                                    {t('test.declaration_title')}
+                                </h3>
+                                <div className="prose prose-sm dark:prose-invert max-w-none text-muted-foreground">
+                                    <FormattedText text={test.declaration_text || t('test.declaration_default')} />
+                                </div>
+                                <label className="flex items-center gap-2 text-sm font-medium cursor-pointer">
+                                    <input
+                                        type="checkbox"
+                                        className="h-4 w-4"
+                                        checked={declarationAccepted}
+                                        onChange={(e) => setDeclarationAccepted(e.target.checked)}
+                                    />
+                                    {t('test.declaration_accept')}
+                                </label>
+                            </div>
+                        )}
+
                         <div className="pt-4">
                             <Button
                                 size="lg"
                                 className="w-full text-lg h-14"
                                 onClick={() => startMutation.mutate()}
-                                disabled={startMutation.isPending}
+                                disabled={startMutation.isPending || (testData.requires_declaration && !declarationAccepted)}
                             >
                                 {startMutation.isPending ? (
                                     <>
@@ -890,6 +922,30 @@ export default function TestPage() {
                                 </RadioGroup>
                             )}
 
+                            {currentQ.type === 'fill_in_blank' && currentQ.blanked_sentence && (
+                                <div className="space-y-4" key={currentQ.id}>
+                                    <p className="text-lg leading-relaxed">{currentQ.blanked_sentence}</p>
+                                    {currentQ.blanked_sentence.split('___').slice(1).map((_, idx) => {
+                                        const words: string[] = Array.isArray(answers[currentQ.id]) ? answers[currentQ.id] : [];
+                                        return (
+                                            <Input
+                                                key={idx}
+                                                placeholder={t('test.placeholder_blank').replace('{n}', String(idx + 1))}
+                                                value={words[idx] ?? ""}
+                                                onChange={(e) => {
+                                                    const next = [...words];
+                                                    next[idx] = e.target.value;
+                                                    setAnswers(prev => ({ ...prev, [currentQ.id]: next }));
+                                                }}
+                                                onBlur={() => {
+                                                    answerMutation.mutate({ question_id: currentQ.id, answer: answers[currentQ.id] ?? [] });
+                                                }}
+                                            />
+                                        );
+                                    })}
+                                </div>
+                            )}
+
                             {currentQ.type === 'short_answer' && (
                                 <div className="space-y-4" key={currentQ.id}>
                                     <Textarea
@@ -62,18 +62,25 @@ export default function CandidateProfilePage() {
                 })
             })
 
-            // If it's a conflict (409) or bad request (400) specifically for duplicates?
-            // Backend currently returns 500/Internal on constraint violation usually if not handled.
-            // But we used a basic Insert.
-            if (!response.ok) {
-                const text = await response.text()
-                if (text.includes("UniqueViolation") || response.status === 409) {
-                    toast.error(t('candidate_profile.already_applied'))
+            if (response.status === 409) {
+                const body = await response.json().catch(() => null)
+                const retryAfter = body?.error?.details?.retry_after
+                if (body?.error?.code === 'reapply_cooldown' && retryAfter) {
+                    const date = new Date(retryAfter).toLocaleDateString()
+                    toast.error(t('candidate_profile.reapply_cooldown').replace('{date}', date))
                     return
                 }
+            }
+            if (!response.ok) {
                 throw new Error('Failed to apply')
             }
 
+            const application = await response.json()
+            if (application.already_applied) {
+                toast.error(t('candidate_profile.already_applied'))
+                return
+            }
+
             toast.success(t('candidate_profile.apply_success'))
         } catch (e) {
             console.error(e)
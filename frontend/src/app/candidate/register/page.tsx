@@ -69,10 +69,10 @@ export default function RegisterPage() {
     // Fetch vacancies for the combobox
     const { data: vacancyData } = useQuery({
         queryKey: ['external-vacancies'],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies'),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies?per_page=100'),
     });
 
-    const vacancies = vacancyData?.vacancies || [];
+    const vacancies = vacancyData?.items || [];
     const hasInitialized = useRef(false);
 
     useEffect(() => {
@@ -83,6 +83,12 @@ export default function RegisterPage() {
         const tg = window?.Telegram?.WebApp;
         const searchParams = new URLSearchParams(window.location.search);
 
+        // Preselect the vacancy when arriving from a channel post
+        const vacancyParam = Number(searchParams.get('vacancy_id'));
+        if (Number.isInteger(vacancyParam) && vacancyParam > 0 && !form.getValues('vacancy_id')) {
+            form.setValue('vacancy_id', vacancyParam);
+        }
+
         if (tg) {
             tg.ready();
             tg.expand();
@@ -219,6 +225,9 @@ export default function RegisterPage() {
             if (values.vacancy_id) formData.append('vacancy_id', values.vacancy_id.toString());
             formData.append('cv', file!);
 
+            const referralCode = new URLSearchParams(window.location.search).get('ref');
+            if (referralCode) formData.append('referral_code', referralCode);
+
             const profileData = JSON.stringify({
                 telegram_username: telegramData?.username,
                 registration_date: new Date().toISOString(),
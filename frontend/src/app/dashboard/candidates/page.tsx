@@ -126,7 +126,7 @@ function CandidatesPageContent() {
     // Fetch vacancies to resolve titles
     const { data: vacancyData } = useQuery({
         queryKey: ['external-vacancies'],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/integration/external-vacancies'),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/integration/external-vacancies?per_page=100'),
     });
 
     // Fetch candidate applications when a candidate is selected
@@ -172,7 +172,7 @@ function CandidatesPageContent() {
     };
 
     const getVacancyTitle = (id: number) => {
-        return vacancyData?.vacancies.find(v => v.id === id)?.title || `${t('common.vacancy')} #${id}`;
+        return vacancyData?.items.find(v => v.id === id)?.title || `${t('common.vacancy')} #${id}`;
     };
 
     const handleStatusUpdate = async (candidateId: string, newStatus: string) => {
@@ -557,7 +557,7 @@ function CandidatesPageContent() {
                             </SelectTrigger>
                             <SelectContent className="max-w-[400px]">
                                 <SelectItem value="all">{t('common.all_vacancies')}</SelectItem>
-                                {vacancyData?.vacancies.map((v) => (
+                                {vacancyData?.items.map((v) => (
                                     <SelectItem key={v.id} value={v.id.toString()}>
                                         <span className="text-secondary-foreground font-medium truncate block">
                                             {v.title.replace(/<\/?[^>]+(>|$)/g, "")}
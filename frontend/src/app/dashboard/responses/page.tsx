@@ -58,13 +58,13 @@ export default function ResponsesPage() {
 
     const { data: vacancyData } = useQuery({
         queryKey: ["external-vacancies"],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>("/api/integration/external-vacancies"),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>("/api/integration/external-vacancies?per_page=100"),
         staleTime: 5 * 60 * 1000,
     })
 
     const vacancyName = useMemo(() => {
         const map = new Map<number, string>()
-        vacancyData?.vacancies?.forEach((v) => map.set(v.id, stripHtml(v.title)))
+        vacancyData?.items?.forEach((v) => map.set(v.id, stripHtml(v.title)))
         return (card: ResponseCard) =>
             map.get(card.vacancy_id) || stripHtml(card.vacancy_title) || `#${card.vacancy_id}`
     }, [vacancyData])
@@ -34,7 +34,7 @@ function VacanciesContent() {
     const dateLocale = language === 'ru' ? ruLocale : enUS;
     const { data, isLoading, error } = useQuery({
         queryKey: ['external-vacancies'],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/integration/external-vacancies'),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/integration/external-vacancies?per_page=100'),
     });
 
     const [selectedVacancy, setSelectedVacancy] = useState<ExternalVacancy | null>(null);
@@ -47,7 +47,7 @@ function VacanciesContent() {
     const cardRefs = useRef<Map<number, HTMLDivElement>>(new Map());
 
     useEffect(() => {
-        if (!highlightId || !data?.vacancies) return;
+        if (!highlightId || !data?.items) return;
         const id = Number(highlightId);
         const t1 = setTimeout(() => {
             const el = cardRefs.current.get(id);
@@ -131,12 +131,12 @@ function VacanciesContent() {
                     </p>
                 </div>
                 <Badge variant="secondary" className="text-xs">
-                    {data?.vacancies.length || 0} {t('dashboard.vacancies.active')}
+                    {data?.total || 0} {t('dashboard.vacancies.active')}
                 </Badge>
             </div>
 
             <div className="grid gap-3 md:grid-cols-2 lg:grid-cols-3 xl:grid-cols-4">
-                {data?.vacancies.map((vacancy) => {
+                {data?.items.map((vacancy) => {
                     const company = getCompany(vacancy.company_id);
                     return (
                         <Card
@@ -198,7 +198,7 @@ function VacanciesContent() {
                     );
                 })}
 
-                {data?.vacancies.length === 0 && (
+                {data?.items.length === 0 && (
                     <div className="col-span-full">
                         <Card className="border-dashed">
                             <CardContent className="flex flex-col items-center justify-center h-48 text-center">
@@ -68,7 +68,7 @@ export function CandidateProfileCard({ candidate, onCvUpdated, isPublicView = fa
     // Fetch vacancies to resolve titles
     const { data: vacancyData } = useQuery({
         queryKey: ['external-vacancies'],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies'),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies?per_page=100'),
     });
 
     // Fetch candidate applications
@@ -79,7 +79,7 @@ export function CandidateProfileCard({ candidate, onCvUpdated, isPublicView = fa
     });
 
     const getVacancyTitle = (id: number) => {
-        return vacancyData?.vacancies.find(v => v.id === id)?.title || `Vacancy #${id}`;
+        return vacancyData?.items.find(v => v.id === id)?.title || `Vacancy #${id}`;
     };
 
     interface HistoryItem {
@@ -585,7 +585,7 @@ export function CandidateProfileCard({ candidate, onCvUpdated, isPublicView = fa
                         </Button>
                         {candidate.cv_url && (
                             <p className="text-xs text-muted-foreground text-center mt-2">
-                                {t('candidate_profile.current_cv')}: {candidate.cv_url.split('/').pop()?.substring(37) || "CV uploaded"}
+                                {t('candidate_profile.current_cv')}: {candidate.cv_url.split('?')[0].split('/').pop()?.substring(37) || "CV uploaded"}
                             </p>
                         )}
                     </div>
@@ -27,7 +27,7 @@ export function VacanciesTab({ onSelectVacancy, selectButtonText }: VacanciesTab
     const dateLocale = language === 'ru' ? ruLocale : enUS;
     const { data, isLoading, error } = useQuery({
         queryKey: ['external-vacancies'],
-        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies'),
+        queryFn: () => apiFetch<ExternalVacancyListResponse>('/api/external-vacancies?per_page=100'),
     });
 
     const [selectedVacancy, setSelectedVacancy] = useState<ExternalVacancy | null>(null);
@@ -44,10 +44,10 @@ export function VacanciesTab({ onSelectVacancy, selectButtonText }: VacanciesTab
     const getCompany = (id: number | null) => id ? companyMap.get(id) : null;
 
     const filteredVacancies = useMemo(() => {
-        if (!data?.vacancies) return [];
-        if (!searchQuery) return data.vacancies;
+        if (!data?.items) return [];
+        if (!searchQuery) return data.items;
         const lowerQuery = searchQuery.toLowerCase();
-        return data.vacancies.filter(v =>
+        return data.items.filter(v =>
             v.title.toLowerCase().includes(lowerQuery) ||
             v.city.toLowerCase().includes(lowerQuery) ||
             v.direction.toLowerCase().includes(lowerQuery) ||
@@ -46,6 +46,8 @@ export async function apiFetch<T>(endpoint: string, options: FetchOptions = {}):
             const errorData = await response.json();
             if (errorData.message) {
                 errorMessage = errorData.message;
+            } else if (errorData.error && typeof errorData.error === 'object') {
+                errorMessage = errorData.error.message || errorData.error.code || errorMessage;
             } else if (errorData.error) {
                 errorMessage = errorData.error;
             } else if (typeof errorData === 'string') {
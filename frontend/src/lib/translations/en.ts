@@ -139,6 +139,9 @@ export const en = {
         continue_test: "Continue Test",
         continue_presentation: "Continue Presentation",
         exit_note: "Note: Exiting the test window will result in a failed attempt.",
+        declaration_title: "Honesty Declaration",
+        declaration_default: "I confirm that I will complete this test on my own, without help from other people or unauthorized materials.",
+        declaration_accept: "I have read and accept the declaration",
         question: "Question",
         of: "of",
         previous: "Previous",
@@ -148,13 +151,15 @@ export const en = {
         default_instructions: "Please read each question carefully. You cannot pause the timer once started. Do not close this window.",
         no_description: "No description available",
         no_comment: "No comment provided",
+        placeholder_blank: "Blank {n}",
         placeholder_answer: "Type your answer here...",
         min_words: "Minimum {count} words required.",
         answer_required: "Please answer the current question before proceeding.",
         types: {
             multiple_choice: "MULTIPLE CHOICE",
             short_answer: "SHORT ANSWER",
-            code: "CODE"
+            code: "CODE",
+            fill_in_blank: "FILL IN THE BLANK"
         },
         submit_success_title: "Test Completed",
         submit_success_desc: "Thank you for completing the test. Your results have been saved. You can view details and your grade in the profile by clicking the 'Activity History' button. We will announce the final decision a bit later.",
@@ -183,6 +188,7 @@ export const en = {
         apply_success: "You have successfully applied for the vacancy",
         apply_error: "Failed to apply. You might have already applied.",
         already_applied: "You have already applied for this vacancy",
+        reapply_cooldown: "You can apply to this vacancy again after {date}",
         tab_profile: "Profile",
         tab_vacancies: "Vacancies",
         loading: "Loading profile...",
@@ -139,6 +139,9 @@ export const ru = {
         continue_test: "Продолжить тест",
         continue_presentation: "Продолжить презентацию",
         exit_note: "Примечание: Выход из окна теста приведет к неудачной попытке.",
+        declaration_title: "Декларация честности",
+        declaration_default: "Я подтверждаю, что выполню этот тест самостоятельно, без помощи других людей и без использования запрещённых материалов.",
+        declaration_accept: "Я прочитал(а) и принимаю декларацию",
         question: "Вопрос",
         of: "из",
         previous: "Предыдущий",
@@ -148,13 +151,15 @@ export const ru = {
         default_instructions: "Пожалуйста, внимательно прочитайте каждый вопрос. Вы не сможете приостановить таймер после запуска. Не закрывайте это окно.",
         no_description: "Описание отсутствует",
         no_comment: "Комментарий отсутствует",
+        placeholder_blank: "Пропуск {n}",
         placeholder_answer: "Введите ваш ответ здесь...",
         min_words: "Минимум {count} слов.",
         answer_required: "Пожалуйста, ответьте на текущий вопрос перед тем, как продолжить.",
         types: {
             multiple_choice: "МНОЖЕСТВЕННЫЙ ВЫБОР",
             short_answer: "КОРОТКИЙ ОТВЕТ",
-            code: "КОД"
+            code: "КОД",
+            fill_in_blank: "ЗАПОЛНИТЕ ПРОПУСК"
         },
         submit_success_title: "Тест завершен",
         submit_success_desc: "Спасибо за прохождение теста. Ваши результаты сохранены. Вы можете увидеть детали и оценку в личном кабинете, нажав на кнопку 'История активности'. Окончательное решение мы вам объявим немного позже.",
@@ -183,6 +188,7 @@ export const ru = {
         apply_success: "Вы успешно откликнулись на вакансию",
         apply_error: "Не удалось откликнуться. Возможно, вы уже откликнулись.",
         already_applied: "Вы уже откликнулись на эту вакансию",
+        reapply_cooldown: "Повторно откликнуться на эту вакансию можно после {date}",
         tab_profile: "Профиль",
         tab_vacancies: "Вакансии",
         loading: "Загрузка профиля...",
@@ -6,18 +6,24 @@ export interface QuestionDetails {
   // Add other specific fields as needed based on QuestionType
 }
 
-export type QuestionType = 'multiple_choice' | 'text' | 'code' | 'short_answer';
+export type QuestionType = 'multiple_choice' | 'text' | 'code' | 'short_answer' | 'fill_in_blank';
+
+export type QuestionDifficulty = 'easy' | 'medium' | 'hard';
 
 export interface CreateQuestion {
   type: QuestionType;
   question: string;
   points: number;
+  difficulty?: QuestionDifficulty;
   options?: string[]; // Flattened from details for easier usage if needed, or keep in details
   correct_answer?: number;
   min_words?: number;
   expected_keywords?: string[];
   ai_grading?: boolean;
   explanation?: string;
+  blanked_sentence?: string; // For fill in the blank, "___" marks each gap
+  correct_words?: string[];
+  case_sensitive?: boolean;
   _uid?: string; // Frontend animation key
   // ... map other details
 }
@@ -83,6 +89,29 @@ export interface VacancyPublicListResponse {
   items: VacancyPublicSummary[];
 }
 
+export interface CandidateStatusPage {
+  name: string;
+  status: string;
+  status_label: string;
+  applications: {
+    vacancy_title: string;
+    status: string;
+    applied_at: string | null;
+  }[];
+  test_invites: {
+    test_title: string;
+    expires_at: string;
+    test_url: string;
+  }[];
+  results: {
+    test_title: string;
+    status: string;
+    completed_at: string | null;
+    passed: boolean | null;
+    percentage: string | null;
+  }[];
+}
+
 export interface VacancyListResponse {
   items: Vacancy[];
 }
@@ -101,11 +130,15 @@ export interface Test {
   shuffle_questions?: boolean;
   shuffle_options?: boolean;
   show_results_immediately?: boolean;
+  reveal_correct_answers?: boolean;
   test_type?: 'question_based' | 'presentation';
   presentation_themes?: string[];
   presentation_extra_info?: string;
+  language?: TestLanguage;
 }
 
+export type TestLanguage = 'ru' | 'tg' | 'en';
+
 export interface CreateTestPayload {
   title: string;
   description?: string;
@@ -116,9 +149,18 @@ export interface CreateTestPayload {
   shuffle_questions?: boolean;
   shuffle_options?: boolean;
   show_results_immediately?: boolean;
+  reveal_correct_answers?: boolean;
   test_type?: 'question_based' | 'presentation';
   presentation_themes?: string[];
   presentation_extra_info?: string;
+  language?: TestLanguage;
+}
+
+export interface CandidateProfileData {
+  city?: string | null;
+  education?: string | null;
+  languages: string[];
+  experience_years?: number | null;
 }
 
 export interface Candidate {
@@ -128,9 +170,12 @@ export interface Candidate {
   telegram_id?: number;
   phone?: string;
   cv_url?: string;
+  photo_url?: string;
+  cv_portfolio?: string[] | null;
   dob?: string;
   vacancy_id?: number;
   profile_data?: any;
+  profile?: CandidateProfileData | null;
   ai_rating?: number;
   ai_comment?: string;
   status: string;
@@ -162,6 +207,7 @@ export interface PublicTestSummary {
   test_type?: 'question_based' | 'presentation';
   presentation_themes?: string[];
   presentation_extra_info?: string;
+  language: TestLanguage;
 }
 
 export interface PublicAttemptSummary {
@@ -194,14 +240,28 @@ export interface SubmitTestRequest {
   answers: any[]; // Define more specifically if needed
 }
 
+export type ResultState = 'shown' | 'hidden' | 'pending_review';
+
+export interface QuestionResult {
+  question_id: number;
+  is_correct: boolean;
+  points_earned: number;
+  max_points: number;
+  correct_answer?: string; // only with reveal_correct_answers
+}
+
+// Also returned by GET /api/public/tests/:token/result.
 export interface SubmitTestResponse {
   attempt_id: string;
   status: string;
-  score: number;
-  max_score: number;
-  percentage: number;
-  passed: boolean;
+  result_state: ResultState;
   show_results: boolean;
+  // Present only when result_state is 'shown'
+  score?: number;
+  max_score?: number;
+  percentage?: number;
+  passed?: boolean;
+  questions?: QuestionResult[];
   message: string;
 }
 
@@ -223,7 +283,11 @@ export interface ExternalCompany {
 }
 
 export interface ExternalVacancyListResponse {
-  vacancies: ExternalVacancy[];
+  items: ExternalVacancy[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
   companies: ExternalCompany[];
 }
 
@@ -0,0 +1,10 @@
+-- Soft delete for candidates: rows are kept for the audit trail and aggregate
+-- statistics. GDPR erasure additionally NULLs personal data, so name/email must
+-- become nullable.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS deleted_reason TEXT;
+
+ALTER TABLE candidates ALTER COLUMN name DROP NOT NULL;
+ALTER TABLE candidates ALTER COLUMN email DROP NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_candidates_active ON candidates(created_at) WHERE deleted_at IS NULL;
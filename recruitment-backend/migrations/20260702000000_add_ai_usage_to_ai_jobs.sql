@@ -0,0 +1,8 @@
+-- Token accounting for AI generation jobs. usage_by_model maps the serving model
+-- to {prompt_tokens, completion_tokens, calls}.
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS prompt_tokens BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS completion_tokens BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS calls_made INT NOT NULL DEFAULT 0;
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS usage_by_model JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX IF NOT EXISTS idx_ai_jobs_created_at ON ai_jobs(created_at);
@@ -0,0 +1,6 @@
+-- Applications get their own lifecycle so candidates can withdraw without
+-- touching the candidate-level status.
+ALTER TABLE candidate_applications ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active';
+ALTER TABLE candidate_applications ADD COLUMN IF NOT EXISTS updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW();
+
+UPDATE candidate_applications SET updated_at = created_at;
@@ -0,0 +1,3 @@
+-- Each presentation attempt gets a single theme from the test's list instead of
+-- the whole list, so candidates cannot share topics ahead of time.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS assigned_theme JSONB;
@@ -0,0 +1,4 @@
+-- Candidates confirm an honesty declaration before starting a test; wording is per test.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS show_honesty_declaration BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS declaration_text TEXT;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS honesty_declaration_accepted BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,13 @@
+-- Referral codes let HR attribute registrations to recruiting campaigns.
+CREATE TABLE IF NOT EXISTS referral_codes (
+    id              UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    code            TEXT NOT NULL UNIQUE,
+    campaign_name   TEXT NOT NULL,
+    created_by      UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS referral_code TEXT;
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS referral_source TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_candidates_referral_source ON candidates(referral_source);
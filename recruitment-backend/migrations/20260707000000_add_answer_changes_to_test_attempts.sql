@@ -0,0 +1,2 @@
+-- Number of times a candidate replaced an already-saved answer with a different one.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS answer_changes INT NOT NULL DEFAULT 0;
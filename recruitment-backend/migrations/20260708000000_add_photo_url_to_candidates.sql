@@ -0,0 +1,2 @@
+-- Avatar uploaded from the Telegram mini-app, stored under uploads/photos.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS photo_url TEXT;
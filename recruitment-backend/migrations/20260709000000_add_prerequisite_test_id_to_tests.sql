@@ -0,0 +1,2 @@
+-- A test may require a passed attempt on another test before invites are issued.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS prerequisite_test_id UUID REFERENCES tests(id) ON DELETE SET NULL;
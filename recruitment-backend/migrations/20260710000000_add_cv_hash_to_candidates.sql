@@ -0,0 +1,4 @@
+-- SHA-256 of the uploaded CV, used to reuse an identical file instead of storing a copy.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS cv_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_candidates_cv_hash ON candidates(cv_hash);
@@ -0,0 +1,8 @@
+-- Hourly copies of the dashboard stats, kept for historical trend charts.
+CREATE TABLE IF NOT EXISTS dashboard_snapshots (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    stats JSONB NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dashboard_snapshots_captured_at ON dashboard_snapshots(captured_at);
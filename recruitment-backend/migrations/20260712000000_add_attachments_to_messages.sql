@@ -0,0 +1,5 @@
+-- Files exchanged in the Telegram chat: stored upload path (or the sent URL) and its kind.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS attachment_path TEXT;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS attachment_type TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_messages_candidate_created ON messages(candidate_id, created_at DESC, id DESC);
@@ -0,0 +1,4 @@
+-- Location of the IP that started the attempt, and whether it is outside EXPECTED_COUNTRY.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS country TEXT;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS city TEXT;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS suspicious_geo BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,6 @@
+-- Overrides for the AI prompts embedded in ai_service.rs, picked up without a restart.
+CREATE TABLE IF NOT EXISTS ai_prompts (
+    key TEXT PRIMARY KEY,
+    content JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Skill tags extracted from the CV by AI, for filtering candidates by skill.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS skills TEXT[];
+
+CREATE INDEX IF NOT EXISTS idx_candidates_skills ON candidates USING GIN (skills);
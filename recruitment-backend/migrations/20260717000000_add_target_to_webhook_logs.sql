@@ -0,0 +1,2 @@
+-- Who delivers a queued log: 'webhook' (bot and subscriptions) or 'onef' (1F pushes).
+ALTER TABLE webhook_logs ADD COLUMN IF NOT EXISTS target TEXT NOT NULL DEFAULT 'webhook';
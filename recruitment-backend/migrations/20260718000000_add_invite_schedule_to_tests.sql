@@ -0,0 +1,7 @@
+-- Per-test invite defaults: expiry used when an invite omits expires_in_hours,
+-- and reminder offsets (hours before expiry) for attempts still open.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS default_invite_expiry_hours INTEGER;
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS reminder_hours_before INTEGER[] NOT NULL DEFAULT '{}';
+
+-- Offsets from reminder_hours_before already sent for the attempt.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS reminders_sent JSONB NOT NULL DEFAULT '[]'::jsonb;
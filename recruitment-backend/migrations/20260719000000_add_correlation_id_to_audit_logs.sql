@@ -0,0 +1,4 @@
+-- Request correlation ID (X-Request-Id) of the call that wrote the entry
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS correlation_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_correlation_id ON audit_logs(correlation_id);
@@ -0,0 +1,6 @@
+-- Reviewer who claimed a needs_review attempt, and when
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS reviewed_by UUID;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_test_attempts_review_queue
+    ON test_attempts(completed_at) WHERE status = 'needs_review';
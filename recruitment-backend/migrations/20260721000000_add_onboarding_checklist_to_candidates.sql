@@ -0,0 +1,3 @@
+-- First-week checklist generated on acceptance, and the indices the candidate ticked off
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS onboarding_checklist JSONB;
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS checklist_completed_items JSONB;
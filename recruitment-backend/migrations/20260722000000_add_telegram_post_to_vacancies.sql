@@ -0,0 +1,3 @@
+-- Channel post created by publish-telegram, kept so it can be edited or deleted later
+ALTER TABLE vacancies ADD COLUMN IF NOT EXISTS telegram_chat_id TEXT;
+ALTER TABLE vacancies ADD COLUMN IF NOT EXISTS telegram_message_id BIGINT;
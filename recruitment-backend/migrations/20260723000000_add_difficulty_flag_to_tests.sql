@@ -0,0 +1,5 @@
+-- Pass-rate based difficulty flag, the pending AI replacement proposal and when one was last applied
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS difficulty_flag TEXT
+    CHECK (difficulty_flag IN ('too_easy', 'too_hard'));
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS difficulty_proposal JSONB;
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS difficulty_adjusted_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+-- CVs are served only through signed links; store their path relative to the upload root.
+UPDATE candidates
+SET cv_url = regexp_replace(cv_url, '^(\./)?/?uploads/', '')
+WHERE cv_url ~ '^(\./)?/?uploads/';
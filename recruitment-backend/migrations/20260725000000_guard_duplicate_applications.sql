@@ -0,0 +1,6 @@
+-- One live application per candidate and vacancy. Withdrawn applications stay
+-- as history, so the candidate can apply again after withdrawing.
+ALTER TABLE candidate_applications DROP CONSTRAINT IF EXISTS candidate_applications_candidate_id_vacancy_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_candidate_applications_live
+    ON candidate_applications(candidate_id, vacancy_id)
+    WHERE status <> 'withdrawn';
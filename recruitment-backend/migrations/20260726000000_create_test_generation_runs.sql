@@ -0,0 +1,20 @@
+-- One row per AI generation that produced a test: the request blueprint, the
+-- generator's log and counts, so odd questions can be traced back later.
+CREATE TABLE IF NOT EXISTS test_generation_runs (
+    id                  UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    test_id             UUID NOT NULL REFERENCES tests(id) ON DELETE CASCADE,
+    ai_job_id           UUID REFERENCES ai_jobs(id) ON DELETE SET NULL,
+    blueprint           JSONB NOT NULL DEFAULT '{}',
+    logs                JSONB NOT NULL DEFAULT '[]',
+    models              TEXT[] NOT NULL DEFAULT '{}',
+    requested_questions INTEGER NOT NULL,
+    returned_questions  INTEGER NOT NULL DEFAULT 0,
+    duplicates_removed  INTEGER NOT NULL DEFAULT 0,
+    topup_rounds        INTEGER NOT NULL DEFAULT 0,
+    delivered_questions INTEGER NOT NULL,
+    prompt_tokens       BIGINT NOT NULL DEFAULT 0,
+    completion_tokens   BIGINT NOT NULL DEFAULT 0,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_test_generation_runs_test ON test_generation_runs(test_id, created_at DESC);
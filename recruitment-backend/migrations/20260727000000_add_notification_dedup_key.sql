@@ -0,0 +1,6 @@
+-- Bot notifications keyed by SHA256(event_type || attempt_id) are queued at
+-- most once, so retried handlers and overlapping sweeps don't message the
+-- candidate twice. Rows without a key are never de-duplicated.
+ALTER TABLE webhook_logs ADD COLUMN IF NOT EXISTS notification_dedup_key TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_logs_notification_dedup_key
+    ON webhook_logs(notification_dedup_key);
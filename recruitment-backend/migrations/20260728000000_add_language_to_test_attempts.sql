@@ -0,0 +1,4 @@
+-- Language of the candidate-facing test UI and Telegram messages for an attempt.
+ALTER TABLE test_attempts
+    ADD COLUMN IF NOT EXISTS language TEXT NOT NULL DEFAULT 'ru'
+    CHECK (language IN ('ru', 'tg'));
@@ -0,0 +1,7 @@
+-- Supports the updated_since filter and latest-attempt lookup of
+-- GET /api/integration/candidates/statuses.
+CREATE INDEX IF NOT EXISTS idx_test_attempts_updated_at_email
+    ON test_attempts(updated_at, candidate_email);
+CREATE INDEX IF NOT EXISTS idx_test_attempts_email_updated_at
+    ON test_attempts(candidate_email, updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_candidates_updated_at ON candidates(updated_at);
@@ -0,0 +1,13 @@
+-- Candidate reports about ambiguous questions or wrong answer keys.
+CREATE TABLE IF NOT EXISTS question_feedback (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    attempt_id UUID NOT NULL REFERENCES test_attempts(id) ON DELETE CASCADE,
+    question_id INT NOT NULL,
+    text TEXT NOT NULL,
+    reviewed BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_question_feedback_attempt_id ON question_feedback(attempt_id);
+CREATE INDEX IF NOT EXISTS idx_question_feedback_unreviewed
+    ON question_feedback(created_at) WHERE reviewed = FALSE;
@@ -0,0 +1,8 @@
+-- One row per internal vacancy that is also published on Koinoti Nav and/or 1F.
+CREATE TABLE IF NOT EXISTS vacancy_links (
+    vacancy_id UUID PRIMARY KEY REFERENCES vacancies(id) ON DELETE CASCADE,
+    koinotinav_id BIGINT UNIQUE,
+    onef_id TEXT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+-- Forum topic in the candidate's Telegram chat that HR messages are posted to.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS telegram_thread_id BIGINT;
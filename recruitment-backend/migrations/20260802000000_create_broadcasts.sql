@@ -0,0 +1,28 @@
+-- HR messages to a filtered candidate segment, sent one Telegram message per recipient.
+CREATE TABLE IF NOT EXISTS broadcasts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    filter JSONB NOT NULL DEFAULT '{}'::jsonb,
+    message_template TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'sending',
+    total_recipients INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS broadcast_recipients (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    broadcast_id UUID NOT NULL REFERENCES broadcasts(id) ON DELETE CASCADE,
+    candidate_id UUID REFERENCES candidates(id) ON DELETE SET NULL,
+    telegram_id BIGINT,
+    telegram_thread_id BIGINT,
+    message TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    error TEXT,
+    attempted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_broadcast_recipients_broadcast_id
+    ON broadcast_recipients(broadcast_id, status);
+CREATE INDEX IF NOT EXISTS idx_broadcast_recipients_pending
+    ON broadcast_recipients(created_at) WHERE status IN ('pending', 'sending');
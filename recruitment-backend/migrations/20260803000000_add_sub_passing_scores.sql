@@ -0,0 +1,7 @@
+-- Separate pass marks for multiple-choice and open questions, and the
+-- per-kind percentages each attempt scored.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS passing_score_mcq NUMERIC(5,2);
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS passing_score_open NUMERIC(5,2);
+
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS mcq_score NUMERIC(5,2);
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS open_score NUMERIC(5,2);
@@ -0,0 +1,8 @@
+-- Where an invite came from, for filtering and per-source analytics.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'integration';
+
+UPDATE test_attempts
+SET source = metadata->>'source'
+WHERE metadata->>'source' IN ('onef', 'auto_invite', 'retake');
+
+CREATE INDEX IF NOT EXISTS idx_test_attempts_source ON test_attempts (source);
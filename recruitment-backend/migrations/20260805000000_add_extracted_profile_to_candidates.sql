@@ -0,0 +1,6 @@
+-- Structured CV profile (skills, experience, last position, education,
+-- languages) extracted by AI; the skills path is indexed for filtering.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS extracted_profile JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_candidates_extracted_skills
+    ON candidates USING GIN ((extracted_profile -> 'skills'));
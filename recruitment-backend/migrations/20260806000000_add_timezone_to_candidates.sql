@@ -0,0 +1,3 @@
+-- IANA timezone (e.g. 'Asia/Dushanbe') used to show deadlines in Telegram
+-- messages in the candidate's local time; NULL means UTC.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS timezone TEXT;
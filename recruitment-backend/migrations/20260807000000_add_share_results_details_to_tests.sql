@@ -0,0 +1,2 @@
+-- Send candidates a per-question breakdown once their attempt is graded.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS share_results_details BOOLEAN NOT NULL DEFAULT FALSE;
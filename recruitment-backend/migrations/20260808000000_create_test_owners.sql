@@ -0,0 +1,14 @@
+-- HR users allowed to see a test. Existing tests are owned by their creator.
+CREATE TABLE IF NOT EXISTS test_owners (
+    test_id UUID NOT NULL REFERENCES tests(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (test_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_test_owners_user_id ON test_owners(user_id);
+
+INSERT INTO test_owners (test_id, user_id)
+SELECT id, created_by FROM tests
+WHERE created_by IS NOT NULL
+ON CONFLICT DO NOTHING;
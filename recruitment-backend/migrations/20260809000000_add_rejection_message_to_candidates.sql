@@ -0,0 +1,3 @@
+-- Last AI-generated rejection message, kept so HR can reopen or resend it
+-- without another LLM call.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS rejection_message TEXT;
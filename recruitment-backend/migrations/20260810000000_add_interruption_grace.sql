@@ -0,0 +1,5 @@
+-- Connection-loss grace: time lost to network gaps is added back to the
+-- attempt's deadline, up to the test's max_grace_seconds.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS max_grace_seconds INTEGER NOT NULL DEFAULT 300;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS interruption_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS previous_heartbeat_at TIMESTAMPTZ;
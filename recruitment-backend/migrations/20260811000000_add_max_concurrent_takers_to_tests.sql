@@ -0,0 +1,2 @@
+-- Most attempts of a test that may be in progress at once; NULL means no limit.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS max_concurrent_takers INTEGER;
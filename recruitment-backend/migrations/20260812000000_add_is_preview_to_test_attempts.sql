@@ -0,0 +1,4 @@
+-- Attempts HR opens to walk through a test; kept out of listings and stats.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS is_preview BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_test_attempts_preview ON test_attempts (created_at) WHERE is_preview;
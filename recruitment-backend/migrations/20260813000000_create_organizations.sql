@@ -0,0 +1,31 @@
+-- Tenants. Rows that existed before organizations belong to the default one.
+CREATE TABLE IF NOT EXISTS organizations (
+    id         UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name       TEXT NOT NULL,
+    slug       TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO organizations (id, name, slug)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Koinoti Nav', 'default')
+ON CONFLICT (id) DO NOTHING;
+
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE vacancies ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+-- Login puts the user's organization into the JWT.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+
+CREATE INDEX IF NOT EXISTS idx_tests_organization ON tests (organization_id);
+CREATE INDEX IF NOT EXISTS idx_candidates_organization ON candidates (organization_id);
+CREATE INDEX IF NOT EXISTS idx_test_attempts_organization ON test_attempts (organization_id);
+CREATE INDEX IF NOT EXISTS idx_vacancies_organization ON vacancies (organization_id);
+CREATE INDEX IF NOT EXISTS idx_ai_jobs_organization ON ai_jobs (organization_id);
@@ -0,0 +1,7 @@
+-- Candidates who blocked the bot: consecutive 403s from Telegram, and the
+-- flag set once they reach the limit. An inbound message clears both.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS telegram_send_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS telegram_unreachable BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_candidates_telegram_unreachable
+    ON candidates (telegram_unreachable) WHERE telegram_unreachable;
@@ -0,0 +1,8 @@
+-- Candidate last activity is the latest of registration, last application,
+-- last test attempt change and last inbound message, looked up per candidate.
+-- Attempts are served by idx_test_attempts_email_updated_at; these cover the
+-- other two with a single backward index scan each.
+CREATE INDEX IF NOT EXISTS idx_candidate_applications_candidate_created
+    ON candidate_applications (candidate_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_messages_candidate_inbound_created
+    ON messages (candidate_id, created_at DESC) WHERE direction = 'inbound';
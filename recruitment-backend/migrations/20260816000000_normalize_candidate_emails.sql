@@ -0,0 +1,34 @@
+-- Candidate emails are stored trimmed and lowercased and are unique
+-- regardless of case. email_hash keys the address with a server secret so
+-- duplicates can be matched across organizations without exposing it; the
+-- application fills it for existing rows on startup.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS email_hash TEXT;
+
+UPDATE candidates c
+SET email = LOWER(TRIM(c.email))
+WHERE c.email IS NOT NULL
+  AND c.email <> LOWER(TRIM(c.email))
+  AND NOT EXISTS (
+      SELECT 1 FROM candidates o
+      WHERE o.id <> c.id AND LOWER(TRIM(o.email)) = LOWER(TRIM(c.email))
+  );
+
+DO $$
+DECLARE
+    clashes TEXT;
+BEGIN
+    SELECT string_agg(email_lower, ', ') INTO clashes
+    FROM (
+        SELECT LOWER(TRIM(email)) AS email_lower
+        FROM candidates
+        WHERE email IS NOT NULL
+        GROUP BY 1
+        HAVING COUNT(*) > 1
+    ) dup;
+    IF clashes IS NOT NULL THEN
+        RAISE EXCEPTION 'Candidates share an email that differs only in case or spacing: %. Merge or erase the duplicates, then restart.', clashes;
+    END IF;
+END $$;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_candidates_email_lower ON candidates (LOWER(email));
+CREATE INDEX IF NOT EXISTS idx_candidates_email_hash ON candidates (email_hash);
@@ -0,0 +1,9 @@
+-- Starting a test replaces the link token; the replaced one stays valid
+-- for a short grace period after access_token_rotated_at.
+ALTER TABLE test_attempts
+    ADD COLUMN IF NOT EXISTS previous_access_token TEXT,
+    ADD COLUMN IF NOT EXISTS access_token_rotated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_test_attempts_previous_access_token
+    ON test_attempts (previous_access_token)
+    WHERE previous_access_token IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Weekly and on-demand AI critiques of each test's question set.
+CREATE TABLE IF NOT EXISTS test_quality_reports (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    test_id UUID NOT NULL REFERENCES tests(id) ON DELETE CASCADE,
+    report JSONB NOT NULL,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_test_quality_reports_test_generated
+    ON test_quality_reports (test_id, generated_at DESC);
@@ -0,0 +1,9 @@
+-- Token for the candidate-facing status page, issued at registration.
+-- Setting status_token_revoked disables the link without losing the token.
+ALTER TABLE candidates
+    ADD COLUMN IF NOT EXISTS status_token TEXT,
+    ADD COLUMN IF NOT EXISTS status_token_revoked BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_candidates_status_token
+    ON candidates (status_token)
+    WHERE status_token IS NOT NULL;
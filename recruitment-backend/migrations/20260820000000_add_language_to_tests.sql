@@ -0,0 +1,4 @@
+-- Language the test's questions are written in; the candidate UI localizes by it.
+ALTER TABLE tests
+    ADD COLUMN IF NOT EXISTS language TEXT NOT NULL DEFAULT 'ru'
+    CHECK (language IN ('ru', 'tg', 'en'));
@@ -0,0 +1,15 @@
+-- Failed AI jobs are retried; after MAX_JOB_ATTEMPTS claims they are parked as
+-- 'dead' with the last error in dead_letter_reason until HR retries them by hand.
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS attempts INT NOT NULL DEFAULT 0;
+ALTER TABLE ai_jobs ADD COLUMN IF NOT EXISTS dead_letter_reason TEXT;
+
+ALTER TABLE ai_jobs DROP CONSTRAINT IF EXISTS ai_jobs_status_check;
+ALTER TABLE ai_jobs ADD CONSTRAINT ai_jobs_status_check
+    CHECK (status IN ('pending', 'running', 'succeeded', 'failed', 'dead'));
+
+-- Jobs that already failed were never retried; park them instead of retrying now.
+UPDATE ai_jobs
+SET status = 'dead', attempts = 5, dead_letter_reason = COALESCE(error, 'Failed before retries were introduced')
+WHERE status = 'failed';
+
+CREATE INDEX IF NOT EXISTS idx_ai_jobs_dead ON ai_jobs(organization_id, finished_at) WHERE status = 'dead';
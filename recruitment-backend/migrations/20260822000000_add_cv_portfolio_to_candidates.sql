@@ -0,0 +1,3 @@
+-- Extra documents uploaded with the CV at registration (portfolio_file_* fields):
+-- a JSON array of stored upload paths.
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS cv_portfolio JSONB;
@@ -0,0 +1,3 @@
+-- Whether candidates shown their results also see the right answers to the
+-- multiple-choice questions they got wrong.
+ALTER TABLE tests ADD COLUMN IF NOT EXISTS reveal_correct_answers BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,4 @@
+-- Time HR added to a running attempt. The answering window is
+-- started_at + duration + extension_seconds, so an extension is not cut off
+-- by the test's duration.
+ALTER TABLE test_attempts ADD COLUMN IF NOT EXISTS extension_seconds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+-- Token usage of AI generations that run inside a request rather than as an
+-- ai_jobs row. The usage report adds these to the queued jobs' usage.
+CREATE TABLE IF NOT EXISTS ai_usage (
+    id             UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source         TEXT NOT NULL,
+    usage_by_model JSONB NOT NULL DEFAULT '{}',
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_usage_created_at ON ai_usage(created_at);
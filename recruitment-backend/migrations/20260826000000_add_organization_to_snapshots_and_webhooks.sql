@@ -0,0 +1,11 @@
+-- Dashboard history and webhook subscriptions belong to one organization,
+-- like the tests and candidates they describe.
+ALTER TABLE dashboard_snapshots ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE webhook_subscriptions ADD COLUMN IF NOT EXISTS organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+
+CREATE INDEX IF NOT EXISTS idx_dashboard_snapshots_organization
+    ON dashboard_snapshots (organization_id, captured_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_organization
+    ON webhook_subscriptions (organization_id);
@@ -0,0 +1,56 @@
+use clap::{Parser, Subcommand};
+
+/// Process exit codes of the binary.
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// Missing or invalid configuration.
+    pub const CONFIG: i32 = 1;
+    /// Unknown command or bad arguments (clap's own code).
+    pub const USAGE: i32 = 2;
+    /// The database could not be reached or a command's query failed.
+    pub const DATABASE: i32 = 3;
+    /// Applied migrations do not match the ones built into the binary.
+    pub const MIGRATIONS: i32 = 4;
+}
+
+/// Minutes a `running` AI job may go without finishing before
+/// `requeue-stale-jobs` puts it back in the queue.
+pub const DEFAULT_STALE_JOB_MINUTES: i64 = 30;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "recruitment-backend",
+    version,
+    about = "Recruitment backend server and maintenance commands"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Check and apply migrations, then run the HTTP server (default).
+    Serve,
+    /// Check and apply migrations, then exit.
+    Migrate,
+    /// Forget applied migrations with `from <= version < to` so they can be
+    /// re-applied or dropped from the history.
+    RepairMigrations {
+        #[arg(long)]
+        from: i64,
+        #[arg(long)]
+        to: i64,
+    },
+    /// Move AI jobs stuck in `running` back to `pending`.
+    RequeueStaleJobs {
+        #[arg(long, default_value_t = DEFAULT_STALE_JOB_MINUTES)]
+        older_than_minutes: i64,
+    },
+}
+
+impl Cli {
+    pub fn command(&self) -> Command {
+        self.command.clone().unwrap_or(Command::Serve)
+    }
+}
@@ -1,7 +1,12 @@
 use crate::error::{Error, Result};
+use chrono::FixedOffset;
 use dotenvy::dotenv;
+use regex::Regex;
+use sqlx::types::ipnetwork::IpNetwork;
 use std::env;
+use std::net::SocketAddr;
 use std::sync::OnceLock;
+use url::Url;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -18,10 +23,43 @@ pub struct Config {
     pub telegram_bot_token: String,
     pub webapp_url: String,
     pub onef_base_urls: Vec<String>,
+    pub metrics_allowed_networks: Vec<IpNetwork>,
+    pub trusted_proxies: Vec<IpNetwork>,
+    pub rejection_message_template: String,
+    pub enable_code_execution: bool,
+    pub report_chat_id: Option<i64>,
+    pub error_chat_id: Option<i64>,
+    pub report_timezone: FixedOffset,
+    pub hard_points_multiplier: f64,
+    /// `AI_MODELS`: chat models in priority order, cheapest first. Empty keeps
+    /// the model each request names.
+    pub ai_models: Vec<String>,
+    /// `AI_GENERATION_TIMEOUT_SECS`: longest a synchronous test generation
+    /// may run before it is given up with no questions.
+    pub ai_generation_timeout_secs: u64,
+    /// `TELEGRAM_INIT_DATA_MAX_AGE_SECS`: oldest WebApp `initData`
+    /// (by `auth_date`) accepted as proof of the candidate's Telegram id.
+    pub telegram_init_data_max_age_secs: u64,
+    pub candidate_submit_per_minute: u32,
+    pub max_cv_size_mb: usize,
+    /// `ONEF_CV_INLINE_MAX_KB`: CVs up to this size are also sent to 1F
+    /// base64-encoded; larger ones only as a download link. 0 disables inlining.
+    pub onef_cv_inline_max_kb: usize,
+    pub review_claim_ttl_hours: i32,
+    pub reapply_cooldown_days: i64,
+    pub expected_country: Option<String>,
+    pub geoip_api_url: Option<String>,
+    pub telegram_api_url: String,
+    pub telegram_channel_id: Option<String>,
+    pub upload_signing_secret: String,
+    /// Key of `candidates.email_hash`; changing it leaves stored hashes stale.
+    pub email_hash_secret: String,
 }
 
 pub static CONFIG: OnceLock<Config> = OnceLock::new();
 
+const DEFAULT_REJECTION_MESSAGE: &str = "Здравствуйте, {name}!\n\nБлагодарим вас за интерес к нашей компании и время, уделённое процессу отбора. К сожалению, на данный момент мы не готовы продолжить рассмотрение вашей кандидатуры.\n\nМы сохраним ваш профиль и свяжемся с вами, если появится подходящая вакансия. Желаем успехов!";
+
 impl Config {
     pub fn from_env() -> Result<Self> {
         dotenv().ok();
@@ -44,10 +82,134 @@ impl Config {
             telegram_bot_token: get_env("TELEGRAM_BOT_TOKEN")?,
             webapp_url: get_env("WEBAPP_URL")?,
             onef_base_urls: parse_onef_base_urls(),
+            metrics_allowed_networks: parse_metrics_allowed_networks()?,
+            trusted_proxies: parse_trusted_proxies()?,
+            rejection_message_template: env::var("REJECTION_MESSAGE_TEMPLATE")
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_REJECTION_MESSAGE.to_string()),
+            enable_code_execution: env::var("ENABLE_CODE_EXECUTION")
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            report_chat_id: match env::var("REPORT_TELEGRAM_CHAT_ID") {
+                Ok(raw) if !raw.trim().is_empty() => Some(raw.trim().parse().map_err(|e| {
+                    Error::Config(format!("Invalid value for REPORT_TELEGRAM_CHAT_ID: {}", e))
+                })?),
+                _ => None,
+            },
+            error_chat_id: match env::var("ERROR_TELEGRAM_CHAT_ID") {
+                Ok(raw) if !raw.trim().is_empty() => Some(raw.trim().parse().map_err(|e| {
+                    Error::Config(format!("Invalid value for ERROR_TELEGRAM_CHAT_ID: {}", e))
+                })?),
+                _ => None,
+            },
+            report_timezone: match env::var("REPORT_TIMEZONE") {
+                Ok(raw) if !raw.trim().is_empty() => parse_utc_offset(&raw).ok_or_else(|| {
+                    Error::Config(format!("Invalid value for REPORT_TIMEZONE: {}", raw))
+                })?,
+                _ => FixedOffset::east_opt(DEFAULT_REPORT_UTC_OFFSET_HOURS * 3600)
+                    .expect("valid default offset"),
+            },
+            hard_points_multiplier: match env::var("AI_HARD_POINTS_MULTIPLIER") {
+                Ok(raw) if !raw.trim().is_empty() => raw
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|m| m.is_finite() && *m >= 1.0)
+                    .ok_or_else(|| {
+                        Error::Config(format!(
+                            "Invalid value for AI_HARD_POINTS_MULTIPLIER: {} (expected a number >= 1)",
+                            raw
+                        ))
+                    })?,
+                _ => crate::services::ai_service::DEFAULT_HARD_POINTS_MULTIPLIER,
+            },
+            ai_models: env::var("AI_MODELS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect(),
+            ai_generation_timeout_secs: get_env_parse_or(
+                "AI_GENERATION_TIMEOUT_SECS",
+                crate::services::ai_service::DEFAULT_AI_GENERATION_TIMEOUT_SECS,
+            )?,
+            telegram_init_data_max_age_secs: get_env_parse_or(
+                "TELEGRAM_INIT_DATA_MAX_AGE_SECS",
+                DEFAULT_TELEGRAM_INIT_DATA_MAX_AGE_SECS,
+            )?,
+            candidate_submit_per_minute: get_env_parse_or("CANDIDATE_SUBMIT_PER_MINUTE", 5)?,
+            max_cv_size_mb: get_env_parse_or("MAX_CV_SIZE_MB", 10)?,
+            onef_cv_inline_max_kb: get_env_parse_or("ONEF_CV_INLINE_MAX_KB", 0)?,
+            review_claim_ttl_hours: get_env_parse_or("REVIEW_CLAIM_TTL_HOURS", 4)?,
+            reapply_cooldown_days: get_env_parse_or("REAPPLY_COOLDOWN_DAYS", 30)?,
+            expected_country: env::var("EXPECTED_COUNTRY")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            geoip_api_url: match env::var("GEOIP_API_URL") {
+                Ok(raw) => Some(raw.trim().trim_end_matches('/').to_string()).filter(|s| !s.is_empty()),
+                Err(_) => Some(crate::services::geo_service::DEFAULT_GEOIP_API_URL.to_string()),
+            },
+            telegram_api_url: env::var("TELEGRAM_API_URL")
+                .ok()
+                .map(|s| s.trim().trim_end_matches('/').to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| {
+                    crate::services::telegram_channel_service::DEFAULT_TELEGRAM_API_URL.to_string()
+                }),
+            telegram_channel_id: env::var("TELEGRAM_CHANNEL_ID")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            upload_signing_secret: match env::var("UPLOAD_SIGNING_SECRET") {
+                Ok(raw) if !raw.trim().is_empty() => raw.trim().to_string(),
+                _ => get_env("JWT_SECRET")?,
+            },
+            email_hash_secret: match env::var("EMAIL_HASH_SECRET") {
+                Ok(raw) if !raw.trim().is_empty() => raw.trim().to_string(),
+                _ => get_env("JWT_SECRET")?,
+            },
         })
     }
 }
 
+/// Asia/Dushanbe; the country has no DST, so a fixed offset is exact.
+const DEFAULT_REPORT_UTC_OFFSET_HOURS: i32 = 5;
+
+/// A day: the Mini App stays open for a whole application, but a leaked
+/// `initData` should not work forever.
+pub const DEFAULT_TELEGRAM_INIT_DATA_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// Parses `UTC`, `+05:00`, `-0330`, `+5` or `UTC+5` into a fixed offset.
+pub fn parse_utc_offset(raw: &str) -> Option<FixedOffset> {
+    let raw = raw.trim();
+    let rest = raw
+        .strip_prefix("UTC")
+        .or_else(|| raw.strip_prefix("GMT"))
+        .unwrap_or(raw);
+    if rest.is_empty() {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, digits) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
 fn get_env(name: &str) -> Result<String> {
     env::var(name).map_err(|_| Error::Config(format!("Missing environment variable: {}", name)))
 }
@@ -62,6 +224,20 @@ where
         .map_err(|e| Error::Config(format!("Invalid value for {}: {}", name, e)))
 }
 
+fn get_env_parse_or<T>(name: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(name) {
+        Ok(raw) if !raw.trim().is_empty() => raw
+            .trim()
+            .parse()
+            .map_err(|e| Error::Config(format!("Invalid value for {}: {}", name, e))),
+        _ => Ok(default),
+    }
+}
+
 pub fn init_config() -> Result<()> {
     let config = Config::from_env()?;
     CONFIG
@@ -76,6 +252,57 @@ pub fn get_config() -> &'static Config {
         .expect("Configuration has not been initialized")
 }
 
+/// Checks values that load fine but would only fail later, at first use.
+/// Returns every problem found, not just the first.
+pub fn validate_config(config: &Config) -> std::result::Result<(), Vec<String>> {
+    static BOT_TOKEN: OnceLock<Regex> = OnceLock::new();
+    let bot_token = BOT_TOKEN
+        .get_or_init(|| Regex::new(r"^\d+:[A-Za-z0-9_-]{35}$").expect("valid bot token pattern"));
+
+    let mut problems = Vec::new();
+    if config.openai_api_key.trim().is_empty() {
+        problems.push("OPENAI_API_KEY is empty".to_string());
+    } else if !config.openai_api_key.starts_with("sk-") {
+        problems.push("OPENAI_API_KEY must start with \"sk-\"".to_string());
+    }
+    if !bot_token.is_match(&config.telegram_bot_token) {
+        problems.push(
+            "TELEGRAM_BOT_TOKEN is not a bot token (expected <bot id>:<35 characters> from @BotFather)"
+                .to_string(),
+        );
+    }
+    if let Err(e) = config.server_address.parse::<SocketAddr>() {
+        problems.push(format!(
+            "SERVER_ADDRESS must be host:port, e.g. 0.0.0.0:8000 ({}: {})",
+            config.server_address, e
+        ));
+    }
+    if config.integration_rps == 0 {
+        problems.push("INTEGRATION_RPS must be greater than 0".to_string());
+    }
+    if config.public_rps == 0 {
+        problems.push("PUBLIC_RPS must be greater than 0".to_string());
+    }
+    if config.ai_generation_timeout_secs == 0 {
+        problems.push("AI_GENERATION_TIMEOUT_SECS must be greater than 0".to_string());
+    }
+    if config.telegram_init_data_max_age_secs == 0 {
+        problems.push("TELEGRAM_INIT_DATA_MAX_AGE_SECS must be greater than 0".to_string());
+    }
+    if let Err(e) = Url::parse(&config.webapp_url) {
+        problems.push(format!(
+            "WEBAPP_URL must be an absolute URL, e.g. https://example.com ({}: {})",
+            config.webapp_url, e
+        ));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
 fn parse_onef_base_urls() -> Vec<String> {
     if let Ok(raw) = env::var("ONEF_BASE_URLS") {
         let urls: Vec<String> = raw
@@ -107,3 +334,38 @@ fn parse_onef_base_urls() -> Vec<String> {
 
     Vec::new()
 }
+
+fn parse_networks(var: &str, default: &[&str]) -> Result<Vec<IpNetwork>> {
+    let raw = env::var(var).unwrap_or_default();
+    let networks = raw
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<IpNetwork>().map_err(|e| {
+                Error::Config(format!("Invalid value for {} ({}): {}", var, s, e))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if networks.is_empty() {
+        return Ok(default
+            .iter()
+            .map(|n| n.parse().expect("valid default network"))
+            .collect());
+    }
+    Ok(networks)
+}
+
+fn parse_metrics_allowed_networks() -> Result<Vec<IpNetwork>> {
+    parse_networks("METRICS_ALLOWED_IPS", &["127.0.0.1/32", "::1/128"])
+}
+
+/// Peers whose `X-Forwarded-For` / `X-Real-IP` we believe. Defaults to
+/// loopback and private ranges, where the reverse proxy runs.
+fn parse_trusted_proxies() -> Result<Vec<IpNetwork>> {
+    parse_networks(
+        "TRUSTED_PROXIES",
+        &["127.0.0.1/32", "::1/128", "10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16", "fc00::/7"],
+    )
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use axum::http::StatusCode;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+use crate::error::{Error, Result};
+
+/// The migrations built into the binary.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Why the applied migrations disagree with the ones in the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationProblem {
+    /// Recorded as applied but not shipped in this binary.
+    Unknown { version: i64, description: String },
+    /// The file changed after it was applied.
+    ChecksumMismatch { version: i64, description: String },
+    /// Started but did not finish.
+    Failed { version: i64, description: String },
+}
+
+impl fmt::Display for MigrationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationProblem::Unknown {
+                version,
+                description,
+            } => write!(
+                f,
+                "{} ({}) is applied but not part of this build",
+                version, description
+            ),
+            MigrationProblem::ChecksumMismatch {
+                version,
+                description,
+            } => write!(
+                f,
+                "{} ({}) was changed after it was applied",
+                version, description
+            ),
+            MigrationProblem::Failed {
+                version,
+                description,
+            } => {
+                write!(f, "{} ({}) did not finish", version, description)
+            }
+        }
+    }
+}
+
+/// Compares `_sqlx_migrations` with `migrator`. Migrations not applied yet
+/// are not a problem; an empty database has none.
+pub async fn migration_problems(
+    pool: &PgPool,
+    migrator: &Migrator,
+) -> Result<Vec<MigrationProblem>> {
+    let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !has_table {
+        return Ok(Vec::new());
+    }
+
+    let applied: Vec<(i64, String, bool, Vec<u8>)> = sqlx::query_as(
+        "SELECT version, description, success, checksum FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await?;
+    let known: HashMap<i64, &[u8]> = migrator
+        .iter()
+        .map(|m| (m.version, m.checksum.as_ref()))
+        .collect();
+
+    let problems = applied
+        .into_iter()
+        .filter_map(|(version, description, success, checksum)| {
+            if !success {
+                return Some(MigrationProblem::Failed {
+                    version,
+                    description,
+                });
+            }
+            match known.get(&version) {
+                None => Some(MigrationProblem::Unknown {
+                    version,
+                    description,
+                }),
+                Some(expected) if *expected != checksum.as_slice() => {
+                    Some(MigrationProblem::ChecksumMismatch {
+                        version,
+                        description,
+                    })
+                }
+                Some(_) => None,
+            }
+        })
+        .collect();
+    Ok(problems)
+}
+
+/// Deletes the history rows with `from <= version < to`; returns how many
+/// were removed. The schema changes those migrations made are left as is.
+pub async fn repair_migrations(pool: &PgPool, from: i64, to: i64) -> Result<u64> {
+    if from >= to {
+        return Err(Error::coded(
+            StatusCode::BAD_REQUEST,
+            "invalid_migration_range",
+            format!("--from ({}) must be below --to ({})", from, to),
+        ));
+    }
+    let removed = sqlx::query("DELETE FROM _sqlx_migrations WHERE version >= $1 AND version < $2")
+        .bind(from)
+        .bind(to)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    Ok(removed)
+}
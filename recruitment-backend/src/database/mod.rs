@@ -1,2 +1,2 @@
+pub mod migrations;
 pub mod pool;
-
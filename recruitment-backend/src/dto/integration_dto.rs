@@ -1,5 +1,6 @@
-use crate::models::question::{QuestionDetails, QuestionType};
+use crate::models::question::{Difficulty, MultipleChoiceDetails, QuestionDetails, QuestionType};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use validator::Validate;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -8,11 +9,13 @@ pub struct CreateQuestion {
     pub question_type: QuestionType,
     pub question: String,
     pub points: i32,
+    #[serde(default)]
+    pub difficulty: Difficulty,
     #[serde(flatten)]
     pub details: QuestionDetails,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct GenerateVacancyDescriptionPayload {
     #[validate(length(min = 1))]
     pub title: String,
@@ -20,7 +23,10 @@ pub struct GenerateVacancyDescriptionPayload {
     pub company: String,
     #[validate(length(min = 1))]
     pub location: String,
+    /// Output language (`ru-RU`, `tg-TJ`, `en-US`); anything else means Russian.
     pub language: Option<String>,
+    /// Several output languages at once; the response is then keyed by code.
+    pub languages: Option<Vec<String>>,
     #[validate(length(min = 1))]
     pub age: Option<String>,
     #[validate(length(min = 1))]
@@ -57,6 +63,47 @@ pub struct CreateTestPayload {
     pub test_type: Option<String>,
     pub presentation_themes: Option<Vec<String>>,
     pub presentation_extra_info: Option<String>,
+    pub show_honesty_declaration: Option<bool>,
+    pub declaration_text: Option<String>,
+    pub prerequisite_test_id: Option<uuid::Uuid>,
+    /// Used by invites that omit `expires_in_hours`.
+    #[validate(range(min = 1, message = "Default invite expiry must be at least 1 hour"))]
+    pub default_invite_expiry_hours: Option<i32>,
+    /// Hours before expiry at which open attempts get a Telegram reminder.
+    pub reminder_hours_before: Option<Vec<i32>>,
+    /// Pass mark for the multiple-choice questions alone. Together with
+    /// `passing_score_open` it replaces `passing_score` for pass/fail.
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "MCQ passing score must be between 0 and 100"
+    ))]
+    pub passing_score_mcq: Option<f64>,
+    /// Pass mark for the short-answer and code questions alone.
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "Open answer passing score must be between 0 and 100"
+    ))]
+    pub passing_score_open: Option<f64>,
+    /// Send the candidate a per-question breakdown over Telegram once the
+    /// attempt is graded. Answer explanations are included only with
+    /// `show_results_immediately`.
+    pub share_results_details: Option<bool>,
+    /// Most time lost to connection drops that is added back to an
+    /// attempt's deadline. Defaults to 300.
+    #[validate(range(min = 0, max = 3600, message = "Grace time must be between 0 and 3600 seconds"))]
+    pub max_grace_seconds: Option<i32>,
+    /// Most candidates that may be taking the test at once; further starts
+    /// get `503` until someone finishes. No limit when omitted.
+    #[validate(range(min = 1, message = "Concurrent taker limit must be at least 1"))]
+    pub max_concurrent_takers: Option<i32>,
+    /// Language the questions are written in (`ru`, `tg` or `en`), for the
+    /// candidate UI. Defaults to `ru`.
+    pub language: Option<String>,
+    /// With `show_results_immediately`, candidates also see the right
+    /// answers to the multiple-choice questions they got wrong.
+    pub reveal_correct_answers: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -97,6 +144,42 @@ pub struct UpdateTestPayload {
     
     #[serde(default, deserialize_with = "trim_optional_string")]
     pub presentation_extra_info: Option<String>,
+
+    pub show_honesty_declaration: Option<bool>,
+
+    #[serde(default, deserialize_with = "trim_optional_string")]
+    pub declaration_text: Option<String>,
+
+    pub prerequisite_test_id: Option<uuid::Uuid>,
+
+    #[validate(range(min = 1, message = "Default invite expiry must be at least 1 hour"))]
+    pub default_invite_expiry_hours: Option<i32>,
+
+    pub reminder_hours_before: Option<Vec<i32>>,
+
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "MCQ passing score must be between 0 and 100"
+    ))]
+    pub passing_score_mcq: Option<f64>,
+
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "Open answer passing score must be between 0 and 100"
+    ))]
+    pub passing_score_open: Option<f64>,
+
+    pub share_results_details: Option<bool>,
+
+    #[validate(range(min = 0, max = 3600, message = "Grace time must be between 0 and 3600 seconds"))]
+    pub max_grace_seconds: Option<i32>,
+
+    #[validate(range(min = 1, message = "Concurrent taker limit must be at least 1"))]
+    pub max_concurrent_takers: Option<i32>,
+
+    pub reveal_correct_answers: Option<bool>,
 }
 
 fn trim_optional_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -125,6 +208,31 @@ pub struct GenerateAiTestPayload {
     pub description: Option<String>,
     pub duration_minutes: Option<i32>,
     pub passing_score: Option<f64>,
+    /// Share of questions per difficulty, e.g. `{"easy": 30, "medium": 50, "hard": 20}`.
+    pub difficulty_distribution: Option<HashMap<String, f64>>,
+    /// Question language: `ru` (default), `tg` or `en`.
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DuplicateTestPayload {
+    #[validate(range(min = 1, message = "num_new_questions must be at least 1"))]
+    pub num_new_questions: usize,
+    /// Defaults to the profession implied by the original test's title.
+    pub profession: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AutoAdjustTestPayload {
+    /// Defaults to `true`: generate and store a proposal without touching
+    /// the test. Send `false` to apply the stored proposal.
+    pub dry_run: Option<bool>,
+}
+
+/// Corrected answer keys, keyed by question id: `{"3": {"options": [...], "correct_answer": 1}}`.
+#[derive(Debug, Deserialize)]
+pub struct RegradeTestPayload {
+    pub corrections: BTreeMap<i32, MultipleChoiceDetails>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -138,6 +246,9 @@ pub struct EnqueueAiJobPayload {
     pub description: Option<String>,
     pub duration_minutes: Option<i32>,
     pub passing_score: Option<f64>,
+    pub difficulty_distribution: Option<HashMap<String, f64>>,
+    /// Question language: `ru` (default), `tg` or `en`.
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -149,6 +260,8 @@ pub struct SpecGenerateTestPayload {
     pub duration_minutes: Option<i32>,
     pub question_types: Option<Vec<String>>, 
     pub distribution: Option<serde_json::Value>,
+    /// Question language: `ru` (default), `tg` or `en`.
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -173,12 +286,26 @@ pub struct GradePresentationPayload {
     #[validate(range(min = 0.0, max = 100.0))]
     pub grade: f64,
     pub comment: Option<String>,
+    /// Grade even though another reviewer has claimed the attempt.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExtendAttemptPayload {
+    #[validate(range(min = 1, max = 120, message = "extra_minutes must be between 1 and 120"))]
+    pub extra_minutes: i64,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct GradeAnswerPayload {
     pub question_id: i32,
     pub is_correct: bool,
+    #[serde(default)]
+    pub force: bool,
+    /// Shown to the candidate in the results breakdown.
+    #[validate(length(max = 2000, message = "comment must be at most 2000 characters"))]
+    pub comment: Option<String>,
 }
 #[derive(Debug, Deserialize, Validate)]
 pub struct SendMessagePayload {
@@ -186,16 +313,64 @@ pub struct SendMessagePayload {
     pub telegram_id: Option<i64>,
     #[validate(length(min = 1, message = "Message content cannot be empty"))]
     pub text: String,
+    #[validate(url(message = "attachment_url must be a valid URL"))]
+    pub attachment_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Deserialize)]
+pub struct ReviewFeedbackPayload {
+    pub reviewed: bool,
+}
+
+pub const DEFAULT_STATUS_SYNC_PAGE_SIZE: i64 = 100;
+pub const MAX_STATUS_SYNC_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CandidateStatusSyncQuery {
+    /// Only candidates changed, or whose attempts changed, after this time.
+    /// Pass the previous response's `next_cursor`.
+    pub updated_since: Option<chrono::DateTime<chrono::Utc>>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl CandidateStatusSyncQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page
+            .unwrap_or(DEFAULT_STATUS_SYNC_PAGE_SIZE)
+            .clamp(1, MAX_STATUS_SYNC_PAGE_SIZE)
+    }
+}
+
+/// A candidate with the status of their latest test attempt.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CandidateStatusSync {
     pub id: uuid::Uuid,
     pub external_id: Option<String>,
-    pub name: String,
-    pub email: String,
+    /// `None` for an erased candidate.
+    pub name: Option<String>,
+    pub email: Option<String>,
     pub status: String,
     pub last_updated: chrono::DateTime<chrono::Utc>,
+    pub latest_attempt_id: Option<uuid::Uuid>,
+    pub percentage: Option<f64>,
+    pub passed: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CandidateStatusSyncPage {
+    pub items: Vec<CandidateStatusSync>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+    /// Latest `last_updated` across all pages; `None` when nothing matched.
+    pub next_cursor: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -207,4 +382,9 @@ pub struct DashboardStats {
     pub candidates_by_status: std::collections::HashMap<String, i64>,
     pub candidates_history: Vec<(String, i64)>,
     pub attempts_status: std::collections::HashMap<String, i64>,
+    pub attempts_by_source: std::collections::HashMap<String, i64>,
+    /// Candidates who blocked the bot and no longer get Telegram messages.
+    pub telegram_unreachable_candidates: i64,
+    /// Candidates still in the pipeline with no activity for `STALE_CANDIDATE_DAYS`.
+    pub stale_candidates: i64,
 }
@@ -1,3 +1,6 @@
+use crate::models::question::{Difficulty, Question, QuestionDetails, QuestionType, TestCase};
+use crate::models::test::Test;
+use crate::models::test_attempt::TestAttempt;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -11,7 +14,11 @@ pub struct PublicTestSummary {
     pub passing_score: f64,
     pub test_type: Option<String>,
     pub presentation_themes: Option<serde_json::Value>,
+    pub assigned_theme: Option<serde_json::Value>,
     pub presentation_extra_info: Option<String>,
+    pub declaration_text: Option<String>,
+    /// Language the questions are written in: `ru`, `tg` or `en`.
+    pub language: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,17 +28,116 @@ pub struct PublicAttemptSummary {
     pub expires_at: chrono::DateTime<chrono::Utc>,
     pub candidate_name: String,
     pub candidate_external_id: Option<String>,
+    /// UI language; fetch its strings from `GET /api/public/i18n/:lang`.
+    pub language: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetTestByTokenResponse {
     pub test: PublicTestSummary,
     pub attempt: PublicAttemptSummary,
+    pub requires_declaration: bool,
+    pub prerequisite_test_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartTestRequest {
+    #[serde(default)]
+    pub accept_declaration: bool,
+}
+
+/// A question as the candidate sees it: the snapshot minus the answer key
+/// (`correct_answer`, `explanation`, `expected_keywords`, `correct_words`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicQuestionView {
+    pub id: i32,
+    #[serde(rename = "type")]
+    pub question_type: QuestionType,
+    pub question: String,
+    pub points: i32,
+    pub difficulty: Difficulty,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starter_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_cases: Option<Vec<TestCase>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_words: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blanked_sentence: Option<String>,
+}
+
+impl From<&Question> for PublicQuestionView {
+    fn from(q: &Question) -> Self {
+        let mut view = Self {
+            id: q.id,
+            question_type: q.question_type.clone(),
+            question: q.question.clone(),
+            points: q.points,
+            difficulty: q.difficulty,
+            options: None,
+            language: None,
+            starter_code: None,
+            test_cases: None,
+            min_words: None,
+            blanked_sentence: None,
+        };
+        match &q.details {
+            QuestionDetails::MultipleChoice(mc) => view.options = Some(mc.options.clone()),
+            QuestionDetails::Code(code) => {
+                view.language = Some(code.language.clone());
+                view.starter_code = code.starter_code.clone();
+                view.test_cases = Some(code.test_cases.clone());
+            }
+            QuestionDetails::ShortAnswer(sa) => view.min_words = sa.min_words,
+            QuestionDetails::FillInBlank(blank) => {
+                view.blanked_sentence = Some(blank.blanked_sentence.clone())
+            }
+        }
+        view
+    }
+}
+
+impl PublicQuestionView {
+    /// Candidate-facing copy of an attempt's `questions_snapshot`. Question
+    /// lists are rebuilt without answer keys; presentation snapshots (an
+    /// object with themes) carry no answers and pass through untouched.
+    pub fn sanitize_snapshot(snapshot: &serde_json::Value) -> serde_json::Value {
+        if !snapshot.is_array() {
+            return snapshot.clone();
+        }
+        match serde_json::from_value::<Vec<Question>>(snapshot.clone()) {
+            Ok(questions) => serde_json::to_value(
+                questions
+                    .iter()
+                    .map(PublicQuestionView::from)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap_or_default(),
+            // Not in the current question format; still never leak the key.
+            Err(_) => {
+                let mut stripped = snapshot.clone();
+                for question in stripped.as_array_mut().into_iter().flatten() {
+                    if let Some(fields) = question.as_object_mut() {
+                        for key in ["correct_answer", "explanation", "expected_keywords"] {
+                            fields.remove(key);
+                        }
+                    }
+                }
+                stripped
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartTestResponse {
     pub attempt_id: uuid::Uuid,
+    /// Replaces the token from the link for every later call on this attempt.
+    pub access_token: String,
     pub status: String,
     pub started_at: chrono::DateTime<chrono::Utc>,
     pub expires_at: chrono::DateTime<chrono::Utc>,
@@ -46,6 +152,13 @@ pub struct SaveAnswerRequest {
     pub marked_for_review: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct QuestionFeedbackRequest {
+    pub question_id: i32,
+    #[validate(length(min = 1, max = 2000, message = "text must be 1-2000 characters"))]
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveAnswerResponse {
     pub saved: bool,
@@ -64,18 +177,127 @@ pub struct SubmitPresentationRequest {
     pub presentation_link: Option<String>,
 }
 
+/// Body of the submit response and of `GET /api/public/tests/:token/result`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitTestResponse {
     pub attempt_id: uuid::Uuid,
     pub status: String,
-    pub score: f64,
-    pub max_score: f64,
-    pub percentage: f64,
-    pub passed: bool,
+    #[serde(flatten)]
+    pub result: CandidateResult,
+}
+
+/// Whether the candidate gets to see their result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultState {
+    /// The test has `show_results_immediately`; scores are included.
+    Shown,
+    /// Results go to HR only.
+    Hidden,
+    /// Some answers await manual grading; nothing is shown until it is done.
+    PendingReview,
+}
+
+/// One question of a shown result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionResult {
+    pub question_id: i64,
+    pub is_correct: bool,
+    pub points_earned: f64,
+    pub max_points: f64,
+    /// Right answer to a missed multiple-choice or fill-in-the-blank
+    /// question, on tests with `reveal_correct_answers`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct_answer: Option<String>,
+}
+
+/// What a candidate may see of their finished attempt. Scores and
+/// `questions` are only present when `result_state` is `shown`; `message`
+/// is in the attempt's language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateResult {
+    pub result_state: ResultState,
     pub show_results: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub questions: Option<Vec<QuestionResult>>,
     pub message: String,
 }
 
+impl CandidateResult {
+    /// Applies `test`'s visibility flags to the graded `attempt`.
+    pub fn new(attempt: &TestAttempt, test: &Test) -> Self {
+        let state = if attempt.status == "needs_review" {
+            ResultState::PendingReview
+        } else if test.show_results_immediately == Some(true) {
+            ResultState::Shown
+        } else {
+            ResultState::Hidden
+        };
+        let message_key = match state {
+            ResultState::Shown => "results_shown",
+            ResultState::Hidden => "results_sent_to_hr",
+            ResultState::PendingReview => "results_pending_review",
+        };
+        let mut result = Self {
+            result_state: state,
+            show_results: state == ResultState::Shown,
+            score: None,
+            max_score: None,
+            percentage: None,
+            passed: None,
+            questions: None,
+            message: crate::utils::strings::text(&attempt.language, message_key).to_string(),
+        };
+        if state != ResultState::Shown {
+            return result;
+        }
+
+        use rust_decimal::prelude::ToPrimitive;
+        result.score = attempt.score.and_then(|v| v.to_f64());
+        result.max_score = attempt.max_score.and_then(|v| v.to_f64());
+        result.percentage = attempt.percentage.and_then(|v| v.to_f64());
+        result.passed = attempt.passed;
+        let graded: Vec<serde_json::Value> = attempt
+            .graded_answers
+            .clone()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        result.questions = Some(
+            graded
+                .iter()
+                .map(|entry| {
+                    let number = |key: &str| entry.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                    let is_correct = entry.get("is_correct").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let closed = matches!(
+                        entry.get("type").and_then(|v| v.as_str()),
+                        Some("multiple_choice" | "fill_in_blank")
+                    );
+                    QuestionResult {
+                        question_id: entry.get("question_id").and_then(|v| v.as_i64()).unwrap_or(0),
+                        is_correct,
+                        points_earned: number("points_earned"),
+                        max_points: number("max_points"),
+                        correct_answer: entry
+                            .get("correct_answer")
+                            .and_then(|v| v.as_str())
+                            .filter(|_| closed && !is_correct && test.reveal_correct_answers)
+                            .map(str::to_string),
+                    }
+                })
+                .collect(),
+        );
+        result
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusResponse {
     pub status: String,
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use validator::Validate;
 
 use crate::models::vacancy::Vacancy;
@@ -59,6 +59,25 @@ pub struct UpdateVacancyPayload {
     pub published_at: Option<DateTime<Utc>>,
 }
 
+/// `PATCH /api/integration/vacancies/:id/links`. An omitted field keeps the
+/// current link; `null` removes it.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateVacancyLinksPayload {
+    #[serde(default, deserialize_with = "present")]
+    pub koinotinav_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    pub onef_id: Option<Option<String>>,
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VacancyResponse {
     pub id: uuid::Uuid,
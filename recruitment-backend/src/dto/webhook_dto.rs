@@ -8,6 +8,8 @@ pub struct TestAssignedWebhook {
     pub test: WebhookTest,
     pub access_token: String,
     pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// `expires_at` in the candidate's timezone, ready to show in Telegram.
+    pub expires_at_local: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,12 +21,43 @@ pub struct TestCompletedWebhook {
     pub score: f64,
     pub percentage: f64,
     pub passed: bool,
+    /// Set when the deadline checker graded the saved answers because the
+    /// candidate ran out of time.
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradeRevisedWebhook {
+    pub event: String,
+    pub attempt_id: uuid::Uuid,
+    pub candidate: WebhookCandidate,
+    pub test: WebhookTest,
+    pub previous_passed: Option<bool>,
+    pub passed: bool,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReminderWebhook {
+    pub event: String,
+    pub attempt_id: uuid::Uuid,
+    pub candidate: WebhookCandidate,
+    pub test: WebhookTest,
+    pub hours_left: i64,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// `expires_at` in the candidate's timezone, ready to show in Telegram.
+    pub expires_at_local: String,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookCandidate {
     pub name: String,
     pub telegram_id: Option<i64>,
+    /// Forum topic to post in; sent as `message_thread_id` to Telegram.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telegram_thread_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
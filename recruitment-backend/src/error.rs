@@ -1,11 +1,18 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Json},
 };
-use serde_json::json;
+use serde_json::{json, Value as JsonValue};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::middleware::request_id::current_request_id;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// `Retry-After` seconds sent with `Error::ServiceUnavailable`.
+pub const SERVICE_UNAVAILABLE_RETRY_AFTER_SECS: u64 = 300;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Configuration error: {0}")]
@@ -20,6 +27,11 @@ pub enum Error {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// Temporary overload; the client should retry after
+    /// `SERVICE_UNAVAILABLE_RETRY_AFTER_SECS`.
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Database error: {0}")]
     Database(sqlx::Error),
 
@@ -46,34 +58,217 @@ pub enum Error {
 
     #[error("Excel export error: {0}")]
     Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("Archive error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// Business-rule failure with a stable machine-readable code.
+    #[error("{message}")]
+    Coded {
+        status: StatusCode,
+        code: &'static str,
+        message: String,
+        details: Option<JsonValue>,
+    },
+}
+
+impl Error {
+    pub fn coded(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Error::Coded {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(self, details: JsonValue) -> Self {
+        match self {
+            Error::Coded { status, code, message, .. } => Error::Coded {
+                status,
+                code,
+                message,
+                details: Some(details),
+            },
+            other => other,
+        }
+    }
+
+    pub fn test_expired() -> Self {
+        Self::coded(StatusCode::FORBIDDEN, "test_expired", "This test invitation has expired")
+    }
+
+    pub fn already_completed(message: impl Into<String>) -> Self {
+        Self::coded(StatusCode::CONFLICT, "already_completed", message)
+    }
+
+    pub fn candidate_not_found() -> Self {
+        Self::coded(StatusCode::NOT_FOUND, "candidate_not_found", "Candidate not found")
+    }
+
+    pub fn pending_invite_exists() -> Self {
+        Self::coded(
+            StatusCode::BAD_REQUEST,
+            "pending_invite_exists",
+            "Candidate already has a pending test invitation. They must start or complete existing tests before receiving new invitations.",
+        )
+    }
+
+    /// Stable snake_case code returned to API clients in `error.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "configuration_error",
+            Error::BadRequest(_) => "bad_request",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::NotFound(_) => "not_found",
+            Error::ServiceUnavailable(_) => "service_unavailable",
+            Error::Database(_) => "database_error",
+            Error::Validation(_) => "validation_failed",
+            Error::Json(_) => "invalid_json",
+            Error::Anyhow(_) => "bad_request",
+            Error::Reqwest(_) => "upstream_error",
+            Error::Internal(_) => "internal_error",
+            Error::Io(_) => "io_error",
+            Error::Multipart(_) => "invalid_multipart",
+            Error::Xlsx(_) => "export_failed",
+            Error::Zip(_) => "export_failed",
+            Error::Coded { code, .. } => code,
+        }
+    }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        let (status, error_message) = match self {
-            Error::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            Error::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
-            Error::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
-            Error::Validation(err) => (StatusCode::BAD_REQUEST, err.to_string()),
-            Error::Database(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
-            Error::Json(err) => (StatusCode::BAD_REQUEST, err.to_string()),
-            Error::Reqwest(err) => (StatusCode::BAD_GATEWAY, format!("External service error: {}", err)),
-            Error::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            Error::Io(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
-            Error::Multipart(err) => (StatusCode::BAD_REQUEST, err.to_string()),
-            Error::Xlsx(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Export error: {}", err)),
-            Error::Anyhow(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+        let code = self.code();
+        // Expected under load; not something to page operators about.
+        let overloaded = matches!(self, Error::ServiceUnavailable(_));
+        let (status, error_message, details) = match self {
+            Error::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg, None),
+            Error::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg, None),
+            Error::NotFound(msg) => (StatusCode::NOT_FOUND, msg, None),
+            Error::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg, None),
+            Error::Validation(err) => (
+                StatusCode::BAD_REQUEST,
+                err.to_string(),
+                Some(validation_details(&err)),
+            ),
+            Error::Database(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string(), None),
+            Error::Json(err) => (StatusCode::BAD_REQUEST, err.to_string(), None),
+            Error::Reqwest(err) => (StatusCode::BAD_GATEWAY, format!("External service error: {}", err), None),
+            Error::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg, None),
+            Error::Io(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string(), None),
+            Error::Multipart(err) => (StatusCode::BAD_REQUEST, err.to_string(), None),
+            Error::Xlsx(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Export error: {}", err), None),
+            Error::Zip(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Export error: {}", err), None),
+            Error::Anyhow(err) => (StatusCode::BAD_REQUEST, err.to_string(), None),
+            Error::Coded { status, message, details, .. } => (status, message, details),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "An unexpected error occurred".to_string(),
+                None,
             ),
         };
 
-        let body = Json(json!({ "error": error_message }));
+        if status.is_server_error() && !overloaded {
+            let request_id = current_request_id();
+            tracing::error!(code, status = status.as_u16(), "{}", error_message);
+            notify_operators(server_error_alert(status, code, &error_message, request_id));
+        }
+
+        let body = Json(error_body(code, &error_message, details));
+        if overloaded {
+            let retry_after = [(header::RETRY_AFTER, SERVICE_UNAVAILABLE_RETRY_AFTER_SECS.to_string())];
+            return (status, retry_after, body).into_response();
+        }
         (status, body).into_response()
     }
 }
 
+/// The `{"error": {code, message, details, request_id}}` envelope shared by every
+/// error response. `request_id` matches the `X-Request-Id` response header.
+pub fn error_body(code: &str, message: &str, details: Option<JsonValue>) -> JsonValue {
+    json!({
+        "error": {
+            "code": code,
+            "message": message,
+            "details": details,
+            "request_id": current_request_id(),
+        }
+    })
+}
+
+const MAX_ALERT_MESSAGE_CHARS: usize = 1000;
+
+/// Telegram text posted to `ERROR_TELEGRAM_CHAT_ID` for a 5xx response.
+pub fn server_error_alert(
+    status: StatusCode,
+    code: &str,
+    message: &str,
+    request_id: Option<Uuid>,
+) -> String {
+    let message = truncate_alert_message(message);
+    let request_id = request_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    format!(
+        "Server error {} ({})\nRequest ID: {}\n\n{}",
+        status.as_u16(),
+        code,
+        request_id,
+        message
+    )
+}
+
+/// Caps an alert body at `MAX_ALERT_MESSAGE_CHARS`, marking the cut with `…`.
+pub(crate) fn truncate_alert_message(message: &str) -> String {
+    if message.chars().count() > MAX_ALERT_MESSAGE_CHARS {
+        let mut cut: String = message.chars().take(MAX_ALERT_MESSAGE_CHARS).collect();
+        cut.push('…');
+        cut
+    } else {
+        message.to_string()
+    }
+}
+
+/// Posts `text` to `ERROR_TELEGRAM_CHAT_ID` in the background; a no-op when
+/// the chat is not configured.
+pub(crate) fn notify_operators(text: String) {
+    let Some(config) = crate::config::CONFIG.get() else { return };
+    let Some(chat_id) = config.error_chat_id else { return };
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+    let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
+    runtime.spawn(
+        async move {
+            let body = json!({ "chat_id": chat_id, "text": text });
+            if let Err(e) = reqwest::Client::new().post(&url).json(&body).send().await {
+                tracing::warn!("Failed to send error notification: {}", e);
+            }
+        }
+        .in_current_span(),
+    );
+}
+
+fn validation_details(err: &validator::ValidationErrors) -> JsonValue {
+    let fields: serde_json::Map<String, JsonValue> = err
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let items: Vec<JsonValue> = errors
+                .iter()
+                .map(|e| {
+                    json!({
+                        "code": e.code,
+                        "message": e.message,
+                        "params": e.params,
+                    })
+                })
+                .collect();
+            (field.to_string(), JsonValue::Array(items))
+        })
+        .collect();
+    json!({ "fields": fields })
+}
+
 impl From<sqlx::Error> for Error {
     fn from(err: sqlx::Error) -> Self {
         match err {
@@ -1,3 +1,4 @@
+pub mod cli;
 pub mod config;
 pub mod database;
 pub mod dto;
@@ -12,31 +13,54 @@ use crate::services::{
     ai_service::AIService, embed_service::EmbedService, eval_service::EvalService,
     notification_service::NotificationService, test_service::TestService,
     vacancy_service::VacancyService, candidate_service::CandidateService,
+    vacancy_link_service::VacancyLinkService,
     koinotinav_service::KoinotinavService, onef_service::OneFService,
     message_service::MessageService,
     attempt_service::AttemptService,
+    broadcast_service::BroadcastService,
     response_service::ResponseService,
+    dashboard_service::DashboardService,
+    dashboard_snapshot_service::DashboardSnapshotService,
+    referral_service::ReferralService,
+    prompt_service::PromptService,
+    report_service::ReportService,
+    webhook_subscription_service::WebhookSubscriptionService,
+    organization_service::OrganizationService,
 };
+use crate::models::message::MessageEvent;
 use crate::utils::login_guard::LoginGuard;
+use metrics_exporter_prometheus::PrometheusHandle;
 use reqwest::Client;
 use sqlx::PgPool;
+use tokio::sync::broadcast;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub login_guard: LoginGuard,
+    pub metrics_handle: PrometheusHandle,
     pub test_service: TestService,
     pub ai_service: AIService,
     pub eval_service: EvalService,
     pub embed_service: EmbedService,
     pub notification_service: NotificationService,
     pub vacancy_service: VacancyService,
+    pub vacancy_link_service: VacancyLinkService,
     pub candidate_service: CandidateService,
     pub koinotinav_service: KoinotinavService,
     pub onef_service: OneFService,
     pub message_service: MessageService,
     pub attempt_service: AttemptService,
     pub response_service: ResponseService,
+    pub dashboard_service: DashboardService,
+    pub dashboard_snapshot_service: DashboardSnapshotService,
+    pub referral_service: ReferralService,
+    pub report_service: ReportService,
+    pub prompt_service: PromptService,
+    pub webhook_subscription_service: WebhookSubscriptionService,
+    pub organization_service: OrganizationService,
+    pub broadcast_service: BroadcastService,
+    pub message_events: broadcast::Sender<MessageEvent>,
 }
 
 impl AppState {
@@ -48,38 +72,64 @@ impl AppState {
             .unwrap();
 
         let test_service = TestService::new(pool.clone());
+        let prompt_service = PromptService::new(pool.clone());
         let ai_service = AIService::new(
             config.openai_api_key.clone(),
             config.openai_base_url.clone(),
             http_client.clone(),
-        );
+        )
+        .with_hard_points_multiplier(config.hard_points_multiplier)
+        .with_models(config.ai_models.clone())
+        .with_prompts(prompt_service.clone());
         let eval_service = EvalService::new(config.openai_api_key.clone(), http_client.clone());
         let embed_service = EmbedService::new(config.openai_api_key.clone(), http_client);
-        let notification_service =
-            NotificationService::new(pool.clone(), config.telegram_bot_webhook_url.clone());
         let vacancy_service = VacancyService::new(pool.clone());
         let candidate_service = CandidateService::new(pool.clone());
-        let koinotinav_service = KoinotinavService::new();
+        let vacancy_link_service = VacancyLinkService::new(pool.clone());
+        let koinotinav_service = KoinotinavService::new().with_links(vacancy_link_service.clone());
         let onef_service = OneFService::new(config.onef_base_urls.clone());
+        let notification_service =
+            NotificationService::new(pool.clone(), config.telegram_bot_webhook_url.clone())
+                .with_onef(onef_service.clone());
         let message_service = MessageService::new(pool.clone());
         let attempt_service = AttemptService::new(pool.clone());
         let response_service = ResponseService::new(pool.clone());
+        let dashboard_service = DashboardService::new(pool.clone(), koinotinav_service.clone());
+        let dashboard_snapshot_service =
+            DashboardSnapshotService::new(pool.clone(), dashboard_service.clone());
+        let referral_service = ReferralService::new(pool.clone());
+        let report_service = ReportService::new(pool.clone(), koinotinav_service.clone());
+        let webhook_subscription_service = WebhookSubscriptionService::new(pool.clone());
+        let broadcast_service = BroadcastService::new(pool.clone());
+        let organization_service = OrganizationService::new(pool.clone());
+        let (message_events, _) = broadcast::channel(256);
 
         Self {
             pool,
             login_guard: LoginGuard::default(),
+            metrics_handle: crate::middleware::metrics::install_recorder(),
             test_service,
             ai_service,
             eval_service,
             embed_service,
             notification_service,
             vacancy_service,
+            vacancy_link_service,
             candidate_service,
             koinotinav_service,
             onef_service,
             message_service,
             attempt_service,
             response_service,
+            dashboard_service,
+            dashboard_snapshot_service,
+            referral_service,
+            report_service,
+            prompt_service,
+            webhook_subscription_service,
+            organization_service,
+            broadcast_service,
+            message_events,
         }
     }
 }
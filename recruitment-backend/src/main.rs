@@ -3,12 +3,17 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use recruitment_backend::middleware::{cache_control, etag};
+use clap::Parser;
+use recruitment_backend::cli::{exit_code, Cli, Command};
+use recruitment_backend::database::migrations;
 use recruitment_backend::services::queue_service::AiQueueService;
 use recruitment_backend::{
-    config::{get_config, init_config},
+    config::{get_config, init_config, validate_config},
     database::pool::create_pool,
     routes, AppState,
 };
+use sqlx::PgPool;
 use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::net::TcpListener;
@@ -17,18 +22,92 @@ use tracing::info;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
     tracing_subscriber::fmt::init();
-    init_config()?;
+    if let Err(e) = init_config() {
+        eprintln!("{}", e);
+        std::process::exit(exit_code::CONFIG);
+    }
     let config = get_config();
+    if let Err(problems) = validate_config(config) {
+        eprintln!("Invalid configuration:");
+        for problem in &problems {
+            eprintln!("  - {}", problem);
+        }
+        std::process::exit(exit_code::CONFIG);
+    }
 
-    let pool = create_pool().await?;
-    
-    let _ = sqlx::query("DELETE FROM _sqlx_migrations WHERE version >= 20260120150000 AND version < 20260120160000")
-        .execute(&pool)
-        .await;
+    let pool = match create_pool().await {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("Cannot connect to the database: {}", e);
+            std::process::exit(exit_code::DATABASE);
+        }
+    };
 
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    match cli.command() {
+        Command::Serve => {
+            apply_migrations(&pool).await;
+            serve(pool).await
+        }
+        Command::Migrate => {
+            apply_migrations(&pool).await;
+            println!("Migrations are up to date");
+            Ok(())
+        }
+        Command::RepairMigrations { from, to } => {
+            match migrations::repair_migrations(&pool, from, to).await {
+                Ok(removed) => {
+                    println!("Removed {} migration record(s) with {} <= version < {}", removed, from, to);
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("Failed to repair migrations: {}", e);
+                    std::process::exit(exit_code::DATABASE);
+                }
+            }
+        }
+        Command::RequeueStaleJobs { older_than_minutes } => {
+            match AiQueueService::new(pool).requeue_stale(older_than_minutes).await {
+                Ok(requeued) => {
+                    println!("Requeued {} AI job(s) running for over {} minutes", requeued, older_than_minutes);
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("Failed to requeue stale AI jobs: {}", e);
+                    std::process::exit(exit_code::DATABASE);
+                }
+            }
+        }
+    }
+}
+
+/// Exits with `MIGRATIONS` when the applied history disagrees with this
+/// build, otherwise applies the pending migrations.
+async fn apply_migrations(pool: &PgPool) {
+    match migrations::migration_problems(pool, &migrations::MIGRATOR).await {
+        Ok(problems) if problems.is_empty() => {}
+        Ok(problems) => {
+            eprintln!("Applied migrations do not match this build:");
+            for problem in &problems {
+                eprintln!("  - {}", problem);
+            }
+            eprintln!("Fix the database or run `recruitment-backend repair-migrations --from <version> --to <version>`.");
+            std::process::exit(exit_code::MIGRATIONS);
+        }
+        Err(e) => {
+            eprintln!("Cannot read the migration history: {}", e);
+            std::process::exit(exit_code::DATABASE);
+        }
+    }
+    if let Err(e) = migrations::MIGRATOR.run(pool).await {
+        eprintln!("Failed to apply migrations: {}", e);
+        std::process::exit(exit_code::MIGRATIONS);
+    }
+}
 
+async fn serve(pool: PgPool) -> anyhow::Result<()> {
+    let config = get_config();
     let app_state = AppState::new(pool);
 
     {
@@ -107,7 +186,8 @@ async fn main() -> anyhow::Result<()> {
                     recruitment_backend::config::get_config()
                         .telegram_bot_webhook_url
                         .clone(),
-                );
+                )
+                .with_onef(state.onef_service.clone());
             loop {
                 match notif.run_once().await {
                     Ok(true) => {}
@@ -123,6 +203,26 @@ async fn main() -> anyhow::Result<()> {
         });
     }
 
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            let telegram =
+                recruitment_backend::services::telegram_channel_service::TelegramChannelService::from_config();
+            loop {
+                match state.broadcast_service.run_once(&telegram).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                    }
+                    Err(e) => {
+                        tracing::error!(error = ?e, "Broadcast worker error");
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+    }
+
     {
         let state = app_state.clone();
         tokio::spawn(async move {
@@ -135,6 +235,10 @@ async fn main() -> anyhow::Result<()> {
                 if let Err(e) = attempt_svc.check_deadlines(&notif).await {
                     tracing::error!("Deadline checker error: {:?}", e);
                 }
+                let ttl_hours = recruitment_backend::config::get_config().review_claim_ttl_hours;
+                if let Err(e) = attempt_svc.release_expired_claims(ttl_hours, chrono::Utc::now()).await {
+                    tracing::error!("Review claim expiry error: {:?}", e);
+                }
                 tokio::time::sleep(Duration::from_secs(60)).await;
             }
         });
@@ -180,7 +284,136 @@ async fn main() -> anyhow::Result<()> {
         });
     }
 
-    let base_routes = Router::new().route("/health", get(routes::health::health));
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            use recruitment_backend::services::test_service::DIFFICULTY_REFRESH_INTERVAL;
+
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            loop {
+                match state.test_service.refresh_difficulty_flags().await {
+                    Ok(flagged) => info!("Test difficulty flags refreshed, {} flagged", flagged),
+                    Err(e) => tracing::error!("Failed to refresh test difficulty flags: {:?}", e),
+                }
+                tokio::time::sleep(DIFFICULTY_REFRESH_INTERVAL).await;
+            }
+        });
+    }
+
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            use recruitment_backend::services::prompt_service::PROMPT_REFRESH_INTERVAL;
+
+            loop {
+                if let Err(e) = state.prompt_service.refresh().await {
+                    tracing::error!("Failed to refresh AI prompts: {:?}", e);
+                }
+                tokio::time::sleep(PROMPT_REFRESH_INTERVAL).await;
+            }
+        });
+    }
+
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            use recruitment_backend::services::dashboard_snapshot_service::next_hourly_run;
+
+            loop {
+                let now = chrono::Utc::now();
+                tokio::time::sleep((next_hourly_run(now) - now).to_std().unwrap_or(Duration::from_secs(60))).await;
+                if let Err(e) = state.dashboard_snapshot_service.record().await {
+                    tracing::error!("Failed to record dashboard snapshot: {:?}", e);
+                }
+            }
+        });
+    }
+
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            use recruitment_backend::services::report_service::{next_weekly_run, ReportWeek};
+
+            let config = get_config();
+            let Some(chat_id) = config.report_chat_id else {
+                info!("REPORT_TELEGRAM_CHAT_ID not set, weekly report scheduler disabled");
+                return;
+            };
+            let tz = config.report_timezone;
+            loop {
+                let now = chrono::Utc::now();
+                let next_run = next_weekly_run(now, tz);
+                info!("Next weekly report scheduled for {}", next_run);
+                tokio::time::sleep((next_run - now).to_std().unwrap_or(Duration::from_secs(60))).await;
+
+                let week = ReportWeek::previous(chrono::Utc::now(), tz);
+                match state.report_service.build_weekly(week, tz).await {
+                    Ok(report) => {
+                        if let Err(e) = state
+                            .report_service
+                            .send_to_telegram(chat_id, &report.to_telegram_text())
+                            .await
+                        {
+                            tracing::error!("Failed to post weekly report {}: {:?}", report.week, e);
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to build weekly report: {:?}", e),
+                }
+            }
+        });
+    }
+
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            let secret = get_config().email_hash_secret.clone();
+            match state.candidate_service.backfill_email_hashes(secret.as_bytes()).await {
+                Ok(0) => {}
+                Ok(n) => info!("Backfilled email_hash for {} candidates", n),
+                Err(e) => tracing::error!("Failed to backfill candidate email hashes: {:?}", e),
+            }
+        });
+    }
+
+    {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            use recruitment_backend::services::report_service::next_weekly_run;
+            use recruitment_backend::services::test_quality_service::{
+                low_quality_digest, TestQualityService,
+            };
+
+            let config = get_config();
+            let tz = config.report_timezone;
+            let quality = TestQualityService::new(state.pool.clone());
+            loop {
+                let now = chrono::Utc::now();
+                let next_run = next_weekly_run(now, tz);
+                info!("Next test quality review scheduled for {}", next_run);
+                tokio::time::sleep((next_run - now).to_std().unwrap_or(Duration::from_secs(60))).await;
+
+                let critiqued = match quality.generate_weekly(&state.ai_service).await {
+                    Ok(critiqued) => critiqued,
+                    Err(e) => {
+                        tracing::error!("Failed to run weekly test quality review: {:?}", e);
+                        continue;
+                    }
+                };
+                info!("Weekly test quality review stored {} reports", critiqued.len());
+                if let (Some(chat_id), Some(text)) = (config.report_chat_id, low_quality_digest(&critiqued)) {
+                    if let Err(e) = state.report_service.send_to_telegram(chat_id, &text).await {
+                        tracing::error!("Failed to post test quality digest: {:?}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    let base_routes = Router::new()
+        .route("/health", get(routes::health::health))
+        .route("/health/detailed", get(routes::health::health_detailed))
+        .route("/health/ready", get(routes::health::health_ready))
+        .route("/metrics", get(routes::metrics::metrics));
 
     let integration_api = Router::new()
         .route(
@@ -191,6 +424,14 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/tests",
             get(routes::integration::list_tests).post(routes::integration::create_test),
         )
+        .route(
+            "/api/integration/tests/import",
+            post(routes::integration::import_test),
+        )
+        .route(
+            "/api/integration/tests/question-template.xlsx",
+            get(routes::integration::question_template),
+        )
         .route(
             "/api/integration/tests/:id",
             get(routes::integration::get_test_by_id)
@@ -199,6 +440,54 @@ async fn main() -> anyhow::Result<()> {
         )
 
 
+        .route(
+            "/api/integration/tests/:id/owners",
+            post(routes::integration::add_test_owner),
+        )
+        .route(
+            "/api/integration/tests/:id/owners/:user_id",
+            axum::routing::delete(routes::integration::remove_test_owner),
+        )
+        .route(
+            "/api/integration/tests/:id/questions/import",
+            post(routes::integration::import_questions),
+        )
+        .route(
+            "/api/integration/tests/:id/regrade",
+            post(routes::integration::regrade_test),
+        )
+        .route(
+            "/api/integration/tests/:id/generation-log",
+            get(routes::integration::get_generation_log),
+        )
+        .route(
+            "/api/integration/tests/:id/feedback",
+            get(routes::integration::list_question_feedback),
+        )
+        .route(
+            "/api/integration/tests/:id/feedback/:feedback_id",
+            axum::routing::patch(routes::integration::review_question_feedback),
+        )
+        .route(
+            "/api/integration/tests/:id/preview",
+            post(routes::integration::preview_test),
+        )
+        .route(
+            "/api/integration/tests/:id/duplicate",
+            post(routes::integration::duplicate_test),
+        )
+        .route(
+            "/api/integration/tests/:id/auto-adjust",
+            post(routes::integration::auto_adjust_test),
+        )
+        .route(
+            "/api/integration/tests/:id/quality-report",
+            post(routes::integration::generate_quality_report),
+        )
+        .route(
+            "/api/integration/tests/:id/quality-report/latest",
+            get(routes::integration::get_latest_quality_report),
+        )
         .route(
             "/api/integration/tests/generate",
             post(routes::integration::generate_test_spec),
@@ -217,7 +506,9 @@ async fn main() -> anyhow::Result<()> {
         )
         .route(
             "/api/integration/external-vacancies",
-            get(routes::koinotinav::list_external_vacancies),
+            get(routes::koinotinav::list_external_vacancies)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .route(
             "/api/onef/vacancies/external",
@@ -233,6 +524,19 @@ async fn main() -> anyhow::Result<()> {
                 .patch(routes::vacancy::update_vacancy)
                 .delete(routes::vacancy::delete_vacancy),
         )
+        .route(
+            "/api/integration/vacancies/:id/analytics",
+            get(routes::vacancy::get_vacancy_analytics),
+        )
+        .route(
+            "/api/integration/vacancies/:id/links",
+            axum::routing::patch(routes::vacancy::update_vacancy_links),
+        )
+        .route(
+            "/api/integration/vacancies/:id/publish-telegram",
+            post(routes::vacancy::publish_vacancy_telegram)
+                .delete(routes::vacancy::unpublish_vacancy_telegram),
+        )
         .route(
             "/api/integration/ai-jobs",
             post(routes::integration::enqueue_ai_job),
@@ -241,6 +545,18 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/ai-jobs/:id",
             get(routes::integration::get_ai_job),
         )
+        .route(
+            "/api/integration/ai-jobs/dead",
+            get(routes::integration::list_dead_ai_jobs),
+        )
+        .route(
+            "/api/integration/ai-jobs/:id/retry",
+            post(routes::integration::retry_ai_job),
+        )
+        .route(
+            "/api/integration/ai-usage",
+            get(routes::integration::get_ai_usage),
+        )
         .route(
             "/api/integration/test-attempts/:id",
             get(routes::integration::get_test_attempt_by_id)
@@ -250,10 +566,30 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/test-attempts/:id/grade",
             post(routes::integration::grade_presentation),
         )
+        .route(
+            "/api/integration/test-attempts/:id/claim",
+            post(routes::integration::claim_attempt_for_review),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/release",
+            post(routes::integration::release_attempt_review),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/extend",
+            post(routes::integration::extend_test_attempt),
+        )
         .route(
             "/api/integration/test-attempts/:id/grade-answer",
             post(routes::integration::grade_test_answer),
         )
+        .route(
+            "/api/integration/test-attempts/:id/summary",
+            get(routes::integration::get_test_attempt_summary),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/change-analysis",
+            post(routes::integration::analyze_answer_changes),
+        )
         .route(
             "/api/integration/test-attempts",
             get(routes::integration::list_test_attempts),
@@ -275,14 +611,42 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/candidates/:id",
             axum::routing::delete(routes::candidate_routes::delete_candidate),
         )
+        .route(
+            "/api/integration/candidates/bulk-status",
+            post(routes::candidate_routes::bulk_update_candidate_status),
+        )
         .route(
             "/api/integration/candidates/:id/status",
             post(routes::candidate_routes::update_candidate_status),
         )
+        .route(
+            "/api/integration/candidates/:id/telegram-thread",
+            axum::routing::patch(routes::candidate_routes::update_candidate_telegram_thread),
+        )
+        .route(
+            "/api/integration/candidates/:id/status-token",
+            axum::routing::delete(routes::candidate_routes::revoke_candidate_status_token),
+        )
+        .route(
+            "/api/integration/candidates/:id/portfolio",
+            get(routes::candidate_routes::get_candidate_portfolio),
+        )
+        .route(
+            "/api/integration/candidates/:id/reparse-cv",
+            post(routes::candidate_routes::reparse_candidate_cv),
+        )
+        .route(
+            "/api/integration/candidates/:id/schedule-interview",
+            post(routes::interviews::schedule_interview),
+        )
         .route(
             "/api/integration/analyze-suitability/:id",
             post(routes::candidate_routes::analyze_candidate_suitability),
         )
+        .route(
+            "/api/integration/candidates/:id/rejection-message",
+            post(routes::candidate_routes::generate_rejection_message),
+        )
         .route(
             "/api/integration/candidates/:id/onef-grade",
             post(routes::candidate_routes::share_candidate_grade_to_onef),
@@ -291,10 +655,59 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/tests/all",
             get(routes::integration::list_all_tests),
         )
+        .route(
+            "/api/integration/candidates/stale",
+            get(routes::integration::list_stale_candidates),
+        )
         .route(
             "/api/integration/candidates/statuses",
             get(routes::integration::sync_candidate_statuses),
         )
+        .route(
+            "/api/integration/candidates/cv-dedup-report",
+            get(routes::candidate_routes::cv_dedup_report),
+        )
+        .route(
+            "/api/integration/candidates/referral-stats",
+            get(routes::referrals::referral_stats),
+        )
+        .route(
+            "/api/integration/referral-codes",
+            post(routes::referrals::create_referral_code),
+        )
+        .route(
+            "/api/integration/webhook-subscriptions",
+            post(routes::webhook_subscriptions::create_webhook_subscription)
+                .get(routes::webhook_subscriptions::list_webhook_subscriptions),
+        )
+        .route(
+            "/api/integration/webhook-subscriptions/:id",
+            get(routes::webhook_subscriptions::get_webhook_subscription)
+                .patch(routes::webhook_subscriptions::update_webhook_subscription)
+                .delete(routes::webhook_subscriptions::delete_webhook_subscription),
+        )
+        // Older paths, kept for existing consumers.
+        .route(
+            "/api/integration/webhooks",
+            post(routes::webhook_subscriptions::create_webhook_subscription)
+                .get(routes::webhook_subscriptions::list_webhook_subscriptions),
+        )
+        .route(
+            "/api/integration/webhooks/:id",
+            axum::routing::delete(routes::webhook_subscriptions::delete_webhook_subscription),
+        )
+        .route(
+            "/api/integration/broadcasts",
+            post(routes::broadcasts::create_broadcast),
+        )
+        .route(
+            "/api/integration/broadcasts/:id",
+            get(routes::broadcasts::get_broadcast),
+        )
+        .route(
+            "/api/integration/reports/weekly",
+            get(routes::reports::weekly_report),
+        )
         .route(
             "/api/integration/test-attempts/needs-review",
             get(routes::integration::list_attempts_for_review),
@@ -307,6 +720,10 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/messages/:candidate_id",
             get(routes::integration::get_chat_messages),
         )
+        .route(
+            "/api/integration/messages/:candidate_id/events",
+            get(routes::integration::chat_events),
+        )
         .route(
             "/api/integration/messages/unread",
             get(routes::integration::get_unread_count),
@@ -320,6 +737,10 @@ async fn main() -> anyhow::Result<()> {
             "/api/integration/dashboard/stats",
             get(routes::integration::get_dashboard_stats),
         )
+        .route(
+            "/api/integration/dashboard/history",
+            get(routes::integration::get_dashboard_history),
+        )
         .route(
             "/api/integration/candidates/:id/export",
             get(routes::export::export_candidate),
@@ -357,7 +778,13 @@ async fn main() -> anyhow::Result<()> {
         )
         .route(
             "/api/public/tests/:token/status",
-            get(routes::public::get_status),
+            get(routes::public::get_status)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
+        )
+        .route(
+            "/api/public/tests/:token/result",
+            get(routes::public::get_result)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
         )
         .route(
             "/api/public/tests/:token/heartbeat",
@@ -367,55 +794,117 @@ async fn main() -> anyhow::Result<()> {
             "/api/public/tests/:token/report-violation",
             post(routes::public::report_violation),
         )
+        .route(
+            "/api/public/tests/:token/feedback",
+            post(routes::public::submit_question_feedback),
+        )
+        .route(
+            "/api/public/i18n/:lang",
+            get(routes::public::get_i18n_strings)
+                .layer(axum::middleware::from_fn(cache_control::public_cache)),
+        )
+        .route(
+            "/api/files/signed/:token",
+            get(routes::candidate_routes::download_signed_file),
+        )
+        .route(
+            "/api/public/timezones",
+            get(routes::public::list_timezones)
+                .layer(axum::middleware::from_fn(cache_control::public_cache)),
+        )
+        .route(
+            "/api/public/candidates/:status_token/status",
+            get(routes::candidate_routes::get_public_candidate_status)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
+        )
         .route(
             "/api/public/vacancies",
-            get(routes::vacancy::list_public_vacancies),
+            get(routes::vacancy::list_public_vacancies)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .route(
             "/api/public/vacancies/:id",
-            get(routes::vacancy::get_public_vacancy),
+            get(routes::vacancy::get_public_vacancy)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .route(
             "/api/webhook/telegram",
             post(routes::telegram::handle_webhook),
         )
-        .route(
-            "/api/candidate/register",
-            post(routes::candidate_routes::register_candidate),
-        )
         .route(
             "/api/candidate/:id",
-            get(routes::candidate_routes::get_candidate),
+            get(routes::candidate_routes::get_candidate)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
         )
         .route(
             "/api/candidate/:id/cv",
             axum::routing::patch(routes::candidate_routes::update_candidate_cv),
         )
         .route(
-            "/api/candidate/apply",
-            post(routes::candidate_routes::apply_for_vacancy),
+            "/api/candidate/:id/applications",
+            get(routes::candidate_routes::get_candidate_applications)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
         )
         .route(
-            "/api/candidate/:id/applications",
-            get(routes::candidate_routes::get_candidate_applications),
+            "/api/candidate/:id/applications/:application_id/withdraw",
+            post(routes::candidate_routes::withdraw_application),
         )
         .route(
             "/api/vacancy/:id/candidates",
             get(routes::candidate_routes::get_candidates_for_vacancy),
         )
+        .route(
+            "/api/candidate/:id/onboarding",
+            get(routes::candidate_routes::get_candidate_onboarding)
+                .patch(routes::candidate_routes::update_candidate_onboarding)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
+        )
         .route(
             "/api/candidate/:id/history",
-            get(routes::candidate_routes::get_candidate_history),
+            get(routes::candidate_routes::get_candidate_history)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
+        )
+        .route(
+            "/api/candidate/:id/export-data",
+            get(routes::export::export_candidate_data)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
         )
         .route(
             "/api/external-vacancies",
-            get(routes::koinotinav::list_external_vacancies),
+            get(routes::koinotinav::list_external_vacancies)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .layer(axum::middleware::from_fn_with_state(
             recruitment_backend::middleware::rate_limit::new_rps_state(config.public_rps),
             recruitment_backend::middleware::rate_limit::rps_middleware,
         ));
 
+    // Unauthenticated writes that create candidates and store uploads get a
+    // much tighter per-IP budget than the rest of the public API.
+    let candidate_submit_api = Router::new()
+        .route(
+            "/api/candidate/register",
+            post(routes::candidate_routes::register_candidate),
+        )
+        .route(
+            "/api/candidate/apply",
+            post(routes::candidate_routes::apply_for_vacancy),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            recruitment_backend::middleware::rate_limit::IpRateLimiter::new(
+                config.candidate_submit_per_minute,
+                Duration::from_secs(60),
+            ),
+            recruitment_backend::middleware::rate_limit::ip_window_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            recruitment_backend::middleware::rate_limit::new_rps_state(config.public_rps),
+            recruitment_backend::middleware::rate_limit::rps_middleware,
+        ));
+
     let onef_api = Router::new()
         .route(
             "/api/onef/messages",
@@ -447,7 +936,8 @@ async fn main() -> anyhow::Result<()> {
         )
         .route(
             "/api/onef/candidates/:id",
-            get(routes::onef::get_candidate),
+            get(routes::onef::get_candidate)
+                .layer(axum::middleware::from_fn(cache_control::private_no_store)),
         )
         .route(
             "/api/onef/candidates/:id/attempts",
@@ -487,11 +977,15 @@ async fn main() -> anyhow::Result<()> {
         )
         .route(
             "/api/onef/dictionaries/candidate-statuses",
-            get(routes::onef::list_candidate_statuses),
+            get(routes::onef::list_candidate_statuses)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .route(
             "/api/onef/dictionaries/test-statuses",
-            get(routes::onef::list_test_statuses),
+            get(routes::onef::list_test_statuses)
+                .layer(axum::middleware::from_fn(cache_control::public_cache))
+                .layer(axum::middleware::from_fn(etag::etag)),
         )
         .layer(axum::middleware::from_fn_with_state(
             recruitment_backend::middleware::rate_limit::new_rps_state(config.integration_rps),
@@ -533,26 +1027,62 @@ async fn main() -> anyhow::Result<()> {
             recruitment_backend::middleware::auth::require_admin,
         ));
 
+    let organizations_admin = Router::new()
+        .route(
+            "/api/admin/organizations",
+            get(routes::organizations::list_organizations)
+                .post(routes::organizations::create_organization),
+        )
+        .route(
+            "/api/admin/organizations/:id",
+            get(routes::organizations::get_organization),
+        )
+        .layer(axum::middleware::from_fn(
+            recruitment_backend::middleware::auth::require_super_admin,
+        ));
+
     let upload_path = std::env::var("UPLOADS_DIR").unwrap_or_else(|_| "/app/uploads".to_string());
     info!("Serving uploads from: {}", upload_path);
 
     let app = base_routes
         .merge(integration_api)
         .merge(public_api)
+        .merge(candidate_submit_api)
         .merge(onef_api)
         .merge(auth_public)
         .merge(auth_session)
         .merge(auth_admin)
-        .nest_service("/uploads", tower_http::services::ServeDir::new(upload_path))
+        .merge(organizations_admin)
+        .nest_service(
+            "/uploads",
+            tower::ServiceBuilder::new()
+                .layer(axum::middleware::from_fn(
+                    recruitment_backend::middleware::signed_upload::require_signed_upload,
+                ))
+                .service(tower_http::services::ServeDir::new(upload_path)),
+        )
         .with_state(app_state)
+        .layer(axum::middleware::from_fn(
+            recruitment_backend::middleware::organization::organization_context,
+        ))
+        .layer(axum::middleware::from_fn(
+            recruitment_backend::middleware::metrics::track_http_metrics,
+        ))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .layer(DefaultBodyLimit::max(50 * 1024 * 1024));
+        .layer(DefaultBodyLimit::max(50 * 1024 * 1024))
+        .layer(axum::middleware::from_fn(
+            recruitment_backend::middleware::request_id::assign_request_id,
+        ));
 
     let addr: SocketAddr = config.server_address.parse()?;
     info!("Server listening on {}", addr);
     let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
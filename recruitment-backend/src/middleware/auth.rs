@@ -1,23 +1,46 @@
 use axum::{
     extract::Request,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::organization::DEFAULT_ORGANIZATION_ID;
+
+/// Manages organizations and acts as `admin` in any of them.
+pub const SUPER_ADMIN_ROLE: &str = "super_admin";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub role: Option<String>,
+    /// Tokens minted before organizations existed have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<Uuid>,
+}
+
+impl Claims {
+    /// The organization the token acts for.
+    pub fn organization_id(&self) -> Uuid {
+        self.org_id.unwrap_or(DEFAULT_ORGANIZATION_ID)
+    }
+
+    pub fn is_super_admin(&self) -> bool {
+        self.role
+            .as_deref()
+            .is_some_and(|role| role.eq_ignore_ascii_case(SUPER_ADMIN_ROLE))
+    }
 }
 
 pub fn mint_token(
     user_id: &str,
     role: &str,
+    org_id: Uuid,
     ttl_hours: i64,
 ) -> Result<String, jsonwebtoken::errors::Error> {
     let exp = (chrono::Utc::now() + chrono::Duration::hours(ttl_hours)).timestamp() as usize;
@@ -25,6 +48,7 @@ pub fn mint_token(
         sub: user_id.to_string(),
         exp,
         role: Some(role.to_string()),
+        org_id: Some(org_id),
     };
     let secret = crate::config::get_config().jwt_secret.clone();
     encode(
@@ -34,8 +58,40 @@ pub fn mint_token(
     )
 }
 
+/// Claims of a valid bearer token, for routes that are not behind an auth
+/// layer but unlock extra behaviour for signed-in users.
+pub fn bearer_claims(headers: &HeaderMap) -> Option<Claims> {
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    let config = crate::config::get_config();
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = true;
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        &validation,
+    )
+    .ok()
+    .map(|data| data.claims)
+}
+
+pub fn is_admin(headers: &HeaderMap) -> bool {
+    bearer_claims(headers)
+        .and_then(|claims| claims.role)
+        .is_some_and(|role| {
+            role.eq_ignore_ascii_case("admin") || role.eq_ignore_ascii_case(SUPER_ADMIN_ROLE)
+        })
+}
+
 pub async fn require_admin(req: Request, next: Next) -> Response {
-    require_roles(req, next, &["admin"]).await
+    require_roles(req, next, &["admin", SUPER_ADMIN_ROLE]).await
+}
+
+pub async fn require_super_admin(req: Request, next: Next) -> Response {
+    require_roles(req, next, &[SUPER_ADMIN_ROLE]).await
 }
 
 pub async fn require_bearer_auth(mut req: Request, next: Next) -> Response {
@@ -114,7 +170,7 @@ pub async fn require_hr_or_admin(mut req: Request, next: Next) -> Response {
     ) {
         Ok(data) => {
             let role = data.claims.role.clone().unwrap_or_default();
-            let allowed = ["admin", "hr"];
+            let allowed = ["admin", "hr", SUPER_ADMIN_ROLE];
             if !allowed.iter().any(|r| r.eq_ignore_ascii_case(&role)) {
                 return (StatusCode::FORBIDDEN, Json(json!({"error":"forbidden"}))).into_response();
             }
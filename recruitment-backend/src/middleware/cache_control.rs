@@ -0,0 +1,30 @@
+use axum::body::Body;
+use axum::http::{header, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+pub const PUBLIC_MAX_AGE: &str = "public, max-age=60";
+pub const PRIVATE_NO_STORE: &str = "private, no-store";
+
+/// Lets browsers and proxies reuse successful responses of read-heavy,
+/// non-personal routes for a minute. Errors are never marked cacheable.
+pub async fn public_cache(req: Request<Body>, next: Next) -> Response {
+    let mut response = next.run(req).await;
+    if response.status().is_success() {
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(PUBLIC_MAX_AGE),
+        );
+    }
+    response
+}
+
+/// Keeps candidate-specific responses out of shared and browser caches.
+pub async fn private_no_store(req: Request<Body>, next: Next) -> Response {
+    let mut response = next.run(req).await;
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(PRIVATE_NO_STORE),
+    );
+    response
+}
@@ -0,0 +1,64 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+
+// Cached routes return small JSON lists; anything larger is passed through untouched.
+const MAX_ETAG_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Short strong ETag: the first 16 hex chars of the body's SHA-256.
+pub fn compute_etag(body: &[u8]) -> String {
+    let digest = hex::encode(Sha256::digest(body));
+    format!("\"{}\"", &digest[..16])
+}
+
+/// True when any entry of `If-None-Match` (or `*`) matches `etag`.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Tags successful GET responses with an `ETag` and answers
+/// `304 Not Modified` when the client already has the same body.
+pub async fn etag(req: Request<Body>, next: Next) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return next.run(req).await;
+    }
+    let request_headers = req.headers().clone();
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::ETAG) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ETAG_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer response body for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let tag = compute_etag(&bytes);
+    let value = HeaderValue::from_str(&tag).expect("hex etag is a valid header value");
+
+    if if_none_match(&request_headers, &tag) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::CACHE_CONTROL, header::VARY] {
+            if let Some(v) = parts.headers.get(&name) {
+                not_modified.headers_mut().insert(name, v.clone());
+            }
+        }
+        not_modified.headers_mut().insert(header::ETAG, value);
+        return not_modified;
+    }
+
+    parts.headers.insert(header::ETAG, value);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(bytes))
+}
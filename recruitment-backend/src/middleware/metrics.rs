@@ -0,0 +1,98 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::async_trait;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, FromRequestParts, MatchedPath};
+use axum::http::{request::Parts, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+use crate::error::Error;
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+pub const AI_GENERATION_DURATION_SECONDS: &str = "ai_generation_duration_seconds";
+pub const AI_QUEUE_DEPTH: &str = "ai_queue_depth";
+pub const TEST_ATTEMPTS_BY_STATUS: &str = "test_attempts_by_status";
+
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+// The recorder is process-global, so repeated AppState construction (tests) reuses it.
+pub fn install_recorder() -> PrometheusHandle {
+    PROMETHEUS_HANDLE
+        .get_or_init(|| {
+            let handle = PrometheusBuilder::new()
+                .install_recorder()
+                .expect("failed to install Prometheus recorder");
+
+            metrics::describe_counter!(HTTP_REQUESTS_TOTAL, "Total HTTP requests by route and status code");
+            metrics::describe_histogram!(HTTP_REQUEST_DURATION_SECONDS, "HTTP request latency by route");
+            metrics::describe_histogram!(AI_GENERATION_DURATION_SECONDS, "AI test generation latency");
+            metrics::describe_gauge!(AI_QUEUE_DEPTH, "Number of pending AI jobs");
+            metrics::describe_gauge!(TEST_ATTEMPTS_BY_STATUS, "Test attempts per status");
+
+            handle
+        })
+        .clone()
+}
+
+pub async fn track_http_metrics(req: Request<Body>, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = req.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16().to_string();
+    metrics::counter!(
+        HTTP_REQUESTS_TOTAL,
+        "method" => method.clone(),
+        "route" => route.clone(),
+        "status" => status
+    )
+    .increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, "method" => method, "route" => route)
+        .record(started.elapsed().as_secs_f64());
+
+    response
+}
+
+pub struct MetricsAccess(pub IpAddr);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for MetricsAccess
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let forbidden = || {
+            Error::coded(
+                StatusCode::FORBIDDEN,
+                "metrics_forbidden",
+                "Metrics are only served to allowed networks",
+            )
+        };
+
+        let Some(ConnectInfo(addr)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() else {
+            return Err(forbidden());
+        };
+
+        let ip = addr.ip();
+        let allowed = &crate::config::get_config().metrics_allowed_networks;
+        if allowed.iter().any(|net| net.contains(ip)) {
+            Ok(MetricsAccess(ip))
+        } else {
+            tracing::warn!("Rejected /metrics request from {}", ip);
+            Err(forbidden())
+        }
+    }
+}
@@ -1,4 +1,10 @@
 pub mod auth;
+pub mod cache_control;
 pub mod cors;
+pub mod etag;
 pub mod logging;
+pub mod metrics;
+pub mod organization;
 pub mod rate_limit;
+pub mod request_id;
+pub mod signed_upload;
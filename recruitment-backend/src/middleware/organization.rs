@@ -0,0 +1,66 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+use std::convert::Infallible;
+use uuid::Uuid;
+
+use crate::middleware::auth::{bearer_claims, Claims};
+use crate::models::organization::DEFAULT_ORGANIZATION_ID;
+
+/// The organization a request acts for, taken from the bearer token's
+/// `org_id`. Requests without a valid token (signed integration calls, the
+/// Telegram bot, the public test pages) act for the default organization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrganizationContext {
+    pub org_id: Uuid,
+}
+
+impl Default for OrganizationContext {
+    fn default() -> Self {
+        Self {
+            org_id: DEFAULT_ORGANIZATION_ID,
+        }
+    }
+}
+
+impl OrganizationContext {
+    fn resolve(parts: &Parts) -> Self {
+        let org_id = parts
+            .extensions
+            .get::<Claims>()
+            .map(Claims::organization_id)
+            .or_else(|| bearer_claims(&parts.headers).map(|claims| claims.organization_id()))
+            .unwrap_or(DEFAULT_ORGANIZATION_ID);
+        Self { org_id }
+    }
+}
+
+/// Stores the request's [`OrganizationContext`] as an extension.
+pub async fn organization_context(req: Request, next: Next) -> Response {
+    let (mut parts, body) = req.into_parts();
+    let context = OrganizationContext::resolve(&parts);
+    parts.extensions.insert(context);
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Reads the extension set by [`organization_context`], resolving it from the
+/// request when the layer is not installed.
+#[async_trait]
+impl<S> FromRequestParts<S> for OrganizationContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<OrganizationContext>()
+            .copied()
+            .unwrap_or_else(|| Self::resolve(parts)))
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use axum::body::Body;
-use axum::extract::State;
-use axum::http::{Request, StatusCode};
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, Request, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 
@@ -60,3 +62,85 @@ pub async fn rps_middleware(
 pub fn new_rps_state(rps: u32) -> RateLimiter {
     RateLimiter::new(rps)
 }
+
+/// Keys with no request inside the window are dropped once the map grows past this.
+const IP_LIMITER_PRUNE_THRESHOLD: usize = 4096;
+
+/// Per-client sliding window: at most `limit` requests in any `window`.
+#[derive(Clone, Debug)]
+pub struct IpRateLimiter {
+    limit: usize,
+    window: Duration,
+    hits: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl IpRateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit: limit.max(1) as usize,
+            window,
+            hits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records a request from `key`; `Err` carries the seconds until the
+    /// oldest request in the window expires.
+    pub fn check(&self, key: &str) -> Result<(), u64> {
+        let mut map = self.hits.lock().expect("ip rate limiter mutex poisoned");
+        let now = Instant::now();
+
+        if map.len() > IP_LIMITER_PRUNE_THRESHOLD {
+            let window = self.window;
+            map.retain(|_, hits| hits.back().is_some_and(|t| now.duration_since(*t) < window));
+        }
+
+        let hits = map.entry(key.to_string()).or_default();
+        while hits
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            hits.pop_front();
+        }
+        if hits.len() >= self.limit {
+            let oldest = *hits.front().expect("limit is at least 1");
+            let wait = self.window.saturating_sub(now.duration_since(oldest));
+            return Err(wait.as_secs() + 1);
+        }
+        hits.push_back(now);
+        Ok(())
+    }
+}
+
+pub async fn ip_window_middleware(
+    State(limiter): State<IpRateLimiter>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    // Forwarding headers count only from a trusted proxy; otherwise a client
+    // could pick a fresh bucket per request.
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = crate::utils::client_ip::resolve_client_ip(
+        req.headers(),
+        peer,
+        &crate::config::get_config().trusted_proxies,
+    )
+    .map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+
+    if let Err(retry_after) = limiter.check(&ip) {
+        tracing::warn!("Rate limit hit on {} from {}", req.uri().path(), ip);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            axum::Json(crate::error::error_body(
+                "rate_limit_exceeded",
+                "Too many submissions from this address, please try again later",
+                Some(serde_json::json!({ "retry_after_seconds": retry_after })),
+            )),
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
@@ -0,0 +1,48 @@
+use std::future::Future;
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: Uuid;
+}
+
+/// Correlation ID of the request currently being handled, if any.
+pub fn current_request_id() -> Option<Uuid> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+/// Runs `fut` with `id` as its correlation ID, e.g. inside a `tokio::spawn`
+/// started by a handler.
+pub async fn with_request_id<F: Future>(id: Uuid, fut: F) -> F::Output {
+    REQUEST_ID.scope(id, fut).await
+}
+
+/// Tags every request with an `X-Request-Id` (reusing a valid incoming one)
+/// and runs the rest of the stack inside a `request` span carrying it.
+pub async fn assign_request_id(mut req: Request<Body>, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v.trim()).ok())
+        .unwrap_or_else(Uuid::new_v4);
+    let header = HeaderValue::from_str(&id.to_string()).expect("uuid is a valid header value");
+    req.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        uri = %req.uri(),
+    );
+    let mut response = REQUEST_ID.scope(id, next.run(req)).instrument(span).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
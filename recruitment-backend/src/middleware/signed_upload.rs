@@ -0,0 +1,34 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use percent_encoding::percent_decode_str;
+
+use crate::error::Error;
+use crate::utils::signed_url::{verify_signed_path, SIGNED_UPLOAD_DIRS};
+
+/// Guards the uploads file server. Files under `SIGNED_UPLOAD_DIRS` are only
+/// served for a link produced by `signed_upload_url` that has not expired.
+/// Expects the path relative to the upload root, as `nest_service` passes it.
+pub async fn require_signed_upload(req: Request<Body>, next: Next) -> Response {
+    // Decoded the way `ServeDir` decodes it, so `%63v/...` cannot slip past.
+    let decoded = percent_decode_str(req.uri().path()).decode_utf8_lossy();
+    let path = decoded.trim_start_matches('/');
+    let protected = path
+        .split('/')
+        .find(|segment| !segment.is_empty() && *segment != ".")
+        .is_some_and(|dir| SIGNED_UPLOAD_DIRS.contains(&dir));
+    if protected {
+        let secret = crate::config::get_config().upload_signing_secret.as_bytes();
+        let now = chrono::Utc::now().timestamp();
+        if !verify_signed_path(path, req.uri().query(), secret, now) {
+            return Error::coded(
+                StatusCode::FORBIDDEN,
+                "invalid_file_signature",
+                "File link is invalid or has expired",
+            )
+            .into_response();
+        }
+    }
+    next.run(req).await
+}
@@ -14,5 +14,6 @@ pub struct AuditLog {
     pub changes: Option<JsonValue>,
     pub ip_address: Option<sqlx::types::ipnetwork::IpNetwork>,
     pub user_agent: Option<String>,
+    pub correlation_id: Option<Uuid>,
     pub created_at: Option<DateTime<Utc>>,
 }
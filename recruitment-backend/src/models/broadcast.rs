@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Which candidates a broadcast goes to. Empty fields don't filter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BroadcastFilter {
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    #[serde(default)]
+    pub vacancy_id: Option<i64>,
+    #[serde(default)]
+    pub min_ai_rating: Option<i32>,
+    #[serde(default)]
+    pub has_telegram: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Broadcast {
+    pub id: Uuid,
+    pub filter: JsonValue,
+    pub message_template: String,
+    /// `sending` until every recipient is `sent` or `failed`, then `completed`.
+    pub status: String,
+    pub total_recipients: i32,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BroadcastRecipient {
+    pub id: Uuid,
+    pub broadcast_id: Uuid,
+    pub candidate_id: Option<Uuid>,
+    pub telegram_id: Option<i64>,
+    pub telegram_thread_id: Option<i64>,
+    /// The template rendered for this candidate.
+    pub message: String,
+    /// `pending`, `sending`, `sent` or `failed`.
+    pub status: String,
+    pub error: Option<String>,
+    pub attempted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A matched candidate as shown by a dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BroadcastPreviewRecipient {
+    pub candidate_id: Uuid,
+    pub name: String,
+    pub status: String,
+    pub ai_rating: Option<i32>,
+    pub telegram_id: Option<i64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BroadcastPreview {
+    pub dry_run: bool,
+    pub matched: usize,
+    pub recipients: Vec<BroadcastPreviewRecipient>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BroadcastFailure {
+    pub candidate_id: Option<Uuid>,
+    pub telegram_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BroadcastProgress {
+    #[serde(flatten)]
+    pub broadcast: Broadcast,
+    pub pending: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub failures: Vec<BroadcastFailure>,
+}
@@ -8,21 +8,186 @@ use uuid::Uuid;
 pub struct Candidate {
     pub id: Uuid,
     pub telegram_id: Option<i64>,
-    pub name: String,
-    pub email: String,
+    /// Forum topic that Telegram messages to this candidate are posted in.
+    pub telegram_thread_id: Option<i64>,
+    /// `None` once the candidate has been erased.
+    pub name: Option<String>,
+    pub email: Option<String>,
     pub phone: Option<String>,
     pub cv_url: Option<String>,
+    pub photo_url: Option<String>,
+    /// Stored paths of the extra documents uploaded with the CV.
+    pub cv_portfolio: Option<JsonValue>,
     pub dob: Option<chrono::NaiveDate>,
     pub vacancy_id: Option<i64>,
     pub profile_data: Option<JsonValue>,
     pub ai_rating: Option<i32>,
     pub ai_comment: Option<String>,
+    pub skills: Option<Vec<String>>,
+    /// `CvProfile` extracted from the CV; `None` until parsed or if parsing failed.
+    pub extracted_profile: Option<JsonValue>,
+    /// IANA timezone for deadlines in Telegram messages; `None` means UTC.
+    pub timezone: Option<String>,
+    /// Telegram refused the last few messages (the candidate blocked the
+    /// bot), so nothing is sent there until they write to the bot again.
+    pub telegram_unreachable: bool,
     pub status: String,
     pub unread_messages: Option<i64>,
+    /// Latest of registration, last application, last test attempt change
+    /// and last inbound message; `None` where the query does not compute it.
+    pub last_activity_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+impl Candidate {
+    /// `name`, or an empty string once the candidate has been erased.
+    pub fn name_or_empty(&self) -> &str {
+        self.name.as_deref().unwrap_or_default()
+    }
+
+    /// `email`, or an empty string once the candidate has been erased.
+    pub fn email_or_empty(&self) -> &str {
+        self.email.as_deref().unwrap_or_default()
+    }
+
+    /// Stored paths in `cv_portfolio`, in upload order.
+    pub fn portfolio_paths(&self) -> Vec<String> {
+        self.cv_portfolio
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Structured view of `profile_data`; `None` for legacy free-form blobs.
+    pub fn profile(&self) -> Option<CandidateProfileData> {
+        let data = self.profile_data.as_ref()?;
+        if data.get(LEGACY_PROFILE_KEY).is_some() {
+            return None;
+        }
+        serde_json::from_value(data.clone()).ok()
+    }
+}
+
+/// Maximum number of `portfolio_file_*` uploads per registration.
+pub const MAX_PORTFOLIO_FILES: usize = 5;
+
+pub const LEGACY_PROFILE_KEY: &str = "legacy";
+pub const MAX_EXPERIENCE_YEARS: u32 = 70;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CandidateProfileData {
+    #[serde(default)]
+    pub city: Option<String>,
+    #[serde(default)]
+    pub education: Option<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub experience_years: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileDataInput {
+    Structured(CandidateProfileData),
+    /// Free-form JSON from older clients, kept verbatim under `LEGACY_PROFILE_KEY`.
+    Legacy(JsonValue),
+}
+
+impl ProfileDataInput {
+    pub fn into_json(self) -> JsonValue {
+        match self {
+            ProfileDataInput::Structured(data) => {
+                serde_json::to_value(data).unwrap_or(JsonValue::Null)
+            }
+            ProfileDataInput::Legacy(raw) => serde_json::json!({ LEGACY_PROFILE_KEY: raw }),
+        }
+    }
+}
+
+impl CandidateProfileData {
+    pub const FIELDS: [&'static str; 4] = ["city", "education", "languages", "experience_years"];
+
+    /// Strictly parses `value`. Objects that use none of the known fields are
+    /// treated as legacy payloads; anything else must match the schema exactly,
+    /// otherwise every problem found is returned.
+    pub fn parse(value: JsonValue) -> std::result::Result<ProfileDataInput, Vec<String>> {
+        let object = match value.as_object() {
+            Some(object) => object,
+            None => return Err(vec!["profile_data must be a JSON object".to_string()]),
+        };
+        if !object.keys().any(|k| Self::FIELDS.contains(&k.as_str())) {
+            return Ok(ProfileDataInput::Legacy(value));
+        }
+
+        let mut problems: Vec<String> = object
+            .keys()
+            .filter(|k| !Self::FIELDS.contains(&k.as_str()))
+            .map(|k| format!("unknown field `{}`", k))
+            .collect();
+
+        for (key, field_value) in object.iter().filter(|(k, _)| Self::FIELDS.contains(&k.as_str())) {
+            let single = serde_json::json!({ key.as_str(): field_value });
+            if let Err(e) = serde_json::from_value::<CandidateProfileData>(single) {
+                problems.push(format!("{}: {}", key, e));
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        let data: CandidateProfileData =
+            serde_json::from_value(value.clone()).map_err(|e| vec![e.to_string()])?;
+        let problems = data.problems();
+        if problems.is_empty() {
+            Ok(ProfileDataInput::Structured(data.normalized()))
+        } else {
+            Err(problems)
+        }
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (name, value) in [("city", &self.city), ("education", &self.education)] {
+            if matches!(value, Some(v) if v.chars().count() > 255) {
+                problems.push(format!("{}: must be at most 255 characters", name));
+            }
+        }
+        if self.languages.len() > 20 {
+            problems.push("languages: at most 20 entries are allowed".to_string());
+        }
+        if self.languages.iter().any(|l| l.trim().is_empty()) {
+            problems.push("languages: entries must not be empty".to_string());
+        }
+        if matches!(self.experience_years, Some(y) if y > MAX_EXPERIENCE_YEARS) {
+            problems.push(format!(
+                "experience_years: must be between 0 and {}",
+                MAX_EXPERIENCE_YEARS
+            ));
+        }
+        problems
+    }
+
+    fn normalized(self) -> Self {
+        let trim = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        Self {
+            city: trim(self.city),
+            education: trim(self.education),
+            languages: self.languages.into_iter().map(|l| l.trim().to_string()).collect(),
+            experience_years: self.experience_years,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryItem {
     pub event_type: String,
@@ -33,10 +198,79 @@ pub struct HistoryItem {
     pub metadata: Option<JsonValue>,
 }
 
+/// Raw onboarding columns of a candidate. `checklist_completed_items` holds
+/// zero-based indices into `onboarding_checklist`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CandidateOnboarding {
+    pub candidate_id: Uuid,
+    pub onboarding_checklist: Option<JsonValue>,
+    pub checklist_completed_items: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnboardingItem {
+    pub index: usize,
+    pub text: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingChecklist {
+    pub candidate_id: Uuid,
+    pub items: Vec<OnboardingItem>,
+    pub completed: usize,
+    pub total: usize,
+}
+
+impl CandidateOnboarding {
+    pub fn items(&self) -> Vec<String> {
+        self.onboarding_checklist
+            .as_ref()
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn completed_items(&self) -> Vec<usize> {
+        self.checklist_completed_items
+            .as_ref()
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// `None` until a checklist has been generated.
+    pub fn checklist(&self) -> Option<OnboardingChecklist> {
+        self.onboarding_checklist.as_ref()?;
+        let done = self.completed_items();
+        let items: Vec<OnboardingItem> = self
+            .items()
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| OnboardingItem { index, text, done: done.contains(&index) })
+            .collect();
+        Some(OnboardingChecklist {
+            candidate_id: self.candidate_id,
+            completed: items.iter().filter(|i| i.done).count(),
+            total: items.len(),
+            items,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CandidateApplication {
     pub id: i32,
     pub candidate_id: Uuid,
     pub vacancy_id: i64,
+    pub status: String,
     pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of applying to a vacancy. `already_applied` is set when the
+/// candidate already had a live application, which is returned as is.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplicationResult {
+    #[serde(flatten)]
+    pub application: CandidateApplication,
+    pub already_applied: bool,
 }
@@ -12,6 +12,8 @@ pub struct Message {
     pub text: String,
     pub created_at: DateTime<Utc>,
     pub read_at: Option<DateTime<Utc>>,
+    pub attachment_path: Option<String>,
+    pub attachment_type: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,4 +22,32 @@ pub struct CreateMessage {
     pub telegram_id: i64,
     pub direction: String,
     pub text: String,
+    #[serde(default)]
+    pub attachment_path: Option<String>,
+    #[serde(default)]
+    pub attachment_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageEvent {
+    #[serde(skip_serializing)]
+    pub candidate_id: Uuid,
+    pub direction: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    pub attachment_path: Option<String>,
+    pub attachment_type: Option<String>,
+}
+
+impl From<&Message> for MessageEvent {
+    fn from(msg: &Message) -> Self {
+        Self {
+            candidate_id: msg.candidate_id,
+            direction: msg.direction.clone(),
+            text: msg.text.clone(),
+            created_at: msg.created_at,
+            attachment_path: msg.attachment_path.clone(),
+            attachment_type: msg.attachment_type.clone(),
+        }
+    }
 }
@@ -1,12 +1,19 @@
 pub mod answer;
 pub mod answer_log;
 pub mod audit_log;
+pub mod broadcast;
 pub mod question;
+pub mod question_feedback;
+pub mod test_quality_report;
+pub mod referral;
 pub mod test;
 pub mod test_attempt;
 pub mod user;
 pub mod candidate;
 pub mod vacancy;
+pub mod vacancy_link;
 pub mod webhook_log;
+pub mod webhook_subscription;
 pub mod message;
+pub mod organization;
 pub mod response;
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Owns every row that existed before organizations were introduced, and
+/// anything created without an organization in context (Telegram sign-ups,
+/// signed integration calls, tokens minted before `org_id` was added).
+pub const DEFAULT_ORGANIZATION_ID: Uuid = Uuid::from_u128(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
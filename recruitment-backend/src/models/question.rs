@@ -9,6 +9,8 @@ pub struct Question {
     pub question: String,
     #[serde(default = "default_points")]
     pub points: i32,
+    #[serde(default)]
+    pub difficulty: Difficulty,
     #[serde(flatten)]
     pub details: QuestionDetails,
 }
@@ -17,12 +19,55 @@ fn default_points() -> i32 {
     1
 }
 
+/// Missing on tests generated before difficulty tagging; those read back as `Medium`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// Accepts the labels the model tends to produce (`Hard`, `difficult`, `beginner`, ...).
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "easy" | "beginner" | "junior" | "basic" => Some(Difficulty::Easy),
+            "medium" | "intermediate" | "middle" | "normal" => Some(Difficulty::Medium),
+            "hard" | "difficult" | "advanced" | "senior" | "expert" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// Relative weight in the 100-point split; medium sits halfway between
+    /// easy (1.0) and hard (`hard_multiplier`).
+    pub fn points_weight(&self, hard_multiplier: f64) -> f64 {
+        match self {
+            Difficulty::Easy => 1.0,
+            Difficulty::Medium => (1.0 + hard_multiplier) / 2.0,
+            Difficulty::Hard => hard_multiplier,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum QuestionType {
     MultipleChoice,
     Code,
     ShortAnswer,
+    FillInBlank,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +76,9 @@ pub enum QuestionType {
 pub enum QuestionDetails {
     MultipleChoice(MultipleChoiceDetails),
     Code(CodeDetails),
+    // Before `ShortAnswer`: untagged variants are tried in order and every
+    // field of `ShortAnswerDetails` is optional.
+    FillInBlank(FillInBlankDetails),
     ShortAnswer(ShortAnswerDetails),
 }
 
@@ -61,3 +109,36 @@ pub struct ShortAnswerDetails {
     #[serde(default)]
     pub ai_grading: bool,
 }
+
+/// Marks a gap in `FillInBlankDetails::blanked_sentence`.
+pub const BLANK_PLACEHOLDER: &str = "___";
+
+/// A sentence with one `___` per missing word; `correct_words` fill the
+/// blanks in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillInBlankDetails {
+    pub blanked_sentence: String,
+    pub correct_words: Vec<String>,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+impl FillInBlankDetails {
+    pub fn blank_count(&self) -> usize {
+        self.blanked_sentence.matches(BLANK_PLACEHOLDER).count()
+    }
+
+    /// Whether `words` fill every blank exactly, ignoring surrounding
+    /// whitespace and, unless `case_sensitive`, letter case.
+    pub fn is_correct(&self, words: &[String]) -> bool {
+        words.len() == self.correct_words.len()
+            && words.iter().zip(&self.correct_words).all(|(given, expected)| {
+                let (given, expected) = (given.trim(), expected.trim());
+                if self.case_sensitive {
+                    given == expected
+                } else {
+                    given.to_lowercase() == expected.to_lowercase()
+                }
+            })
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuestionFeedback {
+    pub id: Uuid,
+    pub attempt_id: Uuid,
+    pub question_id: i32,
+    pub text: String,
+    pub reviewed: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Feedback as listed to HR: the candidate is identified only by a hash of
+/// their email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionFeedbackItem {
+    pub id: Uuid,
+    pub question_id: i32,
+    pub feedback_text: String,
+    pub candidate_email: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -4,6 +4,12 @@ use serde_json::Value as JsonValue;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Invite expiry when neither the request nor the test specifies one.
+pub const DEFAULT_INVITE_EXPIRY_HOURS: i64 = 48;
+
+/// `max_grace_seconds` for tests created without one.
+pub const DEFAULT_MAX_GRACE_SECONDS: i32 = 300;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Test {
     pub id: Uuid,
@@ -23,6 +29,54 @@ pub struct Test {
     pub test_type: Option<String>,
     pub presentation_themes: Option<JsonValue>,
     pub presentation_extra_info: Option<String>,
+    pub show_honesty_declaration: bool,
+    pub declaration_text: Option<String>,
+    pub prerequisite_test_id: Option<Uuid>,
+    pub default_invite_expiry_hours: Option<i32>,
+    pub reminder_hours_before: Vec<i32>,
+    /// Pass mark for the multiple-choice questions alone.
+    pub passing_score_mcq: Option<rust_decimal::Decimal>,
+    /// Pass mark for the short-answer and code questions alone.
+    pub passing_score_open: Option<rust_decimal::Decimal>,
+    /// Send the candidate a per-question breakdown once the attempt is graded.
+    pub share_results_details: bool,
+    /// Most connection-loss time added back to an attempt's deadline.
+    pub max_grace_seconds: i32,
+    /// Most attempts that may be `in_progress` at once; `None` means no limit.
+    pub max_concurrent_takers: Option<i32>,
+    /// Language the questions are written in: `ru`, `tg` or `en`.
+    pub language: String,
+    /// With `show_results_immediately`, also show the right answers to the
+    /// multiple-choice questions the candidate got wrong.
+    pub reveal_correct_answers: bool,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
+
+impl Test {
+    /// `default_invite_expiry_hours`, falling back to `DEFAULT_INVITE_EXPIRY_HOURS`.
+    pub fn invite_expiry_hours(&self) -> i64 {
+        self.default_invite_expiry_hours
+            .map(i64::from)
+            .unwrap_or(DEFAULT_INVITE_EXPIRY_HOURS)
+    }
+}
+
+/// Stored record of the AI generation that produced a test.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TestGenerationRun {
+    pub id: Uuid,
+    pub test_id: Uuid,
+    pub ai_job_id: Option<Uuid>,
+    pub blueprint: JsonValue,
+    pub logs: JsonValue,
+    pub models: Vec<String>,
+    pub requested_questions: i32,
+    pub returned_questions: i32,
+    pub duplicates_removed: i32,
+    pub topup_rounds: i32,
+    pub delivered_questions: i32,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub created_at: DateTime<Utc>,
+}
@@ -41,4 +41,31 @@ pub struct TestAttempt {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// The heartbeat before `last_heartbeat_at`, until `get_status` has
+    /// checked the gap between them.
+    pub previous_heartbeat_at: Option<DateTime<Utc>>,
+    /// Connection-loss time added back to the deadline, at most the test's
+    /// `max_grace_seconds`.
+    pub interruption_seconds: i32,
+    /// Time HR added with an extension after the start; lengthens the
+    /// answering window beyond the test's duration.
+    pub extension_seconds: i32,
+    pub assigned_theme: Option<JsonValue>,
+    pub honesty_declaration_accepted: bool,
+    pub answer_changes: i32,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub suspicious_geo: bool,
+    pub reminders_sent: JsonValue,
+    pub reviewed_by: Option<Uuid>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub language: String,
+    /// Percentage scored on the multiple-choice questions.
+    pub mcq_score: Option<rust_decimal::Decimal>,
+    /// Percentage scored on the short-answer and code questions.
+    pub open_score: Option<rust_decimal::Decimal>,
+    /// How the invite was created: `integration`, `onef`, `auto_invite` or `retake`.
+    pub source: String,
+    /// HR walking through the test; no candidate, webhooks or stats.
+    pub is_preview: bool,
 }
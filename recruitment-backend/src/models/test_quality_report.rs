@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// AI critique of a test's question set at `generated_at`; `report` holds a
+/// serialized `TestCritique`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TestQualityReport {
+    pub id: Uuid,
+    pub test_id: Uuid,
+    pub report: JsonValue,
+    pub generated_at: DateTime<Utc>,
+}
@@ -26,6 +26,7 @@ pub struct AdminUser {
     #[serde(skip_serializing)]
     pub password_hash: Option<String>,
     pub last_login_at: Option<DateTime<Utc>>,
+    pub organization_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
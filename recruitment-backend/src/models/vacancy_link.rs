@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Maps an internal vacancy to its ids on Koinoti Nav and 1F.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct VacancyLink {
+    pub vacancy_id: Uuid,
+    pub koinotinav_id: Option<i64>,
+    pub onef_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The internal vacancy behind an external id, as shown next to candidates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct CanonicalVacancy {
+    pub id: Uuid,
+    pub title: String,
+}
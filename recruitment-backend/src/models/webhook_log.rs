@@ -16,6 +16,8 @@ pub struct WebhookLog {
     pub max_attempts: Option<i32>,
     pub next_retry_at: Option<DateTime<Utc>>,
     pub status: Option<String>,
+    pub subscription_id: Option<Uuid>,
+    pub target: String,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    /// Keys the `X-Webhook-Signature` HMAC; only returned when the subscription is created.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
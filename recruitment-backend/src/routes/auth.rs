@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    http::StatusCode,
     response::IntoResponse,
     Extension, Json,
 };
@@ -9,13 +9,15 @@ use serde_json::{json, Value as JsonValue};
 use uuid::Uuid;
 
 use crate::middleware::auth::{mint_token, Claims};
+use crate::middleware::organization::OrganizationContext;
 use crate::models::user::AdminUser;
+use crate::routes::public::ClientIp;
 use crate::utils::crypto::{hash_password, verify_password};
 use crate::AppState;
 
 /// Columns selected into [`AdminUser`] everywhere in this module.
 const USER_COLS: &str = "id, name, email, role, is_active, must_change_password, \
-    password_hash, last_login_at, created_at, updated_at";
+    password_hash, last_login_at, organization_id, created_at, updated_at";
 
 const ALLOWED_ROLES: [&str; 3] = ["hr", "manager", "admin"];
 const TOKEN_TTL_HOURS: i64 = 12;
@@ -32,23 +34,6 @@ fn db_err(e: sqlx::Error) -> (StatusCode, Json<JsonValue>) {
     err(StatusCode::INTERNAL_SERVER_ERROR, "database_error")
 }
 
-/// Best-effort client IP, honoring the reverse-proxy headers (OpenResty/Caddy).
-fn client_ip(headers: &HeaderMap) -> String {
-    headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .or_else(|| {
-            headers
-                .get("x-real-ip")
-                .and_then(|v| v.to_str().ok())
-                .map(|s| s.to_string())
-        })
-        .unwrap_or_else(|| "unknown".to_string())
-}
-
 fn validate_role(role: &str) -> Result<(), (StatusCode, Json<JsonValue>)> {
     if ALLOWED_ROLES.contains(&role) {
         Ok(())
@@ -86,11 +71,11 @@ pub struct LoginRequest {
 
 pub async fn login(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    ClientIp(client_ip): ClientIp,
     Json(req): Json<LoginRequest>,
 ) -> impl IntoResponse {
     let email = req.email.trim().to_lowercase();
-    let ip = client_ip(&headers);
+    let ip = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
     let key = format!("{}|{}", ip, email);
 
     // Brute-force lockout check.
@@ -148,7 +133,12 @@ pub async fn login(
         .execute(&state.pool)
         .await;
 
-    let token = match mint_token(&user.id.to_string(), &user.role, TOKEN_TTL_HOURS) {
+    let token = match mint_token(
+        &user.id.to_string(),
+        &user.role,
+        user.organization_id,
+        TOKEN_TTL_HOURS,
+    ) {
         Ok(t) => t,
         Err(e) => {
             tracing::error!(error = ?e, "token minting failed");
@@ -239,10 +229,11 @@ pub async fn change_my_password(
 // User management (admin only — gated by require_admin middleware)
 // ---------------------------------------------------------------------------
 
-pub async fn list_users(State(state): State<AppState>) -> ApiResult {
+pub async fn list_users(State(state): State<AppState>, org: OrganizationContext) -> ApiResult {
     let users = sqlx::query_as::<_, AdminUser>(&format!(
-        "SELECT {USER_COLS} FROM users ORDER BY created_at ASC"
+        "SELECT {USER_COLS} FROM users WHERE organization_id = $1 ORDER BY created_at ASC"
     ))
+    .bind(org.org_id)
     .fetch_all(&state.pool)
     .await
     .map_err(db_err)?;
@@ -259,6 +250,10 @@ pub struct CreateUserRequest {
     pub is_active: bool,
     #[serde(default)]
     pub must_change_password: bool,
+    /// Only honoured for super admins; everyone else creates users in
+    /// their own organization.
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
 }
 
 fn default_true() -> bool {
@@ -267,6 +262,7 @@ fn default_true() -> bool {
 
 pub async fn create_user(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Json(req): Json<CreateUserRequest>,
 ) -> ApiResult {
     let name = req.name.trim().to_string();
@@ -277,6 +273,10 @@ pub async fn create_user(
     validate_email(&email)?;
     validate_role(&req.role)?;
     validate_password(&req.password)?;
+    let organization_id = match req.organization_id {
+        Some(id) if claims.is_super_admin() => id,
+        _ => claims.organization_id(),
+    };
 
     let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE lower(email) = $1")
         .bind(&email)
@@ -291,8 +291,8 @@ pub async fn create_user(
         .map_err(|_| err(StatusCode::INTERNAL_SERVER_ERROR, "hash_error"))?;
 
     let user = sqlx::query_as::<_, AdminUser>(&format!(
-        "INSERT INTO users (external_id, name, email, role, password_hash, is_active, must_change_password) \
-         VALUES (NULL, $1, $2, $3, $4, $5, $6) RETURNING {USER_COLS}"
+        "INSERT INTO users (external_id, name, email, role, password_hash, is_active, must_change_password, organization_id) \
+         VALUES (NULL, $1, $2, $3, $4, $5, $6, $7) RETURNING {USER_COLS}"
     ))
     .bind(&name)
     .bind(&email)
@@ -300,6 +300,7 @@ pub async fn create_user(
     .bind(&hash)
     .bind(req.is_active)
     .bind(req.must_change_password)
+    .bind(organization_id)
     .fetch_one(&state.pool)
     .await
     .map_err(db_err)?;
@@ -317,6 +318,7 @@ pub struct UpdateUserRequest {
 
 pub async fn update_user(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateUserRequest>,
 ) -> ApiResult {
@@ -360,13 +362,14 @@ pub async fn update_user(
             email = COALESCE($3, email), \
             role = COALESCE($4, role), \
             is_active = COALESCE($5, is_active) \
-         WHERE id = $1 RETURNING {USER_COLS}"
+         WHERE id = $1 AND organization_id = $6 RETURNING {USER_COLS}"
     ))
     .bind(id)
     .bind(name)
     .bind(email)
     .bind(req.role)
     .bind(req.is_active)
+    .bind(org.org_id)
     .fetch_optional(&state.pool)
     .await
     .map_err(db_err)?
@@ -384,6 +387,7 @@ pub struct ResetPasswordRequest {
 
 pub async fn reset_password(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
     Json(req): Json<ResetPasswordRequest>,
 ) -> ApiResult {
@@ -393,11 +397,12 @@ pub async fn reset_password(
 
     let user = sqlx::query_as::<_, AdminUser>(&format!(
         "UPDATE users SET password_hash = $2, must_change_password = $3 \
-         WHERE id = $1 RETURNING {USER_COLS}"
+         WHERE id = $1 AND organization_id = $4 RETURNING {USER_COLS}"
     ))
     .bind(id)
     .bind(&hash)
     .bind(req.must_change_password)
+    .bind(org.org_id)
     .fetch_optional(&state.pool)
     .await
     .map_err(db_err)?
@@ -408,12 +413,14 @@ pub async fn reset_password(
 
 pub async fn delete_user(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
 ) -> ApiResult {
     guard_last_admin(&state, id).await?;
 
-    let affected = sqlx::query("DELETE FROM users WHERE id = $1")
+    let affected = sqlx::query("DELETE FROM users WHERE id = $1 AND organization_id = $2")
         .bind(id)
+        .bind(org.org_id)
         .execute(&state.pool)
         .await
         .map_err(db_err)?
@@ -424,7 +431,8 @@ pub async fn delete_user(
     Ok(Json(json!({ "status": "deleted" })))
 }
 
-/// Returns an error if `id` is the last active admin (prevents lockout).
+/// Returns an error if `id` is the last active admin of its organization
+/// (prevents lockout).
 async fn guard_last_admin(
     state: &AppState,
     id: Uuid,
@@ -442,8 +450,10 @@ async fn guard_last_admin(
     }
 
     let active_admins = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM users WHERE role = 'admin' AND is_active = true",
+        "SELECT COUNT(*) FROM users WHERE role = 'admin' AND is_active = true \
+         AND organization_id = (SELECT organization_id FROM users WHERE id = $1)",
     )
+    .bind(id)
     .fetch_one(&state.pool)
     .await
     .map_err(db_err)?;
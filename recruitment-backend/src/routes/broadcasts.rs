@@ -0,0 +1,60 @@
+use crate::error::{Error, Result};
+use crate::models::broadcast::BroadcastFilter;
+use crate::services::broadcast_service::normalize_filter;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateBroadcastPayload {
+    #[serde(default)]
+    pub filter: BroadcastFilter,
+    /// Message text; `{name}` is replaced with the candidate's name.
+    #[validate(length(min = 1, max = 4096, message = "message must be 1-4096 characters"))]
+    pub message: String,
+    /// Only count and preview the recipients.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// POST /api/integration/broadcasts — message a candidate segment on Telegram.
+pub async fn create_broadcast(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateBroadcastPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    if payload.message.trim().is_empty() {
+        return Err(Error::BadRequest("message must not be blank".into()));
+    }
+    let filter = normalize_filter(payload.filter)?;
+
+    if payload.dry_run {
+        let preview = state
+            .broadcast_service
+            .preview(&filter, &payload.message)
+            .await?;
+        return Ok((StatusCode::OK, Json(serde_json::to_value(preview)?)));
+    }
+    let broadcast = state
+        .broadcast_service
+        .create(&filter, &payload.message)
+        .await?;
+    let progress = state.broadcast_service.progress(broadcast.id).await?;
+    Ok((StatusCode::ACCEPTED, Json(serde_json::to_value(progress)?)))
+}
+
+/// GET /api/integration/broadcasts/:id — delivery progress.
+pub async fn get_broadcast(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let progress = state.broadcast_service.progress(id).await?;
+    Ok(Json(progress))
+}
@@ -5,6 +5,13 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use crate::{AppState, error::Result};
+use crate::middleware::organization::OrganizationContext;
+use crate::utils::telegram_auth::InitDataError;
+use crate::models::candidate::{Candidate, CandidateProfileData, ProfileDataInput, MAX_PORTFOLIO_FILES};
+use crate::models::vacancy_link::CanonicalVacancy;
+use crate::services::ai_service::CvProfile;
+use crate::utils::signed_url::{upload_disk_path, upload_root};
+use crate::services::candidate_service::{CandidateService, CvDedupReport};
 use tokio::fs;
 use std::path::Path as StdPath;
 
@@ -12,6 +19,20 @@ use std::path::Path as StdPath;
 pub struct RegisterCandidateResponse {
     pub id: uuid::Uuid,
     pub status: String,
+    pub telegram_thread_id: Option<i64>,
+    /// Lets the candidate download their own data
+    /// (`GET /api/candidate/:id/export-data`); shown only at registration.
+    pub portal_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CandidateDetailResponse {
+    #[serde(flatten)]
+    pub candidate: Candidate,
+    pub profile: Option<CandidateProfileData>,
+    /// Internal vacancy linked to the candidate's Koinoti Nav `vacancy_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_vacancy: Option<CanonicalVacancy>,
 }
 
 #[derive(Deserialize)]
@@ -21,24 +42,45 @@ pub struct ApplyVacancyRequest {
     pub email: Option<String>,
     pub phone: Option<String>,
     pub telegram_id: Option<i64>,
+    pub init_data: Option<String>,
     pub profile_data: Option<serde_json::Value>,
     
     pub vacancy_id: i64,
     pub vacancy_name: Option<String>,
 }
 
-async fn save_cv_file(filename: &str, data: &bytes::Bytes) -> Result<String> {
+const CV_EXTENSIONS: [&str; 10] = ["pdf", "doc", "docx", "txt", "rtf", "odt", "jpg", "jpeg", "png", "webp"];
+const PHOTO_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+const MAX_PHOTO_BYTES: usize = 2 * 1024 * 1024;
+
+fn upload_extension(filename: &str, content_type: Option<&str>, allowed: &[&str]) -> Result<String> {
     let ext = StdPath::new(filename)
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase())
-        .unwrap_or_else(|| "bin".to_string());
+        .filter(|e| allowed.contains(&e.as_str()))
+        .or_else(|| {
+            let ext = match content_type? {
+                "image/jpeg" => "jpg",
+                "image/png" => "png",
+                "image/webp" => "webp",
+                "application/pdf" => "pdf",
+                _ => return None,
+            };
+            allowed.contains(&ext).then(|| ext.to_string())
+        });
 
-    let allowed_exts = ["pdf", "doc", "docx", "txt", "rtf", "odt", "jpg", "jpeg", "png", "webp"];
-    if !allowed_exts.contains(&ext.as_str()) {
-        return Err(crate::error::Error::BadRequest(format!("File type .{} is not allowed", ext)));
-    }
+    ext.ok_or_else(|| {
+        let shown = StdPath::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("bin")
+            .to_lowercase();
+        crate::error::Error::BadRequest(format!("File type .{} is not allowed", shown))
+    })
+}
 
+fn check_file_signature(ext: &str, data: &[u8]) -> Result<()> {
     if ext == "pdf" && !data.starts_with(b"%PDF") {
         return Err(crate::error::Error::BadRequest("Invalid PDF file content".into()));
     }
@@ -48,28 +90,210 @@ async fn save_cv_file(filename: &str, data: &bytes::Bytes) -> Result<String> {
     if ext == "png" && !data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
         return Err(crate::error::Error::BadRequest("Invalid PNG file content".into()));
     }
+    if ext == "webp" && !(data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP") {
+        return Err(crate::error::Error::BadRequest("Invalid WEBP file content".into()));
+    }
+    Ok(())
+}
+
+fn download_content_type(path: &str) -> &'static str {
+    let ext = StdPath::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    match ext.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("doc") => "application/msword",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("rtf") => "application/rtf",
+        Some("odt") => "application/vnd.oasis.opendocument.text",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
 
-    let upload_root = std::env::var("UPLOADS_DIR").unwrap_or_else(|_| "/app/uploads".to_string());
-    let cv_dir = format!("{}/cv", upload_root);
+/// GET /api/files/signed/:token — serves the upload a `signed_download_url`
+/// token names, until the token expires.
+pub async fn download_signed_file(Path(token): Path<String>) -> Result<impl axum::response::IntoResponse> {
+    let secret = crate::config::get_config().upload_signing_secret.as_bytes();
+    let path = crate::utils::signed_url::verify_token(&token, secret, chrono::Utc::now().timestamp())
+        .ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::FORBIDDEN,
+                "invalid_file_signature",
+                "File link is invalid or has expired",
+            )
+        })?;
+    let data = fs::read(upload_disk_path(&path))
+        .await
+        .map_err(|_| crate::error::Error::NotFound("File not found".into()))?;
+    let filename = StdPath::new(&path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("file");
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, download_content_type(&path).to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        data,
+    ))
+}
 
-    if let Err(e) = fs::create_dir_all(&cv_dir).await {
-        tracing::error!("Failed to create upload directory {}: {}", cv_dir, e);
+/// Writes `data` to `$UPLOADS_DIR/<subdir>/<uuid>.<ext>` and returns the
+/// public `uploads/<subdir>/...` path.
+pub(crate) async fn store_upload(subdir: &str, ext: &str, data: &bytes::Bytes) -> Result<String> {
+    let target_dir = format!("{}/{}", upload_root(), subdir);
+
+    if let Err(e) = fs::create_dir_all(&target_dir).await {
+        tracing::error!("Failed to create upload directory {}: {}", target_dir, e);
         return Err(crate::error::Error::Internal(format!("Storage error: {}", e)));
     }
 
     let file_id = uuid::Uuid::new_v4();
     let safe_filename = format!("{}.{}", file_id, ext);
-    let absolute_path = format!("{}/{}", cv_dir, safe_filename);
+    let absolute_path = format!("{}/{}", target_dir, safe_filename);
 
     fs::write(&absolute_path, data).await.map_err(|e| {
-        tracing::error!("Failed to write CV file at {}: {}", absolute_path, e);
+        tracing::error!("Failed to write upload at {}: {}", absolute_path, e);
         crate::error::Error::Internal(format!("Failed to save file: {}", e))
     })?;
 
-    Ok(format!("uploads/cv/{}", safe_filename))
+    Ok(format!("uploads/{}/{}", subdir, safe_filename))
+}
+
+struct StoredCv {
+    url: String,
+    hash: String,
+}
+
+/// Size, extension and content checks shared by CVs and portfolio files;
+/// returns the file's extension.
+fn check_cv_upload(filename: &str, data: &bytes::Bytes) -> Result<String> {
+    let max_mb = crate::config::get_config().max_cv_size_mb;
+    if data.len() > max_mb * 1024 * 1024 {
+        return Err(crate::error::Error::coded(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "cv_too_large",
+            format!("CV must not exceed {} MB", max_mb),
+        ));
+    }
+    let ext = upload_extension(filename, None, &CV_EXTENSIONS)?;
+    check_file_signature(&ext, data)?;
+    Ok(ext)
+}
+
+/// Stores a portfolio document next to the CV and returns its stored path.
+/// Like CVs, these are only served through signed links.
+async fn save_portfolio_file(filename: &str, data: &bytes::Bytes) -> Result<String> {
+    let ext = check_cv_upload(filename, data)?;
+    let url = store_upload("portfolio", &ext, data).await?;
+    Ok(crate::utils::signed_url::storage_path(&url).to_string())
+}
+
+/// Stores a CV, reusing the file of any candidate who already uploaded
+/// byte-identical content.
+async fn save_cv_file(candidates: &CandidateService, filename: &str, data: &bytes::Bytes) -> Result<StoredCv> {
+    let ext = check_cv_upload(filename, data)?;
+
+    let hash = crate::utils::crypto::sha256_hex(data);
+    if let Some(existing) = candidates.find_cv_by_hash(&hash).await? {
+        if fs::try_exists(upload_disk_path(&existing)).await.unwrap_or(false) {
+            tracing::info!("Reusing stored CV {} for identical upload", existing);
+            return Ok(StoredCv { url: existing, hash });
+        }
+        tracing::warn!("CV {} matched by hash is missing on disk, storing a new copy", existing);
+    }
+
+    // CVs are stored relative to the upload root and only served through signed links.
+    let url = store_upload("cv", &ext, data).await?;
+    let url = crate::utils::signed_url::storage_path(&url).to_string();
+    Ok(StoredCv { url, hash })
+}
+
+async fn save_photo_file(filename: &str, content_type: Option<&str>, data: &bytes::Bytes) -> Result<String> {
+    if data.len() > MAX_PHOTO_BYTES {
+        return Err(crate::error::Error::coded(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "photo_too_large",
+            format!("Photo must not exceed {} MB", MAX_PHOTO_BYTES / (1024 * 1024)),
+        ));
+    }
+    let ext = upload_extension(filename, content_type, &PHOTO_EXTENSIONS)?;
+    check_file_signature(&ext, data)?;
+    store_upload("photos", &ext, data).await
+}
+
+/// With `init_data` present the Telegram id comes only from its verified
+/// signature; the plain field is used for clients outside the WebApp.
+fn trusted_telegram_id(init_data: Option<&str>, raw_id: Option<i64>) -> Result<Option<i64>> {
+    let Some(init_data) = init_data.filter(|d| !d.trim().is_empty()) else {
+        return Ok(raw_id);
+    };
+    let config = crate::config::get_config();
+    match crate::utils::telegram_auth::verify_telegram_data(
+        init_data,
+        &config.telegram_bot_token,
+        config.telegram_init_data_max_age_secs,
+        chrono::Utc::now().timestamp(),
+    ) {
+        Ok(id) => {
+            if raw_id.is_some_and(|raw| raw != id) {
+                tracing::warn!("telegram_id {:?} does not match signed initData user {}", raw_id, id);
+            }
+            Ok(Some(id))
+        }
+        Err(InitDataError::Invalid) => Err(crate::error::Error::coded(
+            StatusCode::UNAUTHORIZED,
+            "invalid_init_data",
+            "Telegram initData signature is invalid",
+        )),
+        Err(InitDataError::Expired) => Err(crate::error::Error::coded(
+            StatusCode::UNAUTHORIZED,
+            "init_data_expired",
+            "Telegram initData has expired, reopen the app",
+        )),
+    }
+}
+
+fn parse_profile_data(value: serde_json::Value) -> Result<serde_json::Value> {
+    match CandidateProfileData::parse(value) {
+        Ok(input @ ProfileDataInput::Structured(_)) => Ok(input.into_json()),
+        Ok(legacy) => {
+            tracing::warn!("profile_data does not match the candidate profile schema; storing it under the legacy key");
+            Ok(legacy.into_json())
+        }
+        Err(problems) => Err(crate::error::Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_profile_data",
+            "profile_data does not match the candidate profile schema",
+        )
+        .with_details(serde_json::json!({ "problems": problems }))),
+    }
+}
+
+/// Canonical IANA name for the `timezone` form field; blank means "not set".
+pub fn parse_timezone_field(raw: &str) -> Result<Option<String>> {
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+    crate::utils::time::parse_timezone(raw)
+        .map(|tz| Some(tz.name().to_string()))
+        .ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_timezone",
+                "timezone must be an IANA name such as Asia/Dushanbe",
+            )
+            .with_details(serde_json::json!({ "timezone": raw }))
+        })
 }
 
 pub(crate) async fn extract_text_from_file(file_path: &str) -> String {
+    let file_path = &upload_disk_path(file_path);
     let path = std::path::Path::new(file_path);
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
@@ -106,6 +330,44 @@ pub(crate) async fn extract_text_from_file(file_path: &str) -> String {
     }
 }
 
+/// Parses the CV at `cv_path` into a structured profile and stores it,
+/// together with its skill tags, on the candidate.
+pub(crate) async fn reparse_cv_profile(
+    state: &AppState,
+    candidate_id: uuid::Uuid,
+    cv_path: &str,
+) -> Result<CvProfile> {
+    let cv_text = extract_text_from_file(cv_path).await;
+    if cv_text.trim().is_empty() {
+        return Err(crate::error::Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "cv_text_unavailable",
+            "No text could be extracted from the candidate's CV",
+        ));
+    }
+    let profile = state.ai_service.extract_cv_profile(&cv_text).await?;
+    state
+        .candidate_service
+        .update_extracted_profile(candidate_id, &profile)
+        .await?;
+    Ok(profile)
+}
+
+/// Extracts the CV profile in the background. Failures only log the reason:
+/// the profile stays as it was and suitability analysis is unaffected.
+pub(crate) fn spawn_cv_profile_extraction(state: &AppState, candidate_id: uuid::Uuid, cv_path: String) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = reparse_cv_profile(&state, candidate_id, &cv_path).await {
+            tracing::warn!(
+                "CV profile extraction failed for candidate {}: {}",
+                candidate_id,
+                e
+            );
+        }
+    });
+}
+
 async fn extract_text_via_libreoffice(file_path: &str) -> String {
     let temp_dir = format!("/tmp/cv_convert_{}", uuid::Uuid::new_v4());
     if let Err(e) = fs::create_dir_all(&temp_dir).await {
@@ -171,10 +433,16 @@ pub async fn register_candidate(
     let mut email = String::new();
     let mut phone = None;
     let mut telegram_id = None;
+    let mut init_data = None;
     let mut profile_data = None;
     let mut cv_url = None;
+    let mut cv_hash = None;
+    let mut portfolio = Vec::new();
+    let mut photo_url = None;
     let mut dob = None;
     let mut vacancy_id = None;
+    let mut referral_code = None;
+    let mut timezone = None;
 
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         tracing::error!("Failed to get next field: {}", e);
@@ -192,6 +460,7 @@ pub async fn register_candidate(
                     telegram_id = Some(id);
                 }
             },
+            "init_data" => init_data = Some(field.text().await.unwrap_or_default()),
             "vacancy_id" => {
                 let id_str = field.text().await.unwrap_or_default();
                 if let Ok(id) = id_str.parse::<i64>() {
@@ -200,8 +469,16 @@ pub async fn register_candidate(
             },
             "profile_data" => {
                 let data_str = field.text().await.unwrap_or_default();
-                if let Ok(data) = serde_json::from_str::<serde_json::Value>(&data_str) {
-                    profile_data = Some(data);
+                if !data_str.trim().is_empty() {
+                    let data = serde_json::from_str::<serde_json::Value>(&data_str).map_err(|e| {
+                        crate::error::Error::coded(
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            "invalid_profile_data",
+                            "profile_data is not valid JSON",
+                        )
+                        .with_details(serde_json::json!({ "problems": [e.to_string()] }))
+                    })?;
+                    profile_data = Some(parse_profile_data(data)?);
                 }
             },
             "cv" => {
@@ -212,8 +489,11 @@ pub async fn register_candidate(
                 })?;
 
                 if !data.is_empty() {
-                    match save_cv_file(&filename, &data).await {
-                        Ok(path) => cv_url = Some(path),
+                    match save_cv_file(&state.candidate_service, &filename, &data).await {
+                        Ok(stored) => {
+                            cv_url = Some(stored.url);
+                            cv_hash = Some(stored.hash);
+                        }
                         Err(e) => {
                              tracing::error!("CV Save Error: {:?}", e);
                              return Err(e);
@@ -221,6 +501,44 @@ pub async fn register_candidate(
                     }
                 }
             },
+            name if name.starts_with("portfolio_file_") => {
+                let filename = field.file_name().unwrap_or("portfolio.bin").to_string();
+                let data = field.bytes().await.map_err(|e| {
+                    tracing::error!("Failed to read portfolio file bytes: {}", e);
+                    crate::error::Error::BadRequest("Failed to read file upload".into())
+                })?;
+
+                if !data.is_empty() {
+                    if portfolio.len() >= MAX_PORTFOLIO_FILES {
+                        return Err(crate::error::Error::coded(
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            "too_many_portfolio_files",
+                            format!("At most {} portfolio files can be uploaded", MAX_PORTFOLIO_FILES),
+                        )
+                        .with_details(serde_json::json!({ "max": MAX_PORTFOLIO_FILES })));
+                    }
+                    portfolio.push(save_portfolio_file(&filename, &data).await?);
+                }
+            },
+            "photo" => {
+                let filename = field.file_name().unwrap_or("photo.bin").to_string();
+                let content_type = field.content_type().map(|c| c.to_string());
+                let data = field.bytes().await.map_err(|e| {
+                    tracing::error!("Failed to read photo bytes: {}", e);
+                    crate::error::Error::BadRequest("Failed to read file upload".into())
+                })?;
+
+                if !data.is_empty() {
+                    photo_url = Some(save_photo_file(&filename, content_type.as_deref(), &data).await?);
+                }
+            },
+            "timezone" => timezone = parse_timezone_field(&field.text().await.unwrap_or_default())?,
+            "referral_code" => {
+                let code = field.text().await.unwrap_or_default();
+                if !code.trim().is_empty() {
+                    referral_code = Some(code);
+                }
+            },
             "dob" => {
                 let dob_str = field.text().await.unwrap_or_default();
                 if let Ok(d) = chrono::NaiveDate::parse_from_str(&dob_str, "%Y-%m-%d") {
@@ -231,6 +549,7 @@ pub async fn register_candidate(
         }
     }
 
+    let telegram_id = trusted_telegram_id(init_data.as_deref(), telegram_id)?;
     if name.is_empty() { return Err(crate::error::Error::BadRequest("Name is required".into())); }
     if email.is_empty() { return Err(crate::error::Error::BadRequest("Email is required".into())); }
     if phone.as_ref().map(|s| s.is_empty()).unwrap_or(true) { return Err(crate::error::Error::BadRequest("Phone number is required".into())); }
@@ -242,19 +561,42 @@ pub async fn register_candidate(
         crate::error::Error::BadRequest("telegram_id is required".into())
     })?;
 
+    let referral = match referral_code {
+        Some(code) => Some(state.referral_service.find_by_code(&code).await?.ok_or_else(|| {
+            crate::error::Error::coded(StatusCode::BAD_REQUEST, "invalid_referral_code", "Unknown referral code")
+        })?),
+        None => None,
+    };
+
     let candidate = state.candidate_service.create_candidate(
         Some(telegram_id),
         name.clone(),
         email.clone(),
         phone.clone(),
         cv_url.clone(),
+        cv_hash,
+        photo_url,
         dob,
         vacancy_id,
         profile_data,
+        timezone,
     ).await.map_err(|e| {
         tracing::error!("Failed to create candidate DB: {}", e);
         e
     })?;
+    if !portfolio.is_empty() {
+        state.candidate_service.set_cv_portfolio(candidate.id, &portfolio).await?;
+    }
+    enqueue_candidate_event(&state, candidate_registered_payload(&candidate)).await;
+    confirm_registration(&state, &candidate).await?;
+
+    if let Some(ref referral) = referral {
+        state.referral_service.attach_to_candidate(candidate.id, referral).await?;
+    }
+
+    if let Some(ref path) = cv_url {
+        spawn_cv_profile_extraction(&state, candidate.id, path.clone());
+    }
 
     if let Some(vid) = vacancy_id {
         let ai_service = state.ai_service.clone();
@@ -268,6 +610,7 @@ pub async fn register_candidate(
         let c_phone = phone;
         let c_dob = dob;
         let c_cv = cv_url;
+        let c_portfolio = portfolio;
         let c_telegram_id = telegram_id;
 
         tokio::spawn(async move {
@@ -310,6 +653,7 @@ pub async fn register_candidate(
                 c_phone,
                 c_dob,
                 c_cv,
+                &c_portfolio,
                 ai_rating,
                 ai_comment,
             ).await;
@@ -319,20 +663,53 @@ pub async fn register_candidate(
     Ok((StatusCode::CREATED, Json(RegisterCandidateResponse {
         id: candidate.id,
         status: "success".into(),
+        telegram_thread_id: candidate.telegram_thread_id,
+        portal_token: crate::utils::signed_url::portal_token(
+            candidate.id,
+            crate::config::get_config().upload_signing_secret.as_bytes(),
+        ),
     })))
 }
 
 pub async fn get_candidate(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<uuid::Uuid>,
 ) -> Result<impl axum::response::IntoResponse> {
-    let candidate = state.candidate_service.get_candidate(id).await?;
+    let candidate = state.candidate_service.get_candidate_in_org(id, org.org_id).await?;
     match candidate {
-        Some(c) => Ok(Json(c)),
-        None => Err(crate::error::Error::NotFound("Candidate not found".into())),
+        Some(c) => {
+            let canonical_vacancy = match c.vacancy_id {
+                Some(vacancy_id) => state.vacancy_link_service.canonical_for_koinotinav(vacancy_id).await?,
+                None => None,
+            };
+            Ok(Json(CandidateDetailResponse {
+                profile: c.profile(),
+                candidate: c,
+                canonical_vacancy,
+            }))
+        }
+        None => Err(crate::error::Error::candidate_not_found()),
     }
 }
 
+/// GET /api/integration/candidates/cv-dedup-report
+pub async fn cv_dedup_report(
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse> {
+    let counts = state.candidate_service.cv_reference_counts().await?;
+    let mut groups = Vec::with_capacity(counts.len());
+    for (cv_url, references) in counts {
+        let size = if references > 1 {
+            fs::metadata(upload_disk_path(&cv_url)).await.map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        groups.push((references, size));
+    }
+    Ok(Json(CvDedupReport::from_groups(&groups)))
+}
+
 pub async fn update_candidate_cv(
     State(state): State<AppState>,
     Path(id): Path<uuid::Uuid>,
@@ -346,15 +723,15 @@ pub async fn update_candidate_cv(
             let data = field.bytes().await.map_err(|e| crate::error::Error::Internal(e.to_string()))?;
             
             if !data.is_empty() {
-                let path = save_cv_file(&filename, &data).await?;
-                cv_url = Some(path);
+                cv_url = Some(save_cv_file(&state.candidate_service, &filename, &data).await?);
                 break; 
             }
         }
     }
 
-    if let Some(path) = cv_url {
-        let candidate = state.candidate_service.update_cv(id, path).await?;
+    if let Some(stored) = cv_url {
+        let candidate = state.candidate_service.update_cv(id, stored.url.clone(), stored.hash).await?;
+        spawn_cv_profile_extraction(&state, id, stored.url);
         Ok(Json(candidate))
     } else {
         Err(crate::error::Error::BadRequest("No valid CV file provided".into()))
@@ -365,37 +742,58 @@ pub async fn apply_for_vacancy(
     State(state): State<AppState>,
     Json(payload): Json<ApplyVacancyRequest>,
 ) -> Result<impl axum::response::IntoResponse> {
+    let trusted_id = trusted_telegram_id(payload.init_data.as_deref(), payload.telegram_id)?;
+    let signed = payload.init_data.as_deref().is_some_and(|d| !d.trim().is_empty());
     let candidate = if let Some(id) = payload.candidate_id {
-        state.candidate_service.get_candidate(id).await
+        let existing = state.candidate_service.get_candidate(id).await
             .map_err(|e| crate::error::Error::Internal(e.to_string()))?
-            .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?
+            .ok_or_else(crate::error::Error::candidate_not_found)?;
+        if signed && existing.telegram_id != trusted_id {
+            return Err(crate::error::Error::coded(
+                StatusCode::FORBIDDEN,
+                "telegram_id_mismatch",
+                "Candidate belongs to a different Telegram account",
+            ));
+        }
+        existing
     } else {
         let name = payload.name.ok_or_else(|| crate::error::Error::BadRequest("name is required for new candidates".into()))?;
         let email = payload.email.ok_or_else(|| crate::error::Error::BadRequest("email is required for new candidates".into()))?;
         
-        state.candidate_service.create_candidate(
-            payload.telegram_id,
+        let created = state.candidate_service.create_candidate(
+            trusted_id,
             name,
             email,
             payload.phone,
             None,
             None,
-            Some(payload.vacancy_id),
-            payload.profile_data,
-        ).await?
+            None,
+            None,
+            // Applied below, so the duplicate check sees a fresh application.
+            None,
+            payload.profile_data.map(parse_profile_data).transpose()?,
+            None,
+        ).await?;
+        enqueue_candidate_event(&state, candidate_registered_payload(&created)).await;
+        confirm_registration(&state, &created).await?;
+        created
     };
     
     let telegram_id = candidate.telegram_id.ok_or_else(|| {
         crate::error::Error::BadRequest("Candidate must have telegram_id".into())
     })?;
 
-    let application = state.candidate_service.apply_to_vacancy(candidate.id, payload.vacancy_id).await
-        .map_err(|e| crate::error::Error::Internal(e.to_string()))?;
+    let result = state.candidate_service.apply_to_vacancy(candidate.id, payload.vacancy_id).await?;
+    if result.already_applied {
+        tracing::info!("Candidate {} already applied to vacancy {}", candidate.id, payload.vacancy_id);
+        return Ok((StatusCode::OK, Json(result)));
+    }
     
     let onef_service = state.onef_service.clone();
     let c_id = candidate.id;
-    let c_name = candidate.name;
-    let c_email = candidate.email;
+    let c_portfolio = candidate.portfolio_paths();
+    let c_name = candidate.name.unwrap_or_default();
+    let c_email = candidate.email.unwrap_or_default();
     let c_phone = candidate.phone;
     let c_dob = candidate.dob;
     let c_cv = candidate.cv_url;
@@ -448,12 +846,13 @@ pub async fn apply_for_vacancy(
             c_phone,
             c_dob,
             c_cv,
+            &c_portfolio,
             ai_rating,
             ai_comment,
         ).await;
     });
 
-    Ok((StatusCode::CREATED, Json(application)))
+    Ok((StatusCode::CREATED, Json(result)))
 }
 
 pub async fn get_candidate_applications(
@@ -464,6 +863,35 @@ pub async fn get_candidate_applications(
     Ok(Json(applications))
 }
 
+#[derive(Deserialize)]
+pub struct WithdrawApplicationRequest {
+    pub telegram_id: i64,
+}
+
+pub async fn withdraw_application(
+    State(state): State<AppState>,
+    Path((id, application_id)): Path<(uuid::Uuid, i32)>,
+    Json(payload): Json<WithdrawApplicationRequest>,
+) -> Result<impl axum::response::IntoResponse> {
+    let candidate = state.candidate_service.get_candidate(id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+
+    if candidate.telegram_id != Some(payload.telegram_id) {
+        return Err(crate::error::Error::Unauthorized("Telegram ID does not match candidate".into()));
+    }
+
+    let application = state.candidate_service.withdraw_application(id, application_id).await?;
+    tracing::info!("Candidate {} withdrew application {} (vacancy {})", id, application.id, application.vacancy_id);
+
+    let onef = state.onef_service.clone();
+    let vacancy_id = application.vacancy_id;
+    tokio::spawn(async move {
+        let _ = onef.notify_application_withdrawn(id, application_id, vacancy_id).await;
+    });
+
+    Ok(Json(application))
+}
+
 pub async fn get_candidates_for_vacancy(
     State(state): State<AppState>,
     Path(id): Path<i64>,
@@ -472,6 +900,21 @@ pub async fn get_candidates_for_vacancy(
     Ok(Json(candidates))
 }
 
+/// Re-runs CV profile extraction synchronously and returns the stored profile.
+pub async fn reparse_candidate_cv(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<Json<CvProfile>> {
+    state.candidate_service.get_candidate(id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let cv_path = state.candidate_service.stored_cv_path(id).await?.ok_or_else(|| {
+        crate::error::Error::coded(StatusCode::UNPROCESSABLE_ENTITY, "cv_missing", "Candidate has no CV to parse")
+    })?;
+    let profile = reparse_cv_profile(&state, id, &cv_path).await?;
+    tracing::info!("Re-parsed CV profile for candidate {}", id);
+    Ok(Json(profile))
+}
+
 pub async fn analyze_candidate_suitability(
     State(state): State<AppState>,
     Path(id): Path<uuid::Uuid>,
@@ -480,7 +923,7 @@ pub async fn analyze_candidate_suitability(
     let candidate = state.candidate_service.get_candidate(id).await?
         .ok_or_else(|| {
             tracing::error!("Candidate not found: {}", id);
-            crate::error::Error::NotFound("Candidate not found".into())
+            crate::error::Error::candidate_not_found()
         })?;
     
     let vid = candidate.vacancy_id.ok_or_else(|| {
@@ -507,21 +950,18 @@ pub async fn analyze_candidate_suitability(
             crate::error::Error::NotFound(format!("Vacancy #{} not found on the job portal", vid))
         })?;
 
-    let v_name = vacancy.title;
-    let v_desc = vacancy.content;
+    let v_name_clean = crate::utils::html::sanitize_to_text(&vacancy.title);
+    let v_desc_clean = crate::utils::html::sanitize_to_text(&vacancy.content);
 
-    if v_desc.trim().is_empty() {
+    if v_desc_clean.is_empty() {
         return Err(crate::error::Error::BadRequest("Vacancy description is empty, cannot perform analysis".into()));
     }
-
-    let v_name_clean = v_name.replace("<h1>", "").replace("</h1>", "").replace("<strong>", "").replace("</strong>", "").replace("<span>", "").replace("</span>", "");
-    let v_desc_clean = v_desc.replace("<p>", "\n").replace("</p>", "").replace("<br>", "\n").replace("<li>", "- ").replace("</li>", "");
     
-    tracing::info!("Analyzing suitability for '{}' against vacancy: '{}'", candidate.name, v_name_clean);
+    tracing::info!("Analyzing suitability for '{}' against vacancy: '{}'", candidate.name_or_empty(), v_name_clean);
 
     let suitability = state.ai_service.analyze_suitability(
-        &candidate.name,
-        &candidate.email,
+        candidate.name_or_empty(),
+        candidate.email_or_empty(),
         &cv_info,
         candidate.cv_url.as_deref(),
         &v_name_clean,
@@ -542,10 +982,391 @@ pub async fn get_candidate_history(
     Ok(Json(history))
 }
 
+pub async fn get_candidate_onboarding(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<impl axum::response::IntoResponse> {
+    let onboarding = state.candidate_service.get_onboarding(id).await?;
+    let checklist = onboarding
+        .checklist()
+        .ok_or_else(crate::services::candidate_service::onboarding_not_ready)?;
+    Ok(Json(checklist))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOnboardingRequest {
+    /// Zero-based indices of the items that are done; replaces the previous set.
+    pub completed_items: Vec<usize>,
+}
+
+pub async fn update_candidate_onboarding(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+    Json(payload): Json<UpdateOnboardingRequest>,
+) -> Result<impl axum::response::IntoResponse> {
+    let onboarding = state
+        .candidate_service
+        .mark_onboarding_items(id, &payload.completed_items)
+        .await?;
+    let checklist = onboarding
+        .checklist()
+        .ok_or_else(crate::services::candidate_service::onboarding_not_ready)?;
+    Ok(Json(checklist))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTelegramThreadRequest {
+    /// Forum topic id; `null` sends messages to the chat itself again.
+    pub telegram_thread_id: Option<i64>,
+}
+
+pub async fn update_candidate_telegram_thread(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+    Json(payload): Json<UpdateTelegramThreadRequest>,
+) -> Result<impl axum::response::IntoResponse> {
+    let candidate = state
+        .candidate_service
+        .set_telegram_thread_id(id, payload.telegram_thread_id)
+        .await?;
+    Ok(Json(candidate))
+}
+
+/// Public status page of one candidate: only what they entered themselves
+/// plus where their applications and tests stand.
+#[derive(Debug, Serialize)]
+pub struct CandidateStatusPage {
+    pub name: String,
+    pub status: String,
+    pub status_label: &'static str,
+    pub applications: Vec<StatusPageApplication>,
+    pub test_invites: Vec<StatusPageInvite>,
+    pub results: Vec<StatusPageResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusPageApplication {
+    pub vacancy_title: String,
+    pub status: String,
+    pub applied_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A test the candidate has been invited to and not started yet.
+#[derive(Debug, Serialize)]
+pub struct StatusPageInvite {
+    pub test_title: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub test_url: String,
+}
+
+/// A finished attempt. `passed` and `percentage` are only filled in for
+/// graded attempts on tests with `show_results_immediately`.
+#[derive(Debug, Serialize)]
+pub struct StatusPageResult {
+    pub test_title: String,
+    pub status: String,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub passed: Option<bool>,
+    pub percentage: Option<rust_decimal::Decimal>,
+}
+
+/// Attempt statuses listed under `results` on the status page.
+pub(crate) const FINISHED_ATTEMPT_STATUSES: [&str; 4] = ["completed", "timeout", "escaped", "needs_review"];
+
+pub fn status_page_url(webapp_url: &str, status_token: &str) -> String {
+    format!("{}/status/{}", webapp_url.trim_end_matches('/'), status_token)
+}
+
+pub fn registration_confirmation_message(name: &str) -> String {
+    format!(
+        "Спасибо, {}! Ваша заявка получена. Следить за её статусом можно по кнопке ниже.",
+        name
+    )
+}
+
+/// GET /api/public/candidates/:status_token/status
+pub async fn get_public_candidate_status(
+    State(state): State<AppState>,
+    Path(status_token): Path<String>,
+) -> Result<impl axum::response::IntoResponse> {
+    let candidate = state
+        .candidate_service
+        .get_by_status_token(&status_token)
+        .await?
+        .ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::NOT_FOUND,
+                "status_page_not_found",
+                "This status link is unknown or has been revoked",
+            )
+        })?;
+
+    let vacancy_titles = crate::routes::export::vacancy_titles(&state).await?;
+    let applications = state
+        .candidate_service
+        .get_candidate_applications(candidate.id)
+        .await?
+        .into_iter()
+        .map(|app| StatusPageApplication {
+            vacancy_title: vacancy_titles
+                .get(&app.vacancy_id)
+                .map(|t| crate::utils::html::sanitize_to_text(t))
+                .unwrap_or_else(|| format!("Вакансия #{}", app.vacancy_id)),
+            status: app.status,
+            applied_at: app.created_at,
+        })
+        .collect();
+
+    let attempts = match &candidate.email {
+        Some(email) => state.attempt_service.list_for_candidate_email(email).await?,
+        None => Vec::new(),
+    };
+    let mut tests = std::collections::HashMap::new();
+    for attempt in &attempts {
+        if let std::collections::hash_map::Entry::Vacant(entry) = tests.entry(attempt.test_id) {
+            entry.insert(state.test_service.get_test_by_id(attempt.test_id).await?);
+        }
+    }
+
+    let webapp_url = &crate::config::get_config().webapp_url;
+    let now = chrono::Utc::now();
+    let mut test_invites = Vec::new();
+    let mut results = Vec::new();
+    for attempt in attempts {
+        let Some(test) = tests.get(&attempt.test_id) else { continue };
+        if attempt.status == "pending" && attempt.expires_at > now {
+            test_invites.push(StatusPageInvite {
+                test_title: test.title.clone(),
+                expires_at: attempt.expires_at,
+                test_url: format!("{}/test/{}", webapp_url, attempt.access_token),
+            });
+        } else if FINISHED_ATTEMPT_STATUSES.contains(&attempt.status.as_str()) {
+            let show = test.show_results_immediately == Some(true) && attempt.status == "completed";
+            results.push(StatusPageResult {
+                test_title: test.title.clone(),
+                status: attempt.status,
+                completed_at: attempt.completed_at,
+                passed: attempt.passed.filter(|_| show),
+                percentage: attempt.percentage.filter(|_| show),
+            });
+        }
+    }
+
+    Ok(Json(CandidateStatusPage {
+        name: candidate.name.unwrap_or_default(),
+        status_label: crate::services::status_pipeline_service::StatusPipelineService::candidate_label_ru(
+            &candidate.status,
+        ),
+        status: candidate.status,
+        applications,
+        test_invites,
+        results,
+    }))
+}
+
+/// DELETE /api/integration/candidates/:id/status-token
+pub async fn revoke_candidate_status_token(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<impl axum::response::IntoResponse> {
+    if !state.candidate_service.revoke_status_token(id).await? {
+        return Err(crate::error::Error::coded(
+            StatusCode::NOT_FOUND,
+            "status_token_not_found",
+            "The candidate has no status link",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+pub struct PortfolioFile {
+    pub name: String,
+    /// Signed `uploads/portfolio/...` link, valid for an hour.
+    pub url: String,
+}
+
+/// GET /api/integration/candidates/:id/portfolio
+pub async fn get_candidate_portfolio(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<impl axum::response::IntoResponse> {
+    let candidate = state
+        .candidate_service
+        .get_candidate(id)
+        .await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let files: Vec<PortfolioFile> = candidate
+        .portfolio_paths()
+        .iter()
+        .map(|path| PortfolioFile {
+            name: StdPath::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(path)
+                .to_string(),
+            url: crate::utils::signed_url::signed_upload_url(path, crate::utils::signed_url::CV_LINK_TTL),
+        })
+        .collect();
+    Ok(Json(serde_json::json!({ "candidate_id": candidate.id, "files": files })))
+}
+
+/// Issues the status page token of a newly registered candidate and sends
+/// them the registration confirmation with a button opening the page.
+async fn confirm_registration(state: &AppState, candidate: &Candidate) -> Result<()> {
+    let token = state.candidate_service.issue_status_token(candidate.id).await?;
+    let Some(telegram_id) = candidate.telegram_id else {
+        return Ok(());
+    };
+    let reply_markup = serde_json::json!({
+        "inline_keyboard": [[{
+            "text": "Статус заявки",
+            "web_app": { "url": status_page_url(&crate::config::get_config().webapp_url, &token) }
+        }]]
+    });
+    let text = registration_confirmation_message(candidate.name_or_empty());
+    let thread_id = candidate.telegram_thread_id;
+    let candidate_id = candidate.id;
+    let pool = state.pool.clone();
+    tokio::spawn(async move {
+        let delivery = crate::services::telegram_delivery_service::TelegramDeliveryService::from_config(pool);
+        if let Err(e) = delivery.send(telegram_id, thread_id, &text, Some(reply_markup)).await {
+            tracing::warn!("Failed to send registration confirmation to {}: {}", candidate_id, e);
+        }
+    });
+    Ok(())
+}
+
+pub const CANDIDATE_REGISTERED_EVENT: &str = "candidate_registered";
+pub const CANDIDATE_STATUS_CHANGED_EVENT: &str = "candidate_status_changed";
+
+pub fn candidate_registered_payload(candidate: &Candidate) -> serde_json::Value {
+    serde_json::json!({
+        "event": CANDIDATE_REGISTERED_EVENT,
+        "candidate_id": candidate.id,
+        "name": candidate.name,
+        "email": candidate.email,
+        "phone": candidate.phone,
+        "telegram_id": candidate.telegram_id,
+        "vacancy_id": candidate.vacancy_id,
+        "registered_at": candidate.created_at,
+    })
+}
+
+pub fn candidate_status_changed_payload(
+    candidate_id: uuid::Uuid,
+    previous_status: &str,
+    status: &str,
+    vacancy_id: Option<i64>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "event": CANDIDATE_STATUS_CHANGED_EVENT,
+        "candidate_id": candidate_id,
+        "previous_status": previous_status,
+        "status": status,
+        "vacancy_id": vacancy_id,
+        "changed_at": chrono::Utc::now(),
+    })
+}
+
+/// Queues a candidate webhook event. Failures are only logged: the change
+/// that triggered the event has already been saved.
+pub(crate) async fn enqueue_candidate_event(state: &AppState, payload: serde_json::Value) {
+    let event_type = payload["event"].as_str().unwrap_or_default().to_string();
+    if let Err(e) = state.notification_service.enqueue_webhook(&event_type, &payload, None).await {
+        tracing::warn!("Failed to queue {} webhook: {:?}", event_type, e);
+    }
+}
+
+const DEFAULT_COMPANY_NAME: &str = "Koinoti Nav";
+
+pub fn onboarding_message(name: &str, vacancy_title: &str, items: &[String]) -> String {
+    let list = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| format!("{}. {}", i + 1, item))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}, поздравляем с принятием на позицию «{}»!\n\nВаш план на первую неделю:\n\n{}",
+        name, vacancy_title, list
+    )
+}
+
+/// Generates the first-week checklist for a newly accepted candidate, stores
+/// it and sends it to them on Telegram. Runs in the background; failures are
+/// only logged so the status change itself never depends on the AI.
+pub(crate) fn spawn_onboarding_checklist(state: AppState, candidate: Candidate, vacancy_id: Option<i64>) {
+    tokio::spawn(async move {
+        let Some(vacancy_id) = vacancy_id else {
+            tracing::warn!("Skipping onboarding checklist for {}: no vacancy", candidate.id);
+            return;
+        };
+        let vacancy = match state.koinotinav_service.fetch_vacancy(vacancy_id).await {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                tracing::warn!("Skipping onboarding checklist for {}: vacancy #{} not found", candidate.id, vacancy_id);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Skipping onboarding checklist for {}: {}", candidate.id, e);
+                return;
+            }
+        };
+        let company_name = match vacancy.company_id {
+            Some(company_id) => state
+                .koinotinav_service
+                .fetch_companies()
+                .await
+                .ok()
+                .and_then(|companies| companies.into_iter().find(|c| c.id == company_id))
+                .map(|c| c.title),
+            None => None,
+        }
+        .unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string());
+
+        let items = match state.ai_service.generate_onboarding_checklist(&vacancy.title, &company_name).await {
+            Ok(items) => items,
+            Err(e) => {
+                tracing::warn!("Failed to generate onboarding checklist for {}: {}", candidate.id, e);
+                return;
+            }
+        };
+        if let Err(e) = state.candidate_service.set_onboarding_checklist(candidate.id, &items).await {
+            tracing::warn!("Failed to store onboarding checklist for {}: {}", candidate.id, e);
+            return;
+        }
+
+        if let Some(telegram_id) = candidate.telegram_id {
+            let delivery = crate::services::telegram_delivery_service::TelegramDeliveryService::from_config(
+                state.pool.clone(),
+            );
+            let text = onboarding_message(candidate.name_or_empty(), &vacancy.title, &items);
+            if let Err(e) = delivery.send(telegram_id, candidate.telegram_thread_id, &text, None).await {
+                tracing::warn!("Failed to send onboarding checklist: {}", e);
+            }
+        }
+    });
+}
+
+/// `allow_override` bypasses the status workflow and needs an admin token.
+pub(crate) fn check_status_override(headers: &axum::http::HeaderMap, allow_override: bool) -> Result<()> {
+    if allow_override && !crate::middleware::auth::is_admin(headers) {
+        return Err(crate::error::Error::coded(
+            StatusCode::FORBIDDEN,
+            "status_override_forbidden",
+            "Only admins can override the candidate status workflow",
+        ));
+    }
+    Ok(())
+}
+
 #[axum::debug_handler]
 pub async fn update_candidate_status(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<uuid::Uuid>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<impl axum::response::IntoResponse> {
     let status = payload["status"].as_str().ok_or_else(|| {
@@ -553,8 +1374,50 @@ pub async fn update_candidate_status(
     })?.to_string();
 
     let req_vacancy_id = payload["vacancy_id"].as_i64();
+    let send_notification = payload["send_notification"].as_bool().unwrap_or(false);
+    let allow_override = payload["allow_override"].as_bool().unwrap_or(false);
+    check_status_override(&headers, allow_override)?;
+
+    let previous = state.candidate_service.get_candidate_in_org(id, org.org_id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let updated = state
+        .candidate_service
+        .update_status_in_org(id, org.org_id, status.clone(), allow_override)
+        .await?;
+
+    if status == "rejected" {
+        let audit = crate::services::audit_service::AuditService::new(state.pool.clone());
+        if let Err(e) = audit
+            .log(
+                None,
+                "reject_candidate",
+                "candidate",
+                id,
+                Some(serde_json::json!({ "notified": send_notification && updated.telegram_id.is_some() })),
+                None,
+                None,
+            )
+            .await
+        {
+            tracing::warn!("Failed to write audit log for reject_candidate {}: {:?}", id, e);
+        }
 
-    let updated = state.candidate_service.update_status(id, status.clone()).await?;
+        if send_notification {
+            if let Some(telegram_id) = updated.telegram_id {
+                let config = crate::config::get_config();
+                let message_text = config.rejection_message_template.replace("{name}", updated.name_or_empty());
+                let thread_id = updated.telegram_thread_id;
+                let delivery = crate::services::telegram_delivery_service::TelegramDeliveryService::from_config(
+                    state.pool.clone(),
+                );
+                tokio::spawn(async move {
+                    if let Err(e) = delivery.send(telegram_id, thread_id, &message_text, None).await {
+                        tracing::warn!("Failed to send rejection notification: {}", e);
+                    }
+                });
+            }
+        }
+    }
 
     let vacancy_id = if let Some(v) = req_vacancy_id {
         Some(v)
@@ -565,6 +1428,18 @@ pub async fn update_candidate_status(
             .or(updated.vacancy_id)
     };
 
+    if previous.status != updated.status {
+        enqueue_candidate_event(
+            &state,
+            candidate_status_changed_payload(id, &previous.status, &updated.status, vacancy_id),
+        )
+        .await;
+    }
+
+    if status == "accepted" {
+        spawn_onboarding_checklist(state.clone(), updated.clone(), vacancy_id);
+    }
+
     if let Some(v_id) = vacancy_id {
         let onef = state.onef_service.clone();
         tokio::spawn(async move {
@@ -575,6 +1450,120 @@ pub async fn update_candidate_status(
     Ok(Json(updated))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BulkStatusRequest {
+    pub candidate_ids: Vec<uuid::Uuid>,
+    pub status: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStatusResponse {
+    pub updated: usize,
+    pub failed: Vec<uuid::Uuid>,
+}
+
+pub async fn bulk_update_candidate_status(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<BulkStatusRequest>,
+) -> Result<impl axum::response::IntoResponse> {
+    use crate::services::candidate_service::MAX_BULK_STATUS_CANDIDATES;
+
+    if payload.candidate_ids.is_empty() {
+        return Err(crate::error::Error::BadRequest("candidate_ids must not be empty".into()));
+    }
+    if payload.candidate_ids.len() > MAX_BULK_STATUS_CANDIDATES {
+        return Err(crate::error::Error::coded(
+            StatusCode::BAD_REQUEST,
+            "too_many_candidates",
+            format!("At most {} candidates can be updated at once", MAX_BULK_STATUS_CANDIDATES),
+        ));
+    }
+    let reason = payload.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    let result = state
+        .candidate_service
+        .bulk_update_status(&payload.candidate_ids, org.org_id, &payload.status)
+        .await?;
+    tracing::info!(
+        "Bulk status '{}': {} updated, {} failed",
+        payload.status,
+        result.updated.len(),
+        result.failed.len()
+    );
+
+    let actor = crate::middleware::auth::bearer_claims(&headers)
+        .and_then(|claims| uuid::Uuid::parse_str(&claims.sub).ok());
+    let audit = crate::services::audit_service::AuditService::new(state.pool.clone());
+    for change in &result.updated {
+        if let Err(e) = audit
+            .log(
+                actor,
+                "bulk_update_status",
+                "candidate",
+                change.id,
+                Some(serde_json::json!({
+                    "from": change.previous_status,
+                    "to": payload.status,
+                    "reason": reason,
+                })),
+                None,
+                None,
+            )
+            .await
+        {
+            tracing::warn!("Failed to write audit log for bulk_update_status {}: {:?}", change.id, e);
+        }
+    }
+
+    for change in &result.updated {
+        enqueue_candidate_event(
+            &state,
+            candidate_status_changed_payload(change.id, &change.previous_status, &payload.status, change.vacancy_id),
+        )
+        .await;
+    }
+
+    let changes = result.updated.clone();
+    let status = payload.status.clone();
+    let candidates = state.candidate_service.clone();
+    let onef = state.onef_service.clone();
+    tokio::spawn(async move {
+        let mut tasks = tokio::task::JoinSet::new();
+        for change in changes {
+            let candidates = candidates.clone();
+            let onef = onef.clone();
+            let status = status.clone();
+            tasks.spawn(async move {
+                let vacancy_id = candidates
+                    .get_candidate_applications(change.id)
+                    .await
+                    .ok()
+                    .and_then(|apps| apps.first().map(|a| a.vacancy_id))
+                    .or(change.vacancy_id);
+                match vacancy_id {
+                    Some(v_id) => onef.notify_candidate_status(change.id, status, v_id).await.map_err(|e| (change.id, e)),
+                    None => Ok(()),
+                }
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(Err((id, e))) => tracing::warn!("Failed to notify 1F about status of {}: {}", id, e),
+                Err(e) => tracing::warn!("1F status notification task failed: {}", e),
+                Ok(Ok(())) => {}
+            }
+        }
+    });
+
+    Ok(Json(BulkStatusResponse {
+        updated: result.updated.len(),
+        failed: result.failed,
+    }))
+}
+
 #[axum::debug_handler]
 pub async fn share_candidate_grade_to_onef(
     State(state): State<AppState>,
@@ -582,7 +1571,7 @@ pub async fn share_candidate_grade_to_onef(
 ) -> Result<impl axum::response::IntoResponse> {
     let candidate = state.candidate_service.get_candidate(id).await?
         .ok_or_else(|| {
-            crate::error::Error::NotFound("Candidate not found".into())
+            crate::error::Error::candidate_not_found()
         })?;
     
     let grade = candidate.ai_rating.ok_or_else(|| {
@@ -609,13 +1598,140 @@ pub async fn share_candidate_grade_to_onef(
     })))
 }
 
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct RejectionMessageRequest {
+    /// HR's note on why the candidate was rejected; always triggers a new message.
+    pub reason_hint: Option<String>,
+    /// `ru` (default) or `tg`.
+    pub lang: Option<String>,
+    /// Deliver the message to the candidate on Telegram right away.
+    pub send: bool,
+    /// Ignore the cached message and generate a new one.
+    pub regenerate: bool,
+}
+
+/// Returns the candidate's rejection message, generating (and caching) it on
+/// first use. With `send` the message also goes out through the regular
+/// Telegram chat and shows up in the conversation history.
+pub async fn generate_rejection_message(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+    Json(payload): Json<RejectionMessageRequest>,
+) -> Result<impl axum::response::IntoResponse> {
+    let candidate = state.candidate_service.get_candidate(id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let lang = match payload.lang.as_deref() {
+        None => crate::utils::strings::DEFAULT_LANG,
+        Some(lang) => crate::utils::strings::supported_lang(lang).ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unsupported_language",
+                format!("Unsupported language '{}'", lang),
+            )
+            .with_details(serde_json::json!({ "supported": crate::utils::strings::SUPPORTED_LANGS }))
+        })?,
+    };
+    let reason_hint = payload.reason_hint.as_deref().map(str::trim).filter(|r| !r.is_empty());
+
+    let cached = if payload.regenerate || reason_hint.is_some() {
+        None
+    } else {
+        state.candidate_service.rejection_message(id).await?
+    };
+    let from_cache = cached.is_some();
+    let message = match cached {
+        Some(message) => message,
+        None => {
+            let vacancy_title = match candidate.vacancy_id {
+                Some(vacancy_id) => state
+                    .koinotinav_service
+                    .fetch_vacancy(vacancy_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|v| v.title),
+                None => None,
+            }
+            .unwrap_or_default();
+            let message = state
+                .ai_service
+                .generate_rejection_message(candidate.name_or_empty(), &vacancy_title, reason_hint, lang)
+                .await?;
+            state.candidate_service.set_rejection_message(id, &message).await?;
+            message
+        }
+    };
+
+    if payload.send {
+        let telegram_id = candidate.telegram_id.ok_or_else(|| {
+            crate::error::Error::BadRequest("Candidate has no associated Telegram ID".into())
+        })?;
+        crate::routes::telegram::deliver_to_chat(telegram_id, candidate.telegram_thread_id, &message, None).await?;
+
+        let create_msg = crate::models::message::CreateMessage {
+            candidate_id: candidate.id,
+            telegram_id,
+            direction: "outbound".to_string(),
+            text: message.clone(),
+            attachment_type: None,
+            attachment_path: None,
+        };
+        if let Ok(stored) = state.message_service.create(create_msg).await {
+            let _ = state.message_events.send((&stored).into());
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": message,
+        "cached": from_cache,
+        "sent": payload.send,
+    })))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct DeleteCandidateQuery {
+    pub erase: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct DeleteCandidateRequest {
+    pub reason: Option<String>,
+}
+
 pub async fn delete_candidate(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<uuid::Uuid>,
+    axum::extract::Query(query): axum::extract::Query<DeleteCandidateQuery>,
+    payload: Option<Json<DeleteCandidateRequest>>,
 ) -> Result<impl axum::response::IntoResponse> {
-    state.candidate_service.delete_candidate(id).await.map_err(|e| {
-        tracing::error!("Failed to delete candidate {}: {}", id, e);
-        crate::error::Error::Internal(e.to_string())
-    })?;
+    let reason = payload.and_then(|Json(p)| p.reason);
+
+    let action = if query.erase {
+        state.candidate_service.erase_candidate(id, org.org_id).await?;
+        "erase_candidate"
+    } else {
+        state.candidate_service.delete_candidate(id, org.org_id, reason.clone()).await?;
+        "delete_candidate"
+    };
+
+    let audit = crate::services::audit_service::AuditService::new(state.pool.clone());
+    if let Err(e) = audit
+        .log(
+            None,
+            action,
+            "candidate",
+            id,
+            Some(serde_json::json!({ "reason": reason })),
+            None,
+            None,
+        )
+        .await
+    {
+        tracing::warn!("Failed to write audit log for {} {}: {:?}", action, id, e);
+    }
+
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
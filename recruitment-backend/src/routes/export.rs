@@ -1,42 +1,66 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{header, StatusCode},
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
-use crate::{AppState, error::Result};
+use crate::{
+    AppState, error::{Error, Result}, middleware::organization::OrganizationContext,
+    services::export_service::{CandidateDataExport, ExportOptions, ExportService},
+    utils::signed_url::{storage_path, upload_disk_path, verify_portal_token},
+};
 
 #[derive(Debug, Deserialize)]
 pub struct BulkExportRequest {
     pub candidate_ids: Option<Vec<uuid::Uuid>>,
+    /// Adds the "История" sheet with one row per history event.
+    #[serde(default)]
+    pub include_history: bool,
+    /// Adds the "Статистика" sheet with status and AI-rating breakdowns.
+    #[serde(default)]
+    pub include_stats: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalTokenQuery {
+    pub portal_token: Option<String>,
+}
+
+/// Koinoti Nav titles by id, replaced by the internal vacancy's title
+/// wherever a vacancy link exists.
+pub(crate) async fn vacancy_titles(state: &AppState) -> Result<HashMap<i64, String>> {
+    let vacancies = state.koinotinav_service.fetch_vacancies().await.unwrap_or_default();
+    let mut vacancy_map: HashMap<i64, String> = vacancies.into_iter().map(|v| (v.id, v.title)).collect();
+    for (koinotinav_id, canonical) in state.vacancy_link_service.canonical_by_koinotinav_id().await? {
+        vacancy_map.insert(koinotinav_id, canonical.title);
+    }
+    Ok(vacancy_map)
 }
 
 pub async fn export_candidate(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<uuid::Uuid>,
 ) -> Result<impl IntoResponse> {
-    let candidate = state.candidate_service.get_candidate(id).await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
+    let candidate = state.candidate_service.get_candidate_in_org(id, org.org_id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
 
-    let vacancies = state.koinotinav_service.fetch_vacancies().await.unwrap_or_default();
-    let mut vacancy_map = HashMap::new();
-    for v in vacancies {
-        vacancy_map.insert(v.id, v.title);
-    }
+    let vacancy_map = vacancy_titles(&state).await?;
 
     let mut history_map = HashMap::new();
     let history = state.candidate_service.get_candidate_history(candidate.id).await?;
     history_map.insert(candidate.id, history);
 
     let buffer = crate::services::export_service::ExportService::generate_candidates_xlsx(
-        &[candidate.clone()],
+        std::slice::from_ref(&candidate),
         &vacancy_map,
-        &history_map
+        &history_map,
+        ExportOptions::default(),
     )?;
     let filename = format!("candidate_{}_{}.xlsx",
-        candidate.name.replace(' ', "_"),
+        candidate.name_or_empty().replace(' ', "_"),
         chrono::Utc::now().format("%Y%m%d")
     );
     let disposition = format!("attachment; filename=\"{}\"", filename);
@@ -51,26 +75,93 @@ pub async fn export_candidate(
     ))
 }
 
+/// GET /api/candidate/:id/export-data?portal_token=
+///
+/// Everything stored about the candidate as a ZIP, for the candidate
+/// themselves: the token must be the `portal_token` issued to this
+/// candidate at registration, otherwise 403 `invalid_portal_token`.
+pub async fn export_candidate_data(
+    State(state): State<AppState>,
+    Path(id): Path<uuid::Uuid>,
+    Query(query): Query<PortalTokenQuery>,
+) -> Result<impl IntoResponse> {
+    let secret = crate::config::get_config().upload_signing_secret.as_bytes();
+    let token = query.portal_token.unwrap_or_default();
+    if !verify_portal_token(id, &token, secret) {
+        return Err(Error::coded(
+            StatusCode::FORBIDDEN,
+            "invalid_portal_token",
+            "A valid portal_token for this candidate is required",
+        ));
+    }
+    let candidate = state.candidate_service.get_candidate(id).await?
+        .ok_or_else(Error::candidate_not_found)?;
+
+    let applications = state.candidate_service.get_candidate_applications(id).await?;
+    let history = state.candidate_service.get_candidate_history(id).await?;
+    let attempts = match &candidate.email {
+        Some(email) => state.attempt_service.list_for_candidate_email(email).await?,
+        None => Vec::new(),
+    };
+    let messages = state.message_service.list_all_for_candidate(id).await?;
+    let cv = match candidate.cv_url.as_deref() {
+        // `get_candidate` hands back a signed link; name the entry after the stored path.
+        Some(cv_url) => match tokio::fs::read(upload_disk_path(cv_url)).await {
+            Ok(bytes) => Some((storage_path(cv_url), bytes)),
+            Err(e) => {
+                tracing::warn!("CV {} of candidate {} not exported: {}", cv_url, id, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let buffer = ExportService::generate_candidate_data_archive(
+        &candidate,
+        &CandidateDataExport {
+            applications: &applications,
+            history: &history,
+            attempts: &attempts,
+            messages: &messages,
+            cv: cv.as_ref().map(|(name, bytes)| (*name, bytes.as_slice())),
+        },
+    )?;
+    let filename = format!("candidate_data_{}_{}.zip",
+        candidate.id,
+        chrono::Utc::now().format("%Y%m%d")
+    );
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        buffer,
+    ))
+}
+
 pub async fn export_candidates_bulk(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<BulkExportRequest>,
 ) -> Result<impl IntoResponse> {
+    let options = ExportOptions {
+        include_history: payload.include_history,
+        include_stats: payload.include_stats,
+    };
     let candidates = if let Some(ids) = payload.candidate_ids {
         if ids.is_empty() {
-            state.candidate_service.list_candidates().await?
+            state.candidate_service.list_candidates(org.org_id).await?
         } else {
-            let all = state.candidate_service.list_candidates().await?;
+            let all = state.candidate_service.list_candidates(org.org_id).await?;
             all.into_iter().filter(|c| ids.contains(&c.id)).collect()
         }
     } else {
-        state.candidate_service.list_candidates().await?
+        state.candidate_service.list_candidates(org.org_id).await?
     };
 
-    let vacancies = state.koinotinav_service.fetch_vacancies().await.unwrap_or_default();
-    let mut vacancy_map = HashMap::new();
-    for v in vacancies {
-        vacancy_map.insert(v.id, v.title);
-    }
+    let vacancy_map = vacancy_titles(&state).await?;
 
     let mut history_map = HashMap::new();
     for c in &candidates {
@@ -82,7 +173,8 @@ pub async fn export_candidates_bulk(
     let buffer = crate::services::export_service::ExportService::generate_candidates_xlsx(
         &candidates,
         &vacancy_map,
-        &history_map
+        &history_map,
+        options,
     )?;
     let filename = format!("candidates_export_{}.xlsx",
         chrono::Utc::now().format("%Y%m%d_%H%M")
@@ -17,10 +17,15 @@ pub async fn create_external_vacancy(
     Json(payload): Json<ExternalVacancyPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
+    let internal_vacancy_id = payload.internal_vacancy_id;
     let service = ExternalVacancyService::new(state.clone());
     let result = service.create_vacancy(payload).await?;
 
     if result.success {
+        let created_id = result.vacancy_id.as_deref().and_then(|id| id.trim().parse::<i64>().ok());
+        if let (Some(vacancy_id), Some(koinotinav_id)) = (internal_vacancy_id, created_id) {
+            state.vacancy_link_service.link_koinotinav(vacancy_id, koinotinav_id).await?;
+        }
         Ok((StatusCode::ACCEPTED, Json(result)))
     } else {
         Err(Error::BadRequest(result.message))
@@ -33,10 +38,14 @@ pub async fn delete_external_vacancy(
     Json(payload): Json<ExternalVacancyDeletePayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
-    let service = ExternalVacancyService::new(state);
+    let koinotinav_id = payload.vacancy_id.trim().parse::<i64>().ok();
+    let service = ExternalVacancyService::new(state.clone());
     let result = service.delete_vacancy(payload).await?;
 
     if result.success {
+        if let Some(koinotinav_id) = koinotinav_id {
+            state.vacancy_link_service.unlink_koinotinav(koinotinav_id).await?;
+        }
         Ok((StatusCode::ACCEPTED, Json(result)))
     } else {
         Err(Error::BadRequest(result.message))
@@ -1,4 +1,6 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use crate::services::health_service::{check_readiness, HealthService, OverallStatus, Readiness};
+use crate::AppState;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use serde_json::json;
 
 #[axum::debug_handler]
@@ -8,3 +10,26 @@ pub async fn health() -> impl IntoResponse {
     });
     (StatusCode::OK, Json(body))
 }
+
+/// GET /health/detailed — per-dependency status. `503` only when the
+/// database is down; other failures report `degraded` with `200`.
+pub async fn health_detailed(State(state): State<AppState>) -> impl IntoResponse {
+    let report = HealthService::check_all(&state).await;
+    let status = match report.status {
+        OverallStatus::Error => StatusCode::SERVICE_UNAVAILABLE,
+        OverallStatus::Ok | OverallStatus::Degraded => StatusCode::OK,
+    };
+    (status, Json(report))
+}
+
+/// GET /health/ready — readiness probe: database, uploads directory and
+/// queue backlogs. `503` when the database check fails.
+pub async fn health_ready(State(state): State<AppState>) -> impl IntoResponse {
+    let uploads_dir = crate::utils::signed_url::upload_root();
+    let report = check_readiness(&state.pool, std::path::Path::new(&uploads_dir)).await;
+    let status = match report.status {
+        Readiness::Down => StatusCode::SERVICE_UNAVAILABLE,
+        Readiness::Ok | Readiness::Degraded => StatusCode::OK,
+    };
+    (status, Json(report))
+}
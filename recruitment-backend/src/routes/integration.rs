@@ -1,29 +1,53 @@
 use crate::{
     dto::integration_dto::{
-        CreateTestPayload, EnqueueAiJobPayload, GenerateAiTestPayload,
+        CreateTestPayload, EnqueueAiJobPayload, GenerateAiTestPayload, RegradeTestPayload,
         GenerateVacancyDescriptionPayload, UpdateTestPayload, GradePresentationPayload,
-        SendMessagePayload, CandidateStatusSync, DashboardStats,
+        SendMessagePayload, CandidateStatusSyncQuery, DashboardStats, ExtendAttemptPayload,
+        ReviewFeedbackPayload,
     },
     error::Result,
+    middleware::organization::OrganizationContext,
+    services::dashboard_service::DashboardRange,
+    services::dashboard_snapshot_service::DashboardHistoryQuery,
+    services::message_service::MessageQuery,
+    services::question_feedback_service::QuestionFeedbackService,
+    services::question_import_service,
+    services::test_quality_service::TestQualityService,
+    services::test_service::{parse_test_spec, TestSpecFormat},
+    services::telegram_delivery_service::TelegramDeliveryService,
+    utils::strings,
     AppState,
 };
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Multipart, Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
 use serde_json::{json, Value as JsonValue};
+use std::convert::Infallible;
 use std::time::Duration;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 use validator::Validate;
 
-#[axum::debug_handler]
-pub async fn create_test(
-    State(state): State<AppState>,
-    Json(payload): Json<CreateTestPayload>,
-) -> Result<impl IntoResponse> {
-    payload.validate()?;
+/// Owner recorded on tests created through the integration API: the signed-in
+/// caller, else the first user, or a system admin created on first use.
+async fn default_creator(state: &AppState, headers: &axum::http::HeaderMap) -> Result<Uuid> {
+    if let Some(caller) = reviewer_id(headers) {
+        let known = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM users WHERE id = $1) as "exists!""#,
+            caller
+        )
+        .fetch_one(&state.pool)
+        .await?;
+        if known {
+            return Ok(caller);
+        }
+    }
 
     let user = sqlx::query!("SELECT id FROM users LIMIT 1")
         .fetch_optional(&state.pool)
@@ -48,8 +72,20 @@ pub async fn create_test(
              new_id
         }
     };
+    Ok(created_by)
+}
 
-    let test = state.test_service.create_test(payload, created_by).await?;
+#[axum::debug_handler]
+pub async fn create_test(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<CreateTestPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+
+    let created_by = default_creator(&state, &headers).await?;
+    let test = state.test_service.create_test(payload, created_by, org.org_id).await?;
 
     let response = json!({
         "id": test.id,
@@ -61,22 +97,117 @@ pub async fn create_test(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+/// Creates a test from an uploaded JSON or YAML spec (multipart `file` part)
+/// with the same fields as `POST /api/integration/tests`. The format comes
+/// from the file extension, else the part's `Content-Type`.
+pub async fn import_test(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    headers: axum::http::HeaderMap,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse> {
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let format = TestSpecFormat::detect(field.file_name(), field.content_type());
+        upload = Some((format, field.bytes().await?));
+        break;
+    }
+    let (format, data) = upload
+        .ok_or_else(|| crate::error::Error::BadRequest("Multipart field `file` is required".into()))?;
+    let format = format.ok_or_else(|| {
+        crate::error::Error::coded(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_test_spec_format",
+            "Test spec must be a .json, .yaml or .yml file",
+        )
+    })?;
+
+    let payload = parse_test_spec(&data, format)?;
+    payload.validate()?;
+
+    let created_by = default_creator(&state, &headers).await?;
+    let test = state.test_service.create_test(payload, created_by, org.org_id).await?;
+    Ok((StatusCode::CREATED, Json(test)))
+}
+
+#[derive(Debug, serde::Deserialize, Default)]
+#[serde(default)]
+pub struct ImportQuestionsQuery {
+    pub dry_run: bool,
+}
+
+/// POST /api/integration/tests/:id/questions/import — appends the questions
+/// of an uploaded XLSX sheet (multipart `file` part) to the test. With
+/// `dry_run=true` the parsed questions are returned and nothing is saved.
+pub async fn import_questions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ImportQuestionsQuery>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse> {
+    let mut data = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            data = Some(field.bytes().await?);
+            break;
+        }
+    }
+    let data = data
+        .ok_or_else(|| crate::error::Error::BadRequest("Multipart field `file` is required".into()))?;
+
+    let questions = question_import_service::parse_question_sheet(&data)?;
+    if query.dry_run {
+        state.test_service.get_test_by_id(id).await?;
+        return Ok(Json(json!({
+            "dry_run": true,
+            "imported": questions.len(),
+            "questions": questions,
+        })));
+    }
+    let imported = questions.len();
+    let test = state.test_service.append_questions(id, questions).await?;
+    Ok(Json(json!({
+        "dry_run": false,
+        "imported": imported,
+        "test": test,
+    })))
+}
+
+/// GET /api/integration/tests/question-template.xlsx — the empty sheet for
+/// `import_questions`.
+pub async fn question_template() -> Result<impl IntoResponse> {
+    let buffer = question_import_service::question_template()?;
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (axum::http::header::CONTENT_DISPOSITION, "attachment; filename=\"question-template.xlsx\""),
+        ],
+        buffer,
+    ))
+}
+
 pub async fn get_test_by_id(
     State(state): State<AppState>,
+    org: OrganizationContext,
     axum::extract::Path(test_id): axum::extract::Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let test = state.test_service.get_test_by_id(test_id).await?;
+    let test = state.test_service.get_test_in_org(test_id, org.org_id).await?;
     Ok(Json(test))
 }
 
 #[axum::debug_handler]
 pub async fn update_test(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateTestPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
-    let test = state.test_service.update_test(id, payload).await?;
+    let test = state.test_service.update_test(id, org.org_id, payload).await?;
     let response = json!({
         "status": "success",
         "test": test,
@@ -95,6 +226,8 @@ pub struct ListTestsQuery {
 
 pub async fn list_tests(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    headers: axum::http::HeaderMap,
     axum::extract::Query(query): axum::extract::Query<ListTestsQuery>,
 ) -> Result<impl IntoResponse> {
     let page = query.page.unwrap_or(1);
@@ -108,16 +241,82 @@ pub async fn list_tests(
 
     let result = state
         .test_service
-        .list_tests(page, per_page, Some(filter))
+        .list_tests(page, per_page, Some(filter), owner_scope(&headers), org.org_id)
         .await?;
     Ok(Json(result))
 }
 
+/// Signed-in HR users only see tests they own. Admins and callers without
+/// a bearer token (service integrations) see every test.
+fn owner_scope(headers: &axum::http::HeaderMap) -> Option<Uuid> {
+    if crate::middleware::auth::is_admin(headers) {
+        return None;
+    }
+    reviewer_id(headers)
+}
+
+/// Owners and admins may change a test's owners; integration callers
+/// without a bearer token are trusted like for every other test endpoint.
+async fn ensure_can_manage_owners(
+    state: &AppState,
+    org: &OrganizationContext,
+    headers: &axum::http::HeaderMap,
+    test_id: Uuid,
+) -> Result<()> {
+    state.test_service.get_test_in_org(test_id, org.org_id).await?;
+    let Some(caller) = owner_scope(headers) else {
+        return Ok(());
+    };
+    if state.test_service.is_owner(test_id, caller).await? {
+        return Ok(());
+    }
+    Err(crate::error::Error::coded(
+        StatusCode::FORBIDDEN,
+        "not_test_owner",
+        "Only the test's owners can change who has access",
+    ))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct AddTestOwnerPayload {
+    pub user_id: Uuid,
+}
+
+pub async fn add_test_owner(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(test_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<AddTestOwnerPayload>,
+) -> Result<impl IntoResponse> {
+    ensure_can_manage_owners(&state, &org, &headers, test_id).await?;
+    state.test_service.add_owner(test_id, payload.user_id).await?;
+    let owners = state.test_service.list_owners(test_id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "test_id": test_id, "owners": owners })),
+    ))
+}
+
+pub async fn remove_test_owner(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path((test_id, user_id)): Path<(Uuid, Uuid)>,
+    headers: axum::http::HeaderMap,
+) -> Result<impl IntoResponse> {
+    ensure_can_manage_owners(&state, &org, &headers, test_id).await?;
+    state.test_service.remove_owner(test_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn delete_test(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    state.test_service.delete_test(id).await?;
+    if !state.test_service.delete_test(id, org.org_id).await? {
+        return Err(crate::error::Error::NotFound("Test not found".to_string()));
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -125,10 +324,13 @@ pub async fn delete_test(
 pub struct CreateInviteRequest {
     pub test_id: Uuid,
     pub candidate: InviteCandidateDto,
-    pub expires_in_hours: i64,
+    /// Defaults to the test's `default_invite_expiry_hours`.
+    pub expires_in_hours: Option<i64>,
     pub send_notification: Option<bool>,
     pub notification_method: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// Candidate-facing language, `ru` (default) or `tg`.
+    pub lang: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -143,8 +345,25 @@ pub struct InviteCandidateDto {
 #[axum::debug_handler]
 pub async fn create_test_invite(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<CreateInviteRequest>,
 ) -> Result<impl IntoResponse> {
+    let test = state.test_service.get_test_in_org(payload.test_id, org.org_id).await?;
+    let expires_in_hours = payload
+        .expires_in_hours
+        .unwrap_or_else(|| test.invite_expiry_hours());
+    let lang = match payload.lang.as_deref() {
+        None => strings::DEFAULT_LANG,
+        Some(lang) => strings::supported_lang(lang).ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unsupported_language",
+                format!("Unsupported language '{}'", lang),
+            )
+            .with_details(json!({ "supported": strings::SUPPORTED_LANGS }))
+        })?,
+    };
+
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
     let candidate_name = payload.candidate.name.clone();
     let result = svc
@@ -157,13 +376,21 @@ pub async fn create_test_invite(
                 telegram_id: payload.candidate.telegram_id,
                 phone: payload.candidate.phone,
             },
-            payload.expires_in_hours,
+            expires_in_hours,
             payload.metadata,
+            Some(lang),
+            crate::services::attempt_service::InviteSource::Integration,
         )
         .await?;
 
-    let test = state.test_service.get_test_by_id(payload.test_id).await?;
-
+    let telegram_thread_id = state
+        .candidate_service
+        .telegram_thread_id(payload.candidate.telegram_id)
+        .await;
+    let timezone = state
+        .candidate_service
+        .timezone_for_telegram(payload.candidate.telegram_id)
+        .await;
     let notif = crate::services::notification_service::NotificationService::new(
         state.pool.clone(),
         crate::config::get_config().telegram_bot_webhook_url.clone(),
@@ -174,22 +401,28 @@ pub async fn create_test_invite(
         candidate: crate::dto::webhook_dto::WebhookCandidate {
             name: candidate_name,
             telegram_id: payload.candidate.telegram_id,
+            telegram_thread_id,
         },
         test: crate::dto::webhook_dto::WebhookTest {
             title: test.title.clone(),
         },
         access_token: result.access_token.clone(),
         expires_at: result.expires_at,
+        expires_at_local: crate::utils::time::format_deadline(result.expires_at, timezone.as_deref()),
     };
     let payload_json = serde_json::to_value(&assigned)?;
     let _ = notif
-        .enqueue_webhook("test_assigned", &payload_json)
+        .enqueue_webhook(
+            "test_assigned",
+            &payload_json,
+            Some(&result.attempt_id.to_string()),
+        )
         .await?;
 
+    let mut warnings: Vec<&str> = Vec::new();
     if let Some(telegram_id) = payload.candidate.telegram_id {
         let config = crate::config::get_config();
         let webapp_url = &config.webapp_url;
-        let bot_token = &config.telegram_bot_token;
         
         let message_text = if test.test_type.as_deref() == Some("presentation") {
             let themes_count = test.presentation_themes
@@ -197,40 +430,35 @@ pub async fn create_test_invite(
                 .and_then(|t| t.as_array())
                 .map(|a| a.len())
                 .unwrap_or(0);
-            format!(
-                "Вам назначена презентация: {}\n\nКоличество тем: {}\nСрок выполнения: {} часов\n\nНажмите кнопку ниже, чтобы просмотреть задание.",
-                test.title,
-                themes_count,
-                payload.expires_in_hours
-            )
+            strings::render(lang, "invite_presentation", &[
+                ("title", &test.title),
+                ("themes", &themes_count.to_string()),
+                ("hours", &expires_in_hours.to_string()),
+            ])
         } else {
-            format!(
-                "Вам назначен тест: {}\n\nНажмите кнопку ниже, чтобы начать прохождение теста.",
-                test.title
-            )
+            strings::render(lang, "invite_test", &[("title", &test.title)])
         };
         
         let reply_markup = serde_json::json!({
             "inline_keyboard": [[
                 {
-                    "text": "Профиль",
+                    "text": strings::text(lang, "profile_button"),
                     "web_app": { "url": webapp_url }
                 }
             ]]
         });
         
-        let telegram_body = serde_json::json!({
-            "chat_id": telegram_id,
-            "text": message_text,
-            "reply_markup": reply_markup,
-        });
-        
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
-        let client = reqwest::Client::new();
-        if let Err(e) = client.post(&url).json(&telegram_body).send().await {
-            tracing::warn!("Failed to send Telegram notification: {}", e);
-        } else {
-            tracing::info!("Telegram notification sent to chat_id: {}", telegram_id);
+        let delivery = TelegramDeliveryService::from_config(state.pool.clone());
+        match delivery
+            .send(telegram_id, telegram_thread_id, &message_text, Some(reply_markup))
+            .await
+        {
+            Ok(()) => tracing::info!("Telegram notification sent to chat_id: {}", telegram_id),
+            Err(e) if e.code() == "telegram_unreachable" => {
+                tracing::info!("Skipped Telegram notification to blocked chat_id: {}", telegram_id);
+                warnings.push("telegram_unreachable");
+            }
+            Err(e) => tracing::warn!("Failed to send Telegram notification: {}", e),
         }
     }
 
@@ -248,23 +476,27 @@ pub async fn create_test_invite(
         .await?;
 
     let config = crate::config::get_config();
-    let response = json!({
+    let mut response = json!({
         "attempt_id": result.attempt_id,
         "access_token": result.access_token,
         "test_url": format!("{}/test/{}", config.webapp_url, result.access_token),
         "expires_at": result.expires_at,
         "status": result.status,
     });
+    if !warnings.is_empty() {
+        response["warnings"] = json!(warnings);
+    }
     Ok((StatusCode::CREATED, Json(response)))
 }
 
 #[axum::debug_handler]
 pub async fn list_test_invites(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
     let (items, _total) = svc
-        .list_attempts(None, None, None, 1, 100)
+        .list_attempts(None, None, None, None, 1, 100, org.org_id)
         .await?;
     
     let invites: Vec<serde_json::Value> = items.iter().map(|a| {
@@ -284,10 +516,11 @@ pub async fn list_test_invites(
 
 pub async fn get_test_attempt_by_id(
     State(state): State<AppState>,
+    org: OrganizationContext,
     axum::extract::Path(attempt_id): axum::extract::Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let attempt = svc.get_attempt_by_id(attempt_id).await?;
+    let attempt = svc.get_attempt_in_org(attempt_id, org.org_id).await?;
     let test = state.test_service.get_test_by_id(attempt.test_id).await?;
     let resp = serde_json::json!({
         "id": attempt.id,
@@ -317,29 +550,60 @@ pub async fn get_test_attempt_by_id(
         "presentation_grade": attempt.presentation_grade,
         "presentation_grade_comment": attempt.presentation_grade_comment,
         "metadata": attempt.metadata,
+        "answer_changes": attempt.answer_changes,
+        "country": attempt.country,
+        "city": attempt.city,
+        "suspicious_geo": attempt.suspicious_geo,
+        "ip_address": attempt.ip_address.map(|net| net.ip().to_string()),
+        "user_agent": attempt.user_agent,
+        "ip_changes": crate::services::attempt_service::ip_change_flags(attempt.suspicious_activity.as_ref()),
+        "interruption_seconds": attempt.interruption_seconds,
+        "connection_losses": crate::services::attempt_service::connection_loss_flags(attempt.suspicious_activity.as_ref()),
     });
     Ok(Json(resp))
 }
 
+pub async fn analyze_answer_changes(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(attempt_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    state.attempt_service.get_attempt_in_org(attempt_id, org.org_id).await?;
+    let report = state.attempt_service.analyze_answer_changes(attempt_id).await?;
+    Ok(Json(report))
+}
+
 #[derive(Debug, serde::Deserialize, Default)]
 #[serde(default)]
 pub struct ListAttemptsQuery {
     pub test_id: Option<Uuid>,
     pub candidate_email: Option<String>,
     pub status: Option<String>,
+    /// `integration`, `onef`, `auto_invite` or `retake`.
+    pub source: Option<String>,
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
 
 pub async fn list_test_attempts(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Query(q): Query<ListAttemptsQuery>,
 ) -> Result<impl IntoResponse> {
     let page = q.page.unwrap_or(1);
     let limit = q.limit.unwrap_or(20).clamp(1, 100);
+    let source = crate::services::attempt_service::parse_source_filter(q.source.as_deref())?;
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
     let (items, total) = svc
-        .list_attempts(q.test_id, q.candidate_email, q.status, page, limit)
+        .list_attempts(
+            q.test_id,
+            q.candidate_email,
+            q.status,
+            source,
+            page,
+            limit,
+            org.org_id,
+        )
         .await?;
     let total_pages = ((total as f64) / (limit as f64)).ceil() as i64;
     let resp = serde_json::json!({
@@ -352,31 +616,192 @@ pub async fn list_test_attempts(
     Ok(Json(resp))
 }
 
+#[axum::debug_handler]
+pub async fn regrade_test(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RegradeTestPayload>,
+) -> Result<impl IntoResponse> {
+    let summary = state
+        .attempt_service
+        .regrade_test(id, org.org_id, &payload.corrections)
+        .await?;
+    Ok(Json(summary))
+}
+
+/// Creates a preview attempt so HR can take the test as a candidate would.
+#[axum::debug_handler]
+pub async fn preview_test(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let result = state.attempt_service.create_preview(id, org.org_id).await?;
+    let config = crate::config::get_config();
+    let response = json!({
+        "attempt_id": result.attempt_id,
+        "access_token": result.access_token,
+        "test_url": format!("{}/test/{}", config.webapp_url, result.access_token),
+        "expires_at": result.expires_at,
+        "status": result.status,
+        "is_preview": true,
+    });
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[axum::debug_handler]
+pub async fn duplicate_test(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<crate::dto::integration_dto::DuplicateTestPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let cfg = crate::config::get_config();
+    let num_q = payload.num_new_questions.min(cfg.max_ai_questions);
+    let test = state
+        .test_service
+        .duplicate_test(&state.ai_service, id, org.org_id, num_q, payload.profession)
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "id": test.id, "title": test.title })),
+    ))
+}
+
+#[axum::debug_handler]
+pub async fn auto_adjust_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<crate::dto::integration_dto::AutoAdjustTestPayload>,
+) -> Result<impl IntoResponse> {
+    let dry_run = payload.dry_run.unwrap_or(true);
+    let adjustment = if dry_run {
+        state
+            .test_service
+            .propose_difficulty_adjustment(&state.ai_service, id)
+            .await?
+    } else {
+        state.test_service.apply_difficulty_adjustment(id).await?
+    };
+    Ok(Json(json!({
+        "dry_run": dry_run,
+        "applied": !dry_run,
+        "adjustment": adjustment,
+    })))
+}
+
+/// GET /api/integration/tests/:id/feedback — unreviewed candidate feedback.
+pub async fn list_question_feedback(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let items = QuestionFeedbackService::new(state.pool.clone())
+        .list_unreviewed(id)
+        .await?;
+    Ok(Json(items))
+}
+
+pub async fn review_question_feedback(
+    State(state): State<AppState>,
+    Path((id, feedback_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ReviewFeedbackPayload>,
+) -> Result<impl IntoResponse> {
+    let feedback = QuestionFeedbackService::new(state.pool.clone())
+        .set_reviewed(id, feedback_id, payload.reviewed)
+        .await?;
+    Ok(Json(feedback))
+}
+
+/// POST /api/integration/tests/:id/quality-report — AI critique of the
+/// test's current questions; the test is not changed.
+pub async fn generate_quality_report(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let test = state.test_service.get_test_by_id(id).await?;
+    let report = TestQualityService::new(state.pool.clone())
+        .generate(&state.ai_service, &test)
+        .await?;
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// GET /api/integration/tests/:id/quality-report/latest
+pub async fn get_latest_quality_report(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let report = TestQualityService::new(state.pool.clone())
+        .latest(id)
+        .await?
+        .ok_or_else(|| {
+            crate::error::Error::coded(
+                StatusCode::NOT_FOUND,
+                "quality_report_not_found",
+                "No quality report has been generated for this test yet",
+            )
+        })?;
+    Ok(Json(report))
+}
+
+/// GET /api/integration/tests/:id/generation-log — how the AI generated the test.
+pub async fn get_generation_log(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let runs = state.test_service.generation_log(id, org.org_id).await?;
+    Ok(Json(json!({ "test_id": id, "runs": runs })))
+}
+
+/// Adds a request-time generation to the AI usage report; a failure only loses the numbers.
+async fn record_ai_usage(
+    state: &AppState,
+    source: &str,
+    output: &crate::services::ai_service::GenerationOutput,
+) {
+    let queue = crate::services::queue_service::AiQueueService::new(state.pool.clone());
+    if let Err(e) = queue.record_usage(source, output).await {
+        tracing::warn!("Failed to store AI usage for {}: {:?}", source, e);
+    }
+}
+
 #[axum::debug_handler]
 pub async fn generate_ai_test(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<GenerateAiTestPayload>,
 ) -> Result<impl IntoResponse> {
     let cfg = crate::config::get_config();
     let num_q = payload.num_questions.unwrap_or(6).min(cfg.max_ai_questions);
     let skills: Vec<String> = payload.skills.clone().unwrap_or_default();
+    let distribution = crate::services::ai_service::DifficultyDistribution::from_optional_map(
+        payload.difficulty_distribution.as_ref(),
+    )?;
+    let language = crate::services::ai_service::parse_test_language(payload.language.as_deref())?;
 
     let ai_future = state.ai_service.generate_test(
         &payload.profession,
         &skills,
         num_q,
+        &distribution,
+        language,
     );
 
-    let gen_output = match tokio::time::timeout(Duration::from_secs(300), ai_future).await {
+    let timeout = Duration::from_secs(crate::config::get_config().ai_generation_timeout_secs);
+    let gen_output = match tokio::time::timeout(timeout, ai_future).await {
         Ok(Ok(val)) => val,
         _ => {
             tracing::warn!("AI generation failed or timed out");
             crate::services::ai_service::GenerationOutput {
                 questions: vec![],
                 logs: vec!["Timeout or fatal error in generate_test".to_string()],
+                ..Default::default()
             }
         }
     };
+    record_ai_usage(&state, "generate_ai_test", &gen_output).await;
     let questions_val = serde_json::to_value(&gen_output.questions)?;
 
     if payload.persist.unwrap_or(false) {
@@ -421,12 +846,31 @@ pub async fn generate_ai_test(
             test_type: Some("question_based".to_string()),
             presentation_themes: None,
             presentation_extra_info: None,
+            show_honesty_declaration: None,
+            declaration_text: None,
+            prerequisite_test_id: None,
+            default_invite_expiry_hours: None,
+            reminder_hours_before: None,
+            passing_score_mcq: None,
+            passing_score_open: None,
+            share_results_details: None,
+            max_grace_seconds: None,
+            max_concurrent_takers: None,
+            language: Some(language.tag().to_string()),
+            reveal_correct_answers: None,
         };
 
         let test = state
             .test_service
-            .create_test(test_payload, created_by)
+            .create_test(test_payload, created_by, org.org_id)
             .await?;
+        if let Err(e) = state
+            .test_service
+            .record_generation_run(test.id, None, &gen_output, num_q, gen_output.questions.len(), 0)
+            .await
+        {
+            tracing::warn!("Failed to store generation log for test {}: {:?}", test.id, e);
+        }
         Ok((
             StatusCode::OK,
             Json(serde_json::json!({ "questions": questions_val, "test_id": test.id })),
@@ -446,10 +890,26 @@ pub async fn generate_vacancy_description(
     State(state): State<AppState>,
     Json(payload): Json<GenerateVacancyDescriptionPayload>,
 ) -> Result<impl IntoResponse> {
+    use crate::services::ai_service::{parse_vacancy_languages, VacancyLanguage};
+
     payload.validate()?;
+    if let Some(requested) = payload.languages.as_deref().filter(|l| !l.is_empty()) {
+        let languages = parse_vacancy_languages(requested)?;
+        let descriptions = state
+            .ai_service
+            .generate_vacancy_descriptions(&payload, &languages)
+            .await?;
+        return Ok(Json(serde_json::json!({ "descriptions": descriptions })));
+    }
+
+    let language = payload
+        .language
+        .as_deref()
+        .and_then(VacancyLanguage::parse)
+        .unwrap_or_default();
     let description = state
         .ai_service
-        .generate_vacancy_description(&payload)
+        .generate_vacancy_description(&payload, language)
         .await?;
     Ok(Json(serde_json::json!({ "description": description })))
 }
@@ -466,16 +926,23 @@ pub async fn generate_vacancy_description(
 )]
 pub async fn enqueue_ai_job(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<EnqueueAiJobPayload>,
 ) -> Result<impl IntoResponse> {
     let cfg = crate::config::get_config();
     let num_q = payload.num_questions.unwrap_or(6).min(cfg.max_ai_questions);
+    crate::services::ai_service::DifficultyDistribution::from_optional_map(
+        payload.difficulty_distribution.as_ref(),
+    )?;
+    let language = crate::services::ai_service::parse_test_language(payload.language.as_deref())?;
     let queue = crate::services::queue_service::AiQueueService::new(state.pool.clone());
     let job_payload: JsonValue = serde_json::json!({
         "profession": payload.profession,
         "cv_summary": payload.cv_summary.unwrap_or_default(),
         "skills": payload.skills.unwrap_or_default(),
         "num_questions": num_q,
+        "difficulty_distribution": payload.difficulty_distribution,
+        "language": language.tag(),
         "created_by_sub": "local_dev_user",
         "created_by_role": "admin",
     });
@@ -487,6 +954,7 @@ pub async fn enqueue_ai_job(
             payload.description,
             payload.duration_minutes,
             payload.passing_score,
+            org.org_id,
         )
         .await?;
     Ok((
@@ -515,33 +983,108 @@ pub async fn get_ai_job(
     Ok(Json(job))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/integration/ai-jobs/dead",
+    responses(
+        (status = 200, description = "Dead-lettered AI jobs with their payloads and reasons", body = Json<serde_json::Value>),
+    ),
+)]
+pub async fn list_dead_ai_jobs(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+) -> Result<impl IntoResponse> {
+    let queue = crate::services::queue_service::AiQueueService::new(state.pool.clone());
+    let jobs = queue.list_dead(org.org_id).await?;
+    Ok(Json(serde_json::json!({ "jobs": jobs })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/integration/ai-jobs/{id}/retry",
+    params(
+        ("id" = Uuid, Path, description = "AI Job ID")
+    ),
+    responses(
+        (status = 202, description = "Dead job re-queued", body = Json<serde_json::Value>),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job is not dead-lettered"),
+    ),
+)]
+pub async fn retry_ai_job(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let queue = crate::services::queue_service::AiQueueService::new(state.pool.clone());
+    queue.retry_dead(id, org.org_id).await?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "job_id": id, "status": "pending" })),
+    ))
+}
+
+#[derive(Debug, serde::Deserialize, Default)]
+#[serde(default)]
+pub struct AiUsageQuery {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub async fn get_ai_usage(
+    State(state): State<AppState>,
+    Query(q): Query<AiUsageQuery>,
+) -> Result<impl IntoResponse> {
+    let to = q.to.unwrap_or_else(chrono::Utc::now);
+    let from = q.from.unwrap_or_else(|| to - chrono::Duration::days(30));
+    if from >= to {
+        return Err(crate::error::Error::BadRequest("'from' must be before 'to'".into()));
+    }
+    let queue = crate::services::queue_service::AiQueueService::new(state.pool.clone());
+    let summary = queue.usage_summary(from, to).await?;
+    Ok(Json(summary))
+}
+
 #[axum::debug_handler]
 pub async fn generate_test_spec(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<crate::dto::integration_dto::SpecGenerateTestPayload>,
 ) -> Result<impl IntoResponse> {
     let cfg = crate::config::get_config();
     let num_q = payload.question_count.min(cfg.max_ai_questions);
     let skills = payload.topics.clone();
     let title = format!("{} Assessment", payload.position);
+    let distribution = payload
+        .difficulty
+        .as_deref()
+        .and_then(crate::models::question::Difficulty::parse)
+        .map(crate::services::ai_service::DifficultyDistribution::only)
+        .unwrap_or_default();
+    let language = crate::services::ai_service::parse_test_language(payload.language.as_deref())?;
 
     let ai_future = state.ai_service.generate_test(
         &payload.position,
         &skills,
         num_q,
+        &distribution,
+        language,
     );
-    let gen_output = match tokio::time::timeout(std::time::Duration::from_secs(300), ai_future).await
-    {
+    let timeout = Duration::from_secs(crate::config::get_config().ai_generation_timeout_secs);
+    let gen_output = match tokio::time::timeout(timeout, ai_future).await {
         Ok(Ok(v)) => v,
         _ => {
             tracing::warn!("AI generation failed or timed out for spec route");
             crate::services::ai_service::GenerationOutput {
                 questions: vec![],
                 logs: vec!["Timeout or fatal error".to_string()],
+                ..Default::default()
             }
         }
     };
 
+    record_ai_usage(&state, "generate_test_spec", &gen_output).await;
+
     let created_by = Uuid::parse_str("2cd84131-6e83-4c98-91ba-f9b9a5f0a06c").unwrap();
 
     let create_payload = crate::dto::integration_dto::CreateTestPayload {
@@ -558,11 +1101,30 @@ pub async fn generate_test_spec(
         test_type: Some("question_based".to_string()),
         presentation_themes: None,
         presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: Some(language.tag().to_string()),
+        reveal_correct_answers: None,
     };
     let test = state
         .test_service
-        .create_test(create_payload, created_by)
+        .create_test(create_payload, created_by, org.org_id)
         .await?;
+    if let Err(e) = state
+        .test_service
+        .record_generation_run(test.id, None, &gen_output, num_q, gen_output.questions.len(), 0)
+        .await
+    {
+        tracing::warn!("Failed to store generation log for test {}: {:?}", test.id, e);
+    }
 
     let resp = json!({
         "id": test.id,
@@ -582,10 +1144,10 @@ pub async fn send_message(
     
     let candidate = if let Some(cid) = payload.candidate_id {
         state.candidate_service.get_candidate(cid).await?
-            .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?
+            .ok_or_else(crate::error::Error::candidate_not_found)?
     } else if let Some(tid) = payload.telegram_id {
         state.candidate_service.get_by_telegram_id(tid).await?
-            .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?
+            .ok_or_else(crate::error::Error::candidate_not_found)?
     } else {
         return Err(crate::error::Error::BadRequest("Either candidate_id or telegram_id must be provided".into()));
     };
@@ -594,33 +1156,19 @@ pub async fn send_message(
         crate::error::Error::BadRequest("Candidate has no associated Telegram ID".into())
     })?;
 
-    let config = crate::config::get_config();
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
-    let client = reqwest::Client::new();
-    
-    let telegram_body = json!({
-        "chat_id": telegram_id,
-        "text": payload.text,
-    });
-
-    let resp = client.post(&url)
-        .json(&telegram_body)
-        .send()
-        .await
-        .map_err(|e| crate::error::Error::Internal(format!("Failed to send to Telegram: {}", e)))?;
-
-    if !resp.status().is_success() {
-        let err_text = resp.text().await.unwrap_or_default();
-        return Err(crate::error::Error::Internal(format!("Telegram API error: {}", err_text)));
-    }
+    crate::routes::telegram::deliver_to_chat(telegram_id, candidate.telegram_thread_id, &payload.text, payload.attachment_url.as_deref()).await?;
 
     let create_msg = crate::models::message::CreateMessage {
         candidate_id: candidate.id,
         telegram_id,
         direction: "outbound".to_string(),
         text: payload.text.clone(),
+        attachment_type: payload.attachment_url.as_ref().map(|_| "document".to_string()),
+        attachment_path: payload.attachment_url,
     };
-    let _ = state.message_service.create(create_msg).await;
+    if let Ok(message) = state.message_service.create(create_msg).await {
+        let _ = state.message_events.send((&message).into());
+    }
 
     Ok(Json(json!({ "status": "sent" })))
 }
@@ -629,129 +1177,202 @@ pub async fn send_message(
 pub async fn get_chat_messages(
     State(state): State<AppState>,
     Path(candidate_id): Path<Uuid>,
+    Query(query): Query<MessageQuery>,
 ) -> Result<impl IntoResponse> {
-    let messages = state.message_service.get_by_candidate(candidate_id).await?;
+    let messages = state.message_service.get_by_candidate(candidate_id, &query).await?;
     let _ = state.message_service.mark_as_read(candidate_id).await;
     Ok(Json(messages))
 }
 
+const CHAT_EVENTS_KEEPALIVE: Duration = Duration::from_secs(15);
+const CHAT_EVENTS_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// SSE feed of new messages for one candidate's chat. The stream ends after
+/// a minute without messages; clients are expected to reconnect.
+pub async fn chat_events(
+    State(state): State<AppState>,
+    Path(candidate_id): Path<Uuid>,
+) -> Sse<impl futures_util::Stream<Item = std::result::Result<Event, Infallible>>> {
+    let rx = state.message_events.subscribe();
+    let deadline = tokio::time::Instant::now() + CHAT_EVENTS_IDLE_TIMEOUT;
+
+    let stream = futures_util::stream::unfold((rx, deadline), move |(mut rx, deadline)| async move {
+        loop {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Ok(event)) if event.candidate_id == candidate_id => {
+                    let sse_event = Event::default()
+                        .event("message")
+                        .json_data(&event)
+                        .unwrap_or_else(|_| Event::default().event("message"));
+                    let next_deadline = tokio::time::Instant::now() + CHAT_EVENTS_IDLE_TIMEOUT;
+                    return Some((Ok(sse_event), (rx, next_deadline)));
+                }
+                Ok(Ok(_)) => continue,
+                Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                    tracing::warn!("Chat event stream for {} lagged, skipped {} events", candidate_id, skipped);
+                    continue;
+                }
+                Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(CHAT_EVENTS_KEEPALIVE))
+}
+
 #[axum::debug_handler]
 pub async fn get_unread_count(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
-    let count = state.message_service.total_unread_count().await?;
+    let count = state.message_service.total_unread_count(org.org_id).await?;
     Ok(Json(json!({ "unread_count": count })))
 }
 
 pub async fn sync_candidate_statuses(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(query): Query<CandidateStatusSyncQuery>,
 ) -> Result<impl IntoResponse> {
-    let statuses = sqlx::query_as!(
-        CandidateStatusSync,
-        r#"
-        SELECT DISTINCT ON (c.id)
-            c.id,
-            c.telegram_id::text as external_id,
-            c.name as "name!",
-            c.email as "email!",
-            COALESCE(ta.status, 'pending') as "status!",
-            COALESCE(ta.updated_at, c.updated_at, NOW()) as "last_updated!"
-        FROM candidates c
-        LEFT JOIN test_attempts ta ON c.email = ta.candidate_email
-        ORDER BY c.id, ta.updated_at DESC NULLS LAST
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await?;
-
-    Ok(Json(statuses))
+    let page = state.candidate_service.sync_statuses(&query, org.org_id).await?;
+    Ok(Json(page))
 }
 
 pub async fn get_dashboard_stats(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(range): Query<DashboardRange>,
 ) -> Result<impl IntoResponse> {
-    let candidates_status = state.candidate_service.get_status_counts().await?;
-    let total_candidates: i64 = candidates_status.values().sum();
-    let unread_messages = state.message_service.total_unread_count().await?;
-    let tests_list = state.test_service.list_tests(
-        1, 
-        1, 
-        Some(crate::services::test_service::TestFilter {
-            is_active: Some(true),
-            created_by: None,
-            search: None,
-        })
-    ).await?;
-    let active_tests = tests_list.total;
-    let internal_active_vacancies = match state.vacancy_service.list_published(1000).await {
-        Ok(v) => v.len() as i64,
-        Err(e) => {
-            tracing::error!("Failed to fetch vacancies for dashboard: {:?}", e);
-            0
-        }
-    };
-
-    let external_vacancies = match state.koinotinav_service.fetch_vacancies().await {
-        Ok(v) => v.len() as i64,
-        Err(e) => {
-            tracing::error!("Failed to fetch external vacancies for dashboard: {:?}", e);
-            0
-        }
-    };
-
-    let total_active_vacancies = internal_active_vacancies + external_vacancies;
-
-    let candidates_history = state.candidate_service.get_history_counts().await?;
-    let attempts_status = state.attempt_service.get_status_distribution().await?;
+    let snapshot = state.dashboard_service.get_stats(org.org_id, range).await?;
+    Ok(Json(DashboardStats::from(snapshot)))
+}
 
-    let stats = DashboardStats {
-        total_candidates,
-        unread_messages,
-        active_tests,
-        active_vacancies: total_active_vacancies,
-        candidates_by_status: candidates_status,
-        candidates_history,
-        attempts_status,
+pub async fn get_dashboard_history(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(query): Query<DashboardHistoryQuery>,
+) -> Result<impl IntoResponse> {
+    let range = DashboardRange {
+        from: query.from,
+        to: query.to,
     };
-
-    Ok(Json(stats))
+    range.validate()?;
+    let (from, to) = range.history_window(chrono::Utc::now());
+    let points = state
+        .dashboard_snapshot_service
+        .query(org.org_id, from, to, query.granularity.unwrap_or_default())
+        .await?;
+    Ok(Json(points))
 }
 
 pub async fn delete_test_invite(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    svc.delete_attempt(id).await?;
+    svc.delete_attempt(id, org.org_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Debug, serde::Deserialize, Default)]
+pub struct ListCandidatesQuery {
+    /// Comma-separated skill tags, e.g. `Rust,PostgreSQL`; matches any of them.
+    pub skills: Option<String>,
+    /// Comma-separated skills matched against the extracted CV profile.
+    pub skill: Option<String>,
+    #[serde(default)]
+    pub sort: crate::services::candidate_service::CandidateSort,
+}
+
+impl ListCandidatesQuery {
+    pub fn skill_list(&self) -> Vec<String> {
+        split_tags(self.skills.as_deref())
+    }
+
+    pub fn profile_skill_list(&self) -> Vec<String> {
+        split_tags(self.skill.as_deref())
+    }
+}
+
+fn split_tags(raw: Option<&str>) -> Vec<String> {
+    raw.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 pub async fn list_candidates(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(query): Query<ListCandidatesQuery>,
 ) -> Result<impl IntoResponse> {
-    let candidates = state.candidate_service.list_candidates().await?;
+    let mut candidates = state
+        .candidate_service
+        .list_filtered(&query.skill_list(), &query.profile_skill_list(), org.org_id)
+        .await?;
+    query.sort.apply(&mut candidates);
     Ok(Json(candidates))
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct StaleCandidatesQuery {
+    pub days: Option<i64>,
+    pub status: Option<String>,
+}
+
+/// GET /api/integration/candidates/stale — candidates with no activity for
+/// `days` days (default `STALE_CANDIDATE_DAYS`), stalest first.
+pub async fn list_stale_candidates(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(query): Query<StaleCandidatesQuery>,
+) -> Result<impl IntoResponse> {
+    let days = query
+        .days
+        .unwrap_or(crate::services::candidate_service::STALE_CANDIDATE_DAYS);
+    if !(1..=3650).contains(&days) {
+        return Err(crate::error::Error::BadRequest("days must be between 1 and 3650".into()));
+    }
+    let status = query.status.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let candidates = state
+        .candidate_service
+        .list_stale(days, status, org.org_id)
+        .await?;
+    Ok(Json(json!({
+        "days": days,
+        "total": candidates.len(),
+        "candidates": candidates,
+    })))
+}
+
 #[axum::debug_handler]
 pub async fn grade_presentation(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(attempt_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<GradePresentationPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
 
-    let user = sqlx::query!("SELECT id FROM users LIMIT 1")
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(|e| crate::error::Error::Internal(format!("Failed to fetch user: {}", e)))?;
-        
-    let graded_by = match user {
-        Some(u) => u.id,
-        None => Uuid::nil(),
+    let reviewer = reviewer_id(&headers);
+    let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
+    svc.ensure_can_grade(attempt_id, org.org_id, reviewer, payload.force).await?;
+
+    let graded_by = match reviewer {
+        Some(id) => id,
+        None => {
+            let user = sqlx::query!("SELECT id FROM users LIMIT 1")
+                .fetch_optional(&state.pool)
+                .await
+                .map_err(|e| crate::error::Error::Internal(format!("Failed to fetch user: {}", e)))?;
+            user.map(|u| u.id).unwrap_or_else(Uuid::nil)
+        }
     };
 
-    let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
     let attempt = svc
         .grade_presentation(attempt_id, payload.grade, payload.comment.clone(), graded_by)
         .await?;
@@ -760,32 +1381,32 @@ pub async fn grade_presentation(
         let test = state.test_service.get_test_by_id(attempt.test_id).await?;
         let config = crate::config::get_config();
         
-        let message_text = format!(
-            "Ваша презентация по тесту \"{}\" проверена!\n\nОценка: {}/100\nКомментарий: {}\n\nВы можете посмотреть подробности и оценку в профиле, нажав кнопку 'История активности'.\n\nОкончательное решение мы вам объявим немного позже.",
-            test.title,
-            payload.grade,
-            payload.comment.unwrap_or_else(|| "Без комментария".to_string())
-        );
+        let lang = attempt.language.as_str();
+        let comment = payload
+            .comment
+            .unwrap_or_else(|| strings::text(lang, "no_comment").to_string());
+        let message_text = strings::render(lang, "presentation_graded", &[
+            ("title", &test.title),
+            ("grade", &payload.grade.to_string()),
+            ("comment", &comment),
+        ]);
 
         let reply_markup = serde_json::json!({
             "inline_keyboard": [[
                 {
-                    "text": "Профиль",
+                    "text": strings::text(lang, "profile_button"),
                     "web_app": { "url": config.webapp_url }
                 }
             ]]
         });
 
-        let telegram_body = serde_json::json!({
-            "chat_id": telegram_id,
-            "text": message_text,
-            "reply_markup": reply_markup,
-        });
-
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
-        let client = reqwest::Client::new();
+        let thread_id = state.candidate_service.telegram_thread_id(Some(telegram_id)).await;
+        let delivery = TelegramDeliveryService::from_config(state.pool.clone());
         tokio::spawn(async move {
-            if let Err(e) = client.post(&url).json(&telegram_body).send().await {
+            if let Err(e) = delivery
+                .send(telegram_id, thread_id, &message_text, Some(reply_markup))
+                .await
+            {
                 tracing::warn!("Failed to send grading notification: {}", e);
             }
         });
@@ -797,12 +1418,17 @@ pub async fn grade_presentation(
 #[axum::debug_handler]
 pub async fn grade_test_answer(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(attempt_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<crate::dto::integration_dto::GradeAnswerPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let attempt = svc.grade_answer(attempt_id, payload.question_id, payload.is_correct).await?;
+    svc.ensure_can_grade(attempt_id, org.org_id, reviewer_id(&headers), payload.force).await?;
+    let attempt = svc
+        .grade_answer(attempt_id, payload.question_id, payload.is_correct, payload.comment.as_deref())
+        .await?;
 
     if attempt.status == "completed" {
          if let Some(telegram_id) = attempt.candidate_telegram_id {
@@ -812,32 +1438,40 @@ pub async fn grade_test_answer(
                 let test = state_clone.test_service.get_test_by_id(attempt_clone.test_id).await;
                  if let Ok(test) = test {
                     let config = crate::config::get_config();
-                    let message_text = format!(
-                        "Ваш тест \"{}\" проверен!\n\nРезультат: {}%\n\nВы можете посмотреть подробности и оценку в профиле, нажав кнопку 'История активности'.\n\nОкончательное решение мы вам объявим немного позже.",
-                        test.title,
-                        attempt_clone.percentage.unwrap_or_default()
-                    );
+                    let lang = attempt_clone.language.as_str();
+                    let message_text = strings::render(lang, "test_graded", &[
+                        ("title", &test.title),
+                        ("percentage", &attempt_clone.percentage.unwrap_or_default().to_string()),
+                    ]);
 
                     let reply_markup = serde_json::json!({
                         "inline_keyboard": [[
                             {
-                                "text": "Профиль",
+                                "text": strings::text(lang, "profile_button"),
                                 "web_app": { "url": config.webapp_url }
                             }
                         ]]
                     });
 
-                    let telegram_body = serde_json::json!({
-                        "chat_id": telegram_id,
-                        "text": message_text,
-                        "reply_markup": reply_markup,
-                    });
-
-                    let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
-                    let client = reqwest::Client::new();
-                     if let Err(e) = client.post(&url).json(&telegram_body).send().await {
-                         tracing::warn!("Failed to send grading notification: {}", e);
-                     }
+                    let thread_id = state_clone.candidate_service.telegram_thread_id(Some(telegram_id)).await;
+                    let delivery = TelegramDeliveryService::from_config(state_clone.pool.clone());
+                    let sent = delivery
+                        .send(telegram_id, thread_id, &message_text, Some(reply_markup))
+                        .await;
+                    if let Err(e) = &sent {
+                        tracing::warn!("Failed to send grading notification: {}", e);
+                    }
+
+                    let blocked = matches!(&sent, Err(e) if e.code() == "telegram_unreachable");
+                    if test.share_results_details && !blocked {
+                        let summary = results_summary(&test, &attempt_clone, test.show_results_immediately.unwrap_or(false));
+                        for chunk in crate::services::telegram_channel_service::split_message(&summary) {
+                            if let Err(e) = delivery.send(telegram_id, thread_id, &chunk, None).await {
+                                tracing::warn!("Failed to send results breakdown: {}", e);
+                                break;
+                            }
+                        }
+                    }
                  }
             });
         }
@@ -846,6 +1480,122 @@ pub async fn grade_test_answer(
     Ok(Json(attempt))
 }
 
+fn results_summary(
+    test: &crate::models::test::Test,
+    attempt: &crate::models::test_attempt::TestAttempt,
+    include_explanations: bool,
+) -> String {
+    let graded: Vec<serde_json::Value> = attempt
+        .graded_answers
+        .clone()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    crate::services::grading_service::GradingService::results_summary(
+        &test.title,
+        &graded,
+        &attempt.questions_snapshot,
+        include_explanations,
+    )
+}
+
+/// HR view of the breakdown candidates receive, always with explanations.
+#[axum::debug_handler]
+pub async fn get_test_attempt_summary(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(attempt_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let attempt = state.attempt_service.get_attempt_in_org(attempt_id, org.org_id).await?;
+    if attempt.graded_answers.is_none() {
+        return Err(crate::error::Error::coded(
+            StatusCode::CONFLICT,
+            "attempt_not_graded",
+            "Attempt has not been graded yet",
+        ));
+    }
+    let test = state.test_service.get_test_by_id(attempt.test_id).await?;
+    let summary = results_summary(&test, &attempt, true);
+    Ok(Json(json!({
+        "attempt_id": attempt.id,
+        "status": attempt.status,
+        "summary": summary,
+    })))
+}
+
+#[axum::debug_handler]
+pub async fn extend_test_attempt(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(attempt_id): Path<Uuid>,
+    Json(payload): Json<ExtendAttemptPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let previous = state
+        .attempt_service
+        .get_attempt_in_org(attempt_id, org.org_id)
+        .await?;
+    let attempt = state
+        .attempt_service
+        .extend_expiry(attempt_id, payload.extra_minutes)
+        .await?;
+
+    // The extension is already saved; a failed audit write must not turn it
+    // into an error the caller would retry.
+    let audit = crate::services::audit_service::AuditService::new(state.pool.clone());
+    if let Err(e) = audit
+        .log(
+            None,
+            "extend_attempt",
+            "test_attempt",
+            attempt.id,
+            Some(json!({
+                "extra_minutes": payload.extra_minutes,
+                "previous_expires_at": previous.expires_at,
+                "new_expires_at": attempt.expires_at,
+            })),
+            None,
+            None,
+        )
+        .await
+    {
+        tracing::warn!("Failed to write audit log for extend_attempt {}: {:?}", attempt.id, e);
+    }
+
+    if let Some(telegram_id) = attempt.candidate_telegram_id {
+        let test = state.test_service.get_test_by_id(attempt.test_id).await?;
+        let config = crate::config::get_config();
+
+        let timezone = state.candidate_service.timezone_for_telegram(Some(telegram_id)).await;
+        let message_text = format!(
+            "Время на прохождение теста \"{}\" продлено.\n\nНовый срок: {}",
+            test.title,
+            crate::utils::time::format_deadline(attempt.expires_at, timezone.as_deref())
+        );
+
+        let reply_markup = serde_json::json!({
+            "inline_keyboard": [[
+                {
+                    "text": "Профиль",
+                    "web_app": { "url": config.webapp_url }
+                }
+            ]]
+        });
+
+        let thread_id = state.candidate_service.telegram_thread_id(Some(telegram_id)).await;
+        let delivery = TelegramDeliveryService::from_config(state.pool.clone());
+        tokio::spawn(async move {
+            if let Err(e) = delivery
+                .send(telegram_id, thread_id, &message_text, Some(reply_markup))
+                .await
+            {
+                tracing::warn!("Failed to send extension notification: {}", e);
+            }
+        });
+    }
+
+    Ok(Json(attempt))
+}
+
 #[derive(serde::Deserialize)]
 pub struct PollQuery {
     #[serde(default = "default_since")]
@@ -878,7 +1628,7 @@ pub async fn poll_notifications(
     .count.unwrap_or(0);
 
     let total_needs_review_attempts = sqlx::query!(
-        "SELECT COUNT(*) as count FROM test_attempts WHERE status = 'needs_review'"
+        "SELECT COUNT(*) as count FROM test_attempts WHERE status = 'needs_review' AND NOT is_preview"
     )
     .fetch_one(&state.pool)
     .await
@@ -889,7 +1639,8 @@ pub async fn poll_notifications(
         r#"
         SELECT id, candidate_name, status, started_at, completed_at, test_id 
         FROM test_attempts 
-        WHERE (started_at > $1) OR (completed_at > $1) OR (status = 'needs_review' AND updated_at > $1)
+        WHERE NOT is_preview
+          AND ((started_at > $1) OR (completed_at > $1) OR (status = 'needs_review' AND updated_at > $1))
         ORDER BY updated_at DESC
         "#,
         query.since
@@ -924,22 +1675,91 @@ pub async fn poll_notifications(
 
 pub async fn list_all_tests(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
-    let result = state.test_service.list_tests(1, 1000, None).await?;
+    let result = state
+        .test_service
+        .list_tests(1, 1000, None, None, org.org_id)
+        .await?;
     Ok(Json(result.tests))
 }
 
 
 
+#[derive(Debug, serde::Deserialize, Default)]
+#[serde(default)]
+pub struct ReviewQueueQuery {
+    /// `me`, `unassigned` or `all` (default).
+    pub assigned: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Signed-in user grading attempts, taken from the bearer token.
+fn reviewer_id(headers: &axum::http::HeaderMap) -> Option<Uuid> {
+    crate::middleware::auth::bearer_claims(headers).and_then(|claims| Uuid::parse_str(&claims.sub).ok())
+}
+
+fn require_reviewer(headers: &axum::http::HeaderMap) -> Result<Uuid> {
+    reviewer_id(headers)
+        .ok_or_else(|| crate::error::Error::Unauthorized("Sign in to review attempts".to_string()))
+}
+
 pub async fn list_attempts_for_review(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    headers: axum::http::HeaderMap,
+    Query(q): Query<ReviewQueueQuery>,
 ) -> Result<impl IntoResponse> {
-    let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let (items, _total) = svc
-        .list_attempts(None, None, Some("needs_review".to_string()), 1, 100)
+    use crate::services::attempt_service::ReviewAssignment;
+
+    let assignment = match q.assigned.as_deref().unwrap_or("all") {
+        "me" => ReviewAssignment::Mine(require_reviewer(&headers)?),
+        "unassigned" => ReviewAssignment::Unassigned,
+        "all" => ReviewAssignment::All,
+        other => {
+            return Err(crate::error::Error::BadRequest(format!(
+                "Unknown assigned filter '{}'; expected me, unassigned or all",
+                other
+            )))
+        }
+    };
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(20).clamp(1, 100);
+    let (items, total) = state
+        .attempt_service
+        .list_review_queue(assignment, org.org_id, page, limit)
         .await?;
-    
-    Ok(Json(items))
+    let total_pages = ((total as f64) / (limit as f64)).ceil() as i64;
+    Ok(Json(json!({
+        "items": items,
+        "total": total,
+        "page": page,
+        "limit": limit,
+        "total_pages": total_pages,
+    })))
+}
+
+pub async fn claim_attempt_for_review(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(attempt_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+) -> Result<impl IntoResponse> {
+    let reviewer = require_reviewer(&headers)?;
+    let attempt = state.attempt_service.claim_for_review(attempt_id, org.org_id, reviewer).await?;
+    Ok(Json(attempt))
+}
+
+pub async fn release_attempt_review(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(attempt_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+) -> Result<impl IntoResponse> {
+    let reviewer = require_reviewer(&headers)?;
+    let attempt = state.attempt_service.release_review(attempt_id, org.org_id, reviewer).await?;
+    Ok(Json(attempt))
 }
 
 
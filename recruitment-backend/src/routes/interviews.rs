@@ -0,0 +1,110 @@
+use crate::{
+    error::{Error, Result},
+    services::{
+        calendar_service::{
+            add_to_calendar_button, generate_ics, google_calendar_link, interview_message,
+            CalendarEvent,
+        },
+        telegram_delivery_service::TelegramDeliveryService,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ScheduleInterviewPayload {
+    pub datetime: DateTime<Utc>,
+    #[validate(range(min = 5, max = 480, message = "duration_minutes must be 5-480"))]
+    pub duration_minutes: i32,
+    #[validate(length(min = 1, max = 500, message = "location is required"))]
+    pub location: String,
+    #[validate(length(min = 1, max = 255, message = "interviewer_name is required"))]
+    pub interviewer_name: String,
+}
+
+/// POST /api/integration/candidates/:id/schedule-interview — returns the
+/// interview as an `.ics` file and sends the candidate an "add to calendar"
+/// link on Telegram.
+pub async fn schedule_interview(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ScheduleInterviewPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let now = Utc::now();
+    if payload.datetime <= now {
+        return Err(Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "interview_in_past",
+            "Interview time must be in the future",
+        ));
+    }
+    let candidate = state
+        .candidate_service
+        .get_candidate(id)
+        .await?
+        .ok_or_else(Error::candidate_not_found)?;
+
+    let event = CalendarEvent {
+        uid: format!(
+            "interview-{}-{}@recruitment",
+            candidate.id,
+            payload.datetime.timestamp()
+        ),
+        title: "Собеседование".to_string(),
+        description: format!(
+            "Кандидат: {}\nИнтервьюер: {}",
+            candidate.name_or_empty(), payload.interviewer_name
+        ),
+        location: payload.location.trim().to_string(),
+        start: payload.datetime,
+        duration_minutes: payload.duration_minutes,
+        created_at: now,
+    };
+
+    match candidate.telegram_id {
+        Some(telegram_id) => {
+            let text = interview_message(&event, payload.interviewer_name.trim());
+            let markup = add_to_calendar_button(&google_calendar_link(&event));
+            let thread_id = candidate.telegram_thread_id;
+            let delivery = TelegramDeliveryService::from_config(state.pool.clone());
+            tokio::spawn(async move {
+                if let Err(e) = delivery
+                    .send(telegram_id, thread_id, &text, Some(markup))
+                    .await
+                {
+                    tracing::warn!("Failed to send interview invitation to {}: {}", id, e);
+                }
+            });
+        }
+        None => tracing::info!(
+            "Candidate {} has no Telegram ID; interview invitation not sent",
+            id
+        ),
+    }
+
+    let disposition = format!(
+        "attachment; filename=\"interview_{}.ics\"",
+        payload.datetime.format("%Y%m%d_%H%M")
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/calendar; charset=utf-8".to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        generate_ics(event),
+    ))
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     response::{IntoResponse, Json},
 };
 
@@ -8,15 +8,33 @@ use crate::{
     AppState,
 };
 
+#[derive(Debug, serde::Deserialize, Default)]
+#[serde(default)]
+pub struct ListExternalVacanciesQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
 #[axum::debug_handler]
 pub async fn list_external_vacancies(
     State(state): State<AppState>,
+    Query(query): Query<ListExternalVacanciesQuery>,
 ) -> Result<impl IntoResponse> {
-    let vacancies = state.koinotinav_service.fetch_vacancies().await?;
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let (items, total) = state
+        .koinotinav_service
+        .fetch_vacancies_page(page, per_page)
+        .await?;
     let companies = state.koinotinav_service.fetch_companies().await?;
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i64;
 
     Ok(Json(serde_json::json!({
-        "vacancies": vacancies,
+        "items": items,
+        "total": total,
+        "page": page,
+        "per_page": per_page,
+        "total_pages": total_pages,
         "companies": companies
     })))
 }
@@ -0,0 +1,18 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::{middleware::metrics::MetricsAccess, AppState};
+
+pub async fn metrics(
+    _access: MetricsAccess,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics_handle.render(),
+    )
+}
@@ -3,6 +3,7 @@ pub mod external_vacancy;
 pub mod export;
 pub mod health;
 pub mod integration;
+pub mod metrics;
 pub mod public;
 pub mod telegram;
 pub mod candidate_routes;
@@ -11,3 +12,9 @@ pub mod webhook;
 pub mod koinotinav;
 pub mod onef;
 pub mod responses;
+pub mod referrals;
+pub mod webhook_subscriptions;
+pub mod reports;
+pub mod interviews;
+pub mod broadcasts;
+pub mod organizations;
@@ -1,9 +1,16 @@
 use crate::{
     error::Result,
+    middleware::organization::OrganizationContext,
+    models::candidate::CandidateProfileData,
+    services::{
+        dashboard_service::DashboardRange, message_service::MessageQuery,
+        onef_service::onef_cv_download_url, status_pipeline_service::STATUS_WORKFLOW,
+    },
+    utils::{html::sanitize_to_text, strings},
     AppState,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
@@ -16,11 +23,14 @@ use uuid::Uuid;
 pub struct OneFSendMessageRequest {
     pub candidate_id: Uuid,
     pub text: String,
+    pub attachment_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct OneFUpdateStatusRequest {
     pub status: String,
+    #[serde(default)]
+    pub allow_override: bool,
 }
 #[derive(Debug, Deserialize)]
 pub struct OneFPipelineAdviceRequest {
@@ -72,6 +82,8 @@ pub struct OneFChatMessage {
     pub text: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub is_read: bool,
+    pub attachment_path: Option<String>,
+    pub attachment_type: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -80,6 +92,9 @@ pub struct OneFDashboardStats {
     pub candidates_new_today: i64,
     pub active_vacancies: i64,
     pub test_attempts_pending: i64,
+    pub test_attempts_by_source: std::collections::HashMap<String, i64>,
+    pub telegram_unreachable_candidates: i64,
+    pub stale_candidates: i64,
     pub recruitment_funnel: RecruitmentFunnel,
 }
 
@@ -96,14 +111,23 @@ pub struct RecruitmentFunnel {
 pub struct OneFCandidateResponse {
     pub id: Uuid,
     pub telegram_id: Option<i64>,
+    pub telegram_thread_id: Option<i64>,
+    /// Blocked the bot; Telegram messages to them are paused.
+    pub telegram_unreachable: bool,
     pub name: String,
     pub email: String,
     pub phone: Option<String>,
     pub cv_url: Option<String>,
+    /// Same signed download link 1F receives with new applications.
+    pub cv_download_url: Option<String>,
+    pub photo_url: Option<String>,
+    pub profile: Option<CandidateProfileData>,
     pub status: String,
     pub ai_rating: Option<i32>,
     pub ai_comment: Option<String>,
+    pub extracted_profile: Option<serde_json::Value>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_activity_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 pub async fn send_message(
@@ -111,40 +135,31 @@ pub async fn send_message(
     Json(payload): Json<OneFSendMessageRequest>,
 ) -> Result<impl IntoResponse> {
     let candidate = state.candidate_service.get_candidate(payload.candidate_id).await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
 
     let telegram_id = candidate.telegram_id.ok_or_else(|| {
         crate::error::Error::BadRequest("Candidate has no linked Telegram account".into())
     })?;
 
-    let config = crate::config::get_config();
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
-    let client = reqwest::Client::new();
-    
-    let telegram_body = json!({
-        "chat_id": telegram_id,
-        "text": payload.text,
-    });
-
-    let resp = client.post(&url)
-        .json(&telegram_body)
-        .send()
-        .await
-        .map_err(|e| crate::error::Error::Internal(format!("Telegram request failed: {}", e)))?;
-
-    if !resp.status().is_success() {
-        let err_text = resp.text().await.unwrap_or_default();
-        return Err(crate::error::Error::Internal(format!("Telegram API error: {}", err_text)));
+    if let Some(attachment_url) = &payload.attachment_url {
+        url::Url::parse(attachment_url).map_err(|_| {
+            crate::error::Error::BadRequest("attachment_url must be a valid URL".into())
+        })?;
     }
 
+    crate::routes::telegram::deliver_to_chat(telegram_id, candidate.telegram_thread_id, &payload.text, payload.attachment_url.as_deref()).await?;
+
     let create_msg = crate::models::message::CreateMessage {
         candidate_id: candidate.id,
         telegram_id,
         direction: "outbound".to_string(),
         text: payload.text,
+        attachment_type: payload.attachment_url.as_ref().map(|_| "document".to_string()),
+        attachment_path: payload.attachment_url,
     };
     
-    let _ = state.message_service.create(create_msg).await?;
+    let message = state.message_service.create(create_msg).await?;
+    let _ = state.message_events.send((&message).into());
 
     Ok(StatusCode::OK)
 }
@@ -152,8 +167,9 @@ pub async fn send_message(
 pub async fn get_chat_history(
     State(state): State<AppState>,
     Path(candidate_id): Path<Uuid>,
+    Query(query): Query<MessageQuery>,
 ) -> Result<impl IntoResponse> {
-    let messages = state.message_service.get_by_candidate(candidate_id).await?;
+    let messages = state.message_service.get_by_candidate(candidate_id, &query).await?;
     
     let _ = state.message_service.mark_as_read(candidate_id).await;
     
@@ -163,6 +179,8 @@ pub async fn get_chat_history(
         text: m.text,
         created_at: m.created_at,
         is_read: m.read_at.is_some(),
+        attachment_path: m.attachment_path,
+        attachment_type: m.attachment_type,
     }).collect();
 
     Ok(Json(onef_messages))
@@ -170,29 +188,29 @@ pub async fn get_chat_history(
 
 pub async fn get_unread_count(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
-    let count = state.message_service.total_unread_count().await?;
+    let count = state.message_service.total_unread_count(org.org_id).await?;
     Ok(Json(json!({ "unread_count": count })))
 }
 
 pub async fn get_dashboard_stats(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(range): Query<DashboardRange>,
 ) -> Result<impl IntoResponse> {
-    
-    let total_candidates_map = state.candidate_service.get_status_counts().await?;
-    let total_candidates: i64 = total_candidates_map.values().sum();
-    let history = state.candidate_service.get_history_counts().await?;
+    let snapshot = state.dashboard_service.get_stats(org.org_id, range).await?;
+    let total_candidates_map = &snapshot.candidates_by_status;
+    let total_candidates = snapshot.total_candidates;
     let today_str = chrono::Utc::now().format("%Y-%m-%d").to_string();
-    let candidates_new_today = history.iter()
+    let candidates_new_today = snapshot.candidates_history.iter()
         .find(|(date, _)| *date == today_str)
         .map(|(_, count)| *count)
         .unwrap_or(0);
 
-    let internal_vacancies = state.vacancy_service.list_published(50).await?.len() as i64;
-    let external_vacancies = state.koinotinav_service.fetch_vacancies().await.map(|v| v.len() as i64).unwrap_or(0);
-    let active_vacancies = internal_vacancies + external_vacancies;
-    
-    let attempts_status = state.attempt_service.get_status_distribution().await?;
+    let active_vacancies = snapshot.active_vacancies();
+
+    let attempts_status = &snapshot.attempts_status;
     let test_attempts_pending = *attempts_status.get("pending").unwrap_or(&0);
     let test_completed = *attempts_status.get("completed").unwrap_or(&0) + *attempts_status.get("passed").unwrap_or(&0) + *attempts_status.get("failed").unwrap_or(&0);
 
@@ -209,6 +227,9 @@ pub async fn get_dashboard_stats(
         candidates_new_today,
         active_vacancies,
         test_attempts_pending,
+        test_attempts_by_source: snapshot.attempts_by_source.clone(),
+        telegram_unreachable_candidates: snapshot.telegram_unreachable_candidates,
+        stale_candidates: snapshot.stale_candidates,
         recruitment_funnel: funnel,
     };
 
@@ -217,11 +238,39 @@ pub async fn get_dashboard_stats(
 
 pub async fn update_candidate_status(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(candidate_id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<OneFUpdateStatusRequest>,
 ) -> Result<impl IntoResponse> {
-    let _updated = state.candidate_service.update_status(candidate_id, payload.status.clone()).await?;
-    
+    crate::routes::candidate_routes::check_status_override(&headers, payload.allow_override)?;
+    let previous = state
+        .candidate_service
+        .get_candidate_in_org(candidate_id, org.org_id)
+        .await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let updated = state
+        .candidate_service
+        .update_status_in_org(candidate_id, org.org_id, payload.status.clone(), payload.allow_override)
+        .await?;
+
+    if previous.status != updated.status {
+        let event = crate::routes::candidate_routes::candidate_status_changed_payload(
+            candidate_id,
+            &previous.status,
+            &updated.status,
+            updated.vacancy_id,
+        );
+        crate::routes::candidate_routes::enqueue_candidate_event(&state, event).await;
+    }
+
+    if updated.status == "accepted" {
+        let vacancy_id = state.candidate_service.get_candidate_applications(candidate_id).await
+            .ok()
+            .and_then(|apps| apps.first().map(|a| a.vacancy_id))
+            .or(updated.vacancy_id);
+        crate::routes::candidate_routes::spawn_onboarding_checklist(state.clone(), updated, vacancy_id);
+    }
 
     Ok(Json(json!({ 
         "id": candidate_id, 
@@ -254,22 +303,30 @@ pub async fn pipeline_advise(
 
 pub async fn get_candidate(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(candidate_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let candidate = state.candidate_service.get_candidate(candidate_id).await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
+    let candidate = state.candidate_service.get_candidate_in_org(candidate_id, org.org_id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
 
     let response = OneFCandidateResponse {
+        profile: candidate.profile(),
         id: candidate.id,
         telegram_id: candidate.telegram_id,
-        name: candidate.name,
-        email: candidate.email,
+        telegram_thread_id: candidate.telegram_thread_id,
+        telegram_unreachable: candidate.telegram_unreachable,
+        name: candidate.name.unwrap_or_default(),
+        email: candidate.email.unwrap_or_default(),
         phone: candidate.phone,
+        cv_download_url: candidate.cv_url.as_deref().map(onef_cv_download_url),
         cv_url: candidate.cv_url,
+        photo_url: candidate.photo_url,
         status: candidate.status,
         ai_rating: candidate.ai_rating,
         ai_comment: candidate.ai_comment,
+        extracted_profile: candidate.extracted_profile,
         created_at: candidate.created_at,
+        last_activity_at: candidate.last_activity_at,
     };
 
     Ok(Json(response))
@@ -277,13 +334,20 @@ pub async fn get_candidate(
 
 pub async fn get_candidate_attempts(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(candidate_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     let candidate = state.candidate_service.get_candidate(candidate_id).await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
 
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let (items, total) = svc.list_attempts(None, Some(candidate.email), None, 1, 100).await?;
+    // An erased candidate has no email, and no filter would list everyone's.
+    let Some(email) = candidate.email else {
+        return Ok(Json(json!({ "items": [], "total": 0 })));
+    };
+    let (items, total) = svc
+        .list_attempts(None, Some(email), None, None, 1, 100, org.org_id)
+        .await?;
 
     Ok(Json(json!({
         "items": items,
@@ -309,20 +373,33 @@ pub async fn get_test_attempt(
 
 pub async fn list_candidates(
     State(state): State<AppState>,
+    org: OrganizationContext,
+    Query(query): Query<crate::routes::integration::ListCandidatesQuery>,
 ) -> Result<impl IntoResponse> {
-    let candidates = state.candidate_service.list_candidates().await?;
+    let mut candidates = state
+        .candidate_service
+        .list_filtered(&query.skill_list(), &query.profile_skill_list(), org.org_id)
+        .await?;
+    query.sort.apply(&mut candidates);
     
     let response: Vec<OneFCandidateResponse> = candidates.into_iter().map(|c| OneFCandidateResponse {
+        profile: c.profile(),
         id: c.id,
         telegram_id: c.telegram_id,
-        name: c.name,
-        email: c.email,
+        telegram_thread_id: c.telegram_thread_id,
+        telegram_unreachable: c.telegram_unreachable,
+        name: c.name.unwrap_or_default(),
+        email: c.email.unwrap_or_default(),
         phone: c.phone,
+        cv_download_url: c.cv_url.as_deref().map(onef_cv_download_url),
         cv_url: c.cv_url,
+        photo_url: c.photo_url,
         status: c.status,
         ai_rating: c.ai_rating,
         ai_comment: c.ai_comment,
+        extracted_profile: c.extracted_profile,
         created_at: c.created_at,
+        last_activity_at: c.last_activity_at,
     }).collect();
 
     Ok(Json(response))
@@ -330,15 +407,21 @@ pub async fn list_candidates(
 
 pub async fn list_attempts_filter(
     State(state): State<AppState>,
+    org: OrganizationContext,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> Result<impl IntoResponse> {
     let status = params.get("status").cloned();
     let email = params.get("email").cloned();
+    let source = crate::services::attempt_service::parse_source_filter(
+        params.get("source").map(String::as_str),
+    )?;
     let page = params.get("page").and_then(|v| v.parse().ok()).unwrap_or(1);
     let limit = params.get("limit").and_then(|v| v.parse().ok()).unwrap_or(50);
 
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let (items, total) = svc.list_attempts(None, email, status, page, limit).await?;
+    let (items, total) = svc
+        .list_attempts(None, email, status, source, page, limit, org.org_id)
+        .await?;
 
     Ok(Json(json!({
         "items": items,
@@ -350,28 +433,14 @@ pub async fn list_attempts_filter(
 
 pub async fn list_all_attempts(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
-    let (items, _) = svc.list_attempts(None, None, None, 1, 1000).await?;
+    let (items, _) = svc.list_attempts(None, None, None, None, 1, 1000, org.org_id).await?;
 
     Ok(Json(items))
 }
 
-fn strip_html_tags(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut inside_tag = false;
-    for c in input.chars() {
-        if c == '<' {
-            inside_tag = true;
-        } else if c == '>' {
-            inside_tag = false;
-        } else if !inside_tag {
-            output.push(c);
-        }
-    }
-    output.trim().to_string()
-}
-
 pub async fn list_vacancies(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse> {
@@ -395,7 +464,7 @@ pub async fn list_vacancies(
         for v in external {
             combined.push(serde_json::json!({
                 "id": v.id.to_string(),
-                "title": strip_html_tags(&v.title),
+                "title": sanitize_to_text(&v.title),
                 "company": v.direction, 
                 "location": v.city,
                 "status": "published",
@@ -410,10 +479,17 @@ pub async fn list_vacancies(
 
 pub async fn get_vacancy(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id_str): Path<String>,
 ) -> Result<impl IntoResponse> {
+    if let Some(link) = state.vacancy_link_service.resolve(&id_str).await? {
+        if let Ok(vacancy) = state.vacancy_service.get_by_id(link.vacancy_id, org.org_id).await {
+            return Ok(Json(serde_json::to_value(vacancy).unwrap()));
+        }
+    }
+
     if let Ok(uuid) = Uuid::parse_str(&id_str) {
-        if let Ok(vacancy) = state.vacancy_service.get_by_id(uuid).await {
+        if let Ok(vacancy) = state.vacancy_service.get_by_id(uuid, org.org_id).await {
             return Ok(Json(serde_json::to_value(vacancy).unwrap()));
         }
     }
@@ -422,7 +498,7 @@ pub async fn get_vacancy(
         if let Ok(Some(ext_v)) = state.koinotinav_service.fetch_vacancy(ext_id).await {
             return Ok(Json(json!({
                 "id": ext_v.id.to_string(),
-                "title": strip_html_tags(&ext_v.title),
+                "title": sanitize_to_text(&ext_v.title),
                 "company": ext_v.direction,
                 "location": ext_v.city,
                 "description": ext_v.content,
@@ -438,6 +514,7 @@ pub async fn get_vacancy(
 
 pub async fn list_tests(
     State(state): State<AppState>,
+    org: OrganizationContext,
 ) -> Result<impl IntoResponse> {
     let result = state.test_service.list_tests(
         1,
@@ -446,7 +523,9 @@ pub async fn list_tests(
             is_active: Some(true),
             created_by: None,
             search: None,
-        })
+        }),
+        None,
+        org.org_id,
     ).await?;
 
     let tests: Vec<OneFTestSummary> = result.tests.into_iter().map(|t| {
@@ -473,15 +552,7 @@ pub async fn list_tests(
 pub async fn list_candidate_statuses(
     State(_state): State<AppState>,
 ) -> Result<impl IntoResponse> {
-    Ok(Json(json!([
-        { "id": "new", "label": "New" },
-        { "id": "reviewing", "label": "Reviewing" },
-        { "id": "test_assigned", "label": "Test Assigned" },
-        { "id": "test_completed", "label": "Test Completed" },
-        { "id": "interview", "label": "Interview" },
-        { "id": "accepted", "label": "Accepted" },
-        { "id": "rejected", "label": "Rejected" }
-    ])))
+    Ok(Json(STATUS_WORKFLOW))
 }
 
 pub async fn list_test_statuses(
@@ -501,36 +572,42 @@ pub async fn list_test_statuses(
 
 pub async fn create_test_invite(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<OneFCreateInviteRequest>,
 ) -> Result<impl IntoResponse> {
-    let candidate = state.candidate_service.get_candidate(payload.candidate_id).await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
-    let test = state.test_service.get_test_by_id(payload.test_id).await?;
-    let expires_in_hours = payload.expires_in_hours.unwrap_or_else(|| {
-        if test.duration_minutes > 0 && test.test_type.as_deref() == Some("presentation") {
-            (test.duration_minutes / 60) as i64
-        } else {
-            48
-        }
-    });
+    let candidate = state.candidate_service.get_candidate_in_org(payload.candidate_id, org.org_id).await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+    let test = state.test_service.get_test_in_org(payload.test_id, org.org_id).await?;
+    let expires_in_hours = payload
+        .expires_in_hours
+        .or(test.default_invite_expiry_hours.map(i64::from))
+        .unwrap_or_else(|| {
+            if test.duration_minutes > 0 && test.test_type.as_deref() == Some("presentation") {
+                (test.duration_minutes / 60) as i64
+            } else {
+                crate::models::test::DEFAULT_INVITE_EXPIRY_HOURS
+            }
+        });
 
     let svc = crate::services::attempt_service::AttemptService::new(state.pool.clone());
     let result = svc.create_invite(
         payload.test_id,
         crate::services::attempt_service::InviteCandidate {
             external_id: candidate.telegram_id.map(|id| id.to_string()),
-            name: candidate.name.clone(),
-            email: candidate.email.clone(),
+            name: candidate.name.clone().unwrap_or_default(),
+            email: candidate.email.clone().ok_or_else(crate::error::Error::candidate_not_found)?,
             telegram_id: candidate.telegram_id,
             phone: candidate.phone.clone(),
         },
         expires_in_hours,
         Some(json!({ "source": "onef", "vacancy_id": payload.vacancy_id })),
+        None,
+        crate::services::attempt_service::InviteSource::Onef,
     ).await?;
+    let mut warnings: Vec<&str> = Vec::new();
     if let Some(telegram_id) = candidate.telegram_id {
         let config = crate::config::get_config();
         let webapp_url = &config.webapp_url;
-        let bot_token = &config.telegram_bot_token;
 
         let message_text = if test.test_type.as_deref() == Some("presentation") {
             let themes_count = test.presentation_themes
@@ -538,38 +615,41 @@ pub async fn create_test_invite(
                 .and_then(|t| t.as_array())
                 .map(|a| a.len())
                 .unwrap_or(0);
-            format!(
-                "Вам назначена презентация: {}\n\nКоличество тем: {}\nСрок выполнения: {} часов\n\nНажмите кнопку ниже, чтобы просмотреть задание.",
-                test.title, themes_count, expires_in_hours
+            strings::render(
+                strings::DEFAULT_LANG,
+                "invite_presentation",
+                &[
+                    ("title", &test.title),
+                    ("themes", &themes_count.to_string()),
+                    ("hours", &expires_in_hours.to_string()),
+                ],
             )
         } else {
-            format!(
-                "Вам назначен тест: {}\n\nНажмите кнопку ниже, чтобы начать прохождение теста.",
-                test.title
-            )
+            strings::render(strings::DEFAULT_LANG, "invite_test", &[("title", &test.title)])
         };
 
         let reply_markup = json!({
             "inline_keyboard": [[
                 {
-                    "text": "Профиль",
+                    "text": strings::text(strings::DEFAULT_LANG, "profile_button"),
                     "web_app": { "url": webapp_url }
                 }
             ]]
         });
 
-        let telegram_body = json!({
-            "chat_id": telegram_id,
-            "text": message_text,
-            "reply_markup": reply_markup,
-        });
-
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
-        let client = reqwest::Client::new();
-        if let Err(e) = client.post(&url).json(&telegram_body).send().await {
-            tracing::warn!("Failed to send Telegram notification for OneF invite: {}", e);
-        } else {
-            tracing::info!("OneF invite: Telegram notification sent to chat_id: {}", telegram_id);
+        let delivery = crate::services::telegram_delivery_service::TelegramDeliveryService::from_config(
+            state.pool.clone(),
+        );
+        match delivery
+            .send(telegram_id, candidate.telegram_thread_id, &message_text, Some(reply_markup))
+            .await
+        {
+            Ok(()) => tracing::info!("OneF invite: Telegram notification sent to chat_id: {}", telegram_id),
+            Err(e) if e.code() == "telegram_unreachable" => {
+                tracing::info!("OneF invite: skipped Telegram notification to blocked chat_id: {}", telegram_id);
+                warnings.push("telegram_unreachable");
+            }
+            Err(e) => tracing::warn!("Failed to send Telegram notification for OneF invite: {}", e),
         }
     }
 
@@ -581,20 +661,28 @@ pub async fn create_test_invite(
         event: "test_assigned".to_string(),
         attempt_id: result.attempt_id,
         candidate: crate::dto::webhook_dto::WebhookCandidate {
-            name: candidate.name.clone(),
+            name: candidate.name.clone().unwrap_or_default(),
             telegram_id: candidate.telegram_id,
+            telegram_thread_id: candidate.telegram_thread_id,
         },
         test: crate::dto::webhook_dto::WebhookTest {
             title: test.title.clone(),
         },
         access_token: result.access_token.clone(),
         expires_at: result.expires_at,
+        expires_at_local: crate::utils::time::format_deadline(result.expires_at, candidate.timezone.as_deref()),
     };
     let payload_json = serde_json::to_value(&assigned)?;
-    let _ = notif.enqueue_webhook("test_assigned", &payload_json).await;
+    let _ = notif
+        .enqueue_webhook(
+            "test_assigned",
+            &payload_json,
+            Some(&result.attempt_id.to_string()),
+        )
+        .await;
 
     let config = crate::config::get_config();
-    Ok((StatusCode::CREATED, Json(json!({
+    let mut response = json!({
         "attempt_id": result.attempt_id,
         "access_token": result.access_token,
         "test_url": format!("{}/test/{}", config.webapp_url, result.access_token),
@@ -602,5 +690,9 @@ pub async fn create_test_invite(
         "status": result.status,
         "candidate_name": candidate.name,
         "test_title": test.title,
-    }))))
+    });
+    if !warnings.is_empty() {
+        response["warnings"] = json!(warnings);
+    }
+    Ok((StatusCode::CREATED, Json(response)))
 }
@@ -0,0 +1,46 @@
+use crate::{error::Result, AppState};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationPayload {
+    #[validate(length(min = 1, max = 255, message = "name must be 1-255 characters"))]
+    pub name: String,
+    /// Lowercase letters, digits and hyphens, e.g. `acme-hr`.
+    pub slug: String,
+}
+
+/// POST /api/admin/organizations — super admins only.
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateOrganizationPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let organization = state
+        .organization_service
+        .create(&payload.name, &payload.slug)
+        .await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+/// GET /api/admin/organizations
+pub async fn list_organizations(State(state): State<AppState>) -> Result<impl IntoResponse> {
+    let organizations = state.organization_service.list().await?;
+    Ok(Json(organizations))
+}
+
+/// GET /api/admin/organizations/:id
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let organization = state.organization_service.get(id).await?;
+    Ok(Json(organization))
+}
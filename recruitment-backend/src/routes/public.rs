@@ -1,22 +1,62 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Path, State},
+    http::{request::Parts, StatusCode},
     response::{IntoResponse, Json, Response},
 };
 use chrono::Utc;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use url::Url;
 use serde_json::json;
 use validator::Validate;
 
 use crate::dto::public_dto::{
-    GetTestByTokenResponse, SaveAnswerRequest, SaveAnswerResponse, StartTestResponse,
-    StatusResponse, SubmitTestRequest, SubmitTestResponse,
+    GetTestByTokenResponse, PublicQuestionView, QuestionFeedbackRequest, SaveAnswerRequest, SaveAnswerResponse, StartTestRequest,
+    CandidateResult, StartTestResponse, StatusResponse, SubmitTestRequest, SubmitTestResponse,
 };
-use crate::services::attempt_service::AttemptService;
+use crate::services::attempt_service::{attempt_deadline, AttemptService};
 use crate::services::audit_service::AuditService;
 use crate::services::notification_service::NotificationService;
+use crate::services::question_feedback_service::QuestionFeedbackService;
 use crate::AppState;
 
+/// The candidate's IP, resolved through the trusted reverse proxies (see
+/// `utils::client_ip::resolve_client_ip`). `None` if no valid address is known.
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted = &crate::config::get_config().trusted_proxies;
+        Ok(ClientIp(crate::utils::client_ip::resolve_client_ip(&parts.headers, peer, trusted)))
+    }
+}
+
+/// The candidate's `User-Agent`, trimmed and length-capped.
+pub struct UserAgent(pub Option<String>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for UserAgent
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(UserAgent(crate::utils::client_ip::user_agent(&parts.headers)))
+    }
+}
+
 #[axum::debug_handler]
 pub async fn get_test_by_token(
     State(state): State<AppState>,
@@ -25,18 +65,13 @@ pub async fn get_test_by_token(
     let svc = AttemptService::new(state.pool.clone());
     let (attempt, test) = svc.get_attempt_and_test_by_token(&token).await?;
     if attempt.expires_at <= Utc::now() {
-        return Ok((
-            StatusCode::FORBIDDEN,
-            Json(json!({
-                "error": "test_expired",
-                "message": "This test invitation has expired"
-            })),
-        )
-            .into_response());
+        return Err(crate::error::Error::test_expired());
     }
 
     let questions: Vec<crate::models::question::Question> =
         serde_json::from_value(test.questions.clone()).unwrap_or_default();
+    let is_presentation = test.test_type.as_deref() == Some("presentation");
+    let requires_declaration = test.show_honesty_declaration && !attempt.honesty_declaration_accepted;
     let response = GetTestByTokenResponse {
         test: crate::dto::public_dto::PublicTestSummary {
             title: test.title,
@@ -46,8 +81,11 @@ pub async fn get_test_by_token(
             total_questions: questions.len(),
             passing_score: test.passing_score.to_string().parse::<f64>().unwrap_or(0.0),
             test_type: test.test_type,
-            presentation_themes: test.presentation_themes,
+            presentation_themes: if is_presentation { None } else { test.presentation_themes },
+            assigned_theme: if is_presentation { attempt.assigned_theme } else { None },
             presentation_extra_info: test.presentation_extra_info,
+            declaration_text: if test.show_honesty_declaration { test.declaration_text } else { None },
+            language: test.language,
         },
         attempt: crate::dto::public_dto::PublicAttemptSummary {
             id: attempt.id,
@@ -55,88 +93,91 @@ pub async fn get_test_by_token(
             expires_at: attempt.expires_at,
             candidate_name: attempt.candidate_name,
             candidate_external_id: attempt.candidate_external_id,
+            language: attempt.language,
         },
+        requires_declaration,
+        prerequisite_test_id: test.prerequisite_test_id,
     };
     Ok(Json(response).into_response())
 }
 
+/// POST /api/public/tests/:token/feedback — flags a question as ambiguous or
+/// wrongly keyed for HR to review.
+pub async fn submit_question_feedback(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Json(req): Json<QuestionFeedbackRequest>,
+) -> crate::error::Result<Response> {
+    req.validate()?;
+    let svc = AttemptService::new(state.pool.clone());
+    let (attempt, _test) = svc.get_attempt_and_test_by_token(&token).await?;
+    let feedback = QuestionFeedbackService::new(state.pool.clone())
+        .submit(&attempt, req.question_id, &req.text)
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "id": feedback.id, "question_id": feedback.question_id })),
+    )
+        .into_response())
+}
+
+/// GET /api/public/i18n/:lang — candidate-facing UI strings. Unknown
+/// languages and missing translations fall back to Russian.
+pub async fn get_i18n_strings(Path(lang): Path<String>) -> Json<serde_json::Value> {
+    let lang = crate::utils::strings::resolve_lang(Some(&lang));
+    Json(serde_json::json!({
+        "lang": lang,
+        "strings": crate::utils::strings::ui_strings(lang),
+    }))
+}
+
+/// GET /api/public/timezones — IANA timezone names for the registration
+/// form's picker, with the fallback used when none is chosen.
+pub async fn list_timezones() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "default": crate::utils::time::DEFAULT_TIMEZONE,
+        "timezones": crate::utils::time::supported_timezones(),
+    }))
+}
+
 #[axum::debug_handler]
 pub async fn start_test(
     State(state): State<AppState>,
     Path(token): Path<String>,
+    ClientIp(client_ip): ClientIp,
+    UserAgent(user_agent): UserAgent,
+    payload: Option<Json<StartTestRequest>>,
 ) -> crate::error::Result<Response> {
     tracing::info!("Starting test for token: {}", token);
     let svc = AttemptService::new(state.pool.clone());
-    let (attempt, _test) = svc.get_attempt_and_test_by_token(&token).await?;
+    let (attempt, test) = svc.get_attempt_and_test_by_token(&token).await?;
+    let accept_declaration = payload.map(|Json(p)| p.accept_declaration).unwrap_or(false);
     
     tracing::info!("Found attempt: {:?}, expires_at: {:?}, now: {:?}", attempt.id, attempt.expires_at, Utc::now());
 
-    if attempt.expires_at <= Utc::now() {
+    if attempt_deadline(&attempt, test.duration_minutes) <= Utc::now() {
         tracing::warn!("Test expired for token: {}", token);
-        return Ok((
-            StatusCode::FORBIDDEN,
-            Json(json!({
-                "error": "test_expired",
-                "message": "This test invitation has expired"
-            })),
-        )
-            .into_response());
+        return Err(crate::error::Error::test_expired());
+    }
+    if test.show_honesty_declaration && !attempt.honesty_declaration_accepted && !accept_declaration {
+        return Err(crate::error::Error::coded(
+            StatusCode::BAD_REQUEST,
+            "declaration_not_accepted",
+            "The honesty declaration must be accepted before starting the test",
+        ));
     }
-    match svc.start_attempt_by_token(&token).await {
+    match svc.start_attempt_by_token(&token, accept_declaration, client_ip, user_agent).await {
         Ok(updated) => {
              tracing::info!("Test started successfully: {:?}", updated.id);
              let response = StartTestResponse {
                 attempt_id: updated.id,
+                access_token: updated.access_token.clone(),
                 status: updated.status.clone(),
                 started_at: updated.started_at.unwrap_or(Utc::now()),
                 expires_at: updated.expires_at,
-                questions: updated.questions_snapshot,
+                questions: PublicQuestionView::sanitize_snapshot(&updated.questions_snapshot),
             };
 
-            let onef = state.onef_service.clone();
-            let cand_svc = state.candidate_service.clone();
-            let attempt_id = updated.id;
-            let test_id = updated.test_id;
-            let status = updated.status.clone();
-            let email = updated.candidate_email.clone();
-            let attempt_metadata = updated.metadata.clone();
-
-            tokio::spawn(async move {
-                if let Ok(Some(candidate)) = cand_svc.get_by_email(&email).await {
-                    let mut final_vacancy_id = attempt_metadata.as_ref()
-                        .and_then(|m| m.get("vacancy_id").and_then(|v| v.as_i64()));
-                    
-                    if final_vacancy_id.is_none() {
-                        if let Ok(apps) = cand_svc.get_candidate_applications(candidate.id).await {
-                            if let Some(first_app) = apps.first() {
-                                final_vacancy_id = Some(first_app.vacancy_id);
-                            }
-                        }
-                    }
-                    if final_vacancy_id.is_none() {
-                        final_vacancy_id = candidate.vacancy_id;
-                    }
-
-                    let _ = onef.notify_test_status(crate::services::onef_service::OneFTestStatusPayload {
-                        candidate_id: candidate.id,
-                        test_id,
-                        vacancy_id: final_vacancy_id,
-                        test_status: status,
-                        event_date: Utc::now().to_rfc3339(),
-                        event_data: crate::services::onef_service::OneFTestStatusEventData {
-                            attempt_id: Some(attempt_id),
-                            max_score: None,
-                            user_score: None,
-                            percentage: None,
-                            passed: None,
-                            result_url: None,
-                            presentation_link: None,
-                            presentation_file_url: None,
-                        },
-                    }).await;
-                }
-            });
-
             Ok(Json(response).into_response())
         },
         Err(e) => {
@@ -154,16 +195,9 @@ pub async fn save_answer(
 ) -> crate::error::Result<Response> {
     req.validate()?;
     let svc = AttemptService::new(state.pool.clone());
-    let (attempt, _test) = svc.get_attempt_and_test_by_token(&token).await?;
-    if attempt.expires_at <= Utc::now() {
-        return Ok((
-            StatusCode::FORBIDDEN,
-            Json(json!({
-                "error": "test_expired",
-                "message": "This test invitation has expired"
-            })),
-        )
-            .into_response());
+    let (attempt, test) = svc.get_attempt_and_test_by_token(&token).await?;
+    if attempt_deadline(&attempt, test.duration_minutes) <= Utc::now() {
+        return Err(crate::error::Error::test_expired());
     }
     let question_id = req.question_id;
     let ts = svc.save_answer_by_token(&token, req).await?;
@@ -185,13 +219,7 @@ pub async fn submit_presentation(
     let (attempt_init, _test) = svc.get_attempt_and_test_by_token(&token).await?;
 
     if attempt_init.status == "completed" {
-         return Ok((
-            StatusCode::CONFLICT,
-            Json(json!({
-                "error": "already_completed",
-                "message": "Presentation has already been submitted"
-            })),
-        ).into_response());
+         return Err(crate::error::Error::already_completed("Presentation has already been submitted"));
     }
 
     let mut presentation_link: Option<String> = None;
@@ -207,24 +235,20 @@ pub async fn submit_presentation(
                 match Url::parse(trimmed) {
                     Ok(url) => {
                         if url.scheme() != "http" && url.scheme() != "https" {
-                            return Ok((
+                            return Err(crate::error::Error::coded(
                                 StatusCode::BAD_REQUEST,
-                                Json(json!({
-                                    "error": "invalid_url_scheme",
-                                    "message": "Only HTTP and HTTPS links are allowed"
-                                })),
-                            ).into_response());
+                                "invalid_url_scheme",
+                                "Only HTTP and HTTPS links are allowed",
+                            ));
                         }
                         presentation_link = Some(trimmed.to_string());
                     },
                     Err(_) => {
-                        return Ok((
+                        return Err(crate::error::Error::coded(
                             StatusCode::BAD_REQUEST,
-                            Json(json!({
-                                "error": "invalid_url",
-                                "message": "The provided link is not a valid URL"
-                            })),
-                        ).into_response());
+                            "invalid_url",
+                            "The provided link is not a valid URL",
+                        ));
                     }
                 }
             }
@@ -240,13 +264,11 @@ pub async fn submit_presentation(
                     .unwrap_or_default();
 
                 if !allowed_extensions.contains(&extension.as_str()) {
-                    return Ok((
+                    return Err(crate::error::Error::coded(
                         StatusCode::BAD_REQUEST,
-                        Json(json!({
-                            "error": "invalid_file_type",
-                            "message": format!("File type not allowed. Allowed: {}", allowed_extensions.join(", "))
-                        })),
-                    ).into_response());
+                        "invalid_file_type",
+                        format!("File type not allowed. Allowed: {}", allowed_extensions.join(", ")),
+                    ));
                 }
 
                 let upload_dir = "uploads/presentations";
@@ -261,13 +283,11 @@ pub async fn submit_presentation(
     }
 
     if presentation_link.is_none() && file_path.is_none() {
-        return Ok((
+        return Err(crate::error::Error::coded(
             StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "empty_submission",
-                "message": "Please provide either a link or a file for your presentation"
-            })),
-        ).into_response());
+            "empty_submission",
+            "Please provide either a link or a file for your presentation",
+        ));
     }
 
     let attempt = svc.submit_presentation_by_token(&token, presentation_link, file_path).await?;
@@ -277,7 +297,13 @@ pub async fn submit_presentation(
         crate::config::get_config().telegram_bot_webhook_url.clone(),
     );
     
-    if let Ok(test) = state.test_service.get_test_by_id(attempt.test_id).await {
+    // HR previews don't notify anyone.
+    let test = if attempt.is_preview {
+        None
+    } else {
+        state.test_service.get_test_by_id(attempt.test_id).await.ok()
+    };
+    if let Some(test) = test {
         let completed = json!({
             "event": "presentation_submitted",
             "attempt_id": attempt.id,
@@ -291,65 +317,9 @@ pub async fn submit_presentation(
             "submission_link": attempt.presentation_submission_link,
             "has_file": attempt.presentation_submission_file_path.is_some(),
         });
-        let _ = notif.enqueue_webhook("presentation_submitted", &completed).await;
-
-        let onef = state.onef_service.clone();
-        let cand_svc = state.candidate_service.clone();
-        let attempt_id = attempt.id;
-        let test_id = attempt.test_id;
-        let status = attempt.status.clone();
-        let email = attempt.candidate_email.clone();
-        let config = crate::config::get_config();
-
-        let mut result_urls = Vec::new();
-        if let Some(ref link) = attempt.presentation_submission_link {
-            result_urls.push(format!("Link: {}", link));
-        }
-        if let Some(ref path) = attempt.presentation_submission_file_path {
-            result_urls.push(format!("File: {}/{}", config.webapp_url, path));
-        }
-        let result_url = if result_urls.is_empty() {
-            None
-        } else {
-            Some(result_urls.join(" | "))
-        };
-        let attempt_metadata = attempt.metadata.clone();
-
-        tokio::spawn(async move {
-            if let Ok(Some(candidate)) = cand_svc.get_by_email(&email).await {
-                let mut final_vacancy_id = attempt_metadata.as_ref()
-                    .and_then(|m| m.get("vacancy_id").and_then(|v| v.as_i64()));
-                
-                if final_vacancy_id.is_none() {
-                    if let Ok(apps) = cand_svc.get_candidate_applications(candidate.id).await {
-                        if let Some(first_app) = apps.first() {
-                            final_vacancy_id = Some(first_app.vacancy_id);
-                        }
-                    }
-                }
-                if final_vacancy_id.is_none() {
-                    final_vacancy_id = candidate.vacancy_id;
-                }
-
-                let _ = onef.notify_test_status(crate::services::onef_service::OneFTestStatusPayload {
-                    candidate_id: candidate.id,
-                    test_id,
-                    vacancy_id: final_vacancy_id,
-                    test_status: status,
-                    event_date: Utc::now().to_rfc3339(),
-                    event_data: crate::services::onef_service::OneFTestStatusEventData {
-                        attempt_id: Some(attempt_id),
-                        max_score: None,
-                        user_score: None,
-                        percentage: None,
-                        passed: None,
-                        result_url: result_url.clone(),
-                        presentation_link: attempt.presentation_submission_link.clone(),
-                        presentation_file_url: attempt.presentation_submission_file_path.clone().map(|p| format!("{}/{}", config.webapp_url, p)),
-                    },
-                }).await;
-            }
-        });
+        let _ = notif
+            .enqueue_webhook("presentation_submitted", &completed, Some(&attempt.id.to_string()))
+            .await;
     }
 
     Ok(Json(json!({ 
@@ -362,38 +332,26 @@ pub async fn submit_presentation(
 pub async fn submit_test(
     State(state): State<AppState>,
     Path(token): Path<String>,
+    ClientIp(client_ip): ClientIp,
+    UserAgent(user_agent): UserAgent,
     Json(req): Json<SubmitTestRequest>,
 ) -> crate::error::Result<Response> {
     tracing::info!("Submitting test for token: {}, answers count: {}", token, req.answers.len());
     let svc = AttemptService::new(state.pool.clone());
-    let (attempt0, _test) = svc.get_attempt_and_test_by_token(&token).await?;
+    let (attempt0, test0) = svc.get_attempt_and_test_by_token(&token).await?;
 
-    if attempt0.expires_at <= Utc::now() {
+    if attempt_deadline(&attempt0, test0.duration_minutes) <= Utc::now() {
         tracing::warn!("Submission failed: Test expired for token: {}", token);
-        return Ok((
-            StatusCode::FORBIDDEN,
-            Json(json!({
-                "error": "test_expired",
-                "message": "This test invitation has expired"
-            })),
-        )
-            .into_response());
+        return Err(crate::error::Error::test_expired());
     }
 
-    if attempt0.status == "completed" {
+    if attempt0.status != "in_progress" {
          tracing::warn!("Submission failed: Test already completed for token: {}", token);
-         return Ok((
-            StatusCode::CONFLICT,
-            Json(json!({
-                "error": "already_completed",
-                "message": "Test has already been submitted"
-            })),
-        )
-            .into_response());
+         return Err(crate::error::Error::already_completed("Test has already been submitted"));
     }
 
     let (attempt, score, max_score, percentage, passed) =
-        svc.submit_attempt_by_token(&token, req).await?;
+        svc.submit_attempt_by_token(&token, req, client_ip, user_agent).await?;
 
     tracing::info!("Test graded: id={}, score={}, percentage={}, passed={}", attempt.id, score, percentage, passed);
 
@@ -410,6 +368,10 @@ pub async fn submit_test(
                 candidate: crate::dto::webhook_dto::WebhookCandidate {
                     name: attempt.candidate_name.clone(),
                     telegram_id: attempt.candidate_telegram_id,
+                    telegram_thread_id: state
+                        .candidate_service
+                        .telegram_thread_id(attempt.candidate_telegram_id)
+                        .await,
                 },
                 test: crate::dto::webhook_dto::WebhookTest {
                     title: test.title.clone(),
@@ -417,27 +379,21 @@ pub async fn submit_test(
                 score,
                 percentage,
                 passed,
+                timed_out: false,
             };
             let payload_json = serde_json::to_value(&completed)?;
-            if let Err(e) = notif.enqueue_webhook("test_completed", &payload_json).await {
+            if attempt.is_preview {
+                tracing::info!("Skipping webhooks for preview attempt {}", attempt.id);
+            } else if let Err(e) = notif
+                .enqueue_webhook("test_completed", &payload_json, Some(&attempt.id.to_string()))
+                .await
+            {
                 tracing::error!("Failed to enqueue webhook: {:?}", e);
             }
 
-            let onef = state.onef_service.clone();
-            let cand_svc = state.candidate_service.clone();
-            let attempt_id = attempt.id;
-            let test_id = attempt.test_id;
-            let status = attempt.status.clone();
-            let email = attempt.candidate_email.clone();
-            let score_f = score as f64;
-            let max_score_f = max_score as f64;
-            let pct_f = percentage as f64;
-
-            let config = crate::config::get_config();
-            
             let mut report = format!("Test Results for: {}\n", attempt.candidate_name);
             report.push_str(&format!("Score: {}/{} ({}%)\n\n", score, max_score, percentage));
-            let questions: Vec<crate::models::question::Question> = serde_json::from_value(test.questions.clone()).unwrap_or_default();
+            let questions = crate::services::attempt_service::grading_questions(&attempt.questions_snapshot, &test.questions);
             let graded_answers: Vec<serde_json::Value> = attempt.graded_answers.clone().and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default();
             
             for (i, q) in questions.iter().enumerate() {
@@ -482,86 +438,68 @@ pub async fn submit_test(
                     let m_pts = ans.get("max_points").and_then(|p| p.as_i64()).unwrap_or(0);
                     report.push_str(&format!("Score: {}/{}\n", pts, m_pts));
                 }
-                report.push_str("\n");
+                report.push('\n');
             }
             
-            let upload_dir = "uploads/results";
-            let _ = tokio::fs::create_dir_all(upload_dir).await;
-            let result_filename = format!("{}.txt", attempt_id);
-            let result_path = format!("{}/{}", upload_dir, result_filename);
+            let result_path = crate::services::attempt_service::result_report_path(attempt.id);
+            let _ = tokio::fs::create_dir_all("uploads/results").await;
             let _ = tokio::fs::write(&result_path, report).await;
-            
-            let result_url = Some(format!("{}/{}", config.webapp_url, result_path));
-            let attempt_metadata = attempt.metadata.clone();
-
-            tokio::spawn(async move {
-                if let Ok(Some(candidate)) = cand_svc.get_by_email(&email).await {
-                    let mut final_vacancy_id = attempt_metadata.as_ref()
-                        .and_then(|m| m.get("vacancy_id").and_then(|v| v.as_i64()));
-                    
-                    if final_vacancy_id.is_none() {
-                        if let Ok(apps) = cand_svc.get_candidate_applications(candidate.id).await {
-                            if let Some(first_app) = apps.first() {
-                                final_vacancy_id = Some(first_app.vacancy_id);
-                            }
-                        }
-                    }
-                    if final_vacancy_id.is_none() {
-                        final_vacancy_id = candidate.vacancy_id;
-                    }
-
-                    let _ = onef.notify_test_status(crate::services::onef_service::OneFTestStatusPayload {
-                        candidate_id: candidate.id,
-                        test_id,
-                        vacancy_id: final_vacancy_id,
-                        test_status: status,
-                        event_date: Utc::now().to_rfc3339(),
-                        event_data: crate::services::onef_service::OneFTestStatusEventData {
-                            attempt_id: Some(attempt_id),
-                            user_score: Some(score_f),
-                            max_score: Some(max_score_f),
-                            percentage: Some(pct_f),
-                            passed: Some(passed),
-                            result_url,
-                            presentation_link: None,
-                            presentation_file_url: None,
-                        },
-                    }).await;
-                }
-            });
         },
         Err(e) => {
             tracing::error!("Failed to fetch test for notification: {:?}", e);
         }
     }
 
-    let audit = AuditService::new(state.pool.clone());
-    let _ = audit
-        .log(
-            None,
-            "submit_attempt",
-            "test_attempt",
-            attempt.id,
-            Some(serde_json::json!({"score": score, "percentage": percentage, "passed": passed})),
-            None,
-            None,
-        )
-        .await?;
+    if !attempt.is_preview {
+        let audit = AuditService::new(state.pool.clone());
+        let _ = audit
+            .log(
+                None,
+                "submit_attempt",
+                "test_attempt",
+                attempt.id,
+                Some(serde_json::json!({"score": score, "percentage": percentage, "passed": passed})),
+                None,
+                None,
+            )
+            .await?;
+    }
 
     let resp = SubmitTestResponse {
         attempt_id: attempt.id,
+        result: CandidateResult::new(&attempt, &test0),
         status: attempt.status,
-        score,
-        max_score,
-        percentage,
-        passed,
-        show_results: false,
-        message: "Test submitted successfully. Results have been sent to HR.".to_string(),
     };
     tracing::info!("Test submission successful for token: {}", token);
     Ok(Json(resp).into_response())
 }
 
+/// GET /api/public/tests/:token/result — the submit response again, for a
+/// finished attempt. Scores stay hidden unless the test shows results, and
+/// until any manual review is done.
+#[axum::debug_handler]
+pub async fn get_result(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> crate::error::Result<Response> {
+    let svc = AttemptService::new(state.pool.clone());
+    let (attempt, test) = svc.get_attempt_and_test_by_token(&token).await?;
+    if !crate::routes::candidate_routes::FINISHED_ATTEMPT_STATUSES.contains(&attempt.status.as_str()) {
+        return Err(crate::error::Error::coded(
+            StatusCode::CONFLICT,
+            "attempt_not_finished",
+            "The test has not been submitted yet",
+        )
+        .with_details(json!({ "status": attempt.status })));
+    }
+    Ok(Json(SubmitTestResponse {
+        attempt_id: attempt.id,
+        result: CandidateResult::new(&attempt, &test),
+        status: attempt.status,
+    })
+    .into_response())
+}
+
 
 #[axum::debug_handler]
 pub async fn get_status(
@@ -570,6 +508,7 @@ pub async fn get_status(
 ) -> crate::error::Result<Response> {
     let svc = AttemptService::new(state.pool.clone());
     let (attempt, test) = svc.get_attempt_and_test_by_token(&token).await?;
+    let attempt = svc.record_connection_gap(attempt, test.max_grace_seconds).await?;
     let total_questions: i32 = match serde_json::from_value::<Vec<serde_json::Value>>(
         attempt.questions_snapshot.clone(),
     ) {
@@ -582,10 +521,8 @@ pub async fn get_status(
             .unwrap_or(0),
         None => 0,
     };
-    let time_remaining = attempt.started_at.map(|started| {
-        let end = started + chrono::Duration::minutes(test.duration_minutes as i64);
-        let now = Utc::now();
-        (end - now).num_seconds().max(0) as i32
+    let time_remaining = attempt.started_at.map(|_| {
+        AttemptService::get_current_time_remaining(&attempt, test.duration_minutes) as i32
     });
     let resp = StatusResponse {
         status: attempt.status,
@@ -0,0 +1,35 @@
+use crate::{error::Result, middleware::auth::Claims, AppState};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReferralCodePayload {
+    /// Left empty to have a random code generated.
+    #[validate(length(min = 3, max = 64, message = "code must be 3-64 characters"))]
+    pub code: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "campaign_name is required"))]
+    pub campaign_name: String,
+}
+
+/// POST /api/integration/referral-codes — register a code for a recruiting campaign.
+pub async fn create_referral_code(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    Json(payload): Json<CreateReferralCodePayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let created_by = claims.and_then(|Extension(c)| Uuid::parse_str(&c.sub).ok());
+    let code = state
+        .referral_service
+        .create_code(payload.code, payload.campaign_name, created_by)
+        .await?;
+    Ok((StatusCode::CREATED, Json(code)))
+}
+
+/// GET /api/integration/candidates/referral-stats — registrations per campaign.
+pub async fn referral_stats(State(state): State<AppState>) -> Result<impl IntoResponse> {
+    let stats = state.referral_service.stats().await?;
+    Ok(Json(stats))
+}
@@ -0,0 +1,61 @@
+use crate::{
+    config::get_config,
+    error::Result,
+    services::{
+        export_service::ExportService,
+        report_service::{ReportWeek, WeeklyReport},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct WeeklyReportQuery {
+    /// ISO week such as `2025-W07`; defaults to the last complete week.
+    pub week: Option<String>,
+    /// `json` (default) or `xlsx`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeeklyReportResponse {
+    #[serde(flatten)]
+    pub report: WeeklyReport,
+    pub text: String,
+}
+
+/// GET /api/integration/reports/weekly — rebuild the weekly digest for any week.
+pub async fn weekly_report(
+    State(state): State<AppState>,
+    Query(query): Query<WeeklyReportQuery>,
+) -> Result<Response> {
+    let tz = get_config().report_timezone;
+    let week = match query.week.as_deref() {
+        Some(raw) => ReportWeek::parse(raw)?,
+        None => ReportWeek::previous(chrono::Utc::now(), tz),
+    };
+    let report = state.report_service.build_weekly(week, tz).await?;
+
+    if query.format.as_deref() == Some("xlsx") {
+        let buffer = ExportService::generate_weekly_report_xlsx(&report)?;
+        let disposition = format!("attachment; filename=\"weekly_report_{}.xlsx\"", report.week);
+        return Ok((
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            buffer,
+        )
+            .into_response());
+    }
+
+    let text = report.to_telegram_text();
+    Ok(Json(WeeklyReportResponse { report, text }).into_response())
+}
@@ -1,4 +1,4 @@
-use crate::{error::Result, models::response::is_valid_stage, AppState};
+use crate::{error::Result, models::response::is_valid_stage, utils::html::sanitize_to_text, AppState};
 use axum::{
     extract::{Path, State},
     response::IntoResponse,
@@ -18,7 +18,7 @@ pub async fn grade_one(
         .candidate_service
         .get_candidate(candidate_id)
         .await?
-        .ok_or_else(|| crate::error::Error::NotFound("Candidate not found".into()))?;
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
 
     let mut cv_text = String::new();
     if let Some(ref path) = candidate.cv_url {
@@ -37,15 +37,8 @@ pub async fn grade_one(
         .await?
         .ok_or_else(|| crate::error::Error::NotFound(format!("Vacancy #{} not found", vacancy_id)))?;
 
-    let v_name_clean = vacancy
-        .title
-        .replace("<h1>", "").replace("</h1>", "")
-        .replace("<strong>", "").replace("</strong>", "")
-        .replace("<span>", "").replace("</span>", "");
-    let v_desc_clean = vacancy
-        .content
-        .replace("<p>", "\n").replace("</p>", "")
-        .replace("<br>", "\n").replace("<li>", "- ").replace("</li>", "");
+    let v_name_clean = sanitize_to_text(&vacancy.title);
+    let v_desc_clean = sanitize_to_text(&vacancy.content);
 
     if v_desc_clean.trim().is_empty() {
         return Err(crate::error::Error::BadRequest("Vacancy description is empty".into()));
@@ -54,8 +47,8 @@ pub async fn grade_one(
     let suitability = state
         .ai_service
         .analyze_suitability(
-            &candidate.name,
-            &candidate.email,
+            candidate.name_or_empty(),
+            candidate.email_or_empty(),
             &cv_info,
             candidate.cv_url.as_deref(),
             &v_name_clean,
@@ -1,6 +1,14 @@
 use axum::{extract::State, Json};
 use serde::Deserialize;
-use crate::{AppState, error::Result};
+use crate::{AppState, error::Result, models::candidate::Candidate};
+
+/// Bot API `getFile` refuses anything larger.
+const MAX_TELEGRAM_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+const TELEGRAM_CAPTION_LIMIT: usize = 1024;
+const ATTACHMENT_EXTENSIONS: [&str; 14] = [
+    "pdf", "doc", "docx", "txt", "rtf", "odt", "xls", "xlsx", "jpg", "jpeg", "png", "webp", "zip", "mp4",
+];
+const START_HINT: &str = "Чтобы начать работу или открыть свой профиль, пожалуйста, используйте команду /start";
 
 #[derive(Debug, Deserialize)]
 pub struct TelegramUpdate {
@@ -14,6 +22,10 @@ pub struct TelegramMessage {
     pub from: TelegramUser,
     pub chat: TelegramChat,
     pub text: Option<String>,
+    pub caption: Option<String>,
+    pub document: Option<TelegramDocument>,
+    #[serde(default)]
+    pub photo: Vec<TelegramPhotoSize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,45 +43,80 @@ pub struct TelegramChat {
     pub r#type: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TelegramDocument {
+    pub file_id: String,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramPhotoSize {
+    pub file_id: String,
+    pub width: i64,
+    pub height: i64,
+    pub file_size: Option<i64>,
+}
+
+/// A file sent to the bot, not yet downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundAttachment {
+    pub file_id: String,
+    pub kind: &'static str,
+    pub file_name: Option<String>,
+}
+
+impl TelegramMessage {
+    /// The document, or the largest of the sizes Telegram sends for a photo.
+    pub fn attachment(&self) -> Option<InboundAttachment> {
+        if let Some(doc) = &self.document {
+            return Some(InboundAttachment {
+                file_id: doc.file_id.clone(),
+                kind: "document",
+                file_name: doc.file_name.clone(),
+            });
+        }
+        self.photo
+            .iter()
+            .max_by_key(|p| p.width * p.height)
+            .map(|p| InboundAttachment {
+                file_id: p.file_id.clone(),
+                kind: "photo",
+                file_name: None,
+            })
+    }
+}
+
+/// Extension for a stored attachment, taken from the original file name or
+/// Telegram's `file_path`; anything outside the allow-list is kept as `.bin`.
+pub fn attachment_extension(file_name: Option<&str>, file_path: &str) -> String {
+    file_name
+        .into_iter()
+        .chain(std::iter::once(file_path))
+        .filter_map(|name| std::path::Path::new(name).extension()?.to_str())
+        .map(|ext| ext.to_lowercase())
+        .find(|ext| ATTACHMENT_EXTENSIONS.contains(&ext.as_str()))
+        .unwrap_or_else(|| "bin".to_string())
+}
+
 pub async fn handle_webhook(
     State(state): State<AppState>,
     Json(update): Json<TelegramUpdate>,
 ) -> Result<impl axum::response::IntoResponse> {
     tracing::info!("Received Telegram webhook update ID: {}", update.update_id);
     if let Some(message) = update.message {
-        if let Some(text) = &message.text {
+        if let Err(e) = state.candidate_service.mark_telegram_reachable(message.from.id).await {
+            tracing::warn!("Failed to clear telegram_unreachable for {}: {}", message.from.id, e);
+        }
+        if let Some(attachment) = message.attachment() {
+            handle_attachment(&state, &message, attachment).await;
+        } else if let Some(text) = &message.text {
             let user_id = message.from.id;
             let chat_id = message.chat.id;
             
             if let Ok(Some(candidate)) = state.candidate_service.get_by_telegram_id(user_id).await {
-                let create_msg = crate::models::message::CreateMessage {
-                    candidate_id: candidate.id,
-                    telegram_id: user_id,
-                    direction: "inbound".to_string(),
-                    text: text.clone(),
-                };
-                if let Err(e) = state.message_service.create(create_msg).await {
-                    tracing::warn!("Failed to store incoming message: {:?}", e);
-                }
-                
-                let onef = state.onef_service.clone();
-                let cand_svc = state.candidate_service.clone();
-                let cid = candidate.id;
-                let t_text = text.clone();
-                let c_vacancy_id = candidate.vacancy_id;
-                
-                tokio::spawn(async move {
-                    if onef.is_enabled() {
-                         let vacancy_id = cand_svc.get_candidate_applications(cid).await
-                             .ok()
-                             .and_then(|apps| apps.first().map(|a| a.vacancy_id))
-                             .or(c_vacancy_id);
-
-                         if let Some(vid) = vacancy_id {
-                             let _ = onef.notify_new_message(cid, user_id, &t_text, vid).await;
-                         }
-                    }
-                });
+                record_inbound(&state, &candidate, user_id, text.clone(), None, None).await;
             }
             
             if text.starts_with("/start") {
@@ -127,8 +174,7 @@ pub async fn handle_webhook(
 
                 send_telegram_message(chat_id, msg_text, Some(reply_markup)).await?;
             } else {
-                 let help_text = "Чтобы начать работу или открыть свой профиль, пожалуйста, используйте команду /start";
-                 let _ = send_telegram_message(chat_id, help_text, None).await;
+                 let _ = send_telegram_message(chat_id, START_HINT, None).await;
             }
         }
     }
@@ -136,6 +182,171 @@ pub async fn handle_webhook(
     Ok(axum::http::StatusCode::OK)
 }
 
+async fn handle_attachment(state: &AppState, message: &TelegramMessage, attachment: InboundAttachment) {
+    let user_id = message.from.id;
+    let candidate = match state.candidate_service.get_by_telegram_id(user_id).await {
+        Ok(Some(candidate)) => candidate,
+        _ => {
+            let _ = send_telegram_message(message.chat.id, START_HINT, None).await;
+            return;
+        }
+    };
+
+    let attachment_path = match download_telegram_file(&attachment).await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            tracing::warn!("Failed to download Telegram {} {}: {:?}", attachment.kind, attachment.file_id, e);
+            None
+        }
+    };
+    let text = message.caption.clone().unwrap_or_default();
+    record_inbound(state, &candidate, user_id, text, attachment_path, Some(attachment.kind)).await;
+}
+
+/// Stores an inbound message, pushes it to chat listeners and forwards it to 1F.
+async fn record_inbound(
+    state: &AppState,
+    candidate: &Candidate,
+    user_id: i64,
+    text: String,
+    attachment_path: Option<String>,
+    attachment_type: Option<&str>,
+) {
+    let preview = match attachment_type {
+        Some(kind) if text.trim().is_empty() => format!("[{}]", kind),
+        _ => text.clone(),
+    };
+    let create_msg = crate::models::message::CreateMessage {
+        candidate_id: candidate.id,
+        telegram_id: user_id,
+        direction: "inbound".to_string(),
+        text,
+        attachment_path,
+        attachment_type: attachment_type.map(str::to_string),
+    };
+    match state.message_service.create(create_msg).await {
+        Ok(message) => {
+            let _ = state.message_events.send((&message).into());
+        }
+        Err(e) => tracing::warn!("Failed to store incoming message: {:?}", e),
+    }
+
+    let onef = state.onef_service.clone();
+    let cand_svc = state.candidate_service.clone();
+    let cid = candidate.id;
+    let c_vacancy_id = candidate.vacancy_id;
+
+    tokio::spawn(async move {
+        if onef.is_enabled() {
+             let vacancy_id = cand_svc.get_candidate_applications(cid).await
+                 .ok()
+                 .and_then(|apps| apps.first().map(|a| a.vacancy_id))
+                 .or(c_vacancy_id);
+
+             if let Some(vid) = vacancy_id {
+                 let _ = onef.notify_new_message(cid, user_id, &preview, vid).await;
+             }
+        }
+    });
+}
+
+/// Resolves `file_id` through `getFile`, downloads the file and stores it
+/// under `uploads/chat/`, returning the public path.
+async fn download_telegram_file(attachment: &InboundAttachment) -> Result<String> {
+    let config = crate::config::get_config();
+    let client = reqwest::Client::new();
+    let internal = |e: reqwest::Error| crate::error::Error::Internal(e.to_string());
+
+    let info: serde_json::Value = client
+        .get(format!("https://api.telegram.org/bot{}/getFile", config.telegram_bot_token))
+        .query(&[("file_id", attachment.file_id.as_str())])
+        .send()
+        .await
+        .map_err(internal)?
+        .json()
+        .await
+        .map_err(internal)?;
+    let file_path = info
+        .get("result")
+        .and_then(|r| r.get("file_path"))
+        .and_then(|p| p.as_str())
+        .ok_or_else(|| crate::error::Error::Internal(format!("getFile returned no file_path: {}", info)))?;
+
+    let response = client
+        .get(format!("https://api.telegram.org/file/bot{}/{}", config.telegram_bot_token, file_path))
+        .send()
+        .await
+        .map_err(internal)?;
+    if !response.status().is_success() {
+        return Err(crate::error::Error::Internal(format!(
+            "Telegram file download failed with {}",
+            response.status()
+        )));
+    }
+    let data = response.bytes().await.map_err(internal)?;
+    if data.len() > MAX_TELEGRAM_ATTACHMENT_BYTES {
+        return Err(crate::error::Error::Internal(format!(
+            "Telegram attachment of {} bytes exceeds the limit",
+            data.len()
+        )));
+    }
+
+    let ext = attachment_extension(attachment.file_name.as_deref(), file_path);
+    crate::routes::candidate_routes::store_upload("chat", &ext, &data).await
+}
+
+/// Adds `message_thread_id` to a Telegram send payload when the candidate
+/// has a forum topic configured.
+pub fn with_message_thread(mut body: serde_json::Value, thread_id: Option<i64>) -> serde_json::Value {
+    if let Some(thread_id) = thread_id {
+        body["message_thread_id"] = serde_json::json!(thread_id);
+    }
+    body
+}
+
+/// Sends an operator message to the candidate's chat, inside `thread_id`
+/// when set. With `attachment_url` the file goes out through `sendDocument`
+/// and `text` becomes its caption; text too long for a caption follows as a
+/// separate message.
+pub(crate) async fn deliver_to_chat(
+    chat_id: i64,
+    thread_id: Option<i64>,
+    text: &str,
+    attachment_url: Option<&str>,
+) -> Result<()> {
+    let config = crate::config::get_config();
+    let client = reqwest::Client::new();
+
+    let mut requests = Vec::new();
+    match attachment_url {
+        Some(document) if text.chars().count() <= TELEGRAM_CAPTION_LIMIT => {
+            requests.push(("sendDocument", serde_json::json!({ "chat_id": chat_id, "document": document, "caption": text })));
+        }
+        Some(document) => {
+            requests.push(("sendDocument", serde_json::json!({ "chat_id": chat_id, "document": document })));
+            requests.push(("sendMessage", serde_json::json!({ "chat_id": chat_id, "text": text })));
+        }
+        None => requests.push(("sendMessage", serde_json::json!({ "chat_id": chat_id, "text": text }))),
+    }
+
+    for (method, body) in requests {
+        let body = with_message_thread(body, thread_id);
+        let url = format!("https://api.telegram.org/bot{}/{}", config.telegram_bot_token, method);
+        let resp = client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| crate::error::Error::Internal(format!("Failed to send to Telegram: {}", e)))?;
+
+        if !resp.status().is_success() {
+            let err_text = resp.text().await.unwrap_or_default();
+            return Err(crate::error::Error::Internal(format!("Telegram API error: {}", err_text)));
+        }
+    }
+    Ok(())
+}
+
 async fn send_telegram_message(
     chat_id: i64,
     text: &str,
@@ -8,10 +8,18 @@ use validator::Validate;
 
 use crate::{
     dto::vacancy_dto::{
-        CreateVacancyPayload, UpdateVacancyPayload, VacancyListQuery, VacancyListResponse,
-        VacancyPublicListResponse, VacancyPublicQuery, VacancyPublicSummary, VacancyResponse,
+        CreateVacancyPayload, UpdateVacancyLinksPayload, UpdateVacancyPayload, VacancyListQuery,
+        VacancyListResponse, VacancyPublicListResponse, VacancyPublicQuery, VacancyPublicSummary,
+        VacancyResponse,
+    },
+    error::{Error, Result},
+    middleware::organization::OrganizationContext,
+    services::{
+        telegram_channel_service::{
+            apply_button, registration_link, render_vacancy_post, TelegramChannelService,
+        },
+        vacancy_service::VacancyRef,
     },
-    error::Result,
     AppState,
 };
 
@@ -27,10 +35,11 @@ use crate::{
 #[axum::debug_handler]
 pub async fn create_vacancy(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Json(payload): Json<CreateVacancyPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
-    let vacancy = state.vacancy_service.create(payload).await?;
+    let vacancy = state.vacancy_service.create(payload, org.org_id).await?;
     Ok((StatusCode::CREATED, Json(VacancyResponse::from(vacancy))))
 }
 
@@ -50,11 +59,12 @@ pub async fn create_vacancy(
 #[axum::debug_handler]
 pub async fn update_vacancy(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateVacancyPayload>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
-    let vacancy = state.vacancy_service.update(id, payload).await?;
+    let vacancy = state.vacancy_service.update(id, org.org_id, payload).await?;
     Ok(Json(VacancyResponse::from(vacancy)))
 }
 
@@ -72,9 +82,12 @@ pub async fn update_vacancy(
 #[axum::debug_handler]
 pub async fn delete_vacancy(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    state.vacancy_service.delete(id).await?;
+    if state.vacancy_service.delete(id, org.org_id).await?.rows_affected() == 0 {
+        return Err(Error::NotFound("Vacancy not found".into()));
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -95,9 +108,10 @@ pub async fn delete_vacancy(
 #[axum::debug_handler]
 pub async fn list_vacancies(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Query(query): Query<VacancyListQuery>,
 ) -> Result<impl IntoResponse> {
-    let result = state.vacancy_service.list(query).await?;
+    let result = state.vacancy_service.list(query, org.org_id).await?;
     Ok(Json(VacancyListResponse::from(result)))
 }
 
@@ -115,12 +129,63 @@ pub async fn list_vacancies(
 #[axum::debug_handler]
 pub async fn get_vacancy(
     State(state): State<AppState>,
+    org: OrganizationContext,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let vacancy = state.vacancy_service.get_by_id(id).await?;
+    let vacancy = state.vacancy_service.get_by_id(id, org.org_id).await?;
     Ok(Json(VacancyResponse::from(vacancy)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/integration/vacancies/{id}/analytics",
+    params(
+        ("id" = String, Path, description = "Vacancy UUID or numeric Koinoti Nav id")
+    ),
+    responses(
+        (status = 200, description = "Applicant funnel and test outcomes for the vacancy"),
+        (status = 400, description = "Malformed id"),
+        (status = 404, description = "Vacancy not found")
+    )
+)]
+#[axum::debug_handler]
+pub async fn get_vacancy_analytics(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse> {
+    let analytics = state
+        .vacancy_service
+        .analytics(VacancyRef::parse(&id)?, org.org_id)
+        .await?;
+    Ok(Json(analytics))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/integration/vacancies/{id}/links",
+    params(
+        ("id" = Uuid, Path, description = "Vacancy ID")
+    ),
+    responses(
+        (status = 200, description = "Koinoti Nav and 1F ids linked to the vacancy"),
+        (status = 400, description = "Invalid id"),
+        (status = 404, description = "Vacancy not found"),
+        (status = 409, description = "External id is linked to another vacancy")
+    )
+)]
+#[axum::debug_handler]
+pub async fn update_vacancy_links(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateVacancyLinksPayload>,
+) -> Result<impl IntoResponse> {
+    state.vacancy_service.get_by_id(id, org.org_id).await?;
+    let link = state.vacancy_link_service.set_links(id, payload).await?;
+    Ok(Json(link))
+}
+
 #[utoipa::path(
     get,
     path = "/api/public/vacancies",
@@ -158,7 +223,7 @@ pub async fn get_public_vacancy(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let vacancy = state.vacancy_service.get_by_id(id).await?;
+    let vacancy = state.vacancy_service.get_public(id).await?;
     if vacancy.status != "published" {
         return Err(crate::error::Error::Unauthorized(
             "Vacancy not published".into(),
@@ -166,3 +231,101 @@ pub async fn get_public_vacancy(
     }
     Ok(Json(VacancyResponse::from(vacancy)))
 }
+
+fn telegram_channel() -> Result<(TelegramChannelService, String)> {
+    let channel = crate::config::get_config()
+        .telegram_channel_id
+        .clone()
+        .ok_or_else(|| {
+            Error::coded(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "telegram_channel_not_configured",
+                "TELEGRAM_CHANNEL_ID is not set",
+            )
+        })?;
+    Ok((TelegramChannelService::from_config(), channel))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/integration/vacancies/{id}/publish-telegram",
+    params(
+        ("id" = Uuid, Path, description = "Vacancy ID")
+    ),
+    responses(
+        (status = 200, description = "Vacancy posted to the channel, or its existing post updated"),
+        (status = 404, description = "Vacancy not found"),
+        (status = 409, description = "Vacancy is not published"),
+        (status = 502, description = "Telegram rejected the request"),
+        (status = 503, description = "No Telegram channel configured")
+    )
+)]
+#[axum::debug_handler]
+pub async fn publish_vacancy_telegram(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let vacancy = state.vacancy_service.get_by_id(id, org.org_id).await?;
+    if vacancy.status != "published" {
+        return Err(Error::coded(
+            StatusCode::CONFLICT,
+            "vacancy_not_published",
+            "Only published vacancies can be posted to the channel",
+        )
+        .with_details(serde_json::json!({ "status": vacancy.status })));
+    }
+    let (telegram, channel) = telegram_channel()?;
+
+    let link = registration_link(&crate::config::get_config().webapp_url, &vacancy);
+    let text = render_vacancy_post(&vacancy, &link);
+    let markup = apply_button(&link);
+
+    let (post, action) = match state.vacancy_service.telegram_post(id).await? {
+        Some(post) => {
+            telegram.edit(&post, &text, markup).await?;
+            (post, "updated")
+        }
+        None => {
+            let post = telegram.post(&channel, &text, markup).await?;
+            state
+                .vacancy_service
+                .set_telegram_post(id, Some(&post))
+                .await?;
+            (post, "posted")
+        }
+    };
+
+    Ok(Json(serde_json::json!({
+        "vacancy_id": id,
+        "chat_id": post.chat_id,
+        "message_id": post.message_id,
+        "action": action,
+    })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/integration/vacancies/{id}/publish-telegram",
+    params(
+        ("id" = Uuid, Path, description = "Vacancy ID")
+    ),
+    responses(
+        (status = 204, description = "Channel post removed, or there was none"),
+        (status = 404, description = "Vacancy not found"),
+        (status = 502, description = "Telegram rejected the request")
+    )
+)]
+#[axum::debug_handler]
+pub async fn unpublish_vacancy_telegram(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    state.vacancy_service.get_by_id(id, org.org_id).await?;
+    if let Some(post) = state.vacancy_service.telegram_post(id).await? {
+        TelegramChannelService::from_config().delete(&post).await?;
+        state.vacancy_service.set_telegram_post(id, None).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
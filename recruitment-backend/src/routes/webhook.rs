@@ -56,7 +56,11 @@ pub async fn handle_test_assigned(
 
     state
         .notification_service
-        .enqueue_webhook("test_assigned", &payload)
+        .enqueue_webhook(
+            "test_assigned",
+            &payload,
+            Some(&envelope.payload.attempt_id.to_string()),
+        )
         .await?;
 
     Ok((
@@ -88,7 +92,11 @@ pub async fn handle_test_completed(
 
     state
         .notification_service
-        .enqueue_webhook("test_completed", &payload)
+        .enqueue_webhook(
+            "test_completed",
+            &payload,
+            Some(&envelope.payload.attempt_id.to_string()),
+        )
         .await?;
 
     Ok((
@@ -0,0 +1,108 @@
+use crate::middleware::organization::OrganizationContext;
+use crate::services::webhook_subscription_service::WebhookSubscriptionUpdate;
+use crate::{error::Result, AppState};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWebhookSubscriptionPayload {
+    #[validate(url(message = "url must be a valid URL"))]
+    pub url: String,
+    /// Event names such as `test_completed`, or `["*"]` for every event.
+    pub event_types: Vec<String>,
+    /// Keys the `X-Webhook-Signature` HMAC; generated when omitted.
+    #[validate(length(min = 16, max = 255, message = "secret must be 16-255 characters"))]
+    pub secret: Option<String>,
+}
+
+/// POST /api/integration/webhook-subscriptions — register an endpoint for
+/// selected events.
+pub async fn create_webhook_subscription(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Json(payload): Json<CreateWebhookSubscriptionPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let subscription = state
+        .webhook_subscription_service
+        .create(org.org_id, &payload.url, &payload.event_types, payload.secret)
+        .await?;
+
+    // The secret is only ever shown here.
+    let mut body = serde_json::to_value(&subscription)?;
+    body["secret"] = json!(subscription.secret);
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+/// GET /api/integration/webhook-subscriptions
+pub async fn list_webhook_subscriptions(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+) -> Result<impl IntoResponse> {
+    let subscriptions = state.webhook_subscription_service.list(org.org_id).await?;
+    Ok(Json(subscriptions))
+}
+
+/// GET /api/integration/webhook-subscriptions/:id
+pub async fn get_webhook_subscription(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let subscription = state.webhook_subscription_service.get(id, org.org_id).await?;
+    Ok(Json(subscription))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWebhookSubscriptionPayload {
+    #[validate(url(message = "url must be a valid URL"))]
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    /// Replaces the signing secret; the old one stops working immediately.
+    #[validate(length(min = 16, max = 255, message = "secret must be 16-255 characters"))]
+    pub secret: Option<String>,
+    /// Inactive subscriptions keep their settings but receive no new events.
+    pub active: Option<bool>,
+}
+
+/// PATCH /api/integration/webhook-subscriptions/:id
+pub async fn update_webhook_subscription(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookSubscriptionPayload>,
+) -> Result<impl IntoResponse> {
+    payload.validate()?;
+    let subscription = state
+        .webhook_subscription_service
+        .update(
+            id,
+            org.org_id,
+            WebhookSubscriptionUpdate {
+                url: payload.url,
+                event_types: payload.event_types,
+                secret: payload.secret,
+                is_active: payload.active,
+            },
+        )
+        .await?;
+    Ok(Json(subscription))
+}
+
+/// DELETE /api/integration/webhook-subscriptions/:id
+pub async fn delete_webhook_subscription(
+    State(state): State<AppState>,
+    org: OrganizationContext,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    state.webhook_subscription_service.delete(id, org.org_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
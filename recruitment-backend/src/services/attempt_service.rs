@@ -1,14 +1,26 @@
 use crate::error::Result;
 use crate::models::test::Test;
 use crate::models::test_attempt::TestAttempt;
+use crate::utils::crypto::sha256_hex;
 use crate::utils::token::generate_access_token;
 use crate::dto::public_dto::{SaveAnswerRequest, SubmitTestRequest};
-use crate::models::question::Question;
-use rust_decimal::prelude::FromPrimitive;
+use crate::models::answer_log::AnswerLog;
+use crate::models::question::{MultipleChoiceDetails, Question};
+use crate::services::ai_service::AIService;
+use crate::services::code_runner_service::CodeRunnerService;
+use crate::services::geo_service::{is_suspicious_geo, GeoService};
+use crate::services::grading_service::{GradingResult, GradingService, PassingThresholds, SubScores};
+use crate::services::notification_service::NotificationService;
+use crate::services::onef_service::{OneFTestStatusEventData, OneFTestStatusPayload};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sqlx::PgPool;
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::{FromRow, PgPool, Row};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -25,6 +37,8 @@ impl AttemptService {
         candidate: InviteCandidate,
         expires_in_hours: i64,
         metadata: Option<serde_json::Value>,
+        language: Option<&str>,
+        source: InviteSource,
     ) -> Result<CreateInviteResult> {
         let pending_count: i64 = sqlx::query_scalar(
             r#"SELECT COUNT(*) FROM test_attempts WHERE candidate_email = $1 AND status = 'pending'"#
@@ -34,9 +48,7 @@ impl AttemptService {
         .await?;
 
         if pending_count > 0 {
-            return Err(crate::error::Error::BadRequest(
-                "Candidate already has a pending test invitation. They must start or complete existing tests before receiving new invitations.".to_string()
-            ));
+            return Err(crate::error::Error::pending_invite_exists());
         }
 
         let test = sqlx::query_as!(
@@ -50,6 +62,18 @@ impl AttemptService {
                 created_by, is_active, 
                 test_type, presentation_themes as "presentation_themes: serde_json::Value",
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at, updated_at
             FROM tests WHERE id = $1"#,
             test_id
@@ -57,28 +81,32 @@ impl AttemptService {
         .fetch_one(&self.pool)
         .await?;
 
+        if let Some(prerequisite_id) = test.prerequisite_test_id {
+            if !self.has_passed_test(prerequisite_id, &candidate.email).await? {
+                return Err(crate::error::Error::BadRequest(
+                    "Candidate has not passed the prerequisite test".to_string(),
+                ));
+            }
+        }
+
         let access_token = generate_access_token(32);
         let expires_at: DateTime<Utc> = Utc::now() + Duration::hours(expires_in_hours);
 
-        let mut questions_snapshot = test.questions.clone();
-        if test.test_type.as_deref() == Some("presentation") {
-            questions_snapshot = json!({
-                "test_type": "presentation",
-                "themes": test.presentation_themes,
-                "extra_info": test.presentation_extra_info
-            });
-        }
+        let (questions_snapshot, assigned_theme) = self.questions_snapshot(&test).await?;
+        let metadata = with_snapshot_hash(metadata, &questions_snapshot);
 
         let attempt = sqlx::query_as::<_, TestAttempt>(
             r#"
             INSERT INTO test_attempts (
                 test_id, candidate_external_id, candidate_name, candidate_email, candidate_telegram_id, candidate_phone,
                 access_token, expires_at, questions_snapshot, answers, score, max_score, percentage, passed,
-                started_at, completed_at, time_spent_seconds, status, ip_address, user_agent, tab_switches, suspicious_activity, metadata
+                started_at, completed_at, time_spent_seconds, status, ip_address, user_agent, tab_switches, suspicious_activity, metadata,
+                assigned_theme, language, source, organization_id
             ) VALUES (
                 $1, $2, $3, $4, $5, $6,
                 $7, $8, $9, NULL, NULL, NULL, NULL, NULL,
-                NULL, NULL, NULL, 'pending', NULL, NULL, 0, NULL, $10
+                NULL, NULL, NULL, 'pending', NULL, NULL, 0, NULL, $10,
+                $11, $12, $13, (SELECT organization_id FROM tests WHERE id = $1)
             )
             RETURNING *
             "#
@@ -93,6 +121,57 @@ impl AttemptService {
         .bind(expires_at)
         .bind(questions_snapshot)
         .bind(metadata)
+        .bind(assigned_theme)
+        .bind(crate::utils::strings::resolve_lang(language))
+        .bind(source.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.advance_candidate_status(&attempt.candidate_email, "test_assigned").await;
+
+        Ok(CreateInviteResult {
+            attempt_id: attempt.id,
+            access_token: attempt.access_token,
+            expires_at,
+            status: attempt.status,
+        })
+    }
+
+    /// Creates an attempt HR can take to see the test as candidates do. It
+    /// has no candidate, expires after `PREVIEW_EXPIRY_HOURS` and is deleted
+    /// by `check_deadlines` once `PREVIEW_RETENTION_HOURS` old.
+    pub async fn create_preview(&self, test_id: Uuid, org_id: Uuid) -> Result<CreateInviteResult> {
+        let test = crate::services::test_service::TestService::new(self.pool.clone())
+            .get_test_in_org(test_id, org_id)
+            .await?;
+
+        let access_token = generate_access_token(32);
+        let expires_at: DateTime<Utc> = Utc::now() + Duration::hours(PREVIEW_EXPIRY_HOURS);
+        let (questions_snapshot, assigned_theme) = self.questions_snapshot(&test).await?;
+        let metadata = with_snapshot_hash(None, &questions_snapshot);
+
+        let attempt = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            INSERT INTO test_attempts (
+                test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, tab_switches, metadata, assigned_theme, language, is_preview,
+                organization_id
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, 'pending', 0, $7, $8, $9, TRUE,
+                (SELECT organization_id FROM tests WHERE id = $1)
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(test.id)
+        .bind(PREVIEW_CANDIDATE_NAME)
+        .bind(PREVIEW_CANDIDATE_EMAIL)
+        .bind(access_token)
+        .bind(expires_at)
+        .bind(questions_snapshot)
+        .bind(metadata)
+        .bind(assigned_theme)
+        .bind(crate::utils::strings::DEFAULT_LANG)
         .fetch_one(&self.pool)
         .await?;
 
@@ -104,11 +183,89 @@ impl AttemptService {
         })
     }
 
+    /// The questions a new attempt is given, and for presentation tests the
+    /// theme it was assigned.
+    async fn questions_snapshot(
+        &self,
+        test: &Test,
+    ) -> Result<(serde_json::Value, Option<serde_json::Value>)> {
+        if test.test_type.as_deref() != Some("presentation") {
+            return Ok((test.questions.clone(), None));
+        }
+        let theme = self.pick_presentation_theme(test.id).await?;
+        let snapshot = json!({
+            "test_type": "presentation",
+            "themes": if theme.is_null() { json!([]) } else { json!([theme.clone()]) },
+            "extra_info": test.presentation_extra_info
+        });
+        Ok((snapshot, Some(theme).filter(|t| !t.is_null())))
+    }
+
+    pub async fn has_passed_test(&self, test_id: Uuid, candidate_email: &str) -> Result<bool> {
+        let passed: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM test_attempts
+                WHERE test_id = $1 AND candidate_email = $2
+                  AND completed_at IS NOT NULL AND passed = TRUE
+            )
+            "#,
+        )
+        .bind(test_id)
+        .bind(candidate_email)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(passed)
+    }
+
+    /// Picks the theme used least often among the test's recent attempts, so
+    /// candidates invited around the same time get different topics.
+    pub async fn pick_presentation_theme(&self, test_id: Uuid) -> Result<serde_json::Value> {
+        const RECENT_ATTEMPTS: i64 = 50;
+
+        let themes: Option<serde_json::Value> =
+            sqlx::query_scalar("SELECT presentation_themes FROM tests WHERE id = $1")
+                .bind(test_id)
+                .fetch_one(&self.pool)
+                .await?;
+        let themes: Vec<serde_json::Value> = themes
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        if themes.is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+
+        let recent: Vec<Option<serde_json::Value>> = sqlx::query_scalar(
+            r#"
+            SELECT assigned_theme FROM test_attempts
+            WHERE test_id = $1 AND assigned_theme IS NOT NULL AND NOT is_preview
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(test_id)
+        .bind(RECENT_ATTEMPTS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let theme = themes
+            .iter()
+            .min_by_key(|theme| recent.iter().flatten().filter(|used| used == theme).count())
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        Ok(theme)
+    }
+
     pub async fn get_attempt_and_test_by_token(&self, token: &str) -> Result<(TestAttempt, Test)> {
         let attempt = sqlx::query_as::<_, TestAttempt>(
-            r#"SELECT * FROM test_attempts WHERE access_token = $1"#
+            r#"
+            SELECT * FROM test_attempts
+            WHERE access_token = $1
+               OR (previous_access_token = $1 AND access_token_rotated_at > $2)
+            "#
         )
         .bind(token)
+        .bind(Utc::now() - Duration::seconds(TOKEN_ROTATION_GRACE_SECS))
         .fetch_one(&self.pool)
         .await?;
 
@@ -123,6 +280,18 @@ impl AttemptService {
                 created_by, is_active, 
                 test_type, presentation_themes as "presentation_themes: serde_json::Value",
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at, updated_at
             FROM tests WHERE id = $1"#,
             attempt.test_id
@@ -133,32 +302,183 @@ impl AttemptService {
         Ok((attempt, test))
     }
 
-    pub async fn start_attempt_by_token(&self, token: &str) -> Result<TestAttempt> {
+    /// Marks the attempt as started. The first start also records `client_ip`
+    /// and `user_agent` and geo-locates the IP in the background (see `record_geo`).
+    /// Every start replaces `access_token` with a fresh one, since the link
+    /// it came from may have leaked; the old token keeps working for
+    /// `TOKEN_ROTATION_GRACE_SECS` so requests already in flight still land.
+    /// A start made with that old token resumes without rotating again, so
+    /// a retried start doesn't strand the token it was sent with.
+    pub async fn start_attempt_by_token(
+        &self,
+        token: &str,
+        declaration_accepted: bool,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> Result<TestAttempt> {
         let (attempt, test) = self.get_attempt_and_test_by_token(token).await?;
 
-        let now = Utc::now();
-        let expires_candidate = now + Duration::minutes(test.duration_minutes as i64);
-        let new_expires = if expires_candidate < attempt.expires_at { expires_candidate } else { attempt.expires_at };
+        // Candidates resuming a started attempt, and HR previews, are never turned away.
+        let exempt = attempt.status == "in_progress" || attempt.is_preview;
+        let mut tx = self.pool.begin().await?;
+        if let (Some(cap), false) = (test.max_concurrent_takers, exempt) {
+            // Locking the test row makes capped starts count and start one at
+            // a time; otherwise simultaneous starts all see the same free slot.
+            sqlx::query("SELECT id FROM tests WHERE id = $1 FOR UPDATE")
+                .bind(test.id)
+                .execute(&mut *tx)
+                .await?;
+            let taking: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM test_attempts WHERE test_id = $1 AND status = 'in_progress' AND NOT is_preview",
+            )
+            .bind(test.id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if at_capacity(taking, cap) {
+                tracing::info!("Test {} is at capacity ({} of {} taking it)", test.id, taking, cap);
+                return Err(crate::error::Error::ServiceUnavailable(
+                    "Test is at capacity, please try again in a few minutes".to_string(),
+                ));
+            }
+        }
 
-        let updated = sqlx::query_as::<_, TestAttempt>(
+        // One guarded statement: the row lock serialises concurrent starts,
+        // so only the first one sets `started_at` and the deadline and sees a
+        // `pending` status, and a finished or expired attempt is never reopened.
+        let now = Utc::now();
+        let row = sqlx::query(
             r#"
-            UPDATE test_attempts
-            SET status = 'in_progress', started_at = COALESCE(started_at, $1), expires_at = $2
-            WHERE access_token = $3
-            RETURNING *
+            WITH prev AS (
+                SELECT id, status FROM test_attempts WHERE id = $3 FOR UPDATE
+            )
+            UPDATE test_attempts t
+            SET status = 'in_progress', started_at = COALESCE(t.started_at, $1),
+                expires_at = CASE WHEN t.started_at IS NULL
+                    THEN LEAST(t.expires_at, $1 + make_interval(mins => $2))
+                    ELSE t.expires_at END,
+                honesty_declaration_accepted = t.honesty_declaration_accepted OR $4,
+                ip_address = COALESCE(t.ip_address, $5),
+                user_agent = COALESCE(t.user_agent, $6),
+                previous_access_token = CASE WHEN t.access_token = $8 THEN t.access_token ELSE t.previous_access_token END,
+                access_token = CASE WHEN t.access_token = $8 THEN $7 ELSE t.access_token END,
+                access_token_rotated_at = CASE WHEN t.access_token = $8 THEN $1 ELSE t.access_token_rotated_at END
+            FROM prev
+            WHERE t.id = prev.id
+              AND prev.status IN ('pending', 'in_progress')
+              AND t.expires_at > $1
+            RETURNING t.*, prev.status AS previous_status
             "#
         )
         .bind(now)
-        .bind(new_expires)
+        .bind(test.duration_minutes)
+        .bind(attempt.id)
+        .bind(declaration_accepted)
+        .bind(client_ip.map(IpNetwork::from))
+        .bind(user_agent)
+        .bind(generate_access_token(32))
         .bind(token)
-        .fetch_one(&self.pool)
+        .fetch_optional(&mut *tx)
         .await?;
+        tx.commit().await?;
+
+        let Some(row) = row else {
+            let current = self.get_attempt_by_id(attempt.id).await?;
+            return Err(if !is_startable_status(&current.status) {
+                crate::error::Error::already_completed("This test has already been completed")
+            } else {
+                crate::error::Error::test_expired()
+            });
+        };
+        let updated = TestAttempt::from_row(&row)?;
+        let previous_status: String = row.try_get("previous_status")?;
+
+        if previous_status != updated.status {
+            self.push_onef_status(&updated).await;
+        }
+
+        if let (None, Some(ip)) = (attempt.ip_address, client_ip) {
+            if let Some(geo) = GeoService::from_config() {
+                let svc = self.clone();
+                let attempt_id = updated.id;
+                tokio::spawn(async move {
+                    if let Err(e) = svc.record_geo(attempt_id, ip, &geo).await {
+                        tracing::warn!("Geo lookup for attempt {} failed: {:?}", attempt_id, e);
+                    }
+                });
+            }
+        }
 
         Ok(updated)
     }
 
+    /// Fills in the client details if the start didn't capture them and flags
+    /// an `ip_change` when the submit comes from a different address.
+    async fn record_submit_client(
+        &self,
+        attempt: &TestAttempt,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> Result<()> {
+        let previous = attempt.ip_address.map(|net| net.ip());
+        let flag = ip_change_entry(previous, client_ip, Utc::now());
+        if let Some(entry) = &flag {
+            tracing::warn!(
+                "Attempt {} submitted from {} but started from {}",
+                attempt.id,
+                entry["submit_ip"],
+                entry["start_ip"]
+            );
+        }
+        sqlx::query(
+            r#"
+            UPDATE test_attempts
+            SET ip_address = COALESCE(ip_address, $2),
+                user_agent = COALESCE(user_agent, $3),
+                suspicious_activity = CASE
+                    WHEN $4::jsonb IS NULL THEN suspicious_activity
+                    ELSE COALESCE(suspicious_activity, '[]'::jsonb) || jsonb_build_array($4::jsonb)
+                END
+            WHERE id = $1
+            "#,
+        )
+        .bind(attempt.id)
+        .bind(client_ip.map(IpNetwork::from))
+        .bind(user_agent)
+        .bind(flag)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_geo(&self, attempt_id: Uuid, ip: IpAddr, geo: &GeoService) -> Result<()> {
+        let Some(location) = geo.lookup(ip).await? else {
+            return Ok(());
+        };
+        let expected = crate::config::get_config().expected_country.as_deref();
+        let suspicious = is_suspicious_geo(expected, &location);
+        if suspicious {
+            tracing::warn!(
+                "Attempt {} started from {} ({}), outside the expected country",
+                attempt_id,
+                location.country,
+                ip
+            );
+        }
+
+        sqlx::query(
+            "UPDATE test_attempts SET country = $2, city = $3, suspicious_geo = $4 WHERE id = $1",
+        )
+        .bind(attempt_id)
+        .bind(&location.country_code)
+        .bind(&location.city)
+        .bind(suspicious)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn save_answer_by_token(&self, token: &str, req: SaveAnswerRequest) -> Result<DateTime<Utc>> {
-        let (mut attempt, _test) = self.get_attempt_and_test_by_token(token).await?;
+        let (attempt, _test) = self.get_attempt_and_test_by_token(token).await?;
         let timestamp = Utc::now();
 
         sqlx::query!(
@@ -171,11 +491,6 @@ impl AttemptService {
         .execute(&self.pool)
         .await?;
 
-        let mut answers: Vec<serde_json::Value> = match attempt.answers.take() {
-            Some(v) => serde_json::from_value(v).unwrap_or_default(),
-            None => Vec::new(),
-        };
-
         let new_item = json!({
             "question_id": req.question_id,
             "answer": req.answer,
@@ -184,54 +499,78 @@ impl AttemptService {
             "answered_at": timestamp,
         });
 
-        if let Some(pos) = answers.iter().position(|a| a.get("question_id").and_then(|v| v.as_i64()) == Some(req.question_id as i64)) {
-            answers[pos] = new_item;
-        } else {
-            answers.push(new_item);
-        }
-
-        let answers_json = serde_json::to_value(answers)?;
-        sqlx::query!(
-            r#"UPDATE test_attempts SET answers = $1, updated_at = NOW() WHERE id = $2"#,
-            answers_json,
-            attempt.id
+        // Replace-or-append happens inside a single UPDATE so concurrent autosaves
+        // serialize on the row lock instead of overwriting each other's answers.
+        sqlx::query(
+            r#"
+            UPDATE test_attempts
+            SET answers = COALESCE(
+                    (SELECT jsonb_agg(elem)
+                     FROM jsonb_array_elements(COALESCE(answers, '[]'::jsonb)) AS elem
+                     WHERE (elem->>'question_id')::int IS DISTINCT FROM $2),
+                    '[]'::jsonb
+                ) || jsonb_build_array($1::jsonb),
+                answer_changes = answer_changes + CASE WHEN EXISTS (
+                    SELECT 1 FROM jsonb_array_elements(COALESCE(answers, '[]'::jsonb)) AS elem
+                    WHERE (elem->>'question_id')::int = $2
+                      AND elem->'answer' IS DISTINCT FROM $4::jsonb
+                ) THEN 1 ELSE 0 END,
+                updated_at = NOW()
+            WHERE id = $3
+            "#
         )
+        .bind(new_item)
+        .bind(req.question_id)
+        .bind(attempt.id)
+        .bind(&req.answer)
         .execute(&self.pool)
         .await?;
 
         Ok(timestamp)
     }
 
-    pub async fn submit_attempt_by_token(&self, token: &str, req: SubmitTestRequest) -> Result<(TestAttempt, f64, f64, f64, bool)> {
+    pub async fn submit_attempt_by_token(
+        &self,
+        token: &str,
+        req: SubmitTestRequest,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> Result<(TestAttempt, f64, f64, f64, bool)> {
         let (attempt, test) = self.get_attempt_and_test_by_token(token).await?;
+        self.record_submit_client(&attempt, client_ip, user_agent).await?;
 
         let status = req.status.clone().unwrap_or_else(|| "completed".to_string());
 
+        // A timed-out or already submitted attempt keeps its answers and grade.
         let answers_json = serde_json::to_value(&req.answers)?;
-        sqlx::query!(
-            r#"UPDATE test_attempts SET answers = $1 WHERE id = $2"#,
+        let stored = sqlx::query!(
+            r#"UPDATE test_attempts SET answers = $1 WHERE id = $2 AND status = 'in_progress'"#,
             answers_json,
             attempt.id
         )
         .execute(&self.pool)
         .await?;
+        if stored.rows_affected() == 0 {
+            return Err(crate::error::Error::already_completed("Test has already been submitted"));
+        }
+
+        let answers: Vec<serde_json::Value> = serde_json::from_value(answers_json).unwrap_or_default();
+        let AttemptGrade {
+            score: score_f,
+            max_score: max_score_f,
+            percentage,
+            passed,
+            needs_review,
+            graded,
+            sub_scores,
+        } = grade_answers(&attempt, &test, &answers).await?;
 
-        let questions: Vec<Question> = serde_json::from_value(test.questions.clone()).unwrap_or_default();
-        let answers: Vec<serde_json::Value> = serde_json::from_value(answers_json.clone()).unwrap_or_default();
-        let (earned_points, total_max_points, graded_answers, needs_review) = crate::services::grading_service::GradingService::grade_mcq_only(&questions, &answers);
-        
         let mut final_status = status.clone();
         if needs_review && final_status == "completed" {
             final_status = "needs_review".to_string();
         }
 
-        let score_f = earned_points as f64;
-        let max_score_f = total_max_points as f64;
-        let percentage = if max_score_f > 0.0 { (score_f / max_score_f) * 100.0 } else { 0.0 };
-        let passing_threshold = test.passing_score.to_string().parse::<f64>().unwrap_or(0.0);
-        let passed = percentage >= passing_threshold;
-
-        let graded_json = serde_json::to_value(graded_answers)?;
+        let graded_json = serde_json::to_value(graded)?;
         let now = Utc::now();
         let score_dec = Decimal::from_f64(score_f).unwrap_or_else(|| Decimal::new(0, 0));
         let max_score_dec = Decimal::from_f64(max_score_f).unwrap_or_else(|| Decimal::new(0, 0));
@@ -242,8 +581,9 @@ impl AttemptService {
             UPDATE test_attempts
             SET status = $8, completed_at = $1, 
                 time_spent_seconds = ROUND(EXTRACT(EPOCH FROM ($1 - started_at)))::integer,
-                score = $2, max_score = $3, percentage = $4, passed = $5, graded_answers = $6
-            WHERE id = $7
+                score = $2, max_score = $3, percentage = $4, passed = $5, graded_answers = $6,
+                mcq_score = $9, open_score = $10
+            WHERE id = $7 AND status = 'in_progress'
             RETURNING *
             "#
         )
@@ -255,12 +595,109 @@ impl AttemptService {
         .bind(graded_json)
         .bind(attempt.id)
         .bind(final_status)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(sub_scores.mcq.and_then(Decimal::from_f64))
+        .bind(sub_scores.open.and_then(Decimal::from_f64))
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| crate::error::Error::already_completed("Test has already been submitted"))?;
+
+        if !updated.is_preview && (updated.status == "completed" || updated.status == "needs_review") {
+            self.advance_candidate_status(&updated.candidate_email, "test_completed").await;
+        }
+        self.push_onef_status(&updated).await;
 
         Ok((updated, score_f, max_score_f, percentage, passed))
     }
 
+    async fn telegram_thread_id(&self, telegram_id: Option<i64>) -> Option<i64> {
+        crate::services::candidate_service::CandidateService::new(self.pool.clone())
+            .telegram_thread_id(telegram_id)
+            .await
+    }
+
+    async fn local_deadline(&self, telegram_id: Option<i64>, expires_at: DateTime<Utc>) -> String {
+        let timezone = crate::services::candidate_service::CandidateService::new(self.pool.clone())
+            .timezone_for_telegram(telegram_id)
+            .await;
+        crate::utils::time::format_deadline(expires_at, timezone.as_deref())
+    }
+
+    /// Builds the 1F test status push for `attempt`; `None` when no candidate
+    /// is registered under the attempt's email.
+    pub async fn onef_status_payload(&self, attempt: &TestAttempt) -> Result<Option<OneFTestStatusPayload>> {
+        let candidate_service = crate::services::candidate_service::CandidateService::new(self.pool.clone());
+        let Some(candidate) = candidate_service.get_by_email(&attempt.candidate_email).await? else {
+            return Ok(None);
+        };
+
+        let mut vacancy_id = attempt
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("vacancy_id").and_then(|v| v.as_i64()));
+        if vacancy_id.is_none() {
+            vacancy_id = candidate_service
+                .get_candidate_applications(candidate.id)
+                .await?
+                .first()
+                .map(|app| app.vacancy_id);
+        }
+
+        Ok(Some(OneFTestStatusPayload {
+            candidate_id: candidate.id,
+            test_id: attempt.test_id,
+            vacancy_id: vacancy_id.or(candidate.vacancy_id),
+            test_status: attempt.status.clone(),
+            event_date: Utc::now().to_rfc3339(),
+            event_data: onef_event_data(attempt, &crate::config::get_config().webapp_url),
+        }))
+    }
+
+    /// Queues a 1F push for the attempt's current status. The status change is
+    /// already stored, so failures here are only logged.
+    async fn push_onef_status(&self, attempt: &TestAttempt) {
+        let base_urls = &crate::config::get_config().onef_base_urls;
+        if base_urls.is_empty() || attempt.is_preview {
+            return;
+        }
+        let payload = match self.onef_status_payload(attempt).await {
+            Ok(Some(payload)) => payload,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to build 1F status push for attempt {}: {:?}", attempt.id, e);
+                return;
+            }
+        };
+        if let Err(e) = NotificationService::enqueue_onef_test_status(&self.pool, base_urls, &payload).await {
+            tracing::warn!("Failed to enqueue 1F status push for attempt {}: {:?}", attempt.id, e);
+        }
+    }
+
+    async fn advance_candidate_status(&self, candidate_email: &str, status: &str) {
+        let candidate_service = crate::services::candidate_service::CandidateService::new(self.pool.clone());
+        let candidate = match candidate_service.get_by_email(candidate_email).await {
+            Ok(Some(c)) => c,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to look up candidate {} for status update: {:?}", candidate_email, e);
+                return;
+            }
+        };
+        let result = if status == "test_completed" {
+            // Only close out the test stage; don't override statuses HR set manually.
+            if candidate.status != "test_assigned" {
+                return;
+            }
+            candidate_service.update_status(candidate.id, status.to_string(), false).await.map(Some)
+        } else {
+            candidate_service.advance_status(candidate.id, status).await
+        };
+        match result {
+            Ok(Some(_)) => tracing::info!("Candidate {} moved to '{}'", candidate.id, status),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to move candidate {} to '{}': {:?}", candidate.id, status, e),
+        }
+    }
+
     pub async fn submit_presentation_by_token(
         &self,
         token: &str,
@@ -290,6 +727,7 @@ impl AttemptService {
         .fetch_one(&self.pool)
         .await?;
 
+        self.push_onef_status(&updated).await;
         Ok(updated)
     }
 
@@ -304,21 +742,57 @@ impl AttemptService {
         Ok(attempt)
     }
 
+    /// [`Self::get_attempt_by_id`] for an HR request: attempts of other
+    /// organizations are `404`, like missing ones.
+    pub async fn get_attempt_in_org(&self, attempt_id: Uuid, org_id: Uuid) -> Result<TestAttempt> {
+        sqlx::query_as::<_, TestAttempt>(
+            "SELECT * FROM test_attempts WHERE id = $1 AND organization_id = $2",
+        )
+        .bind(attempt_id)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| crate::error::Error::NotFound("Test attempt not found".to_string()))
+    }
+
+    /// Every non-preview attempt made under `email`, oldest first, across
+    /// organizations.
+    pub async fn list_for_candidate_email(&self, email: &str) -> Result<Vec<TestAttempt>> {
+        let attempts = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            SELECT * FROM test_attempts
+            WHERE LOWER(candidate_email) = LOWER($1) AND NOT is_preview
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(attempts)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_attempts(
         &self,
         test_id: Option<Uuid>,
         candidate_email: Option<String>,
         status: Option<String>,
+        source: Option<InviteSource>,
         page: i64,
         limit: i64,
+        org_id: Uuid,
     ) -> Result<(Vec<TestAttempt>, i64)> {
         let offset = (page - 1) * limit;
+        let source = source.map(|s| s.as_str());
         let rows = sqlx::query_as::<_, TestAttempt>(
             r#"
             SELECT * FROM test_attempts
-            WHERE ($1::uuid IS NULL OR test_id = $1)
+            WHERE NOT is_preview
+              AND ($1::uuid IS NULL OR test_id = $1)
               AND ($2::text IS NULL OR candidate_email = $2)
               AND ($3::text IS NULL OR status = $3)
+              AND ($6::text IS NULL OR source = $6)
+              AND organization_id = $7
             ORDER BY created_at DESC
             LIMIT $4 OFFSET $5
             "#
@@ -328,17 +802,24 @@ impl AttemptService {
         .bind(status.clone())
         .bind(limit)
         .bind(offset)
+        .bind(source)
+        .bind(org_id)
         .fetch_all(&self.pool)
         .await?;
 
         let total = sqlx::query_scalar!(
             r#"SELECT COUNT(*) as "count!" FROM test_attempts
-               WHERE ($1::uuid IS NULL OR test_id = $1)
+               WHERE NOT is_preview
+                 AND ($1::uuid IS NULL OR test_id = $1)
                  AND ($2::text IS NULL OR candidate_email = $2)
-                 AND ($3::text IS NULL OR status = $3)"#,
+                 AND ($3::text IS NULL OR status = $3)
+                 AND ($4::text IS NULL OR source = $4)
+                 AND organization_id = $5"#,
             test_id,
             candidate_email,
-            status
+            status,
+            source,
+            org_id
         )
         .fetch_one(&self.pool)
         .await?;
@@ -346,8 +827,8 @@ impl AttemptService {
         Ok((rows, total))
     }
 
-    pub async fn delete_attempt(&self, attempt_id: Uuid) -> Result<()> {
-        let attempt = self.get_attempt_by_id(attempt_id).await?;
+    pub async fn delete_attempt(&self, attempt_id: Uuid, org_id: Uuid) -> Result<()> {
+        let attempt = self.get_attempt_in_org(attempt_id, org_id).await?;
         if attempt.status != "pending" {
             return Err(crate::error::Error::BadRequest(format!(
                 "Cannot delete invitation with status '{}'. Only 'pending' invitations can be removed.",
@@ -396,10 +877,17 @@ impl AttemptService {
         .fetch_one(&self.pool)
         .await?;
 
+        self.push_onef_status(&attempt).await;
         Ok(attempt)
     }
 
-    pub async fn grade_answer(&self, attempt_id: Uuid, question_id: i32, is_correct: bool) -> Result<TestAttempt> {
+    pub async fn grade_answer(
+        &self,
+        attempt_id: Uuid,
+        question_id: i32,
+        is_correct: bool,
+        comment: Option<&str>,
+    ) -> Result<TestAttempt> {
         let attempt = self.get_attempt_by_id(attempt_id).await?;
         let graded_val = attempt.graded_answers.clone().unwrap_or_else(|| serde_json::json!([]));
         let mut graded_answers: Vec<serde_json::Value> = serde_json::from_value(graded_val).unwrap_or_default();
@@ -417,6 +905,9 @@ impl AttemptService {
                 ans["points_earned"] = serde_json::json!(earned_pts);
                 ans["is_correct"] = serde_json::json!(is_correct);
                 ans["needs_review"] = serde_json::json!(false);
+                if let Some(comment) = comment.map(str::trim).filter(|c| !c.is_empty()) {
+                    ans["reviewer_comment"] = serde_json::json!(comment);
+                }
                 found = true;
             }
             
@@ -438,19 +929,28 @@ impl AttemptService {
             ans.get("needs_review").and_then(|v| v.as_bool()).unwrap_or(false)
         });
 
-        let test = sqlx::query!("SELECT passing_score FROM tests WHERE id = $1", attempt.test_id)
-            .fetch_one(&self.pool)
-            .await?;
-        
-        let passing_threshold = test.passing_score;
-        let passed = percentage >= passing_threshold;
+        let test = sqlx::query!(
+            "SELECT passing_score, passing_score_mcq, passing_score_open FROM tests WHERE id = $1",
+            attempt.test_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let thresholds = PassingThresholds::from_decimals(
+            test.passing_score,
+            test.passing_score_mcq,
+            test.passing_score_open,
+        );
+        let sub_scores = GradingService::sub_scores(&graded_answers);
+        let passed = thresholds.passes(percentage.to_f64().unwrap_or(0.0), sub_scores);
 
         let status = if still_needs_review { "needs_review" } else { "completed" };
 
         let updated = sqlx::query_as::<_, TestAttempt>(
             r#"
             UPDATE test_attempts
-            SET status = $2, graded_answers = $3, score = $4, max_score = $5, percentage = $6, passed = $7, updated_at = NOW()
+            SET status = $2, graded_answers = $3, score = $4, max_score = $5, percentage = $6, passed = $7,
+                mcq_score = $8, open_score = $9, updated_at = NOW()
             WHERE id = $1
             RETURNING *
             "#
@@ -462,102 +962,864 @@ impl AttemptService {
         .bind(max_score)
         .bind(percentage)
         .bind(passed)
+        .bind(sub_scores.mcq.and_then(Decimal::from_f64))
+        .bind(sub_scores.open.and_then(Decimal::from_f64))
         .fetch_one(&self.pool)
         .await?;
 
+        if updated.status != attempt.status {
+            self.push_onef_status(&updated).await;
+        }
         Ok(updated)
     }
 
-    pub async fn heartbeat(&self, token: &str) -> Result<()> {
-        let now = Utc::now();
-        sqlx::query!(
-            "UPDATE test_attempts SET last_heartbeat_at = $1 WHERE access_token = $2",
-            now,
-            token
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| crate::error::Error::Internal(format!("Failed to update heartbeat: {}", e)))?;
-        Ok(())
-    }
-
-    pub async fn check_deadlines(&self, notification_service: &crate::services::notification_service::NotificationService) -> Result<()> {
-        let now = Utc::now();
-
-        let warning_threshold = now + Duration::hours(1);
-        let warnings = sqlx::query_as::<_, TestAttempt>(
+    /// needs_review attempts, oldest submission first.
+    pub async fn list_review_queue(
+        &self,
+        assignment: ReviewAssignment,
+        org_id: Uuid,
+        page: i64,
+        limit: i64,
+    ) -> Result<(Vec<TestAttempt>, i64)> {
+        let offset = (page - 1) * limit;
+        let (unassigned_only, reviewer) = match assignment {
+            ReviewAssignment::Mine(reviewer) => (false, Some(reviewer)),
+            ReviewAssignment::Unassigned => (true, None),
+            ReviewAssignment::All => (false, None),
+        };
+        let rows = sqlx::query_as::<_, TestAttempt>(
             r#"
-            SELECT ta.* 
-            FROM test_attempts ta
-            JOIN tests t ON ta.test_id = t.id
-            WHERE ta.status = 'in_progress' 
-              AND t.test_type = 'presentation'
-              AND ta.expires_at <= $1
-              AND ta.deadline_notified = FALSE
-            "#
+            SELECT * FROM test_attempts
+            WHERE status = 'needs_review' AND NOT is_preview
+              AND (NOT $1 OR reviewed_by IS NULL)
+              AND ($2::uuid IS NULL OR reviewed_by = $2)
+              AND organization_id = $5
+            ORDER BY completed_at ASC NULLS LAST, created_at ASC
+            LIMIT $3 OFFSET $4
+            "#,
         )
-        .bind(warning_threshold)
+        .bind(unassigned_only)
+        .bind(reviewer)
+        .bind(limit)
+        .bind(offset)
+        .bind(org_id)
         .fetch_all(&self.pool)
         .await?;
 
-        for attempt in warnings {
-            let payload = json!({
-                "event": "deadline_warning",
-                "attempt_id": attempt.id,
-                "candidate_name": attempt.candidate_name,
-                "candidate_telegram_id": attempt.candidate_telegram_id,
-                "expires_at": attempt.expires_at,
-            });
-            if let Err(e) = notification_service.enqueue_webhook("deadline_warning", &payload).await {
-                tracing::error!("Failed to enqueue deadline warning: {:?}", e);
-            } else {
-                sqlx::query!("UPDATE test_attempts SET deadline_notified = TRUE WHERE id = $1", attempt.id)
-                    .execute(&self.pool)
-                    .await?;
-            }
-        }
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM test_attempts
+               WHERE status = 'needs_review' AND NOT is_preview
+                 AND (NOT $1 OR reviewed_by IS NULL)
+                 AND ($2::uuid IS NULL OR reviewed_by = $2)
+                 AND organization_id = $3"#,
+        )
+        .bind(unassigned_only)
+        .bind(reviewer)
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
 
-        sqlx::query!(
+        Ok((rows, total))
+    }
+
+    /// Assigns a needs_review attempt to `reviewer`. The conditional UPDATE
+    /// serialises concurrent claims, so only one reviewer can win.
+    pub async fn claim_for_review(&self, attempt_id: Uuid, org_id: Uuid, reviewer: Uuid) -> Result<TestAttempt> {
+        let claimed = sqlx::query_as::<_, TestAttempt>(
             r#"
             UPDATE test_attempts
-            SET status = 'timeout', 
-                completed_at = expires_at,
-                updated_at = NOW(),
-                score = COALESCE(score, 0),
-                max_score = COALESCE(max_score, 0),
-                percentage = COALESCE(percentage, 0),
-                passed = FALSE
-            WHERE status IN ('pending', 'in_progress')
-              AND expires_at <= $1
+            SET reviewed_by = $2, claimed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'needs_review' AND organization_id = $3
+              AND (reviewed_by IS NULL OR reviewed_by = $2)
+            RETURNING *
             "#,
-            now
         )
-        .execute(&self.pool)
+        .bind(attempt_id)
+        .bind(reviewer)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
         .await?;
+        if let Some(attempt) = claimed {
+            return Ok(attempt);
+        }
 
-        let abandon_threshold = now - Duration::minutes(2);
+        let attempt = self.get_attempt_in_org(attempt_id, org_id).await?;
+        check_review_claim(&attempt, Some(reviewer), false)?;
+        Err(not_in_review(&attempt))
+    }
+
+    /// Drops `reviewer`'s claim. Releasing an unclaimed attempt is a no-op.
+    pub async fn release_review(&self, attempt_id: Uuid, org_id: Uuid, reviewer: Uuid) -> Result<TestAttempt> {
+        let released = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            UPDATE test_attempts
+            SET reviewed_by = NULL, claimed_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND status = 'needs_review' AND reviewed_by = $2 AND organization_id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(attempt_id)
+        .bind(reviewer)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(attempt) = released {
+            return Ok(attempt);
+        }
+
+        let attempt = self.get_attempt_in_org(attempt_id, org_id).await?;
+        check_review_claim(&attempt, Some(reviewer), false)?;
+        if attempt.status != "needs_review" {
+            return Err(not_in_review(&attempt));
+        }
+        Ok(attempt)
+    }
+
+    /// Rejects grading an attempt that another reviewer has claimed, or one
+    /// of another organization (`404`).
+    pub async fn ensure_can_grade(
+        &self,
+        attempt_id: Uuid,
+        org_id: Uuid,
+        reviewer: Option<Uuid>,
+        force: bool,
+    ) -> Result<()> {
+        let attempt = self.get_attempt_in_org(attempt_id, org_id).await?;
+        check_review_claim(&attempt, reviewer, force)
+    }
+
+    /// Returns claims older than `ttl_hours` to the unassigned queue.
+    pub async fn release_expired_claims(&self, ttl_hours: i32, now: DateTime<Utc>) -> Result<u64> {
+        let released = sqlx::query(
+            r#"
+            UPDATE test_attempts
+            SET reviewed_by = NULL, claimed_at = NULL, updated_at = NOW()
+            WHERE status = 'needs_review'
+              AND claimed_at IS NOT NULL
+              AND claimed_at <= $1 - make_interval(hours => $2)
+            "#,
+        )
+        .bind(now)
+        .bind(ttl_hours)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        if released > 0 {
+            tracing::info!("Released {} expired review claims", released);
+        }
+        Ok(released)
+    }
+
+    /// Fixes the answer key of `test_id` and re-grades every completed,
+    /// needs_review or timed-out attempt against its own snapshot, all in one
+    /// transaction. Attempts whose pass/fail flips get a `grade_revised`
+    /// webhook queued in the same transaction.
+    pub async fn regrade_test(
+        &self,
+        test_id: Uuid,
+        org_id: Uuid,
+        corrections: &BTreeMap<i32, MultipleChoiceDetails>,
+    ) -> Result<RegradeSummary> {
+        if corrections.is_empty() {
+            return Err(crate::error::Error::BadRequest("No corrections given".into()));
+        }
+        for (question_id, details) in corrections {
+            if details.options.len() < 2 {
+                return Err(crate::error::Error::BadRequest(format!(
+                    "Question {} needs at least two options",
+                    question_id
+                )));
+            }
+            if details.correct_answer < 0 || details.correct_answer as usize >= details.options.len() {
+                return Err(crate::error::Error::BadRequest(format!(
+                    "Question {}: correct_answer {} is out of range",
+                    question_id, details.correct_answer
+                )));
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        #[allow(clippy::type_complexity)]
+        let (title, questions_json, passing_score, passing_score_mcq, passing_score_open, test_type): (
+            String,
+            serde_json::Value,
+            Decimal,
+            Option<Decimal>,
+            Option<Decimal>,
+            Option<String>,
+        ) = sqlx::query_as(
+            "SELECT title, questions, passing_score, passing_score_mcq, passing_score_open, test_type FROM tests WHERE id = $1 AND organization_id = $2 FOR UPDATE",
+        )
+        .bind(test_id)
+        .bind(org_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| crate::error::Error::NotFound("Test not found".into()))?;
+
+        if test_type.as_deref() == Some("presentation") {
+            return Err(crate::error::Error::BadRequest(
+                "Presentation tests have no answer key to correct".into(),
+            ));
+        }
+
+        let thresholds = PassingThresholds::from_decimals(passing_score, passing_score_mcq, passing_score_open);
+        let mut questions: Vec<Question> = serde_json::from_value(questions_json)?;
+        let patched = GradingService::patch_answer_key(&mut questions, corrections);
+        let rejected: Vec<i32> = corrections.keys().filter(|id| !patched.contains(*id)).copied().collect();
+        if !rejected.is_empty() {
+            return Err(crate::error::Error::BadRequest(format!(
+                "Not multiple-choice questions of this test: {:?}",
+                rejected
+            )));
+        }
+
+        sqlx::query("UPDATE tests SET questions = $1, updated_at = NOW() WHERE id = $2")
+            .bind(serde_json::to_value(&questions)?)
+            .bind(test_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let attempts = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            SELECT * FROM test_attempts
+            WHERE test_id = $1 AND status IN ('completed', 'needs_review', 'timeout') AND NOT is_preview
+            ORDER BY completed_at NULLS LAST, id
+            FOR UPDATE
+            "#,
+        )
+        .bind(test_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let webhook_url = crate::config::get_config().telegram_bot_webhook_url.clone();
+        let now = Utc::now();
+        let mut summary = RegradeSummary::default();
+
+        for attempt in attempts {
+            let Ok(mut snapshot) = serde_json::from_value::<Vec<Question>>(attempt.questions_snapshot.clone()) else {
+                tracing::warn!("Skipping regrade of attempt {}: snapshot is not a question list", attempt.id);
+                continue;
+            };
+            let affected = GradingService::patch_answer_key(&mut snapshot, corrections);
+            if affected.is_empty() {
+                continue;
+            }
+
+            let answers: Vec<serde_json::Value> = attempt
+                .answers
+                .clone()
+                .and_then(|a| serde_json::from_value(a).ok())
+                .unwrap_or_default();
+            let mut graded: Vec<serde_json::Value> = attempt
+                .graded_answers
+                .clone()
+                .and_then(|g| serde_json::from_value(g).ok())
+                .unwrap_or_default();
+            GradingService::regrade_questions(&snapshot, &answers, &mut graded, &affected);
+
+            let (earned, max) = GradingService::totals(&graded);
+            let score = Decimal::from(earned);
+            let max_score = Decimal::from(max);
+            let percentage = if max_score > Decimal::ZERO {
+                (score / max_score * Decimal::new(100, 0)).round_dp(2)
+            } else {
+                Decimal::ZERO
+            };
+            let sub_scores = GradingService::sub_scores(&graded);
+            let passed = thresholds.passes(percentage.to_f64().unwrap_or(0.0), sub_scores);
+
+            let event = json!({
+                "type": "regrade",
+                "at": now,
+                "questions": affected,
+                "score_before": attempt.score,
+                "score_after": score,
+                "percentage_before": attempt.percentage,
+                "percentage_after": percentage,
+                "passed_before": attempt.passed,
+                "passed_after": passed,
+            });
+            let metadata = append_regrade_event(attempt.metadata.clone(), event);
+
+            sqlx::query(
+                r#"
+                UPDATE test_attempts
+                SET questions_snapshot = $2, graded_answers = $3, score = $4, max_score = $5,
+                    percentage = $6, passed = $7, metadata = $8, mcq_score = $9, open_score = $10,
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(attempt.id)
+            .bind(serde_json::to_value(&snapshot)?)
+            .bind(serde_json::to_value(&graded)?)
+            .bind(score)
+            .bind(max_score)
+            .bind(percentage)
+            .bind(passed)
+            .bind(metadata)
+            .bind(sub_scores.mcq.and_then(Decimal::from_f64))
+            .bind(sub_scores.open.and_then(Decimal::from_f64))
+            .execute(&mut *tx)
+            .await?;
+
+            summary.attempts_regraded += 1;
+            if attempt.passed != Some(passed) {
+                let webhook = crate::dto::webhook_dto::GradeRevisedWebhook {
+                    event: "grade_revised".to_string(),
+                    attempt_id: attempt.id,
+                    candidate: crate::dto::webhook_dto::WebhookCandidate {
+                        name: attempt.candidate_name.clone(),
+                        telegram_id: attempt.candidate_telegram_id,
+                        telegram_thread_id: self.telegram_thread_id(attempt.candidate_telegram_id).await,
+                    },
+                    test: crate::dto::webhook_dto::WebhookTest { title: title.clone() },
+                    previous_passed: attempt.passed,
+                    passed,
+                    percentage: percentage.to_f64().unwrap_or(0.0),
+                };
+                // One notification per attempt and regrade run.
+                let dedup_key = format!("{}:{}", attempt.id, now.to_rfc3339());
+                NotificationService::enqueue_webhook_in(
+                    &mut tx,
+                    &webhook_url,
+                    "grade_revised",
+                    &serde_json::to_value(&webhook)?,
+                    Some(&dedup_key),
+                )
+                .await?;
+
+                summary.pass_changes.push(PassChange {
+                    attempt_id: attempt.id,
+                    before: attempt.passed,
+                    after: passed,
+                });
+            }
+        }
+
+        tx.commit().await?;
+        tracing::info!(
+            "Regraded {} attempts of test {} ({} pass/fail changes)",
+            summary.attempts_regraded,
+            test_id,
+            summary.pass_changes.len()
+        );
+        Ok(summary)
+    }
+
+    pub async fn extend_expiry(&self, attempt_id: Uuid, extra_minutes: i64) -> Result<TestAttempt> {
+        let attempt = self.get_attempt_by_id(attempt_id).await?;
+        if attempt.status != "in_progress" && attempt.status != "pending" {
+            return Err(crate::error::Error::BadRequest(format!(
+                "Cannot extend attempt with status '{}'. Only 'pending' or 'in_progress' attempts can be extended.",
+                attempt.status
+            )));
+        }
+
+        let mut new_expires = attempt.expires_at + Duration::minutes(extra_minutes);
+        if let Some(started_at) = attempt.started_at {
+            let hard_limit = started_at + Duration::hours(24);
+            if new_expires > hard_limit {
+                new_expires = hard_limit;
+            }
+        }
+        if new_expires <= attempt.expires_at {
+            return Err(crate::error::Error::BadRequest(
+                "Attempt already reached the maximum of 24 hours after start".to_string(),
+            ));
+        }
+
+        // A started attempt's window is capped by the duration as well, so
+        // the added time is recorded for `attempt_deadline`; before the start
+        // only the invite's expiry moves.
+        let added_seconds = if attempt.started_at.is_some() {
+            (new_expires - attempt.expires_at).num_seconds() as i32
+        } else {
+            0
+        };
+        let updated = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            UPDATE test_attempts
+            SET expires_at = $2, extension_seconds = extension_seconds + $3,
+                deadline_notified = FALSE, updated_at = NOW()
+            WHERE id = $1 AND status IN ('pending', 'in_progress')
+            RETURNING *
+            "#
+        )
+        .bind(attempt_id)
+        .bind(new_expires)
+        .bind(added_seconds)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| crate::error::Error::BadRequest("Attempt is no longer active".to_string()))?;
+
+        Ok(updated)
+    }
+
+    /// Seconds the candidate has left on `attempt`; see `time_remaining_at`.
+    /// A reconnect after more than the heartbeat grace period is logged.
+    pub fn get_current_time_remaining(attempt: &TestAttempt, duration_minutes: i32) -> i64 {
+        let now = Utc::now();
+        let gap = offline_gap(attempt, now);
+        if attempt.status == "in_progress" && gap > Duration::zero() {
+            tracing::info!(
+                "Attempt {} resumed after {}s offline beyond the grace period",
+                attempt.id,
+                gap.num_seconds()
+            );
+        }
+        time_remaining_at(attempt, duration_minutes, now)
+    }
+
+    /// Credits the gap between the two latest heartbeats, if it was long
+    /// enough to count as a lost connection, to `interruption_seconds` and
+    /// logs it as `connection_loss`. The gap is consumed either way, so a
+    /// repeated status check never counts it twice.
+    pub async fn record_connection_gap(&self, attempt: TestAttempt, max_grace_seconds: i32) -> Result<TestAttempt> {
+        if attempt.status != "in_progress" {
+            return Ok(attempt);
+        }
+        let (Some(previous), Some(latest)) = (attempt.previous_heartbeat_at, attempt.last_heartbeat_at) else {
+            return Ok(attempt);
+        };
+        let Some(gap) = heartbeat_gap(previous, latest) else {
+            return Ok(attempt);
+        };
+        let interruption = accumulate_interruption(attempt.interruption_seconds, gap, max_grace_seconds);
+        let entry = connection_loss_entry(previous, latest, gap, interruption - attempt.interruption_seconds);
+
+        let updated = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            UPDATE test_attempts
+            SET interruption_seconds = $2,
+                previous_heartbeat_at = last_heartbeat_at,
+                suspicious_activity = COALESCE(suspicious_activity, '[]'::jsonb) || jsonb_build_array($3::jsonb),
+                updated_at = NOW()
+            WHERE id = $1 AND previous_heartbeat_at = $4 AND last_heartbeat_at = $5
+            RETURNING *
+            "#,
+        )
+        .bind(attempt.id)
+        .bind(interruption)
+        .bind(&entry)
+        .bind(previous)
+        .bind(latest)
+        .fetch_optional(&self.pool)
+        .await?;
+        match updated {
+            Some(updated) => {
+                tracing::info!(
+                    "Attempt {} lost connection for {}s; {}s of grace used",
+                    updated.id,
+                    gap,
+                    updated.interruption_seconds
+                );
+                Ok(updated)
+            }
+            // Another request consumed the gap, or a new heartbeat moved on.
+            None => self.get_attempt_by_id(attempt.id).await,
+        }
+    }
+
+    pub async fn heartbeat(&self, token: &str) -> Result<()> {
+        let now = Utc::now();
         sqlx::query!(
             r#"
-            UPDATE test_attempts ta
-            SET status = 'escaped',
-                completed_at = $1,
-                updated_at = $1
-            FROM tests t
-            WHERE ta.test_id = t.id
-              AND ta.status = 'in_progress'
-              AND t.test_type != 'presentation'
-              AND ta.last_heartbeat_at IS NOT NULL
-              AND ta.last_heartbeat_at < $2
+            UPDATE test_attempts SET previous_heartbeat_at = last_heartbeat_at, last_heartbeat_at = $1
+            WHERE access_token = $2
+               OR (previous_access_token = $2 AND access_token_rotated_at > $3)
             "#,
             now,
-            abandon_threshold
+            token,
+            now - Duration::seconds(TOKEN_ROTATION_GRACE_SECS)
         )
         .execute(&self.pool)
+        .await
+        .map_err(|e| crate::error::Error::Internal(format!("Failed to update heartbeat: {}", e)))?;
+        Ok(())
+    }
+
+    /// Queues a Telegram reminder for each `reminder_hours_before` offset an
+    /// open attempt has reached. Offsets are claimed in `reminders_sent` before
+    /// queuing, so a reminder is never sent twice.
+    pub async fn send_due_reminders(&self, notification_service: &NotificationService, now: DateTime<Utc>) -> Result<usize> {
+        let rows = sqlx::query_as::<_, ReminderRow>(
+            r#"
+            SELECT ta.id, ta.candidate_name, ta.candidate_telegram_id, ta.expires_at,
+                   ta.reminders_sent, t.reminder_hours_before, t.title
+            FROM test_attempts ta
+            JOIN tests t ON ta.test_id = t.id
+            WHERE ta.status IN ('pending', 'in_progress')
+              AND NOT ta.is_preview
+              AND ta.candidate_telegram_id IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM candidates c
+                  WHERE c.telegram_id = ta.candidate_telegram_id AND c.telegram_unreachable
+              )
+              AND ta.expires_at > $1
+              AND cardinality(t.reminder_hours_before) > 0
+              AND ta.expires_at <= $1 + make_interval(hours => (SELECT MAX(h) FROM unnest(t.reminder_hours_before) AS h))
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
         .await?;
 
+        let mut sent = 0;
+        for row in rows {
+            let already: Vec<i32> = serde_json::from_value(row.reminders_sent).unwrap_or_default();
+            let due = due_reminder_offsets(&row.reminder_hours_before, &already, row.expires_at, now);
+            if due.is_empty() {
+                continue;
+            }
+
+            let claimed = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                UPDATE test_attempts
+                SET reminders_sent = reminders_sent || $2
+                WHERE id = $1
+                  AND NOT EXISTS (
+                      SELECT 1 FROM jsonb_array_elements(reminders_sent) AS sent
+                      WHERE sent::int = ANY($3)
+                  )
+                RETURNING id
+                "#
+            )
+            .bind(row.id)
+            .bind(json!(due))
+            .bind(&due)
+            .fetch_optional(&self.pool)
+            .await?;
+            if claimed.is_none() {
+                continue;
+            }
+
+            let hours_left = hours_until(row.expires_at, now);
+            let reminder = crate::dto::webhook_dto::TestReminderWebhook {
+                event: "test_reminder".to_string(),
+                attempt_id: row.id,
+                candidate: crate::dto::webhook_dto::WebhookCandidate {
+                    name: row.candidate_name,
+                    telegram_id: row.candidate_telegram_id,
+                    telegram_thread_id: self.telegram_thread_id(row.candidate_telegram_id).await,
+                },
+                test: crate::dto::webhook_dto::WebhookTest { title: row.title },
+                hours_left,
+                expires_at: row.expires_at,
+                expires_at_local: self.local_deadline(row.candidate_telegram_id, row.expires_at).await,
+                message: reminder_message(hours_left),
+            };
+            let payload = serde_json::to_value(&reminder)?;
+            // One reminder per attempt and offset, even if two sweeps overlap.
+            let dedup_key = format!("{}:{}", row.id, due.iter().min().copied().unwrap_or_default());
+            match notification_service
+                .enqueue_webhook("test_reminder", &payload, Some(&dedup_key))
+                .await
+            {
+                Ok(_) => sent += 1,
+                Err(e) => tracing::error!("Failed to enqueue reminder for attempt {}: {:?}", row.id, e),
+            }
+        }
+        Ok(sent)
+    }
+
+    pub async fn check_deadlines(&self, notification_service: &NotificationService) -> Result<()> {
+        let now = Utc::now();
+
+        if let Err(e) = self.send_due_reminders(notification_service, now).await {
+            tracing::error!("Failed to send test reminders: {:?}", e);
+        }
+
+        let warning_threshold = now + Duration::hours(1);
+        let warnings = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            SELECT ta.* 
+            FROM test_attempts ta
+            JOIN tests t ON ta.test_id = t.id
+            WHERE ta.status = 'in_progress' 
+              AND t.test_type = 'presentation'
+              AND ta.expires_at <= $1
+              AND ta.deadline_notified = FALSE
+              AND NOT ta.is_preview
+            "#
+        )
+        .bind(warning_threshold)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for attempt in warnings {
+            let payload = json!({
+                "event": "deadline_warning",
+                "attempt_id": attempt.id,
+                "candidate_name": attempt.candidate_name,
+                "candidate_telegram_id": attempt.candidate_telegram_id,
+                "candidate_telegram_thread_id": self.telegram_thread_id(attempt.candidate_telegram_id).await,
+                "expires_at": attempt.expires_at,
+                "expires_at_local": self.local_deadline(attempt.candidate_telegram_id, attempt.expires_at).await,
+            });
+            if let Err(e) = notification_service
+                .enqueue_webhook("deadline_warning", &payload, Some(&attempt.id.to_string()))
+                .await
+            {
+                tracing::error!("Failed to enqueue deadline warning: {:?}", e);
+            } else {
+                sqlx::query!("UPDATE test_attempts SET deadline_notified = TRUE WHERE id = $1", attempt.id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        let mut expiring: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM test_attempts
+            WHERE status IN ('pending', 'in_progress')
+              AND expires_at + make_interval(secs => interruption_seconds) <= $1
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // `expires_at` is capped to the duration at start, but the duration may
+        // have been shortened since; time out by the same clock `get_status` shows.
+        // The predicate is `attempt_deadline`'s; LEAST skips a NULL start.
+        let over_duration: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT ta.id
+            FROM test_attempts ta
+            JOIN tests t ON ta.test_id = t.id
+            WHERE ta.status = 'in_progress'
+              AND t.test_type IS DISTINCT FROM 'presentation'
+              AND LEAST(
+                      ta.started_at + make_interval(mins => t.duration_minutes, secs => ta.extension_seconds),
+                      ta.expires_at
+                  ) + make_interval(secs => ta.interruption_seconds) <= $1
+            "#
+        )
+        .bind(now - Duration::seconds(HEARTBEAT_GRACE_PERIOD_SECS))
+        .fetch_all(&self.pool)
+        .await?;
+        for id in over_duration {
+            if !expiring.contains(&id) {
+                expiring.push(id);
+            }
+        }
+
+        // Grading may call the AI for open answers, so a batch is graded
+        // concurrently and a failing attempt is left for the next run.
+        let mut timed_out = Vec::new();
+        for batch in expiring.chunks(TIMEOUT_BATCH_SIZE) {
+            let results = futures_util::future::join_all(
+                batch.iter().map(|&id| self.time_out_attempt(id, now, notification_service)),
+            )
+            .await;
+            for (id, result) in batch.iter().zip(results) {
+                match result {
+                    Ok(Some(attempt)) => timed_out.push(attempt),
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("Failed to time out attempt {}: {:?}", id, e),
+                }
+            }
+        }
+
+        let abandon_threshold = now - Duration::minutes(2);
+        let silent: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT ta.id
+            FROM test_attempts ta
+            JOIN tests t ON ta.test_id = t.id
+            WHERE ta.status = 'in_progress'
+              AND t.test_type != 'presentation'
+              AND ta.last_heartbeat_at IS NOT NULL
+              AND ta.last_heartbeat_at < $1
+            "#
+        )
+        .bind(abandon_threshold)
+        .fetch_all(&self.pool)
+        .await?;
+        // A lost heartbeat alone is often just a slow network; candidates
+        // still saving answers are left running.
+        let mut abandoned = Vec::new();
+        for id in silent {
+            if !self.was_recently_active(id, ESCAPE_ACTIVITY_WINDOW_MINUTES).await? {
+                abandoned.push(id);
+            }
+        }
+        let escaped = if abandoned.is_empty() {
+            Vec::new()
+        } else {
+            sqlx::query_as::<_, TestAttempt>(
+                r#"
+                UPDATE test_attempts
+                SET status = 'escaped',
+                    completed_at = $1,
+                    updated_at = $1
+                WHERE id = ANY($2)
+                  AND status = 'in_progress'
+                  AND last_heartbeat_at < $3
+                RETURNING *
+                "#
+            )
+            .bind(now)
+            .bind(&abandoned)
+            .bind(abandon_threshold)
+            .fetch_all(&self.pool)
+            .await?
+        };
+
+        for attempt in timed_out.iter().chain(escaped.iter()) {
+            self.push_onef_status(attempt).await;
+        }
+
+        let removed = self.delete_old_previews(now).await?;
+        if removed > 0 {
+            tracing::info!("Deleted {} expired test preview(s)", removed);
+        }
+
+        let distribution = self.get_status_distribution().await?;
+        for (status, count) in distribution {
+            metrics::gauge!(crate::middleware::metrics::TEST_ATTEMPTS_BY_STATUS, "status" => status)
+                .set(count as f64);
+        }
+
         Ok(())
     }
 
+    /// Closes an expired attempt, scoring the answers the candidate saved the
+    /// same way a submission would. Answers that still need a reviewer send it
+    /// to `needs_review` with `timed_out` set in its metadata; otherwise it ends
+    /// as `timeout`. Attempts that were never started, and presentations, are
+    /// closed with a zero score. `None` when the attempt finished meanwhile.
+    async fn time_out_attempt(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        notification_service: &NotificationService,
+    ) -> Result<Option<TestAttempt>> {
+        let Some(attempt) = sqlx::query_as::<_, TestAttempt>(
+            "SELECT * FROM test_attempts WHERE id = $1 AND status IN ('pending', 'in_progress')",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let test = crate::services::test_service::TestService::new(self.pool.clone())
+            .get_test_by_id(attempt.test_id)
+            .await?;
+
+        if attempt.status != "in_progress" || test.test_type.as_deref() == Some("presentation") {
+            let closed = sqlx::query_as::<_, TestAttempt>(
+                r#"
+                UPDATE test_attempts
+                SET status = 'timeout',
+                    completed_at = LEAST($2, expires_at + make_interval(secs => interruption_seconds)),
+                    updated_at = NOW(),
+                    score = COALESCE(score, 0),
+                    max_score = COALESCE(max_score, 0),
+                    percentage = COALESCE(percentage, 0),
+                    passed = FALSE
+                WHERE id = $1 AND status IN ('pending', 'in_progress')
+                RETURNING *
+                "#
+            )
+            .bind(id)
+            .bind(now)
+            .fetch_optional(&self.pool)
+            .await?;
+            return Ok(closed);
+        }
+
+        let answers: Vec<serde_json::Value> = attempt
+            .answers
+            .clone()
+            .and_then(|a| serde_json::from_value(a).ok())
+            .unwrap_or_default();
+        let grade = grade_answers(&attempt, &test, &answers).await?;
+        let status = if grade.needs_review { "needs_review" } else { "timeout" };
+
+        let Some(updated) = sqlx::query_as::<_, TestAttempt>(
+            r#"
+            UPDATE test_attempts
+            SET status = $2,
+                completed_at = LEAST($3, expires_at + make_interval(secs => interruption_seconds)),
+                time_spent_seconds = ROUND(EXTRACT(EPOCH FROM (
+                    LEAST($3, expires_at + make_interval(secs => interruption_seconds)) - started_at
+                )))::integer,
+                updated_at = NOW(),
+                score = $4, max_score = $5, percentage = $6, passed = $7, graded_answers = $8,
+                mcq_score = $9, open_score = $10,
+                metadata = COALESCE(metadata, '{}'::jsonb) || '{"timed_out": true}'::jsonb
+            WHERE id = $1 AND status = 'in_progress'
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(status)
+        .bind(now)
+        .bind(Decimal::from_f64(grade.score).unwrap_or_default())
+        .bind(Decimal::from_f64(grade.max_score).unwrap_or_default())
+        .bind(Decimal::from_f64(grade.percentage).unwrap_or_default())
+        .bind(grade.passed)
+        .bind(serde_json::to_value(&grade.graded)?)
+        .bind(grade.sub_scores.mcq.and_then(Decimal::from_f64))
+        .bind(grade.sub_scores.open.and_then(Decimal::from_f64))
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        if !updated.is_preview {
+            let completed = crate::dto::webhook_dto::TestCompletedWebhook {
+                event: "test_completed".to_string(),
+                attempt_id: updated.id,
+                candidate: crate::dto::webhook_dto::WebhookCandidate {
+                    name: updated.candidate_name.clone(),
+                    telegram_id: updated.candidate_telegram_id,
+                    telegram_thread_id: self.telegram_thread_id(updated.candidate_telegram_id).await,
+                },
+                test: crate::dto::webhook_dto::WebhookTest { title: test.title.clone() },
+                score: grade.score,
+                percentage: grade.percentage,
+                passed: grade.passed,
+                timed_out: true,
+            };
+            if let Err(e) = notification_service
+                .enqueue_webhook("test_completed", &serde_json::to_value(&completed)?, Some(&updated.id.to_string()))
+                .await
+            {
+                tracing::error!("Failed to enqueue timeout webhook for attempt {}: {:?}", updated.id, e);
+            }
+        }
+        Ok(Some(updated))
+    }
+
+    /// Deletes previews created more than `PREVIEW_RETENTION_HOURS` before `now`.
+    pub async fn delete_old_previews(&self, now: DateTime<Utc>) -> Result<u64> {
+        let removed = sqlx::query("DELETE FROM test_attempts WHERE is_preview AND created_at < $1")
+            .bind(now - Duration::hours(PREVIEW_RETENTION_HOURS))
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(removed)
+    }
+
+    /// Whether the attempt saved an answer within the last `within_minutes`.
+    pub async fn was_recently_active(&self, attempt_id: Uuid, within_minutes: i64) -> Result<bool> {
+        let since = Utc::now() - Duration::minutes(within_minutes);
+        let active: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM answer_logs WHERE attempt_id = $1 AND created_at >= $2)",
+        )
+        .bind(attempt_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(active)
+    }
+
     pub async fn report_violation(&self, token: &str, violation_type: &str) -> Result<(i32, bool)> {
         let (attempt, _test) = self.get_attempt_and_test_by_token(token).await?;
 
@@ -586,7 +1848,7 @@ impl AttemptService {
         let terminated = new_count >= MAX_VIOLATIONS;
 
         if terminated {
-            sqlx::query!(
+            let escaped = sqlx::query_as::<_, TestAttempt>(
                 r#"
                 UPDATE test_attempts
                 SET tab_switches = $1,
@@ -598,16 +1860,18 @@ impl AttemptService {
                     percentage = 0,
                     passed = FALSE,
                     updated_at = $3
-                WHERE access_token = $4
-                "#,
-                new_count,
-                activity_json,
-                now,
-                token
+                WHERE id = $4
+                RETURNING *
+                "#
             )
-            .execute(&self.pool)
+            .bind(new_count)
+            .bind(activity_json)
+            .bind(now)
+            .bind(attempt.id)
+            .fetch_one(&self.pool)
             .await
             .map_err(|e| crate::error::Error::Internal(format!("Failed to terminate attempt: {}", e)))?;
+            self.push_onef_status(&escaped).await;
 
             tracing::warn!(
                 "Anti-cheat: Test auto-failed for token={} after {} tab switches",
@@ -621,12 +1885,12 @@ impl AttemptService {
                 SET tab_switches = $1,
                     suspicious_activity = $2,
                     updated_at = $3
-                WHERE access_token = $4
+                WHERE id = $4
                 "#,
                 new_count,
                 activity_json,
                 now,
-                token
+                attempt.id
             )
             .execute(&self.pool)
             .await
@@ -642,21 +1906,341 @@ impl AttemptService {
         Ok((new_count, terminated))
     }
 
+    /// Attempt counts per status across every organization.
     pub async fn get_status_distribution(&self) -> Result<std::collections::HashMap<String, i64>> {
-        let rows = sqlx::query!(
-            r#"SELECT status as "status!", COUNT(*) as "count!" FROM test_attempts GROUP BY status"#
+        self.status_distribution(None, None, None).await
+    }
+
+    pub async fn get_status_distribution_between(
+        &self,
+        org_id: Uuid,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<std::collections::HashMap<String, i64>> {
+        self.status_distribution(Some(org_id), start, end).await
+    }
+
+    async fn status_distribution(
+        &self,
+        org_id: Option<Uuid>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<std::collections::HashMap<String, i64>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT status, COUNT(*)
+            FROM test_attempts
+            WHERE NOT is_preview
+              AND ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at < $2)
+              AND ($3::uuid IS NULL OR organization_id = $3)
+            GROUP BY status
+            "#,
         )
+        .bind(start)
+        .bind(end)
+        .bind(org_id)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut map = std::collections::HashMap::new();
-        for row in rows {
-            map.insert(row.status, row.count);
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Attempt counts per invite source, created within `[start, end)`.
+    pub async fn get_source_distribution_between(
+        &self,
+        org_id: Uuid,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<std::collections::HashMap<String, i64>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT source, COUNT(*)
+            FROM test_attempts
+            WHERE NOT is_preview
+              AND ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at < $2)
+              AND organization_id = $3
+            GROUP BY source
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    pub async fn analyze_answer_changes(&self, attempt_id: Uuid) -> Result<AnswerChangeReport> {
+        let attempt = self.get_attempt_by_id(attempt_id).await?;
+        let logs = sqlx::query_as::<_, AnswerLog>(
+            r#"
+            SELECT id, attempt_id, question_id, answer_value, time_spent_seconds, created_at
+            FROM answer_logs
+            WHERE attempt_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(attempt_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(build_answer_change_report(attempt_id, &logs, attempt.completed_at))
+    }
+}
+
+pub const SUSPICIOUS_ANSWER_CHANGES: i32 = 3;
+pub const LAST_SECOND_ANSWER_SECS: i64 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestionChangeStats {
+    pub question_id: i32,
+    pub saves: i32,
+    pub changes: i32,
+    pub final_answer_at: DateTime<Utc>,
+    pub seconds_before_submit: Option<i64>,
+    pub flags: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnswerChangeReport {
+    pub attempt_id: Uuid,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub total_changes: i32,
+    pub flagged_questions: Vec<i32>,
+    pub questions: Vec<QuestionChangeStats>,
+}
+
+/// Logs must be in chronological order. A "change" is a save whose value
+/// differs from the previous save for the same question; the final answer
+/// time is when the last distinct value was first saved.
+pub fn build_answer_change_report(
+    attempt_id: Uuid,
+    logs: &[AnswerLog],
+    submitted_at: Option<DateTime<Utc>>,
+) -> AnswerChangeReport {
+    let mut order: Vec<i32> = Vec::new();
+    let mut per_question: std::collections::HashMap<i32, (i32, i32, &serde_json::Value, DateTime<Utc>)> =
+        std::collections::HashMap::new();
+
+    for log in logs {
+        match per_question.get_mut(&log.question_id) {
+            Some((saves, changes, last_value, final_at)) => {
+                *saves += 1;
+                if *last_value != &log.answer_value {
+                    *changes += 1;
+                    *last_value = &log.answer_value;
+                    *final_at = log.created_at;
+                }
+            }
+            None => {
+                order.push(log.question_id);
+                per_question.insert(log.question_id, (1, 0, &log.answer_value, log.created_at));
+            }
+        }
+    }
+
+    let questions: Vec<QuestionChangeStats> = order
+        .into_iter()
+        .map(|question_id| {
+            let (saves, changes, _, final_answer_at) = per_question[&question_id];
+            let seconds_before_submit = submitted_at.map(|s| (s - final_answer_at).num_seconds());
+            let mut flags = Vec::new();
+            if changes > SUSPICIOUS_ANSWER_CHANGES {
+                flags.push("frequent_changes");
+            }
+            if seconds_before_submit.is_some_and(|s| s <= LAST_SECOND_ANSWER_SECS) {
+                flags.push("last_second_answer");
+            }
+            QuestionChangeStats {
+                question_id,
+                saves,
+                changes,
+                final_answer_at,
+                seconds_before_submit,
+                flags,
+            }
+        })
+        .collect();
+
+    AnswerChangeReport {
+        attempt_id,
+        submitted_at,
+        total_changes: questions.iter().map(|q| q.changes).sum(),
+        flagged_questions: questions
+            .iter()
+            .filter(|q| !q.flags.is_empty())
+            .map(|q| q.question_id)
+            .collect(),
+        questions,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PassChange {
+    pub attempt_id: Uuid,
+    pub before: Option<bool>,
+    pub after: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RegradeSummary {
+    pub attempts_regraded: usize,
+    pub pass_changes: Vec<PassChange>,
+}
+
+/// `metadata` as an object. Non-object metadata is kept under `previous` so
+/// nothing is lost.
+fn metadata_object(metadata: Option<serde_json::Value>) -> serde_json::Map<String, serde_json::Value> {
+    match metadata {
+        Some(serde_json::Value::Object(map)) => map,
+        None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+        Some(other) => {
+            let mut map = serde_json::Map::new();
+            map.insert("previous".to_string(), other);
+            map
         }
-        Ok(map)
     }
 }
 
+/// Adds `event` to `metadata.regrades`.
+pub fn append_regrade_event(metadata: Option<serde_json::Value>, event: serde_json::Value) -> serde_json::Value {
+    let mut object = metadata_object(metadata);
+    match object.get_mut("regrades") {
+        Some(serde_json::Value::Array(events)) => events.push(event),
+        _ => {
+            object.insert("regrades".to_string(), json!([event]));
+        }
+    }
+    serde_json::Value::Object(object)
+}
+
+/// Hex SHA-256 of the serialized snapshot. `serde_json` writes object keys
+/// sorted, so the hash survives a round trip through JSONB.
+pub fn snapshot_hash(snapshot: &serde_json::Value) -> String {
+    sha256_hex(snapshot.to_string().as_bytes())
+}
+
+/// Records [`snapshot_hash`] as `metadata.snapshot_hash` at invite time.
+pub fn with_snapshot_hash(metadata: Option<serde_json::Value>, snapshot: &serde_json::Value) -> serde_json::Value {
+    let mut object = metadata_object(metadata);
+    object.insert("snapshot_hash".to_string(), json!(snapshot_hash(snapshot)));
+    serde_json::Value::Object(object)
+}
+
+/// Questions to grade an attempt against: the ones the candidate was shown,
+/// or the live test for legacy attempts stored without a snapshot.
+pub fn grading_questions(snapshot: &serde_json::Value, live: &serde_json::Value) -> Vec<Question> {
+    match serde_json::from_value::<Vec<Question>>(snapshot.clone()) {
+        Ok(questions) if !questions.is_empty() => questions,
+        _ => serde_json::from_value(live.clone()).unwrap_or_default(),
+    }
+}
+
+/// Outcome of grading an attempt's answers.
+struct AttemptGrade {
+    score: f64,
+    max_score: f64,
+    percentage: f64,
+    passed: bool,
+    needs_review: bool,
+    graded: Vec<serde_json::Value>,
+    sub_scores: SubScores,
+}
+
+/// Grades `answers` against the questions the attempt was shown, running
+/// code answers through the sandbox when code execution is enabled. Shared
+/// by submission and the deadline checker so a timed-out attempt is scored
+/// the same way as a submitted one.
+async fn grade_answers(
+    attempt: &TestAttempt,
+    test: &Test,
+    answers: &[serde_json::Value],
+) -> Result<AttemptGrade> {
+    let questions = grading_questions(&attempt.questions_snapshot, &test.questions);
+    let config = crate::config::get_config();
+    let ai_service = AIService::new(
+        config.openai_api_key.clone(),
+        config.openai_base_url.clone(),
+        reqwest::Client::new(),
+    )
+    .with_models(config.ai_models.clone());
+    let GradingResult {
+        mut earned_points,
+        total_max_points,
+        mut graded,
+        ..
+    } = GradingService::grade_parallel(questions.clone(), answers.to_vec(), &ai_service).await?;
+    if config.enable_code_execution {
+        earned_points += GradingService::grade_code_answers(
+            &questions,
+            answers,
+            &mut graded,
+            &CodeRunnerService::new(),
+        )
+        .await;
+    }
+
+    let score = earned_points as f64;
+    let max_score = total_max_points as f64;
+    let percentage = if max_score > 0.0 { (score / max_score) * 100.0 } else { 0.0 };
+    let sub_scores = GradingService::sub_scores(&graded);
+    Ok(AttemptGrade {
+        score,
+        max_score,
+        percentage,
+        passed: PassingThresholds::from_test(test).passes(percentage, sub_scores),
+        needs_review: GradingService::needs_review(&graded),
+        graded,
+        sub_scores,
+    })
+}
+
+pub const IP_CHANGE_ACTIVITY: &str = "ip_change";
+
+/// `suspicious_activity` entry for a submit from a different address than the
+/// start; `None` when either address is unknown or they match.
+pub fn ip_change_entry(
+    start_ip: Option<IpAddr>,
+    submit_ip: Option<IpAddr>,
+    at: DateTime<Utc>,
+) -> Option<serde_json::Value> {
+    match (start_ip, submit_ip) {
+        (Some(start), Some(submit)) if start != submit => Some(json!({
+            "type": IP_CHANGE_ACTIVITY,
+            "start_ip": start.to_string(),
+            "submit_ip": submit.to_string(),
+            "timestamp": at.to_rfc3339(),
+        })),
+        _ => None,
+    }
+}
+
+/// The `ip_change` entries of an attempt's `suspicious_activity`.
+pub fn ip_change_flags(activity: Option<&serde_json::Value>) -> Vec<serde_json::Value> {
+    activity_entries(activity, IP_CHANGE_ACTIVITY)
+}
+
+/// The `connection_loss` entries of an attempt's `suspicious_activity`.
+pub fn connection_loss_flags(activity: Option<&serde_json::Value>) -> Vec<serde_json::Value> {
+    activity_entries(activity, CONNECTION_LOSS_ACTIVITY)
+}
+
+fn activity_entries(activity: Option<&serde_json::Value>, kind: &str) -> Vec<serde_json::Value> {
+    activity
+        .and_then(|a| a.as_array())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|e| e["type"] == kind)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone)]
 pub struct InviteCandidate {
     pub external_id: Option<String>,
@@ -666,6 +2250,23 @@ pub struct InviteCandidate {
     pub phone: Option<String>,
 }
 
+/// Statuses `start_attempt_by_token` may move to `in_progress`; every other
+/// status is final.
+pub fn is_startable_status(status: &str) -> bool {
+    matches!(status, "pending" | "in_progress")
+}
+
+/// How long the token replaced at test start is still accepted.
+pub const TOKEN_ROTATION_GRACE_SECS: i64 = 60;
+
+/// How long a preview link can be used.
+pub const PREVIEW_EXPIRY_HOURS: i64 = 2;
+/// Previews are deleted this long after they were created.
+pub const PREVIEW_RETENTION_HOURS: i64 = 24;
+pub const PREVIEW_CANDIDATE_NAME: &str = "Preview";
+/// Reserved TLD, so it never matches a real candidate.
+pub const PREVIEW_CANDIDATE_EMAIL: &str = "preview@preview.invalid";
+
 #[derive(Debug, Clone)]
 pub struct CreateInviteResult {
     pub attempt_id: Uuid,
@@ -673,3 +2274,258 @@ pub struct CreateInviteResult {
     pub expires_at: DateTime<Utc>,
     pub status: String,
 }
+
+/// How an invite was created, stored as `test_attempts.source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InviteSource {
+    Integration,
+    Onef,
+    AutoInvite,
+    Retake,
+}
+
+impl InviteSource {
+    pub const ALL: [InviteSource; 4] = [
+        InviteSource::Integration,
+        InviteSource::Onef,
+        InviteSource::AutoInvite,
+        InviteSource::Retake,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InviteSource::Integration => "integration",
+            InviteSource::Onef => "onef",
+            InviteSource::AutoInvite => "auto_invite",
+            InviteSource::Retake => "retake",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == raw)
+    }
+}
+
+/// Parses a `source` query filter, rejecting unknown values so a typo
+/// doesn't silently match nothing.
+pub fn parse_source_filter(raw: Option<&str>) -> Result<Option<InviteSource>> {
+    let Some(raw) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    InviteSource::parse(raw).map(Some).ok_or_else(|| {
+        crate::error::Error::coded(
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            "unknown_invite_source",
+            format!("Unknown invite source '{}'", raw),
+        )
+        .with_details(json!({
+            "source": raw,
+            "known_sources": InviteSource::ALL.map(|s| s.as_str()),
+        }))
+    })
+}
+
+/// Which part of the review queue to list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAssignment {
+    Mine(Uuid),
+    Unassigned,
+    All,
+}
+
+/// Errors unless `reviewer` may grade `attempt`: it must be unclaimed or
+/// claimed by them, or `force` must be set.
+pub fn check_review_claim(attempt: &TestAttempt, reviewer: Option<Uuid>, force: bool) -> Result<()> {
+    match attempt.reviewed_by {
+        Some(owner) if !force && attempt.status == "needs_review" && reviewer != Some(owner) => {
+            Err(crate::error::Error::coded(
+                axum::http::StatusCode::CONFLICT,
+                "attempt_claimed",
+                "Attempt is being reviewed by another user",
+            )
+            .with_details(json!({
+                "reviewed_by": owner,
+                "claimed_at": attempt.claimed_at,
+            })))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn not_in_review(attempt: &TestAttempt) -> crate::error::Error {
+    crate::error::Error::coded(
+        axum::http::StatusCode::CONFLICT,
+        "attempt_not_in_review",
+        format!("Attempt is '{}'; only needs_review attempts can be claimed", attempt.status),
+    )
+}
+
+#[derive(sqlx::FromRow)]
+struct ReminderRow {
+    id: Uuid,
+    candidate_name: String,
+    candidate_telegram_id: Option<i64>,
+    expires_at: DateTime<Utc>,
+    reminders_sent: serde_json::Value,
+    reminder_hours_before: Vec<i32>,
+    title: String,
+}
+
+/// Offsets (hours before `expires_at`) that `now` has reached and that are
+/// not in `sent`. Nothing is due once the attempt has expired.
+pub fn due_reminder_offsets(offsets: &[i32], sent: &[i32], expires_at: DateTime<Utc>, now: DateTime<Utc>) -> Vec<i32> {
+    if now >= expires_at {
+        return Vec::new();
+    }
+    offsets
+        .iter()
+        .copied()
+        .filter(|h| *h > 0 && !sent.contains(h))
+        .filter(|h| expires_at - Duration::hours(*h as i64) <= now)
+        .collect()
+}
+
+/// Whole hours left until `expires_at`, rounded up and at least 1.
+pub fn hours_until(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let seconds = (expires_at - now).num_seconds().max(0);
+    ((seconds + 3599) / 3600).max(1)
+}
+
+/// Heartbeat silence up to this long (a reload, a flaky connection) is not
+/// treated as a disconnect.
+pub const HEARTBEAT_GRACE_PERIOD_SECS: i64 = 60;
+
+/// An attempt with a lapsed heartbeat is not marked `escaped` while it has
+/// saved an answer within this many minutes.
+pub const ESCAPE_ACTIVITY_WINDOW_MINUTES: i64 = 5;
+
+/// Expired attempts graded concurrently per `check_deadlines` batch.
+pub const TIMEOUT_BATCH_SIZE: usize = 10;
+
+/// How long the candidate has been disconnected beyond the grace period at
+/// `now`; zero while heartbeats arrive or before the first one.
+pub fn offline_gap(attempt: &TestAttempt, now: DateTime<Utc>) -> Duration {
+    attempt
+        .last_heartbeat_at
+        .map(|last| now - last - Duration::seconds(HEARTBEAT_GRACE_PERIOD_SECS))
+        .filter(|gap| *gap > Duration::zero())
+        .unwrap_or_else(Duration::zero)
+}
+
+/// End of the answering window: `started_at + duration_minutes` plus any
+/// `extension_seconds` from HR, never past `expires_at`, plus the
+/// `interruption_seconds` credited for connection loss. Before the start only
+/// `expires_at` applies. `check_deadlines` repeats this in SQL.
+pub fn attempt_deadline(attempt: &TestAttempt, duration_minutes: i32) -> DateTime<Utc> {
+    let deadline = match attempt.started_at {
+        Some(started) => (started
+            + Duration::minutes(duration_minutes as i64)
+            + Duration::seconds(attempt.extension_seconds as i64))
+        .min(attempt.expires_at),
+        None => attempt.expires_at,
+    };
+    deadline + Duration::seconds(attempt.interruption_seconds as i64)
+}
+
+/// Whether `taking` in-progress attempts leave no room under `cap`.
+pub fn at_capacity(taking: i64, cap: i32) -> bool {
+    taking >= cap as i64
+}
+
+/// A silence between two heartbeats longer than this is a lost connection.
+pub const CONNECTION_GAP_THRESHOLD_SECS: i64 = 30;
+
+/// `suspicious_activity` type for a lost connection. Kept apart from tab
+/// switches and IP changes so reviewers don't read it as cheating.
+pub const CONNECTION_LOSS_ACTIVITY: &str = "connection_loss";
+
+/// Seconds between two heartbeats, if more than `CONNECTION_GAP_THRESHOLD_SECS`.
+pub fn heartbeat_gap(previous: DateTime<Utc>, latest: DateTime<Utc>) -> Option<i64> {
+    let gap = (latest - previous).num_seconds();
+    (gap > CONNECTION_GAP_THRESHOLD_SECS).then_some(gap)
+}
+
+/// `interruption_seconds` after crediting `gap_secs`, capped at
+/// `max_grace_seconds`. Time already credited is never taken back, even if
+/// the test's cap was lowered since.
+pub fn accumulate_interruption(current: i32, gap_secs: i64, max_grace_seconds: i32) -> i32 {
+    let total = (current as i64 + gap_secs.max(0)).min(max_grace_seconds.max(0) as i64);
+    total.max(current as i64) as i32
+}
+
+pub fn connection_loss_entry(
+    previous: DateTime<Utc>,
+    latest: DateTime<Utc>,
+    gap_secs: i64,
+    credited_secs: i32,
+) -> serde_json::Value {
+    json!({
+        "type": CONNECTION_LOSS_ACTIVITY,
+        "from": previous.to_rfc3339(),
+        "to": latest.to_rfc3339(),
+        "gap_seconds": gap_secs,
+        "credited_seconds": credited_secs,
+        "timestamp": latest.to_rfc3339(),
+    })
+}
+
+/// Seconds left at `now`. The window runs on wall time from `started_at`, so
+/// time spent disconnected (`offline_gap`) is used up when the candidate comes
+/// back, except for the connection-loss grace in `interruption_seconds`;
+/// closing the browser never pauses the clock.
+pub fn time_remaining_at(attempt: &TestAttempt, duration_minutes: i32, now: DateTime<Utc>) -> i64 {
+    let remaining = (attempt_deadline(attempt, duration_minutes) - now).num_seconds().max(0);
+    if attempt.started_at.is_none() {
+        return remaining.min(duration_minutes as i64 * 60);
+    }
+    remaining
+}
+
+pub fn reminder_message(hours_left: i64) -> String {
+    format!("Ваш тест истекает через {} часов", hours_left)
+}
+
+/// Where the plain-text results report for an attempt is written.
+pub fn result_report_path(attempt_id: Uuid) -> String {
+    format!("uploads/results/{}.txt", attempt_id)
+}
+
+/// Score and submission details for a 1F test status push. Scores are only
+/// included once the attempt has finished.
+pub fn onef_event_data(attempt: &TestAttempt, webapp_url: &str) -> OneFTestStatusEventData {
+    let finished = attempt.completed_at.is_some();
+    let is_presentation = attempt.presentation_submission_link.is_some()
+        || attempt.presentation_submission_file_path.is_some();
+    let presentation_file_url = attempt
+        .presentation_submission_file_path
+        .as_ref()
+        .map(|path| format!("{}/{}", webapp_url, path));
+
+    let result_url = if is_presentation {
+        let mut parts = Vec::new();
+        if let Some(link) = &attempt.presentation_submission_link {
+            parts.push(format!("Link: {}", link));
+        }
+        if let Some(url) = &presentation_file_url {
+            parts.push(format!("File: {}", url));
+        }
+        Some(parts.join(" | "))
+    } else if attempt.graded_answers.is_some() {
+        Some(format!("{}/{}", webapp_url, result_report_path(attempt.id)))
+    } else {
+        None
+    };
+
+    let decimal = |d: Option<Decimal>| d.filter(|_| finished).and_then(|d| d.to_f64());
+    OneFTestStatusEventData {
+        attempt_id: Some(attempt.id),
+        max_score: decimal(attempt.max_score),
+        user_score: decimal(attempt.score),
+        percentage: decimal(attempt.percentage),
+        passed: attempt.passed.filter(|_| finished),
+        result_url,
+        presentation_link: attempt.presentation_submission_link.clone(),
+        presentation_file_url,
+    }
+}
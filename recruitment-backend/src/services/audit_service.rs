@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::middleware::request_id::current_request_id;
 use crate::models::audit_log::AuditLog;
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
@@ -14,6 +15,8 @@ impl AuditService {
         Self { pool }
     }
 
+    /// Entries written while handling a request carry its correlation ID.
+    #[allow(clippy::too_many_arguments)]
     pub async fn log(
         &self,
         user_id: Option<Uuid>,
@@ -27,9 +30,9 @@ impl AuditService {
         let row = sqlx::query_as!(
             AuditLog,
             r#"
-            INSERT INTO audit_logs (user_id, action, entity_type, entity_id, changes, ip_address, user_agent)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_id, action, entity_type, entity_id, changes as "changes: serde_json::Value", ip_address as "ip_address?: sqlx::types::ipnetwork::IpNetwork", user_agent, created_at as "created_at?: _"
+            INSERT INTO audit_logs (user_id, action, entity_type, entity_id, changes, ip_address, user_agent, correlation_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, action, entity_type, entity_id, changes as "changes: serde_json::Value", ip_address as "ip_address?: sqlx::types::ipnetwork::IpNetwork", user_agent, correlation_id, created_at as "created_at?: _"
             "#,
             user_id,
             action,
@@ -37,7 +40,8 @@ impl AuditService {
             entity_id,
             changes,
             ip,
-            ua
+            ua,
+            current_request_id()
         )
         .fetch_one(&self.pool)
         .await?;
@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::models::broadcast::{
+    Broadcast, BroadcastFailure, BroadcastFilter, BroadcastPreview, BroadcastPreviewRecipient,
+    BroadcastProgress, BroadcastRecipient,
+};
+use crate::services::status_pipeline_service::StatusPipelineService;
+use crate::services::telegram_channel_service::TelegramChannelService;
+use crate::services::telegram_delivery_service::TelegramDeliveryService;
+
+/// Recipients listed by a dry run.
+pub const PREVIEW_RECIPIENTS: usize = 10;
+/// Recipients claimed per worker pass.
+pub const BROADCAST_BATCH_SIZE: i64 = 25;
+/// Gap between two sends: about 25 messages a second, under Telegram's
+/// 30/s limit for a bot.
+pub const BROADCAST_SEND_INTERVAL: Duration = Duration::from_millis(40);
+/// A recipient left in `sending` this long (worker restarted mid-batch) is
+/// picked up again.
+const STALE_SENDING_SECS: i32 = 300;
+pub const NO_TELEGRAM_ERROR: &str = "Candidate has no Telegram account";
+
+/// `template` with `{name}` replaced by the candidate's name.
+pub fn render_broadcast(template: &str, name: &str) -> String {
+    template.replace("{name}", name)
+}
+
+/// Trims and de-duplicates the statuses and rejects unknown ones, so a typo
+/// doesn't silently match nobody.
+pub fn normalize_filter(mut filter: BroadcastFilter) -> Result<BroadcastFilter> {
+    let mut statuses: Vec<String> = Vec::new();
+    for status in filter
+        .statuses
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+    {
+        if !StatusPipelineService::is_known(status) {
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unknown_candidate_status",
+                format!("Unknown candidate status '{}'", status),
+            )
+            .with_details(json!({
+                "status": status,
+                "known_statuses": StatusPipelineService::statuses().collect::<Vec<_>>(),
+            })));
+        }
+        if !statuses.iter().any(|s| s == status) {
+            statuses.push(status.to_string());
+        }
+    }
+    filter.statuses = statuses;
+    if filter
+        .min_ai_rating
+        .is_some_and(|r| !(0..=100).contains(&r))
+    {
+        return Err(Error::BadRequest(
+            "min_ai_rating must be between 0 and 100".into(),
+        ));
+    }
+    Ok(filter)
+}
+
+#[derive(sqlx::FromRow)]
+struct MatchedCandidate {
+    id: Uuid,
+    name: String,
+    status: String,
+    ai_rating: Option<i32>,
+    telegram_id: Option<i64>,
+    telegram_thread_id: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct BroadcastService {
+    pool: PgPool,
+}
+
+impl BroadcastService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn matching(&self, filter: &BroadcastFilter) -> Result<Vec<MatchedCandidate>> {
+        let candidates = sqlx::query_as::<_, MatchedCandidate>(
+            r#"
+            SELECT id, name, status, ai_rating, telegram_id, telegram_thread_id
+            FROM candidates
+            WHERE deleted_at IS NULL
+              AND (cardinality($1::text[]) = 0 OR status = ANY($1))
+              AND ($2::bigint IS NULL OR vacancy_id = $2)
+              AND ($3::int IS NULL OR ai_rating >= $3)
+              AND ($4::bool IS NULL OR (telegram_id IS NOT NULL) = $4)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(&filter.statuses)
+        .bind(filter.vacancy_id)
+        .bind(filter.min_ai_rating)
+        .bind(filter.has_telegram)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candidates)
+    }
+
+    /// How many candidates `filter` matches and the first few of them, with
+    /// the message each would get. Nothing is stored or sent.
+    pub async fn preview(
+        &self,
+        filter: &BroadcastFilter,
+        template: &str,
+    ) -> Result<BroadcastPreview> {
+        let candidates = self.matching(filter).await?;
+        Ok(BroadcastPreview {
+            dry_run: true,
+            matched: candidates.len(),
+            recipients: candidates
+                .into_iter()
+                .take(PREVIEW_RECIPIENTS)
+                .map(|c| BroadcastPreviewRecipient {
+                    message: render_broadcast(template, &c.name),
+                    candidate_id: c.id,
+                    name: c.name,
+                    status: c.status,
+                    ai_rating: c.ai_rating,
+                    telegram_id: c.telegram_id,
+                })
+                .collect(),
+        })
+    }
+
+    /// Stores the broadcast with one pending recipient per matched candidate
+    /// for the worker to send. Candidates without Telegram are recorded as
+    /// failed right away.
+    pub async fn create(&self, filter: &BroadcastFilter, template: &str) -> Result<Broadcast> {
+        let candidates = self.matching(filter).await?;
+        let mut tx = self.pool.begin().await?;
+        let broadcast = sqlx::query_as::<_, Broadcast>(
+            r#"
+            INSERT INTO broadcasts (filter, message_template, total_recipients, status, completed_at)
+            VALUES ($1, $2, $3,
+                    CASE WHEN $3 = 0 THEN 'completed' ELSE 'sending' END,
+                    CASE WHEN $3 = 0 THEN NOW() END)
+            RETURNING *
+            "#,
+        )
+        .bind(serde_json::to_value(filter)?)
+        .bind(template)
+        .bind(candidates.len() as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut candidate_ids = Vec::with_capacity(candidates.len());
+        let mut telegram_ids = Vec::with_capacity(candidates.len());
+        let mut thread_ids = Vec::with_capacity(candidates.len());
+        let mut messages = Vec::with_capacity(candidates.len());
+        let mut statuses = Vec::with_capacity(candidates.len());
+        let mut errors = Vec::with_capacity(candidates.len());
+        for c in &candidates {
+            candidate_ids.push(c.id);
+            telegram_ids.push(c.telegram_id);
+            thread_ids.push(c.telegram_thread_id);
+            messages.push(render_broadcast(template, &c.name));
+            statuses.push(if c.telegram_id.is_some() {
+                "pending"
+            } else {
+                "failed"
+            });
+            errors.push(c.telegram_id.is_none().then_some(NO_TELEGRAM_ERROR));
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO broadcast_recipients
+                (broadcast_id, candidate_id, telegram_id, telegram_thread_id, message, status, error)
+            SELECT $1, * FROM UNNEST($2::uuid[], $3::bigint[], $4::bigint[], $5::text[], $6::text[], $7::text[])
+            "#,
+        )
+        .bind(broadcast.id)
+        .bind(&candidate_ids)
+        .bind(&telegram_ids)
+        .bind(&thread_ids)
+        .bind(&messages)
+        .bind(&statuses)
+        .bind(&errors)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(broadcast)
+    }
+
+    pub async fn progress(&self, id: Uuid) -> Result<BroadcastProgress> {
+        let broadcast = sqlx::query_as::<_, Broadcast>("SELECT * FROM broadcasts WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| Error::NotFound("Broadcast not found".into()))?;
+        let (pending, sent, failed): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE status IN ('pending', 'sending')),
+                   COUNT(*) FILTER (WHERE status = 'sent'),
+                   COUNT(*) FILTER (WHERE status = 'failed')
+            FROM broadcast_recipients
+            WHERE broadcast_id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        let failures = sqlx::query_as::<_, BroadcastFailure>(
+            r#"
+            SELECT candidate_id, telegram_id, error
+            FROM broadcast_recipients
+            WHERE broadcast_id = $1 AND status = 'failed'
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(BroadcastProgress {
+            broadcast,
+            pending,
+            sent,
+            failed,
+            failures,
+        })
+    }
+
+    /// Sends one batch of pending messages, `BROADCAST_SEND_INTERVAL` apart.
+    /// A failed send (e.g. the candidate blocked the bot) is recorded on its
+    /// recipient and the batch goes on. Returns whether anything was claimed.
+    pub async fn run_once(&self, telegram: &TelegramChannelService) -> Result<bool> {
+        let batch = sqlx::query_as::<_, BroadcastRecipient>(
+            r#"
+            UPDATE broadcast_recipients
+            SET status = 'sending', attempted_at = NOW()
+            WHERE id IN (
+                SELECT id FROM broadcast_recipients
+                WHERE status = 'pending'
+                   OR (status = 'sending' AND attempted_at < NOW() - make_interval(secs => $2))
+                ORDER BY created_at, id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(BROADCAST_BATCH_SIZE)
+        .bind(STALE_SENDING_SECS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let delivery = TelegramDeliveryService::new(self.pool.clone(), telegram.clone());
+        for (i, recipient) in batch.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(BROADCAST_SEND_INTERVAL).await;
+            }
+            let result = match recipient.telegram_id {
+                Some(chat_id) => {
+                    delivery
+                        .send(
+                            chat_id,
+                            recipient.telegram_thread_id,
+                            &recipient.message,
+                            None,
+                        )
+                        .await
+                }
+                None => Err(Error::BadRequest(NO_TELEGRAM_ERROR.into())),
+            };
+            let error = result.err().map(|e| {
+                tracing::warn!(
+                    recipient = %recipient.id,
+                    error = %e,
+                    "Broadcast message not delivered"
+                );
+                e.to_string()
+            });
+            sqlx::query(
+                r#"
+                UPDATE broadcast_recipients
+                SET status = CASE WHEN $2::text IS NULL THEN 'sent' ELSE 'failed' END,
+                    error = $2,
+                    attempted_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(recipient.id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE broadcasts b
+            SET status = 'completed', completed_at = NOW()
+            WHERE b.status = 'sending'
+              AND NOT EXISTS (
+                  SELECT 1 FROM broadcast_recipients r
+                  WHERE r.broadcast_id = b.id AND r.status IN ('pending', 'sending')
+              )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(!batch.is_empty())
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value as JsonValue};
+
+pub const GOOGLE_CALENDAR_URL: &str = "https://calendar.google.com/calendar/render";
+pub const ADD_TO_CALENDAR_BUTTON_TEXT: &str = "Добавить в календарь";
+const ICS_PRODID: &str = "-//Koinoti Nav//Recruitment//RU";
+/// RFC 5545 content lines are folded at 75 octets.
+const ICS_LINE_LIMIT: usize = 75;
+
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    /// Stable id so calendar apps update rather than duplicate the event.
+    pub uid: String,
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub start: DateTime<Utc>,
+    pub duration_minutes: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CalendarEvent {
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::minutes(self.duration_minutes as i64)
+    }
+}
+
+/// Single-event iCalendar (RFC 5545) document with CRLF line endings.
+pub fn generate_ics(event: CalendarEvent) -> String {
+    let lines = [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", ICS_PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", event.uid),
+        format!("DTSTAMP:{}", ics_time(event.created_at)),
+        format!("DTSTART:{}", ics_time(event.start)),
+        format!("DTEND:{}", ics_time(event.end())),
+        format!("SUMMARY:{}", escape_text(&event.title)),
+        format!("DESCRIPTION:{}", escape_text(&event.description)),
+        format!("LOCATION:{}", escape_text(&event.location)),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+/// Google Calendar "create event" link prefilled with the event.
+pub fn google_calendar_link(event: &CalendarEvent) -> String {
+    let dates = format!("{}/{}", ics_time(event.start), ics_time(event.end()));
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("action", "TEMPLATE")
+        .append_pair("text", &event.title)
+        .append_pair("dates", &dates)
+        .append_pair("details", &event.description)
+        .append_pair("location", &event.location)
+        .finish();
+    format!("{}?{}", GOOGLE_CALENDAR_URL, query)
+}
+
+pub fn add_to_calendar_button(link: &str) -> JsonValue {
+    json!({ "inline_keyboard": [[{ "text": ADD_TO_CALENDAR_BUTTON_TEXT, "url": link }]] })
+}
+
+pub fn interview_message(event: &CalendarEvent, interviewer_name: &str) -> String {
+    format!(
+        "Вы приглашены на собеседование.\n\nДата и время: {} (UTC)\nПродолжительность: {} мин.\nМесто: {}\nИнтервьюер: {}",
+        event.start.format("%d.%m.%Y %H:%M"),
+        event.duration_minutes,
+        event.location,
+        interviewer_name
+    )
+}
+
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits `line` into lines of at most 75 octets without cutting a UTF-8
+/// character; continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / ICS_LINE_LIMIT * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
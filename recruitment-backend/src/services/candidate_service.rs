@@ -1,8 +1,160 @@
-use crate::models::candidate::{Candidate, CandidateApplication, HistoryItem};
+use crate::dto::integration_dto::{CandidateStatusSync, CandidateStatusSyncPage, CandidateStatusSyncQuery};
+use crate::models::candidate::{ApplicationResult, Candidate, CandidateApplication, CandidateOnboarding, HistoryItem};
+use crate::services::status_pipeline_service::StatusPipelineService;
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
 use anyhow::Result;
 
+/// Length of the alphanumeric status page token.
+pub const STATUS_TOKEN_LENGTH: usize = 32;
+
+pub fn onboarding_not_ready() -> crate::error::Error {
+    crate::error::Error::coded(
+        axum::http::StatusCode::NOT_FOUND,
+        "onboarding_not_ready",
+        "No onboarding checklist has been prepared for this candidate yet",
+    )
+}
+
+/// Replaces the stored CV path with a short-lived signed download link.
+fn with_signed_cv(mut candidate: Candidate) -> Candidate {
+    candidate.cv_url = candidate
+        .cv_url
+        .map(|path| crate::utils::signed_url::signed_upload_url(&path, crate::utils::signed_url::CV_LINK_TTL));
+    candidate
+}
+
+/// When a candidate rejected from a vacancy may apply to it again.
+pub fn reapply_available_at(
+    rejected_at: chrono::DateTime<chrono::Utc>,
+    cooldown_days: i64,
+) -> chrono::DateTime<chrono::Utc> {
+    rejected_at + chrono::Duration::days(cooldown_days)
+}
+
+pub fn reapply_cooldown(retry_at: chrono::DateTime<chrono::Utc>) -> crate::error::Error {
+    crate::error::Error::coded(
+        axum::http::StatusCode::CONFLICT,
+        "reapply_cooldown",
+        format!("You can apply to this vacancy again after {}", retry_at.format("%Y-%m-%d")),
+    )
+    .with_details(serde_json::json!({ "retry_after": retry_at }))
+}
+
+/// Sorted, de-duplicated indices; every one must point at an existing item.
+pub fn normalize_completed_items(completed: &[usize], total: usize) -> crate::error::Result<Vec<usize>> {
+    if let Some(bad) = completed.iter().find(|&&i| i >= total) {
+        return Err(crate::error::Error::coded(
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_checklist_item",
+            format!("Checklist item {} does not exist (checklist has {} items)", bad, total),
+        )
+        .with_details(serde_json::json!({ "index": bad, "total": total })));
+    }
+    let mut completed = completed.to_vec();
+    completed.sort_unstable();
+    completed.dedup();
+    Ok(completed)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct CvDedupReport {
+    pub unique_files: usize,
+    pub deduplicated: usize,
+    pub saved_bytes: u64,
+}
+
+impl CvDedupReport {
+    /// `groups` holds, per stored CV file, the number of candidates referencing
+    /// it and its size on disk.
+    pub fn from_groups(groups: &[(i64, u64)]) -> Self {
+        groups.iter().fold(Self::default(), |mut report, &(references, size)| {
+            let extra = references.max(1) as usize - 1;
+            report.unique_files += 1;
+            report.deduplicated += extra;
+            report.saved_bytes += extra as u64 * size;
+            report
+        })
+    }
+}
+
+pub const MAX_BULK_STATUS_CANDIDATES: usize = 500;
+
+/// The form candidate emails are stored and compared in: trimmed and lowercased.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Hex HMAC-SHA256 of the normalised email, stored as `email_hash` so the
+/// same person can be found across organizations without comparing addresses.
+pub fn email_hash(email: &str, secret: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret)
+        .expect("HMAC accepts keys of any length");
+    mac.update(normalize_email(email).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Rows filled per query by `backfill_email_hashes`.
+const EMAIL_HASH_BACKFILL_BATCH: i64 = 500;
+
+/// Days without activity after which a candidate counts as stale on the dashboard.
+pub const STALE_CANDIDATE_DAYS: i64 = 14;
+
+/// Order of candidate lists; `created_at` (newest first) unless asked otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSort {
+    #[default]
+    CreatedAt,
+    /// Most recently active first; candidates without a computed activity last.
+    LastActivityAt,
+}
+
+impl CandidateSort {
+    pub fn apply(self, candidates: &mut [Candidate]) {
+        if self == CandidateSort::LastActivityAt {
+            candidates.sort_by_key(|c| std::cmp::Reverse(c.last_activity_at));
+        }
+    }
+}
+
+/// A candidate moved by `bulk_update_status`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BulkStatusChange {
+    pub id: uuid::Uuid,
+    pub previous_status: String,
+    pub vacancy_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BulkStatusUpdate {
+    pub updated: Vec<BulkStatusChange>,
+    /// Requested IDs that are unknown, deleted, or whose current status
+    /// doesn't allow the transition. Candidates already in the target status
+    /// are neither updated nor failed.
+    pub failed: Vec<uuid::Uuid>,
+}
+
+/// What `erase_candidate` needs from the row before clearing it.
+#[derive(sqlx::FromRow)]
+struct ErasedCandidate {
+    email: Option<String>,
+    telegram_id: Option<i64>,
+    organization_id: uuid::Uuid,
+    cv_url: Option<String>,
+    photo_url: Option<String>,
+    cv_portfolio: Option<JsonValue>,
+}
+
+#[derive(sqlx::FromRow)]
+struct StatusSyncRow {
+    #[sqlx(flatten)]
+    item: CandidateStatusSync,
+    total: i64,
+    cursor: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Clone)]
 pub struct CandidateService {
     pool: PgPool,
@@ -17,10 +169,19 @@ impl CandidateService {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
-            SELECT id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at,
-            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages
-            FROM candidates 
-            WHERE telegram_id = $1
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE telegram_id = $1 AND deleted_at IS NULL
             "#,
             telegram_id
         )
@@ -30,29 +191,59 @@ impl CandidateService {
     }
 
     pub async fn get_candidate(&self, id: uuid::Uuid) -> Result<Option<Candidate>> {
+        self.find_candidate(id, None).await
+    }
+
+    /// [`Self::get_candidate`] for an HR request: candidates of other
+    /// organizations are `None`, like missing ones.
+    pub async fn get_candidate_in_org(&self, id: uuid::Uuid, org_id: uuid::Uuid) -> Result<Option<Candidate>> {
+        self.find_candidate(id, Some(org_id)).await
+    }
+
+    async fn find_candidate(&self, id: uuid::Uuid, org_id: Option<uuid::Uuid>) -> Result<Option<Candidate>> {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
-            SELECT id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at,
-            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages
-            FROM candidates 
-            WHERE id = $1
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE id = $1 AND deleted_at IS NULL
+              AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            org_id
         )
         .fetch_optional(&self.pool)
         .await?;
-        Ok(candidate)
+        Ok(candidate.map(with_signed_cv))
     }
 
     pub async fn get_by_email(&self, email: &str) -> Result<Option<Candidate>> {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
-            SELECT id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at,
-            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages
-            FROM candidates 
-            WHERE email = $1
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE LOWER(email) = LOWER($1) AND deleted_at IS NULL
             "#,
             email
         )
@@ -61,6 +252,7 @@ impl CandidateService {
         Ok(candidate)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_candidate(
         &self,
         telegram_id: Option<i64>,
@@ -68,10 +260,18 @@ impl CandidateService {
         email: String,
         phone: Option<String>,
         cv_url: Option<String>,
+        cv_hash: Option<String>,
+        photo_url: Option<String>,
         dob: Option<chrono::NaiveDate>,
         vacancy_id: Option<i64>,
         profile_data: Option<JsonValue>,
+        timezone: Option<String>,
     ) -> Result<Candidate> {
+        let email = normalize_email(&email);
+        let email_hash = email_hash(
+            &email,
+            crate::config::get_config().email_hash_secret.as_bytes(),
+        );
         if let Some(tg_id) = telegram_id {
             let exists = sqlx::query!("SELECT id FROM candidates WHERE telegram_id = $1", tg_id)
                 .fetch_optional(&self.pool)
@@ -81,7 +281,7 @@ impl CandidateService {
             }
         }
 
-        let exists_email = sqlx::query!("SELECT id FROM candidates WHERE email = $1", email)
+        let exists_email = sqlx::query!("SELECT id FROM candidates WHERE LOWER(email) = $1", email)
             .fetch_optional(&self.pool)
             .await?;
         if exists_email.is_some() {
@@ -102,18 +302,22 @@ impl CandidateService {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
-            INSERT INTO candidates (telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'new')
-            RETURNING id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at, 0::bigint as "unread_messages!"
+            INSERT INTO candidates (telegram_id, name, email, phone, cv_url, cv_hash, photo_url, dob, vacancy_id, profile_data, timezone, email_hash, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, 'new')
+            RETURNING id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at, 0::bigint as "unread_messages!", NULL::timestamptz as last_activity_at
             "#,
             telegram_id,
             name,
             email,
             phone,
             cv_url,
+            cv_hash,
+            photo_url,
             dob,
             vacancy_id,
-            profile_data
+            profile_data,
+            timezone,
+            email_hash
         )
         .fetch_one(&self.pool)
         .await?;
@@ -124,71 +328,552 @@ impl CandidateService {
         Ok(candidate)
     }
 
-    pub async fn update_cv(&self, id: uuid::Uuid, cv_url: String) -> Result<Candidate> {
+    /// Stores the portfolio documents uploaded alongside the CV.
+    pub async fn set_cv_portfolio(&self, id: uuid::Uuid, paths: &[String]) -> Result<()> {
+        sqlx::query("UPDATE candidates SET cv_portfolio = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(serde_json::json!(paths))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_cv(&self, id: uuid::Uuid, cv_url: String, cv_hash: String) -> Result<Candidate> {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
             UPDATE candidates
-            SET cv_url = $1, updated_at = NOW()
+            SET cv_url = $1, cv_hash = $3, updated_at = NOW()
             WHERE id = $2
-            RETURNING id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at, 0::bigint as "unread_messages!"
+            RETURNING id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at, 0::bigint as "unread_messages!", NULL::timestamptz as last_activity_at
             "#,
             cv_url,
-            id
+            id,
+            cv_hash
         )
         .fetch_one(&self.pool)
         .await?;
-        Ok(candidate)
+        Ok(with_signed_cv(candidate))
+    }
+
+    /// Sets or clears the forum topic HR messages to this candidate go to.
+    pub async fn set_telegram_thread_id(
+        &self,
+        id: uuid::Uuid,
+        thread_id: Option<i64>,
+    ) -> crate::error::Result<Candidate> {
+        let updated = sqlx::query(
+            "UPDATE candidates SET telegram_thread_id = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(thread_id)
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(crate::error::Error::candidate_not_found());
+        }
+        self.get_candidate(id)
+            .await?
+            .ok_or_else(crate::error::Error::candidate_not_found)
+    }
+
+    /// Forum topic of the candidate behind `telegram_id`, for notifications
+    /// that only know the chat. Lookup failures are logged and treated as
+    /// "no topic" so the message still goes out.
+    pub async fn telegram_thread_id(&self, telegram_id: Option<i64>) -> Option<i64> {
+        let telegram_id = telegram_id?;
+        let thread_id: std::result::Result<Option<Option<i64>>, sqlx::Error> = sqlx::query_scalar(
+            "SELECT telegram_thread_id FROM candidates WHERE telegram_id = $1 AND deleted_at IS NULL LIMIT 1",
+        )
+        .bind(telegram_id)
+        .fetch_optional(&self.pool)
+        .await;
+        match thread_id {
+            Ok(thread_id) => thread_id.flatten(),
+            Err(e) => {
+                tracing::warn!("Failed to look up Telegram thread for {}: {}", telegram_id, e);
+                None
+            }
+        }
+    }
+
+    /// Timezone of the candidate behind `telegram_id`, for deadlines in
+    /// Telegram messages. Lookup failures are logged and treated as unset.
+    pub async fn timezone_for_telegram(&self, telegram_id: Option<i64>) -> Option<String> {
+        let telegram_id = telegram_id?;
+        let timezone: std::result::Result<Option<Option<String>>, sqlx::Error> = sqlx::query_scalar(
+            "SELECT timezone FROM candidates WHERE telegram_id = $1 AND deleted_at IS NULL LIMIT 1",
+        )
+        .bind(telegram_id)
+        .fetch_optional(&self.pool)
+        .await;
+        match timezone {
+            Ok(timezone) => timezone.flatten(),
+            Err(e) => {
+                tracing::warn!("Failed to look up timezone for {}: {}", telegram_id, e);
+                None
+            }
+        }
+    }
+
+    /// The candidate behind `telegram_id` wrote to the bot, so it can reach
+    /// them again: clears `telegram_unreachable` and the failure count.
+    pub async fn mark_telegram_reachable(&self, telegram_id: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE candidates
+            SET telegram_unreachable = FALSE, telegram_send_failures = 0, updated_at = NOW()
+            WHERE telegram_id = $1 AND (telegram_unreachable OR telegram_send_failures > 0)
+            "#,
+        )
+        .bind(telegram_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count_telegram_unreachable(&self, org_id: uuid::Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM candidates WHERE telegram_unreachable AND deleted_at IS NULL AND organization_id = $1",
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Candidates with no activity in the last `days` days, stalest first,
+    /// optionally only those in `status`.
+    pub async fn list_stale(
+        &self,
+        days: i64,
+        status: Option<&str>,
+        org_id: uuid::Uuid,
+    ) -> Result<Vec<Candidate>> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days);
+        let candidates = sqlx::query_as!(
+            Candidate,
+            r#"
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE deleted_at IS NULL
+              AND organization_id = $1
+              AND ($2::text IS NULL OR status = $2)
+              AND activity.last_activity_at < $3
+            ORDER BY activity.last_activity_at ASC
+            "#,
+            org_id,
+            status,
+            cutoff
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candidates.into_iter().map(with_signed_cv).collect())
+    }
+
+    /// Candidates still in the pipeline (not accepted or rejected) with no
+    /// activity in the last `days` days.
+    pub async fn count_stale(&self, days: i64, org_id: uuid::Uuid) -> Result<i64> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days);
+        let count = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE deleted_at IS NULL
+              AND organization_id = $2
+              AND status NOT IN ('accepted', 'rejected')
+              AND activity.last_activity_at < $1
+            "#,
+        )
+        .bind(cutoff)
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Fills `email_hash` for candidates stored before it existed; returns
+    /// how many rows were updated.
+    pub async fn backfill_email_hashes(&self, secret: &[u8]) -> Result<u64> {
+        let mut updated = 0;
+        loop {
+            let rows: Vec<(uuid::Uuid, String)> = sqlx::query_as(
+                "SELECT id, email FROM candidates WHERE email_hash IS NULL AND email IS NOT NULL LIMIT $1",
+            )
+            .bind(EMAIL_HASH_BACKFILL_BATCH)
+            .fetch_all(&self.pool)
+            .await?;
+            if rows.is_empty() {
+                return Ok(updated);
+            }
+            let ids: Vec<uuid::Uuid> = rows.iter().map(|(id, _)| *id).collect();
+            let hashes: Vec<String> = rows
+                .iter()
+                .map(|(_, email)| email_hash(email, secret))
+                .collect();
+            updated += sqlx::query(
+                r#"
+                UPDATE candidates c SET email_hash = h.email_hash
+                FROM UNNEST($1::uuid[], $2::text[]) AS h(id, email_hash)
+                WHERE c.id = h.id
+                "#,
+            )
+            .bind(&ids)
+            .bind(&hashes)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+    }
+
+    /// Path of a previously stored CV with the same content hash, if any.
+    /// Gives the candidate a fresh status page token, replacing (and
+    /// un-revoking) any earlier one.
+    pub async fn issue_status_token(&self, id: uuid::Uuid) -> Result<String> {
+        let token = crate::utils::token::generate_access_token(STATUS_TOKEN_LENGTH);
+        sqlx::query(
+            "UPDATE candidates SET status_token = $2, status_token_revoked = FALSE WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&token)
+        .execute(&self.pool)
+        .await?;
+        Ok(token)
+    }
+
+    /// Disables the candidate's status page link; `false` if they had none.
+    pub async fn revoke_status_token(&self, id: uuid::Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE candidates SET status_token_revoked = TRUE WHERE id = $1 AND status_token IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The candidate a live (issued, not revoked) status token belongs to.
+    pub async fn get_by_status_token(&self, token: &str) -> Result<Option<Candidate>> {
+        let id: Option<uuid::Uuid> = sqlx::query_scalar(
+            "SELECT id FROM candidates WHERE status_token = $1 AND NOT status_token_revoked AND deleted_at IS NULL",
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await?;
+        match id {
+            Some(id) => self.get_candidate(id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn find_cv_by_hash(&self, cv_hash: &str) -> Result<Option<String>> {
+        let cv_url: Option<String> = sqlx::query_scalar(
+            "SELECT cv_url FROM candidates WHERE cv_hash = $1 AND cv_url IS NOT NULL ORDER BY created_at LIMIT 1",
+        )
+        .bind(cv_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(cv_url)
+    }
+
+    /// Stored (unsigned) CV path of a live candidate, if they have one.
+    pub async fn stored_cv_path(&self, id: uuid::Uuid) -> Result<Option<String>> {
+        let cv_url: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT cv_url FROM candidates WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(cv_url.flatten())
+    }
+
+    /// How many candidates reference each stored CV file.
+    pub async fn cv_reference_counts(&self) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT cv_url, COUNT(*) FROM candidates WHERE cv_url IS NOT NULL GROUP BY cv_url",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
     }
 
-    pub async fn list_candidates(&self) -> Result<Vec<Candidate>> {
+    pub async fn list_candidates(&self, org_id: uuid::Uuid) -> Result<Vec<Candidate>> {
         let candidates = sqlx::query_as!(
             Candidate,
             r#"
-            SELECT id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at,
-            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages
-            FROM candidates 
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE deleted_at IS NULL AND organization_id = $1
             ORDER BY created_at DESC
-            "#
+            "#,
+            org_id
         )
         .fetch_all(&self.pool)
         .await?;
-        Ok(candidates)
+        Ok(candidates.into_iter().map(with_signed_cv).collect())
     }
 
-    pub async fn apply_to_vacancy(&self, candidate_id: uuid::Uuid, vacancy_id: i64) -> Result<CandidateApplication> {
-        let mut tx = self.pool.begin().await?;
+    /// Candidates with at least one of `skills` (exact tags, array overlap).
+    pub async fn list_by_skills(
+        &self,
+        skills: &[String],
+        org_id: uuid::Uuid,
+    ) -> Result<Vec<Candidate>> {
+        let candidates = sqlx::query_as!(
+            Candidate,
+            r#"
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE deleted_at IS NULL AND skills && $1 AND organization_id = $2
+            ORDER BY created_at DESC
+            "#,
+            skills,
+            org_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candidates.into_iter().map(with_signed_cv).collect())
+    }
 
-        let application = sqlx::query_as!(
-            CandidateApplication,
+    /// Candidates whose extracted CV profile lists at least one of
+    /// `profile_skills` (exact tags, served by the GIN index on the path).
+    /// `skills`, when given, additionally requires an overlap with the
+    /// candidate's skill tags.
+    pub async fn list_by_profile_skills(
+        &self,
+        profile_skills: &[String],
+        skills: Option<&[String]>,
+        org_id: uuid::Uuid,
+    ) -> Result<Vec<Candidate>> {
+        let candidates = sqlx::query_as!(
+            Candidate,
             r#"
-            INSERT INTO candidate_applications (candidate_id, vacancy_id)
-            VALUES ($1, $2)
-            ON CONFLICT (candidate_id, vacancy_id) DO UPDATE SET vacancy_id = EXCLUDED.vacancy_id
-            RETURNING id, candidate_id, vacancy_id, created_at
+            SELECT id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = candidates.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
+            FROM candidates
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    candidates.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = candidates.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = candidates.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = candidates.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE deleted_at IS NULL
+              AND extracted_profile -> 'skills' ?| $1
+              AND ($2::text[] IS NULL OR skills && $2)
+              AND organization_id = $3
+            ORDER BY created_at DESC
             "#,
-            candidate_id,
-            vacancy_id
+            profile_skills,
+            skills,
+            org_id
         )
-        .fetch_one(&mut *tx)
+        .fetch_all(&self.pool)
         .await?;
+        Ok(candidates.into_iter().map(with_signed_cv).collect())
+    }
+
+    /// Applies the candidate list filters; empty slices mean "no filter".
+    pub async fn list_filtered(
+        &self,
+        skills: &[String],
+        profile_skills: &[String],
+        org_id: uuid::Uuid,
+    ) -> Result<Vec<Candidate>> {
+        match (skills.is_empty(), profile_skills.is_empty()) {
+            (true, true) => self.list_candidates(org_id).await,
+            (false, true) => self.list_by_skills(skills, org_id).await,
+            (true, false) => self.list_by_profile_skills(profile_skills, None, org_id).await,
+            (false, false) => {
+                self.list_by_profile_skills(profile_skills, Some(skills), org_id)
+                    .await
+            }
+        }
+    }
 
+    /// Stores a parsed CV profile; its skills also replace the skill tags.
+    pub async fn update_extracted_profile(
+        &self,
+        id: uuid::Uuid,
+        profile: &crate::services::ai_service::CvProfile,
+    ) -> Result<()> {
         sqlx::query!(
-            "UPDATE candidates SET vacancy_id = $1, updated_at = NOW() WHERE id = $2",
-            vacancy_id,
-            candidate_id
+            "UPDATE candidates SET extracted_profile = $1, skills = $2, updated_at = NOW() WHERE id = $3",
+            serde_json::to_value(profile)?,
+            &profile.skills,
+            id
         )
-        .execute(&mut *tx)
+        .execute(&self.pool)
         .await?;
-        
+        Ok(())
+    }
+
+    pub async fn update_skills(&self, id: uuid::Uuid, skills: &[String]) -> Result<()> {
+        sqlx::query!(
+            "UPDATE candidates SET skills = $1, updated_at = NOW() WHERE id = $2",
+            skills,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// One live application per candidate and vacancy. Applying again returns
+    /// the existing application with `already_applied` set; after a rejection
+    /// the candidate has to wait out `REAPPLY_COOLDOWN_DAYS`.
+    pub async fn apply_to_vacancy(
+        &self,
+        candidate_id: uuid::Uuid,
+        vacancy_id: i64,
+    ) -> crate::error::Result<ApplicationResult> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, CandidateApplication>(
+            r#"
+            SELECT id, candidate_id, vacancy_id, status, created_at, updated_at
+            FROM candidate_applications
+            WHERE candidate_id = $1 AND vacancy_id = $2 AND status <> 'withdrawn'
+            FOR UPDATE
+            "#,
+        )
+        .bind(candidate_id)
+        .bind(vacancy_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let application = match existing {
+            Some(application) if application.status == "rejected" => {
+                let cooldown = crate::config::get_config().reapply_cooldown_days;
+                let rejected_at = application.updated_at.unwrap_or_else(chrono::Utc::now);
+                let retry_at = reapply_available_at(rejected_at, cooldown);
+                if chrono::Utc::now() < retry_at {
+                    return Err(reapply_cooldown(retry_at));
+                }
+                sqlx::query_as::<_, CandidateApplication>(
+                    r#"
+                    UPDATE candidate_applications
+                    SET status = 'active', created_at = NOW(), updated_at = NOW()
+                    WHERE id = $1
+                    RETURNING id, candidate_id, vacancy_id, status, created_at, updated_at
+                    "#,
+                )
+                .bind(application.id)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+            Some(application) => {
+                tx.commit().await?;
+                return Ok(ApplicationResult { application, already_applied: true });
+            }
+            None => {
+                let inserted = sqlx::query_as::<_, CandidateApplication>(
+                    r#"
+                    INSERT INTO candidate_applications (candidate_id, vacancy_id)
+                    VALUES ($1, $2)
+                    ON CONFLICT (candidate_id, vacancy_id) WHERE status <> 'withdrawn' DO NOTHING
+                    RETURNING id, candidate_id, vacancy_id, status, created_at, updated_at
+                    "#,
+                )
+                .bind(candidate_id)
+                .bind(vacancy_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+                match inserted {
+                    Some(application) => application,
+                    None => {
+                        // A concurrent tap inserted it first.
+                        let application = sqlx::query_as::<_, CandidateApplication>(
+                            r#"
+                            SELECT id, candidate_id, vacancy_id, status, created_at, updated_at
+                            FROM candidate_applications
+                            WHERE candidate_id = $1 AND vacancy_id = $2 AND status <> 'withdrawn'
+                            "#,
+                        )
+                        .bind(candidate_id)
+                        .bind(vacancy_id)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                        tx.commit().await?;
+                        return Ok(ApplicationResult { application, already_applied: true });
+                    }
+                }
+            }
+        };
+
+        sqlx::query("UPDATE candidates SET vacancy_id = $1, updated_at = NOW() WHERE id = $2")
+            .bind(vacancy_id)
+            .bind(candidate_id)
+            .execute(&mut *tx)
+            .await?;
+
         tx.commit().await?;
-        Ok(application)
+        Ok(ApplicationResult { application, already_applied: false })
+    }
+
+    /// Marks the application for each candidate's current vacancy as rejected;
+    /// its `updated_at` starts the re-application cooldown.
+    async fn reject_current_applications(&self, ids: &[uuid::Uuid]) -> crate::error::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE candidate_applications ca
+            SET status = 'rejected', updated_at = NOW()
+            FROM candidates c
+            WHERE ca.candidate_id = c.id
+              AND c.id = ANY($1)
+              AND ca.vacancy_id = c.vacancy_id
+              AND ca.status = 'active'
+            "#,
+        )
+        .bind(ids)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
     }
 
     pub async fn get_candidate_applications(&self, candidate_id: uuid::Uuid) -> Result<Vec<CandidateApplication>> {
         let applications = sqlx::query_as!(
             CandidateApplication,
-            r#"SELECT id, candidate_id, vacancy_id, created_at FROM candidate_applications WHERE candidate_id = $1 ORDER BY created_at DESC"#,
+            r#"SELECT id, candidate_id, vacancy_id, status, created_at, updated_at FROM candidate_applications WHERE candidate_id = $1 ORDER BY created_at DESC"#,
             candidate_id
         )
         .fetch_all(&self.pool)
@@ -196,22 +881,55 @@ impl CandidateService {
         Ok(applications)
     }
 
+    pub async fn withdraw_application(
+        &self,
+        candidate_id: uuid::Uuid,
+        application_id: i32,
+    ) -> crate::error::Result<CandidateApplication> {
+        let application = sqlx::query_as!(
+            CandidateApplication,
+            r#"
+            UPDATE candidate_applications
+            SET status = 'withdrawn', updated_at = NOW()
+            WHERE id = $1 AND candidate_id = $2 AND status = 'active'
+            RETURNING id, candidate_id, vacancy_id, status, created_at, updated_at
+            "#,
+            application_id,
+            candidate_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        application.ok_or_else(|| {
+            crate::error::Error::BadRequest("Application not found or already withdrawn".into())
+        })
+    }
+
     pub async fn get_vacancy_candidates(&self, vacancy_id: i64) -> Result<Vec<Candidate>> {
         let candidates = sqlx::query_as!(
             Candidate,
             r#"
-            SELECT c.id, c.telegram_id, c.name, c.email, c.phone, c.cv_url, c.dob, c.vacancy_id, c.profile_data, c.ai_rating, c.ai_comment, c.status, c.created_at, c.updated_at,
-            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = c.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages
+            SELECT c.id, c.telegram_id, c.telegram_thread_id, c.name, c.email, c.phone, c.cv_url, c.photo_url, c.dob, c.vacancy_id, c.profile_data, c.ai_rating, c.ai_comment, c.skills, c.extracted_profile, c.timezone, c.cv_portfolio, c.telegram_unreachable, c.status, c.deleted_at, c.created_at, c.updated_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.candidate_id = c.id AND m.read_at IS NULL AND m.direction = 'inbound') as unread_messages,
+            activity.last_activity_at
             FROM candidates c
             JOIN candidate_applications ca ON c.id = ca.candidate_id
-            WHERE ca.vacancy_id = $1
+            LEFT JOIN LATERAL (
+                SELECT GREATEST(
+                    c.created_at,
+                    (SELECT MAX(app.created_at) FROM candidate_applications app WHERE app.candidate_id = c.id),
+                    (SELECT MAX(att.updated_at) FROM test_attempts att WHERE att.candidate_email = c.email),
+                    (SELECT MAX(msg.created_at) FROM messages msg WHERE msg.candidate_id = c.id AND msg.direction = 'inbound')
+                ) AS last_activity_at
+            ) activity ON TRUE
+            WHERE ca.vacancy_id = $1 AND c.deleted_at IS NULL
             ORDER BY ca.created_at DESC
             "#,
             vacancy_id
         )
         .fetch_all(&self.pool)
         .await?;
-        Ok(candidates)
+        Ok(candidates.into_iter().map(with_signed_cv).collect())
     }
 
     pub async fn update_ai_suitability(&self, id: uuid::Uuid, rating: i32, comment: String) -> Result<Candidate> {
@@ -221,7 +939,7 @@ impl CandidateService {
             UPDATE candidates
             SET ai_rating = $1, ai_comment = $2, updated_at = NOW()
             WHERE id = $3
-            RETURNING id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at, 0::bigint as "unread_messages!"
+            RETURNING id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at, 0::bigint as "unread_messages!", NULL::timestamptz as last_activity_at
             "#,
             rating,
             comment,
@@ -232,30 +950,328 @@ impl CandidateService {
         Ok(candidate)
     }
 
-    pub async fn update_status(&self, id: uuid::Uuid, status: String) -> Result<Candidate> {
+    pub async fn get_onboarding(&self, id: uuid::Uuid) -> crate::error::Result<CandidateOnboarding> {
+        let onboarding = sqlx::query_as::<_, CandidateOnboarding>(
+            "SELECT id AS candidate_id, onboarding_checklist, checklist_completed_items FROM candidates WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        onboarding.ok_or_else(crate::error::Error::candidate_not_found)
+    }
+
+    /// Stores a freshly generated checklist; any previous progress is reset.
+    pub async fn set_onboarding_checklist(&self, id: uuid::Uuid, items: &[String]) -> crate::error::Result<()> {
+        sqlx::query(
+            "UPDATE candidates SET onboarding_checklist = $1, checklist_completed_items = '[]'::jsonb, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(serde_json::json!(items))
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The cached rejection message; `Ok(None)` when none was generated yet.
+    pub async fn rejection_message(&self, id: uuid::Uuid) -> crate::error::Result<Option<String>> {
+        let row: Option<(Option<String>,)> = sqlx::query_as(
+            "SELECT rejection_message FROM candidates WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(|(message,)| message).ok_or_else(crate::error::Error::candidate_not_found)
+    }
+
+    pub async fn set_rejection_message(&self, id: uuid::Uuid, message: &str) -> crate::error::Result<()> {
+        sqlx::query("UPDATE candidates SET rejection_message = $1, updated_at = NOW() WHERE id = $2")
+            .bind(message)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the set of checklist items the candidate has marked done.
+    pub async fn mark_onboarding_items(
+        &self,
+        id: uuid::Uuid,
+        completed: &[usize],
+    ) -> crate::error::Result<CandidateOnboarding> {
+        let current = self.get_onboarding(id).await?;
+        if current.onboarding_checklist.is_none() {
+            return Err(onboarding_not_ready());
+        }
+        let completed = normalize_completed_items(completed, current.items().len())?;
+        let onboarding = sqlx::query_as::<_, CandidateOnboarding>(
+            r#"
+            UPDATE candidates
+            SET checklist_completed_items = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING id AS candidate_id, onboarding_checklist, checklist_completed_items
+            "#,
+        )
+        .bind(serde_json::json!(completed))
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(onboarding)
+    }
+
+    /// Moves every candidate in `ids` whose status allows it to `status` with a
+    /// single UPDATE. Follows the status workflow; there is no override here.
+    /// Candidates of other organizations end up in `failed`, like missing ones.
+    pub async fn bulk_update_status(
+        &self,
+        ids: &[uuid::Uuid],
+        org_id: uuid::Uuid,
+        status: &str,
+    ) -> crate::error::Result<BulkStatusUpdate> {
+        StatusPipelineService::validate_status(status)?;
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        let from: Vec<String> = StatusPipelineService::previous_statuses(status)
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let updated = sqlx::query_as::<_, BulkStatusChange>(
+            r#"
+            UPDATE candidates c
+            SET status = $1, updated_at = NOW()
+            FROM candidates old
+            WHERE old.id = c.id
+              AND c.id = ANY($2)
+              AND c.status = ANY($3)
+              AND c.deleted_at IS NULL
+              AND c.organization_id = $4
+            RETURNING c.id, old.status AS previous_status, c.vacancy_id
+            "#,
+        )
+        .bind(status)
+        .bind(&ids)
+        .bind(&from)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let in_status: Vec<uuid::Uuid> = sqlx::query_scalar(
+            "SELECT id FROM candidates WHERE id = ANY($1) AND status = $2 AND deleted_at IS NULL AND organization_id = $3",
+        )
+        .bind(&ids)
+        .bind(status)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let failed = ids.into_iter().filter(|id| !in_status.contains(id)).collect();
+
+        if status == "rejected" {
+            let rejected: Vec<uuid::Uuid> = updated.iter().map(|c| c.id).collect();
+            self.reject_current_applications(&rejected).await?;
+        }
+
+        Ok(BulkStatusUpdate { updated, failed })
+    }
+
+    /// Manual status change. `allow_override` (admins only) skips the
+    /// transition check but still rejects unknown statuses.
+    pub async fn update_status(
+        &self,
+        id: uuid::Uuid,
+        status: String,
+        allow_override: bool,
+    ) -> crate::error::Result<Candidate> {
+        self.change_status(id, None, status, allow_override).await
+    }
+
+    /// [`Self::update_status`] for an HR request: candidates of other
+    /// organizations are `404 candidate_not_found`.
+    pub async fn update_status_in_org(
+        &self,
+        id: uuid::Uuid,
+        org_id: uuid::Uuid,
+        status: String,
+        allow_override: bool,
+    ) -> crate::error::Result<Candidate> {
+        self.change_status(id, Some(org_id), status, allow_override).await
+    }
+
+    async fn change_status(
+        &self,
+        id: uuid::Uuid,
+        org_id: Option<uuid::Uuid>,
+        status: String,
+        allow_override: bool,
+    ) -> crate::error::Result<Candidate> {
+        let current = self.current_status(id, org_id).await?;
+        if allow_override {
+            StatusPipelineService::validate_status(&status)?;
+            if !StatusPipelineService::can_transition(&current, &status) {
+                tracing::info!("Status override for candidate {}: '{}' -> '{}'", id, current, status);
+            }
+        } else {
+            StatusPipelineService::validate_transition(&current, &status)?;
+        }
+        self.set_status(id, org_id, status).await
+    }
+
+    /// System-driven transition (test invite / submission). Moves the candidate
+    /// forward to `status` if the graph allows it, otherwise leaves it untouched.
+    pub async fn advance_status(&self, id: uuid::Uuid, status: &str) -> crate::error::Result<Option<Candidate>> {
+        let current = self.current_status(id, None).await?;
+        if current == status || !StatusPipelineService::is_reachable(&current, status) {
+            return Ok(None);
+        }
+        let candidate = self.set_status(id, None, status.to_string()).await?;
+        Ok(Some(candidate))
+    }
+
+    async fn current_status(&self, id: uuid::Uuid, org_id: Option<uuid::Uuid>) -> crate::error::Result<String> {
+        let status: Option<String> = sqlx::query_scalar(
+            "SELECT status FROM candidates WHERE id = $1 AND deleted_at IS NULL AND ($2::uuid IS NULL OR organization_id = $2)",
+        )
+        .bind(id)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        status.ok_or_else(crate::error::Error::candidate_not_found)
+    }
+
+    async fn set_status(
+        &self,
+        id: uuid::Uuid,
+        org_id: Option<uuid::Uuid>,
+        status: String,
+    ) -> crate::error::Result<Candidate> {
         let candidate = sqlx::query_as!(
             Candidate,
             r#"
             UPDATE candidates
             SET status = $1, updated_at = NOW()
-            WHERE id = $2
-            RETURNING id, telegram_id, name, email, phone, cv_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, status, created_at, updated_at, 0::bigint as "unread_messages!"
+            WHERE id = $2 AND ($3::uuid IS NULL OR organization_id = $3)
+            RETURNING id, telegram_id, telegram_thread_id, name, email, phone, cv_url, photo_url, dob, vacancy_id, profile_data, ai_rating, ai_comment, skills, extracted_profile, timezone, cv_portfolio, telegram_unreachable, status, deleted_at, created_at, updated_at, 0::bigint as "unread_messages!", NULL::timestamptz as last_activity_at
             "#,
             status,
-            id
+            id,
+            org_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(crate::error::Error::candidate_not_found)?;
+        if candidate.status == "rejected" {
+            self.reject_current_applications(&[id]).await?;
+        }
+        Ok(candidate)
+    }
+
+    /// Candidates with their latest attempt, oldest change first. With
+    /// `updated_since`, only candidates updated after it, or with an attempt
+    /// updated after it, are returned.
+    /// Only candidates of `org_id`, and their attempts in it, are synced.
+    pub async fn sync_statuses(
+        &self,
+        query: &CandidateStatusSyncQuery,
+        org_id: uuid::Uuid,
+    ) -> crate::error::Result<CandidateStatusSyncPage> {
+        let (page, per_page) = (query.page(), query.per_page());
+        let rows = sqlx::query_as::<_, StatusSyncRow>(
+            r#"
+            WITH changed AS (
+                SELECT id FROM candidates
+                WHERE organization_id = $4 AND ($1::timestamptz IS NULL OR updated_at > $1)
+                UNION
+                SELECT c.id
+                FROM test_attempts ta
+                JOIN candidates c ON c.email = ta.candidate_email AND c.organization_id = ta.organization_id
+                WHERE ta.updated_at > $1 AND ta.organization_id = $4
+            ), synced AS (
+                SELECT
+                    c.id,
+                    c.telegram_id::text AS external_id,
+                    c.name,
+                    c.email,
+                    COALESCE(latest.status, 'pending') AS status,
+                    COALESCE(GREATEST(c.updated_at, latest.updated_at), NOW()) AS last_updated,
+                    latest.id AS latest_attempt_id,
+                    latest.percentage::float8 AS percentage,
+                    latest.passed
+                FROM candidates c
+                JOIN changed ON changed.id = c.id
+                LEFT JOIN LATERAL (
+                    SELECT ta.id, ta.status, ta.percentage, ta.passed, ta.updated_at
+                    FROM test_attempts ta
+                    WHERE ta.candidate_email = c.email AND ta.organization_id = c.organization_id
+                    ORDER BY ta.updated_at DESC NULLS LAST
+                    LIMIT 1
+                ) latest ON TRUE
+            )
+            SELECT synced.*,
+                COUNT(*) OVER () AS total,
+                MAX(last_updated) OVER () AS cursor
+            FROM synced
+            ORDER BY last_updated, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(query.updated_since)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total = match rows.first() {
+            Some(row) => row.total,
+            None => self.count_status_sync(query.updated_since, org_id).await?,
+        };
+        let next_cursor = rows.first().map(|row| row.cursor);
+        Ok(CandidateStatusSyncPage {
+            items: rows.into_iter().map(|row| row.item).collect(),
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+            next_cursor,
+        })
+    }
+
+    /// Matching candidates for a page past the end, where the windowed
+    /// counts aren't available.
+    async fn count_status_sync(
+        &self,
+        updated_since: Option<chrono::DateTime<chrono::Utc>>,
+        org_id: uuid::Uuid,
+    ) -> crate::error::Result<i64> {
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM (
+                SELECT id FROM candidates
+                WHERE organization_id = $2 AND ($1::timestamptz IS NULL OR updated_at > $1)
+                UNION
+                SELECT c.id
+                FROM test_attempts ta
+                JOIN candidates c ON c.email = ta.candidate_email AND c.organization_id = ta.organization_id
+                WHERE ta.updated_at > $1 AND ta.organization_id = $2
+            ) changed
+            "#,
         )
+        .bind(updated_since)
+        .bind(org_id)
         .fetch_one(&self.pool)
         .await?;
-        Ok(candidate)
+        Ok(total)
     }
 
-    pub async fn get_status_counts(&self) -> Result<std::collections::HashMap<String, i64>> {
+    pub async fn get_status_counts(&self, org_id: uuid::Uuid) -> Result<std::collections::HashMap<String, i64>> {
         let rows = sqlx::query!(
             r#"
             SELECT status, COUNT(*) as count
             FROM candidates
+            WHERE deleted_at IS NULL AND organization_id = $1
             GROUP BY status
-            "#
+            "#,
+            org_id
         )
         .fetch_all(&self.pool)
         .await?;
@@ -276,7 +1292,7 @@ impl CandidateService {
         history.push(HistoryItem {
             event_type: "registration".to_string(),
             title: "candidate_profile.event_registered".to_string(),
-            description: Some(candidate.email.clone()),
+            description: candidate.email.clone(),
             timestamp: candidate.created_at.unwrap_or_else(chrono::Utc::now),
             status: Some("candidate_profile.status_completed".to_string()),
             metadata: None,
@@ -305,16 +1321,58 @@ impl CandidateService {
                 status: Some("candidate_profile.status_submitted".to_string()),
                 metadata: None,
             });
+            if app.status == "withdrawn" {
+                history.push(HistoryItem {
+                    event_type: "application_withdrawn".to_string(),
+                    title: "candidate_profile.event_withdrawn".to_string(),
+                    description: Some(app.vacancy_id.to_string()),
+                    timestamp: app.updated_at.unwrap_or_else(chrono::Utc::now),
+                    status: Some("candidate_profile.status_withdrawn".to_string()),
+                    metadata: Some(serde_json::json!({ "application_id": app.id })),
+                });
+            }
+        }
+
+        let rejections: Vec<(Option<chrono::DateTime<chrono::Utc>>, Option<JsonValue>)> = sqlx::query_as(
+            r#"
+            SELECT created_at, changes FROM audit_logs
+            WHERE entity_type = 'candidate' AND entity_id = $1 AND action = 'reject_candidate'
+            ORDER BY created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (created_at, changes) in rejections {
+            history.push(HistoryItem {
+                event_type: "rejection".to_string(),
+                title: "candidate_profile.event_rejected".to_string(),
+                description: None,
+                timestamp: created_at.unwrap_or_else(chrono::Utc::now),
+                status: Some("candidate_profile.status_rejected".to_string()),
+                metadata: changes,
+            });
         }
         
         let attempt_svc = crate::services::attempt_service::AttemptService::new(self.pool.clone());
-        let (attempts, _) = attempt_svc.list_attempts(
-            None, 
-            Some(candidate.email.clone()),
-            None,
-            1,
-            100
-        ).await?;
+        let org_id: uuid::Uuid =
+            sqlx::query_scalar("SELECT organization_id FROM candidates WHERE id = $1")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+        // An erased candidate has no email, and no filter would list everyone's.
+        let attempts = match candidate.email.clone() {
+            Some(email) => attempt_svc.list_attempts(
+                None,
+                Some(email),
+                None,
+                None,
+                1,
+                100,
+                org_id
+            ).await?.0,
+            None => Vec::new(),
+        };
         
         for attempt in attempts {
             let status_key = match attempt.status.as_str() {
@@ -327,11 +1385,7 @@ impl CandidateService {
                 _ => "dashboard.invites.statuses.pending",
             };
             
-            let desc = if let Some(score) = attempt.percentage {
-                Some(format!("{:.1}%", score))
-            } else {
-                None
-            };
+            let desc = attempt.percentage.map(|score| format!("{:.1}%", score));
             
             history.push(HistoryItem {
                 event_type: "test_attempt".to_string(),
@@ -350,51 +1404,174 @@ impl CandidateService {
             });
         }
         
-        history.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        history.sort_by_key(|h| std::cmp::Reverse(h.timestamp));
         Ok(history)
     }
 
-    pub async fn get_history_counts(&self) -> Result<Vec<(String, i64)>> {
-        let rows = sqlx::query!(
+    pub async fn get_history_counts(
+        &self,
+        org_id: uuid::Uuid,
+        start: chrono::DateTime<chrono::Utc>,
+        end: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<(String, i64)>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
             r#"
-            SELECT TO_CHAR(created_at, 'YYYY-MM-DD') as "date!", COUNT(*) as "count!"
+            SELECT TO_CHAR(created_at, 'YYYY-MM-DD') as date, COUNT(*) as count
             FROM candidates
-            WHERE created_at > NOW() - INTERVAL '7 days'
+            WHERE created_at >= $1
+              AND ($2::timestamptz IS NULL OR created_at < $2)
+              AND organization_id = $3
             GROUP BY TO_CHAR(created_at, 'YYYY-MM-DD')
-            ORDER BY "date!"
-            "#
+            ORDER BY date
+            "#,
         )
+        .bind(start)
+        .bind(end)
+        .bind(org_id)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(|r| (r.date, r.count)).collect())
+        Ok(rows)
     }
 
-    pub async fn delete_candidate(&self, id: uuid::Uuid) -> Result<()> {
-        let mut tx = self.pool.begin().await?;
+    /// Candidates of other organizations are `404 candidate_not_found`.
+    pub async fn delete_candidate(
+        &self,
+        id: uuid::Uuid,
+        org_id: uuid::Uuid,
+        reason: Option<String>,
+    ) -> crate::error::Result<()> {
+        let deleted = sqlx::query(
+            r#"
+            UPDATE candidates
+            SET deleted_at = COALESCE(deleted_at, NOW()),
+                deleted_reason = COALESCE($2, deleted_reason),
+                updated_at = NOW()
+            WHERE id = $1 AND organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(reason)
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(crate::error::Error::candidate_not_found());
+        }
+        Ok(())
+    }
 
-        let candidate = sqlx::query!("SELECT email FROM candidates WHERE id = $1", id)
-            .fetch_optional(&mut *tx)
-            .await?;
+    /// Removes everything that identifies the candidate, in one transaction:
+    /// contact, profile and CV data on the row, the copies of their name,
+    /// email, phone and Telegram id on their test attempts in the same
+    /// organization, and their Telegram messages. The soft-deleted row stays
+    /// for statistics. The CV, photo and portfolio files are deleted once the
+    /// transaction has committed.
+    pub async fn erase_candidate(&self, id: uuid::Uuid, org_id: uuid::Uuid) -> crate::error::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let Some(erased) = sqlx::query_as::<_, ErasedCandidate>(
+            r#"
+            SELECT email, telegram_id, organization_id, cv_url, photo_url, cv_portfolio
+            FROM candidates WHERE id = $1 AND organization_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(org_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Err(crate::error::Error::candidate_not_found());
+        };
 
-        if let Some(c) = candidate {
-            let email = c.email;
+        sqlx::query(
+            r#"
+            UPDATE candidates
+            SET name = NULL,
+                email = NULL,
+                email_hash = NULL,
+                status_token = NULL,
+                phone = NULL,
+                telegram_id = NULL,
+                telegram_thread_id = NULL,
+                cv_url = NULL,
+                cv_hash = NULL,
+                cv_portfolio = NULL,
+                photo_url = NULL,
+                profile_data = NULL,
+                extracted_profile = NULL,
+                skills = NULL,
+                ai_comment = NULL,
+                rejection_message = NULL,
+                timezone = NULL,
+                dob = NULL,
+                deleted_at = COALESCE(deleted_at, NOW()),
+                deleted_reason = COALESCE(deleted_reason, 'gdpr_erasure'),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
 
-            sqlx::query!("DELETE FROM test_attempts WHERE candidate_email = $1", email)
-                .execute(&mut *tx)
-                .await?;
-            sqlx::query!("DELETE FROM candidate_applications WHERE candidate_id = $1", id)
-                .execute(&mut *tx)
-                .await?;
-            sqlx::query!("DELETE FROM messages WHERE candidate_id = $1", id)
-                .execute(&mut *tx)
-                .await?;
-            sqlx::query!("DELETE FROM candidates WHERE id = $1", id)
-                .execute(&mut *tx)
-                .await?;
-        }
+        // Attempts keep their answers and scores; only who took them goes.
+        sqlx::query(
+            r#"
+            UPDATE test_attempts
+            SET candidate_name = 'erased',
+                candidate_email = 'erased-' || id || '@erased.invalid',
+                candidate_phone = NULL,
+                candidate_telegram_id = NULL,
+                ip_address = NULL,
+                user_agent = NULL,
+                updated_at = NOW()
+            WHERE test_id IN (SELECT id FROM tests WHERE organization_id = $3)
+              AND (lower(candidate_email) = lower($1) OR candidate_telegram_id = $2)
+            "#,
+        )
+        .bind(&erased.email)
+        .bind(erased.telegram_id)
+        .bind(erased.organization_id)
+        .execute(&mut *tx)
+        .await?;
 
+        sqlx::query("DELETE FROM messages WHERE candidate_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
         tx.commit().await?;
+
+        let portfolio = erased
+            .cv_portfolio
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.as_str().map(str::to_string));
+        for path in erased.cv_url.into_iter().chain(erased.photo_url).chain(portfolio) {
+            // Identical CV uploads share one file; keep it while anyone else uses it.
+            let shared: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM candidates
+                    WHERE id <> $1 AND (cv_url = $2 OR photo_url = $2 OR cv_portfolio ? $2)
+                )
+                "#,
+            )
+            .bind(id)
+            .bind(&path)
+            .fetch_one(&self.pool)
+            .await?;
+            if shared {
+                continue;
+            }
+            let disk_path = crate::utils::signed_url::upload_disk_path(&path);
+            match tokio::fs::remove_file(&disk_path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Failed to delete {} of erased candidate {}: {}", disk_path, id, e),
+            }
+        }
         Ok(())
     }
 }
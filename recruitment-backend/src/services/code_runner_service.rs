@@ -1 +1,471 @@
+use crate::error::{Error, Result};
+use crate::models::question::TestCase;
+use serde::Serialize;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use uuid::Uuid;
 
+/// Only this much of each stream is kept in `graded_answers`; the full
+/// (capped) output is still used for comparison.
+const STORED_OUTPUT_PREVIEW: usize = 4096;
+
+/// When the server runs as root every run drops to its own uid from this
+/// range, so `RLIMIT_NPROC` counts only that run's processes.
+const SANDBOX_UID_BASE: u32 = 200_000;
+const SANDBOX_UID_SLOTS: u32 = 4096;
+static NEXT_SANDBOX_SLOT: AtomicU32 = AtomicU32::new(0);
+
+/// Environment rustc keeps; everything else (database URL, secrets) is
+/// cleared so `env!` cannot bake it into the binary.
+const COMPILER_ENV: [&str; 5] = ["PATH", "HOME", "RUSTUP_HOME", "RUSTUP_TOOLCHAIN", "CARGO_HOME"];
+
+/// Macros that read files while compiling, outside the sandbox.
+const FILE_READING_MACROS: [&str; 3] = ["include", "include_str", "include_bytes"];
+
+#[derive(Debug, Clone)]
+pub struct CodeRunnerLimits {
+    pub cpu_seconds: u64,
+    pub wall_timeout: Duration,
+    pub compile_timeout: Duration,
+    pub max_output_bytes: u64,
+    pub max_memory_bytes: u64,
+}
+
+impl Default for CodeRunnerLimits {
+    fn default() -> Self {
+        Self {
+            cpu_seconds: 5,
+            wall_timeout: Duration::from_secs(10),
+            compile_timeout: Duration::from_secs(60),
+            max_output_bytes: 64 * 1024 * 1024,
+            max_memory_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCaseResult {
+    pub input: String,
+    pub expected: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub output_truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeRunReport {
+    pub compile_error: Option<String>,
+    pub results: Vec<TestCaseResult>,
+}
+
+impl CodeRunReport {
+    pub fn passed_count(&self) -> usize {
+        self.results.iter().filter(|r| r.passed).count()
+    }
+}
+
+#[derive(Clone)]
+pub struct CodeRunnerService {
+    rustc: String,
+    limits: CodeRunnerLimits,
+}
+
+impl Default for CodeRunnerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeRunnerService {
+    pub fn new() -> Self {
+        Self::with_limits(CodeRunnerLimits::default())
+    }
+
+    pub fn with_limits(limits: CodeRunnerLimits) -> Self {
+        Self {
+            rustc: std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()),
+            limits,
+        }
+    }
+
+    pub fn supports(language: &str) -> bool {
+        language.trim().eq_ignore_ascii_case("rust")
+    }
+
+    /// Compiles `source` as a binary and runs it once per test case, feeding
+    /// `input` on stdin and comparing trimmed stdout with `expected`.
+    pub async fn run_rust(&self, source: &str, test_cases: &[TestCase]) -> Result<CodeRunReport> {
+        let dir = std::env::temp_dir().join(format!("code-run-{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await?;
+        let report = self.run_in_dir(&dir, source, test_cases).await;
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        report
+    }
+
+    async fn run_in_dir(
+        &self,
+        dir: &Path,
+        source: &str,
+        test_cases: &[TestCase],
+    ) -> Result<CodeRunReport> {
+        let src_path = dir.join("main.rs");
+        let bin_path = dir.join("main");
+        tokio::fs::write(&src_path, source).await?;
+        tokio::fs::create_dir(dir.join("jail")).await?;
+
+        if let Some(name) = file_reading_macro(source) {
+            return Ok(CodeRunReport {
+                compile_error: Some(format!("`{}!` is not allowed", name)),
+                results: Vec::new(),
+            });
+        }
+
+        let mut compile = Command::new(&self.rustc);
+        compile.env_clear();
+        for key in COMPILER_ENV {
+            if let Some(value) = std::env::var_os(key) {
+                compile.env(key, value);
+            }
+        }
+        // Static, so the binary runs in an empty chroot.
+        let compile = compile
+            .args([
+                "--edition",
+                "2021",
+                "-C",
+                "opt-level=1",
+                "-C",
+                "debuginfo=0",
+                "-C",
+                "target-feature=+crt-static",
+                "-o",
+            ])
+            .arg(&bin_path)
+            .arg(&src_path)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+
+        let compiled = tokio::time::timeout(self.limits.compile_timeout, compile)
+            .await
+            .map_err(|_| Error::Internal("Code compilation timed out".to_string()))??;
+
+        if !compiled.status.success() {
+            return Ok(CodeRunReport {
+                compile_error: Some(preview(&compiled.stderr)),
+                results: Vec::new(),
+            });
+        }
+
+        let jail = Jail::new(dir)?;
+        let mut results = Vec::with_capacity(test_cases.len());
+        for case in test_cases {
+            results.push(self.run_case(&jail, case).await?);
+        }
+
+        Ok(CodeRunReport {
+            compile_error: None,
+            results,
+        })
+    }
+
+    async fn run_case(&self, jail: &Jail, case: &TestCase) -> Result<TestCaseResult> {
+        // The path inside the chroot set up by `apply_sandbox`.
+        let mut cmd = Command::new("/main");
+        cmd.env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let limits = self.limits.clone();
+        let jail = jail.clone();
+        // SAFETY: apply_sandbox only calls async-signal-safe libc functions
+        // and does not allocate.
+        unsafe {
+            cmd.pre_exec(move || apply_sandbox(&limits, &jail));
+        }
+
+        let mut child = cmd.spawn()?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = case.input.clone();
+        tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let cap = self.limits.max_output_bytes;
+
+        let run = async {
+            let (out, err) = tokio::join!(read_capped(stdout, cap), read_capped(stderr, cap));
+            let (out, err) = (out?, err?);
+            if out.1 || err.1 {
+                let _ = child.start_kill();
+            }
+            let status = child.wait().await?;
+            Ok::<(Vec<u8>, Vec<u8>, bool, ExitStatus), std::io::Error>((
+                out.0,
+                err.0,
+                out.1 || err.1,
+                status,
+            ))
+        };
+
+        match tokio::time::timeout(self.limits.wall_timeout, run).await {
+            Ok(outcome) => {
+                let (stdout, stderr, truncated, status) = outcome?;
+                let stdout = String::from_utf8_lossy(&stdout).to_string();
+                let passed =
+                    !truncated && status.success() && stdout.trim() == case.expected.trim();
+                Ok(TestCaseResult {
+                    input: case.input.clone(),
+                    expected: case.expected.clone(),
+                    stdout: truncate_preview(&stdout),
+                    stderr: preview(&stderr),
+                    passed,
+                    exit_code: status.code(),
+                    timed_out: !truncated && cpu_limit_exceeded(&status),
+                    output_truncated: truncated,
+                })
+            }
+            Err(_) => {
+                let _ = child.kill().await;
+                Ok(TestCaseResult {
+                    input: case.input.clone(),
+                    expected: case.expected.clone(),
+                    stdout: String::new(),
+                    stderr: "Execution timed out".to_string(),
+                    passed: false,
+                    exit_code: None,
+                    timed_out: true,
+                    output_truncated: false,
+                })
+            }
+        }
+    }
+}
+
+async fn read_capped<R: AsyncRead + Unpin>(
+    reader: R,
+    cap: u64,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut buf = Vec::new();
+    reader.take(cap + 1).read_to_end(&mut buf).await?;
+    let truncated = buf.len() as u64 > cap;
+    buf.truncate(cap as usize);
+    Ok((buf, truncated))
+}
+
+fn preview(bytes: &[u8]) -> String {
+    truncate_preview(&String::from_utf8_lossy(bytes))
+}
+
+fn truncate_preview(s: &str) -> String {
+    if s.len() <= STORED_OUTPUT_PREVIEW {
+        return s.to_string();
+    }
+    let mut end = STORED_OUTPUT_PREVIEW;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &s[..end])
+}
+
+/// The first file-reading macro `source` names, comments included.
+fn file_reading_macro(source: &str) -> Option<&'static str> {
+    source
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .find_map(|word| FILE_READING_MACROS.iter().find(|m| **m == word).copied())
+}
+
+/// Paths and ids for one run's sandbox, prepared before forking so the child
+/// does not allocate.
+#[derive(Clone)]
+struct Jail {
+    /// Empty directory a private tmpfs is mounted on and chrooted into.
+    root: std::ffi::CString,
+    binary: std::ffi::CString,
+    /// `/main` inside `root`; the binary is bind-mounted here read-only.
+    target: std::ffi::CString,
+    /// Used when the server runs as root.
+    uid: u32,
+    /// Written to the new user namespace when the server does not run as root.
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+}
+
+impl Jail {
+    fn new(dir: &Path) -> Result<Self> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = |path: &Path| {
+            std::ffi::CString::new(path.as_os_str().as_bytes())
+                .map_err(|_| Error::Internal("Sandbox path contains a NUL byte".to_string()))
+        };
+        let slot = NEXT_SANDBOX_SLOT.fetch_add(1, Ordering::Relaxed) % SANDBOX_UID_SLOTS;
+        // SAFETY: getuid and getgid cannot fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        Ok(Self {
+            root: c_path(&dir.join("jail"))?,
+            binary: c_path(&dir.join("main"))?,
+            target: c_path(&dir.join("jail").join("main"))?,
+            uid: SANDBOX_UID_BASE + slot,
+            uid_map: format!("{} {} 1", uid, uid).into_bytes(),
+            gid_map: format!("{} {} 1", gid, gid).into_bytes(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn cpu_limit_exceeded(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    matches!(status.signal(), Some(libc::SIGXCPU) | Some(libc::SIGKILL))
+}
+
+#[cfg(not(unix))]
+fn cpu_limit_exceeded(_status: &ExitStatus) -> bool {
+    false
+}
+
+/// Runs in the forked child before exec. Besides the resource limits the
+/// program gets private mount and network namespaces, a chroot into a
+/// read-only tmpfs that holds only its binary, and no way to start other
+/// processes or threads (`RLIMIT_NPROC` of one). As root it drops to the
+/// run's own uid; otherwise a user namespace provides the privileges for the
+/// mounts and the uid stays the server's, without capabilities after exec.
+/// Any failure aborts the spawn.
+#[cfg(target_os = "linux")]
+fn apply_sandbox(limits: &CodeRunnerLimits, jail: &Jail) -> std::io::Result<()> {
+    fn check(ret: libc::c_int) -> std::io::Result<()> {
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+    fn rlimit(soft: u64, hard: u64) -> libc::rlimit {
+        libc::rlimit {
+            rlim_cur: soft as libc::rlim_t,
+            rlim_max: hard as libc::rlim_t,
+        }
+    }
+    unsafe fn write_file(path: &std::ffi::CStr, data: &[u8]) -> std::io::Result<()> {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let written = libc::write(fd, data.as_ptr().cast(), data.len());
+        libc::close(fd);
+        if written != data.len() as isize {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    let null = std::ptr::null::<libc::c_char>();
+    unsafe {
+        check(libc::setrlimit(
+            libc::RLIMIT_CPU,
+            &rlimit(limits.cpu_seconds, limits.cpu_seconds + 1),
+        ))?;
+        check(libc::setrlimit(
+            libc::RLIMIT_AS,
+            &rlimit(limits.max_memory_bytes, limits.max_memory_bytes),
+        ))?;
+        check(libc::setrlimit(
+            libc::RLIMIT_FSIZE,
+            &rlimit(limits.max_output_bytes, limits.max_output_bytes),
+        ))?;
+        check(libc::setrlimit(libc::RLIMIT_CORE, &rlimit(0, 0)))?;
+
+        let as_root = libc::geteuid() == 0;
+        if as_root {
+            check(libc::unshare(libc::CLONE_NEWNS | libc::CLONE_NEWNET))?;
+        } else {
+            check(libc::unshare(
+                libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET,
+            ))?;
+            write_file(c"/proc/self/setgroups", b"deny")?;
+            write_file(c"/proc/self/uid_map", &jail.uid_map)?;
+            write_file(c"/proc/self/gid_map", &jail.gid_map)?;
+        }
+
+        check(libc::mount(
+            null,
+            c"/".as_ptr(),
+            null,
+            libc::MS_REC | libc::MS_PRIVATE,
+            std::ptr::null(),
+        ))?;
+        let fs_flags = libc::MS_NOSUID | libc::MS_NODEV;
+        check(libc::mount(
+            c"tmpfs".as_ptr(),
+            jail.root.as_ptr(),
+            c"tmpfs".as_ptr(),
+            fs_flags,
+            c"size=64k,mode=0755".as_ptr().cast(),
+        ))?;
+        let fd = libc::open(
+            jail.target.as_ptr(),
+            libc::O_CREAT | libc::O_WRONLY | libc::O_CLOEXEC,
+            0o555,
+        );
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        libc::close(fd);
+        check(libc::mount(
+            jail.binary.as_ptr(),
+            jail.target.as_ptr(),
+            null,
+            libc::MS_BIND,
+            std::ptr::null(),
+        ))?;
+        check(libc::mount(
+            null,
+            jail.target.as_ptr(),
+            null,
+            libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | fs_flags,
+            std::ptr::null(),
+        ))?;
+        check(libc::mount(
+            null,
+            jail.root.as_ptr(),
+            null,
+            libc::MS_REMOUNT | libc::MS_RDONLY | fs_flags,
+            std::ptr::null(),
+        ))?;
+        check(libc::chdir(jail.root.as_ptr()))?;
+        check(libc::chroot(c".".as_ptr()))?;
+        check(libc::chdir(c"/".as_ptr()))?;
+
+        check(libc::setrlimit(libc::RLIMIT_NPROC, &rlimit(1, 1)))?;
+        if as_root {
+            check(libc::setgroups(0, std::ptr::null()))?;
+            check(libc::setgid(jail.uid))?;
+            check(libc::setuid(jail.uid))?;
+        }
+        check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_sandbox(_limits: &CodeRunnerLimits, _jail: &Jail) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "code execution sandbox requires Linux",
+    ))
+}
@@ -0,0 +1,219 @@
+use crate::dto::integration_dto::DashboardStats;
+use crate::error::{Error, Result};
+use crate::services::{
+    attempt_service::AttemptService,
+    candidate_service::CandidateService,
+    koinotinav_service::KoinotinavService,
+    message_service::MessageService,
+    test_service::{TestFilter, TestService},
+    vacancy_service::VacancyService,
+};
+use crate::utils::ttl_cache::TtlCache;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures_util::TryFutureExt;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+const DASHBOARD_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(20);
+pub const DEFAULT_HISTORY_DAYS: i64 = 7;
+
+/// Inclusive calendar-day range (UTC) accepted as `?from=YYYY-MM-DD&to=YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+pub struct DashboardRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DashboardRange {
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(Error::BadRequest(
+                    "'from' must not be after 'to'".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.from.map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+    }
+
+    /// Exclusive upper bound: midnight after `to`.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.to
+            .and_then(|d| d.succ_opt())
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+    }
+
+    /// Window for the daily registrations chart; falls back to the last
+    /// `DEFAULT_HISTORY_DAYS` days when no lower bound is given.
+    pub fn history_window(&self, now: DateTime<Utc>) -> (DateTime<Utc>, Option<DateTime<Utc>>) {
+        let end = self.end();
+        let start = self
+            .start()
+            .unwrap_or_else(|| end.unwrap_or(now) - Duration::days(DEFAULT_HISTORY_DAYS));
+        (start, end)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DashboardSnapshot {
+    pub candidates_by_status: HashMap<String, i64>,
+    pub total_candidates: i64,
+    pub unread_messages: i64,
+    pub active_tests: i64,
+    pub internal_vacancies: i64,
+    pub external_vacancies: i64,
+    pub candidates_history: Vec<(String, i64)>,
+    pub attempts_status: HashMap<String, i64>,
+    pub attempts_by_source: HashMap<String, i64>,
+    pub telegram_unreachable_candidates: i64,
+    pub stale_candidates: i64,
+}
+
+impl DashboardSnapshot {
+    pub fn active_vacancies(&self) -> i64 {
+        self.internal_vacancies + self.external_vacancies
+    }
+}
+
+impl From<DashboardSnapshot> for DashboardStats {
+    fn from(snapshot: DashboardSnapshot) -> Self {
+        Self {
+            total_candidates: snapshot.total_candidates,
+            unread_messages: snapshot.unread_messages,
+            active_tests: snapshot.active_tests,
+            active_vacancies: snapshot.active_vacancies(),
+            candidates_by_status: snapshot.candidates_by_status,
+            candidates_history: snapshot.candidates_history,
+            attempts_status: snapshot.attempts_status,
+            attempts_by_source: snapshot.attempts_by_source,
+            telegram_unreachable_candidates: snapshot.telegram_unreachable_candidates,
+            stale_candidates: snapshot.stale_candidates,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DashboardService {
+    pool: PgPool,
+    koinotinav_service: KoinotinavService,
+    cache: TtlCache<(uuid::Uuid, DashboardRange), DashboardSnapshot>,
+}
+
+impl DashboardService {
+    pub fn new(pool: PgPool, koinotinav_service: KoinotinavService) -> Self {
+        Self {
+            pool,
+            koinotinav_service,
+            cache: TtlCache::new(DASHBOARD_CACHE_TTL),
+        }
+    }
+
+    /// Figures of one organization; external vacancies are shared by all.
+    pub async fn get_stats(&self, org_id: uuid::Uuid, range: DashboardRange) -> Result<DashboardSnapshot> {
+        range.validate()?;
+        if let Some(snapshot) = self.cache.get(&(org_id, range)) {
+            return Ok(snapshot);
+        }
+
+        let snapshot = self.compute(org_id, range).await?;
+        self.cache.insert((org_id, range), snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// Every figure is an independent read, so they run concurrently; the
+    /// endpoint costs about as much as its slowest query instead of the sum.
+    async fn compute(&self, org_id: uuid::Uuid, range: DashboardRange) -> Result<DashboardSnapshot> {
+        let candidate_service = CandidateService::new(self.pool.clone());
+        let message_service = MessageService::new(self.pool.clone());
+        let test_service = TestService::new(self.pool.clone());
+        let vacancy_service = VacancyService::new(self.pool.clone());
+        let attempt_service = AttemptService::new(self.pool.clone());
+        let (history_start, history_end) = range.history_window(Utc::now());
+
+        let active_tests = async {
+            test_service
+                .list_tests(
+                    1,
+                    1,
+                    Some(TestFilter {
+                        is_active: Some(true),
+                        created_by: None,
+                        search: None,
+                    }),
+                    None,
+                    org_id,
+                )
+                .await
+                .map(|page| page.total)
+        };
+
+        let internal_vacancies = async {
+            Ok::<_, Error>(match vacancy_service.count_published(org_id).await {
+                Ok(count) => count,
+                Err(e) => {
+                    tracing::error!("Failed to fetch vacancies for dashboard: {:?}", e);
+                    0
+                }
+            })
+        };
+
+        let external_vacancies = async {
+            Ok::<_, Error>(match self.koinotinav_service.fetch_vacancies_page(1, 1).await {
+                Ok((_, total)) => total,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to fetch external vacancy total for dashboard: {:?}",
+                        e
+                    );
+                    self.koinotinav_service.cached_vacancy_count().unwrap_or(0) as i64
+                }
+            })
+        };
+
+        let (
+            candidates_by_status,
+            telegram_unreachable_candidates,
+            stale_candidates,
+            unread_messages,
+            active_tests,
+            internal_vacancies,
+            external_vacancies,
+            candidates_history,
+            attempts_status,
+            attempts_by_source,
+        ) = tokio::try_join!(
+            candidate_service.get_status_counts(org_id).err_into(),
+            candidate_service.count_telegram_unreachable(org_id).err_into(),
+            candidate_service
+                .count_stale(crate::services::candidate_service::STALE_CANDIDATE_DAYS, org_id)
+                .err_into(),
+            message_service.total_unread_count(org_id),
+            active_tests,
+            internal_vacancies,
+            external_vacancies,
+            candidate_service.get_history_counts(org_id, history_start, history_end).err_into(),
+            attempt_service.get_status_distribution_between(org_id, range.start(), range.end()),
+            attempt_service.get_source_distribution_between(org_id, range.start(), range.end()),
+        )?;
+        let total_candidates: i64 = candidates_by_status.values().sum();
+
+        Ok(DashboardSnapshot {
+            candidates_by_status,
+            total_candidates,
+            unread_messages,
+            active_tests,
+            internal_vacancies,
+            external_vacancies,
+            candidates_history,
+            attempts_status,
+            attempts_by_source,
+            telegram_unreachable_candidates,
+            stale_candidates,
+        })
+    }
+}
@@ -0,0 +1,201 @@
+use crate::dto::integration_dto::DashboardStats;
+use crate::error::{Error, Result};
+use crate::services::dashboard_service::{DashboardRange, DashboardService};
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Hourly,
+    Daily,
+}
+
+impl Granularity {
+    fn bucket_size(self) -> Duration {
+        match self {
+            Self::Hourly => Duration::hours(1),
+            Self::Daily => Duration::days(1),
+        }
+    }
+
+    /// Start of the UTC hour or day containing `at`.
+    pub fn bucket(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at.duration_trunc(self.bucket_size()).unwrap_or(at)
+    }
+}
+
+/// `?from=YYYY-MM-DD&to=YYYY-MM-DD&granularity=hourly|daily`
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct DashboardHistoryQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub granularity: Option<Granularity>,
+}
+
+/// Counters of one bucket, averaged over the snapshots that fell into it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DashboardHistoryPoint {
+    pub captured_at: DateTime<Utc>,
+    pub samples: usize,
+    pub total_candidates: f64,
+    pub unread_messages: f64,
+    pub active_tests: f64,
+    pub active_vacancies: f64,
+    pub candidates_by_status: BTreeMap<String, f64>,
+    pub attempts_status: BTreeMap<String, f64>,
+}
+
+/// The numeric part of a stored `DashboardStats`; missing fields read as zero
+/// so older snapshots stay usable if the stats shape grows.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct StoredCounters {
+    total_candidates: i64,
+    unread_messages: i64,
+    active_tests: i64,
+    active_vacancies: i64,
+    candidates_by_status: HashMap<String, i64>,
+    attempts_status: HashMap<String, i64>,
+}
+
+fn average(sum: i64, samples: usize) -> f64 {
+    (sum as f64 * 100.0 / samples as f64).round() / 100.0
+}
+
+fn average_map(sums: BTreeMap<String, i64>, samples: usize) -> BTreeMap<String, f64> {
+    sums.into_iter()
+        .map(|(key, sum)| (key, average(sum, samples)))
+        .collect()
+}
+
+/// Groups snapshots into hourly or daily buckets and averages every counter.
+/// A status missing from a snapshot counts as zero for that sample.
+pub fn aggregate(
+    rows: &[(DateTime<Utc>, JsonValue)],
+    granularity: Granularity,
+) -> Vec<DashboardHistoryPoint> {
+    let mut buckets: BTreeMap<DateTime<Utc>, Vec<StoredCounters>> = BTreeMap::new();
+    for (captured_at, stats) in rows {
+        let counters = match StoredCounters::deserialize(stats) {
+            Ok(counters) => counters,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping unreadable dashboard snapshot at {}: {}",
+                    captured_at,
+                    e
+                );
+                continue;
+            }
+        };
+        buckets
+            .entry(granularity.bucket(*captured_at))
+            .or_default()
+            .push(counters);
+    }
+
+    buckets
+        .into_iter()
+        .map(|(bucket, samples)| {
+            let n = samples.len();
+            let mut by_status: BTreeMap<String, i64> = BTreeMap::new();
+            let mut attempts: BTreeMap<String, i64> = BTreeMap::new();
+            for sample in &samples {
+                for (status, count) in &sample.candidates_by_status {
+                    *by_status.entry(status.clone()).or_default() += count;
+                }
+                for (status, count) in &sample.attempts_status {
+                    *attempts.entry(status.clone()).or_default() += count;
+                }
+            }
+            DashboardHistoryPoint {
+                captured_at: bucket,
+                samples: n,
+                total_candidates: average(samples.iter().map(|s| s.total_candidates).sum(), n),
+                unread_messages: average(samples.iter().map(|s| s.unread_messages).sum(), n),
+                active_tests: average(samples.iter().map(|s| s.active_tests).sum(), n),
+                active_vacancies: average(samples.iter().map(|s| s.active_vacancies).sum(), n),
+                candidates_by_status: average_map(by_status, n),
+                attempts_status: average_map(attempts, n),
+            }
+        })
+        .collect()
+}
+
+/// Top of the next UTC hour strictly after `now`.
+pub fn next_hourly_run(now: DateTime<Utc>) -> DateTime<Utc> {
+    Granularity::Hourly.bucket(now) + Duration::hours(1)
+}
+
+#[derive(Clone)]
+pub struct DashboardSnapshotService {
+    pool: PgPool,
+    dashboard_service: DashboardService,
+}
+
+impl DashboardSnapshotService {
+    pub fn new(pool: PgPool, dashboard_service: DashboardService) -> Self {
+        Self {
+            pool,
+            dashboard_service,
+        }
+    }
+
+    /// Captures one snapshot per organization.
+    pub async fn record(&self) -> Result<()> {
+        let organizations: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM organizations ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+        for org_id in organizations {
+            self.record_for(org_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn record_for(&self, org_id: Uuid) -> Result<()> {
+        let snapshot = self
+            .dashboard_service
+            .get_stats(org_id, DashboardRange::default())
+            .await?;
+        let stats = serde_json::to_value(DashboardStats::from(snapshot))
+            .map_err(|e| Error::Internal(format!("Failed to serialize dashboard stats: {}", e)))?;
+
+        sqlx::query("INSERT INTO dashboard_snapshots (stats, organization_id) VALUES ($1, $2)")
+            .bind(stats)
+            .bind(org_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Snapshots captured in `[from, to)` (open-ended when `to` is `None`),
+    /// bucketed by `granularity`.
+    pub async fn query(
+        &self,
+        org_id: Uuid,
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
+        granularity: Granularity,
+    ) -> Result<Vec<DashboardHistoryPoint>> {
+        let rows: Vec<(DateTime<Utc>, JsonValue)> = sqlx::query_as(
+            r#"
+            SELECT captured_at, stats FROM dashboard_snapshots
+            WHERE captured_at >= $1 AND ($2::timestamptz IS NULL OR captured_at < $2)
+              AND organization_id = $3
+            ORDER BY captured_at
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(aggregate(&rows, granularity))
+    }
+}
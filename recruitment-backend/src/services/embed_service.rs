@@ -55,6 +55,49 @@ impl EmbedService {
         Ok(parsed.data.into_iter().map(|d| d.embedding).collect())
     }
 
+    /// [`embed_texts`](Self::embed_texts) in sequential requests of at most
+    /// `batch_size` texts each, so large question sets stay under the API's
+    /// request size limit. Embeddings keep the order of `texts`.
+    pub async fn embed_texts_batched(
+        &self,
+        texts: &[String],
+        batch_size: usize,
+    ) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(batch_size.max(1)) {
+            embeddings.extend(self.embed_texts(batch).await?);
+        }
+        Ok(embeddings)
+    }
+
+    /// Pairwise [`cosine_sim`](Self::cosine_sim) of `embs`. Each vector is
+    /// normalised once and only the upper triangle is computed, so every
+    /// pair costs a single dot product.
+    pub fn cosine_sim_matrix(embs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let unit: Vec<Option<Vec<f32>>> = embs
+            .iter()
+            .map(|e| {
+                let norm = dot(e, e).sqrt();
+                (norm != 0.0).then(|| e.iter().map(|x| x / norm).collect())
+            })
+            .collect();
+
+        let n = unit.len();
+        let mut matrix = vec![vec![0f32; n]; n];
+        for (i, a) in unit.iter().enumerate() {
+            let Some(a) = a else { continue };
+            matrix[i][i] = 1.0;
+            for (j, b) in unit.iter().enumerate().skip(i + 1) {
+                if let Some(b) = b {
+                    let sim = dot(a, b);
+                    matrix[i][j] = sim;
+                    matrix[j][i] = sim;
+                }
+            }
+        }
+        matrix
+    }
+
     pub fn cosine_sim(a: &[f32], b: &[f32]) -> f32 {
         let mut dot = 0f32;
         let mut na = 0f32;
@@ -71,3 +114,7 @@ impl EmbedService {
         }
     }
 }
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
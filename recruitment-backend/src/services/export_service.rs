@@ -1,27 +1,141 @@
-use crate::models::candidate::{Candidate, HistoryItem};
+use crate::models::candidate::{Candidate, CandidateApplication, HistoryItem};
+use crate::models::message::Message;
+use crate::models::test_attempt::TestAttempt;
 use crate::error::Result;
+use crate::services::report_service::WeeklyReport;
+use crate::utils::html::sanitize_to_text;
 use rust_xlsxwriter::*;
+use serde_json::json;
 use std::collections::HashMap;
+use std::io::{Cursor, Write};
 use uuid::Uuid;
+use zip::write::SimpleFileOptions;
 
 pub struct ExportService;
 
+/// Optional worksheets of the candidates export. Both are off by default so
+/// small exports only build the main sheet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    pub include_history: bool,
+    pub include_stats: bool,
+}
+
+/// Everything stored about one candidate besides the candidate row, for
+/// `generate_candidate_data_archive`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CandidateDataExport<'a> {
+    pub applications: &'a [CandidateApplication],
+    pub history: &'a [HistoryItem],
+    pub attempts: &'a [TestAttempt],
+    pub messages: &'a [Message],
+    /// File name and contents of the stored CV.
+    pub cv: Option<(&'a str, &'a [u8])>,
+}
+
+/// Attempt columns left out of the data export: live access tokens and the
+/// question snapshot with its correct answers.
+pub const ATTEMPT_WITHHELD_FIELDS: [&str; 2] = ["access_token", "questions_snapshot"];
+
+const KNOWN_STATUSES: [&str; 5] = ["new", "reviewing", "contacted", "accepted", "rejected"];
+
+/// Candidate count per status: the five main statuses always (in pipeline
+/// order), followed by any other status present, alphabetically.
+pub fn status_distribution(candidates: &[Candidate]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for c in candidates {
+        *counts.entry(c.status.as_str()).or_default() += 1;
+    }
+    let mut others: Vec<&str> = counts
+        .keys()
+        .copied()
+        .filter(|s| !KNOWN_STATUSES.contains(s))
+        .collect();
+    others.sort_unstable();
+    KNOWN_STATUSES
+        .iter()
+        .copied()
+        .chain(others)
+        .map(|s| (s.to_string(), counts.get(s).copied().unwrap_or(0)))
+        .collect()
+}
+
+/// AI ratings in ten-point buckets (`0–9` … `90–100`), plus candidates
+/// without a rating under `Без оценки`.
+pub fn rating_histogram(candidates: &[Candidate]) -> Vec<(String, usize)> {
+    let mut buckets = [0usize; 10];
+    let mut unrated = 0;
+    for c in candidates {
+        match c.ai_rating {
+            Some(r) => buckets[(r.clamp(0, 100) / 10).min(9) as usize] += 1,
+            None => unrated += 1,
+        }
+    }
+    let mut histogram: Vec<(String, usize)> = buckets
+        .iter()
+        .enumerate()
+        .map(|(i, n)| {
+            let upper = if i == 9 { 100 } else { i * 10 + 9 };
+            (format!("{}–{}", i * 10, upper), *n)
+        })
+        .collect();
+    histogram.push(("Без оценки".to_string(), unrated));
+    histogram
+}
+
 impl ExportService {
-    fn strip_html(input: &str) -> String {
-        let mut result = String::new();
-        let mut inside_tag = false;
-        
-        for c in input.chars() {
-            if c == '<' {
-                inside_tag = true;
-            } else if c == '>' {
-                inside_tag = false;
-            } else if !inside_tag {
-                result.push(c);
-            }
+    /// "Файлы портфолио" cell: the file count and their names, e.g.
+    /// `2: a.pdf, b.png`; `—` without a portfolio.
+    pub fn portfolio_summary(paths: &[String]) -> String {
+        if paths.is_empty() {
+            return "—".to_string();
+        }
+        let names: Vec<&str> = paths
+            .iter()
+            .map(|path| {
+                std::path::Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path)
+            })
+            .collect();
+        format!("{}: {}", paths.len(), names.join(", "))
+    }
+
+    fn status_label(status: &str) -> &str {
+        match status {
+            "new" => "Новый",
+            "reviewing" => "Рассмотрение",
+            "contacted" => "Связались",
+            "accepted" => "Приняты",
+            "rejected" => "Отказано",
+            _ => status,
+        }
+    }
+
+    fn event_title(event_type: &str) -> &str {
+        match event_type {
+            "registration" => "Регистрация",
+            "application" => "Отклик",
+            "profile_update" => "Обновление",
+            "test_attempt" => "Тест",
+            _ => event_type,
+        }
+    }
+
+    fn history_status_label(status: &str) -> &str {
+        match status {
+            "candidate_profile.status_completed" => "Завершено",
+            "candidate_profile.status_passed" => "Пройден",
+            "candidate_profile.status_failed" => "Не пройден",
+            "candidate_profile.status_submitted" => "Отправлено",
+            "dashboard.invites.statuses.pending" => "Ожидает",
+            "dashboard.invites.statuses.in_progress" => "В процессе",
+            "dashboard.invites.statuses.timeout" => "Время вышло",
+            "dashboard.invites.statuses.escaped" => "Покинул",
+            "dashboard.invites.statuses.needs_review" => "Проверка",
+            _ => status,
         }
-        
-        result.trim().replace("&nbsp;", " ").replace("&quot;", "\"").replace("&amp;", "&").to_string()
     }
 }
 
@@ -29,7 +143,8 @@ impl ExportService {
     pub fn generate_candidates_xlsx(
         candidates: &[Candidate],
         vacancy_map: &HashMap<i64, String>,
-        history_map: &HashMap<Uuid, Vec<HistoryItem>>
+        history_map: &HashMap<Uuid, Vec<HistoryItem>>,
+        options: ExportOptions,
     ) -> Result<Vec<u8>> {
         let mut workbook = Workbook::new();
         let worksheet = workbook.add_worksheet();
@@ -65,6 +180,13 @@ impl ExportService {
             ("Дата регистрации", 20.0),
             ("Последнее обновление", 22.0),
             ("Непрочит. сообщ.", 16.0),
+            ("Город",            20.0),
+            ("Образование",      30.0),
+            ("Языки",            20.0),
+            ("Опыт (лет)",       12.0),
+            ("Фото",             30.0),
+            ("Telegram тема",    14.0),
+            ("Файлы портфолио",  40.0),
         ];
 
         for (i, (_, width)) in columns.iter().enumerate() {
@@ -131,8 +253,8 @@ impl ExportService {
             worksheet.write_number_with_format(row, 0, (idx + 1) as f64, &center_fmt)?;
 
             let name_fmt = base_fmt.clone().set_bold();
-            worksheet.write_string_with_format(row, 1, &candidate.name, &name_fmt)?;
-            worksheet.write_string_with_format(row, 2, &candidate.email, &base_fmt)?;
+            worksheet.write_string_with_format(row, 1, candidate.name_or_empty(), &name_fmt)?;
+            worksheet.write_string_with_format(row, 2, candidate.email_or_empty(), &base_fmt)?;
             worksheet.write_string_with_format(row, 3, candidate.phone.as_deref().unwrap_or("—"), &base_fmt)?;
             let dob_str = candidate.dob
                 .map(|d| d.format("%d.%m.%Y").to_string())
@@ -150,14 +272,7 @@ impl ExportService {
                 "rejected" => status_rejected,
                 _ => Color::RGB(0x64748B),
             };
-            let status_display = match candidate.status.as_str() {
-                "new" => "Новый",
-                "reviewing" => "Рассмотрение",
-                "contacted" => "Связались",
-                "accepted" => "Приняты",
-                "rejected" => "Отказано",
-                _ => &candidate.status,
-            };
+            let status_display = Self::status_label(&candidate.status);
             let status_fmt = Format::new()
                 .set_font_size(10)
                 .set_bold()
@@ -190,7 +305,7 @@ impl ExportService {
 
             let vac_name = candidate.vacancy_id
                 .and_then(|id| vacancy_map.get(&id))
-                .map(|s| Self::strip_html(s))
+                .map(|s| sanitize_to_text(s))
                 .unwrap_or_else(|| "—".to_string());
             let vac_display = if let Some(id) = candidate.vacancy_id {
                 format!("{} (id:{})", vac_name, id)
@@ -203,27 +318,9 @@ impl ExportService {
             if let Some(hist) = history_map.get(&candidate.id) {
                 for (h_idx, item) in hist.iter().enumerate() {
                     let date = item.timestamp.with_timezone(&chrono::Local).format("%d.%m").to_string();
-                    let title = match item.event_type.as_str() {
-                        "registration" => "Регистрация",
-                        "application" => "Отклик",
-                        "profile_update" => "Обновление",
-                        "test_attempt" => "Тест",
-                        _ => &item.event_type,
-                    };
+                    let title = Self::event_title(&item.event_type);
                     let status = if let Some(s) = &item.status {
-                        let translated = match s.as_str() {
-                            "candidate_profile.status_completed" => "Завершено",
-                            "candidate_profile.status_passed" => "Пройден",
-                            "candidate_profile.status_failed" => "Не пройден",
-                            "candidate_profile.status_submitted" => "Отправлено",
-                            "dashboard.invites.statuses.pending" => "Ожидает",
-                            "dashboard.invites.statuses.in_progress" => "В процессе",
-                            "dashboard.invites.statuses.timeout" => "Время вышло",
-                            "dashboard.invites.statuses.escaped" => "Покинул",
-                            "dashboard.invites.statuses.needs_review" => "Проверка",
-                            _ => s,
-                        };
-                        format!(" [{}]", translated)
+                        format!(" [{}]", Self::history_status_label(s))
                     } else {
                         "".to_string()
                     };
@@ -265,6 +362,27 @@ impl ExportService {
             } else {
                 worksheet.write_string_with_format(row, 13, "0", &center_fmt)?;
             }
+
+            let profile = candidate.profile().unwrap_or_default();
+            worksheet.write_string_with_format(row, 14, profile.city.as_deref().unwrap_or("—"), &base_fmt)?;
+            worksheet.write_string_with_format(row, 15, profile.education.as_deref().unwrap_or("—"), &wrap_fmt)?;
+            let languages = if profile.languages.is_empty() {
+                "—".to_string()
+            } else {
+                profile.languages.join(", ")
+            };
+            worksheet.write_string_with_format(row, 16, &languages, &wrap_fmt)?;
+            if let Some(years) = profile.experience_years {
+                worksheet.write_number_with_format(row, 17, years as f64, &center_fmt)?;
+            } else {
+                worksheet.write_string_with_format(row, 17, "—", &center_fmt)?;
+            }
+            worksheet.write_string_with_format(row, 18, candidate.photo_url.as_deref().unwrap_or("—"), &base_fmt)?;
+            let thread_str = candidate.telegram_thread_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "—".to_string());
+            worksheet.write_string_with_format(row, 19, &thread_str, &center_fmt)?;
+            worksheet.write_string_with_format(row, 20, Self::portfolio_summary(&candidate.portfolio_paths()), &wrap_fmt)?;
         }
 
         let total_row = data_start_row + candidates.len() as u32 + 1;
@@ -314,7 +432,271 @@ impl ExportService {
         worksheet.set_freeze_panes(3, 0)?;
         worksheet.autofilter(2, 0, (data_start_row + candidates.len() as u32 - 1).max(2), (columns.len() - 1) as u16)?;
 
+        if options.include_history {
+            Self::write_history_sheet(&mut workbook, candidates, history_map)?;
+        }
+        if options.include_stats {
+            Self::write_stats_sheet(&mut workbook, candidates)?;
+        }
+
         let buffer = workbook.save_to_buffer()?;
         Ok(buffer)
     }
+
+    /// One row per history event, candidates in export order and events in
+    /// the order `history_map` holds them.
+    fn write_history_sheet(
+        workbook: &mut Workbook,
+        candidates: &[Candidate],
+        history_map: &HashMap<Uuid, Vec<HistoryItem>>,
+    ) -> Result<()> {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name("История")?;
+
+        let border_color = Color::RGB(0xE2E8F0);
+        let header_format = Format::new()
+            .set_bold()
+            .set_font_size(10)
+            .set_font_color(Color::White)
+            .set_background_color(Color::RGB(0x0F172A))
+            .set_align(FormatAlign::Center)
+            .set_align(FormatAlign::VerticalCenter)
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let cell_format = Format::new()
+            .set_font_size(10)
+            .set_align(FormatAlign::VerticalCenter)
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let wrap_format = cell_format.clone().set_text_wrap();
+        let center_format = cell_format.clone().set_align(FormatAlign::Center);
+
+        let columns = [
+            ("ФИО", 30.0),
+            ("Событие", 16.0),
+            ("Дата", 18.0),
+            ("Описание", 60.0),
+            ("Статус", 18.0),
+        ];
+        for (i, (name, width)) in columns.iter().enumerate() {
+            worksheet.set_column_width(i as u16, *width)?;
+            worksheet.write_string_with_format(0, i as u16, *name, &header_format)?;
+        }
+
+        let mut row = 1;
+        for candidate in candidates {
+            for item in history_map.get(&candidate.id).into_iter().flatten() {
+                let date = item.timestamp.with_timezone(&chrono::Local).format("%d.%m.%Y %H:%M").to_string();
+                let status = item.status.as_deref().map(Self::history_status_label).unwrap_or("—");
+                worksheet.write_string_with_format(row, 0, candidate.name_or_empty(), &cell_format)?;
+                worksheet.write_string_with_format(row, 1, Self::event_title(&item.event_type), &cell_format)?;
+                worksheet.write_string_with_format(row, 2, &date, &center_format)?;
+                worksheet.write_string_with_format(row, 3, item.description.as_deref().unwrap_or("—"), &wrap_format)?;
+                worksheet.write_string_with_format(row, 4, status, &center_format)?;
+                row += 1;
+            }
+        }
+
+        worksheet.set_freeze_panes(1, 0)?;
+        worksheet.autofilter(0, 0, row - 1, (columns.len() - 1) as u16)?;
+        Ok(())
+    }
+
+    /// Status distribution and AI-rating histogram as two small tables.
+    fn write_stats_sheet(workbook: &mut Workbook, candidates: &[Candidate]) -> Result<()> {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name("Статистика")?;
+
+        let border_color = Color::RGB(0xE2E8F0);
+        let section_format = Format::new()
+            .set_bold()
+            .set_font_size(11)
+            .set_font_color(Color::White)
+            .set_background_color(Color::RGB(0x0F172A))
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let header_format = Format::new()
+            .set_bold()
+            .set_font_size(10)
+            .set_background_color(Color::RGB(0xE0E7FF))
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let cell_format = Format::new()
+            .set_font_size(10)
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let number_format = cell_format.clone().set_align(FormatAlign::Center);
+
+        worksheet.set_column_width(0, 24.0)?;
+        worksheet.set_column_width(1, 12.0)?;
+        worksheet.set_column_width(2, 12.0)?;
+
+        let total = candidates.len();
+        let share = |n: usize| if total == 0 { 0.0 } else { (n as f64 * 1000.0 / total as f64).round() / 10.0 };
+
+        let mut row = 0;
+        let tables = [
+            ("Распределение по статусам", "Статус", status_distribution(candidates), true),
+            ("AI рейтинг", "Диапазон (%)", rating_histogram(candidates), false),
+        ];
+        for (title, label, entries, translate) in tables {
+            if row > 0 {
+                row += 1;
+            }
+            worksheet.merge_range(row, 0, row, 2, title, &section_format)?;
+            row += 1;
+            worksheet.write_string_with_format(row, 0, label, &header_format)?;
+            worksheet.write_string_with_format(row, 1, "Кол-во", &header_format)?;
+            worksheet.write_string_with_format(row, 2, "%", &header_format)?;
+            row += 1;
+            for (key, count) in &entries {
+                let key = if translate { Self::status_label(key) } else { key };
+                worksheet.write_string_with_format(row, 0, key, &cell_format)?;
+                worksheet.write_number_with_format(row, 1, *count as f64, &number_format)?;
+                worksheet.write_number_with_format(row, 2, share(*count), &number_format)?;
+                row += 1;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn generate_weekly_report_xlsx(report: &WeeklyReport) -> Result<Vec<u8>> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&report.week)?;
+
+        let primary_color = Color::RGB(0x1E293B);
+        let header_bg = Color::RGB(0x0F172A);
+        let border_color = Color::RGB(0xE2E8F0);
+
+        worksheet.set_column_width(0, 8.0)?;
+        worksheet.set_column_width(1, 45.0)?;
+        worksheet.set_column_width(2, 18.0)?;
+
+        let title_format = Format::new()
+            .set_font_size(16)
+            .set_bold()
+            .set_font_color(Color::White)
+            .set_background_color(primary_color)
+            .set_align(FormatAlign::CenterAcross)
+            .set_align(FormatAlign::VerticalCenter);
+        let section_format = Format::new()
+            .set_bold()
+            .set_font_size(11)
+            .set_font_color(Color::White)
+            .set_background_color(header_bg)
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let cell_format = Format::new()
+            .set_font_size(10)
+            .set_border(FormatBorder::Thin)
+            .set_border_color(border_color);
+        let number_format = cell_format.clone().set_align(FormatAlign::Center);
+
+        worksheet.set_row_height(0, 32)?;
+        let title = format!(
+            "Еженедельный отчёт {} ({} — {})",
+            report.week,
+            report.period_start.format("%d.%m.%Y"),
+            report.period_end.format("%d.%m.%Y")
+        );
+        worksheet.merge_range(0, 0, 0, 2, &title, &title_format)?;
+
+        let mut row = 2;
+        worksheet.merge_range(row, 0, row, 2, "Сводка", &section_format)?;
+        row += 1;
+        let summary: [(&str, Option<f64>); 6] = [
+            ("Новые кандидаты", Some(report.new_candidates as f64)),
+            ("Отклики", Some(report.total_applications() as f64)),
+            ("Тестов завершено", Some(report.tests_completed as f64)),
+            ("Тестов сдано", Some(report.tests_passed as f64)),
+            ("Процент сдачи (%)", report.pass_rate),
+            ("Средний AI рейтинг (%)", report.average_ai_rating),
+        ];
+        for (label, value) in summary {
+            worksheet.merge_range(row, 0, row, 1, label, &cell_format)?;
+            match value {
+                Some(v) => worksheet.write_number_with_format(row, 2, v, &number_format)?,
+                None => worksheet.write_string_with_format(row, 2, "—", &number_format)?,
+            };
+            row += 1;
+        }
+
+        row += 1;
+        worksheet.merge_range(row, 0, row, 2, "Отклики по вакансиям", &section_format)?;
+        row += 1;
+        if report.applications.is_empty() {
+            worksheet.merge_range(row, 0, row, 2, "Откликов не было", &cell_format)?;
+            row += 1;
+        }
+        for item in &report.applications {
+            worksheet.write_number_with_format(row, 0, item.vacancy_id as f64, &number_format)?;
+            worksheet.write_string_with_format(row, 1, &item.title, &cell_format)?;
+            worksheet.write_number_with_format(row, 2, item.applications as f64, &number_format)?;
+            row += 1;
+        }
+
+        row += 1;
+        worksheet.merge_range(row, 0, row, 2, "Топ кандидатов по AI рейтингу", &section_format)?;
+        row += 1;
+        if report.top_candidates.is_empty() {
+            worksheet.merge_range(row, 0, row, 2, "Нет оценённых кандидатов", &cell_format)?;
+        }
+        for (idx, candidate) in report.top_candidates.iter().enumerate() {
+            worksheet.write_number_with_format(row, 0, (idx + 1) as f64, &number_format)?;
+            worksheet.write_string_with_format(row, 1, &candidate.name, &cell_format)?;
+            worksheet.write_number_with_format(row, 2, candidate.ai_rating as f64, &number_format)?;
+            row += 1;
+        }
+
+        let buffer = workbook.save_to_buffer()?;
+        Ok(buffer)
+    }
+
+    /// ZIP with `candidate.json` (the candidate row, structured profile,
+    /// applications, history and test attempts with their answers),
+    /// `messages.json` (the whole chat) and the original CV under `cv/`.
+    pub fn generate_candidate_data_archive(
+        candidate: &Candidate,
+        data: &CandidateDataExport,
+    ) -> Result<Vec<u8>> {
+        let attempts: Vec<serde_json::Value> = data
+            .attempts
+            .iter()
+            .map(|attempt| {
+                let mut value = serde_json::to_value(attempt)?;
+                if let Some(fields) = value.as_object_mut() {
+                    for field in ATTEMPT_WITHHELD_FIELDS {
+                        fields.remove(field);
+                    }
+                }
+                Ok(value)
+            })
+            .collect::<Result<_>>()?;
+        let document = json!({
+            "exported_at": chrono::Utc::now(),
+            "candidate": candidate,
+            "profile": candidate.profile(),
+            "applications": data.applications,
+            "history": data.history,
+            "test_attempts": attempts,
+        });
+
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        archive.start_file("candidate.json", options)?;
+        archive.write_all(&serde_json::to_vec_pretty(&document)?)?;
+        archive.start_file("messages.json", options)?;
+        archive.write_all(&serde_json::to_vec_pretty(data.messages)?)?;
+        if let Some((name, bytes)) = data.cv {
+            let name = std::path::Path::new(name)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("cv");
+            archive.start_file(format!("cv/{}", name), options)?;
+            archive.write_all(bytes)?;
+        }
+        Ok(archive.finish()?.into_inner())
+    }
 }
@@ -23,6 +23,8 @@ pub struct ExternalVacancyPayload {
     #[serde(default)]
     pub hot: bool,
     pub driver_binary: Option<String>,
+    /// Internal vacancy to link to the created Koinoti Nav id.
+    pub internal_vacancy_id: Option<uuid::Uuid>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, Validate)]
@@ -181,7 +183,7 @@ impl ExternalVacancyService {
         command.arg("--password").arg(&payload.password);
         command
             .arg("--vacancy-id")
-            .arg(payload.vacancy_id.to_string());
+            .arg(&payload.vacancy_id);
         command.arg("--delete");
         command.arg("--headless");
 
@@ -0,0 +1,120 @@
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::time::Duration;
+
+pub const DEFAULT_GEOIP_API_URL: &str = "http://ip-api.com/json";
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoLocation {
+    /// ISO 3166-1 alpha-2 code, e.g. `TJ`.
+    pub country_code: String,
+    pub country: String,
+    pub city: Option<String>,
+}
+
+/// ip-api.com response; failures come back as `{"status":"fail","message":...}`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IpApiResponse {
+    status: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+    #[serde(default)]
+    country_code: Option<String>,
+    #[serde(default)]
+    city: Option<String>,
+}
+
+/// Reads an ip-api.com JSON body; `None` when the lookup failed or has no country.
+pub fn parse_ip_api(body: &serde_json::Value) -> Option<GeoLocation> {
+    let resp = IpApiResponse::deserialize(body).ok()?;
+    if resp.status != "success" {
+        tracing::debug!("Geo lookup failed: {}", resp.message.unwrap_or_default());
+        return None;
+    }
+    let country_code = resp.country_code.filter(|c| !c.trim().is_empty())?;
+    Some(GeoLocation {
+        country: resp.country.unwrap_or_else(|| country_code.clone()),
+        country_code,
+        city: resp.city.filter(|c| !c.trim().is_empty()),
+    })
+}
+
+/// `expected` may be an ISO code or a country name; without it nothing is flagged.
+pub fn is_suspicious_geo(expected: Option<&str>, location: &GeoLocation) -> bool {
+    match expected.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(expected) => {
+            !expected.eq_ignore_ascii_case(&location.country_code)
+                && !expected.eq_ignore_ascii_case(&location.country)
+        }
+        None => false,
+    }
+}
+
+/// Private, loopback and link-local addresses have no location worth asking for.
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast())
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GeoService {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl GeoService {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            client: reqwest::Client::builder()
+                .timeout(LOOKUP_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// `None` when lookups are switched off (`GEOIP_API_URL=""`).
+    pub fn from_config() -> Option<Self> {
+        crate::config::get_config()
+            .geoip_api_url
+            .as_deref()
+            .map(Self::new)
+    }
+
+    pub async fn lookup(&self, ip: IpAddr) -> Result<Option<GeoLocation>> {
+        if !is_public_ip(&ip) {
+            return Ok(None);
+        }
+        let url = format!("{}/{}", self.base_url, ip);
+        let body: serde_json::Value = self
+            .client
+            .get(&url)
+            .query(&[("fields", "status,message,country,countryCode,city")])
+            .send()
+            .await
+            .map_err(|e| Error::Internal(format!("Geo lookup failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| Error::Internal(format!("Invalid geo lookup response: {}", e)))?;
+        Ok(parse_ip_api(&body))
+    }
+}
@@ -1,5 +1,81 @@
-use crate::models::question::{Question, QuestionDetails, QuestionType};
+use crate::error::Result;
+use crate::models::question::{MultipleChoiceDetails, Question, QuestionDetails, QuestionType};
+use crate::models::test::Test;
+use crate::services::ai_service::AIService;
+use crate::services::code_runner_service::CodeRunnerService;
 use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::task::JoinSet;
+
+/// Question text beyond this is cut in the candidate results summary.
+const SUMMARY_TOPIC_CHARS: usize = 80;
+
+#[derive(Debug, Clone)]
+pub struct GradingResult {
+    pub earned_points: i32,
+    pub total_max_points: i32,
+    pub graded: Vec<JsonValue>,
+    pub needs_review: bool,
+    pub mcq_score: Option<f64>,
+    pub open_score: Option<f64>,
+}
+
+/// Percentages scored on multiple-choice and on open (short-answer, code and
+/// fill-in-the-blank) questions. `None` when the test has no question of
+/// that kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SubScores {
+    pub mcq: Option<f64>,
+    pub open: Option<f64>,
+}
+
+/// A test's pass marks, as percentages.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PassingThresholds {
+    pub total: f64,
+    pub mcq: Option<f64>,
+    pub open: Option<f64>,
+}
+
+impl PassingThresholds {
+    pub fn from_test(test: &Test) -> Self {
+        Self::from_decimals(
+            test.passing_score,
+            test.passing_score_mcq,
+            test.passing_score_open,
+        )
+    }
+
+    pub fn from_decimals(
+        total: rust_decimal::Decimal,
+        mcq: Option<rust_decimal::Decimal>,
+        open: Option<rust_decimal::Decimal>,
+    ) -> Self {
+        let to_f64 = |d: rust_decimal::Decimal| d.to_string().parse::<f64>().unwrap_or(0.0);
+        Self {
+            total: to_f64(total),
+            mcq: mcq.map(to_f64),
+            open: open.map(to_f64),
+        }
+    }
+
+    /// With both sub-thresholds set each kind must reach its own mark and
+    /// the overall percentage is not checked. A single sub-threshold applies
+    /// on top of the overall one. A kind the test has no questions of
+    /// always meets its mark.
+    pub fn passes(&self, percentage: f64, scores: SubScores) -> bool {
+        let meets = |score: Option<f64>, threshold: Option<f64>| match (score, threshold) {
+            (Some(score), Some(threshold)) => score >= threshold,
+            _ => true,
+        };
+        let by_kind = meets(scores.mcq, self.mcq) && meets(scores.open, self.open);
+        if self.mcq.is_some() && self.open.is_some() {
+            by_kind
+        } else {
+            by_kind && percentage >= self.total
+        }
+    }
+}
 
 pub struct GradingService;
 
@@ -81,16 +157,43 @@ impl GradingService {
                         "needs_review": true,
                     }));
                 }
-                _ => {
+                QuestionType::FillInBlank => {
+                    let mut points_earned = 0;
+                    let mut is_correct = false;
+                    let mut correct_val = serde_json::json!(null);
+
+                    if let QuestionDetails::FillInBlank(ref blank) = q.details {
+                        correct_val = serde_json::json!(blank.correct_words.join(", "));
+                        if blank.is_correct(&blank_words(&candidate_answer)) {
+                            points_earned = q.points;
+                            is_correct = true;
+                        }
+                    }
+
+                    earned_points += points_earned;
+                    graded.push(serde_json::json!({
+                        "question_id": question_id,
+                        "question_text": q.question,
+                        "type": "fill_in_blank",
+                        "candidate_answer": candidate_answer,
+                        "correct_answer": correct_val,
+                        "points_earned": points_earned,
+                        "max_points": q.points,
+                        "is_correct": is_correct,
+                    }));
+                }
+                QuestionType::Code => {
+                    needs_review = true;
                     graded.push(serde_json::json!({
                         "question_id": question_id,
                         "question_text": q.question,
-                        "type": format!("{:?}", q.question_type).to_lowercase(),
+                        "type": "code",
                         "candidate_answer": candidate_answer,
-                        "correct_answer": "Auto-grading not supported for this type",
+                        "correct_answer": "Manual review required",
                         "points_earned": 0,
                         "max_points": q.points,
                         "is_correct": false,
+                        "needs_review": true,
                     }));
                 }
             }
@@ -98,4 +201,393 @@ impl GradingService {
 
         (earned_points, total_max_points, graded, needs_review)
     }
+
+    /// Grades MCQs inline and sends every short answer with `ai_grading`
+    /// enabled to the LLM, one `JoinSet` task per question. A failed call
+    /// leaves that answer at zero points with `needs_review`; the rest of
+    /// the results are kept.
+    pub async fn grade_parallel(
+        questions: Vec<Question>,
+        answers: Vec<JsonValue>,
+        ai_service: &AIService,
+    ) -> Result<GradingResult> {
+        let (mut earned_points, total_max_points, mut graded, _) =
+            Self::grade_mcq_only(&questions, &answers);
+
+        let mut tasks = JoinSet::new();
+        for (idx, q) in questions.iter().enumerate() {
+            let QuestionDetails::ShortAnswer(ref details) = q.details else {
+                continue;
+            };
+            if !matches!(q.question_type, QuestionType::ShortAnswer) || !details.ai_grading {
+                continue;
+            }
+
+            let question_id = q.id.max((idx as i32) + 1);
+            let answer = answers
+                .iter()
+                .find(|a| a.get("question_id").and_then(|v| v.as_i64()) == Some(question_id as i64))
+                .and_then(|a| a.get("answer"))
+                .and_then(|a| a.as_str().or_else(|| a.get("text").and_then(|t| t.as_str())))
+                .map(|a| a.trim().to_string())
+                .unwrap_or_default();
+
+            if answer.is_empty() {
+                if let Some(entry) = graded_entry(&mut graded, question_id) {
+                    entry["needs_review"] = serde_json::json!(false);
+                    entry["correct_answer"] = serde_json::json!(null);
+                }
+                continue;
+            }
+
+            let ai_service = ai_service.clone();
+            let question = q.question.clone();
+            let keywords = details.expected_keywords.clone().unwrap_or_default();
+            tasks.spawn(async move {
+                let outcome = ai_service
+                    .grade_open_answer(&question, &keywords, &answer)
+                    .await;
+                (question_id, outcome)
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            let (question_id, outcome) = match joined {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("Open answer grading task aborted: {}", e);
+                    continue;
+                }
+            };
+            let Some(entry) = graded_entry(&mut graded, question_id) else {
+                continue;
+            };
+
+            match outcome {
+                Ok((score, comment)) => {
+                    let max_points = entry
+                        .get("max_points")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(0) as i32;
+                    let points_earned = max_points * score / 100;
+                    earned_points += points_earned;
+
+                    entry["points_earned"] = serde_json::json!(points_earned);
+                    entry["is_correct"] = serde_json::json!(points_earned * 2 >= max_points);
+                    entry["needs_review"] = serde_json::json!(false);
+                    entry["correct_answer"] = serde_json::json!(null);
+                    entry["ai_score"] = serde_json::json!(score);
+                    entry["ai_comment"] = serde_json::json!(comment);
+                }
+                Err(e) => {
+                    tracing::warn!("AI grading failed for question {}: {}", question_id, e);
+                    entry["grading_error"] = serde_json::json!(e.to_string());
+                }
+            }
+        }
+
+        let needs_review = Self::needs_review(&graded);
+        let SubScores { mcq, open } = Self::sub_scores(&graded);
+        Ok(GradingResult {
+            earned_points,
+            total_max_points,
+            graded,
+            needs_review,
+            mcq_score: mcq,
+            open_score: open,
+        })
+    }
+
+    /// Runs code answers through the sandbox and rewrites their entries in
+    /// `graded`. Points are proportional to passed test cases. Entries whose
+    /// execution fails keep `needs_review`. Returns the extra points earned.
+    pub async fn grade_code_answers(
+        questions: &[Question],
+        answers: &[JsonValue],
+        graded: &mut [JsonValue],
+        runner: &CodeRunnerService,
+    ) -> i32 {
+        let mut earned_points = 0;
+
+        for (idx, q) in questions.iter().enumerate() {
+            let QuestionDetails::Code(ref details) = q.details else {
+                continue;
+            };
+            if !matches!(q.question_type, QuestionType::Code)
+                || !CodeRunnerService::supports(&details.language)
+            {
+                continue;
+            }
+
+            let question_id = q.id.max((idx as i32) + 1);
+            let Some(entry) = graded.iter_mut().find(|g| {
+                g.get("question_id").and_then(|v| v.as_i64()) == Some(question_id as i64)
+            }) else {
+                continue;
+            };
+
+            let code = answers
+                .iter()
+                .find(|a| a.get("question_id").and_then(|v| v.as_i64()) == Some(question_id as i64))
+                .and_then(|a| a.get("answer"))
+                .and_then(|a| {
+                    a.as_str()
+                        .or_else(|| a.get("code").and_then(|c| c.as_str()))
+                })
+                .map(str::trim)
+                .unwrap_or_default();
+
+            if details.test_cases.is_empty() {
+                continue;
+            }
+            if code.is_empty() {
+                entry["needs_review"] = serde_json::json!(false);
+                entry["correct_answer"] = serde_json::json!(null);
+                continue;
+            }
+
+            let report = match runner.run_rust(code, &details.test_cases).await {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::warn!("Code execution failed for question {}: {}", question_id, e);
+                    continue;
+                }
+            };
+
+            let total = details.test_cases.len() as i32;
+            let passed = report.passed_count() as i32;
+            let points_earned = q.points * passed / total;
+            earned_points += points_earned;
+
+            entry["points_earned"] = serde_json::json!(points_earned);
+            entry["is_correct"] = serde_json::json!(passed == total);
+            entry["needs_review"] = serde_json::json!(false);
+            entry["correct_answer"] = serde_json::json!(null);
+            entry["tests_passed"] = serde_json::json!(passed);
+            entry["tests_total"] = serde_json::json!(total);
+            entry["compile_error"] = serde_json::json!(report.compile_error);
+            entry["test_results"] = serde_json::json!(report.results);
+        }
+
+        earned_points
+    }
+
+    /// Replaces the answer key of every multiple-choice question whose id
+    /// appears in `corrections`. Returns the ids that were patched; ids
+    /// missing from `questions` or pointing at other question types are
+    /// left out.
+    pub fn patch_answer_key(
+        questions: &mut [Question],
+        corrections: &BTreeMap<i32, MultipleChoiceDetails>,
+    ) -> BTreeSet<i32> {
+        let mut patched = BTreeSet::new();
+        for (idx, q) in questions.iter_mut().enumerate() {
+            let question_id = q.id.max((idx as i32) + 1);
+            let Some(correction) = corrections.get(&question_id) else {
+                continue;
+            };
+            if !matches!(q.question_type, QuestionType::MultipleChoice) {
+                continue;
+            }
+            if let QuestionDetails::MultipleChoice(ref mut mc) = q.details {
+                *mc = correction.clone();
+                patched.insert(question_id);
+            }
+        }
+        patched
+    }
+
+    /// Re-grades only `question_ids` and swaps their entries in `graded`,
+    /// leaving manual, AI and code grades of other questions untouched.
+    pub fn regrade_questions(
+        questions: &[Question],
+        answers: &[JsonValue],
+        graded: &mut Vec<JsonValue>,
+        question_ids: &BTreeSet<i32>,
+    ) {
+        let (_, _, fresh, _) = Self::grade_mcq_only(questions, answers);
+        for entry in fresh {
+            let Some(question_id) = entry.get("question_id").and_then(|v| v.as_i64()) else {
+                continue;
+            };
+            if !question_ids.contains(&(question_id as i32)) {
+                continue;
+            }
+            match graded_entry(graded, question_id as i32) {
+                Some(existing) => *existing = entry,
+                None => graded.push(entry),
+            }
+        }
+    }
+
+    /// `(earned, max)` summed over graded entries.
+    pub fn totals(graded: &[JsonValue]) -> (i32, i32) {
+        graded.iter().fold((0, 0), |(earned, max), g| {
+            let points = |key: &str| g.get(key).and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            (earned + points("points_earned"), max + points("max_points"))
+        })
+    }
+
+    /// Per-kind percentages over graded entries, rounded to two decimals
+    /// like the overall percentage.
+    pub fn sub_scores(graded: &[JsonValue]) -> SubScores {
+        let score = |mcq: bool| {
+            let entries: Vec<JsonValue> = graded
+                .iter()
+                .filter(|g| {
+                    (g.get("type").and_then(|v| v.as_str()) == Some("multiple_choice")) == mcq
+                })
+                .cloned()
+                .collect();
+            if entries.is_empty() {
+                return None;
+            }
+            let (earned, max) = Self::totals(&entries);
+            Some(if max > 0 {
+                ((earned as f64 / max as f64) * 10000.0).round() / 100.0
+            } else {
+                0.0
+            })
+        };
+        SubScores {
+            mcq: score(true),
+            open: score(false),
+        }
+    }
+
+    pub fn needs_review(graded: &[JsonValue]) -> bool {
+        graded.iter().any(|g| {
+            g.get("needs_review")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Candidate-facing breakdown in Russian: per question the topic,
+    /// verdict, points and, for open answers, the reviewer or AI comment.
+    /// With `include_explanations` wrong multiple-choice and fill-in-the-blank
+    /// answers also get the correct answer and the explanation from
+    /// `questions_snapshot`.
+    pub fn results_summary(
+        title: &str,
+        graded: &[JsonValue],
+        questions_snapshot: &JsonValue,
+        include_explanations: bool,
+    ) -> String {
+        let (earned, max) = Self::totals(graded);
+        let percentage = if max > 0 {
+            ((earned as f64 / max as f64) * 10000.0).round() / 100.0
+        } else {
+            0.0
+        };
+        let questions = questions_snapshot
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut text = format!(
+            "Разбор теста \"{}\"\nИтог: {} из {} баллов ({}%)",
+            title, earned, max, percentage
+        );
+        for (idx, entry) in graded.iter().enumerate() {
+            let question_id = entry.get("question_id").and_then(|v| v.as_i64());
+            let question = questions.iter().enumerate().find_map(|(q_idx, q)| {
+                let id = q
+                    .get("id")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0)
+                    .max(q_idx as i64 + 1);
+                (Some(id) == question_id).then_some(q)
+            });
+            let topic = entry
+                .get("question_text")
+                .and_then(|v| v.as_str())
+                .or_else(|| {
+                    question
+                        .and_then(|q| q.get("question"))
+                        .and_then(|v| v.as_str())
+                })
+                .unwrap_or_default();
+            let points = |key: &str| entry.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+            let (points_earned, max_points) = (points("points_earned"), points("max_points"));
+            let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+            let verdict = if flag("needs_review") {
+                "⏳ на проверке"
+            } else if flag("is_correct") {
+                "✅ верно"
+            } else if points_earned > 0 {
+                "🟡 частично"
+            } else {
+                "❌ неверно"
+            };
+
+            text.push_str(&format!(
+                "\n\n{}. {}\n{}, {}/{}",
+                idx + 1,
+                summary_topic(topic),
+                verdict,
+                points_earned,
+                max_points
+            ));
+
+            let kind = entry
+                .get("type")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            if kind == "multiple_choice" || kind == "fill_in_blank" {
+                if include_explanations && !flag("is_correct") {
+                    if let Some(correct) = entry.get("correct_answer").and_then(|v| v.as_str()) {
+                        text.push_str(&format!("\nПравильный ответ: {}", correct));
+                    }
+                    if let Some(explanation) = question
+                        .and_then(|q| q.get("explanation"))
+                        .and_then(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|e| !e.is_empty())
+                    {
+                        text.push_str(&format!("\nПояснение: {}", explanation));
+                    }
+                }
+            } else if let Some(comment) = ["reviewer_comment", "ai_comment"]
+                .iter()
+                .filter_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+                .map(str::trim)
+                .find(|c| !c.is_empty())
+            {
+                text.push_str(&format!("\nКомментарий: {}", comment));
+            }
+        }
+        text
+    }
+}
+
+/// Question text on one line, cut to `SUMMARY_TOPIC_CHARS`.
+fn summary_topic(question: &str) -> String {
+    let line = question.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= SUMMARY_TOPIC_CHARS {
+        return line;
+    }
+    let mut cut: String = line.chars().take(SUMMARY_TOPIC_CHARS - 1).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// A fill-in-the-blank answer as one word per blank: an array of strings,
+/// or a plain string for a single blank.
+fn blank_words(answer: &JsonValue) -> Vec<String> {
+    match answer {
+        JsonValue::Array(words) => words
+            .iter()
+            .map(|w| w.as_str().unwrap_or_default().to_string())
+            .collect(),
+        JsonValue::String(word) => vec![word.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn graded_entry(graded: &mut [JsonValue], question_id: i32) -> Option<&mut JsonValue> {
+    graded
+        .iter_mut()
+        .find(|g| g.get("question_id").and_then(|v| v.as_i64()) == Some(question_id as i64))
 }
@@ -0,0 +1,329 @@
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+
+use crate::AppState;
+
+/// Budget for each dependency check.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pending AI jobs above which readiness reports the AI queue as degraded.
+pub const AI_QUEUE_BACKLOG_LIMIT: i64 = 50;
+
+/// Pending webhook deliveries above which the webhook queue is degraded.
+pub const WEBHOOK_QUEUE_BACKLOG_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Working, but backlogged.
+    Degraded,
+    Error,
+}
+
+impl CheckStatus {
+    fn from_ok(ok: bool) -> Self {
+        if ok {
+            CheckStatus::Ok
+        } else {
+            CheckStatus::Error
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverallStatus {
+    Ok,
+    /// Only non-critical dependencies are failing; the API still works.
+    Degraded,
+    /// The database is unreachable.
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: OverallStatus,
+    pub database: CheckStatus,
+    pub ai_provider: CheckStatus,
+    pub telegram: CheckStatus,
+    pub onef: CheckStatus,
+    /// Pending AI jobs; 0 when the database can't be read.
+    pub queue_depth: i64,
+    pub version: String,
+}
+
+impl HealthReport {
+    /// The database is the only critical dependency.
+    pub fn overall(
+        database: CheckStatus,
+        ai_provider: CheckStatus,
+        telegram: CheckStatus,
+        onef: CheckStatus,
+    ) -> OverallStatus {
+        if database == CheckStatus::Error {
+            OverallStatus::Error
+        } else if [ai_provider, telegram, onef].contains(&CheckStatus::Error) {
+            OverallStatus::Degraded
+        } else {
+            OverallStatus::Ok
+        }
+    }
+}
+
+/// Verdict of the readiness probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Readiness {
+    Ok,
+    /// Serving, with a non-critical check failing or backlogged.
+    Degraded,
+    /// The database is unusable; stop routing traffic here.
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentCheck {
+    pub status: CheckStatus,
+    pub latency_ms: u64,
+    /// Queue length, for the queue checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessChecks {
+    pub database: ComponentCheck,
+    pub uploads: ComponentCheck,
+    pub ai_queue: ComponentCheck,
+    pub webhook_queue: ComponentCheck,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub status: Readiness,
+    pub checks: ReadinessChecks,
+}
+
+impl ReadinessReport {
+    /// `Down` only when the database fails; anything else not `Ok` degrades.
+    pub fn verdict(checks: &ReadinessChecks) -> Readiness {
+        if checks.database.status != CheckStatus::Ok {
+            Readiness::Down
+        } else if [&checks.uploads, &checks.ai_queue, &checks.webhook_queue]
+            .iter()
+            .any(|c| c.status != CheckStatus::Ok)
+        {
+            Readiness::Degraded
+        } else {
+            Readiness::Ok
+        }
+    }
+}
+
+/// `Degraded` once a queue holds more than `limit` items.
+pub fn queue_status(depth: i64, limit: i64) -> CheckStatus {
+    if depth > limit {
+        CheckStatus::Degraded
+    } else {
+        CheckStatus::Ok
+    }
+}
+
+/// Runs `check` under `CHECK_TIMEOUT`, recording how long it took.
+async fn timed<T>(
+    check: impl Future<Output = std::result::Result<T, String>>,
+) -> (std::result::Result<T, String>, u64) {
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())));
+    (result, started.elapsed().as_millis() as u64)
+}
+
+fn component(result: std::result::Result<CheckStatus, String>, latency_ms: u64) -> ComponentCheck {
+    match result {
+        Ok(status) => ComponentCheck {
+            status,
+            latency_ms,
+            depth: None,
+            error: None,
+        },
+        Err(error) => ComponentCheck {
+            status: CheckStatus::Error,
+            latency_ms,
+            depth: None,
+            error: Some(error),
+        },
+    }
+}
+
+async fn queue_check(pool: &PgPool, sql: &'static str, limit: i64) -> ComponentCheck {
+    let (depth, latency_ms) = timed(async {
+        sqlx::query_scalar::<_, i64>(sql)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())
+    })
+    .await;
+    let depth_value = depth.as_ref().ok().copied();
+    ComponentCheck {
+        depth: depth_value,
+        ..component(depth.map(|d| queue_status(d, limit)), latency_ms)
+    }
+}
+
+/// Writes and removes a probe file to prove `dir` accepts uploads.
+async fn uploads_writable(dir: &Path) -> std::result::Result<CheckStatus, String> {
+    let probe = dir.join(format!(".ready-{}", uuid::Uuid::new_v4()));
+    tokio::fs::write(&probe, b"ok")
+        .await
+        .map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(CheckStatus::Ok)
+}
+
+/// Readiness checks: database, uploads directory and queue backlogs, run
+/// concurrently. External providers are left to `/health/detailed`.
+pub async fn check_readiness(pool: &PgPool, uploads_dir: &Path) -> ReadinessReport {
+    let (database, uploads, ai_queue, webhook_queue) = tokio::join!(
+        timed(async {
+            sqlx::query("SELECT 1")
+                .execute(pool)
+                .await
+                .map(|_| CheckStatus::Ok)
+                .map_err(|e| e.to_string())
+        }),
+        timed(uploads_writable(uploads_dir)),
+        queue_check(
+            pool,
+            "SELECT COUNT(*) FROM ai_jobs WHERE status = 'pending'",
+            AI_QUEUE_BACKLOG_LIMIT,
+        ),
+        queue_check(
+            pool,
+            "SELECT COUNT(*) FROM webhook_logs WHERE status = 'pending'",
+            WEBHOOK_QUEUE_BACKLOG_LIMIT,
+        ),
+    );
+    let checks = ReadinessChecks {
+        database: component(database.0, database.1),
+        uploads: component(uploads.0, uploads.1),
+        ai_queue,
+        webhook_queue,
+    };
+    ReadinessReport {
+        status: ReadinessReport::verdict(&checks),
+        checks,
+    }
+}
+
+/// `false` when `check` fails or takes longer than `CHECK_TIMEOUT`.
+async fn within_timeout(check: impl Future<Output = bool>) -> bool {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or(false)
+}
+
+/// Probes the services the backend depends on.
+#[derive(Clone)]
+pub struct HealthService {
+    client: Client,
+    ai_url: String,
+    telegram_get_me_url: String,
+    onef_urls: Vec<String>,
+}
+
+impl HealthService {
+    pub fn new(ai_url: String, telegram_get_me_url: String, onef_urls: Vec<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(CHECK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            ai_url,
+            telegram_get_me_url,
+            onef_urls,
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let config = crate::config::get_config();
+        Self::new(
+            config.openai_base_url.clone(),
+            format!(
+                "{}/bot{}/getMe",
+                config.telegram_api_url, config.telegram_bot_token
+            ),
+            config.onef_base_urls.clone(),
+        )
+    }
+
+    pub async fn check_all(state: &AppState) -> HealthReport {
+        Self::from_config().check(&state.pool).await
+    }
+
+    /// Runs every check concurrently.
+    pub async fn check(&self, pool: &PgPool) -> HealthReport {
+        let (database, queue_depth, ai_provider, telegram, onef) = tokio::join!(
+            within_timeout(async { sqlx::query("SELECT 1").execute(pool).await.is_ok() }),
+            tokio::time::timeout(
+                CHECK_TIMEOUT,
+                sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM ai_jobs WHERE status = 'pending'"
+                )
+                .fetch_one(pool),
+            ),
+            within_timeout(self.reachable(&self.ai_url)),
+            within_timeout(self.telegram_ok()),
+            within_timeout(self.onef_reachable()),
+        );
+        let database = CheckStatus::from_ok(database);
+        let ai_provider = CheckStatus::from_ok(ai_provider);
+        let telegram = CheckStatus::from_ok(telegram);
+        let onef = CheckStatus::from_ok(onef);
+        HealthReport {
+            status: HealthReport::overall(database, ai_provider, telegram, onef),
+            database,
+            ai_provider,
+            telegram,
+            onef,
+            queue_depth: queue_depth.ok().and_then(|r| r.ok()).unwrap_or(0),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Any HTTP answer counts: the provider is up even if it rejects an
+    /// unauthenticated HEAD.
+    async fn reachable(&self, url: &str) -> bool {
+        self.client.head(url).send().await.is_ok()
+    }
+
+    async fn telegram_ok(&self) -> bool {
+        let Ok(resp) = self.client.get(&self.telegram_get_me_url).send().await else {
+            return false;
+        };
+        resp.json::<JsonValue>()
+            .await
+            .map(|body| body["ok"].as_bool() == Some(true))
+            .unwrap_or(false)
+    }
+
+    /// Without configured 1F URLs there is nothing to reach.
+    async fn onef_reachable(&self) -> bool {
+        for url in &self.onef_urls {
+            if !self.reachable(url).await {
+                return false;
+            }
+        }
+        true
+    }
+}
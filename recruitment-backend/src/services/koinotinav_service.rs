@@ -1,20 +1,25 @@
 use crate::error::Result;
+use crate::services::vacancy_link_service::VacancyLinkService;
+use crate::utils::html::{sanitize_to_safe_html, sanitize_to_text};
 use reqwest::Client;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-fn strip_html_tags(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut in_tag = false;
-    for c in input.chars() {
-        if c == '<' {
-            in_tag = true;
-        } else if c == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            output.push(c);
-        }
-    }
-    output.trim().to_string()
+const VACANCY_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const VACANCY_PAGE_TIMEOUT: Duration = Duration::from_secs(10);
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+/// Older Koinoti Nav ids are test data and never shown.
+const MIN_VACANCY_ID: i64 = 137;
+
+/// Koinoti Nav sends titles and descriptions as raw HTML. Titles, city and
+/// direction become plain text, the description keeps only safe markup.
+fn sanitize_vacancy(vacancy: &mut ExternalVacancy) {
+    vacancy.title = sanitize_to_text(&vacancy.title);
+    vacancy.city = sanitize_to_text(&vacancy.city);
+    vacancy.direction = sanitize_to_text(&vacancy.direction);
+    vacancy.content = sanitize_to_safe_html(&vacancy.content);
 }
 
 fn deserialize_bool_flexible<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
@@ -53,6 +58,57 @@ pub struct ExternalVacancy {
     pub created_at: String,
 }
 
+/// Reads one page of `GET /api/vacancies`. Accepts a paginated object
+/// (`data`/`items` with `total` or `meta.total`) or a plain array; a plain
+/// array without `X-Total-Count` means pagination was ignored upstream, so the
+/// page is cut locally and `total` is the full filtered length. `page` and
+/// `per_page` must be at least 1.
+pub fn parse_vacancy_page(
+    body: JsonValue,
+    header_total: Option<i64>,
+    page: i64,
+    per_page: i64,
+) -> Result<(Vec<ExternalVacancy>, i64)> {
+    let offset = (page - 1) * per_page;
+    let (items, total, paged_upstream) = match body {
+        JsonValue::Array(items) => (JsonValue::Array(items), header_total, header_total.is_some()),
+        JsonValue::Object(mut obj) => {
+            let total = obj
+                .get("total")
+                .or_else(|| obj.get("meta").and_then(|m| m.get("total")))
+                .and_then(JsonValue::as_i64)
+                .or(header_total);
+            let items = obj
+                .remove("data")
+                .or_else(|| obj.remove("items"))
+                .unwrap_or(JsonValue::Array(Vec::new()));
+            (items, total, true)
+        }
+        other => {
+            return Err(crate::error::Error::Internal(format!(
+                "Unexpected vacancy list payload: {}",
+                other
+            )))
+        }
+    };
+
+    let mut vacancies: Vec<ExternalVacancy> = serde_json::from_value(items)?;
+    vacancies.iter_mut().for_each(sanitize_vacancy);
+    vacancies.retain(|v| v.id >= MIN_VACANCY_ID);
+
+    if paged_upstream {
+        let total = total.unwrap_or(offset + vacancies.len() as i64);
+        return Ok((vacancies, total));
+    }
+    let total = vacancies.len() as i64;
+    let items = vacancies
+        .into_iter()
+        .skip(offset as usize)
+        .take(per_page as usize)
+        .collect();
+    Ok((items, total))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalCompany {
     pub id: i64,
@@ -60,10 +116,21 @@ pub struct ExternalCompany {
     pub logo: String,
 }
 
+/// Vacancies fetched at the given instant.
+type VacancyCache = Arc<Mutex<Option<(Instant, Vec<ExternalVacancy>)>>>;
+
 #[derive(Clone)]
 pub struct KoinotinavService {
     client: Client,
     base_url: String,
+    vacancy_cache: VacancyCache,
+    links: Option<VacancyLinkService>,
+}
+
+impl Default for KoinotinavService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl KoinotinavService {
@@ -71,10 +138,42 @@ impl KoinotinavService {
         Self {
             client: Client::new(),
             base_url: "https://job.koinotinav.tj".to_string(),
+            vacancy_cache: Arc::new(Mutex::new(None)),
+            links: None,
         }
     }
 
+    /// Points the client at another Koinoti Nav host, e.g. a staging mirror.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Lets `fetch_vacancy` accept 1F ids that are linked to a Koinoti Nav vacancy.
+    pub fn with_links(mut self, links: VacancyLinkService) -> Self {
+        self.links = Some(links);
+        self
+    }
+
     pub async fn fetch_vacancies(&self) -> Result<Vec<ExternalVacancy>> {
+        if let Some((fetched_at, vacancies)) = self.vacancy_cache.lock().expect("vacancy cache mutex poisoned").as_ref() {
+            if fetched_at.elapsed() < VACANCY_CACHE_TTL {
+                return Ok(vacancies.clone());
+            }
+        }
+        self.refresh_vacancies().await
+    }
+
+    /// Number of vacancies from the last successful fetch, without hitting the API.
+    pub fn cached_vacancy_count(&self) -> Option<usize> {
+        self.vacancy_cache
+            .lock()
+            .expect("vacancy cache mutex poisoned")
+            .as_ref()
+            .map(|(_, vacancies)| vacancies.len())
+    }
+
+    pub async fn refresh_vacancies(&self) -> Result<Vec<ExternalVacancy>> {
         let url = format!("{}/api/vacancies", self.base_url);
         let response = self
             .client
@@ -85,14 +184,44 @@ impl KoinotinavService {
             .json::<Vec<ExternalVacancy>>()
             .await?;
             
-        for v in &mut vacancies {
-            v.title = strip_html_tags(&v.title);
-        }
+        vacancies.iter_mut().for_each(sanitize_vacancy);
             
-        Ok(vacancies.into_iter().filter(|v| v.id >= 137).collect())
+        let vacancies: Vec<ExternalVacancy> = vacancies.into_iter().filter(|v| v.id >= MIN_VACANCY_ID).collect();
+        *self.vacancy_cache.lock().expect("vacancy cache mutex poisoned") = Some((Instant::now(), vacancies.clone()));
+        Ok(vacancies)
+    }
+
+    /// One page of vacancies plus the upstream total, bypassing the cache.
+    pub async fn fetch_vacancies_page(
+        &self,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<ExternalVacancy>, i64)> {
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let url = format!("{}/api/vacancies", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("page", page), ("per_page", per_page)])
+            .timeout(VACANCY_PAGE_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let header_total = response
+            .headers()
+            .get(TOTAL_COUNT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<i64>().ok());
+        let body = response.json::<JsonValue>().await?;
+        parse_vacancy_page(body, header_total, page, per_page)
     }
 
     pub async fn fetch_vacancy(&self, id: i64) -> Result<Option<ExternalVacancy>> {
+        let id = match &self.links {
+            Some(links) => links.koinotinav_id_for(&id.to_string()).await?.unwrap_or(id),
+            None => id,
+        };
         let url = format!("{}/api/vacancies/{}", self.base_url, id);
         tracing::info!("Fetching single vacancy details from: {}", url);
         
@@ -111,7 +240,7 @@ impl KoinotinavService {
             if content_type.contains("application/json") {
                 match response.json::<ExternalVacancy>().await {
                     Ok(mut v) => {
-                        v.title = strip_html_tags(&v.title);
+                        sanitize_vacancy(&mut v);
                         return Ok(Some(v));
                     },
                     Err(e) => tracing::warn!("Failed to parse single vacancy JSON: {}. Falling back to list.", e),
@@ -1,8 +1,49 @@
+use serde::Deserialize;
 use sqlx::PgPool;
 use uuid::Uuid;
-use crate::error::Result;
+use chrono::{DateTime, Utc};
+use crate::error::{Error, Result};
 use crate::models::message::{Message, CreateMessage};
 
+pub const DEFAULT_MESSAGE_PAGE_SIZE: i64 = 100;
+pub const MAX_MESSAGE_PAGE_SIZE: i64 = 500;
+
+/// `?limit=&before_id=&search=` for the chat history endpoints. A page holds
+/// the newest `limit` messages older than `before_id`, oldest first.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MessageQuery {
+    pub limit: Option<i64>,
+    pub before_id: Option<Uuid>,
+    pub search: Option<String>,
+}
+
+impl MessageQuery {
+    pub fn page_size(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_MESSAGE_PAGE_SIZE)
+            .clamp(1, MAX_MESSAGE_PAGE_SIZE)
+    }
+
+    /// Case-insensitive `ILIKE` pattern for `search`, with `%`, `_` and `\`
+    /// matched literally. `None` when there is nothing to search for.
+    pub fn search_pattern(&self) -> Option<String> {
+        let term = self.search.as_deref()?.trim();
+        if term.is_empty() {
+            return None;
+        }
+        let mut pattern = String::with_capacity(term.len() + 2);
+        pattern.push('%');
+        for c in term.chars() {
+            if matches!(c, '\\' | '%' | '_') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('%');
+        Some(pattern)
+    }
+}
+
 #[derive(Clone)]
 pub struct MessageService {
     pool: PgPool,
@@ -16,8 +57,8 @@ impl MessageService {
     pub async fn create(&self, msg: CreateMessage) -> Result<Message> {
         let message = sqlx::query_as::<_, Message>(
             r#"
-            INSERT INTO messages (candidate_id, telegram_id, direction, text)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO messages (candidate_id, telegram_id, direction, text, attachment_path, attachment_type)
+            VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING *
             "#
         )
@@ -25,21 +66,59 @@ impl MessageService {
         .bind(msg.telegram_id)
         .bind(&msg.direction)
         .bind(&msg.text)
+        .bind(&msg.attachment_path)
+        .bind(&msg.attachment_type)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(message)
     }
 
-    pub async fn get_by_candidate(&self, candidate_id: Uuid) -> Result<Vec<Message>> {
+    pub async fn get_by_candidate(&self, candidate_id: Uuid, query: &MessageQuery) -> Result<Vec<Message>> {
+        let cursor: Option<(DateTime<Utc>, Uuid)> = match query.before_id {
+            Some(before_id) => Some(
+                sqlx::query_as(
+                    "SELECT created_at, id FROM messages WHERE id = $1 AND candidate_id = $2",
+                )
+                .bind(before_id)
+                .bind(candidate_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| Error::NotFound(format!("Message {} not found", before_id)))?,
+            ),
+            None => None,
+        };
+
         let messages = sqlx::query_as::<_, Message>(
             r#"
-            SELECT * FROM messages
-            WHERE candidate_id = $1
-            ORDER BY created_at ASC
+            SELECT * FROM (
+                SELECT * FROM messages
+                WHERE candidate_id = $1
+                  AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+                  AND ($4::text IS NULL OR text ILIKE $4 ESCAPE '\')
+                ORDER BY created_at DESC, id DESC
+                LIMIT $5
+            ) page
+            ORDER BY created_at ASC, id ASC
             "#
         )
         .bind(candidate_id)
+        .bind(cursor.map(|(created_at, _)| created_at))
+        .bind(cursor.map(|(_, id)| id))
+        .bind(query.search_pattern())
+        .bind(query.page_size())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// The candidate's whole chat history, oldest first, for the data export.
+    pub async fn list_all_for_candidate(&self, candidate_id: Uuid) -> Result<Vec<Message>> {
+        let messages = sqlx::query_as::<_, Message>(
+            "SELECT * FROM messages WHERE candidate_id = $1 ORDER BY created_at ASC, id ASC",
+        )
+        .bind(candidate_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -75,13 +154,16 @@ impl MessageService {
         Ok(count.0)
     }
 
-    pub async fn total_unread_count(&self) -> Result<i64> {
+    /// Unread inbound messages of the organization's candidates.
+    pub async fn total_unread_count(&self, org_id: uuid::Uuid) -> Result<i64> {
         let count: (i64,) = sqlx::query_as(
             r#"
-            SELECT COUNT(*) FROM messages
-            WHERE direction = 'inbound' AND read_at IS NULL
+            SELECT COUNT(*) FROM messages m
+            JOIN candidates c ON c.id = m.candidate_id
+            WHERE m.direction = 'inbound' AND m.read_at IS NULL AND c.organization_id = $1
             "#
         )
+        .bind(org_id)
         .fetch_one(&self.pool)
         .await?;
 
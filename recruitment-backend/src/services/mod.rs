@@ -1,17 +1,36 @@
 pub mod ai_service;
 pub mod candidate_service;
+pub mod code_runner_service;
+pub mod dashboard_service;
+pub mod dashboard_snapshot_service;
 pub mod attempt_service;
 pub mod audit_service;
+pub mod broadcast_service;
 pub mod embed_service;
 pub mod eval_service;
 pub mod export_service;
 pub mod external_vacancy_service;
+pub mod geo_service;
 pub mod grading_service;
+pub mod health_service;
 pub mod notification_service;
+pub mod prompt_service;
+pub mod question_import_service;
+pub mod question_feedback_service;
 pub mod queue_service;
+pub mod referral_service;
+pub mod report_service;
+pub mod test_quality_service;
 pub mod test_service;
 pub mod vacancy_service;
+pub mod vacancy_link_service;
+pub mod webhook_subscription_service;
 pub mod koinotinav_service;
 pub mod onef_service;
 pub mod message_service;
-pub mod response_service;
\ No newline at end of file
+pub mod organization_service;
+pub mod response_service;
+pub mod status_pipeline_service;
+pub mod telegram_channel_service;
+pub mod telegram_delivery_service;
+pub mod calendar_service;
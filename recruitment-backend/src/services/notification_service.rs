@@ -1,15 +1,69 @@
 use crate::error::Result;
+use crate::models::organization::DEFAULT_ORGANIZATION_ID;
 use crate::models::webhook_log::WebhookLog;
+use crate::services::onef_service::{test_status_url, OneFService, OneFTestStatusPayload};
+use crate::services::webhook_subscription_service::WebhookSubscriptionService;
+use hmac::{Hmac, Mac};
 use reqwest::Client;
 use serde_json::Value as JsonValue;
-use sqlx::{PgPool, Row};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgExecutor, PgPool, Row};
 use uuid::Uuid;
 
+/// `webhook_logs.target` for pushes delivered through `OneFService`.
+pub const ONEF_TARGET: &str = "onef";
+pub const ONEF_TEST_STATUS_EVENT: &str = "onef_test_status";
+
+/// Header carrying [`webhook_signature`] of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// `sha256=<hex HMAC-SHA256 of body>` keyed by the endpoint's secret, so
+/// receivers can check the payload was not altered on the way.
+pub fn webhook_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// `webhook_logs.notification_dedup_key`: hex SHA256 of `event_type || key`.
+pub fn notification_dedup_key(event_type: &str, key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(event_type.as_bytes());
+    hasher.update(key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// The organization owning the attempt or candidate an event payload names
+/// (`attempt_id`, then `candidate_id`); the default organization otherwise.
+async fn event_organization(conn: &mut PgConnection, payload: &JsonValue) -> Result<Uuid> {
+    let id = |key: &str| {
+        payload
+            .get(key)
+            .and_then(JsonValue::as_str)
+            .and_then(|s| Uuid::parse_str(s).ok())
+    };
+    let org_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(
+            (SELECT organization_id FROM test_attempts WHERE id = $1),
+            (SELECT organization_id FROM candidates WHERE id = $2)
+        )
+        "#,
+    )
+    .bind(id("attempt_id"))
+    .bind(id("candidate_id"))
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(org_id.unwrap_or(DEFAULT_ORGANIZATION_ID))
+}
+
 #[derive(Clone)]
 pub struct NotificationService {
     pool: PgPool,
     client: Client,
     target_url: String,
+    onef: Option<OneFService>,
 }
 
 impl NotificationService {
@@ -18,54 +72,161 @@ impl NotificationService {
             pool,
             client: Client::new(),
             target_url,
+            onef: None,
         }
     }
 
+    /// Lets this instance deliver logs targeted at 1F.
+    pub fn with_onef(mut self, onef: OneFService) -> Self {
+        self.onef = Some(onef);
+        self
+    }
+
+    /// Queues `event_type` for the bot webhook and for every active
+    /// subscription of the event's organization whose `event_types` include
+    /// it (or `*`). Each row is delivered and retried on its own.
+    ///
+    /// With a `dedup_key` (usually the attempt id) the event is queued at most
+    /// once per key: a repeat returns an empty list and queues nothing.
     pub async fn enqueue_webhook(
         &self,
         event_type: &str,
         payload: &JsonValue,
-    ) -> Result<WebhookLog> {
-        let row = sqlx::query_as!(
-            WebhookLog,
+        dedup_key: Option<&str>,
+    ) -> Result<Vec<WebhookLog>> {
+        let mut tx = self.pool.begin().await?;
+        let logs =
+            Self::enqueue_webhook_in(&mut tx, &self.target_url, event_type, payload, dedup_key)
+                .await?;
+        tx.commit().await?;
+        Ok(logs)
+    }
+
+    /// [`Self::enqueue_webhook`] on the caller's connection, so the event can
+    /// be queued inside the transaction that caused it. `bot_url` is the
+    /// bot webhook's target.
+    pub async fn enqueue_webhook_in(
+        conn: &mut PgConnection,
+        bot_url: &str,
+        event_type: &str,
+        payload: &JsonValue,
+        dedup_key: Option<&str>,
+    ) -> Result<Vec<WebhookLog>> {
+        let bot_log = sqlx::query_as::<_, WebhookLog>(
             r#"
-            INSERT INTO webhook_logs (event_type, payload, target_url, status)
-            VALUES ($1, $2, $3, 'pending')
-            RETURNING 
-                id, event_type, payload as "payload: serde_json::Value", target_url,
-                http_status, response_body, attempts, max_attempts, next_retry_at, status,
-                created_at as "created_at?: _", updated_at as "updated_at?: _"
+            INSERT INTO webhook_logs (event_type, payload, target_url, status, notification_dedup_key)
+            VALUES ($1, $2, $3, 'pending', $4)
+            ON CONFLICT (notification_dedup_key) DO NOTHING
+            RETURNING
+                id, event_type, payload, target_url, http_status, response_body, attempts,
+                max_attempts, next_retry_at, status, subscription_id, target, created_at, updated_at
             "#,
-            event_type,
-            payload,
-            self.target_url
         )
-        .fetch_one(&self.pool)
+        .bind(event_type)
+        .bind(payload)
+        .bind(bot_url)
+        .bind(dedup_key.map(|key| notification_dedup_key(event_type, key)))
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some(bot_log) = bot_log else {
+            tracing::info!(
+                "Skipping duplicate {} notification for {}",
+                event_type,
+                dedup_key.unwrap_or_default()
+            );
+            return Ok(Vec::new());
+        };
+        let org_id = event_organization(conn, payload).await?;
+        let mut logs = vec![bot_log];
+        logs.extend(
+            WebhookSubscriptionService::enqueue_for_subscribers(conn, org_id, event_type, payload)
+                .await?,
+        );
+        Ok(logs)
+    }
+
+    /// Queues a test status push for each 1F base URL. Delivery goes through
+    /// the regular worker, so pushes are retried while 1F is unreachable.
+    pub async fn enqueue_onef_test_status<'e>(
+        executor: impl PgExecutor<'e>,
+        base_urls: &[String],
+        payload: &OneFTestStatusPayload,
+    ) -> Result<Vec<WebhookLog>> {
+        if base_urls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let urls: Vec<String> = base_urls.iter().map(|base| test_status_url(base)).collect();
+        let body = serde_json::json!({ "requestBody": payload });
+        let logs = sqlx::query_as::<_, WebhookLog>(
+            r#"
+            INSERT INTO webhook_logs (event_type, payload, target_url, status, target)
+            SELECT $1, $2, url, 'pending', $3
+            FROM UNNEST($4::TEXT[]) AS url
+            RETURNING
+                id, event_type, payload, target_url, http_status, response_body, attempts,
+                max_attempts, next_retry_at, status, subscription_id, target, created_at, updated_at
+            "#,
+        )
+        .bind(ONEF_TEST_STATUS_EVENT)
+        .bind(&body)
+        .bind(ONEF_TARGET)
+        .bind(&urls)
+        .fetch_all(executor)
         .await?;
-        Ok(row)
+        Ok(logs)
     }
 
     pub async fn deliver_once(&self, log_id: uuid::Uuid) -> Result<()> {
         let log = sqlx::query_as!(
             WebhookLog,
-            r#"SELECT id, event_type, payload as "payload: serde_json::Value", target_url, http_status, response_body, attempts, max_attempts, next_retry_at, status, created_at as "created_at?: _", updated_at as "updated_at?: _" FROM webhook_logs WHERE id = $1"#,
+            r#"SELECT id, event_type, payload as "payload: serde_json::Value", target_url, http_status, response_body, attempts, max_attempts, next_retry_at, status, subscription_id, target, created_at as "created_at?: _", updated_at as "updated_at?: _" FROM webhook_logs WHERE id = $1"#,
             log_id
         )
         .fetch_one(&self.pool)
         .await?;
 
-        let secret = crate::config::get_config().webhook_secret.clone();
-        let res = self
-            .client
-            .post(&log.target_url)
-            .header("X-Webhook-Secret", secret)
-            .json(&log.payload)
-            .send()
-            .await;
+        let res = if log.target == ONEF_TARGET {
+            match &self.onef {
+                Some(onef) => onef
+                    .deliver(&log.target_url, &log.payload)
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => Err("1F delivery is not configured on this worker".to_string()),
+            }
+        } else {
+            let secret = match log.subscription_id {
+                Some(subscription_id) => {
+                    sqlx::query_scalar::<_, String>(
+                        "SELECT secret FROM webhook_subscriptions WHERE id = $1",
+                    )
+                    .bind(subscription_id)
+                    .fetch_one(&self.pool)
+                    .await?
+                }
+                None => crate::config::get_config().webhook_secret.clone(),
+            };
+            let body = serde_json::to_vec(&log.payload)?;
+            let mut request = self
+                .client
+                .post(&log.target_url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, webhook_signature(&secret, &body));
+            // The bot still checks the shared secret itself; subscribers only
+            // get the signature, so the secret never travels with the request.
+            if log.subscription_id.is_none() {
+                request = request.header("X-Webhook-Secret", secret);
+            }
+            match request.body(body).send().await {
+                Ok(resp) => {
+                    let status = resp.status().as_u16();
+                    Ok((status, resp.text().await.unwrap_or_default()))
+                }
+                Err(err) => Err(err.to_string()),
+            }
+        };
         match res {
-            Ok(resp) => {
-                let status = resp.status().as_u16() as i32;
-                let body = resp.text().await.unwrap_or_default();
+            Ok((status, body)) => {
+                let status = status as i32;
                 sqlx::query!(
                     r#"UPDATE webhook_logs SET http_status = $1, response_body = $2, status = CASE WHEN $1 BETWEEN 200 AND 299 THEN 'success' ELSE 'failed' END, attempts = COALESCE(attempts,0) + 1, updated_at = NOW() WHERE id = $3"#,
                     status,
@@ -78,7 +239,7 @@ impl NotificationService {
             Err(err) => {
                 sqlx::query!(
                     r#"UPDATE webhook_logs SET response_body = $1, status = 'failed', attempts = COALESCE(attempts,0) + 1, updated_at = NOW() WHERE id = $2"#,
-                    format!("{}", err),
+                    err,
                     log.id
                 )
                 .execute(&self.pool)
@@ -113,10 +274,13 @@ impl NotificationService {
         let max_attempts: i32 = row2.try_get::<Option<i32>, _>("max_attempts")?.unwrap_or(3);
         let status: String = row2.try_get("status")?;
 
+        // Failed deliveries go back to 'pending' with a backoff until they
+        // run out of attempts.
         if status == "failed" && attempts < max_attempts {
             sqlx::query(
                 r#"UPDATE webhook_logs 
-                   SET next_retry_at = NOW() + make_interval(secs => LEAST(3600, 30 * power(2::float, GREATEST(0, attempts-1))::int))
+                   SET status = 'pending',
+                       next_retry_at = NOW() + make_interval(secs => LEAST(3600, 30 * power(2::float, GREATEST(0, attempts-1))::int))
                    WHERE id = $1"#,
             )
             .bind(id)
@@ -38,6 +38,17 @@ pub struct OneFCandidateInfo {
     pub dob: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cv_url: Option<String>,
+    /// Signed `/api/files/signed/:token` link; works without exposing the path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cv_download_url: Option<String>,
+    /// The CV itself, only for files within `ONEF_CV_INLINE_MAX_KB`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cv_base64: Option<String>,
+    /// Portfolio documents uploaded with the CV, base64-encoded, in upload
+    /// order. Files over `ONEF_CV_INLINE_MAX_KB` (or all of them when it is
+    /// `0`) are left out.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cv_portfolio: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ai_rating: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -91,6 +102,32 @@ pub struct OneFCandidateStatusPayload {
 const PATH_CANDIDATE_RESPONSE: &str = "/action/candidateResponse";
 const PATH_POST_TEST_STATUS: &str = "/action/postTestStatus";
 const PATH_RECEIVE_MESSAGE: &str = "/action/receivemessage";
+/// 1F keeps the CV link on the application, so it outlives dashboard links.
+const ONEF_CV_LINK_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 24 * 3600);
+
+/// Absolute signed download link for a stored CV, as sent to 1F.
+pub fn onef_cv_download_url(stored: &str) -> String {
+    let link = crate::utils::signed_url::signed_download_url(stored, ONEF_CV_LINK_TTL);
+    format!("{}/{}", crate::config::get_config().webapp_url, link)
+}
+
+/// Base64 of the CV at `stored` when it is at most `max_bytes` long; `None`
+/// for larger or unreadable files, which 1F then fetches by link.
+pub async fn inline_cv(stored: &str, max_bytes: u64) -> Option<String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    let path = crate::utils::signed_url::upload_disk_path(stored);
+    let size = tokio::fs::metadata(&path).await.ok()?.len();
+    if size > max_bytes {
+        return None;
+    }
+    let data = tokio::fs::read(&path).await.ok()?;
+    Some(STANDARD.encode(data))
+}
+
+/// The test status endpoint on a 1F base URL.
+pub fn test_status_url(base_url: &str) -> String {
+    format!("{}{}", base_url, PATH_POST_TEST_STATUS)
+}
 
 #[derive(Clone)]
 pub struct OneFService {
@@ -120,6 +157,7 @@ impl OneFService {
         !self.base_urls.is_empty()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn notify_application(
         &self,
         vacancy_id: i64,
@@ -131,6 +169,7 @@ impl OneFService {
         phone: Option<String>,
         dob: Option<chrono::NaiveDate>,
         cv_url: Option<String>,
+        cv_portfolio: &[String],
         ai_rating: Option<i32>,
         ai_comment: Option<String>,
     ) -> Result<(), String> {
@@ -150,9 +189,25 @@ impl OneFService {
             }
         };
 
+        let config = crate::config::get_config();
+        let cv_download_url = cv_url.as_deref().map(onef_cv_download_url);
+        let cv_base64 = match cv_url.as_deref() {
+            Some(path) if config.onef_cv_inline_max_kb > 0 => {
+                inline_cv(path, config.onef_cv_inline_max_kb as u64 * 1024).await
+            }
+            _ => None,
+        };
+        let mut portfolio_base64 = Vec::new();
+        if config.onef_cv_inline_max_kb > 0 {
+            for path in cv_portfolio {
+                if let Some(encoded) = inline_cv(path, config.onef_cv_inline_max_kb as u64 * 1024).await {
+                    portfolio_base64.push(encoded);
+                }
+            }
+        }
         let full_cv_url = cv_url.map(|path| {
-            let config = crate::config::get_config();
-            format!("{}/{}", config.webapp_url, path)
+            let link = crate::utils::signed_url::signed_upload_url(&path, ONEF_CV_LINK_TTL);
+            format!("{}/{}", config.webapp_url, link)
         });
 
         let payload = OneFApplicationPayload {
@@ -169,6 +224,9 @@ impl OneFService {
                 phone,
                 dob: dob.map(|d| d.format("%Y-%m-%d").to_string()),
                 cv_url: full_cv_url,
+                cv_download_url,
+                cv_base64,
+                cv_portfolio: portfolio_base64,
                 ai_rating,
                 ai_comment,
             },
@@ -195,6 +253,41 @@ impl OneFService {
         Ok(())
     }
 
+    pub async fn notify_application_withdrawn(
+        &self,
+        candidate_id: uuid::Uuid,
+        application_id: i32,
+        vacancy_id: i64,
+    ) -> Result<(), String> {
+        if self.base_urls.is_empty() {
+            return Ok(());
+        }
+
+        let payload = json!({
+            "event_type": "application_withdrawn",
+            "candidate_id": candidate_id,
+            "application_id": application_id,
+            "vacancy_id": vacancy_id,
+            "withdrawn_at": chrono::Utc::now().to_rfc3339(),
+        });
+
+        let wrapper = json!({
+            "requestBody": payload
+        });
+
+        info!(
+            "Notifying 1F of withdrawal: candidate {} vacancy {} → {} target(s)",
+            candidate_id, vacancy_id, self.base_urls.len()
+        );
+
+        let urls: Vec<String> = self.base_urls.iter()
+            .map(|base| format!("{}{}", base, PATH_CANDIDATE_RESPONSE))
+            .collect();
+
+        self.fan_out_post(&urls, &wrapper, "application_withdrawn").await;
+        Ok(())
+    }
+
     pub async fn notify_grade(
         &self,
         candidate_id: uuid::Uuid,
@@ -276,7 +369,7 @@ impl OneFService {
         }
 
         let urls: Vec<String> = self.base_urls.iter()
-            .map(|base| format!("{}{}", base, PATH_POST_TEST_STATUS))
+            .map(|base| test_status_url(base))
             .collect();
 
         let wrapper = json!({
@@ -327,6 +420,24 @@ impl OneFService {
         Ok(())
     }
 
+    /// Delivers a queued 1F push (see `NotificationService::deliver_once`),
+    /// returning the HTTP status and body so the queue can retry failures.
+    pub async fn deliver(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> Result<(u16, String), reqwest::Error> {
+        let resp = self.client.post(url).json(body).send().await?;
+        let status = resp.status().as_u16();
+        let resp_body = resp.text().await.unwrap_or_default();
+        if (200..300).contains(&status) {
+            info!("1F queued push → {} returned {}", url, status);
+        } else {
+            warn!("1F queued push → {} returned {}: {}", url, status, resp_body);
+        }
+        Ok((status, resp_body))
+    }
+
     async fn fan_out_post(
         &self,
         urls: &[String],
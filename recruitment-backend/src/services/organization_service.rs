@@ -0,0 +1,88 @@
+use crate::error::{Error, Result};
+use crate::models::organization::Organization;
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const ORGANIZATION_COLUMNS: &str = "id, name, slug, created_at";
+
+/// Slugs are lowercase ASCII letters, digits and single inner hyphens,
+/// 2-63 characters, so they are safe in URLs and subdomains.
+pub fn is_valid_slug(slug: &str) -> bool {
+    (2..=63).contains(&slug.len())
+        && slug
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && !slug.contains("--")
+}
+
+pub fn organization_not_found(id: Uuid) -> Error {
+    Error::coded(
+        StatusCode::NOT_FOUND,
+        "organization_not_found",
+        format!("Organization {} not found", id),
+    )
+}
+
+#[derive(Clone)]
+pub struct OrganizationService {
+    pool: PgPool,
+}
+
+impl OrganizationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, name: &str, slug: &str) -> Result<Organization> {
+        let slug = slug.trim().to_lowercase();
+        if !is_valid_slug(&slug) {
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_organization_slug",
+                "slug must be 2-63 lowercase letters, digits or hyphens",
+            )
+            .with_details(json!({ "slug": slug })));
+        }
+
+        let created = sqlx::query_as::<_, Organization>(&format!(
+            "INSERT INTO organizations (name, slug) VALUES ($1, $2) ON CONFLICT (slug) DO NOTHING RETURNING {}",
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(name.trim())
+        .bind(&slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        created.ok_or_else(|| {
+            Error::coded(
+                StatusCode::CONFLICT,
+                "organization_slug_taken",
+                format!("An organization with slug '{}' already exists", slug),
+            )
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<Organization>> {
+        let rows = sqlx::query_as::<_, Organization>(&format!(
+            "SELECT {} FROM organizations ORDER BY created_at, slug",
+            ORGANIZATION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Organization> {
+        sqlx::query_as::<_, Organization>(&format!(
+            "SELECT {} FROM organizations WHERE id = $1",
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| organization_not_found(id))
+    }
+}
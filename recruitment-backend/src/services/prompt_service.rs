@@ -0,0 +1,71 @@
+use crate::error::{Error, Result};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+pub const PROMPT_REFRESH_INTERVAL: Duration = Duration::from_secs(120);
+
+/// A prompt stored either as a plain string or as an array of lines.
+pub fn prompt_text(content: &JsonValue) -> Option<String> {
+    match content {
+        JsonValue::String(s) if !s.trim().is_empty() => Some(s.clone()),
+        JsonValue::Array(lines) => {
+            let lines: Option<Vec<&str>> = lines.iter().map(|l| l.as_str()).collect();
+            lines.filter(|l| !l.is_empty()).map(|l| l.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Prompts from the `ai_prompts` table, cached in memory. Lookups never hit
+/// the database; the cache is replaced by `refresh`, which `main` runs every
+/// `PROMPT_REFRESH_INTERVAL`.
+#[derive(Clone)]
+pub struct PromptService {
+    pool: PgPool,
+    cache: Arc<RwLock<HashMap<String, JsonValue>>>,
+}
+
+impl PromptService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn refresh(&self) -> Result<usize> {
+        let rows: Vec<(String, JsonValue)> = sqlx::query_as("SELECT key, content FROM ai_prompts")
+            .fetch_all(&self.pool)
+            .await?;
+        let count = rows.len();
+        *self.cache.write().await = rows.into_iter().collect();
+        Ok(count)
+    }
+
+    pub async fn get(&self, key: &str) -> Result<JsonValue> {
+        self.cache
+            .read()
+            .await
+            .get(key)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Prompt '{}' not found", key)))
+    }
+
+    /// The stored prompt for `key`, or `default` when it is missing or not text.
+    pub async fn text_or(&self, key: &str, default: &str) -> String {
+        match self.get(key).await {
+            Ok(content) => prompt_text(&content).unwrap_or_else(|| {
+                tracing::warn!(
+                    "Prompt '{}' is not a string or array of strings, using default",
+                    key
+                );
+                default.to_string()
+            }),
+            Err(_) => default.to_string(),
+        }
+    }
+}
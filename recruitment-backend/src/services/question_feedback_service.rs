@@ -0,0 +1,149 @@
+use crate::error::{Error, Result};
+use crate::models::question_feedback::{QuestionFeedback, QuestionFeedbackItem};
+use crate::models::test_attempt::TestAttempt;
+use crate::utils::crypto::sha256_hex;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value as JsonValue};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Caps how much feedback one attempt can leave through the public API.
+pub const MAX_FEEDBACK_PER_ATTEMPT: i64 = 20;
+
+/// Identifies a candidate to HR without revealing the address: hex SHA-256
+/// of the trimmed, lower-cased email.
+pub fn hash_email(email: &str) -> String {
+    sha256_hex(email.trim().to_lowercase().as_bytes())
+}
+
+/// Whether `question_id` is one of the questions in an attempt's snapshot.
+pub fn snapshot_has_question(snapshot: &JsonValue, question_id: i32) -> bool {
+    snapshot
+        .as_array()
+        .map(|questions| {
+            questions
+                .iter()
+                .any(|q| q.get("id").and_then(JsonValue::as_i64) == Some(question_id as i64))
+        })
+        .unwrap_or(false)
+}
+
+#[derive(sqlx::FromRow)]
+struct FeedbackRow {
+    id: Uuid,
+    question_id: i32,
+    text: String,
+    candidate_email: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct QuestionFeedbackService {
+    pool: PgPool,
+}
+
+impl QuestionFeedbackService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn submit(
+        &self,
+        attempt: &TestAttempt,
+        question_id: i32,
+        text: &str,
+    ) -> Result<QuestionFeedback> {
+        if !snapshot_has_question(&attempt.questions_snapshot, question_id) {
+            return Err(Error::coded(
+                StatusCode::NOT_FOUND,
+                "question_not_found",
+                format!("Question {} is not part of this test", question_id),
+            ));
+        }
+        let feedback = sqlx::query_as::<_, QuestionFeedback>(
+            r#"
+            INSERT INTO question_feedback (attempt_id, question_id, text)
+            SELECT $1, $2, $3
+            WHERE (SELECT COUNT(*) FROM question_feedback WHERE attempt_id = $1) < $4
+            RETURNING id, attempt_id, question_id, text, reviewed, created_at
+            "#,
+        )
+        .bind(attempt.id)
+        .bind(question_id)
+        .bind(text.trim())
+        .bind(MAX_FEEDBACK_PER_ATTEMPT)
+        .fetch_optional(&self.pool)
+        .await?;
+        feedback.ok_or_else(|| {
+            Error::coded(
+                StatusCode::TOO_MANY_REQUESTS,
+                "feedback_limit_reached",
+                "Too much feedback for this test attempt",
+            )
+            .with_details(json!({ "max_per_attempt": MAX_FEEDBACK_PER_ATTEMPT }))
+        })
+    }
+
+    /// Unreviewed feedback on `test_id`, oldest first.
+    pub async fn list_unreviewed(&self, test_id: Uuid) -> Result<Vec<QuestionFeedbackItem>> {
+        self.ensure_test_exists(test_id).await?;
+        let rows = sqlx::query_as::<_, FeedbackRow>(
+            r#"
+            SELECT qf.id, qf.question_id, qf.text, ta.candidate_email, qf.created_at
+            FROM question_feedback qf
+            JOIN test_attempts ta ON ta.id = qf.attempt_id
+            WHERE ta.test_id = $1 AND qf.reviewed = FALSE
+            ORDER BY qf.created_at, qf.id
+            "#,
+        )
+        .bind(test_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| QuestionFeedbackItem {
+                id: row.id,
+                question_id: row.question_id,
+                feedback_text: row.text,
+                candidate_email: hash_email(&row.candidate_email),
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    pub async fn set_reviewed(
+        &self,
+        test_id: Uuid,
+        feedback_id: Uuid,
+        reviewed: bool,
+    ) -> Result<QuestionFeedback> {
+        let feedback = sqlx::query_as::<_, QuestionFeedback>(
+            r#"
+            UPDATE question_feedback qf
+            SET reviewed = $3
+            FROM test_attempts ta
+            WHERE qf.id = $2 AND ta.id = qf.attempt_id AND ta.test_id = $1
+            RETURNING qf.id, qf.attempt_id, qf.question_id, qf.text, qf.reviewed, qf.created_at
+            "#,
+        )
+        .bind(test_id)
+        .bind(feedback_id)
+        .bind(reviewed)
+        .fetch_optional(&self.pool)
+        .await?;
+        feedback.ok_or_else(|| Error::NotFound("Feedback not found".into()))
+    }
+
+    async fn ensure_test_exists(&self, test_id: Uuid) -> Result<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tests WHERE id = $1)")
+            .bind(test_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if exists {
+            Ok(())
+        } else {
+            Err(Error::NotFound("Test not found".into()))
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::io::Cursor;
+
+use axum::http::StatusCode;
+use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::dto::integration_dto::CreateQuestion;
+use crate::error::{Error, Result};
+use crate::models::question::{
+    Difficulty, MultipleChoiceDetails, QuestionDetails, QuestionType, ShortAnswerDetails,
+};
+
+/// Columns of the question sheet, in order. Row 1 holds these headers and
+/// is skipped on import; every following non-empty row is one question.
+pub const QUESTION_SHEET_COLUMNS: [&str; 11] = [
+    "type",
+    "question",
+    "option1",
+    "option2",
+    "option3",
+    "option4",
+    "correct_option",
+    "explanation",
+    "keywords",
+    "min_words",
+    "points",
+];
+
+const COLUMN_HINTS: [&str; 11] = [
+    "multiple_choice или short_answer",
+    "Текст вопроса",
+    "Вариант 1 (multiple_choice)",
+    "Вариант 2 (multiple_choice)",
+    "Вариант 3, необязательно",
+    "Вариант 4, необязательно",
+    "Номер правильного варианта, 1–4 (multiple_choice)",
+    "Пояснение к ответу, необязательно (multiple_choice)",
+    "Ключевые слова через запятую, необязательно (short_answer)",
+    "Минимум слов в ответе, необязательно (short_answer)",
+    "Баллы, целое число больше 0; пусто — 1",
+];
+
+/// Problems found in one sheet row; `row` is the spreadsheet row number.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RowError {
+    pub row: u32,
+    pub problems: Vec<String>,
+}
+
+/// Reads questions from the first worksheet of an XLSX upload laid out as
+/// [`QUESTION_SHEET_COLUMNS`]. A file that is not a workbook is 422
+/// `invalid_question_sheet`; rows that do not make a valid question are
+/// collected and returned together as 422 `invalid_question_rows`.
+pub fn parse_question_sheet(data: &[u8]) -> Result<Vec<CreateQuestion>> {
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(data))
+        .map_err(|e| invalid_sheet(format!("File is not a readable XLSX workbook: {}", e)))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| invalid_sheet("Workbook has no worksheets".to_string()))?
+        .map_err(|e| invalid_sheet(format!("Failed to read the first worksheet: {}", e)))?;
+
+    let mut questions = Vec::new();
+    let mut errors = Vec::new();
+    let last_row = range.end().map(|(row, _)| row).unwrap_or(0);
+    for row in 1..=last_row {
+        let cells: Vec<String> = (0..QUESTION_SHEET_COLUMNS.len() as u32)
+            .map(|col| {
+                range
+                    .get_value((row, col))
+                    .map(cell_text)
+                    .unwrap_or_default()
+            })
+            .collect();
+        if cells.iter().all(|c| c.is_empty()) {
+            continue;
+        }
+        match parse_row(&cells) {
+            Ok(question) => questions.push(question),
+            Err(problems) => errors.push(RowError {
+                row: row + 1,
+                problems,
+            }),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_question_rows",
+            format!("{} row(s) of the question sheet are invalid", errors.len()),
+        )
+        .with_details(json!({ "rows": errors })));
+    }
+    if questions.is_empty() {
+        return Err(invalid_sheet(
+            "The question sheet has no questions".to_string(),
+        ));
+    }
+    Ok(questions)
+}
+
+/// The empty question sheet HR fills in: headers on the first worksheet and
+/// a description of every column on the second.
+pub fn question_template() -> Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let header = Format::new().set_bold();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Вопросы")?;
+    for (col, name) in QUESTION_SHEET_COLUMNS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *name, &header)?;
+        sheet.set_column_width(col as u16, if col == 1 { 50 } else { 16 })?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    let help = workbook.add_worksheet();
+    help.set_name("Инструкция")?;
+    help.write_string_with_format(0, 0, "Колонка", &header)?;
+    help.write_string_with_format(0, 1, "Содержимое", &header)?;
+    help.set_column_width(0, 16)?;
+    help.set_column_width(1, 60)?;
+    for (i, (name, hint)) in QUESTION_SHEET_COLUMNS.iter().zip(COLUMN_HINTS).enumerate() {
+        help.write_string(i as u32 + 1, 0, *name)?;
+        help.write_string(i as u32 + 1, 1, hint)?;
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+fn invalid_sheet(message: String) -> Error {
+    Error::coded(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "invalid_question_sheet",
+        message,
+    )
+}
+
+/// Whole-number floats print without the `.0` Excel stores them with.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) => s.trim().to_string(),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => (*f as i64).to_string(),
+        other => other.to_string().trim().to_string(),
+    }
+}
+
+fn parse_row(cells: &[String]) -> std::result::Result<CreateQuestion, Vec<String>> {
+    let mut problems = Vec::new();
+    let cell = |name: &str| {
+        let col = QUESTION_SHEET_COLUMNS
+            .iter()
+            .position(|c| *c == name)
+            .unwrap();
+        Some(cells[col].as_str()).filter(|v| !v.is_empty())
+    };
+
+    let question = cell("question").unwrap_or_default().to_string();
+    if question.is_empty() {
+        problems.push("question is required".to_string());
+    }
+    let points = match cell("points") {
+        None => 1,
+        Some(raw) => match raw.parse::<i32>() {
+            Ok(p) if p > 0 => p,
+            _ => {
+                problems.push(format!(
+                    "points must be a whole number above 0, got '{}'",
+                    raw
+                ));
+                1
+            }
+        },
+    };
+
+    let details = match cell("type").map(str::to_lowercase).as_deref() {
+        Some("multiple_choice") => {
+            let slots: Vec<Option<&str>> = ["option1", "option2", "option3", "option4"]
+                .iter()
+                .map(|c| cell(c))
+                .collect();
+            let options: Vec<String> = slots.iter().map_while(|o| o.map(str::to_string)).collect();
+            if slots[options.len()..].iter().any(Option::is_some) {
+                problems
+                    .push("options must be filled in order from option1 without gaps".to_string());
+            } else if options.len() < 2 {
+                problems.push("multiple_choice needs at least 2 options".to_string());
+            }
+            let correct_answer = match cell("correct_option").map(|raw| (raw, raw.parse::<usize>()))
+            {
+                None => {
+                    problems.push("correct_option is required for multiple_choice".to_string());
+                    0
+                }
+                Some((_, Ok(n))) if (1..=options.len()).contains(&n) => n as i32 - 1,
+                Some((raw, _)) => {
+                    problems.push(format!(
+                        "correct_option must be between 1 and {}, got '{}'",
+                        options.len().max(1),
+                        raw
+                    ));
+                    0
+                }
+            };
+            Some((
+                QuestionType::MultipleChoice,
+                QuestionDetails::MultipleChoice(MultipleChoiceDetails {
+                    options,
+                    correct_answer,
+                    explanation: cell("explanation").map(str::to_string),
+                }),
+            ))
+        }
+        Some("short_answer") => {
+            let expected_keywords = cell("keywords").map(|raw| {
+                raw.split([',', ';'])
+                    .map(|k| k.trim().to_string())
+                    .filter(|k| !k.is_empty())
+                    .collect::<Vec<_>>()
+            });
+            let min_words = match cell("min_words") {
+                None => None,
+                Some(raw) => match raw.parse::<i32>() {
+                    Ok(n) if n >= 0 => Some(n),
+                    _ => {
+                        problems.push(format!("min_words must be a whole number, got '{}'", raw));
+                        None
+                    }
+                },
+            };
+            Some((
+                QuestionType::ShortAnswer,
+                QuestionDetails::ShortAnswer(ShortAnswerDetails {
+                    expected_keywords,
+                    min_words,
+                    ai_grading: false,
+                }),
+            ))
+        }
+        Some(other) => {
+            problems.push(format!(
+                "type must be multiple_choice or short_answer, got '{}'",
+                other
+            ));
+            None
+        }
+        None => {
+            problems.push("type is required".to_string());
+            None
+        }
+    };
+
+    match details {
+        Some((question_type, details)) if problems.is_empty() => Ok(CreateQuestion {
+            question_type,
+            question,
+            points,
+            difficulty: Difficulty::default(),
+            details,
+        }),
+        _ => Err(problems),
+    }
+}
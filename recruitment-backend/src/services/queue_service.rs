@@ -1,10 +1,27 @@
 use crate::error::Result;
+use crate::services::ai_service::{DifficultyDistribution, VacancyLanguage};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use serde_json::Value as JsonValue;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Claims a job gets before a failure parks it as `dead`.
+pub const MAX_JOB_ATTEMPTS: i32 = 5;
+
+/// Telegram text posted to the operators' chat when a job is dead-lettered.
+pub fn dead_job_alert(job_id: Uuid, profession: &str, attempts: i32, reason: &str) -> String {
+    format!(
+        "AI job {} ({}) failed {} times and was moved to the dead-letter queue.\nRetry it with POST /api/integration/ai-jobs/{}/retry once the cause is fixed.\n\n{}",
+        job_id,
+        if profession.is_empty() { "-" } else { profession },
+        attempts,
+        job_id,
+        crate::error::truncate_alert_message(reason)
+    )
+}
+
 #[derive(Clone)]
 pub struct AiQueueService {
     pub pool: PgPool,
@@ -15,6 +32,7 @@ impl AiQueueService {
         Self { pool }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn enqueue(
         &self,
         payload: JsonValue,
@@ -23,12 +41,13 @@ impl AiQueueService {
         description: Option<String>,
         duration_minutes: Option<i32>,
         passing_score: Option<f64>,
+        org_id: Uuid,
     ) -> Result<Uuid> {
         let passing_dec: Option<Decimal> = passing_score.and_then(Decimal::from_f64);
         let row = sqlx::query(
             r#"
-            INSERT INTO ai_jobs (payload, persist, title, description, duration_minutes, passing_score)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO ai_jobs (payload, persist, title, description, duration_minutes, passing_score, organization_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#,
         )
@@ -38,6 +57,7 @@ impl AiQueueService {
         .bind(description)
         .bind(duration_minutes)
         .bind(passing_dec)
+        .bind(org_id)
         .fetch_one(&self.pool)
         .await?;
         let id: Uuid = row.try_get("id")?;
@@ -46,7 +66,10 @@ impl AiQueueService {
 
     pub async fn get(&self, id: Uuid) -> Result<JsonValue> {
         let row = sqlx::query(
-            r#"SELECT id, status, payload, result, error, test_id, created_at, started_at, finished_at FROM ai_jobs WHERE id=$1"#,
+            r#"SELECT id, status, payload, result, error, test_id, created_at, started_at, finished_at,
+                      prompt_tokens, completion_tokens, calls_made, usage_by_model,
+                      attempts, dead_letter_reason
+               FROM ai_jobs WHERE id=$1"#,
         )
         .bind(id)
         .fetch_one(&self.pool)
@@ -57,19 +80,256 @@ impl AiQueueService {
             "payload": row.try_get::<JsonValue,_>("payload")?,
             "result": row.try_get::<Option<JsonValue>,_>("result")?,
             "error": row.try_get::<Option<String>,_>("error")?,
+            "attempts": row.try_get::<i32,_>("attempts")?,
+            "dead_letter_reason": row.try_get::<Option<String>,_>("dead_letter_reason")?,
             "test_id": row.try_get::<Option<Uuid>,_>("test_id")?,
             "created_at": row.try_get::<chrono::DateTime<chrono::Utc>,_>("created_at")?,
             "started_at": row.try_get::<Option<chrono::DateTime<chrono::Utc>>,_>("started_at")?,
             "finished_at": row.try_get::<Option<chrono::DateTime<chrono::Utc>>,_>("finished_at")?,
+            "usage": {
+                "total_prompt_tokens": row.try_get::<i64,_>("prompt_tokens")?,
+                "total_completion_tokens": row.try_get::<i64,_>("completion_tokens")?,
+                "calls_made": row.try_get::<i32,_>("calls_made")?,
+                "by_model": row.try_get::<JsonValue,_>("usage_by_model")?,
+            },
+        }))
+    }
+
+    /// Stores the token usage of a generation that ran inside a request, so the
+    /// usage report covers it alongside queued jobs.
+    pub async fn record_usage(
+        &self,
+        source: &str,
+        output: &crate::services::ai_service::GenerationOutput,
+    ) -> Result<()> {
+        if output.usage_by_model.is_empty() {
+            return Ok(());
+        }
+        sqlx::query("INSERT INTO ai_usage (source, usage_by_model) VALUES ($1, $2)")
+            .bind(source)
+            .bind(serde_json::to_value(&output.usage_by_model)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn usage_summary(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<JsonValue> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                TO_CHAR(j.created_at, 'YYYY-MM-DD') AS day,
+                m.key AS model,
+                SUM(COALESCE((m.value->>'prompt_tokens')::bigint, 0))::bigint AS prompt_tokens,
+                SUM(COALESCE((m.value->>'completion_tokens')::bigint, 0))::bigint AS completion_tokens,
+                SUM(COALESCE((m.value->>'calls')::bigint, 0))::bigint AS calls
+            FROM (
+                SELECT created_at, usage_by_model FROM ai_jobs
+                UNION ALL
+                SELECT created_at, usage_by_model FROM ai_usage
+            ) j
+            CROSS JOIN LATERAL jsonb_each(j.usage_by_model) AS m
+            WHERE j.created_at >= $1 AND j.created_at < $2
+            GROUP BY day, model
+            ORDER BY day, model
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut daily = Vec::with_capacity(rows.len());
+        let mut by_model: std::collections::BTreeMap<String, (i64, i64, i64)> = Default::default();
+        for row in rows {
+            let model: String = row.try_get("model")?;
+            let prompt: i64 = row.try_get("prompt_tokens")?;
+            let completion: i64 = row.try_get("completion_tokens")?;
+            let calls: i64 = row.try_get("calls")?;
+            let totals = by_model.entry(model.clone()).or_default();
+            totals.0 += prompt;
+            totals.1 += completion;
+            totals.2 += calls;
+            daily.push(serde_json::json!({
+                "day": row.try_get::<String,_>("day")?,
+                "model": model,
+                "prompt_tokens": prompt,
+                "completion_tokens": completion,
+                "calls": calls,
+            }));
+        }
+
+        let by_model: Vec<JsonValue> = by_model
+            .into_iter()
+            .map(|(model, (prompt, completion, calls))| serde_json::json!({
+                "model": model,
+                "prompt_tokens": prompt,
+                "completion_tokens": completion,
+                "calls": calls,
+            }))
+            .collect();
+
+        Ok(serde_json::json!({
+            "from": from,
+            "to": to,
+            "daily": daily,
+            "by_model": by_model,
         }))
     }
 
+    pub async fn pending_count(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ai_jobs WHERE status = 'pending'")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Puts jobs left `running` for over `older_than_minutes` (the worker
+    /// died mid-job) back to `pending`; returns how many were requeued. A job
+    /// that already used up its attempts is dead-lettered instead.
+    pub async fn requeue_stale(&self, older_than_minutes: i64) -> Result<u64> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE ai_jobs
+            SET status = CASE WHEN attempts >= $2 THEN 'dead' ELSE 'pending' END,
+                dead_letter_reason = CASE WHEN attempts >= $2
+                    THEN 'Worker stopped while running the job' END,
+                started_at = CASE WHEN attempts >= $2 THEN started_at END,
+                finished_at = CASE WHEN attempts >= $2 THEN NOW() END
+            WHERE status = 'running' AND started_at < NOW() - make_interval(mins => $1::int)
+            RETURNING id, status, attempts, payload, dead_letter_reason
+            "#,
+        )
+        .bind(older_than_minutes as i32)
+        .bind(MAX_JOB_ATTEMPTS)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut requeued = 0;
+        for row in rows {
+            if row.try_get::<String, _>("status")? == "dead" {
+                Self::alert_dead(&row)?;
+            } else {
+                requeued += 1;
+            }
+        }
+        Ok(requeued)
+    }
+
+    /// Records a failed run. The job goes back in line as `failed` (picked up
+    /// again after a backoff of one minute per attempt) until it has been
+    /// claimed `MAX_JOB_ATTEMPTS` times, then it is parked as `dead` with
+    /// the error as its `dead_letter_reason` and the operators are alerted.
+    pub async fn record_failure(&self, job_id: Uuid, error: &str) -> Result<()> {
+        let row = sqlx::query(
+            r#"
+            UPDATE ai_jobs
+            SET status = CASE WHEN attempts >= $3 THEN 'dead' ELSE 'failed' END,
+                error = $2,
+                dead_letter_reason = CASE WHEN attempts >= $3 THEN $2 END,
+                finished_at = NOW()
+            WHERE id = $1
+            RETURNING id, status, attempts, payload, dead_letter_reason
+            "#,
+        )
+        .bind(job_id)
+        .bind(error)
+        .bind(MAX_JOB_ATTEMPTS)
+        .fetch_one(&self.pool)
+        .await?;
+        if row.try_get::<String, _>("status")? == "dead" {
+            Self::alert_dead(&row)?;
+        }
+        Ok(())
+    }
+
+    fn alert_dead(row: &sqlx::postgres::PgRow) -> Result<()> {
+        let job_id: Uuid = row.try_get("id")?;
+        let attempts: i32 = row.try_get("attempts")?;
+        let payload: JsonValue = row.try_get("payload")?;
+        let reason: Option<String> = row.try_get("dead_letter_reason")?;
+        let reason = reason.unwrap_or_default();
+        tracing::error!("AI job {} dead-lettered after {} attempts: {}", job_id, attempts, reason);
+        let profession = payload.get("profession").and_then(|v| v.as_str()).unwrap_or("");
+        crate::error::notify_operators(dead_job_alert(job_id, profession, attempts, &reason));
+        Ok(())
+    }
+
+    /// Dead-lettered jobs of the organization, most recent first.
+    pub async fn list_dead(&self, org_id: Uuid) -> Result<Vec<JsonValue>> {
+        let rows = sqlx::query(
+            r#"SELECT id, payload, attempts, dead_letter_reason, created_at, finished_at
+               FROM ai_jobs
+               WHERE status = 'dead' AND organization_id = $1
+               ORDER BY finished_at DESC NULLS LAST, created_at DESC"#,
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(serde_json::json!({
+                    "id": row.try_get::<Uuid,_>("id")?,
+                    "payload": row.try_get::<JsonValue,_>("payload")?,
+                    "attempts": row.try_get::<i32,_>("attempts")?,
+                    "dead_letter_reason": row.try_get::<Option<String>,_>("dead_letter_reason")?,
+                    "created_at": row.try_get::<chrono::DateTime<chrono::Utc>,_>("created_at")?,
+                    "finished_at": row.try_get::<Option<chrono::DateTime<chrono::Utc>>,_>("finished_at")?,
+                }))
+            })
+            .collect()
+    }
+
+    /// Puts a dead job back in line with a fresh set of attempts. `NotFound`
+    /// when the organization has no such job, `409 ai_job_not_dead` when it
+    /// is not dead-lettered.
+    pub async fn retry_dead(&self, id: Uuid, org_id: Uuid) -> Result<()> {
+        let retried = sqlx::query(
+            r#"UPDATE ai_jobs
+               SET status = 'pending', attempts = 0, dead_letter_reason = NULL,
+                   started_at = NULL, finished_at = NULL
+               WHERE id = $1 AND organization_id = $2 AND status = 'dead'"#,
+        )
+        .bind(id)
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        if retried > 0 {
+            return Ok(());
+        }
+        let status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM ai_jobs WHERE id = $1 AND organization_id = $2")
+                .bind(id)
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        match status {
+            None => Err(crate::error::Error::NotFound("AI job not found".to_string())),
+            Some(status) => Err(crate::error::Error::coded(
+                axum::http::StatusCode::CONFLICT,
+                "ai_job_not_dead",
+                "Only dead-lettered AI jobs can be retried",
+            )
+            .with_details(serde_json::json!({ "status": status }))),
+        }
+    }
+
     pub async fn run_once(&self, app_state: &crate::AppState) -> Result<bool> {
+        if let Ok(depth) = self.pending_count().await {
+            metrics::gauge!(crate::middleware::metrics::AI_QUEUE_DEPTH).set(depth as f64);
+        }
+
         let rec = sqlx::query(
             r#"
-            UPDATE ai_jobs SET status='running', started_at=NOW()
+            UPDATE ai_jobs SET status='running', started_at=NOW(), attempts = attempts + 1
             WHERE id = (
-                SELECT id FROM ai_jobs WHERE status='pending' ORDER BY created_at ASC FOR UPDATE SKIP LOCKED LIMIT 1
+                SELECT id FROM ai_jobs
+                WHERE status = 'pending'
+                   OR (status = 'failed' AND finished_at < NOW() - make_interval(mins => attempts))
+                ORDER BY created_at ASC FOR UPDATE SKIP LOCKED LIMIT 1
             )
             RETURNING id
             "#
@@ -80,7 +340,7 @@ impl AiQueueService {
         let job_id: Uuid = row.try_get("id")?;
 
         let job_row = sqlx::query(
-            r#"SELECT id, payload, persist, title, description, duration_minutes, passing_score FROM ai_jobs WHERE id=$1"#,
+            r#"SELECT id, payload, persist, title, description, duration_minutes, passing_score, organization_id FROM ai_jobs WHERE id=$1"#,
         )
         .bind(job_id)
         .fetch_one(&self.pool)
@@ -91,6 +351,7 @@ impl AiQueueService {
         let description: Option<String> = job_row.try_get("description")?;
         let duration_minutes: Option<i32> = job_row.try_get("duration_minutes")?;
         let passing_score_dec: Option<Decimal> = job_row.try_get("passing_score")?;
+        let org_id: Uuid = job_row.try_get("organization_id")?;
 
         let profession = payload
             .get("profession")
@@ -113,6 +374,16 @@ impl AiQueueService {
             .get("num_questions")
             .and_then(|v| v.as_u64())
             .unwrap_or(6) as usize;
+        let distribution = payload
+            .get("difficulty_distribution")
+            .and_then(|v| serde_json::from_value::<HashMap<String, f64>>(v.clone()).ok())
+            .and_then(|map| DifficultyDistribution::from_map(&map).ok())
+            .unwrap_or_default();
+        let language = payload
+            .get("language")
+            .and_then(|v| v.as_str())
+            .and_then(VacancyLanguage::parse)
+            .unwrap_or_default();
         let created_by_sub = payload
             .get("created_by_sub")
             .and_then(|v| v.as_str())
@@ -124,28 +395,24 @@ impl AiQueueService {
                 profession,
                 &skills,
                 num_q,
+                &distribution,
+                language,
             )
             .await;
 
         let gen_output = match gen_result {
             Ok(out) => out,
             Err(e) => {
-                let error_message = format!(
-                    "AI generation failed permanently for job. job_id={} error={}",
-                    job_id, e
-                );
-                tracing::error!("{}", error_message);
-                sqlx::query("UPDATE ai_jobs SET status = 'failed', error = $1, finished_at = NOW() WHERE id = $2")
-                    .bind(e.to_string())
-                    .bind(job_id)
-                    .execute(&self.pool)
-                    .await?;
+                tracing::error!("AI generation failed for job. job_id={} error={}", job_id, e);
+                self.record_failure(job_id, &e.to_string()).await?;
                 return Ok(true);
             }
         };
 
-        let mut questions = gen_output.questions;
+        let mut questions = gen_output.questions.clone();
+        let mut topup_rounds = 0;
         if questions.len() < num_q {
+            topup_rounds += 1;
             let need = num_q - questions.len();
             tracing::warn!(
                 "AI returned {} questions, topping up {} via fallbacks",
@@ -156,7 +423,7 @@ impl AiQueueService {
             let raw = serde_json::to_value(&questions)?;
             let filled = app_state
                 .ai_service
-                .sanitize_questions(&raw, num_q);
+                .sanitize_questions(&raw, num_q, &distribution, language);
             if !filled.is_empty() {
                 questions = filled;
             }
@@ -166,7 +433,7 @@ impl AiQueueService {
 
         let mut test_id: Option<Uuid> = None;
         if persist.unwrap_or(false) {
-            let result = (|| async {
+            let result = async {
                 let create_questions = app_state.ai_service.to_create_questions(&questions);
                 let created_by = match created_by_sub {
                     Some(ref sub) if !sub.is_empty() => {
@@ -183,7 +450,7 @@ impl AiQueueService {
                             )
                             .bind(new_id)
                             .bind(sub)
-                            .bind(format!("{}", sub))
+                            .bind(sub.to_string())
                             .bind(format!("{}@example.com", sub))
                             .bind("admin")
                             .execute(&self.pool)
@@ -222,11 +489,23 @@ impl AiQueueService {
                     test_type: Some("question_based".to_string()),
                     presentation_themes: None,
                     presentation_extra_info: None,
+                    show_honesty_declaration: None,
+                    declaration_text: None,
+                    prerequisite_test_id: None,
+                    default_invite_expiry_hours: None,
+                    reminder_hours_before: None,
+                    passing_score_mcq: None,
+                    passing_score_open: None,
+                    share_results_details: None,
+                    max_grace_seconds: None,
+                    max_concurrent_takers: None,
+                    language: Some(language.tag().to_string()),
+                    reveal_correct_answers: None,
                 };
 
-                let test = app_state.test_service.create_test(test_payload, created_by).await?;
+                let test = app_state.test_service.create_test(test_payload, created_by, org_id).await?;
                 anyhow::Ok(test.id)
-            })()
+            }
             .await;
 
             match result {
@@ -239,27 +518,33 @@ impl AiQueueService {
                         .bind(id)
                         .execute(&self.pool)
                         .await?;
+                    if let Err(e) = app_state
+                        .test_service
+                        .record_generation_run(id, Some(job_id), &gen_output, num_q, questions.len(), topup_rounds)
+                        .await
+                    {
+                        tracing::warn!("Failed to store generation log for test {}: {:?}", id, e);
+                    }
                 }
                 Err(e) => {
-                    let error_message = format!("Failed to persist test: {}", e);
-                    sqlx::query(
-                        r#"UPDATE ai_jobs SET status='failed', error=$1, finished_at=NOW() WHERE id=$2"#,
-                    )
-                    .bind(error_message)
-                    .bind(job_id)
-                    .execute(&self.pool)
-                    .await?;
+                    self.record_failure(job_id, &format!("Failed to persist test: {}", e)).await?;
                     return Ok(true);
                 }
             }
         }
 
         sqlx::query(
-            r#"UPDATE ai_jobs SET status='succeeded', result=$1, test_id=$2, finished_at=NOW() WHERE id=$3"#,
+            r#"UPDATE ai_jobs SET status='succeeded', result=$1, test_id=$2, finished_at=NOW(),
+                   prompt_tokens=$4, completion_tokens=$5, calls_made=$6, usage_by_model=$7
+               WHERE id=$3"#,
         )
         .bind(questions_val)
         .bind(test_id)
         .bind(job_id)
+        .bind(gen_output.total_prompt_tokens)
+        .bind(gen_output.total_completion_tokens)
+        .bind(gen_output.calls_made)
+        .bind(serde_json::to_value(&gen_output.usage_by_model)?)
         .execute(&self.pool)
         .await?;
 
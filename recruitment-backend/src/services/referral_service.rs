@@ -0,0 +1,110 @@
+use crate::error::{Error, Result};
+use crate::models::referral::{ReferralCode, ReferralSourceCount, ReferralStats};
+use crate::utils::token::generate_access_token;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const TOP_SOURCES_LIMIT: i64 = 10;
+
+#[derive(Clone)]
+pub struct ReferralService {
+    pool: PgPool,
+}
+
+impl ReferralService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Codes are matched case-insensitively, so they are stored upper-cased.
+    pub fn normalize_code(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+
+    pub async fn create_code(
+        &self,
+        code: Option<String>,
+        campaign_name: String,
+        created_by: Option<Uuid>,
+    ) -> Result<ReferralCode> {
+        let code = code
+            .map(|c| Self::normalize_code(&c))
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(|| generate_access_token(8).to_uppercase());
+
+        let created = sqlx::query_as::<_, ReferralCode>(
+            r#"
+            INSERT INTO referral_codes (code, campaign_name, created_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (code) DO NOTHING
+            RETURNING id, code, campaign_name, created_by, created_at
+            "#,
+        )
+        .bind(&code)
+        .bind(campaign_name.trim())
+        .bind(created_by)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        created.ok_or_else(|| {
+            Error::coded(
+                axum::http::StatusCode::CONFLICT,
+                "referral_code_exists",
+                format!("Referral code '{}' already exists", code),
+            )
+        })
+    }
+
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<ReferralCode>> {
+        let row = sqlx::query_as::<_, ReferralCode>(
+            "SELECT id, code, campaign_name, created_by, created_at FROM referral_codes WHERE code = $1",
+        )
+        .bind(Self::normalize_code(code))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn attach_to_candidate(
+        &self,
+        candidate_id: Uuid,
+        referral: &ReferralCode,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE candidates SET referral_code = $2, referral_source = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(candidate_id)
+        .bind(&referral.code)
+        .bind(&referral.campaign_name)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn stats(&self) -> Result<ReferralStats> {
+        let top_sources = sqlx::query_as::<_, ReferralSourceCount>(
+            r#"
+            SELECT referral_source AS source, COUNT(*) AS count
+            FROM candidates
+            WHERE referral_source IS NOT NULL AND deleted_at IS NULL
+            GROUP BY referral_source
+            ORDER BY count DESC, source
+            LIMIT $1
+            "#,
+        )
+        .bind(TOP_SOURCES_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total_referred: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM candidates WHERE referral_source IS NOT NULL AND deleted_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ReferralStats {
+            top_sources,
+            total_referred,
+        })
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::{Error, Result};
+use crate::services::koinotinav_service::KoinotinavService;
+use crate::utils::html::sanitize_to_text;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Local hour (in `report_timezone`) at which the Monday digest goes out.
+pub const WEEKLY_REPORT_HOUR: u32 = 9;
+const TOP_CANDIDATES_LIMIT: i64 = 5;
+
+/// ISO-8601 week, written as `2025-W07`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportWeek {
+    pub year: i32,
+    pub week: u32,
+}
+
+impl ReportWeek {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let invalid = || Error::BadRequest(format!("Invalid week '{}', expected YYYY-Www", raw));
+        let (year, week) = raw
+            .trim()
+            .split_once(['W', 'w'])
+            .ok_or_else(invalid)?;
+        let year: i32 = year.strip_suffix('-').unwrap_or(year).parse().map_err(|_| invalid())?;
+        let week: u32 = week.parse().map_err(|_| invalid())?;
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).ok_or_else(invalid)?;
+        Ok(Self { year, week })
+    }
+
+    pub fn containing(date: NaiveDate) -> Self {
+        let iso = date.iso_week();
+        Self {
+            year: iso.year(),
+            week: iso.week(),
+        }
+    }
+
+    /// The last complete week before `now`, as seen in `tz`.
+    pub fn previous(now: DateTime<Utc>, tz: FixedOffset) -> Self {
+        Self::containing(now.with_timezone(&tz).date_naive() - Duration::days(7))
+    }
+
+    pub fn monday(&self) -> NaiveDate {
+        NaiveDate::from_isoywd_opt(self.year, self.week, Weekday::Mon).expect("validated ISO week")
+    }
+
+    pub fn sunday(&self) -> NaiveDate {
+        self.monday() + Duration::days(6)
+    }
+
+    /// `[start, end)` in UTC: Monday 00:00 to the following Monday 00:00 in `tz`.
+    pub fn bounds(&self, tz: FixedOffset) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = local_to_utc(self.monday(), NaiveTime::MIN, tz);
+        (start, start + Duration::days(7))
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}-W{:02}", self.year, self.week)
+    }
+}
+
+fn local_to_utc(date: NaiveDate, time: NaiveTime, tz: FixedOffset) -> DateTime<Utc> {
+    tz.from_local_datetime(&date.and_time(time))
+        .single()
+        .expect("fixed offsets have no gaps or folds")
+        .with_timezone(&Utc)
+}
+
+/// First Monday `WEEKLY_REPORT_HOUR`:00 in `tz` strictly after `now`.
+pub fn next_weekly_run(now: DateTime<Utc>, tz: FixedOffset) -> DateTime<Utc> {
+    let local = now.with_timezone(&tz);
+    let days_until_monday = (7 - local.weekday().num_days_from_monday()) % 7;
+    let send_time = NaiveTime::from_hms_opt(WEEKLY_REPORT_HOUR, 0, 0).expect("valid hour");
+    let run = local_to_utc(
+        local.date_naive() + Duration::days(days_until_monday as i64),
+        send_time,
+        tz,
+    );
+    if run > now {
+        run
+    } else {
+        run + Duration::days(7)
+    }
+}
+
+pub fn pass_rate(completed: i64, passed: i64) -> Option<f64> {
+    if completed <= 0 {
+        return None;
+    }
+    Some((passed as f64 * 1000.0 / completed as f64).round() / 10.0)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VacancyApplications {
+    pub vacancy_id: i64,
+    pub title: String,
+    pub applications: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TopCandidate {
+    pub id: Uuid,
+    pub name: String,
+    pub ai_rating: i32,
+    pub vacancy_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    pub week: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub new_candidates: i64,
+    pub applications: Vec<VacancyApplications>,
+    pub tests_completed: i64,
+    pub tests_passed: i64,
+    pub pass_rate: Option<f64>,
+    pub average_ai_rating: Option<f64>,
+    pub top_candidates: Vec<TopCandidate>,
+}
+
+impl WeeklyReport {
+    pub fn empty(week: ReportWeek) -> Self {
+        Self {
+            week: week.label(),
+            period_start: week.monday(),
+            period_end: week.sunday(),
+            new_candidates: 0,
+            applications: Vec::new(),
+            tests_completed: 0,
+            tests_passed: 0,
+            pass_rate: None,
+            average_ai_rating: None,
+            top_candidates: Vec::new(),
+        }
+    }
+
+    pub fn total_applications(&self) -> i64 {
+        self.applications.iter().map(|a| a.applications).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new_candidates == 0 && self.applications.is_empty() && self.tests_completed == 0
+    }
+
+    /// Plain-text digest for Telegram (no parse_mode, so names need no escaping).
+    pub fn to_telegram_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "📊 Еженедельный отчёт по подбору");
+        let _ = writeln!(
+            text,
+            "🗓 {} — {} ({})",
+            self.period_start.format("%d.%m.%Y"),
+            self.period_end.format("%d.%m.%Y"),
+            self.week
+        );
+
+        if self.is_empty() {
+            text.push_str("\n💤 За эту неделю активности не было.");
+            return text;
+        }
+
+        let _ = writeln!(text, "\n👥 Новые кандидаты: {}", self.new_candidates);
+
+        let _ = writeln!(text, "\n💼 Отклики по вакансиям ({}):", self.total_applications());
+        if self.applications.is_empty() {
+            text.push_str("• Откликов не было\n");
+        }
+        for item in &self.applications {
+            let _ = writeln!(text, "• {} — {}", item.title, item.applications);
+        }
+
+        let _ = writeln!(text, "\n📝 Тесты:");
+        let _ = writeln!(text, "• Завершено: {}", self.tests_completed);
+        match self.pass_rate {
+            Some(rate) => {
+                let _ = writeln!(text, "• Сдано: {} ({:.1}%)", self.tests_passed, rate);
+            }
+            None => text.push_str("• Сдано: —\n"),
+        }
+
+        match self.average_ai_rating {
+            Some(avg) => {
+                let _ = writeln!(text, "\n⭐ Средний AI-рейтинг: {:.1}%", avg);
+            }
+            None => text.push_str("\n⭐ Средний AI-рейтинг: —\n"),
+        }
+
+        let _ = writeln!(text, "\n🏆 Топ-{} кандидатов:", TOP_CANDIDATES_LIMIT);
+        if self.top_candidates.is_empty() {
+            text.push_str("• Нет оценённых кандидатов\n");
+        }
+        for (idx, candidate) in self.top_candidates.iter().enumerate() {
+            let _ = writeln!(text, "{}. {} — {}%", idx + 1, candidate.name, candidate.ai_rating);
+        }
+
+        text.trim_end().to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct ReportService {
+    pool: PgPool,
+    koinotinav_service: KoinotinavService,
+}
+
+impl ReportService {
+    pub fn new(pool: PgPool, koinotinav_service: KoinotinavService) -> Self {
+        Self {
+            pool,
+            koinotinav_service,
+        }
+    }
+
+    pub async fn build_weekly(&self, week: ReportWeek, tz: FixedOffset) -> Result<WeeklyReport> {
+        let (start, end) = week.bounds(tz);
+        let mut report = WeeklyReport::empty(week);
+
+        report.new_candidates = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM candidates
+            WHERE created_at >= $1 AND created_at < $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let per_vacancy: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT vacancy_id, COUNT(*) FROM candidate_applications
+            WHERE created_at >= $1 AND created_at < $2
+            GROUP BY vacancy_id
+            ORDER BY COUNT(*) DESC, vacancy_id
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if !per_vacancy.is_empty() {
+            let titles: HashMap<i64, String> = match self.koinotinav_service.fetch_vacancies().await {
+                Ok(vacancies) => vacancies
+                    .into_iter()
+                    .map(|v| (v.id, sanitize_to_text(&v.title)))
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!("Weekly report: vacancy titles unavailable: {:?}", e);
+                    HashMap::new()
+                }
+            };
+            report.applications = per_vacancy
+                .into_iter()
+                .map(|(vacancy_id, applications)| VacancyApplications {
+                    vacancy_id,
+                    title: titles
+                        .get(&vacancy_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("Вакансия #{}", vacancy_id)),
+                    applications,
+                })
+                .collect();
+        }
+
+        let (completed, passed): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE passed = TRUE)
+            FROM test_attempts
+            WHERE completed_at >= $1 AND completed_at < $2 AND NOT is_preview
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await?;
+        report.tests_completed = completed;
+        report.tests_passed = passed;
+        report.pass_rate = pass_rate(completed, passed);
+
+        report.average_ai_rating = sqlx::query_scalar::<_, Option<f64>>(
+            r#"
+            SELECT ROUND(AVG(ai_rating)::numeric, 1)::float8 FROM candidates
+            WHERE created_at >= $1 AND created_at < $2
+              AND deleted_at IS NULL AND ai_rating IS NOT NULL
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await?;
+
+        report.top_candidates = sqlx::query_as::<_, TopCandidate>(
+            r#"
+            SELECT id, COALESCE(name, '') AS name, ai_rating, vacancy_id FROM candidates
+            WHERE created_at >= $1 AND created_at < $2
+              AND deleted_at IS NULL AND ai_rating IS NOT NULL
+            ORDER BY ai_rating DESC, created_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .bind(TOP_CANDIDATES_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    pub async fn send_to_telegram(&self, chat_id: i64, text: &str) -> Result<()> {
+        let config = crate::config::get_config();
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", config.telegram_bot_token);
+        let response = reqwest::Client::new()
+            .post(&url)
+            .json(&serde_json::json!({ "chat_id": chat_id, "text": text }))
+            .send()
+            .await
+            .map_err(|e| Error::Internal(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Internal(format!(
+                "Telegram rejected weekly report ({}): {}",
+                status, body
+            )));
+        }
+        Ok(())
+    }
+}
@@ -37,12 +37,24 @@ impl ResponseService {
                 r.test_attempt_id, r.decision, r.responded_at, r.updated_at
             FROM responses r
             JOIN candidates c ON c.id = r.candidate_id
+            WHERE c.deleted_at IS NULL
             ORDER BY r.responded_at DESC
             "#,
         )
         .fetch_all(&self.pool)
         .await?;
-        Ok(rows)
+        Ok(rows
+            .into_iter()
+            .map(|mut card| {
+                card.candidate_cv_url = card.candidate_cv_url.map(|path| {
+                    crate::utils::signed_url::signed_upload_url(
+                        &path,
+                        crate::utils::signed_url::CV_LINK_TTL,
+                    )
+                });
+                card
+            })
+            .collect())
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Option<Response>> {
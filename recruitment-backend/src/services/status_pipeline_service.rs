@@ -0,0 +1,143 @@
+use crate::error::{Error, Result};
+use axum::http::StatusCode;
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StatusDefinition {
+    pub id: &'static str,
+    pub label: &'static str,
+    #[serde(rename = "allowed_next_statuses")]
+    pub next: &'static [&'static str],
+}
+
+/// Candidate statuses in pipeline order with the manual transitions each one
+/// allows. `rejected` can be reached from every other status.
+pub const STATUS_WORKFLOW: &[StatusDefinition] = &[
+    StatusDefinition { id: "new", label: "New", next: &["reviewing", "rejected"] },
+    StatusDefinition { id: "reviewing", label: "Reviewing", next: &["test_assigned", "rejected"] },
+    StatusDefinition { id: "test_assigned", label: "Test Assigned", next: &["test_completed", "rejected"] },
+    StatusDefinition { id: "test_completed", label: "Test Completed", next: &["interview", "rejected"] },
+    StatusDefinition { id: "interview", label: "Interview", next: &["accepted", "rejected"] },
+    StatusDefinition { id: "accepted", label: "Accepted", next: &["rejected"] },
+    StatusDefinition { id: "rejected", label: "Rejected", next: &[] },
+];
+
+pub struct StatusPipelineService;
+
+impl StatusPipelineService {
+    pub fn statuses() -> impl Iterator<Item = &'static str> {
+        STATUS_WORKFLOW.iter().map(|s| s.id)
+    }
+
+    pub fn is_known(status: &str) -> bool {
+        STATUS_WORKFLOW.iter().any(|s| s.id == status)
+    }
+
+    /// How a status reads to the candidate, in Russian. Unknown statuses
+    /// fall back to "На рассмотрении" rather than leaking internal ids.
+    pub fn candidate_label_ru(status: &str) -> &'static str {
+        match status {
+            "new" => "Заявка получена",
+            "reviewing" => "На рассмотрении",
+            "test_assigned" => "Назначен тест",
+            "test_completed" => "Тест завершён, ожидайте решения",
+            "interview" => "Приглашение на собеседование",
+            "accepted" => "Вы приняты",
+            "rejected" => "Отказ",
+            _ => "На рассмотрении",
+        }
+    }
+
+    pub fn next_statuses(from: &str) -> &'static [&'static str] {
+        STATUS_WORKFLOW
+            .iter()
+            .find(|s| s.id == from)
+            .map(|s| s.next)
+            .unwrap_or(&[])
+    }
+
+    pub fn can_transition(from: &str, to: &str) -> bool {
+        from == to || Self::next_statuses(from).contains(&to)
+    }
+
+    /// Statuses with a direct manual transition to `to` (not counting `to` itself).
+    pub fn previous_statuses(to: &str) -> Vec<&'static str> {
+        STATUS_WORKFLOW
+            .iter()
+            .filter(|s| s.id != to && s.next.contains(&to))
+            .map(|s| s.id)
+            .collect()
+    }
+
+    /// True when `to` can be reached from `from` by following the graph forward.
+    /// Used by system-driven transitions, which may skip intermediate stages.
+    pub fn is_reachable(from: &str, to: &str) -> bool {
+        let mut frontier = vec![from];
+        let mut seen: Vec<&str> = Vec::new();
+        while let Some(current) = frontier.pop() {
+            if current == to {
+                return true;
+            }
+            if seen.contains(&current) {
+                continue;
+            }
+            seen.push(current);
+            frontier.extend(Self::next_statuses(current).iter().copied());
+        }
+        false
+    }
+
+    /// Rejects typos; used on its own for admin overrides.
+    pub fn validate_status(status: &str) -> Result<()> {
+        if Self::is_known(status) {
+            return Ok(());
+        }
+        let known: Vec<&str> = Self::statuses().collect();
+        Err(Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "unknown_candidate_status",
+            format!(
+                "Unknown candidate status '{}'. Expected one of: {}",
+                status,
+                known.join(", ")
+            ),
+        )
+        .with_details(json!({ "status": status, "known_statuses": known })))
+    }
+
+    pub fn validate_transition(from: &str, to: &str) -> Result<()> {
+        let allowed = Self::next_statuses(from);
+        if !Self::is_known(to) {
+            let known: Vec<&str> = Self::statuses().collect();
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unknown_candidate_status",
+                format!(
+                    "Unknown candidate status '{}'. Expected one of: {}",
+                    to,
+                    known.join(", ")
+                ),
+            )
+            .with_details(json!({
+                "status": to,
+                "known_statuses": known,
+                "allowed_next_statuses": allowed,
+            })));
+        }
+        if !Self::can_transition(from, to) {
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_status_transition",
+                format!(
+                    "Invalid status transition '{}' -> '{}'. Allowed: {}",
+                    from,
+                    to,
+                    if allowed.is_empty() { "none".to_string() } else { allowed.join(", ") }
+                ),
+            )
+            .with_details(json!({ "from": from, "to": to, "allowed_next_statuses": allowed })));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use crate::error::{Error, Result};
+use crate::models::vacancy::Vacancy;
+use crate::utils::html::sanitize_to_text;
+use axum::http::StatusCode;
+use serde_json::{json, Value as JsonValue};
+use std::time::Duration;
+
+pub const DEFAULT_TELEGRAM_API_URL: &str = "https://api.telegram.org";
+/// Telegram rejects message texts longer than this.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+pub const MAX_POST_DESCRIPTION_CHARS: usize = 3500;
+pub const APPLY_BUTTON_TEXT: &str = "Откликнуться";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Registration form with the vacancy preselected. The form works with
+/// numeric Koinoti Nav ids, so a numeric `external_id` wins over the UUID.
+pub fn registration_link(webapp_url: &str, vacancy: &Vacancy) -> String {
+    let vacancy_id = vacancy
+        .external_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| id.parse::<i64>().is_ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| vacancy.id.to_string());
+    format!(
+        "{}/candidate/register?vacancy_id={}",
+        webapp_url.trim_end_matches('/'),
+        vacancy_id
+    )
+}
+
+/// Inline keyboard with the apply button. Channels don't allow `web_app`
+/// buttons (Telegram answers BUTTON_TYPE_INVALID), so this is a plain URL
+/// button; inside Telegram it opens the same registration page.
+pub fn apply_button(link: &str) -> JsonValue {
+    json!({ "inline_keyboard": [[{ "text": APPLY_BUTTON_TEXT, "url": link }]] })
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// Splits `text` into messages of at most `TELEGRAM_MESSAGE_LIMIT` chars,
+/// breaking between lines where possible.
+pub fn split_message(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in text.split('\n') {
+        let mut line = line.to_string();
+        loop {
+            let line_len = line.chars().count();
+            let needed = if current.is_empty() {
+                line_len
+            } else {
+                current_len + 1 + line_len
+            };
+            if needed <= TELEGRAM_MESSAGE_LIMIT {
+                if !current.is_empty() {
+                    current.push('\n');
+                }
+                current.push_str(&line);
+                current_len = needed;
+                break;
+            }
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+                continue;
+            }
+            let head: String = line.chars().take(TELEGRAM_MESSAGE_LIMIT).collect();
+            line = line.chars().skip(TELEGRAM_MESSAGE_LIMIT).collect();
+            chunks.push(head);
+        }
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn salary_line(vacancy: &Vacancy) -> Option<String> {
+    if vacancy.negotiated_salary {
+        return Some("💰 По договорённости".to_string());
+    }
+    let currency = vacancy.currency.as_deref().unwrap_or("").trim();
+    let range = match (vacancy.salary_from, vacancy.salary_to) {
+        (Some(from), Some(to)) => format!("{} – {}", from.normalize(), to.normalize()),
+        (Some(from), None) => format!("от {}", from.normalize()),
+        (None, Some(to)) => format!("до {}", to.normalize()),
+        (None, None) => return None,
+    };
+    Some(format!("💰 {} {}", range, currency).trim_end().to_string())
+}
+
+/// Plain-text channel post: title, company and location, salary and the
+/// description trimmed to `MAX_POST_DESCRIPTION_CHARS`. If it still exceeds
+/// Telegram's limit it is cut with an ellipsis and a link to the full form.
+pub fn render_vacancy_post(vacancy: &Vacancy, link: &str) -> String {
+    let mut text = sanitize_to_text(&vacancy.title);
+    text.push_str(&format!(
+        "\n📍 {} · {}",
+        vacancy.location.trim(),
+        vacancy.company.trim()
+    ));
+    if let Some(kind) = vacancy
+        .employment_type
+        .as_deref()
+        .filter(|k| !k.trim().is_empty())
+    {
+        text.push_str(&format!("\n🕒 {}", kind.trim()));
+    }
+    if let Some(salary) = salary_line(vacancy) {
+        text.push('\n');
+        text.push_str(&salary);
+    }
+    let description = vacancy
+        .description
+        .as_deref()
+        .map(sanitize_to_text)
+        .unwrap_or_default();
+    if !description.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&truncate_chars(&description, MAX_POST_DESCRIPTION_CHARS));
+    }
+
+    if text.chars().count() <= TELEGRAM_MESSAGE_LIMIT {
+        return text;
+    }
+    let tail = format!("\n\nПодробнее: {}", link);
+    let room = TELEGRAM_MESSAGE_LIMIT.saturating_sub(tail.chars().count());
+    format!("{}{}", truncate_chars(&text, room), tail)
+}
+
+/// Where a vacancy was posted, so the post can later be edited or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelPost {
+    pub chat_id: String,
+    pub message_id: i64,
+}
+
+fn telegram_error(method: &str, description: String, error_code: Option<i64>) -> Error {
+    Error::coded(
+        StatusCode::BAD_GATEWAY,
+        "telegram_error",
+        format!("Telegram {} failed: {}", method, description),
+    )
+    .with_details(json!({ "method": method, "error_code": error_code, "description": description }))
+}
+
+/// True when Telegram refused a message with 403, which it does once the
+/// user has blocked the bot or deleted their account.
+pub fn is_blocked_by_user(err: &Error) -> bool {
+    match err {
+        Error::Coded {
+            code,
+            details: Some(details),
+            ..
+        } => *code == "telegram_error" && details["error_code"].as_i64() == Some(403),
+        _ => false,
+    }
+}
+
+#[derive(Clone)]
+pub struct TelegramChannelService {
+    api_base: String,
+    bot_token: String,
+    client: reqwest::Client,
+}
+
+impl TelegramChannelService {
+    pub fn new(api_base: impl Into<String>, bot_token: impl Into<String>) -> Self {
+        Self {
+            api_base: api_base.into().trim_end_matches('/').to_string(),
+            bot_token: bot_token.into(),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let config = crate::config::get_config();
+        Self::new(
+            config.telegram_api_url.clone(),
+            config.telegram_bot_token.clone(),
+        )
+    }
+
+    /// Calls a Bot API method. Transport failures and `ok: false` answers
+    /// become a 502 carrying Telegram's description.
+    async fn call(&self, method: &str, body: JsonValue) -> Result<JsonValue> {
+        let url = format!("{}/bot{}/{}", self.api_base, self.bot_token, method);
+        let resp = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| telegram_error(method, e.without_url().to_string(), None))?;
+        let status = resp.status();
+        let payload: JsonValue = resp.json().await.unwrap_or(JsonValue::Null);
+        if payload["ok"].as_bool() == Some(true) {
+            return Ok(payload["result"].clone());
+        }
+        let description = payload["description"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("HTTP {}", status));
+        Err(telegram_error(
+            method,
+            description,
+            payload["error_code"].as_i64(),
+        ))
+    }
+
+    pub async fn post(
+        &self,
+        chat_id: &str,
+        text: &str,
+        reply_markup: JsonValue,
+    ) -> Result<ChannelPost> {
+        let result = self
+            .call(
+                "sendMessage",
+                json!({ "chat_id": chat_id, "text": text, "reply_markup": reply_markup }),
+            )
+            .await?;
+        let message_id = result["message_id"].as_i64().ok_or_else(|| {
+            telegram_error("sendMessage", "response has no message_id".into(), None)
+        })?;
+        Ok(ChannelPost {
+            chat_id: chat_id.to_string(),
+            message_id,
+        })
+    }
+
+    /// Sends a plain message to a candidate's chat, inside the forum topic
+    /// `thread_id` when set.
+    pub async fn send_message(
+        &self,
+        chat_id: i64,
+        thread_id: Option<i64>,
+        text: &str,
+    ) -> Result<()> {
+        self.send_message_with_markup(chat_id, thread_id, text, None)
+            .await
+    }
+
+    /// [`send_message`](Self::send_message) with an optional inline keyboard.
+    pub async fn send_message_with_markup(
+        &self,
+        chat_id: i64,
+        thread_id: Option<i64>,
+        text: &str,
+        reply_markup: Option<JsonValue>,
+    ) -> Result<()> {
+        let mut body = json!({ "chat_id": chat_id, "text": text });
+        if let Some(thread_id) = thread_id {
+            body["message_thread_id"] = json!(thread_id);
+        }
+        if let Some(reply_markup) = reply_markup {
+            body["reply_markup"] = reply_markup;
+        }
+        self.call("sendMessage", body).await.map(|_| ())
+    }
+
+    /// Re-renders an existing post. An unchanged text is not an error.
+    pub async fn edit(
+        &self,
+        post: &ChannelPost,
+        text: &str,
+        reply_markup: JsonValue,
+    ) -> Result<()> {
+        let body = json!({
+            "chat_id": post.chat_id,
+            "message_id": post.message_id,
+            "text": text,
+            "reply_markup": reply_markup,
+        });
+        match self.call("editMessageText", body).await {
+            Err(e) if e.to_string().contains("message is not modified") => Ok(()),
+            other => other.map(|_| ()),
+        }
+    }
+
+    /// Removes a post; one that is already gone counts as removed.
+    pub async fn delete(&self, post: &ChannelPost) -> Result<()> {
+        let body = json!({ "chat_id": post.chat_id, "message_id": post.message_id });
+        match self.call("deleteMessage", body).await {
+            Err(e) if e.to_string().contains("message to delete not found") => Ok(()),
+            other => other.map(|_| ()),
+        }
+    }
+}
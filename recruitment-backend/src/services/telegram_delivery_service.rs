@@ -0,0 +1,136 @@
+use crate::error::{Error, Result};
+use crate::services::telegram_channel_service::{is_blocked_by_user, TelegramChannelService};
+use axum::http::StatusCode;
+use serde_json::{json, Value as JsonValue};
+use sqlx::PgPool;
+
+/// Consecutive 403s after which a candidate is marked `telegram_unreachable`.
+pub const UNREACHABLE_AFTER_FAILURES: i32 = 3;
+
+pub fn telegram_unreachable(telegram_id: i64) -> Error {
+    Error::coded(
+        StatusCode::CONFLICT,
+        "telegram_unreachable",
+        "The candidate has blocked the bot; messages are paused until they write to it again",
+    )
+    .with_details(json!({ "telegram_id": telegram_id }))
+}
+
+/// Sends bot messages to candidates and keeps count of 403 answers per
+/// `telegram_id`. After `UNREACHABLE_AFTER_FAILURES` in a row the candidate
+/// is flagged and further sends fail fast with `telegram_unreachable`
+/// instead of calling Telegram. The flag is cleared when the candidate
+/// writes to the bot again.
+#[derive(Clone)]
+pub struct TelegramDeliveryService {
+    pool: PgPool,
+    telegram: TelegramChannelService,
+}
+
+impl TelegramDeliveryService {
+    pub fn new(pool: PgPool, telegram: TelegramChannelService) -> Self {
+        Self { pool, telegram }
+    }
+
+    pub fn from_config(pool: PgPool) -> Self {
+        Self::new(pool, TelegramChannelService::from_config())
+    }
+
+    pub async fn is_unreachable(&self, telegram_id: i64) -> Result<bool> {
+        let unreachable: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM candidates WHERE telegram_id = $1 AND telegram_unreachable)",
+        )
+        .bind(telegram_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(unreachable)
+    }
+
+    /// Sends `text` to the candidate's chat. Bookkeeping failures are logged
+    /// and never turn a delivered message into an error.
+    pub async fn send(
+        &self,
+        chat_id: i64,
+        thread_id: Option<i64>,
+        text: &str,
+        reply_markup: Option<JsonValue>,
+    ) -> Result<()> {
+        match self.is_unreachable(chat_id).await {
+            Ok(true) => return Err(telegram_unreachable(chat_id)),
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                "Failed to check Telegram reachability of {}: {}",
+                chat_id,
+                e
+            ),
+        }
+        match self
+            .telegram
+            .send_message_with_markup(chat_id, thread_id, text, reply_markup)
+            .await
+        {
+            Ok(()) => {
+                self.record_delivered(chat_id).await;
+                Ok(())
+            }
+            Err(e) if is_blocked_by_user(&e) => {
+                if self.record_blocked(chat_id).await {
+                    tracing::warn!(
+                        "Telegram user {} blocked the bot; pausing messages to them",
+                        chat_id
+                    );
+                    Err(telegram_unreachable(chat_id))
+                } else {
+                    Err(e)
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn record_delivered(&self, telegram_id: i64) {
+        let result = sqlx::query(
+            "UPDATE candidates SET telegram_send_failures = 0 \
+             WHERE telegram_id = $1 AND telegram_send_failures > 0",
+        )
+        .bind(telegram_id)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to reset Telegram failures for {}: {}",
+                telegram_id,
+                e
+            );
+        }
+    }
+
+    /// Counts a 403 and returns whether the candidate is now unreachable.
+    async fn record_blocked(&self, telegram_id: i64) -> bool {
+        let result: std::result::Result<Vec<bool>, sqlx::Error> = sqlx::query_scalar(
+            r#"
+            UPDATE candidates
+            SET telegram_send_failures = telegram_send_failures + 1,
+                telegram_unreachable = telegram_unreachable
+                    OR telegram_send_failures + 1 >= $2
+            WHERE telegram_id = $1
+            RETURNING telegram_unreachable
+            "#,
+        )
+        .bind(telegram_id)
+        .bind(UNREACHABLE_AFTER_FAILURES)
+        .fetch_all(&self.pool)
+        .await;
+        match result {
+            Ok(flags) => flags.into_iter().any(|unreachable| unreachable),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to record Telegram failure for {}: {}",
+                    telegram_id,
+                    e
+                );
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Write as _;
+
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::models::test::Test;
+use crate::models::test_quality_report::TestQualityReport;
+use crate::services::ai_service::{AIService, TestCritique};
+
+/// Tests scoring below this are listed in the weekly Telegram digest.
+pub const LOW_QUALITY_SCORE: i32 = 60;
+
+/// A test and its latest critique, as listed in the digest.
+#[derive(Debug, Clone)]
+pub struct CritiquedTest {
+    pub test_id: Uuid,
+    pub title: String,
+    pub critique: TestCritique,
+}
+
+/// Plain-text Telegram digest of the tests scoring below `LOW_QUALITY_SCORE`,
+/// worst first; `None` when every test scored well enough.
+pub fn low_quality_digest(tests: &[CritiquedTest]) -> Option<String> {
+    let mut low: Vec<&CritiquedTest> = tests
+        .iter()
+        .filter(|t| t.critique.score < LOW_QUALITY_SCORE)
+        .collect();
+    if low.is_empty() {
+        return None;
+    }
+    low.sort_by_key(|t| t.critique.score);
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "🧪 Качество тестов: требуют доработки ({})",
+        low.len()
+    );
+    for test in low {
+        let _ = writeln!(
+            text,
+            "\n• {} — {}/100, сложность: {}",
+            test.title, test.critique.score, test.critique.estimated_difficulty
+        );
+        let _ = writeln!(text, "  {}", test.critique.diagnosis);
+        if let Some(first) = test.critique.suggested_improvements.first() {
+            let _ = writeln!(text, "  ➜ {}", first);
+        }
+    }
+    Some(text.trim_end().to_string())
+}
+
+#[derive(Clone)]
+pub struct TestQualityService {
+    pool: PgPool,
+}
+
+impl TestQualityService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Critiques the test's current questions and stores the result. The
+    /// test itself is not changed.
+    pub async fn generate(&self, ai_service: &AIService, test: &Test) -> Result<TestQualityReport> {
+        let critique = self.critique(ai_service, test).await?;
+        self.store(test.id, &critique).await
+    }
+
+    async fn critique(&self, ai_service: &AIService, test: &Test) -> Result<TestCritique> {
+        if test.test_type.as_deref() == Some("presentation") {
+            return Err(Error::BadRequest(
+                "Presentation tests have no questions to review".to_string(),
+            ));
+        }
+        if test.questions.as_array().is_none_or(|q| q.is_empty()) {
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "no_questions",
+                "The test has no questions to review",
+            ));
+        }
+        ai_service
+            .critique_test(&test.title, test.description.as_deref(), &test.questions)
+            .await
+    }
+
+    pub async fn store(&self, test_id: Uuid, critique: &TestCritique) -> Result<TestQualityReport> {
+        let report = sqlx::query_as::<_, TestQualityReport>(
+            r#"
+            INSERT INTO test_quality_reports (test_id, report)
+            VALUES ($1, $2)
+            RETURNING id, test_id, report, generated_at
+            "#,
+        )
+        .bind(test_id)
+        .bind(serde_json::to_value(critique)?)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(report)
+    }
+
+    pub async fn latest(&self, test_id: Uuid) -> Result<Option<TestQualityReport>> {
+        let report = sqlx::query_as::<_, TestQualityReport>(
+            r#"
+            SELECT id, test_id, report, generated_at
+            FROM test_quality_reports
+            WHERE test_id = $1
+            ORDER BY generated_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(test_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(report)
+    }
+
+    /// Critiques every active question-based test, one at a time. A test
+    /// the model fails on is logged and skipped.
+    pub async fn generate_weekly(&self, ai_service: &AIService) -> Result<Vec<CritiquedTest>> {
+        let tests = sqlx::query_as::<_, Test>(
+            r#"
+            SELECT * FROM tests
+            WHERE COALESCE(is_active, TRUE)
+              AND COALESCE(test_type, 'question_based') <> 'presentation'
+              AND jsonb_typeof(questions) = 'array'
+              AND jsonb_array_length(questions) > 0
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut critiqued = Vec::with_capacity(tests.len());
+        for test in tests {
+            match self.critique(ai_service, &test).await {
+                Ok(critique) => {
+                    self.store(test.id, &critique).await?;
+                    critiqued.push(CritiquedTest {
+                        test_id: test.id,
+                        title: test.title,
+                        critique,
+                    });
+                }
+                Err(e) => tracing::warn!("Quality report for test {} failed: {:?}", test.id, e),
+            }
+        }
+        Ok(critiqued)
+    }
+}
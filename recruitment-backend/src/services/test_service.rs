@@ -1,12 +1,19 @@
 use crate::error::Error;
 use crate::error::Result;
-use crate::models::question::Question;
-use crate::models::test::Test;
+use crate::models::question::{Difficulty, Question};
+use crate::models::test::{Test, TestGenerationRun, DEFAULT_MAX_GRACE_SECONDS};
+use crate::services::ai_service::{
+    parse_test_language, AIService, DifficultyDistribution, GenerationOutput, VacancyLanguage,
+};
+use crate::services::report_service::pass_rate;
+use axum::http::StatusCode;
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 #[allow(unused_imports)]
 use serde_json::Value as JsonValue;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::time::Duration;
 use uuid::Uuid;
 #[derive(Debug, serde::Serialize)]
 pub struct PaginatedTests {
@@ -39,6 +46,7 @@ impl TestService {
         &self,
         payload: crate::dto::integration_dto::CreateTestPayload,
         created_by: Uuid,
+        org_id: Uuid,
     ) -> Result<Test> {
         let questions_json = match &payload.questions {
             Some(qs) => {
@@ -50,9 +58,17 @@ impl TestService {
         
         let passing_score_decimal = Decimal::from_f64(payload.passing_score)
             .ok_or_else(|| crate::error::Error::Anyhow(anyhow::anyhow!("Invalid passing score")))?;
+        let passing_score_mcq = optional_score(payload.passing_score_mcq)?;
+        let passing_score_open = optional_score(payload.passing_score_open)?;
+
+        if let Some(prerequisite_id) = payload.prerequisite_test_id {
+            self.validate_prerequisite(None, prerequisite_id).await?;
+        }
 
         let presentation_themes_json = payload.presentation_themes.map(|t| serde_json::to_value(t).unwrap_or(serde_json::json!([])));
         let test_type = payload.test_type.unwrap_or_else(|| "question_based".to_string());
+        let reminder_hours = normalize_reminder_hours(payload.reminder_hours_before.as_deref().unwrap_or_default())?;
+        let language = parse_test_language(payload.language.as_deref())?;
 
         let test = sqlx::query_as!(
             Test,
@@ -61,9 +77,13 @@ impl TestService {
                 title, external_id, description, instructions, questions, 
                 duration_minutes, passing_score, shuffle_questions, shuffle_options, 
                 show_results_immediately, created_by, test_type, 
-                presentation_themes, presentation_extra_info
+                presentation_themes, presentation_extra_info,
+                show_honesty_declaration, declaration_text, prerequisite_test_id,
+                default_invite_expiry_hours, reminder_hours_before,
+                passing_score_mcq, passing_score_open, share_results_details, max_grace_seconds,
+                max_concurrent_takers, organization_id, language, reveal_correct_answers
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)
             RETURNING 
                 id,
                 title,
@@ -82,6 +102,18 @@ impl TestService {
                 test_type,
                 presentation_themes as "presentation_themes: JsonValue",
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at,
                 updated_at
             "#,
@@ -98,11 +130,26 @@ impl TestService {
             created_by,
             test_type,
             presentation_themes_json,
-            payload.presentation_extra_info
+            payload.presentation_extra_info,
+            payload.show_honesty_declaration.unwrap_or(true),
+            payload.declaration_text,
+            payload.prerequisite_test_id,
+            payload.default_invite_expiry_hours,
+            &reminder_hours,
+            passing_score_mcq,
+            passing_score_open,
+            payload.share_results_details.unwrap_or(false),
+            payload.max_grace_seconds.unwrap_or(DEFAULT_MAX_GRACE_SECONDS),
+            payload.max_concurrent_takers,
+            org_id,
+            language.tag(),
+            payload.reveal_correct_answers.unwrap_or(false)
         )
         .fetch_one(&self.pool)
         .await?;
 
+        self.add_owner(test.id, created_by).await?;
+
         Ok(test)
     }
 
@@ -117,6 +164,18 @@ impl TestService {
                 created_by, is_active, 
                 test_type, presentation_themes as "presentation_themes: JsonValue", 
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at, updated_at
             FROM tests
             WHERE id = $1
@@ -129,9 +188,22 @@ impl TestService {
         Ok(test)
     }
 
+    /// [`Self::get_test_by_id`] for an HR request: tests of other
+    /// organizations are `404`, like missing ones.
+    pub async fn get_test_in_org(&self, test_id: Uuid, org_id: Uuid) -> Result<Test> {
+        sqlx::query_as::<_, Test>("SELECT * FROM tests WHERE id = $1 AND organization_id = $2")
+            .bind(test_id)
+            .bind(org_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| Error::NotFound("Test not found".to_string()))
+    }
+
+    /// Tests of other organizations are `404`, like missing ones.
     pub async fn update_test(
         &self,
         test_id: Uuid,
+        org_id: Uuid,
         payload: crate::dto::integration_dto::UpdateTestPayload,
     ) -> Result<Test> {
         let questions_json = match payload.questions {
@@ -150,7 +222,16 @@ impl TestService {
             None => None,
         };
 
+        if let Some(prerequisite_id) = payload.prerequisite_test_id {
+            self.validate_prerequisite(Some(test_id), prerequisite_id).await?;
+        }
+
         let presentation_themes_json = payload.presentation_themes.map(|t| serde_json::to_value(t).unwrap_or(serde_json::json!([])));
+        let reminder_hours = payload
+            .reminder_hours_before
+            .as_deref()
+            .map(normalize_reminder_hours)
+            .transpose()?;
 
         let test = sqlx::query_as!(
             Test,
@@ -172,8 +253,19 @@ impl TestService {
                 test_type = COALESCE($13, test_type),
                 presentation_themes = COALESCE($14, presentation_themes),
                 presentation_extra_info = COALESCE($15, presentation_extra_info),
+                show_honesty_declaration = COALESCE($16, show_honesty_declaration),
+                declaration_text = COALESCE($17, declaration_text),
+                prerequisite_test_id = COALESCE($18, prerequisite_test_id),
+                default_invite_expiry_hours = COALESCE($19, default_invite_expiry_hours),
+                reminder_hours_before = COALESCE($20, reminder_hours_before),
+                passing_score_mcq = COALESCE($21, passing_score_mcq),
+                passing_score_open = COALESCE($22, passing_score_open),
+                share_results_details = COALESCE($23, share_results_details),
+                max_grace_seconds = COALESCE($24, max_grace_seconds),
+                max_concurrent_takers = COALESCE($25, max_concurrent_takers),
+                reveal_correct_answers = COALESCE($26, reveal_correct_answers),
                 updated_at = NOW()
-            WHERE id = $16
+            WHERE id = $27 AND organization_id = $28
             RETURNING
                 id, title, external_id, description, instructions, questions as "questions: JsonValue",
                 duration_minutes, passing_score as "passing_score: rust_decimal::Decimal",
@@ -181,6 +273,18 @@ impl TestService {
                 created_by, is_active, 
                 test_type, presentation_themes as "presentation_themes: JsonValue",
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at, updated_at
             "#,
             payload.title,
@@ -198,7 +302,19 @@ impl TestService {
             payload.test_type,
             presentation_themes_json,
             payload.presentation_extra_info,
-            test_id
+            payload.show_honesty_declaration,
+            payload.declaration_text,
+            payload.prerequisite_test_id,
+            payload.default_invite_expiry_hours,
+            reminder_hours.as_deref(),
+            optional_score(payload.passing_score_mcq)?,
+            optional_score(payload.passing_score_open)?,
+            payload.share_results_details,
+            payload.max_grace_seconds,
+            payload.max_concurrent_takers,
+            payload.reveal_correct_answers,
+            test_id,
+            org_id
         )
         .fetch_one(&self.pool)
         .await?;
@@ -206,14 +322,83 @@ impl TestService {
         Ok(test)
     }
 
+    /// Adds `questions` after the test's existing ones and renumbers them all.
+    pub async fn append_questions(
+        &self,
+        test_id: Uuid,
+        questions: Vec<crate::dto::integration_dto::CreateQuestion>,
+    ) -> Result<Test> {
+        let test = self.get_test_by_id(test_id).await?;
+        let existing: Vec<Question> = serde_json::from_value(test.questions)?;
+        let all: Vec<crate::dto::integration_dto::CreateQuestion> = existing
+            .into_iter()
+            .map(|q| crate::dto::integration_dto::CreateQuestion {
+                question_type: q.question_type,
+                question: q.question,
+                points: q.points,
+                difficulty: q.difficulty,
+                details: q.details,
+            })
+            .chain(questions)
+            .collect();
+
+        sqlx::query("UPDATE tests SET questions = $2, updated_at = NOW() WHERE id = $1")
+            .bind(test_id)
+            .bind(serde_json::to_value(assign_question_ids(&all))?)
+            .execute(&self.pool)
+            .await?;
+        self.get_test_by_id(test_id).await
+    }
+
+    /// Ensures the prerequisite exists and that following the chain from it
+    /// never leads back to `test_id`.
+    async fn validate_prerequisite(&self, test_id: Option<Uuid>, prerequisite_id: Uuid) -> Result<()> {
+        const MAX_CHAIN_DEPTH: usize = 32;
+
+        if Some(prerequisite_id) == test_id {
+            return Err(Error::BadRequest("A test cannot be its own prerequisite".to_string()));
+        }
+
+        let mut current = prerequisite_id;
+        for depth in 0..MAX_CHAIN_DEPTH {
+            let next: Option<Option<Uuid>> =
+                sqlx::query_scalar("SELECT prerequisite_test_id FROM tests WHERE id = $1")
+                    .bind(current)
+                    .fetch_optional(&self.pool)
+                    .await?;
+            let next = match next {
+                Some(next) => next,
+                None if depth == 0 => {
+                    return Err(Error::BadRequest("Prerequisite test not found".to_string()))
+                }
+                None => return Ok(()),
+            };
+            match next {
+                Some(next) if Some(next) == test_id => {
+                    return Err(Error::BadRequest(
+                        "Prerequisite chain would be circular".to_string(),
+                    ))
+                }
+                Some(next) => current = next,
+                None => return Ok(()),
+            }
+        }
+
+        Err(Error::BadRequest("Prerequisite chain is too deep".to_string()))
+    }
+
+    /// With `caller_user_id` only tests the caller owns are listed; pass
+    /// `None` for admins and integration callers.
     pub async fn list_tests(
         &self,
         page: i64,
         per_page: i64,
         filter: Option<TestFilter>,
+        caller_user_id: Option<Uuid>,
+        org_id: Uuid,
     ) -> Result<PaginatedTests> {
         let offset = (page - 1) * per_page;
-        let filter = filter.unwrap_or_else(|| TestFilter {
+        let filter = filter.unwrap_or(TestFilter {
             is_active: None,
             created_by: None,
             search: None,
@@ -228,10 +413,16 @@ impl TestService {
             WHERE ($1::bool IS NULL OR is_active = $1)
               AND ($2::uuid IS NULL OR created_by = $2)
               AND ($3::text IS NULL OR (title ILIKE $3 OR description ILIKE $3 OR external_id ILIKE $3))
+              AND ($4::uuid IS NULL OR EXISTS (
+                  SELECT 1 FROM test_owners o WHERE o.test_id = tests.id AND o.user_id = $4
+              ))
+              AND organization_id = $5
             "#,
             is_active_param,
             created_by_param,
-            search_param
+            search_param,
+            caller_user_id,
+            org_id
         )
         .fetch_one(&self.pool)
         .await?;
@@ -263,20 +454,38 @@ impl TestService {
                 test_type,
                 presentation_themes as "presentation_themes: JsonValue",
                 presentation_extra_info,
+                show_honesty_declaration,
+                declaration_text,
+                prerequisite_test_id,
+                default_invite_expiry_hours,
+                reminder_hours_before,
+                passing_score_mcq as "passing_score_mcq: rust_decimal::Decimal",
+                passing_score_open as "passing_score_open: rust_decimal::Decimal",
+                share_results_details,
+                max_grace_seconds,
+                max_concurrent_takers,
+                reveal_correct_answers,
+                language,
                 created_at,
                 updated_at
             FROM tests
             WHERE ($1::bool IS NULL OR is_active = $1)
               AND ($2::uuid IS NULL OR created_by = $2)
               AND ($3::text IS NULL OR (title ILIKE $3 OR description ILIKE $3 OR external_id ILIKE $3))
+              AND ($4::uuid IS NULL OR EXISTS (
+                  SELECT 1 FROM test_owners o WHERE o.test_id = tests.id AND o.user_id = $4
+              ))
+              AND organization_id = $7
             ORDER BY created_at DESC
-            LIMIT $4 OFFSET $5
+            LIMIT $5 OFFSET $6
             "#,
             is_active_param,
             created_by_param,
             search_param,
+            caller_user_id,
             per_page,
-            offset
+            offset,
+            org_id
         )
         .fetch_all(&self.pool)
         .await?;
@@ -290,17 +499,756 @@ impl TestService {
         })
     }
 
-    pub async fn delete_test(&self, test_id: Uuid) -> Result<bool> {
-        let result = sqlx::query!("DELETE FROM tests WHERE id = $1", test_id)
-            .execute(&self.pool)
+    pub async fn list_owners(&self, test_id: Uuid) -> Result<Vec<Uuid>> {
+        let owners = sqlx::query_scalar!(
+            "SELECT user_id FROM test_owners WHERE test_id = $1 ORDER BY created_at, user_id",
+            test_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(owners)
+    }
+
+    pub async fn is_owner(&self, test_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let owned = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM test_owners WHERE test_id = $1 AND user_id = $2) as "owned!""#,
+            test_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(owned)
+    }
+
+    /// Grants `user_id` access to the test. Adding an existing owner is a no-op.
+    pub async fn add_owner(&self, test_id: Uuid, user_id: Uuid) -> Result<()> {
+        let user_exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM users WHERE id = $1) as "exists!""#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if !user_exists {
+            return Err(Error::coded(
+                StatusCode::NOT_FOUND,
+                "user_not_found",
+                "User not found",
+            ));
+        }
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO test_owners (test_id, user_id)
+            SELECT id, $2 FROM tests WHERE id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            test_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        if inserted.rows_affected() == 0 && !self.is_owner(test_id, user_id).await? {
+            return Err(Error::NotFound("Test not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn remove_owner(&self, test_id: Uuid, user_id: Uuid) -> Result<()> {
+        let removed = sqlx::query!(
+            "DELETE FROM test_owners WHERE test_id = $1 AND user_id = $2",
+            test_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        if removed.rows_affected() == 0 {
+            return Err(Error::coded(
+                StatusCode::NOT_FOUND,
+                "test_owner_not_found",
+                "User is not an owner of this test",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copies `test_id` with `num_new_questions` freshly generated questions
+    /// as "<title> (Variant N)". Generation runs first; numbering and the
+    /// insert share one transaction so concurrent copies get distinct N.
+    /// The copy keeps the original's owners.
+    pub async fn duplicate_test(
+        &self,
+        ai_service: &AIService,
+        test_id: Uuid,
+        org_id: Uuid,
+        num_new_questions: usize,
+        profession: Option<String>,
+    ) -> Result<Test> {
+        let original = self.get_test_in_org(test_id, org_id).await?;
+        if original.test_type.as_deref() == Some("presentation") {
+            return Err(Error::BadRequest(
+                "Presentation tests have no questions to replace".to_string(),
+            ));
+        }
+
+        let profession = profession
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| profession_from_title(&original.title));
+        let generated = ai_service
+            .generate_test(
+                &profession,
+                &[],
+                num_new_questions,
+                &DifficultyDistribution::default(),
+                VacancyLanguage::parse(&original.language).unwrap_or_default(),
+            )
+            .await?;
+        if generated.questions.is_empty() {
+            return Err(Error::Internal(format!(
+                "AI generation returned no questions for '{}'",
+                profession
+            )));
+        }
+        let questions = serde_json::to_value(renumber_questions(generated.questions))?;
+
+        let base = variant_base_title(&original.title).to_string();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(&base)
+            .execute(&mut *tx)
             .await?;
+        let titles: Vec<String> =
+            sqlx::query_scalar("SELECT title FROM tests WHERE left(title, char_length($1)) = $1")
+                .bind(&base)
+                .fetch_all(&mut *tx)
+                .await?;
+        let title = format!("{} (Variant {})", base, next_variant_number(&base, &titles));
+
+        let test = sqlx::query_as::<_, Test>(
+            r#"
+            INSERT INTO tests (
+                title, external_id, description, instructions, questions,
+                duration_minutes, passing_score, max_attempts, shuffle_questions, shuffle_options,
+                show_results_immediately, created_by, is_active, test_type,
+                presentation_themes, presentation_extra_info,
+                show_honesty_declaration, declaration_text, prerequisite_test_id,
+                default_invite_expiry_hours, reminder_hours_before,
+                passing_score_mcq, passing_score_open, share_results_details, max_grace_seconds,
+                max_concurrent_takers, organization_id, language, reveal_correct_answers
+            )
+            SELECT
+                $2, NULL, description, instructions, $3,
+                duration_minutes, passing_score, max_attempts, shuffle_questions, shuffle_options,
+                show_results_immediately, created_by, is_active, test_type,
+                presentation_themes, presentation_extra_info,
+                show_honesty_declaration, declaration_text, prerequisite_test_id,
+                default_invite_expiry_hours, reminder_hours_before,
+                passing_score_mcq, passing_score_open, share_results_details, max_grace_seconds,
+                max_concurrent_takers, organization_id, language, reveal_correct_answers
+            FROM tests WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(test_id)
+        .bind(&title)
+        .bind(&questions)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO test_owners (test_id, user_id) SELECT $2, user_id FROM test_owners WHERE test_id = $1",
+        )
+        .bind(test_id)
+        .bind(test.id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(test)
+    }
+
+    /// `false` when `org_id` has no such test.
+    pub async fn delete_test(&self, test_id: Uuid, org_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM tests WHERE id = $1 AND organization_id = $2",
+            test_id,
+            org_id
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Stores how `test_id` was generated. `delivered` is the number of
+    /// questions that ended up in the test; `topup_rounds` counts refills
+    /// after the model returned too few.
+    pub async fn record_generation_run(
+        &self,
+        test_id: Uuid,
+        ai_job_id: Option<Uuid>,
+        output: &GenerationOutput,
+        requested: usize,
+        delivered: usize,
+        topup_rounds: i32,
+    ) -> Result<TestGenerationRun> {
+        let run = sqlx::query_as::<_, TestGenerationRun>(
+            r#"
+            INSERT INTO test_generation_runs
+                (test_id, ai_job_id, blueprint, logs, models, requested_questions, returned_questions,
+                 duplicates_removed, topup_rounds, delivered_questions, prompt_tokens, completion_tokens)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+            "#,
+        )
+        .bind(test_id)
+        .bind(ai_job_id)
+        .bind(&output.blueprint)
+        .bind(serde_json::to_value(&output.logs)?)
+        .bind(output.models())
+        .bind(requested as i32)
+        .bind(output.returned_questions as i32)
+        .bind(output.duplicates_removed as i32)
+        .bind(topup_rounds)
+        .bind(delivered as i32)
+        .bind(output.total_prompt_tokens)
+        .bind(output.total_completion_tokens)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(run)
+    }
+
+    /// Generation runs for a test, newest first. 404 for an unknown test or
+    /// one of another organization.
+    pub async fn generation_log(&self, test_id: Uuid, org_id: Uuid) -> Result<Vec<TestGenerationRun>> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM tests WHERE id = $1 AND organization_id = $2)",
+        )
+        .bind(test_id)
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if !exists {
+            return Err(Error::NotFound("Test not found".into()));
+        }
+        let runs = sqlx::query_as::<_, TestGenerationRun>(
+            "SELECT * FROM test_generation_runs WHERE test_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(test_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
+    /// Completed and passed attempts since the questions were last adjusted;
+    /// older attempts were taken against questions that no longer exist.
+    async fn pass_counts(&self, test_id: Uuid) -> Result<(i64, i64)> {
+        let counts = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE a.passed)
+            FROM test_attempts a
+            JOIN tests t ON t.id = a.test_id
+            WHERE a.test_id = $1
+              AND a.status = 'completed'
+              AND NOT a.is_preview
+              AND (t.difficulty_adjusted_at IS NULL OR a.completed_at >= t.difficulty_adjusted_at)
+            "#,
+        )
+        .bind(test_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Share of completed attempts that passed, in percent.
+    pub async fn get_pass_rate(&self, test_id: Uuid) -> Result<f64> {
+        let (completed, passed) = self.pass_counts(test_id).await?;
+        pass_rate(completed, passed).ok_or_else(|| {
+            Error::coded(
+                StatusCode::CONFLICT,
+                "no_completed_attempts",
+                "The test has no completed attempts yet",
+            )
+        })
+    }
+
+    pub async fn get_difficulty_flag(&self, test_id: Uuid) -> Result<Option<DifficultyFlag>> {
+        let flag: Option<String> =
+            sqlx::query_scalar("SELECT difficulty_flag FROM tests WHERE id = $1")
+                .bind(test_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(flag.as_deref().and_then(DifficultyFlag::parse))
+    }
+
+    /// Recomputes and stores the flag of one test. Tests with fewer than
+    /// `MIN_ATTEMPTS_FOR_DIFFICULTY_FLAG` completed attempts are unflagged.
+    pub async fn refresh_difficulty_flag(&self, test_id: Uuid) -> Result<Option<DifficultyFlag>> {
+        let (completed, passed) = self.pass_counts(test_id).await?;
+        let flag = if completed >= MIN_ATTEMPTS_FOR_DIFFICULTY_FLAG {
+            pass_rate(completed, passed).and_then(difficulty_flag)
+        } else {
+            None
+        };
+        sqlx::query("UPDATE tests SET difficulty_flag = $2 WHERE id = $1")
+            .bind(test_id)
+            .bind(flag.map(|f| f.as_str()))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(flag)
+    }
+
+    /// Daily pass over every question-based test. Returns how many are flagged.
+    pub async fn refresh_difficulty_flags(&self) -> Result<usize> {
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM tests WHERE COALESCE(test_type, 'question_based') <> 'presentation'",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut flagged = 0;
+        for id in ids {
+            if self.refresh_difficulty_flag(id).await?.is_some() {
+                flagged += 1;
+            }
+        }
+        Ok(flagged)
+    }
+
+    /// Per-question correctness over the same attempts as `get_pass_rate`.
+    /// `answer_logs` only keeps raw saves, so correctness is read from the
+    /// graded answers of completed attempts.
+    pub async fn question_answer_rates(&self, test_id: Uuid) -> Result<Vec<QuestionAnswerRate>> {
+        let rates = sqlx::query_as::<_, QuestionAnswerRate>(
+            r#"
+            SELECT
+                (ga->>'question_id')::int AS question_id,
+                COUNT(*) AS answered,
+                COUNT(*) FILTER (WHERE COALESCE((ga->>'is_correct')::boolean, false)) AS correct
+            FROM test_attempts a
+            JOIN tests t ON t.id = a.test_id
+            CROSS JOIN LATERAL jsonb_array_elements(
+                CASE WHEN jsonb_typeof(a.graded_answers) = 'array' THEN a.graded_answers ELSE '[]'::jsonb END
+            ) ga
+            WHERE a.test_id = $1
+              AND a.status = 'completed'
+              AND NOT a.is_preview
+              AND (t.difficulty_adjusted_at IS NULL OR a.completed_at >= t.difficulty_adjusted_at)
+              AND ga ? 'question_id'
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(test_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    /// Generates replacements for the questions everyone (too easy) or
+    /// no one (too hard) answered correctly and stores them as the pending
+    /// proposal. Nothing changes on the test until `apply_difficulty_adjustment`.
+    pub async fn propose_difficulty_adjustment(
+        &self,
+        ai_service: &AIService,
+        test_id: Uuid,
+    ) -> Result<DifficultyAdjustment> {
+        let test = self.get_test_by_id(test_id).await?;
+        if test.test_type.as_deref() == Some("presentation") {
+            return Err(Error::BadRequest(
+                "Presentation tests have no questions to replace".to_string(),
+            ));
+        }
+        let flag = self.get_difficulty_flag(test_id).await?.ok_or_else(|| {
+            Error::coded(
+                StatusCode::CONFLICT,
+                "difficulty_not_flagged",
+                "The test is not flagged as too easy or too hard",
+            )
+        })?;
+        let pass_rate = self.get_pass_rate(test_id).await?;
+
+        let questions: Vec<Question> = serde_json::from_value(test.questions.clone())?;
+        let rates = self.question_answer_rates(test_id).await?;
+        let selected = questions_to_replace(flag, &rates, MAX_AUTO_ADJUST_QUESTIONS);
+        if selected.is_empty() {
+            return Err(Error::coded(
+                StatusCode::CONFLICT,
+                "no_questions_to_adjust",
+                "No question was answered correctly by everyone or by no one",
+            )
+            .with_details(serde_json::json!({ "difficulty_flag": flag.as_str() })));
+        }
+
+        let profession = profession_from_title(&test.title);
+        let generated = ai_service
+            .generate_test(
+                &profession,
+                &[],
+                selected.len(),
+                &DifficultyDistribution::only(flag.target_difficulty()),
+                VacancyLanguage::parse(&test.language).unwrap_or_default(),
+            )
+            .await?;
+        if generated.questions.is_empty() {
+            return Err(Error::Internal(format!(
+                "AI generation returned no questions for '{}'",
+                profession
+            )));
+        }
+
+        let replacements: Vec<QuestionReplacement> = selected
+            .iter()
+            .zip(generated.questions)
+            .filter_map(|(rate, generated)| {
+                let previous = questions.iter().find(|q| q.id == rate.question_id)?.clone();
+                Some(QuestionReplacement {
+                    question_id: previous.id,
+                    answered: rate.answered,
+                    correct: rate.correct,
+                    replacement: Question {
+                        id: previous.id,
+                        points: previous.points,
+                        ..generated
+                    },
+                    previous,
+                })
+            })
+            .collect();
+
+        let adjustment = DifficultyAdjustment {
+            test_id,
+            difficulty_flag: flag,
+            pass_rate,
+            replacements,
+            proposed_at: chrono::Utc::now(),
+        };
+        sqlx::query("UPDATE tests SET difficulty_proposal = $2 WHERE id = $1")
+            .bind(test_id)
+            .bind(serde_json::to_value(&adjustment)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(adjustment)
+    }
+
+    /// Swaps in the stored proposal. Fails if there is none or if any
+    /// question it replaces was edited after the proposal was made.
+    pub async fn apply_difficulty_adjustment(&self, test_id: Uuid) -> Result<DifficultyAdjustment> {
+        let mut tx = self.pool.begin().await?;
+        let row: Option<(JsonValue, Option<JsonValue>)> = sqlx::query_as(
+            "SELECT questions, difficulty_proposal FROM tests WHERE id = $1 FOR UPDATE",
+        )
+        .bind(test_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (questions, proposal) = row.ok_or_else(|| Error::NotFound("Test not found".to_string()))?;
+        let adjustment: DifficultyAdjustment = match proposal {
+            Some(p) => serde_json::from_value(p)?,
+            None => {
+                return Err(Error::coded(
+                    StatusCode::CONFLICT,
+                    "adjustment_not_previewed",
+                    "Run auto-adjust with dry_run first and review the proposal",
+                ))
+            }
+        };
+
+        let questions: Vec<Question> = serde_json::from_value(questions)?;
+        let questions = apply_replacements(questions, &adjustment.replacements).ok_or_else(|| {
+            Error::coded(
+                StatusCode::CONFLICT,
+                "adjustment_outdated",
+                "The test's questions changed after the proposal; run a new dry run",
+            )
+        })?;
+
+        sqlx::query(
+            r#"
+            UPDATE tests
+            SET questions = $2, difficulty_flag = NULL, difficulty_proposal = NULL,
+                difficulty_adjusted_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(test_id)
+        .bind(serde_json::to_value(&questions)?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(adjustment)
+    }
+}
+
+/// Reminder offsets sorted from furthest to closest to expiry, without
+/// duplicates. Offsets must be positive hours.
+/// Converts an optional 0–100 score from a payload for storage.
+fn optional_score(score: Option<f64>) -> Result<Option<Decimal>> {
+    score
+        .map(|s| {
+            Decimal::from_f64(s)
+                .ok_or_else(|| Error::Anyhow(anyhow::anyhow!("Invalid passing score")))
+        })
+        .transpose()
+}
+
+pub fn normalize_reminder_hours(hours: &[i32]) -> Result<Vec<i32>> {
+    if let Some(bad) = hours.iter().find(|h| **h <= 0) {
+        return Err(Error::BadRequest(format!(
+            "Reminder offsets must be positive hours, got {}",
+            bad
+        )));
+    }
+    let mut hours = hours.to_vec();
+    hours.sort_unstable_by(|a, b| b.cmp(a));
+    hours.dedup();
+    Ok(hours)
+}
+
+const VARIANT_PREFIX: &str = " (Variant ";
+
+/// `title` without a trailing " (Variant N)", so variants of a variant are
+/// numbered against the same original.
+pub fn variant_base_title(title: &str) -> &str {
+    title
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(VARIANT_PREFIX))
+        .filter(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        .map(|(base, _)| base)
+        .unwrap_or(title)
+}
+
+/// One past the highest "<base> (Variant N)" among `titles`.
+pub fn next_variant_number(base: &str, titles: &[String]) -> u32 {
+    titles
+        .iter()
+        .filter_map(|t| t.strip_prefix(base)?.strip_prefix(VARIANT_PREFIX)?.strip_suffix(')'))
+        .filter_map(|n| n.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+/// Profession for question generation, recovered from titles such as
+/// "AI Backend Developer Test" or "Backend Developer Assessment".
+pub fn profession_from_title(title: &str) -> String {
+    let base = variant_base_title(title).trim();
+    let stripped = base.strip_prefix("AI ").unwrap_or(base);
+    let stripped = stripped
+        .strip_suffix(" Test")
+        .or_else(|| stripped.strip_suffix(" Assessment"))
+        .unwrap_or(stripped)
+        .trim();
+    if stripped.is_empty() {
+        base.to_string()
+    } else {
+        stripped.to_string()
+    }
+}
+
+pub const TOO_EASY_PASS_RATE: f64 = 90.0;
+pub const TOO_HARD_PASS_RATE: f64 = 30.0;
+/// Below this many completed attempts a pass rate says little, so no flag is set.
+pub const MIN_ATTEMPTS_FOR_DIFFICULTY_FLAG: i64 = 5;
+/// Upper bound on questions replaced by one auto-adjust run.
+pub const MAX_AUTO_ADJUST_QUESTIONS: usize = 5;
+pub const DIFFICULTY_REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DifficultyFlag {
+    TooEasy,
+    TooHard,
+}
+
+impl DifficultyFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DifficultyFlag::TooEasy => "too_easy",
+            DifficultyFlag::TooHard => "too_hard",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "too_easy" => Some(DifficultyFlag::TooEasy),
+            "too_hard" => Some(DifficultyFlag::TooHard),
+            _ => None,
+        }
+    }
+
+    /// Difficulty of the questions generated to replace the outliers.
+    pub fn target_difficulty(&self) -> Difficulty {
+        match self {
+            DifficultyFlag::TooEasy => Difficulty::Hard,
+            DifficultyFlag::TooHard => Difficulty::Easy,
+        }
+    }
+}
+
+/// `too_easy` above `TOO_EASY_PASS_RATE`, `too_hard` below `TOO_HARD_PASS_RATE`.
+pub fn difficulty_flag(pass_rate: f64) -> Option<DifficultyFlag> {
+    if pass_rate > TOO_EASY_PASS_RATE {
+        Some(DifficultyFlag::TooEasy)
+    } else if pass_rate < TOO_HARD_PASS_RATE {
+        Some(DifficultyFlag::TooHard)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuestionAnswerRate {
+    pub question_id: i32,
+    pub answered: i64,
+    pub correct: i64,
+}
+
+/// Questions answered correctly by everyone (`too_easy`) or by no one
+/// (`too_hard`), most-answered first, at most `max`.
+pub fn questions_to_replace(
+    flag: DifficultyFlag,
+    rates: &[QuestionAnswerRate],
+    max: usize,
+) -> Vec<QuestionAnswerRate> {
+    let mut selected: Vec<QuestionAnswerRate> = rates
+        .iter()
+        .filter(|r| r.answered > 0)
+        .filter(|r| match flag {
+            DifficultyFlag::TooEasy => r.correct == r.answered,
+            DifficultyFlag::TooHard => r.correct == 0,
+        })
+        .cloned()
+        .collect();
+    selected.sort_by(|a, b| b.answered.cmp(&a.answered).then(a.question_id.cmp(&b.question_id)));
+    selected.truncate(max);
+    selected
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionReplacement {
+    pub question_id: i32,
+    pub answered: i64,
+    pub correct: i64,
+    pub previous: Question,
+    pub replacement: Question,
+}
+
+/// Proposal returned by a dry run and kept on the test until applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DifficultyAdjustment {
+    pub test_id: Uuid,
+    pub difficulty_flag: DifficultyFlag,
+    pub pass_rate: f64,
+    pub replacements: Vec<QuestionReplacement>,
+    pub proposed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Puts each replacement in place of the question with the same id. `None`
+/// if a replaced question is gone or its text no longer matches `previous`.
+pub fn apply_replacements(
+    mut questions: Vec<Question>,
+    replacements: &[QuestionReplacement],
+) -> Option<Vec<Question>> {
+    for r in replacements {
+        let slot = questions.iter_mut().find(|q| q.id == r.question_id)?;
+        if slot.question != r.previous.question {
+            return None;
+        }
+        *slot = r.replacement.clone();
+    }
+    Some(questions)
+}
+
+/// Syntax of a test spec uploaded to `POST /api/integration/tests/import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestSpecFormat {
+    Json,
+    Yaml,
+}
+
+impl TestSpecFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestSpecFormat::Json => "json",
+            TestSpecFormat::Yaml => "yaml",
+        }
+    }
+
+    /// From the file extension, falling back to the part's `Content-Type`.
+    pub fn detect(filename: Option<&str>, content_type: Option<&str>) -> Option<Self> {
+        let ext = filename
+            .and_then(|f| f.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("json") => return Some(TestSpecFormat::Json),
+            Some("yaml" | "yml") => return Some(TestSpecFormat::Yaml),
+            _ => {}
+        }
+        let mime = content_type?.split(';').next()?.trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "application/json" | "text/json" => Some(TestSpecFormat::Json),
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Some(TestSpecFormat::Yaml)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses an uploaded spec into the same payload `POST /api/integration/tests`
+/// takes. Syntax and shape errors are 422 `invalid_test_spec` with the
+/// position in `details`; field validation is left to the caller.
+pub fn parse_test_spec(
+    data: &[u8],
+    format: TestSpecFormat,
+) -> Result<crate::dto::integration_dto::CreateTestPayload> {
+    let (message, line, column) = match format {
+        TestSpecFormat::Json => match serde_json::from_slice(data) {
+            Ok(payload) => return Ok(payload),
+            Err(e) => (e.to_string(), Some(e.line()), Some(e.column())),
+        },
+        TestSpecFormat::Yaml => match serde_yaml::from_slice(data) {
+            Ok(payload) => return Ok(payload),
+            Err(e) => {
+                let location = e.location();
+                (
+                    e.to_string(),
+                    location.as_ref().map(|l| l.line()),
+                    location.as_ref().map(|l| l.column()),
+                )
+            }
+        },
+    };
+    Err(Error::coded(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "invalid_test_spec",
+        format!("Test spec is not valid {}: {}", format.as_str(), message),
+    )
+    .with_details(serde_json::json!({
+        "format": format.as_str(),
+        "line": line,
+        "column": column,
+    })))
+}
+
+fn renumber_questions(questions: Vec<Question>) -> Vec<Question> {
+    questions
+        .into_iter()
+        .enumerate()
+        .map(|(idx, q)| Question {
+            id: (idx as i32) + 1,
+            ..q
+        })
+        .collect()
 }
 
 fn assign_question_ids(
-    questions: &Vec<crate::dto::integration_dto::CreateQuestion>,
+    questions: &[crate::dto::integration_dto::CreateQuestion],
 ) -> Vec<Question> {
     questions
         .iter()
@@ -310,6 +1258,7 @@ fn assign_question_ids(
             question_type: q.question_type.clone(),
             question: q.question.clone(),
             points: q.points,
+            difficulty: q.difficulty,
             details: q.details.clone(),
         })
         .collect()
@@ -318,6 +1267,7 @@ fn assign_question_ids(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::organization::DEFAULT_ORGANIZATION_ID;
     use dotenvy::dotenv;
     use sqlx::postgres::PgPoolOptions;
     use std::env;
@@ -374,8 +1324,21 @@ mod tests {
                     test_type: Some("question_based".to_string()),
                     presentation_themes: None,
                     presentation_extra_info: None,
+                    show_honesty_declaration: None,
+                    declaration_text: None,
+                    prerequisite_test_id: None,
+                    default_invite_expiry_hours: None,
+                    reminder_hours_before: None,
+                    passing_score_mcq: None,
+                    passing_score_open: None,
+                    share_results_details: None,
+                    max_grace_seconds: None,
+                    max_concurrent_takers: None,
+                    language: None,
+                    reveal_correct_answers: None,
                 },
                 user_id,
+                DEFAULT_ORGANIZATION_ID,
             )
             .await
             .unwrap();
@@ -396,13 +1359,29 @@ mod tests {
                     test_type: Some("question_based".to_string()),
                     presentation_themes: None,
                     presentation_extra_info: None,
+                    show_honesty_declaration: None,
+                    declaration_text: None,
+                    prerequisite_test_id: None,
+                    default_invite_expiry_hours: None,
+                    reminder_hours_before: None,
+                    passing_score_mcq: None,
+                    passing_score_open: None,
+                    share_results_details: None,
+                    max_grace_seconds: None,
+                    max_concurrent_takers: None,
+                    language: None,
+                    reveal_correct_answers: None,
                 },
                 user_id,
+                DEFAULT_ORGANIZATION_ID,
             )
             .await
             .unwrap();
 
-        let result = service.list_tests(1, 10, None).await.unwrap();
+        let result = service
+            .list_tests(1, 10, None, None, DEFAULT_ORGANIZATION_ID)
+            .await
+            .unwrap();
         assert!(result.tests.len() >= 2);
         assert!(result.total >= 2);
 
@@ -415,6 +1394,8 @@ mod tests {
                     created_by: None,
                     search: None,
                 }),
+                None,
+                DEFAULT_ORGANIZATION_ID,
             )
             .await
             .unwrap();
@@ -430,6 +1411,8 @@ mod tests {
                     created_by: None,
                     search: Some("Test 1".to_string()),
                 }),
+                None,
+                DEFAULT_ORGANIZATION_ID,
             )
             .await
             .unwrap();
@@ -437,7 +1420,7 @@ mod tests {
         assert_eq!(result.tests[0].title, "Test 1");
         assert_eq!(result.total, 1);
 
-        service.delete_test(test1.id).await.unwrap();
-        service.delete_test(test2.id).await.unwrap();
+        service.delete_test(test1.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+        service.delete_test(test2.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
     }
 }
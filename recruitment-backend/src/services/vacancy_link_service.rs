@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::dto::vacancy_dto::UpdateVacancyLinksPayload;
+use crate::error::{Error, Result};
+use crate::models::vacancy_link::{CanonicalVacancy, VacancyLink};
+
+/// Picks the link `raw` refers to. An internal UUID wins over a Koinoti Nav
+/// id, which wins over a 1F id, so a numeric id shared by both platforms
+/// resolves to the Koinoti Nav vacancy.
+pub fn match_link<'a>(links: &'a [VacancyLink], raw: &str) -> Option<&'a VacancyLink> {
+    let raw = raw.trim();
+    if let Ok(id) = Uuid::parse_str(raw) {
+        if let Some(link) = links.iter().find(|l| l.vacancy_id == id) {
+            return Some(link);
+        }
+    }
+    if let Ok(id) = raw.parse::<i64>() {
+        if let Some(link) = links.iter().find(|l| l.koinotinav_id == Some(id)) {
+            return Some(link);
+        }
+    }
+    links.iter().find(|l| l.onef_id.as_deref() == Some(raw))
+}
+
+#[derive(Clone)]
+pub struct VacancyLinkService {
+    pool: PgPool,
+}
+
+impl VacancyLinkService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The link for an internal UUID, a Koinoti Nav id or a 1F id.
+    pub async fn resolve(&self, raw: &str) -> Result<Option<VacancyLink>> {
+        let raw = raw.trim();
+        let candidates = sqlx::query_as::<_, VacancyLink>(
+            r#"
+            SELECT * FROM vacancy_links
+            WHERE vacancy_id = $1 OR koinotinav_id = $2 OR onef_id = $3
+            "#,
+        )
+        .bind(Uuid::parse_str(raw).ok())
+        .bind(raw.parse::<i64>().ok())
+        .bind(raw)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(match_link(&candidates, raw).cloned())
+    }
+
+    /// Koinoti Nav id of the vacancy `raw` refers to, if it has one.
+    pub async fn koinotinav_id_for(&self, raw: &str) -> Result<Option<i64>> {
+        Ok(self.resolve(raw).await?.and_then(|link| link.koinotinav_id))
+    }
+
+    /// Applies `payload` to the vacancy's links, creating the row if needed.
+    pub async fn set_links(
+        &self,
+        vacancy_id: Uuid,
+        payload: UpdateVacancyLinksPayload,
+    ) -> Result<VacancyLink> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM vacancies WHERE id = $1)")
+                .bind(vacancy_id)
+                .fetch_one(&self.pool)
+                .await?;
+        if !exists {
+            return Err(Error::NotFound("Vacancy not found".into()));
+        }
+
+        let current =
+            sqlx::query_as::<_, VacancyLink>("SELECT * FROM vacancy_links WHERE vacancy_id = $1")
+                .bind(vacancy_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let koinotinav_id = match payload.koinotinav_id {
+            Some(id) => id,
+            None => current.as_ref().and_then(|l| l.koinotinav_id),
+        };
+        let onef_id = match payload.onef_id {
+            Some(id) => id.map(|id| id.trim().to_string()),
+            None => current.and_then(|l| l.onef_id),
+        };
+        if koinotinav_id.is_some_and(|id| id <= 0) {
+            return Err(Error::BadRequest(
+                "koinotinav_id must be a positive number".into(),
+            ));
+        }
+        if onef_id.as_deref().is_some_and(str::is_empty) {
+            return Err(Error::BadRequest("onef_id must not be empty".into()));
+        }
+
+        let taken_by: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT vacancy_id FROM vacancy_links
+            WHERE vacancy_id <> $1 AND (koinotinav_id = $2 OR onef_id = $3)
+            LIMIT 1
+            "#,
+        )
+        .bind(vacancy_id)
+        .bind(koinotinav_id)
+        .bind(onef_id.as_deref())
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(other) = taken_by {
+            return Err(Error::coded(
+                StatusCode::CONFLICT,
+                "vacancy_link_conflict",
+                "External id is already linked to another vacancy",
+            )
+            .with_details(json!({ "vacancy_id": other })));
+        }
+
+        let link = sqlx::query_as::<_, VacancyLink>(
+            r#"
+            INSERT INTO vacancy_links (vacancy_id, koinotinav_id, onef_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (vacancy_id) DO UPDATE
+            SET koinotinav_id = EXCLUDED.koinotinav_id,
+                onef_id = EXCLUDED.onef_id,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(vacancy_id)
+        .bind(koinotinav_id)
+        .bind(onef_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(link)
+    }
+
+    /// Records the Koinoti Nav id of a vacancy just published there.
+    pub async fn link_koinotinav(
+        &self,
+        vacancy_id: Uuid,
+        koinotinav_id: i64,
+    ) -> Result<VacancyLink> {
+        self.set_links(
+            vacancy_id,
+            UpdateVacancyLinksPayload {
+                koinotinav_id: Some(Some(koinotinav_id)),
+                onef_id: None,
+            },
+        )
+        .await
+    }
+
+    /// Forgets a Koinoti Nav vacancy that was deleted there.
+    pub async fn unlink_koinotinav(&self, koinotinav_id: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE vacancy_links SET koinotinav_id = NULL, updated_at = NOW() WHERE koinotinav_id = $1",
+        )
+        .bind(koinotinav_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Internal vacancy for every linked Koinoti Nav id.
+    pub async fn canonical_by_koinotinav_id(&self) -> Result<HashMap<i64, CanonicalVacancy>> {
+        let rows = sqlx::query_as::<_, (i64, Uuid, String)>(
+            r#"
+            SELECT l.koinotinav_id, v.id, v.title
+            FROM vacancy_links l
+            JOIN vacancies v ON v.id = l.vacancy_id
+            WHERE l.koinotinav_id IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(koinotinav_id, id, title)| (koinotinav_id, CanonicalVacancy { id, title }))
+            .collect())
+    }
+
+    pub async fn canonical_for_koinotinav(
+        &self,
+        koinotinav_id: i64,
+    ) -> Result<Option<CanonicalVacancy>> {
+        let vacancy = sqlx::query_as::<_, CanonicalVacancy>(
+            r#"
+            SELECT v.id, v.title
+            FROM vacancy_links l
+            JOIN vacancies v ON v.id = l.vacancy_id
+            WHERE l.koinotinav_id = $1
+            "#,
+        )
+        .bind(koinotinav_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(vacancy)
+    }
+}
@@ -1,7 +1,12 @@
 use crate::dto::vacancy_dto::{CreateVacancyPayload, UpdateVacancyPayload, VacancyListQuery};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::models::vacancy::Vacancy;
+use crate::services::report_service::pass_rate;
+use crate::services::telegram_channel_service::ChannelPost;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
 use sqlx::{postgres::PgQueryResult, PgPool};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -17,12 +22,93 @@ pub struct VacancyList {
     pub total_pages: i64,
 }
 
+/// `:id` of the analytics endpoint: an internal vacancy or a Koinoti Nav id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VacancyRef {
+    Internal(Uuid),
+    External(i64),
+}
+
+impl VacancyRef {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if let Ok(id) = Uuid::parse_str(raw) {
+            return Ok(Self::Internal(id));
+        }
+        raw.parse::<i64>().map(Self::External).map_err(|_| {
+            Error::BadRequest(format!(
+                "'{}' is neither a vacancy UUID nor a numeric Koinoti Nav id",
+                raw
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyApplications {
+    /// Monday of the ISO week.
+    pub week_start: NaiveDate,
+    pub applications: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VacancyAnalytics {
+    /// Koinoti Nav id that candidates reference; `None` for an internal
+    /// vacancy without a numeric `external_id`, which has no applicants.
+    pub vacancy_id: Option<i64>,
+    pub applicants: i64,
+    pub by_status: BTreeMap<String, i64>,
+    pub invites_sent: i64,
+    pub attempts_completed: i64,
+    pub attempts_passed: i64,
+    pub average_percentage: Option<f64>,
+    pub pass_rate: Option<f64>,
+    pub average_ai_rating: Option<f64>,
+    pub applications_per_week: Vec<WeeklyApplications>,
+}
+
+impl VacancyAnalytics {
+    pub fn empty(vacancy_id: Option<i64>) -> Self {
+        Self {
+            vacancy_id,
+            applicants: 0,
+            by_status: BTreeMap::new(),
+            invites_sent: 0,
+            attempts_completed: 0,
+            attempts_passed: 0,
+            average_percentage: None,
+            pass_rate: None,
+            average_ai_rating: None,
+            applications_per_week: Vec::new(),
+        }
+    }
+}
+
+/// Fills weeks without applications with zeros so the series charts evenly.
+/// `weeks` must be sorted by week start.
+pub fn fill_weekly_series(weeks: &[(NaiveDate, i64)]) -> Vec<WeeklyApplications> {
+    let (Some(&(first, _)), Some(&(last, _))) = (weeks.first(), weeks.last()) else {
+        return Vec::new();
+    };
+    let counts: BTreeMap<NaiveDate, i64> = weeks.iter().copied().collect();
+    let mut series = Vec::new();
+    let mut week = first;
+    while week <= last {
+        series.push(WeeklyApplications {
+            week_start: week,
+            applications: counts.get(&week).copied().unwrap_or(0),
+        });
+        week += Duration::weeks(1);
+    }
+    series
+}
+
 impl VacancyService {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 
-    pub async fn create(&self, payload: CreateVacancyPayload) -> Result<Vacancy> {
+    pub async fn create(&self, payload: CreateVacancyPayload, org_id: Uuid) -> Result<Vacancy> {
         let status = payload
             .status
             .clone()
@@ -34,12 +120,12 @@ impl VacancyService {
                 external_id, title, company, location, employment_type,
                 salary_from, salary_to, currency, negotiated_salary, description, requirements,
                 responsibilities, benefits, apply_url, contact_email, contact_phone,
-                status, published_at
+                status, published_at, organization_id
             ) VALUES (
                 $1,$2,$3,$4,$5,
                 $6,$7,$8,$9,$10,
                 $11,$12,$13,$14,$15,
-                $16,$17,$18
+                $16,$17,$18,$19
             )
             RETURNING
                 id,
@@ -82,6 +168,7 @@ impl VacancyService {
             payload.contact_phone,
             status,
             payload.published_at,
+            org_id,
         )
         .fetch_one(&self.pool)
         .await?;
@@ -89,8 +176,9 @@ impl VacancyService {
         Ok(vacancy)
     }
 
-    pub async fn update(&self, id: Uuid, payload: UpdateVacancyPayload) -> Result<Vacancy> {
-        self.get_by_id(id).await?;
+    /// Vacancies of other organizations are `404`, like missing ones.
+    pub async fn update(&self, id: Uuid, org_id: Uuid, payload: UpdateVacancyPayload) -> Result<Vacancy> {
+        self.get_by_id(id, org_id).await?;
 
         let vacancy = sqlx::query_as!(
             Vacancy,
@@ -116,7 +204,7 @@ impl VacancyService {
                 status = COALESCE($18, status),
                 published_at = COALESCE($19, published_at),
                 updated_at = NOW()
-            WHERE id = $1
+            WHERE id = $1 AND organization_id = $20
             RETURNING
                 id,
                 external_id,
@@ -159,6 +247,7 @@ impl VacancyService {
             payload.contact_phone,
             payload.status,
             payload.published_at,
+            org_id,
         )
         .fetch_one(&self.pool)
         .await?;
@@ -166,13 +255,13 @@ impl VacancyService {
         Ok(vacancy)
     }
 
-    pub async fn list(&self, query: VacancyListQuery) -> Result<VacancyList> {
+    pub async fn list(&self, query: VacancyListQuery, org_id: Uuid) -> Result<VacancyList> {
         let page = query.page.unwrap_or(1).max(1);
         let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
         let offset = (page - 1) * per_page;
 
-        let mut filters = Vec::new();
-        let mut args: Vec<String> = Vec::new();
+        let mut filters = vec!["organization_id = $1::uuid".to_string()];
+        let mut args: Vec<String> = vec![org_id.to_string()];
 
         if let Some(status) = query.status {
             filters.push(format!("status = ${}", args.len() + 1));
@@ -193,11 +282,7 @@ impl VacancyService {
             args.push(format!("%{}%", search));
         }
 
-        let where_clause = if filters.is_empty() {
-            "".to_string()
-        } else {
-            format!("WHERE {}", filters.join(" AND "))
-        };
+        let where_clause = format!("WHERE {}", filters.join(" AND "));
 
         let items_query = format!(
             "SELECT id, external_id, title, company, location, employment_type, salary_from, salary_to, currency, negotiated_salary, description, requirements, responsibilities, benefits, apply_url, contact_email, contact_phone, status, published_at, created_at, updated_at
@@ -236,15 +321,26 @@ impl VacancyService {
         })
     }
 
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Vacancy> {
+    /// Vacancies of other organizations are `404`, like missing ones.
+    pub async fn get_by_id(&self, id: Uuid, org_id: Uuid) -> Result<Vacancy> {
+        self.find(id, Some(org_id)).await
+    }
+
+    /// A vacancy of any organization, for the public pages.
+    pub async fn get_public(&self, id: Uuid) -> Result<Vacancy> {
+        self.find(id, None).await
+    }
+
+    async fn find(&self, id: Uuid, org_id: Option<Uuid>) -> Result<Vacancy> {
         let vacancy = sqlx::query_as!(
             Vacancy,
             r#"
             SELECT id, external_id, title, company, location, employment_type, salary_from, salary_to, currency, negotiated_salary, description, requirements, responsibilities, benefits, apply_url, contact_email, contact_phone, status, published_at, created_at, updated_at
             FROM vacancies
-            WHERE id = $1
+            WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            org_id
         )
         .fetch_one(&self.pool)
         .await?;
@@ -252,14 +348,116 @@ impl VacancyService {
         Ok(vacancy)
     }
 
-    pub async fn delete(&self, id: Uuid) -> Result<PgQueryResult> {
-        let res = sqlx::query!("DELETE FROM vacancies WHERE id = $1", id)
+    /// Funnel numbers for one vacancy. Candidates link via `vacancy_id`,
+    /// their test attempts via `candidate_email`.
+    /// Only candidates of `org_id` are counted.
+    pub async fn analytics(&self, vacancy: VacancyRef, org_id: Uuid) -> Result<VacancyAnalytics> {
+        let vacancy_id = match vacancy {
+            VacancyRef::External(id) => id,
+            VacancyRef::Internal(id) => {
+                let vacancy = self.get_by_id(id, org_id).await?;
+                match vacancy
+                    .external_id
+                    .and_then(|e| e.trim().parse::<i64>().ok())
+                {
+                    Some(external_id) => external_id,
+                    None => return Ok(VacancyAnalytics::empty(None)),
+                }
+            }
+        };
+
+        let mut analytics = VacancyAnalytics::empty(Some(vacancy_id));
+
+        let (applicants, average_ai_rating): (i64, Option<f64>) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), ROUND(AVG(ai_rating)::numeric, 1)::float8
+            FROM candidates
+            WHERE vacancy_id = $1 AND organization_id = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(vacancy_id)
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        analytics.applicants = applicants;
+        analytics.average_ai_rating = average_ai_rating;
+
+        let by_status: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT status, COUNT(*) FROM candidates
+            WHERE vacancy_id = $1 AND organization_id = $2 AND deleted_at IS NULL
+            GROUP BY status
+            "#,
+        )
+        .bind(vacancy_id)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+        analytics.by_status = by_status.into_iter().collect();
+
+        let (invites, completed, passed, average_percentage): (i64, i64, i64, Option<f64>) =
+            sqlx::query_as(
+                r#"
+                SELECT
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE completed_at IS NOT NULL),
+                    COUNT(*) FILTER (WHERE completed_at IS NOT NULL AND passed = TRUE),
+                    ROUND(AVG(percentage) FILTER (WHERE completed_at IS NOT NULL), 1)::float8
+                FROM test_attempts
+                WHERE NOT is_preview AND LOWER(candidate_email) IN (
+                    SELECT LOWER(email) FROM candidates
+                    WHERE vacancy_id = $1 AND organization_id = $2 AND deleted_at IS NULL AND email IS NOT NULL
+                )
+                "#,
+            )
+            .bind(vacancy_id)
+            .bind(org_id)
+            .fetch_one(&self.pool)
+            .await?;
+        analytics.invites_sent = invites;
+        analytics.attempts_completed = completed;
+        analytics.attempts_passed = passed;
+        analytics.average_percentage = average_percentage;
+        analytics.pass_rate = pass_rate(completed, passed);
+
+        let weeks: Vec<(NaiveDate, i64)> = sqlx::query_as(
+            r#"
+            SELECT date_trunc('week', created_at)::date AS week, COUNT(*)
+            FROM candidates
+            WHERE vacancy_id = $1 AND organization_id = $2 AND deleted_at IS NULL AND created_at IS NOT NULL
+            GROUP BY week
+            ORDER BY week
+            "#,
+        )
+        .bind(vacancy_id)
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+        analytics.applications_per_week = fill_weekly_series(&weeks);
+
+        Ok(analytics)
+    }
+
+    /// Vacancies of other organizations are left alone; the caller checks
+    /// `rows_affected`.
+    pub async fn delete(&self, id: Uuid, org_id: Uuid) -> Result<PgQueryResult> {
+        let res = sqlx::query!("DELETE FROM vacancies WHERE id = $1 AND organization_id = $2", id, org_id)
             .execute(&self.pool)
             .await?;
 
         Ok(res)
     }
 
+    pub async fn count_published(&self, org_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM vacancies WHERE status = 'published' AND organization_id = $1",
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
     pub async fn list_published(&self, limit: i64) -> Result<Vec<Vacancy>> {
         let limit = if limit <= 0 { 20 } else { limit.min(100) };
         let items = sqlx::query_as!(
@@ -278,4 +476,36 @@ impl VacancyService {
 
         Ok(items)
     }
+
+    /// Channel post previously created for this vacancy, if any.
+    pub async fn telegram_post(&self, id: Uuid) -> Result<Option<ChannelPost>> {
+        let row: Option<(Option<String>, Option<i64>)> = sqlx::query_as(
+            "SELECT telegram_chat_id, telegram_message_id FROM vacancies WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            None => Err(Error::NotFound(format!("Vacancy {} not found", id))),
+            Some((Some(chat_id), Some(message_id))) => Ok(Some(ChannelPost {
+                chat_id,
+                message_id,
+            })),
+            Some(_) => Ok(None),
+        }
+    }
+
+    pub async fn set_telegram_post(&self, id: Uuid, post: Option<&ChannelPost>) -> Result<()> {
+        sqlx::query(
+            "UPDATE vacancies SET telegram_chat_id = $2, telegram_message_id = $3 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(post.map(|p| p.chat_id.clone()))
+        .bind(post.map(|p| p.message_id))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
 }
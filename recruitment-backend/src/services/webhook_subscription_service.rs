@@ -0,0 +1,254 @@
+use crate::error::{Error, Result};
+use crate::models::webhook_log::WebhookLog;
+use crate::models::webhook_subscription::WebhookSubscription;
+use crate::utils::token::generate_access_token;
+use axum::http::StatusCode;
+use serde_json::{json, Value as JsonValue};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Matches every event type.
+pub const ALL_EVENTS: &str = "*";
+
+/// Event types the platform queues to webhook_logs.
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "test_assigned",
+    "test_completed",
+    "presentation_submitted",
+    "deadline_warning",
+    "grade_revised",
+    "test_reminder",
+    "candidate_registered",
+    "candidate_status_changed",
+];
+
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, url, event_types, secret, is_active, created_at, updated_at";
+
+/// Trims and de-duplicates the requested event types; `*` anywhere collapses
+/// the list to `["*"]`. Unknown names are rejected so a typo does not leave a
+/// subscription that silently never fires.
+pub fn normalize_event_types(event_types: &[String]) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for event in event_types
+        .iter()
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+    {
+        if event == ALL_EVENTS {
+            return Ok(vec![ALL_EVENTS.to_string()]);
+        }
+        if !WEBHOOK_EVENTS.contains(&event) {
+            return Err(Error::coded(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unknown_webhook_event",
+                format!(
+                    "Unknown webhook event '{}'. Expected '*' or one of: {}",
+                    event,
+                    WEBHOOK_EVENTS.join(", ")
+                ),
+            )
+            .with_details(json!({ "event_type": event, "known_events": WEBHOOK_EVENTS })));
+        }
+        if !normalized.iter().any(|e| e == event) {
+            normalized.push(event.to_string());
+        }
+    }
+    if normalized.is_empty() {
+        return Err(Error::coded(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "empty_webhook_events",
+            "event_types must name at least one event, or '*' for all",
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Whether a subscription listening to `event_types` wants `event_type`.
+pub fn subscription_wants(event_types: &[String], event_type: &str) -> bool {
+    event_types
+        .iter()
+        .any(|e| e == ALL_EVENTS || e == event_type)
+}
+
+/// Active subscriptions that should receive `event_type`, in input order.
+pub fn matching_subscriptions<'a>(
+    subscriptions: &'a [WebhookSubscription],
+    event_type: &str,
+) -> Vec<&'a WebhookSubscription> {
+    subscriptions
+        .iter()
+        .filter(|s| s.is_active && subscription_wants(&s.event_types, event_type))
+        .collect()
+}
+
+/// Changes to a subscription; `None` keeps the current value.
+#[derive(Debug, Default)]
+pub struct WebhookSubscriptionUpdate {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub secret: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Clone)]
+pub struct WebhookSubscriptionService {
+    pool: PgPool,
+}
+
+impl WebhookSubscriptionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// A random secret is generated when none is given.
+    pub async fn create(
+        &self,
+        org_id: Uuid,
+        url: &str,
+        event_types: &[String],
+        secret: Option<String>,
+    ) -> Result<WebhookSubscription> {
+        let event_types = normalize_event_types(event_types)?;
+        let secret = secret
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| generate_access_token(32));
+
+        let created = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "INSERT INTO webhook_subscriptions (url, event_types, secret, organization_id) VALUES ($1, $2, $3, $4) RETURNING {}",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(url.trim())
+        .bind(&event_types)
+        .bind(&secret)
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(created)
+    }
+
+    pub async fn list(&self, org_id: Uuid) -> Result<Vec<WebhookSubscription>> {
+        let rows = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE organization_id = $1 ORDER BY created_at DESC",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(&self, id: Uuid, org_id: Uuid) -> Result<WebhookSubscription> {
+        sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE id = $1 AND organization_id = $2",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| subscription_not_found(id))
+    }
+
+    /// Pending deliveries keep the URL they were queued with; only new
+    /// events use the updated settings.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        org_id: Uuid,
+        update: WebhookSubscriptionUpdate,
+    ) -> Result<WebhookSubscription> {
+        let event_types = update
+            .event_types
+            .as_deref()
+            .map(normalize_event_types)
+            .transpose()?;
+        let secret = update
+            .secret
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        sqlx::query_as::<_, WebhookSubscription>(&format!(
+            r#"UPDATE webhook_subscriptions
+               SET url = COALESCE($2, url),
+                   event_types = COALESCE($3, event_types),
+                   secret = COALESCE($4, secret),
+                   is_active = COALESCE($5, is_active),
+                   updated_at = NOW()
+               WHERE id = $1 AND organization_id = $6
+               RETURNING {}"#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(update.url.as_deref().map(str::trim))
+        .bind(event_types)
+        .bind(secret)
+        .bind(update.is_active)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| subscription_not_found(id))
+    }
+
+    /// Pending deliveries for the subscription are dropped with it.
+    pub async fn delete(&self, id: Uuid, org_id: Uuid) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1 AND organization_id = $2")
+                .bind(id)
+                .bind(org_id)
+                .execute(&self.pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(subscription_not_found(id));
+        }
+        Ok(())
+    }
+
+    /// Queues one delivery per active subscription of `org_id` that wants
+    /// `event_type`. Takes a connection so callers can enqueue inside their
+    /// own transaction.
+    pub async fn enqueue_for_subscribers(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        event_type: &str,
+        payload: &JsonValue,
+    ) -> Result<Vec<WebhookLog>> {
+        let subscriptions = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE is_active AND organization_id = $1",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(org_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let ids: Vec<Uuid> = matching_subscriptions(&subscriptions, event_type)
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, WebhookLog>(
+            r#"
+            INSERT INTO webhook_logs (event_type, payload, target_url, status, subscription_id)
+            SELECT $1, $2, s.url, 'pending', s.id
+            FROM webhook_subscriptions s
+            WHERE s.id = ANY($3)
+            RETURNING
+                id, event_type, payload, target_url, http_status, response_body, attempts,
+                max_attempts, next_retry_at, status, subscription_id, target, created_at, updated_at
+            "#,
+        )
+        .bind(event_type)
+        .bind(payload)
+        .bind(&ids)
+        .fetch_all(&mut *conn)
+        .await?;
+        Ok(rows)
+    }
+}
+
+fn subscription_not_found(id: Uuid) -> Error {
+    Error::NotFound(format!("Webhook subscription {} not found", id))
+}
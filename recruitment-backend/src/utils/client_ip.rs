@@ -0,0 +1,215 @@
+use std::net::IpAddr;
+
+use axum::http::HeaderMap;
+use sqlx::types::ipnetwork::IpNetwork;
+
+pub const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Parses a single forwarding hop: a bare address, `ip:port` or `[ipv6]:port`.
+pub fn parse_forwarded_ip(raw: &str) -> Option<IpAddr> {
+    let raw = raw.trim().trim_matches('"');
+    if let Ok(ip) = raw.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = raw.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    match raw.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') && port.parse::<u16>().is_ok() => {
+            host.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// The real client address. A peer outside `trusted_proxies` is the client
+/// itself and its forwarding headers are ignored. Behind a trusted proxy,
+/// `X-Forwarded-For` is walked right to left and the first untrusted hop wins;
+/// a malformed hop stops the walk (anything left of it could be forged), in
+/// which case `X-Real-IP` and then the peer are used.
+pub fn resolve_client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &[IpNetwork],
+) -> Option<IpAddr> {
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if let Some(peer) = peer.filter(|ip| !is_trusted(*ip)) {
+        return Some(peer);
+    }
+    forwarded_for(headers, is_trusted)
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_forwarded_ip)
+        })
+        .or(peer)
+}
+
+fn forwarded_for(headers: &HeaderMap, is_trusted: impl Fn(IpAddr) -> bool) -> Option<IpAddr> {
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .collect();
+
+    let mut leftmost = None;
+    for hop in hops.iter().rev() {
+        let ip = parse_forwarded_ip(hop)?;
+        if !is_trusted(ip) {
+            return Some(ip);
+        }
+        leftmost = Some(ip);
+    }
+    leftmost
+}
+
+/// `User-Agent`, trimmed and capped at `MAX_USER_AGENT_LEN` characters.
+pub fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| {
+            ua.trim()
+                .chars()
+                .take(MAX_USER_AGENT_LEN)
+                .collect::<String>()
+        })
+        .filter(|ua| !ua.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn proxies() -> Vec<IpNetwork> {
+        vec![
+            "127.0.0.1/32".parse().unwrap(),
+            "10.0.0.0/8".parse().unwrap(),
+        ]
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn ip(raw: &str) -> IpAddr {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_hops_with_ports_and_brackets() {
+        assert_eq!(parse_forwarded_ip(" 203.0.113.7 "), Some(ip("203.0.113.7")));
+        assert_eq!(
+            parse_forwarded_ip("203.0.113.7:4711"),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(parse_forwarded_ip("2001:db8::1"), Some(ip("2001:db8::1")));
+        assert_eq!(
+            parse_forwarded_ip("[2001:db8::1]:443"),
+            Some(ip("2001:db8::1"))
+        );
+        assert_eq!(
+            parse_forwarded_ip("\"203.0.113.7\""),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(parse_forwarded_ip("unknown"), None);
+        assert_eq!(parse_forwarded_ip("203.0.113.7:http"), None);
+        assert_eq!(parse_forwarded_ip("999.1.1.1"), None);
+        assert_eq!(parse_forwarded_ip(""), None);
+    }
+
+    #[test]
+    fn picks_the_first_untrusted_hop_from_the_right() {
+        let h = headers(&[("x-forwarded-for", "198.51.100.1, 203.0.113.7, 10.0.0.5")]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("203.0.113.7"))
+        );
+
+        // Repeated headers are read as one list.
+        let h = headers(&[
+            ("x-forwarded-for", "198.51.100.1"),
+            ("x-forwarded-for", "203.0.113.7, 10.0.0.5"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("203.0.113.7"))
+        );
+
+        // Every hop is a proxy: the leftmost one is the best we have.
+        let h = headers(&[("x-forwarded-for", "10.1.1.1, 10.0.0.5")]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("10.1.1.1"))
+        );
+    }
+
+    #[test]
+    fn ignores_headers_from_untrusted_peers() {
+        let h = headers(&[
+            ("x-forwarded-for", "198.51.100.1"),
+            ("x-real-ip", "198.51.100.2"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("203.0.113.9")), &proxies()),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &[]),
+            Some(ip("127.0.0.1"))
+        );
+    }
+
+    #[test]
+    fn malformed_hops_fall_back_to_real_ip_then_peer() {
+        let h = headers(&[
+            ("x-forwarded-for", "198.51.100.1, garbage"),
+            ("x-real-ip", "198.51.100.2"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("198.51.100.2"))
+        );
+
+        let h = headers(&[("x-forwarded-for", ", ,"), ("x-real-ip", "nope")]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("127.0.0.1"))
+        );
+
+        // A malformed hop left of the first untrusted one doesn't matter.
+        let h = headers(&[("x-forwarded-for", "garbage, 203.0.113.7, 10.0.0.5")]);
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("127.0.0.1")), &proxies()),
+            Some(ip("203.0.113.7"))
+        );
+
+        assert_eq!(resolve_client_ip(&HeaderMap::new(), None, &proxies()), None);
+    }
+
+    #[test]
+    fn user_agent_is_trimmed_and_capped() {
+        assert_eq!(
+            user_agent(&headers(&[("user-agent", " Mozilla/5.0 ")])),
+            Some("Mozilla/5.0".to_string())
+        );
+        assert_eq!(user_agent(&headers(&[("user-agent", "  ")])), None);
+        assert_eq!(user_agent(&HeaderMap::new()), None);
+        let long = "a".repeat(MAX_USER_AGENT_LEN + 100);
+        assert_eq!(
+            user_agent(&headers(&[("user-agent", &long)]))
+                .unwrap()
+                .len(),
+            MAX_USER_AGENT_LEN
+        );
+    }
+}
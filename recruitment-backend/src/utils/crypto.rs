@@ -18,3 +18,8 @@ pub fn verify_password(plain: &str, hashed: &str) -> Result<bool, argon2::passwo
         .is_ok();
     Ok(ok)
 }
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(data))
+}
@@ -0,0 +1,334 @@
+//! Cleaning of HTML that comes from outside: Koinoti Nav vacancies and
+//! descriptions HR paste into the dashboard. `sanitize_to_text` is for
+//! prompts, spreadsheets and Telegram; `sanitize_to_safe_html` for places
+//! that render the markup.
+
+/// Tags dropped together with their content.
+const HIDDEN_TAGS: [&str; 5] = ["script", "style", "noscript", "template", "head"];
+
+/// Tags that start a new line in plain text.
+const LINE_TAGS: [&str; 15] = [
+    "p", "br", "div", "ul", "ol", "tr", "table", "blockquote", "h1", "h2", "h3", "h4", "h5", "h6",
+    "hr",
+];
+
+/// Tags kept by `sanitize_to_safe_html`, without attributes.
+const SAFE_TAGS: [&str; 4] = ["p", "br", "li", "strong"];
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    Tag { name: String, closing: bool },
+}
+
+/// Splits `input` into text and tags. A `<` not followed by a letter, `/`,
+/// `!` or `?` is text; an unclosed tag swallows the rest of the input.
+/// Comments, doctypes and the content of `HIDDEN_TAGS` are skipped.
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let opens_tag = after
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        if !opens_tag {
+            tokens.push(Token::Text(&rest[..start + 1]));
+            rest = after;
+            continue;
+        }
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = after.find('>') else {
+            return tokens;
+        };
+        let body = &after[..end];
+        rest = &after[end + 1..];
+        if body.starts_with(['!', '?']) {
+            continue;
+        }
+        let closing = body.starts_with('/');
+        let name: String = body
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if !closing && HIDDEN_TAGS.contains(&name.as_str()) && !body.ends_with('/') {
+            rest = skip_past_closing_tag(rest, &name);
+            continue;
+        }
+        tokens.push(Token::Tag { name, closing });
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+    tokens
+}
+
+/// The input after `</name>`, or nothing when the tag is never closed.
+fn skip_past_closing_tag<'a>(input: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lower = input.to_ascii_lowercase();
+    lower
+        .find(&closing)
+        .and_then(|at| input[at..].find('>').map(|end| &input[at + end + 1..]))
+        .unwrap_or("")
+}
+
+/// Decodes named (`&nbsp;`, `&amp;`, `&quot;`, ...) and numeric entities;
+/// unknown ones are left as they are.
+fn decode_entities(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let decoded = after
+            .find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .and_then(|end| entity(&after[..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(code) = name.strip_prefix('#') {
+        let value = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        return char::from_u32(value);
+    }
+    Some(match name {
+        "nbsp" => ' ',
+        "amp" => '&',
+        "quot" => '"',
+        "apos" => '\'',
+        "lt" => '<',
+        "gt" => '>',
+        "laquo" => '«',
+        "raquo" => '»',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "bull" => '•',
+        "copy" => '©',
+        _ => return None,
+    })
+}
+
+/// Every run of whitespace as a single space.
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !c.is_whitespace() {
+            out.push(c);
+        } else if !out.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Plain text from `input`: tags stripped (scripts and styles with their
+/// content), entities decoded, whitespace collapsed. Paragraphs, line breaks
+/// and headings become lines and list items `• ` lines; empty lines are
+/// dropped.
+pub fn sanitize_to_text(input: &str) -> String {
+    let mut raw = String::with_capacity(input.len());
+    for token in tokenize(input) {
+        match token {
+            Token::Text(text) => raw.push_str(&decode_entities(text)),
+            Token::Tag { name, closing } if name == "li" => {
+                raw.push('\n');
+                if !closing {
+                    raw.push_str("• ");
+                }
+            }
+            Token::Tag { name, .. } if LINE_TAGS.contains(&name.as_str()) => raw.push('\n'),
+            Token::Tag { name, .. } if name == "td" || name == "th" => raw.push(' '),
+            Token::Tag { .. } => {}
+        }
+    }
+    raw.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `input` reduced to `<p>`, `<br>`, `<li>` and `<strong>` (`<b>` counts as
+/// `<strong>`) without attributes. Text is re-escaped, other tags are dropped
+/// with their text kept, stray closing tags are ignored and tags left open
+/// are closed at the end.
+pub fn sanitize_to_safe_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut open: Vec<&'static str> = Vec::new();
+    for token in tokenize(input) {
+        let (name, closing) = match token {
+            Token::Text(text) => {
+                out.push_str(&escape(&collapse_whitespace(&decode_entities(text))));
+                continue;
+            }
+            Token::Tag { name, closing } => (name, closing),
+        };
+        let name = if name == "b" { "strong" } else { name.as_str() };
+        let Some(&tag) = SAFE_TAGS.iter().find(|t| **t == name) else {
+            continue;
+        };
+        if tag == "br" {
+            if !closing {
+                out.push_str("<br>");
+            }
+        } else if !closing {
+            out.push_str(&format!("<{}>", tag));
+            open.push(tag);
+        } else if let Some(depth) = open.iter().rposition(|t| *t == tag) {
+            for inner in open.drain(depth..).rev() {
+                out.push_str(&format!("</{}>", inner));
+            }
+        }
+    }
+    for tag in open.into_iter().rev() {
+        out.push_str(&format!("</{}>", tag));
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_strips_nested_tags() {
+        assert_eq!(
+            sanitize_to_text("<div><p>Senior <strong><em>Rust</em></strong> dev</p></div>"),
+            "Senior Rust dev"
+        );
+    }
+
+    #[test]
+    fn text_keeps_paragraphs_and_list_items_as_lines() {
+        assert_eq!(
+            sanitize_to_text("<h1>Role</h1><p>Build services.</p><ul><li>axum</li><li>sqlx</li></ul>Line<br/>break"),
+            "Role\nBuild services.\n• axum\n• sqlx\nLine\nbreak"
+        );
+    }
+
+    #[test]
+    fn text_drops_unclosed_tags_and_keeps_lone_brackets() {
+        assert_eq!(sanitize_to_text("Salary <b>high"), "Salary high");
+        assert_eq!(sanitize_to_text("Experience <a href=\"x"), "Experience");
+        assert_eq!(sanitize_to_text("a < b and c > d"), "a < b and c > d");
+    }
+
+    #[test]
+    fn text_decodes_entities() {
+        assert_eq!(
+            sanitize_to_text("Tom&nbsp;&amp;&nbsp;Jerry &quot;Ltd&quot; &lt;tag&gt; &#1071; &#x42F; &laquo;ok&raquo;"),
+            "Tom & Jerry \"Ltd\" <tag> Я Я «ok»"
+        );
+        assert_eq!(sanitize_to_text("R&D & QA &unknown;"), "R&D & QA &unknown;");
+    }
+
+    #[test]
+    fn text_drops_scripts_styles_and_comments() {
+        assert_eq!(
+            sanitize_to_text(
+                "<p>Hi</p><script type=\"text/javascript\">alert('<p>x</p>')</SCRIPT><style>p{color:red}</style><!-- note -->there"
+            ),
+            "Hi\nthere"
+        );
+        assert_eq!(sanitize_to_text("Safe<script>never closed"), "Safe");
+    }
+
+    #[test]
+    fn text_collapses_whitespace() {
+        assert_eq!(sanitize_to_text("  a \t b\n\n\n  c  "), "a b\nc");
+        assert_eq!(sanitize_to_text(""), "");
+    }
+
+    #[test]
+    fn text_keeps_cyrillic() {
+        assert_eq!(
+            sanitize_to_text("<p>Бухгалтер&nbsp;— <b>1С</b>, отчётность</p><li>Душанбе</li>"),
+            "Бухгалтер — 1С, отчётность\n• Душанбе"
+        );
+    }
+
+    #[test]
+    fn safe_html_keeps_only_the_allowlist() {
+        assert_eq!(
+            sanitize_to_safe_html(
+                "<div class=\"x\"><p style=\"color:red\">Hello <b>team</b><br/><a href=\"javascript:alert(1)\">link</a></p><ul><li onclick=\"x()\">one</li></ul></div>"
+            ),
+            "<p>Hello <strong>team</strong><br>link</p><li>one</li>"
+        );
+    }
+
+    #[test]
+    fn safe_html_drops_scripts_and_escapes_text() {
+        assert_eq!(
+            sanitize_to_safe_html("<p>1 &lt; 2 &amp; \"q\"</p><script>alert(1)</script><img src=x onerror=alert(1)>"),
+            "<p>1 &lt; 2 &amp; &quot;q&quot;</p>"
+        );
+    }
+
+    #[test]
+    fn safe_html_balances_tags() {
+        assert_eq!(sanitize_to_safe_html("<p><strong>open"), "<p><strong>open</strong></p>");
+        assert_eq!(sanitize_to_safe_html("text</p></strong>"), "text");
+        assert_eq!(
+            sanitize_to_safe_html("<p><strong>a</p>b"),
+            "<p><strong>a</strong></p>b"
+        );
+        assert_eq!(sanitize_to_safe_html("<p>cut <strong"), "<p>cut </p>");
+    }
+
+    #[test]
+    fn safe_html_keeps_cyrillic_and_collapses_whitespace() {
+        assert_eq!(
+            sanitize_to_safe_html("<p>  Ведущий   <strong>юрист</strong>&nbsp;компании </p>"),
+            "<p> Ведущий <strong>юрист</strong> компании </p>"
+        );
+    }
+
+    #[test]
+    fn sanitized_html_reads_back_as_the_same_text() {
+        let raw = "<p>R&amp;D <b>team</b></p><ul><li>5&nbsp;лет</li></ul><script>x</script>";
+        assert_eq!(sanitize_to_text(&sanitize_to_safe_html(raw)), sanitize_to_text(raw));
+    }
+}
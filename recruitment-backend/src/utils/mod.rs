@@ -1,5 +1,11 @@
+pub mod client_ip;
 pub mod crypto;
+pub mod html;
 pub mod login_guard;
+pub mod signed_url;
+pub mod strings;
+pub mod telegram_auth;
 pub mod time;
 pub mod token;
+pub mod ttl_cache;
 pub mod validation;
@@ -0,0 +1,237 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Lifetime of CV download links handed to the dashboard.
+pub const CV_LINK_TTL: Duration = Duration::from_secs(3600);
+
+/// Upload subdirectories that are only served with a valid signature.
+pub const SIGNED_UPLOAD_DIRS: [&str; 2] = ["cv", "portfolio"];
+
+/// Appends `?expires=<epoch>&sig=<HMAC-SHA256>` to `path`. The signature
+/// covers the path and the expiry, so neither can be changed by the client.
+pub fn sign_url(path: &str, expires_in: Duration, secret: &[u8]) -> String {
+    let expires = chrono::Utc::now().timestamp() + expires_in.as_secs() as i64;
+    sign_url_until(path, expires, secret)
+}
+
+pub fn sign_url_until(path: &str, expires: i64, secret: &[u8]) -> String {
+    format!(
+        "{}?expires={}&sig={}",
+        path,
+        expires,
+        hex::encode(signature(path, expires, secret))
+    )
+}
+
+/// Checks the `expires`/`sig` pair in `query` against `path` at time `now`.
+pub fn verify_signed_path(path: &str, query: Option<&str>, secret: &[u8], now: i64) -> bool {
+    let mut expires = None;
+    let mut sig = None;
+    for (key, value) in url::form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+        match key.as_ref() {
+            "expires" => expires = value.parse::<i64>().ok(),
+            "sig" => sig = hex::decode(value.as_bytes()).ok(),
+            _ => {}
+        }
+    }
+    let (Some(expires), Some(sig)) = (expires, sig) else {
+        return false;
+    };
+    if expires < now {
+        return false;
+    }
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret) else {
+        return false;
+    };
+    mac.update(signed_payload(path, expires).as_bytes());
+    mac.verify_slice(&sig).is_ok()
+}
+
+/// Stored upload path relative to the upload root: strips `./`, a leading
+/// `/`, the `uploads/` prefix and any query string.
+pub fn storage_path(path: &str) -> &str {
+    let path = path.split('?').next().unwrap_or_default();
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    path.strip_prefix("uploads/").unwrap_or(path)
+}
+
+/// Root directory uploads are stored under (`UPLOADS_DIR`).
+pub fn upload_root() -> String {
+    std::env::var("UPLOADS_DIR").unwrap_or_else(|_| "/app/uploads".to_string())
+}
+
+/// Maps a stored, public or signed `uploads/...` path back to its location on disk.
+pub fn upload_disk_path(public_path: &str) -> String {
+    format!("{}/{}", upload_root(), storage_path(public_path))
+}
+
+/// Opaque token for `GET /api/files/signed/:token`:
+/// `<base64url(path)>.<expires>.<hex HMAC>`, signed like `sign_url`.
+pub fn sign_token(path: &str, expires: i64, secret: &[u8]) -> String {
+    let path = path.trim_start_matches('/');
+    format!(
+        "{}.{}.{}",
+        URL_SAFE_NO_PAD.encode(path),
+        expires,
+        hex::encode(signature(path, expires, secret))
+    )
+}
+
+/// The upload path a token names, if its signature holds and it has not
+/// expired at `now`. Paths leaving the upload root are rejected.
+pub fn verify_token(token: &str, secret: &[u8], now: i64) -> Option<String> {
+    let mut parts = token.splitn(3, '.');
+    let path = URL_SAFE_NO_PAD.decode(parts.next()?).ok()?;
+    let path = String::from_utf8(path).ok()?;
+    let expires = parts.next()?.parse::<i64>().ok()?;
+    let sig = hex::decode(parts.next()?).ok()?;
+    if expires < now || path.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    let mut mac = HmacSha256::new_from_slice(secret).ok()?;
+    mac.update(signed_payload(&path, expires).as_bytes());
+    mac.verify_slice(&sig).ok()?;
+    Some(path)
+}
+
+/// `api/files/signed/<token>` download link for a stored upload, valid for
+/// `expires_in`. Unlike `signed_upload_url` the path is not exposed.
+pub fn signed_download_url(stored: &str, expires_in: Duration) -> String {
+    let secret = crate::config::get_config().upload_signing_secret.as_bytes();
+    let expires = chrono::Utc::now().timestamp() + expires_in.as_secs() as i64;
+    format!(
+        "api/files/signed/{}",
+        sign_token(storage_path(stored), expires, secret)
+    )
+}
+
+/// Public `uploads/...` link for a stored upload, valid for `expires_in`.
+pub fn signed_upload_url(stored: &str, expires_in: Duration) -> String {
+    let secret = crate::config::get_config().upload_signing_secret.as_bytes();
+    format!(
+        "uploads/{}",
+        sign_url(storage_path(stored), expires_in, secret)
+    )
+}
+
+/// Candidate-held token for their own data (`portal_token`): hex
+/// HMAC-SHA256 over the candidate id. It does not expire; changing the
+/// signing secret revokes every issued token.
+pub fn portal_token(candidate_id: Uuid, secret: &[u8]) -> String {
+    hex::encode(portal_mac(candidate_id, secret).finalize().into_bytes())
+}
+
+/// Constant-time check of a `portal_token` against `candidate_id`.
+pub fn verify_portal_token(candidate_id: Uuid, token: &str, secret: &[u8]) -> bool {
+    let Ok(sig) = hex::decode(token.trim()) else {
+        return false;
+    };
+    portal_mac(candidate_id, secret).verify_slice(&sig).is_ok()
+}
+
+fn portal_mac(candidate_id: Uuid, secret: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(format!("portal\n{}", candidate_id).as_bytes());
+    mac
+}
+
+fn signed_payload(path: &str, expires: i64) -> String {
+    format!("{}\n{}", path.trim_start_matches('/'), expires)
+}
+
+fn signature(path: &str, expires: i64, secret: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(signed_payload(path, expires).as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn query(url: &str) -> Option<&str> {
+        url.split_once('?').map(|(_, q)| q)
+    }
+
+    #[test]
+    fn signed_path_verifies_until_expiry() {
+        let url = sign_url_until("cv/a.pdf", 1_000, SECRET);
+        assert!(url.starts_with("cv/a.pdf?expires=1000&sig="));
+        assert!(verify_signed_path("cv/a.pdf", query(&url), SECRET, 1_000));
+        assert!(verify_signed_path("/cv/a.pdf", query(&url), SECRET, 999));
+        assert!(!verify_signed_path("cv/a.pdf", query(&url), SECRET, 1_001));
+    }
+
+    #[test]
+    fn rejects_tampering_and_missing_params() {
+        let url = sign_url_until("cv/a.pdf", 1_000, SECRET);
+        assert!(!verify_signed_path("cv/b.pdf", query(&url), SECRET, 0));
+        assert!(!verify_signed_path("cv/a.pdf", query(&url), b"other", 0));
+        let extended = query(&url).unwrap().replace("expires=1000", "expires=9000");
+        assert!(!verify_signed_path("cv/a.pdf", Some(&extended), SECRET, 0));
+        assert!(!verify_signed_path("cv/a.pdf", None, SECRET, 0));
+        assert!(!verify_signed_path(
+            "cv/a.pdf",
+            Some("expires=1000&sig=zz"),
+            SECRET,
+            0
+        ));
+    }
+
+    #[test]
+    fn token_names_its_path_until_expiry() {
+        let token = sign_token("/cv/a.pdf", 1_000, SECRET);
+        assert!(!token.contains('/'));
+        assert_eq!(
+            verify_token(&token, SECRET, 1_000).as_deref(),
+            Some("cv/a.pdf")
+        );
+        assert_eq!(verify_token(&token, SECRET, 1_001), None);
+    }
+
+    #[test]
+    fn token_rejects_tampering_and_wrong_secret() {
+        let token = sign_token("cv/a.pdf", 1_000, SECRET);
+        assert_eq!(verify_token(&token, b"other", 0), None);
+
+        let (_, rest) = token.split_once('.').unwrap();
+        let other_path = format!("{}.{}", URL_SAFE_NO_PAD.encode("cv/b.pdf"), rest);
+        assert_eq!(verify_token(&other_path, SECRET, 0), None);
+        let extended = token.replace(".1000.", ".9000.");
+        assert_eq!(verify_token(&extended, SECRET, 0), None);
+
+        assert_eq!(verify_token("", SECRET, 0), None);
+        assert_eq!(verify_token("not-a-token", SECRET, 0), None);
+        let escape = sign_token("../etc/passwd", 1_000, SECRET);
+        assert_eq!(verify_token(&escape, SECRET, 0), None);
+    }
+
+    #[test]
+    fn portal_token_is_bound_to_the_candidate() {
+        let id = Uuid::new_v4();
+        let token = portal_token(id, SECRET);
+        assert!(verify_portal_token(id, &token, SECRET));
+        assert!(!verify_portal_token(Uuid::new_v4(), &token, SECRET));
+        assert!(!verify_portal_token(id, &token, b"other"));
+        assert!(!verify_portal_token(id, "", SECRET));
+        assert!(!verify_portal_token(id, "not-hex", SECRET));
+    }
+
+    #[test]
+    fn storage_path_drops_root_and_query() {
+        assert_eq!(storage_path("uploads/cv/a.pdf"), "cv/a.pdf");
+        assert_eq!(storage_path("./uploads/cv/a.pdf"), "cv/a.pdf");
+        assert_eq!(
+            storage_path("/uploads/cv/a.pdf?expires=1&sig=ab"),
+            "cv/a.pdf"
+        );
+        assert_eq!(storage_path("cv/a.pdf"), "cv/a.pdf");
+    }
+}
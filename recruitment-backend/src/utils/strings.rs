@@ -0,0 +1,101 @@
+//! Candidate-facing strings for the test mini-app and Telegram messages.
+//! Lookups fall back from the requested language to Russian, then to the key.
+
+use std::collections::BTreeMap;
+
+pub const DEFAULT_LANG: &str = "ru";
+pub const SUPPORTED_LANGS: &[&str] = &["ru", "tg"];
+
+/// Keys served to the mini-app by `GET /api/public/i18n/:lang`.
+pub const UI_KEYS: &[&str] = &[
+    "test_expired",
+    "time_up",
+    "submit_confirmation",
+    "submit_success",
+    "violation_tab_switch",
+    "violation_copy_paste",
+    "violation_fullscreen",
+    "violation_final_warning",
+];
+
+const RU: &[(&str, &str)] = &[
+    ("test_expired", "Срок действия ссылки на тест истёк. Обратитесь к рекрутеру за новой ссылкой."),
+    ("time_up", "Время вышло. Ваши ответы отправлены автоматически."),
+    ("submit_confirmation", "Отправить ответы? После отправки изменить их будет нельзя."),
+    ("submit_success", "Ответы отправлены. Спасибо!"),
+    ("violation_tab_switch", "Вы покинули вкладку с тестом. Это нарушение будет зафиксировано."),
+    ("violation_copy_paste", "Копирование и вставка во время теста запрещены."),
+    ("violation_fullscreen", "Не выходите из полноэкранного режима во время теста."),
+    ("violation_final_warning", "Последнее предупреждение: при следующем нарушении тест будет завершён."),
+    ("profile_button", "Профиль"),
+    ("invite_test", "Вам назначен тест: {title}\n\nНажмите кнопку ниже, чтобы начать прохождение теста."),
+    ("invite_presentation", "Вам назначена презентация: {title}\n\nКоличество тем: {themes}\nСрок выполнения: {hours} часов\n\nНажмите кнопку ниже, чтобы просмотреть задание."),
+    ("test_graded", "Ваш тест \"{title}\" проверен!\n\nРезультат: {percentage}%\n\nВы можете посмотреть подробности и оценку в профиле, нажав кнопку 'История активности'.\n\nОкончательное решение мы вам объявим немного позже."),
+    ("presentation_graded", "Ваша презентация по тесту \"{title}\" проверена!\n\nОценка: {grade}/100\nКомментарий: {comment}\n\nВы можете посмотреть подробности и оценку в профиле, нажав кнопку 'История активности'.\n\nОкончательное решение мы вам объявим немного позже."),
+    ("no_comment", "Без комментария"),
+    ("results_shown", "Тест отправлен. Ваш результат ниже."),
+    ("results_sent_to_hr", "Тест отправлен. Результаты переданы HR."),
+    ("results_pending_review", "Тест отправлен. Часть ответов проверит HR — результат появится после проверки."),
+];
+
+const TG: &[(&str, &str)] = &[
+    ("test_expired", "Мӯҳлати пайванди тест гузаштааст. Барои пайванди нав ба рекрутер муроҷиат кунед."),
+    ("time_up", "Вақт тамом шуд. Ҷавобҳои шумо худкор фиристода шуданд."),
+    ("submit_confirmation", "Ҷавобҳоро фиристед? Пас аз фиристодан онҳоро тағйир додан мумкин нест."),
+    ("submit_success", "Ҷавобҳо фиристода шуданд. Ташаккур!"),
+    ("violation_tab_switch", "Шумо саҳифаи тестро тарк кардед. Ин вайронкунӣ сабт карда мешавад."),
+    ("violation_copy_paste", "Нусхабардорӣ ва гузоштан ҳангоми тест манъ аст."),
+    ("violation_fullscreen", "Ҳангоми тест аз реҷаи пурраи экран набароед."),
+    ("violation_final_warning", "Огоҳии охирин: ҳангоми вайронкунии навбатӣ тест анҷом дода мешавад."),
+    ("profile_button", "Профил"),
+    ("invite_test", "Ба шумо тест таъин шуд: {title}\n\nБарои оғози тест тугмаи поёнро пахш кунед."),
+    ("invite_presentation", "Ба шумо презентатсия таъин шуд: {title}\n\nШумораи мавзӯъҳо: {themes}\nМӯҳлати иҷро: {hours} соат\n\nБарои дидани супориш тугмаи поёнро пахш кунед."),
+    ("test_graded", "Тести шумо \"{title}\" санҷида шуд!\n\nНатиҷа: {percentage}%\n\nТафсилот ва баҳоро дар профил бо пахши тугмаи 'Таърихи фаъолият' дидан мумкин аст.\n\nҚарори ниҳоиро каме дертар ба шумо хабар медиҳем."),
+    ("presentation_graded", "Презентатсияи шумо аз рӯи тести \"{title}\" санҷида шуд!\n\nБаҳо: {grade}/100\nШарҳ: {comment}\n\nТафсилот ва баҳоро дар профил бо пахши тугмаи 'Таърихи фаъолият' дидан мумкин аст.\n\nҚарори ниҳоиро каме дертар ба шумо хабар медиҳем."),
+    ("no_comment", "Бе шарҳ"),
+    ("results_shown", "Тест фиристода шуд. Натиҷаи шумо дар поён."),
+    ("results_sent_to_hr", "Тест фиристода шуд. Натиҷаҳо ба HR фиристода шуданд."),
+    ("results_pending_review", "Тест фиристода шуд. Баъзе ҷавобҳоро HR месанҷад — натиҷа пас аз санҷиш пайдо мешавад."),
+];
+
+/// Lower-cased primary subtag (`"TG-tj"` → `"tg"`) if it is supported.
+pub fn supported_lang(lang: &str) -> Option<&'static str> {
+    let primary = lang.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    SUPPORTED_LANGS.iter().copied().find(|l| *l == primary)
+}
+
+/// Like [`supported_lang`], but unknown or missing languages become Russian.
+pub fn resolve_lang(lang: Option<&str>) -> &'static str {
+    lang.and_then(supported_lang).unwrap_or(DEFAULT_LANG)
+}
+
+fn table(lang: &str) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        "tg" => TG,
+        _ => RU,
+    }
+}
+
+fn find(table: &[(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// `key` in `lang`, else in Russian, else the key itself.
+pub fn text<'a>(lang: &str, key: &'a str) -> &'a str {
+    find(table(resolve_lang(Some(lang))), key)
+        .or_else(|| find(RU, key))
+        .unwrap_or(key)
+}
+
+/// [`text`] with `{name}` placeholders replaced from `args`.
+pub fn render(lang: &str, key: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(text(lang, key).to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// Every [`UI_KEYS`] string for `lang`, with Russian filling any gaps.
+pub fn ui_strings(lang: &str) -> BTreeMap<&'static str, &'static str> {
+    UI_KEYS.iter().map(|key| (*key, text(lang, key))).collect()
+}
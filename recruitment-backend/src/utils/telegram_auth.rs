@@ -1,57 +1,73 @@
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
-use std::collections::HashMap;
 
 type HmacSha256 = Hmac<Sha256>;
 
-pub fn verify_telegram_data(init_data: &str, bot_token: &str) -> Option<i64> {
-    let mut params: HashMap<String, String> = HashMap::new();
-    for pair in init_data.split('&') {
-        let mut parts = pair.splitn(2, '=');
-        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-            let decoded_value = url::form_urlencoded::parse(value.as_bytes())
-                .next()
-                .map(|(_, v)| v.to_string())
-                .unwrap_or(value.to_string());
-            params.insert(key.to_string(), decoded_value);
+/// How far `auth_date` may lie in the future, for clock skew with Telegram.
+const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitDataError {
+    /// Missing or wrong signature, or no signed user.
+    Invalid,
+    /// Signed, but `auth_date` is older than the allowed age; the WebApp has
+    /// to be reopened. Stops a captured `initData` from being replayed.
+    Expired,
+}
+
+/// Checks a Telegram WebApp `initData` string against `bot_token` and returns
+/// the signed `user.id`. The data-check-string is built from the URL-decoded
+/// fields, sorted by key, as described in the WebApp docs. `auth_date` must be
+/// at most `max_age_secs` before `now` (unix seconds).
+pub fn verify_telegram_data(
+    init_data: &str,
+    bot_token: &str,
+    max_age_secs: u64,
+    now: i64,
+) -> Result<i64, InitDataError> {
+    let user_id = verify_signature(init_data, bot_token).ok_or(InitDataError::Invalid)?;
+    let auth_date: i64 = url::form_urlencoded::parse(init_data.trim().as_bytes())
+        .find(|(key, _)| key == "auth_date")
+        .and_then(|(_, value)| value.parse().ok())
+        .ok_or(InitDataError::Invalid)?;
+    let age = now - auth_date;
+    if age < -MAX_CLOCK_SKEW_SECS || age > max_age_secs as i64 {
+        return Err(InitDataError::Expired);
+    }
+    Ok(user_id)
+}
+
+fn verify_signature(init_data: &str, bot_token: &str) -> Option<i64> {
+    let mut hash = None;
+    let mut user = None;
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for (key, value) in url::form_urlencoded::parse(init_data.trim().as_bytes()) {
+        match key.as_ref() {
+            "hash" => hash = Some(value.into_owned()),
+            "user" => {
+                user = Some(value.to_string());
+                pairs.push((key.into_owned(), value.into_owned()));
+            }
+            _ => pairs.push((key.into_owned(), value.into_owned())),
         }
     }
+    let hash = hex::decode(hash?).ok()?;
 
-    let hash = params.get("hash")?;
-    
-    let mut pairs: Vec<(String, String)> = init_data.split('&')
-        .filter_map(|pair| {
-            let mut parts = pair.splitn(2, '=');
-            let key = parts.next()?;
-            let value = parts.next()?;
-            if key == "hash" { return None; }
-            Some((key.to_string(), value.to_string()))
-        })
-        .collect();
-        
     pairs.sort_by(|a, b| a.0.cmp(&b.0));
-    
-    let data_check_string = pairs.iter()
+    let data_check_string = pairs
+        .iter()
         .map(|(k, v)| format!("{}={}", k, v))
         .collect::<Vec<_>>()
         .join("\n");
 
-    let secret_key = HmacSha256::new_from_slice("WebAppData".as_bytes())
-        .and_then(|mut mac| {
-            mac.update(bot_token.as_bytes());
-            Ok(mac.finalize().into_bytes())
-        })
-        .ok()?;
+    let mut secret = HmacSha256::new_from_slice(b"WebAppData").ok()?;
+    secret.update(bot_token.as_bytes());
+    let secret_key = secret.finalize().into_bytes();
 
     let mut mac = HmacSha256::new_from_slice(&secret_key).ok()?;
     mac.update(data_check_string.as_bytes());
-    let calculated_hash = hex::encode(mac.finalize().into_bytes());
-
-    if calculated_hash.eq_ignore_ascii_case(hash) {
-        let user_encoded = params.get("user")?;
-        let user: serde_json::Value = serde_json::from_str(user_encoded).ok()?;
-        user.get("id")?.as_i64()
-    } else {
-        None
-    }
+    mac.verify_slice(&hash).ok()?;
+
+    let user: serde_json::Value = serde_json::from_str(&user?).ok()?;
+    user.get("id")?.as_i64()
 }
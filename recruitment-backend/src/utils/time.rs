@@ -11,3 +11,29 @@ pub fn to_rfc3339(dt: DateTime<Utc>) -> String {
 pub fn from_rfc3339(s: &str) -> anyhow::Result<DateTime<Utc>> {
     Ok(DateTime::parse_from_rfc3339(s)?.with_timezone(&Utc))
 }
+
+/// Timezone used for candidates who have not picked one.
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Parses an IANA timezone name such as `Asia/Dushanbe`.
+pub fn parse_timezone(name: &str) -> Option<chrono_tz::Tz> {
+    name.trim().parse().ok()
+}
+
+/// All IANA timezone names the server can convert to, alphabetically.
+pub fn supported_timezones() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name()).collect();
+    names.sort_unstable();
+    names
+}
+
+/// `dd.mm.yyyy HH:MM (zone)` in the candidate's timezone, for Telegram
+/// messages. Missing or unknown zones fall back to UTC.
+pub fn format_deadline(at: DateTime<Utc>, timezone: Option<&str>) -> String {
+    let tz = timezone.and_then(parse_timezone).unwrap_or(chrono_tz::UTC);
+    format!(
+        "{} ({})",
+        at.with_timezone(&tz).format("%d.%m.%Y %H:%M"),
+        tz.name()
+    )
+}
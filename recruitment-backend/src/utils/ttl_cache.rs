@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct TtlCache<K, V> {
+    inner: Arc<Mutex<HashMap<K, (Instant, V)>>>,
+    ttl: Duration,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut map = self.inner.lock().expect("ttl cache mutex poisoned");
+        match map.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                map.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut map = self.inner.lock().expect("ttl cache mutex poisoned");
+        map.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        map.insert(key, (Instant::now(), value));
+    }
+}
@@ -0,0 +1,177 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::queue_service::{
+    dead_job_alert, AiQueueService, MAX_JOB_ATTEMPTS,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+async fn enqueue(queue: &AiQueueService, profession: &str) -> Uuid {
+    queue
+        .enqueue(
+            json!({ "profession": profession }),
+            false,
+            None,
+            None,
+            None,
+            None,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap()
+}
+
+/// Marks the job as claimed `attempts` times and currently running.
+async fn claimed(pool: &PgPool, id: Uuid, attempts: i32) {
+    sqlx::query(
+        "UPDATE ai_jobs SET status = 'running', started_at = NOW() - INTERVAL '2 hours', attempts = $2 WHERE id = $1",
+    )
+    .bind(id)
+    .bind(attempts)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[test]
+fn dead_job_alert_names_the_job_and_its_reason() {
+    let id = Uuid::new_v4();
+    let alert = dead_job_alert(id, "Accountant", 5, "upstream 502");
+    assert!(alert.contains(&id.to_string()));
+    assert!(alert.contains("Accountant"));
+    assert!(alert.contains("5 times"));
+    assert!(alert.ends_with("upstream 502"));
+    assert!(alert.contains(&format!("/api/integration/ai-jobs/{}/retry", id)));
+
+    let long = "x".repeat(5000);
+    assert!(dead_job_alert(id, "", 5, &long).chars().count() < 1500);
+}
+
+#[tokio::test]
+async fn failures_are_retried_then_dead_lettered_and_can_be_retried_by_hand() {
+    let pool = setup_pool().await;
+    let queue = AiQueueService::new(pool.clone());
+    let id = enqueue(&queue, "Dead letter").await;
+
+    claimed(&pool, id, 1).await;
+    queue.record_failure(id, "timeout").await.unwrap();
+    let job = queue.get(id).await.unwrap();
+    assert_eq!(job["status"], "failed", "an early failure is retried");
+    assert_eq!(job["error"], "timeout");
+    assert_eq!(job["dead_letter_reason"], Value::Null);
+
+    claimed(&pool, id, MAX_JOB_ATTEMPTS).await;
+    queue.record_failure(id, "model refused").await.unwrap();
+    let job = queue.get(id).await.unwrap();
+    assert_eq!(job["status"], "dead");
+    assert_eq!(job["dead_letter_reason"], "model refused");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/ai-jobs/dead",
+            get(recruitment_backend::routes::integration::list_dead_ai_jobs),
+        )
+        .route(
+            "/api/integration/ai-jobs/:id/retry",
+            post(recruitment_backend::routes::integration::retry_ai_job),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let call = |method: &str, uri: String| {
+        let app = app.clone();
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        async move {
+            let resp = app.oneshot(request).await.unwrap();
+            let status = resp.status();
+            let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null))
+        }
+    };
+
+    let (status, body) = call("GET", "/api/integration/ai-jobs/dead".into()).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|job| job["id"] == json!(id))
+        .expect("dead job listed");
+    assert_eq!(listed["payload"]["profession"], "Dead letter");
+    assert_eq!(listed["attempts"], MAX_JOB_ATTEMPTS);
+    assert_eq!(listed["dead_letter_reason"], "model refused");
+
+    let (status, _) = call("POST", format!("/api/integration/ai-jobs/{}/retry", id)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let job = queue.get(id).await.unwrap();
+    assert_eq!(job["status"], "pending");
+    assert_eq!(job["attempts"], 0);
+    assert_eq!(job["dead_letter_reason"], Value::Null);
+
+    let (status, body) = call("POST", format!("/api/integration/ai-jobs/{}/retry", id)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "ai_job_not_dead");
+    let (status, _) = call(
+        "POST",
+        format!("/api/integration/ai-jobs/{}/retry", Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM ai_jobs WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stale_jobs_out_of_attempts_are_dead_lettered() {
+    let pool = setup_pool().await;
+    let queue = AiQueueService::new(pool.clone());
+    let fresh = enqueue(&queue, "Stale retry").await;
+    let spent = enqueue(&queue, "Stale dead").await;
+    claimed(&pool, fresh, 1).await;
+    claimed(&pool, spent, MAX_JOB_ATTEMPTS).await;
+
+    queue.requeue_stale(30).await.unwrap();
+    assert_eq!(queue.get(fresh).await.unwrap()["status"], "pending");
+    let job = queue.get(spent).await.unwrap();
+    assert_eq!(job["status"], "dead");
+    assert!(job["dead_letter_reason"].as_str().is_some());
+
+    sqlx::query("DELETE FROM ai_jobs WHERE id = ANY($1)")
+        .bind(vec![fresh, spent])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
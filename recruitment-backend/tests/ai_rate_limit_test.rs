@@ -0,0 +1,355 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use recruitment_backend::services::ai_service::{
+    classify_completion, completion_content, parse_retry_after, AIService, AiCallError,
+    AiFallbackPolicy, DifficultyDistribution, VacancyLanguage, DEFAULT_PER_MODEL_TIMEOUT_SECS,
+    MAX_AI_RETRIES,
+};
+use serde_json::json;
+
+/// Status, `Retry-After` header and body of one canned response.
+type Reply = (u16, Option<&'static str>, String);
+
+/// Canned provider responses, served in order; the last one repeats.
+#[derive(Clone, Default)]
+struct Script {
+    replies: Arc<Mutex<VecDeque<Reply>>>,
+    calls: Arc<Mutex<usize>>,
+    models: Arc<Mutex<Vec<String>>>,
+}
+
+async fn completions(State(script): State<Script>, body: String) -> Response {
+    *script.calls.lock().unwrap() += 1;
+    let request: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+    if let Some(model) = request["model"].as_str() {
+        script.models.lock().unwrap().push(model.to_string());
+    }
+    let (status, retry_after, body) = {
+        let mut replies = script.replies.lock().unwrap();
+        if replies.len() > 1 {
+            replies.pop_front().unwrap()
+        } else {
+            replies.front().cloned().unwrap()
+        }
+    };
+    let mut resp = (StatusCode::from_u16(status).unwrap(), body).into_response();
+    resp.headers_mut()
+        .insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+    if let Some(value) = retry_after {
+        resp.headers_mut()
+            .insert(header::RETRY_AFTER, value.parse().unwrap());
+    }
+    resp
+}
+
+async fn mock_provider(replies: Vec<(u16, Option<&'static str>, String)>) -> (AIService, Script) {
+    let script = Script {
+        replies: Arc::new(Mutex::new(replies.into())),
+        ..Default::default()
+    };
+    let app = Router::new()
+        .route("/chat/completions", post(completions))
+        .with_state(script.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let ai = AIService::new(
+        "sk-test".into(),
+        format!("http://{}", addr),
+        reqwest::Client::new(),
+    );
+    (ai, script)
+}
+
+fn completion(content: &str) -> String {
+    json!({
+        "model": "gpt-4o",
+        "choices": [{ "message": { "role": "assistant", "content": content } }],
+        "usage": { "prompt_tokens": 10, "completion_tokens": 5 }
+    })
+    .to_string()
+}
+
+fn questions_completion() -> String {
+    completion(
+        &json!({
+            "questions": [{
+                "type": "multiple_choice",
+                "difficulty": "medium",
+                "question": "Что такое ownership?",
+                "options": ["A", "B", "C", "D"],
+                "correct_answer": 1
+            }]
+        })
+        .to_string(),
+    )
+}
+
+async fn generate(ai: &AIService) -> recruitment_backend::error::Result<Vec<String>> {
+    ai.generate_test(
+        "Backend Developer",
+        &[],
+        1,
+        &DifficultyDistribution::default(),
+        VacancyLanguage::Russian,
+    )
+    .await
+    .map(|out| out.logs)
+}
+
+#[test]
+fn parses_retry_after_forms() {
+    assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+    assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon"), None);
+}
+
+#[test]
+fn classifies_failure_modes() {
+    let rate_limited = classify_completion(
+        429,
+        Some("5"),
+        r#"{"error":{"code":429,"message":"Rate limit exceeded: free-models-per-min"}}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        rate_limited,
+        AiCallError::RateLimited {
+            retry_after: Some(Duration::from_secs(5)),
+            message: "Rate limit exceeded: free-models-per-min".into(),
+        }
+    );
+
+    let unavailable = classify_completion(503, None, "upstream overloaded").unwrap_err();
+    assert_eq!(unavailable.reason(), "model_unavailable");
+    assert!(unavailable.to_string().contains("upstream overloaded"));
+
+    let in_200 = classify_completion(
+        200,
+        None,
+        r#"{"error":{"code":502,"message":"Provider returned error"}}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        in_200,
+        AiCallError::ModelUnavailable {
+            status: Some(502),
+            message: "Provider returned error".into(),
+        }
+    );
+
+    let limited_in_200 = classify_completion(
+        200,
+        None,
+        r#"{"choices":[{"error":{"code":"429","message":"Upstream rate limited"}}]}"#,
+    )
+    .unwrap_err();
+    assert_eq!(limited_in_200.reason(), "rate_limited");
+
+    assert_eq!(
+        classify_completion(200, None, "<html>oops</html>")
+            .unwrap_err()
+            .reason(),
+        "bad_output"
+    );
+
+    let body = classify_completion(200, None, &completion("not json")).unwrap();
+    assert_eq!(
+        completion_content(&body).unwrap_err().reason(),
+        "bad_output"
+    );
+    let body = classify_completion(200, None, &completion(r#"{"ok":true}"#)).unwrap();
+    assert_eq!(completion_content(&body).unwrap(), json!({ "ok": true }));
+}
+
+#[tokio::test]
+async fn rate_limit_is_retried_after_retry_after() {
+    let limited = r#"{"error":{"code":429,"message":"Rate limit exceeded"}}"#.to_string();
+    let (ai, script) = mock_provider(vec![
+        (429, Some("0"), limited.clone()),
+        (200, None, questions_completion()),
+    ])
+    .await;
+
+    let logs = generate(&ai).await.expect("succeeds after one retry");
+    assert_eq!(*script.calls.lock().unwrap(), 2);
+    assert!(logs
+        .iter()
+        .any(|l| l.contains("rate_limited: Rate limit exceeded, retry 1/2")));
+
+    let (ai, script) = mock_provider(vec![(429, Some("0"), limited)]).await;
+    let err = generate(&ai).await.unwrap_err();
+    assert_eq!(err.code(), "ai_rate_limited");
+    assert_eq!(*script.calls.lock().unwrap(), 1 + MAX_AI_RETRIES as usize);
+}
+
+#[tokio::test]
+async fn long_retry_after_fails_without_waiting() {
+    let (ai, script) = mock_provider(vec![(429, Some("3600"), "{}".into())]).await;
+    let err = generate(&ai).await.unwrap_err();
+    assert_eq!(err.code(), "ai_rate_limited");
+    assert_eq!(*script.calls.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn unavailable_model_and_bad_output_fail_immediately() {
+    let cases: [(u16, String, &str); 3] = [
+        (503, "Service Unavailable".into(), "ai_model_unavailable"),
+        (
+            200,
+            r#"{"error":{"code":502,"message":"Provider returned error"}}"#.into(),
+            "ai_model_unavailable",
+        ),
+        (
+            200,
+            completion("Sorry, I can't help with that."),
+            "ai_bad_output",
+        ),
+    ];
+    for (status, body, code) in cases {
+        let (ai, script) = mock_provider(vec![(status, None, body)]).await;
+        let err = generate(&ai).await.unwrap_err();
+        assert_eq!(err.code(), code);
+        assert_eq!(*script.calls.lock().unwrap(), 1);
+    }
+
+    let unreachable = AIService::new(
+        "sk-test".into(),
+        "http://127.0.0.1:9".into(),
+        reqwest::Client::new(),
+    );
+    assert_eq!(
+        generate(&unreachable).await.unwrap_err().code(),
+        "ai_model_unavailable"
+    );
+}
+
+#[tokio::test]
+async fn failed_job_error_names_the_reason() {
+    let (ai, _) = mock_provider(vec![(
+        200,
+        None,
+        r#"{"error":{"code":502,"message":"Provider returned error"}}"#.into(),
+    )])
+    .await;
+    let err = generate(&ai).await.unwrap_err();
+    // This is what the AI queue stores in ai_jobs.error.
+    assert_eq!(
+        err.to_string(),
+        "model_unavailable (502): Provider returned error"
+    );
+}
+
+fn models(names: &[&str]) -> Vec<String> {
+    names.iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn sequential_fallback_is_the_default() {
+    assert_eq!(
+        AiFallbackPolicy::default(),
+        AiFallbackPolicy::Sequential {
+            per_model_timeout_secs: DEFAULT_PER_MODEL_TIMEOUT_SECS
+        }
+    );
+}
+
+#[tokio::test]
+async fn sequential_policy_calls_the_next_model_only_on_failure() {
+    let policy = AiFallbackPolicy::Sequential {
+        per_model_timeout_secs: 30,
+    };
+    let (ai, script) = mock_provider(vec![(200, None, completion(r#"{"ok":true}"#))]).await;
+    let mut logs = Vec::new();
+    let (content, _) = ai
+        .chat_json_multi(json!({}), &models(&["free", "paid"]), &policy, &mut logs)
+        .await
+        .unwrap();
+    assert_eq!(content, json!({ "ok": true }));
+    assert_eq!(*script.models.lock().unwrap(), models(&["free"]));
+
+    let (ai, script) = mock_provider(vec![
+        (503, None, "Service Unavailable".into()),
+        (200, None, completion(r#"{"ok":true}"#)),
+    ])
+    .await;
+    let mut logs = Vec::new();
+    ai.chat_json_multi(json!({}), &models(&["free", "paid"]), &policy, &mut logs)
+        .await
+        .unwrap();
+    assert_eq!(*script.models.lock().unwrap(), models(&["free", "paid"]));
+    assert!(logs.iter().any(|l| l == "Falling back from free to paid"));
+
+    let (ai, _) = mock_provider(vec![(503, None, "Service Unavailable".into())]).await;
+    let err = ai
+        .chat_json_multi(
+            json!({}),
+            &models(&["free", "paid"]),
+            &policy,
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "ai_model_unavailable");
+}
+
+#[tokio::test]
+async fn sequential_policy_moves_on_after_the_per_model_timeout() {
+    let (ai, script) = mock_provider(vec![(200, None, completion(r#"{"ok":true}"#))]).await;
+    let mut logs = Vec::new();
+    let (content, _) = ai
+        .chat_json_multi(
+            json!({}),
+            &models(&["slow", "fast"]),
+            &AiFallbackPolicy::Sequential {
+                per_model_timeout_secs: 0,
+            },
+            &mut logs,
+        )
+        .await
+        .unwrap();
+    assert_eq!(content, json!({ "ok": true }));
+    assert!(logs.iter().any(|l| l.contains("slow timed out after 0s")));
+    assert_eq!(script.models.lock().unwrap().last().unwrap(), "fast");
+}
+
+#[tokio::test]
+async fn parallel_policy_calls_every_model() {
+    let (ai, script) = mock_provider(vec![(200, None, completion(r#"{"ok":true}"#))]).await;
+    let (content, _) = ai
+        .chat_json_multi(
+            json!({}),
+            &models(&["a", "b"]),
+            &AiFallbackPolicy::Parallel,
+            &mut Vec::new(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(content, json!({ "ok": true }));
+    assert!(*script.calls.lock().unwrap() >= 1);
+
+    let (ai, script) = mock_provider(vec![(503, None, "Service Unavailable".into())]).await;
+    let ai = ai
+        .with_models(models(&["a", "b"]))
+        .with_fallback_policy(AiFallbackPolicy::Parallel);
+    assert_eq!(
+        generate(&ai).await.unwrap_err().code(),
+        "ai_model_unavailable"
+    );
+    let mut called = script.models.lock().unwrap().clone();
+    called.sort();
+    assert_eq!(called, models(&["a", "b"]));
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::ai_service::{GenerationOutput, ModelUsage};
+use recruitment_backend::services::queue_service::AiQueueService;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn output(model: &str, prompt: i64, completion: i64, calls: i64) -> GenerationOutput {
+    GenerationOutput {
+        total_prompt_tokens: prompt,
+        total_completion_tokens: completion,
+        calls_made: calls,
+        usage_by_model: HashMap::from([(
+            model.to_string(),
+            ModelUsage {
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+                calls,
+            },
+        )]),
+        ..Default::default()
+    }
+}
+
+fn model_totals(summary: &Value, model: &str) -> Option<Value> {
+    summary["by_model"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["model"] == model)
+        .cloned()
+}
+
+#[tokio::test]
+async fn synchronous_generations_count_towards_usage() {
+    let pool = setup_pool().await;
+    let queue = AiQueueService::new(pool.clone());
+    let model = format!("usage-model-{}", Uuid::new_v4());
+
+    let job_id = queue
+        .enqueue(
+            json!({ "profession": "QA" }),
+            false,
+            None,
+            None,
+            None,
+            None,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    sqlx::query("UPDATE ai_jobs SET usage_by_model = $2 WHERE id = $1")
+        .bind(job_id)
+        .bind(json!({ &model: { "prompt_tokens": 100, "completion_tokens": 40, "calls": 1 } }))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    queue
+        .record_usage("generate_ai_test", &output(&model, 30, 20, 2))
+        .await
+        .unwrap();
+    queue
+        .record_usage("generate_test_spec", &output(&model, 5, 5, 1))
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    let summary = queue
+        .usage_summary(now - Duration::hours(1), now + Duration::hours(1))
+        .await
+        .unwrap();
+    let totals = model_totals(&summary, &model).expect("model in report");
+    assert_eq!(totals["prompt_tokens"], 135);
+    assert_eq!(totals["completion_tokens"], 65);
+    assert_eq!(totals["calls"], 4);
+
+    let daily: Vec<&Value> = summary["daily"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["model"] == model.as_str())
+        .collect();
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily[0]["prompt_tokens"], 135);
+
+    let earlier = queue
+        .usage_summary(now - Duration::days(3), now - Duration::days(2))
+        .await
+        .unwrap();
+    assert!(model_totals(&earlier, &model).is_none());
+}
+
+#[tokio::test]
+async fn generations_without_usage_are_not_recorded() {
+    let pool = setup_pool().await;
+    let queue = AiQueueService::new(pool.clone());
+    let source = format!("usage-source-{}", Uuid::new_v4());
+
+    queue
+        .record_usage(&source, &GenerationOutput::default())
+        .await
+        .unwrap();
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ai_usage WHERE source = $1")
+        .bind(&source)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+}
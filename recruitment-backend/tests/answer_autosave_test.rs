@@ -0,0 +1,153 @@
+use std::env;
+
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn parallel_autosaves_keep_every_answer() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    env::set_var("PUBLIC_RPS", "100");
+    env::set_var("INTEGRATION_RPS", "100");
+
+    recruitment_backend::config::init_config().expect("init config");
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    let _ = sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, $3, $4, $5, $6)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind("Autosave User")
+    .bind(format!("autosave_{}@example.com", creator))
+    .bind("hr")
+    .bind(true)
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let questions = (1..=20)
+        .map(|i| recruitment_backend::dto::integration_dto::CreateQuestion {
+            question_type: recruitment_backend::models::question::QuestionType::MultipleChoice,
+            question: format!("Question {}", i),
+            points: 1,
+            difficulty: Default::default(),
+            details: recruitment_backend::models::question::QuestionDetails::MultipleChoice(
+                recruitment_backend::models::question::MultipleChoiceDetails {
+                    options: vec!["a".into(), "b".into(), "c".into(), "d".into()],
+                    correct_answer: 0,
+                    explanation: None,
+                },
+            ),
+        })
+        .collect();
+
+    let test_service = recruitment_backend::services::test_service::TestService::new(pool.clone());
+    let test = test_service
+        .create_test(
+            recruitment_backend::dto::integration_dto::CreateTestPayload {
+                title: "Autosave Test".into(),
+                external_id: None,
+                description: None,
+                instructions: None,
+                questions: Some(questions),
+                duration_minutes: 30,
+                passing_score: 50.0,
+                shuffle_questions: Some(false),
+                shuffle_options: Some(false),
+                show_results_immediately: Some(false),
+                test_type: Some("question_based".to_string()),
+                presentation_themes: None,
+                presentation_extra_info: None,
+                show_honesty_declaration: None,
+                declaration_text: None,
+                prerequisite_test_id: None,
+                default_invite_expiry_hours: None,
+                reminder_hours_before: None,
+                passing_score_mcq: None,
+                passing_score_open: None,
+                share_results_details: None,
+                max_grace_seconds: None,
+                max_concurrent_takers: None,
+                language: None,
+                reveal_correct_answers: None,
+            },
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create test");
+
+    let attempt_service =
+        recruitment_backend::services::attempt_service::AttemptService::new(pool.clone());
+    let invite = attempt_service
+        .create_invite(
+            test.id,
+            recruitment_backend::services::attempt_service::InviteCandidate {
+                external_id: None,
+                name: "Racer".into(),
+                email: format!("racer_{}@example.com", Uuid::new_v4()),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            recruitment_backend::services::attempt_service::InviteSource::Integration,
+        )
+        .await
+        .expect("invite");
+    let token = invite.access_token;
+    attempt_service
+        .start_attempt_by_token(&token, true, None, None)
+        .await
+        .expect("start");
+
+    let mut handles = Vec::new();
+    for question_id in 1..=20 {
+        let svc = attempt_service.clone();
+        let token = token.clone();
+        handles.push(tokio::spawn(async move {
+            svc.save_answer_by_token(
+                &token,
+                recruitment_backend::dto::public_dto::SaveAnswerRequest {
+                    question_id,
+                    answer: serde_json::json!({ "selected": 0 }),
+                    time_spent_seconds: 1,
+                    marked_for_review: None,
+                },
+            )
+            .await
+        }));
+    }
+    for handle in handles {
+        handle.await.expect("join").expect("save answer");
+    }
+
+    let attempt = attempt_service
+        .get_attempt_by_id(invite.attempt_id)
+        .await
+        .expect("reload attempt");
+    let answers = attempt
+        .answers
+        .and_then(|a| a.as_array().cloned())
+        .unwrap_or_default();
+    let mut ids: Vec<i64> = answers
+        .iter()
+        .filter_map(|a| a.get("question_id").and_then(|v| v.as_i64()))
+        .collect();
+    ids.sort();
+    assert_eq!(ids, (1..=20).collect::<Vec<i64>>());
+}
@@ -0,0 +1,60 @@
+use chrono::{Duration, TimeZone, Utc};
+use recruitment_backend::models::answer_log::AnswerLog;
+use recruitment_backend::services::attempt_service::build_answer_change_report;
+use serde_json::json;
+use uuid::Uuid;
+
+fn log(attempt_id: Uuid, question_id: i32, answer: serde_json::Value, secs: i64) -> AnswerLog {
+    AnswerLog {
+        id: Uuid::new_v4(),
+        attempt_id,
+        question_id,
+        answer_value: answer,
+        time_spent_seconds: None,
+        created_at: Utc.with_ymd_and_hms(2026, 5, 1, 10, 0, 0).unwrap() + Duration::seconds(secs),
+    }
+}
+
+#[test]
+fn counts_value_changes_and_flags_suspicious_questions() {
+    let attempt_id = Uuid::new_v4();
+    let logs = vec![
+        log(attempt_id, 1, json!(0), 0),
+        log(attempt_id, 1, json!(0), 5),
+        log(attempt_id, 1, json!(1), 10),
+        log(attempt_id, 1, json!(2), 20),
+        log(attempt_id, 1, json!(3), 30),
+        log(attempt_id, 1, json!(0), 40),
+        log(attempt_id, 2, json!("draft"), 50),
+        log(attempt_id, 2, json!("final"), 298),
+        log(attempt_id, 3, json!(2), 60),
+    ];
+    let submitted_at = Utc.with_ymd_and_hms(2026, 5, 1, 10, 5, 0).unwrap();
+
+    let report = build_answer_change_report(attempt_id, &logs, Some(submitted_at));
+
+    assert_eq!(report.total_changes, 5);
+    assert_eq!(report.flagged_questions, vec![1, 2]);
+
+    let q1 = &report.questions[0];
+    assert_eq!((q1.saves, q1.changes), (6, 4));
+    assert_eq!(q1.flags, vec!["frequent_changes"]);
+
+    let q2 = &report.questions[1];
+    assert_eq!(q2.changes, 1);
+    assert_eq!(q2.seconds_before_submit, Some(2));
+    assert_eq!(q2.flags, vec!["last_second_answer"]);
+
+    let q3 = &report.questions[2];
+    assert_eq!(q3.changes, 0);
+    assert!(q3.flags.is_empty());
+}
+
+#[test]
+fn unsubmitted_attempts_are_not_flagged_for_timing() {
+    let attempt_id = Uuid::new_v4();
+    let logs = vec![log(attempt_id, 1, json!(1), 0)];
+    let report = build_answer_change_report(attempt_id, &logs, None);
+    assert_eq!(report.questions[0].seconds_before_submit, None);
+    assert!(report.flagged_questions.is_empty());
+}
@@ -0,0 +1,137 @@
+use std::env;
+
+use chrono::{Duration, TimeZone, Utc};
+use recruitment_backend::error::Error;
+use recruitment_backend::services::candidate_service::{reapply_available_at, CandidateService};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+async fn seed(pool: &PgPool) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, status) VALUES ($1, 'Applicant', $2, 'new')",
+    )
+    .bind(id)
+    .bind(format!("apply_{}@example.com", id))
+    .execute(pool)
+    .await
+    .expect("seed candidate");
+    id
+}
+
+async fn live_applications(pool: &PgPool, candidate_id: Uuid) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM candidate_applications WHERE candidate_id = $1 AND status <> 'withdrawn'",
+    )
+    .bind(candidate_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[test]
+fn cooldown_counts_from_the_rejection() {
+    let rejected_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        reapply_available_at(rejected_at, 30),
+        Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap()
+    );
+    assert_eq!(reapply_available_at(rejected_at, 0), rejected_at);
+}
+
+#[tokio::test]
+async fn double_tap_returns_the_existing_application() {
+    let pool = setup_pool().await;
+    let svc = CandidateService::new(pool.clone());
+    let candidate = seed(&pool).await;
+
+    let first = svc.apply_to_vacancy(candidate, 9101).await.unwrap();
+    assert!(!first.already_applied);
+
+    let second = svc.apply_to_vacancy(candidate, 9101).await.unwrap();
+    assert!(second.already_applied);
+    assert_eq!(second.application.id, first.application.id);
+    assert_eq!(live_applications(&pool, candidate).await, 1);
+}
+
+#[tokio::test]
+async fn can_apply_again_after_withdrawing() {
+    let pool = setup_pool().await;
+    let svc = CandidateService::new(pool.clone());
+    let candidate = seed(&pool).await;
+
+    let first = svc.apply_to_vacancy(candidate, 9102).await.unwrap();
+    svc.withdraw_application(candidate, first.application.id)
+        .await
+        .unwrap();
+
+    let again = svc.apply_to_vacancy(candidate, 9102).await.unwrap();
+    assert!(!again.already_applied);
+    assert_ne!(again.application.id, first.application.id);
+    assert_eq!(again.application.status, "active");
+    assert_eq!(live_applications(&pool, candidate).await, 1);
+}
+
+#[tokio::test]
+async fn rejected_candidates_wait_out_the_cooldown() {
+    let pool = setup_pool().await;
+    let svc = CandidateService::new(pool.clone());
+    let candidate = seed(&pool).await;
+
+    let first = svc.apply_to_vacancy(candidate, 9103).await.unwrap();
+    svc.update_status(candidate, "rejected".into(), false)
+        .await
+        .unwrap();
+
+    let err = svc.apply_to_vacancy(candidate, 9103).await.unwrap_err();
+    assert_eq!(err.code(), "reapply_cooldown");
+    let Error::Coded {
+        details: Some(details),
+        ..
+    } = err
+    else {
+        panic!("expected a coded error with details");
+    };
+    let retry_after: chrono::DateTime<Utc> =
+        serde_json::from_value(details["retry_after"].clone()).unwrap();
+    let cooldown = recruitment_backend::config::get_config().reapply_cooldown_days;
+    assert!(retry_after > Utc::now() + Duration::days(cooldown) - Duration::minutes(1));
+
+    // Other vacancies are unaffected.
+    assert!(
+        !svc.apply_to_vacancy(candidate, 9104)
+            .await
+            .unwrap()
+            .already_applied
+    );
+
+    sqlx::query("UPDATE candidate_applications SET updated_at = NOW() - make_interval(days => $2) WHERE id = $1")
+        .bind(first.application.id)
+        .bind(cooldown as i32 + 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let again = svc.apply_to_vacancy(candidate, 9103).await.unwrap();
+    assert!(!again.already_applied);
+    assert_eq!(again.application.id, first.application.id);
+    assert_eq!(again.application.status, "active");
+}
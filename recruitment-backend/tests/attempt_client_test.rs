@@ -0,0 +1,193 @@
+use std::env;
+use std::net::IpAddr;
+
+use chrono::Utc;
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::dto::public_dto::SubmitTestRequest;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    ip_change_entry, ip_change_flags, AttemptService,
+};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::json;
+use uuid::Uuid;
+
+fn ip(raw: &str) -> IpAddr {
+    raw.parse().unwrap()
+}
+
+#[test]
+fn ip_change_needs_two_different_known_addresses() {
+    let now = Utc::now();
+    let entry = ip_change_entry(Some(ip("203.0.113.7")), Some(ip("198.51.100.1")), now).unwrap();
+    assert_eq!(entry["type"], "ip_change");
+    assert_eq!(entry["start_ip"], "203.0.113.7");
+    assert_eq!(entry["submit_ip"], "198.51.100.1");
+
+    assert!(ip_change_entry(Some(ip("203.0.113.7")), Some(ip("203.0.113.7")), now).is_none());
+    assert!(ip_change_entry(None, Some(ip("203.0.113.7")), now).is_none());
+    assert!(ip_change_entry(Some(ip("203.0.113.7")), None, now).is_none());
+
+    let activity = json!([
+        { "type": "tab_switch", "tab_switches": 1 },
+        entry.clone(),
+    ]);
+    assert_eq!(ip_change_flags(Some(&activity)), vec![entry]);
+    assert!(ip_change_flags(None).is_empty());
+    assert!(ip_change_flags(Some(&json!({ "legacy": true }))).is_empty());
+}
+
+fn payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 30,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+#[tokio::test]
+async fn records_client_and_flags_ip_change_on_submit() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Client User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("client_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(payload("Client Capture"), creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+
+    let mut tokens = Vec::new();
+    for _ in 0..2 {
+        let token = Uuid::new_v4().simple().to_string();
+        sqlx::query(
+            r#"INSERT INTO test_attempts
+                   (test_id, candidate_name, candidate_email, access_token, expires_at,
+                    questions_snapshot, status)
+               VALUES ($1, 'Client Candidate', $2, $3, NOW() + INTERVAL '1 day', '[]', 'pending')"#,
+        )
+        .bind(test.id)
+        .bind(format!("client_{}@example.com", Uuid::new_v4()))
+        .bind(&token)
+        .execute(&pool)
+        .await
+        .expect("seed attempt");
+        tokens.push(token);
+    }
+
+    let svc = AttemptService::new(pool.clone());
+    let submit = || SubmitTestRequest {
+        answers: vec![],
+        status: None,
+    };
+
+    // Moved networks between start and submit.
+    let started = svc
+        .start_attempt_by_token(
+            &tokens[0],
+            false,
+            Some(ip("203.0.113.7")),
+            Some("Mozilla/5.0 (Start)".into()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(started.ip_address.map(|n| n.ip()), Some(ip("203.0.113.7")));
+    assert_eq!(started.user_agent.as_deref(), Some("Mozilla/5.0 (Start)"));
+
+    let (submitted, ..) = svc
+        .submit_attempt_by_token(
+            &tokens[0],
+            submit(),
+            Some(ip("198.51.100.1")),
+            Some("Mozilla/5.0 (Submit)".into()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        submitted.ip_address.map(|n| n.ip()),
+        Some(ip("203.0.113.7"))
+    );
+    assert_eq!(submitted.user_agent.as_deref(), Some("Mozilla/5.0 (Start)"));
+    let flags = ip_change_flags(submitted.suspicious_activity.as_ref());
+    assert_eq!(flags.len(), 1);
+    assert_eq!(flags[0]["start_ip"], "203.0.113.7");
+    assert_eq!(flags[0]["submit_ip"], "198.51.100.1");
+
+    // Same address throughout, and details missing at start are filled in on submit.
+    svc.start_attempt_by_token(&tokens[1], false, None, None)
+        .await
+        .unwrap();
+    let (submitted, ..) = svc
+        .submit_attempt_by_token(
+            &tokens[1],
+            submit(),
+            Some(ip("203.0.113.9")),
+            Some("Mozilla/5.0".into()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        submitted.ip_address.map(|n| n.ip()),
+        Some(ip("203.0.113.9"))
+    );
+    assert_eq!(submitted.user_agent.as_deref(), Some("Mozilla/5.0"));
+    assert!(ip_change_flags(submitted.suspicious_activity.as_ref()).is_empty());
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tests.delete_test(test.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
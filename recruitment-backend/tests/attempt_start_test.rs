@@ -0,0 +1,199 @@
+use std::env;
+
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::dto::public_dto::{SaveAnswerRequest, SubmitTestRequest};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    is_startable_status, AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+/// A one-question test; returns its id.
+async fn seed_test(pool: &PgPool) -> Uuid {
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Start User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("start_{}@example.com", creator))
+    .execute(pool)
+    .await
+    .unwrap();
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Start Test",
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [{
+            "type": "multiple_choice",
+            "question": "2 + 2?",
+            "points": 1,
+            "options": ["3", "4"],
+            "correct_answer": 1
+        }],
+    }))
+    .unwrap();
+    TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn invite(svc: &AttemptService, test_id: Uuid) -> (Uuid, String) {
+    let invite = svc
+        .create_invite(
+            test_id,
+            InviteCandidate {
+                external_id: None,
+                name: "Starter".into(),
+                email: format!("starter_{}@example.com", Uuid::new_v4()),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .expect("invite");
+    (invite.attempt_id, invite.access_token)
+}
+
+async fn cleanup(pool: &PgPool, test_id: Uuid) {
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn only_pending_and_in_progress_attempts_can_start() {
+    assert!(is_startable_status("pending"));
+    assert!(is_startable_status("in_progress"));
+    for status in [
+        "completed",
+        "timeout",
+        "escaped",
+        "needs_review",
+        "abandoned",
+    ] {
+        assert!(!is_startable_status(status), "{} must be final", status);
+    }
+}
+
+#[tokio::test]
+async fn concurrent_starts_share_one_start_time() {
+    let pool = setup_pool().await;
+    let test_id = seed_test(&pool).await;
+    let svc = AttemptService::new(pool.clone());
+    let (attempt_id, token) = invite(&svc, test_id).await;
+
+    let (a, b) = tokio::join!(
+        svc.start_attempt_by_token(&token, true, None, None),
+        svc.start_attempt_by_token(&token, true, None, None),
+    );
+    let (a, b) = (a.expect("first start"), b.expect("second start"));
+    assert_eq!(a.started_at, b.started_at);
+    assert_eq!(a.expires_at, b.expires_at);
+
+    let stored = svc.get_attempt_by_id(attempt_id).await.unwrap();
+    assert_eq!(stored.status, "in_progress");
+    assert_eq!(stored.started_at, a.started_at);
+    let started = stored.started_at.unwrap();
+    assert!(stored.expires_at <= started + chrono::Duration::minutes(30));
+
+    cleanup(&pool, test_id).await;
+}
+
+#[tokio::test]
+async fn finished_or_expired_attempts_are_never_reopened() {
+    let pool = setup_pool().await;
+    let test_id = seed_test(&pool).await;
+    let svc = AttemptService::new(pool.clone());
+
+    let (attempt_id, token) = invite(&svc, test_id).await;
+    let started = svc
+        .start_attempt_by_token(&token, true, None, None)
+        .await
+        .expect("start");
+    let token = started.access_token;
+    let (submitted, ..) = svc
+        .submit_attempt_by_token(
+            &token,
+            SubmitTestRequest {
+                answers: vec![SaveAnswerRequest {
+                    question_id: 1,
+                    answer: json!(1),
+                    time_spent_seconds: 5,
+                    marked_for_review: None,
+                }],
+                status: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .expect("submit");
+    assert!(!is_startable_status(&submitted.status));
+
+    let (again, late) = tokio::join!(
+        svc.start_attempt_by_token(&token, true, None, None),
+        svc.start_attempt_by_token(&token, true, None, None),
+    );
+    for err in [again.unwrap_err(), late.unwrap_err()] {
+        assert_eq!(err.code(), "already_completed");
+    }
+    let stored = svc.get_attempt_by_id(attempt_id).await.unwrap();
+    assert_eq!(stored.status, submitted.status, "terminal status regressed");
+    assert_eq!(
+        stored.access_token, token,
+        "a refused start rotated the token"
+    );
+
+    let (expired_id, expired_token) = invite(&svc, test_id).await;
+    sqlx::query("UPDATE test_attempts SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(expired_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let err = svc
+        .start_attempt_by_token(&expired_token, true, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "test_expired");
+    let stored = svc.get_attempt_by_id(expired_id).await.unwrap();
+    assert_eq!(stored.status, "pending");
+    assert!(stored.started_at.is_none());
+
+    cleanup(&pool, test_id).await;
+}
@@ -0,0 +1,255 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Path, State},
+    http::{header, Request, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use recruitment_backend::models::broadcast::BroadcastFilter;
+use recruitment_backend::services::broadcast_service::{
+    normalize_filter, render_broadcast, BroadcastService, NO_TELEGRAM_ERROR,
+};
+use recruitment_backend::services::telegram_channel_service::TelegramChannelService;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+type Calls = Arc<Mutex<Vec<Value>>>;
+
+#[test]
+fn filter_statuses_are_checked_and_deduplicated() {
+    let filter = normalize_filter(BroadcastFilter {
+        statuses: vec![" reviewing".into(), "reviewing".into(), "".into()],
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(filter.statuses, vec!["reviewing".to_string()]);
+
+    let err = normalize_filter(BroadcastFilter {
+        statuses: vec!["reveiwing".into()],
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(err.code(), "unknown_candidate_status");
+    assert!(normalize_filter(BroadcastFilter {
+        min_ai_rating: Some(101),
+        ..Default::default()
+    })
+    .is_err());
+
+    assert_eq!(
+        render_broadcast("Здравствуйте, {name}!", "Алия"),
+        "Здравствуйте, Алия!"
+    );
+}
+
+/// Fake Bot API; negative chat ids play users who blocked the bot.
+async fn bot_api(
+    State(calls): State<Calls>,
+    Path((_bot, _method)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> impl IntoResponse {
+    calls.lock().unwrap().push(body.clone());
+    if body["chat_id"].as_i64().is_some_and(|id| id < 0) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "ok": false,
+                "error_code": 403,
+                "description": "Forbidden: bot was blocked by the user"
+            })),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(json!({ "ok": true, "result": { "message_id": 1 } })),
+    )
+}
+
+async fn mock_telegram() -> (TelegramChannelService, Calls) {
+    let calls: Calls = Arc::default();
+    let app = Router::new()
+        .route("/:bot/:method", post(bot_api))
+        .with_state(calls.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (
+        TelegramChannelService::new(format!("http://{}", addr), "123:abc"),
+        calls,
+    )
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn broadcast_records_each_delivery() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let vacancy_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 1;
+    let reachable = (Uuid::new_v4().as_u128() % 1_000_000_000_000) as i64 + 1_000;
+    // (name, telegram_id, status, ai_rating)
+    let seeds = [
+        ("Reachable", Some(reachable), "reviewing", 80),
+        ("Blocked", Some(-reachable), "reviewing", 75),
+        ("No Telegram", None, "reviewing", 90),
+        ("Low Rating", Some(reachable + 1), "reviewing", 20),
+        ("Other Status", Some(reachable + 2), "new", 95),
+    ];
+    let mut ids = Vec::new();
+    for (name, telegram_id, status, rating) in seeds {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO candidates (id, telegram_id, name, email, vacancy_id, status, ai_rating)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+        )
+        .bind(id)
+        .bind(telegram_id)
+        .bind(name)
+        .bind(format!("broadcast_{}@example.com", id))
+        .bind(vacancy_id)
+        .bind(status)
+        .bind(rating)
+        .execute(&pool)
+        .await
+        .expect("seed candidate");
+        ids.push(id);
+    }
+
+    let app = Router::new()
+        .route(
+            "/api/integration/broadcasts",
+            post(recruitment_backend::routes::broadcasts::create_broadcast),
+        )
+        .route(
+            "/api/integration/broadcasts/:id",
+            get(recruitment_backend::routes::broadcasts::get_broadcast),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let request = json!({
+        "filter": { "statuses": ["reviewing"], "vacancy_id": vacancy_id, "min_ai_rating": 50 },
+        "message": "{name}, приглашаем на день найма!",
+        "dry_run": true,
+    });
+
+    let (status, preview) =
+        send(&app, "POST", "/api/integration/broadcasts", request.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(preview["matched"], 3);
+    assert_eq!(preview["recipients"][0]["name"], "Reachable");
+    assert_eq!(
+        preview["recipients"][0]["message"],
+        "Reachable, приглашаем на день найма!"
+    );
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/integration/broadcasts",
+        json!({ "filter": { "statuses": ["nope"] }, "message": "Hi" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "unknown_candidate_status");
+
+    let mut request = request;
+    request["dry_run"] = json!(false);
+    let (status, created) = send(&app, "POST", "/api/integration/broadcasts", request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(created["total_recipients"], 3);
+    assert_eq!(created["status"], "sending");
+    assert_eq!(created["pending"], 2);
+    assert_eq!(created["failures"][0]["error"], NO_TELEGRAM_ERROR);
+
+    let (telegram, calls) = mock_telegram().await;
+    let service = BroadcastService::new(pool.clone());
+    while service.run_once(&telegram).await.unwrap() {}
+
+    let (status, progress) = send(
+        &app,
+        "GET",
+        &format!(
+            "/api/integration/broadcasts/{}",
+            created["id"].as_str().unwrap()
+        ),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(progress["status"], "completed");
+    assert_eq!(
+        (&progress["pending"], &progress["sent"], &progress["failed"]),
+        (&json!(0), &json!(1), &json!(2))
+    );
+    let blocked = progress["failures"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["telegram_id"] == -reachable)
+        .expect("blocked recipient recorded");
+    assert!(blocked["error"]
+        .as_str()
+        .unwrap()
+        .contains("bot was blocked by the user"));
+    assert!(calls
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|c| c["chat_id"] == reachable && c["text"] == "Reachable, приглашаем на день найма!"));
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/integration/broadcasts/{}", Uuid::new_v4()),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM broadcasts WHERE id = $1")
+        .bind(Uuid::parse_str(created["id"].as_str().unwrap()).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
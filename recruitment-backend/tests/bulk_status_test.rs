@@ -0,0 +1,109 @@
+use std::env;
+
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::candidate_service::CandidateService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn seed(pool: &PgPool, status: &str, deleted: bool) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, status, deleted_at) VALUES ($1, 'Bulk Candidate', $2, $3, CASE WHEN $4 THEN NOW() END)",
+    )
+    .bind(id)
+    .bind(format!("bulk_{}@example.com", id))
+    .bind(status)
+    .bind(deleted)
+    .execute(pool)
+    .await
+    .expect("seed candidate");
+    id
+}
+
+async fn status_of(pool: &PgPool, id: Uuid) -> String {
+    sqlx::query_scalar("SELECT status FROM candidates WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn rejects_a_cohort_in_one_update() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let fresh = seed(&pool, "new", false).await;
+    let interviewed = seed(&pool, "interview", false).await;
+    let already = seed(&pool, "rejected", false).await;
+    let deleted = seed(&pool, "new", true).await;
+    let unknown = Uuid::new_v4();
+
+    let svc = CandidateService::new(pool.clone());
+    let result = svc
+        .bulk_update_status(
+            &[fresh, interviewed, interviewed, already, deleted, unknown],
+            DEFAULT_ORGANIZATION_ID,
+            "rejected",
+        )
+        .await
+        .unwrap();
+
+    let mut updated: Vec<(Uuid, String)> = result
+        .updated
+        .iter()
+        .map(|c| (c.id, c.previous_status.clone()))
+        .collect();
+    updated.sort();
+    let mut expected = vec![
+        (fresh, "new".to_string()),
+        (interviewed, "interview".to_string()),
+    ];
+    expected.sort();
+    assert_eq!(updated, expected);
+
+    let mut failed = result.failed.clone();
+    failed.sort();
+    let mut expected_failed = vec![deleted, unknown];
+    expected_failed.sort();
+    assert_eq!(failed, expected_failed);
+
+    assert_eq!(status_of(&pool, fresh).await, "rejected");
+    assert_eq!(status_of(&pool, interviewed).await, "rejected");
+    assert_eq!(status_of(&pool, deleted).await, "new");
+
+    // The workflow still applies: a rejected candidate can't be accepted.
+    let result = svc
+        .bulk_update_status(&[already], DEFAULT_ORGANIZATION_ID, "accepted")
+        .await
+        .unwrap();
+    assert!(result.updated.is_empty());
+    assert_eq!(result.failed, vec![already]);
+
+    assert_eq!(
+        svc.bulk_update_status(&[fresh], DEFAULT_ORGANIZATION_ID, "rejectd")
+            .await
+            .unwrap_err()
+            .code(),
+        "unknown_candidate_status"
+    );
+
+    sqlx::query("DELETE FROM candidates WHERE id = ANY($1)")
+        .bind(vec![fresh, interviewed, already, deleted])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,87 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use recruitment_backend::middleware::cache_control::{
+    private_no_store, public_cache, PRIVATE_NO_STORE, PUBLIC_MAX_AGE,
+};
+use recruitment_backend::middleware::etag::{compute_etag, etag};
+use serde_json::json;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/vacancies",
+            get(|| async { Json(json!([{ "id": 1, "title": "Backend Developer" }])) })
+                .layer(axum::middleware::from_fn(public_cache))
+                .layer(axum::middleware::from_fn(etag)),
+        )
+        .route(
+            "/broken",
+            get(|| async { StatusCode::SERVICE_UNAVAILABLE })
+                .layer(axum::middleware::from_fn(public_cache))
+                .layer(axum::middleware::from_fn(etag)),
+        )
+        .route(
+            "/candidate",
+            get(|| async { Json(json!({ "name": "Jane" })) })
+                .layer(axum::middleware::from_fn(private_no_store)),
+        )
+}
+
+async fn call(uri: &str, if_none_match: Option<&str>) -> axum::response::Response {
+    let mut req = Request::builder().uri(uri);
+    if let Some(tag) = if_none_match {
+        req = req.header(header::IF_NONE_MATCH, tag);
+    }
+    app()
+        .oneshot(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn public_routes_are_cacheable_and_tagged() {
+    let resp = call("/vacancies", None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], PUBLIC_MAX_AGE);
+    let tag = resp.headers()[header::ETAG].to_str().unwrap().to_string();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert_eq!(tag, compute_etag(&body));
+    assert_eq!(tag.len(), 18);
+
+    let resp = call("/vacancies", Some(&tag)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(resp.headers()[header::ETAG], tag.as_str());
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], PUBLIC_MAX_AGE);
+    assert!(to_bytes(resp.into_body(), 1024).await.unwrap().is_empty());
+
+    let list = format!("\"0000000000000000\", W/{}", tag);
+    assert_eq!(
+        call("/vacancies", Some(&list)).await.status(),
+        StatusCode::NOT_MODIFIED
+    );
+    assert_eq!(
+        call("/vacancies", Some("\"stale\"")).await.status(),
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn errors_are_not_cached_or_tagged() {
+    let resp = call("/broken", None).await;
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(resp.headers().get(header::CACHE_CONTROL).is_none());
+    assert!(resp.headers().get(header::ETAG).is_none());
+}
+
+#[tokio::test]
+async fn candidate_routes_are_never_stored() {
+    let resp = call("/candidate", None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], PRIVATE_NO_STORE);
+    assert!(resp.headers().get(header::ETAG).is_none());
+}
@@ -0,0 +1,201 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::services::candidate_service::{CandidateService, CandidateSort};
+use recruitment_backend::services::organization_service::OrganizationService;
+use recruitment_backend::services::test_service::TestService;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+/// A candidate registered `days_ago` days ago with no other footprint.
+async fn candidate(
+    pool: &PgPool,
+    org_id: Uuid,
+    name: &str,
+    status: &str,
+    days_ago: i64,
+) -> (Uuid, String) {
+    let id = Uuid::new_v4();
+    let email = format!("activity_{}@example.com", id);
+    sqlx::query(
+        r#"INSERT INTO candidates (id, name, email, status, organization_id, created_at)
+           VALUES ($1, $2, $3, $4, $5, $6)"#,
+    )
+    .bind(id)
+    .bind(name)
+    .bind(&email)
+    .bind(status)
+    .bind(org_id)
+    .bind(Utc::now() - Duration::days(days_ago))
+    .execute(pool)
+    .await
+    .unwrap();
+    (id, email)
+}
+
+async fn message(pool: &PgPool, candidate_id: Uuid, direction: &str, days_ago: i64) {
+    sqlx::query(
+        r#"INSERT INTO messages (candidate_id, telegram_id, direction, text, created_at)
+           VALUES ($1, 1, $2, 'hi', $3)"#,
+    )
+    .bind(candidate_id)
+    .bind(direction)
+    .bind(Utc::now() - Duration::days(days_ago))
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn last_activity_takes_the_latest_footprint_and_surfaces_stale_candidates() {
+    let pool = setup_pool().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let org = OrganizationService::new(pool.clone())
+        .create("Activity", &format!("activity-{}", &suffix[..8]))
+        .await
+        .unwrap();
+
+    let (silent, _) = candidate(&pool, org.id, "Silent", "new", 40).await;
+    let (contacted, _) = candidate(&pool, org.id, "Contacted", "contacted", 30).await;
+    let (applied, _) = candidate(&pool, org.id, "Applied", "new", 40).await;
+    let (tested, tested_email) = candidate(&pool, org.id, "Tested", "new", 40).await;
+    let (wrote, _) = candidate(&pool, org.id, "Wrote", "new", 40).await;
+    let (messaged, _) = candidate(&pool, org.id, "Only messaged", "new", 20).await;
+    let (fresh, _) = candidate(&pool, org.id, "Fresh", "new", 0).await;
+
+    sqlx::query(
+        "INSERT INTO candidate_applications (candidate_id, vacancy_id, created_at) VALUES ($1, 7, $2)",
+    )
+    .bind(applied)
+    .bind(Utc::now() - Duration::days(3))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Activity User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("activity_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Activity Test",
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [],
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, org.id)
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"INSERT INTO test_attempts (test_id, candidate_name, candidate_email, access_token,
+               expires_at, questions_snapshot, status, organization_id, created_at, updated_at)
+           VALUES ($1, 'Tested', $2, $3, $4, '[]'::jsonb, 'completed', $5, $6, $7)"#,
+    )
+    .bind(test.id)
+    .bind(&tested_email)
+    .bind(format!("activity-{}", Uuid::new_v4()))
+    .bind(Utc::now() + Duration::days(1))
+    .bind(org.id)
+    .bind(Utc::now() - Duration::days(10))
+    .bind(Utc::now() - Duration::days(2))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    message(&pool, wrote, "inbound", 1).await;
+    message(&pool, messaged, "outbound", 1).await;
+
+    let service = CandidateService::new(pool.clone());
+    let mut listed = service.list_candidates(org.id).await.unwrap();
+    assert_eq!(listed.len(), 7);
+    let days_idle = |id: Uuid, list: &[recruitment_backend::models::candidate::Candidate]| {
+        let at = list
+            .iter()
+            .find(|c| c.id == id)
+            .unwrap()
+            .last_activity_at
+            .unwrap();
+        (Utc::now() - at).num_days()
+    };
+    assert_eq!(days_idle(silent, &listed), 40);
+    assert_eq!(days_idle(applied, &listed), 3, "application counts");
+    assert_eq!(
+        days_idle(tested, &listed),
+        2,
+        "latest attempt change counts"
+    );
+    assert_eq!(days_idle(wrote, &listed), 1, "inbound message counts");
+    assert_eq!(
+        days_idle(messaged, &listed),
+        20,
+        "outbound messages do not count"
+    );
+
+    CandidateSort::LastActivityAt.apply(&mut listed);
+    let order: Vec<Uuid> = listed.iter().map(|c| c.id).collect();
+    assert_eq!(order[0], fresh);
+    assert_eq!(order[order.len() - 1], silent);
+
+    let stale: Vec<Uuid> = service
+        .list_stale(14, None, org.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(stale, vec![silent, contacted, messaged], "stalest first");
+
+    let stale_new: Vec<Uuid> = service
+        .list_stale(14, Some("new"), org.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(stale_new, vec![silent, messaged]);
+    assert_eq!(service.count_stale(14, org.id).await.unwrap(), 3);
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE organization_id = $1")
+        .bind(org.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
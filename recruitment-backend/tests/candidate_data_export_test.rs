@@ -0,0 +1,187 @@
+use std::env;
+use std::io::{Cursor, Read};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::{Duration, Utc};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::test_service::TestService;
+use recruitment_backend::utils::signed_url::portal_token;
+use serde_json::{json, Value as JsonValue};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn export_data_requires_the_candidates_portal_token() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let root = env::temp_dir().join(format!("export-data-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("cv")).unwrap();
+    std::fs::write(root.join("cv/export.pdf"), b"%PDF-1.4 export").unwrap();
+    env::set_var("UPLOADS_DIR", &root);
+
+    let id = Uuid::new_v4();
+    let email = format!("export_{}@example.com", id);
+    sqlx::query(
+        r#"INSERT INTO candidates (id, name, email, cv_url, status, ai_rating, ai_comment)
+           VALUES ($1, 'Export Candidate', $2, 'uploads/cv/export.pdf', 'reviewing', 73, 'Solid')"#,
+    )
+    .bind(id)
+    .bind(&email)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO messages (candidate_id, telegram_id, direction, text) VALUES ($1, 1, 'inbound', 'Hello')",
+    )
+    .bind(id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Export User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("export_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Export Test",
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [],
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    let attempt_token = format!("export-{}", Uuid::new_v4());
+    sqlx::query(
+        r#"INSERT INTO test_attempts (test_id, candidate_name, candidate_email, access_token,
+               expires_at, questions_snapshot, answers, status)
+           VALUES ($1, 'Export Candidate', $2, $3, $4, '[]'::jsonb,
+                   '[{"question_id": 1, "answer": "B"}]'::jsonb, 'completed')"#,
+    )
+    .bind(test.id)
+    .bind(&email)
+    .bind(&attempt_token)
+    .bind(Utc::now() + Duration::days(1))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/candidate/:id/export-data",
+            get(recruitment_backend::routes::export::export_candidate_data),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let fetch = |uri: String| {
+        let app = app.clone();
+        async move {
+            app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+        }
+    };
+    let secret = recruitment_backend::config::get_config()
+        .upload_signing_secret
+        .as_bytes();
+
+    let resp = fetch(format!("/api/candidate/{}/export-data", id)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: JsonValue =
+        serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    assert_eq!(body["error"]["code"], "invalid_portal_token");
+
+    let someone_else = portal_token(Uuid::new_v4(), secret);
+    let resp = fetch(format!(
+        "/api/candidate/{}/export-data?portal_token={}",
+        id, someone_else
+    ))
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = fetch(format!(
+        "/api/candidate/{}/export-data?portal_token={}",
+        id,
+        portal_token(id, secret)
+    ))
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/zip");
+    assert!(resp.headers()[header::CONTENT_DISPOSITION]
+        .to_str()
+        .unwrap()
+        .contains(&format!("candidate_data_{}", id)));
+    let archive = to_bytes(resp.into_body(), 10 * 1024 * 1024).await.unwrap();
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive.to_vec())).expect("zip");
+
+    let mut cv = Vec::new();
+    zip.by_name("cv/export.pdf")
+        .unwrap()
+        .read_to_end(&mut cv)
+        .unwrap();
+    assert_eq!(cv, b"%PDF-1.4 export");
+
+    let mut json = String::new();
+    zip.by_name("messages.json")
+        .unwrap()
+        .read_to_string(&mut json)
+        .unwrap();
+    let messages: JsonValue = serde_json::from_str(&json).unwrap();
+    assert_eq!(messages[0]["text"], "Hello");
+
+    json.clear();
+    zip.by_name("candidate.json")
+        .unwrap()
+        .read_to_string(&mut json)
+        .unwrap();
+    assert!(!json.contains(&attempt_token), "access tokens are withheld");
+    let document: JsonValue = serde_json::from_str(&json).unwrap();
+    assert_eq!(document["candidate"]["ai_rating"], 73);
+    assert_eq!(document["candidate"]["ai_comment"], "Solid");
+    let attempt = &document["test_attempts"][0];
+    assert_eq!(attempt["answers"][0]["answer"], "B");
+    assert!(attempt.get("questions_snapshot").is_none());
+
+    sqlx::query("DELETE FROM test_attempts WHERE candidate_email = $1")
+        .bind(&email)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(root).ok();
+}
@@ -0,0 +1,133 @@
+use std::env;
+
+use recruitment_backend::services::candidate_service::{
+    email_hash, normalize_email, CandidateService,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+#[test]
+fn hash_ignores_case_and_spacing_but_depends_on_the_secret() {
+    assert_eq!(
+        normalize_email("  John.Doe@Example.COM \n"),
+        "john.doe@example.com"
+    );
+
+    let hash = email_hash("john.doe@example.com", b"secret");
+    assert_eq!(hash.len(), 64);
+    assert_eq!(email_hash(" John.Doe@EXAMPLE.com", b"secret"), hash);
+    assert_ne!(email_hash("john.doe@example.com", b"other"), hash);
+    assert_ne!(email_hash("jane.doe@example.com", b"secret"), hash);
+}
+
+#[tokio::test]
+async fn emails_are_stored_normalised_and_unique_regardless_of_case() {
+    let pool = setup_pool().await;
+    let service = CandidateService::new(pool.clone());
+    let local = Uuid::new_v4().simple().to_string();
+    let stored = format!("{}@example.com", local);
+
+    let candidate = service
+        .create_candidate(
+            None,
+            "Email Case".into(),
+            format!("  {}@Example.COM ", local.to_uppercase()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("create candidate");
+    assert_eq!(candidate.email, Some(stored.clone()));
+
+    let found = service
+        .get_by_email(&format!("{}@EXAMPLE.com", local.to_uppercase()))
+        .await
+        .unwrap()
+        .expect("lookup ignores case");
+    assert_eq!(found.id, candidate.id);
+
+    let err = service
+        .create_candidate(
+            None,
+            "Email Case Again".into(),
+            format!("{}@example.com", local.to_uppercase()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("email address already exists"));
+
+    let raw = sqlx::query("INSERT INTO candidates (name, email) VALUES ('Raw', $1)")
+        .bind(stored.to_uppercase())
+        .execute(&pool)
+        .await;
+    assert!(raw.is_err(), "the LOWER(email) index rejects case variants");
+
+    let secret = recruitment_backend::config::get_config()
+        .email_hash_secret
+        .clone();
+    let hash: Option<String> =
+        sqlx::query_scalar("SELECT email_hash FROM candidates WHERE id = $1")
+            .bind(candidate.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(hash, Some(email_hash(&stored, secret.as_bytes())));
+
+    sqlx::query("UPDATE candidates SET email_hash = NULL WHERE id = $1")
+        .bind(candidate.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(
+        service
+            .backfill_email_hashes(secret.as_bytes())
+            .await
+            .unwrap()
+            >= 1
+    );
+    let hash: Option<String> =
+        sqlx::query_scalar("SELECT email_hash FROM candidates WHERE id = $1")
+            .bind(candidate.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(hash, Some(email_hash(&stored, secret.as_bytes())));
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,285 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::delete,
+    Router,
+};
+use recruitment_backend::models::candidate::Candidate;
+use recruitment_backend::routes::candidate_routes::delete_candidate;
+use recruitment_backend::AppState;
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+/// One upload root per test binary: `UPLOADS_DIR` is process-wide.
+fn uploads() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = env::temp_dir().join(format!("erasure-{}", Uuid::new_v4()));
+        env::set_var("UPLOADS_DIR", &dir);
+        dir
+    })
+    .clone()
+}
+
+fn upload(relative: &str) -> PathBuf {
+    let path = uploads().join(relative);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, b"personal").unwrap();
+    path
+}
+
+async fn erase(app: &Router, candidate: Uuid, erase: bool) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!(
+                    "/api/integration/candidates/{}?erase={}",
+                    candidate, erase
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+fn app(pool: &PgPool) -> Router {
+    Router::new()
+        .route("/api/integration/candidates/:id", delete(delete_candidate))
+        .with_state(AppState::new(pool.clone()))
+}
+
+async fn attempt(pool: &PgPool, test_id: Uuid, email: &str, telegram_id: Option<i64>) -> Uuid {
+    sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, candidate_phone, candidate_telegram_id,
+                access_token, expires_at, questions_snapshot, status, ip_address, user_agent)
+           VALUES ($1, 'Erase Me', $2, '+992900000000', $3, $4, NOW() + INTERVAL '1 day', '[]',
+                   'completed', '203.0.113.5', 'Mozilla/5.0')
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(email)
+    .bind(telegram_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn erasure_removes_personal_data_everywhere() {
+    let pool = setup_pool().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let files = [
+        format!("cvs/erase-{}.pdf", suffix),
+        format!("photos/erase-{}.jpg", suffix),
+        format!("portfolio/erase-{}.pdf", suffix),
+    ];
+    let paths: Vec<PathBuf> = files.iter().map(|f| upload(f)).collect();
+
+    let email = format!("erase_{}@example.com", suffix);
+    let telegram_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 1;
+    let candidate: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO candidates
+               (name, email, phone, telegram_id, cv_url, photo_url, cv_portfolio, profile_data,
+                extracted_profile, ai_comment)
+           VALUES ('Erase Me', $1, '+992900000000', $2, $3, $4, $5, $6, $7, 'Erase Me fits the role')
+           RETURNING id"#,
+    )
+    .bind(&email)
+    .bind(telegram_id)
+    .bind(format!("uploads/{}", files[0]))
+    .bind(format!("uploads/{}", files[1]))
+    .bind(json!([format!("uploads/{}", files[2])]))
+    .bind(json!({ "city": "Dushanbe", "about": "Erase Me" }))
+    .bind(json!({ "full_name": "Erase Me" }))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO messages (candidate_id, telegram_id, direction, text) VALUES ($1, $2, 'inbound', 'My passport number is ...')",
+    )
+    .bind(candidate)
+    .bind(telegram_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score) VALUES ('Erasure test', '[]', 'question_based', 0) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let by_email = attempt(&pool, test_id, &email.to_uppercase(), None).await;
+    let by_telegram = attempt(
+        &pool,
+        test_id,
+        "other_address@example.com",
+        Some(telegram_id),
+    )
+    .await;
+    let someone_else = attempt(&pool, test_id, "someone_else@example.com", None).await;
+
+    let status = erase(&app(&pool), candidate, true).await;
+    assert!(status.is_success(), "{}", status);
+
+    // The erased row still decodes, with nothing left to identify the person.
+    let erased: Candidate = sqlx::query_as(
+        "SELECT *, NULL::bigint AS unread_messages, NULL::timestamptz AS last_activity_at FROM candidates WHERE id = $1",
+    )
+    .bind(candidate)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(erased.name, None);
+    assert_eq!(erased.email, None);
+    assert_eq!(erased.phone, None);
+    assert_eq!(erased.telegram_id, None);
+    assert_eq!(erased.cv_url, None);
+    assert_eq!(erased.photo_url, None);
+    assert_eq!(erased.cv_portfolio, None);
+    assert_eq!(erased.profile_data, None);
+    assert_eq!(erased.extracted_profile, None);
+    assert_eq!(erased.ai_comment, None);
+    assert!(erased.deleted_at.is_some());
+
+    let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE candidate_id = $1")
+        .bind(candidate)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(messages, 0);
+
+    for id in [by_email, by_telegram] {
+        let (name, attempt_email, phone, tg, ip): (String, String, Option<String>, Option<i64>, Option<String>) =
+            sqlx::query_as(
+                "SELECT candidate_name, candidate_email, candidate_phone, candidate_telegram_id, host(ip_address) FROM test_attempts WHERE id = $1",
+            )
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "erased");
+        assert!(
+            attempt_email.ends_with("@erased.invalid"),
+            "{}",
+            attempt_email
+        );
+        assert_eq!((phone, tg, ip), (None, None, None));
+    }
+    let untouched: String =
+        sqlx::query_scalar("SELECT candidate_name FROM test_attempts WHERE id = $1")
+            .bind(someone_else)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(untouched, "Erase Me");
+
+    for path in &paths {
+        assert!(!path.exists(), "{} was kept", path.display());
+    }
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn erasure_keeps_a_cv_file_another_candidate_shares() {
+    let pool = setup_pool().await;
+    let suffix = Uuid::new_v4().simple().to_string();
+    let file = format!("cvs/shared-{}.pdf", suffix);
+    let path = upload(&file);
+    let cv_url = format!("uploads/{}", file);
+
+    let mut ids = Vec::new();
+    for who in ["first", "second"] {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO candidates (name, email, cv_url, cv_hash) VALUES ('Shared CV', $1, $2, $3) RETURNING id",
+        )
+        .bind(format!("shared_{}_{}@example.com", who, suffix))
+        .bind(&cv_url)
+        .bind(&suffix)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+
+    let app = app(&pool);
+    assert!(erase(&app, ids[0], true).await.is_success());
+    assert!(
+        path.exists(),
+        "CV still used by the second candidate was deleted"
+    );
+    let kept: Option<String> = sqlx::query_scalar("SELECT cv_url FROM candidates WHERE id = $1")
+        .bind(ids[1])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(kept.as_deref(), Some(cv_url.as_str()));
+
+    // Once nobody else references it, the file goes with the last owner.
+    assert!(erase(&app, ids[1], true).await.is_success());
+    assert!(!path.exists());
+
+    sqlx::query("DELETE FROM candidates WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn erasing_or_deleting_an_unknown_candidate_is_not_found() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+    for erase_data in [true, false] {
+        assert_eq!(
+            erase(&app, Uuid::new_v4(), erase_data).await,
+            StatusCode::NOT_FOUND,
+            "erase={}",
+            erase_data
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use recruitment_backend::models::candidate::{CandidateProfileData, ProfileDataInput};
+use serde_json::json;
+
+#[test]
+fn structured_profile_is_parsed_and_trimmed() {
+    let parsed = CandidateProfileData::parse(json!({
+        "city": " Dushanbe ",
+        "languages": ["tg", " ru"],
+        "experience_years": 4
+    }))
+    .expect("valid profile");
+
+    assert_eq!(
+        parsed,
+        ProfileDataInput::Structured(CandidateProfileData {
+            city: Some("Dushanbe".to_string()),
+            education: None,
+            languages: vec!["tg".to_string(), "ru".to_string()],
+            experience_years: Some(4),
+        })
+    );
+    assert_eq!(parsed.into_json()["experience_years"], json!(4));
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let problems = CandidateProfileData::parse(json!({
+        "city": "Khujand",
+        "languages": "ru",
+        "experience_years": -1,
+        "hobby": "chess"
+    }))
+    .expect_err("invalid profile");
+
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.contains("`hobby`")));
+    assert!(problems.iter().any(|p| p.starts_with("languages:")));
+    assert!(problems.iter().any(|p| p.starts_with("experience_years:")));
+
+    let problems = CandidateProfileData::parse(json!({ "experience_years": 120 }))
+        .expect_err("out of range");
+    assert_eq!(problems.len(), 1);
+}
+
+#[test]
+fn legacy_blob_is_wrapped() {
+    let legacy = json!({ "telegram_username": "jdoe", "registration_date": "2026-01-01" });
+    let parsed = CandidateProfileData::parse(legacy.clone()).expect("legacy accepted");
+
+    assert_eq!(parsed, ProfileDataInput::Legacy(legacy.clone()));
+    assert_eq!(parsed.into_json(), json!({ "legacy": legacy }));
+    assert!(CandidateProfileData::parse(json!(["not", "an", "object"])).is_err());
+}
@@ -0,0 +1,92 @@
+use std::env;
+
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::routes::integration::ListCandidatesQuery;
+use recruitment_backend::services::ai_service::{normalize_skills, MAX_SKILL_TAGS};
+use recruitment_backend::services::candidate_service::CandidateService;
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn normalizes_extracted_skills() {
+    let skills = normalize_skills(&json!({
+        "skills": [" Rust ", "PostgreSQL", "rust", "", 42, "x".repeat(80), "Docker"]
+    }));
+    assert_eq!(skills, vec!["Rust", "PostgreSQL", "Docker"]);
+
+    assert!(normalize_skills(&json!({ "tags": ["Rust"] })).is_empty());
+
+    let many: Vec<String> = (0..50).map(|i| format!("Skill {}", i)).collect();
+    assert_eq!(
+        normalize_skills(&json!({ "skills": many })).len(),
+        MAX_SKILL_TAGS
+    );
+}
+
+#[test]
+fn splits_skills_query() {
+    let query = ListCandidatesQuery {
+        skills: Some(" Rust, ,PostgreSQL ".into()),
+        skill: Some("Go".into()),
+        ..Default::default()
+    };
+    assert_eq!(query.skill_list(), vec!["Rust", "PostgreSQL"]);
+    assert_eq!(query.profile_skill_list(), vec!["Go"]);
+    assert!(ListCandidatesQuery::default().skill_list().is_empty());
+    assert!(ListCandidatesQuery::default()
+        .profile_skill_list()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn filters_candidates_by_skill() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let tag = format!("skill-{}", Uuid::new_v4().simple());
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO candidates (id, name, email) VALUES ($1, 'Skilled', $2)")
+        .bind(id)
+        .bind(format!("{}@example.com", tag))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let service = CandidateService::new(pool.clone());
+    assert!(service
+        .list_by_skills(std::slice::from_ref(&tag), DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap()
+        .is_empty());
+
+    service
+        .update_skills(id, &[tag.clone(), "Rust".into()])
+        .await
+        .unwrap();
+    let found = service
+        .list_by_skills(&[tag.clone(), "Cobol".into()], DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, id);
+    assert_eq!(found[0].skills.as_ref().unwrap()[0], tag);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,248 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::{Duration, Utc};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::candidate_service::CandidateService;
+use recruitment_backend::services::test_service::TestService;
+use serde_json::{json, Value as JsonValue};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn app(pool: &PgPool) -> Router {
+    Router::new()
+        .route(
+            "/api/public/candidates/:status_token/status",
+            get(recruitment_backend::routes::candidate_routes::get_public_candidate_status),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()))
+}
+
+async fn call(app: &Router, token: &str) -> (StatusCode, JsonValue) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/public/candidates/{}/status", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+async fn create_test(pool: &PgPool, creator: Uuid, title: &str, show_results: bool) -> Uuid {
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": title,
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "show_results_immediately": show_results,
+        "questions": [],
+    }))
+    .unwrap();
+    TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn attempt(pool: &PgPool, test_id: Uuid, email: &str, status: &str, expires_in: Duration) {
+    sqlx::query(
+        r#"INSERT INTO test_attempts (test_id, candidate_name, candidate_email, access_token,
+               expires_at, questions_snapshot, status, passed, percentage, completed_at)
+           VALUES ($1, 'Status Candidate', $2, $3, $4, '[]'::jsonb, $5,
+                   CASE WHEN $5 = 'completed' THEN TRUE END,
+                   CASE WHEN $5 = 'completed' THEN 80 END,
+                   CASE WHEN $5 = 'completed' THEN NOW() END)"#,
+    )
+    .bind(test_id)
+    .bind(email)
+    .bind(format!("status-{}", Uuid::new_v4()))
+    .bind(Utc::now() + expires_in)
+    .bind(status)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn unknown_and_revoked_tokens_are_not_found() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+
+    let (status, body) = call(&app, "definitely-not-a-token").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "status_page_not_found");
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, status) VALUES ($1, 'Revoked', $2, 'new')",
+    )
+    .bind(id)
+    .bind(format!("revoked_{}@example.com", id))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let service = CandidateService::new(pool.clone());
+    let token = service.issue_status_token(id).await.unwrap();
+    let (status, _) = call(&app, &token).await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert!(service.revoke_status_token(id).await.unwrap());
+    let (status, body) = call(&app, &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "status_page_not_found");
+
+    let reissued = service.issue_status_token(id).await.unwrap();
+    assert_ne!(reissued, token);
+    assert_eq!(call(&app, &token).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(call(&app, &reissued).await.0, StatusCode::OK);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn status_page_lists_applications_invites_and_allowed_results() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+
+    let id = Uuid::new_v4();
+    let email = format!("status_{}@example.com", id);
+    sqlx::query(
+        r#"INSERT INTO candidates (id, name, email, phone, status, ai_rating, ai_comment)
+           VALUES ($1, 'Status Candidate', $2, '+992900000000', 'test_assigned', 91, 'Internal note')"#,
+    )
+    .bind(id)
+    .bind(&email)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO candidate_applications (candidate_id, vacancy_id) VALUES ($1, 7)")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Status User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("status_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let open_test = create_test(&pool, creator, "Open Results", true).await;
+    let closed_test = create_test(&pool, creator, "Hidden Results", false).await;
+    let invite_test = create_test(&pool, creator, "Upcoming", false).await;
+    attempt(&pool, open_test, &email, "completed", Duration::days(1)).await;
+    attempt(&pool, closed_test, &email, "completed", Duration::days(1)).await;
+    attempt(&pool, invite_test, &email, "pending", Duration::days(2)).await;
+    attempt(&pool, invite_test, &email, "pending", Duration::days(-1)).await;
+
+    let token = CandidateService::new(pool.clone())
+        .issue_status_token(id)
+        .await
+        .unwrap();
+    let (status, body) = call(&app, &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    assert_eq!(body["name"], "Status Candidate");
+    assert_eq!(body["status"], "test_assigned");
+    assert_eq!(body["status_label"], "Назначен тест");
+    for hidden in [
+        "id",
+        "email",
+        "phone",
+        "ai_rating",
+        "ai_comment",
+        "telegram_id",
+    ] {
+        assert!(body.get(hidden).is_none(), "{} is exposed", hidden);
+    }
+    assert!(!body.to_string().contains("Internal note"));
+
+    assert_eq!(body["applications"].as_array().unwrap().len(), 1);
+    assert!(!body["applications"][0]["vacancy_title"]
+        .as_str()
+        .unwrap()
+        .is_empty());
+
+    let invites = body["test_invites"].as_array().unwrap();
+    assert_eq!(invites.len(), 1, "expired invites are left out");
+    assert_eq!(invites[0]["test_title"], "Upcoming");
+    assert!(invites[0]["test_url"]
+        .as_str()
+        .unwrap()
+        .contains("/test/status-"));
+
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    let result = |title: &str| {
+        results
+            .iter()
+            .find(|r| r["test_title"] == title)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(result("Open Results")["passed"], true);
+    let percentage: f64 = result("Open Results")["percentage"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(percentage, 80.0);
+    assert!(result("Hidden Results")["passed"].is_null());
+    assert!(result("Hidden Results")["percentage"].is_null());
+
+    sqlx::query("DELETE FROM test_attempts WHERE candidate_email = $1")
+        .bind(&email)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = ANY($1)")
+        .bind(vec![open_test, closed_test, invite_test])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
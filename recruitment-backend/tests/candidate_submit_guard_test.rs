@@ -0,0 +1,153 @@
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use recruitment_backend::middleware::rate_limit::{ip_window_middleware, IpRateLimiter};
+use recruitment_backend::utils::telegram_auth::{verify_telegram_data, InitDataError};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const BOT_TOKEN: &str = "123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw";
+
+/// Signed with `BOT_TOKEN` following the WebApp initData algorithm.
+const INIT_DATA: &str = "query_id=AAHdF6IQAAAAAN0XohDhrOrc\
+    &user=%7B%22id%22%3A279058397%2C%22first_name%22%3A%22Vladislav%22%2C%22last_name%22%3A%22Kibenko%22%2C%22username%22%3A%22vdkfrost%22%2C%22language_code%22%3A%22ru%22%7D\
+    &auth_date=1662771648\
+    &hash=edc61e9e8f7faabc74ba9382300ae43fa6082c5b395c14b855793d20f74fbe76";
+const AUTH_DATE: i64 = 1662771648;
+const DAY: u64 = 24 * 60 * 60;
+
+fn verify(init_data: &str, bot_token: &str) -> Result<i64, InitDataError> {
+    verify_telegram_data(init_data, bot_token, DAY, AUTH_DATE + 60)
+}
+
+#[test]
+fn valid_init_data_yields_signed_user_id() {
+    assert_eq!(verify(INIT_DATA, BOT_TOKEN), Ok(279058397));
+}
+
+#[test]
+fn field_order_does_not_matter() {
+    let mut parts: Vec<&str> = INIT_DATA.split('&').collect();
+    parts.reverse();
+    assert_eq!(verify(&parts.join("&"), BOT_TOKEN), Ok(279058397));
+}
+
+#[test]
+fn wrong_bot_token_is_rejected() {
+    assert_eq!(
+        verify(INIT_DATA, "987654321:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw"),
+        Err(InitDataError::Invalid)
+    );
+}
+
+#[test]
+fn tampered_user_is_rejected() {
+    let forged = INIT_DATA.replace("279058397", "279058398");
+    assert_eq!(verify(&forged, BOT_TOKEN), Err(InitDataError::Invalid));
+}
+
+#[test]
+fn missing_or_malformed_hash_is_rejected() {
+    let without_hash = INIT_DATA.split("&hash=").next().unwrap();
+    assert_eq!(verify(without_hash, BOT_TOKEN), Err(InitDataError::Invalid));
+    assert_eq!(
+        verify(&format!("{}&hash=zz", without_hash), BOT_TOKEN),
+        Err(InitDataError::Invalid)
+    );
+    assert_eq!(verify("", BOT_TOKEN), Err(InitDataError::Invalid));
+}
+
+#[test]
+fn old_init_data_is_rejected_as_expired() {
+    let day = DAY as i64;
+    assert_eq!(
+        verify_telegram_data(INIT_DATA, BOT_TOKEN, DAY, AUTH_DATE + day),
+        Ok(279058397)
+    );
+    assert_eq!(
+        verify_telegram_data(INIT_DATA, BOT_TOKEN, DAY, AUTH_DATE + day + 1),
+        Err(InitDataError::Expired)
+    );
+    assert_eq!(
+        verify_telegram_data(INIT_DATA, BOT_TOKEN, 300, AUTH_DATE + 301),
+        Err(InitDataError::Expired)
+    );
+    // Signed in the future beyond clock skew.
+    assert_eq!(
+        verify_telegram_data(INIT_DATA, BOT_TOKEN, DAY, AUTH_DATE - 3600),
+        Err(InitDataError::Expired)
+    );
+    // A bad signature is reported as invalid whatever its age.
+    let forged = INIT_DATA.replace("279058397", "279058398");
+    assert_eq!(
+        verify_telegram_data(&forged, BOT_TOKEN, DAY, AUTH_DATE + 10 * day),
+        Err(InitDataError::Invalid)
+    );
+}
+
+#[test]
+fn ip_limiter_uses_a_sliding_window_per_client() {
+    let limiter = IpRateLimiter::new(2, Duration::from_millis(200));
+
+    assert!(limiter.check("10.0.0.1").is_ok());
+    assert!(limiter.check("10.0.0.1").is_ok());
+    assert_eq!(limiter.check("10.0.0.1"), Err(1));
+    assert!(limiter.check("10.0.0.2").is_ok());
+
+    std::thread::sleep(Duration::from_millis(250));
+    assert!(limiter.check("10.0.0.1").is_ok());
+}
+
+fn init_config() {
+    std::env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    std::env::set_var("JWT_SECRET", "test_secret_key");
+    std::env::set_var("WEBHOOK_SECRET", "whsec_test");
+    std::env::set_var("OPENAI_API_KEY", "sk-test");
+    std::env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+}
+
+async fn submit(app: &Router, peer: &str, forwarded_for: &str) -> StatusCode {
+    let mut req = Request::builder()
+        .method("POST")
+        .uri("/submit")
+        .header("x-forwarded-for", forwarded_for)
+        .body(Body::empty())
+        .unwrap();
+    req.extensions_mut()
+        .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+    app.clone().oneshot(req).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn ip_limiter_ignores_forwarded_for_from_untrusted_peers() {
+    init_config();
+    let app = Router::new()
+        .route("/submit", post(|| async { "ok" }))
+        .layer(axum::middleware::from_fn_with_state(
+            IpRateLimiter::new(2, Duration::from_secs(60)),
+            ip_window_middleware,
+        ));
+
+    // A client connecting directly cannot pick a new bucket per request.
+    assert_eq!(submit(&app, "203.0.113.7:5000", "198.51.100.1").await, StatusCode::OK);
+    assert_eq!(submit(&app, "203.0.113.7:5000", "198.51.100.2").await, StatusCode::OK);
+    assert_eq!(
+        submit(&app, "203.0.113.7:5000", "198.51.100.3").await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Behind the local proxy the forwarded client is limited on its own.
+    assert_eq!(submit(&app, "127.0.0.1:5000", "198.51.100.9").await, StatusCode::OK);
+    assert_eq!(
+        submit(&app, "127.0.0.1:5000", "203.0.113.8, 198.51.100.9").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        submit(&app, "127.0.0.1:5000", "198.51.100.9").await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
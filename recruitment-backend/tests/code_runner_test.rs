@@ -0,0 +1,201 @@
+use recruitment_backend::models::question::TestCase;
+use recruitment_backend::services::code_runner_service::{CodeRunnerLimits, CodeRunnerService};
+use std::time::{Duration, Instant};
+
+fn rustc_available() -> bool {
+    std::process::Command::new("rustc")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn case(input: &str, expected: &str) -> TestCase {
+    TestCase {
+        input: input.to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn runs_each_test_case_against_stdin() {
+    if !rustc_available() {
+        return;
+    }
+    let source = r#"
+        use std::io::Read;
+        fn main() {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s).unwrap();
+            let n: i64 = s.trim().parse().unwrap();
+            println!("{}", n * 2);
+        }
+    "#;
+    let report = CodeRunnerService::new()
+        .run_rust(source, &[case("2", "4"), case("5", "10"), case("1", "3")])
+        .await
+        .expect("run");
+
+    assert!(report.compile_error.is_none());
+    assert_eq!(report.results.len(), 3);
+    assert_eq!(report.passed_count(), 2);
+    assert_eq!(report.results[1].stdout.trim(), "10");
+    assert!(!report.results[2].passed);
+}
+
+#[tokio::test]
+async fn reports_compile_errors() {
+    if !rustc_available() {
+        return;
+    }
+    let report = CodeRunnerService::new()
+        .run_rust("fn main() { let x: i32 = \"nope\"; }", &[case("", "")])
+        .await
+        .expect("run");
+    assert!(report.compile_error.is_some());
+    assert_eq!(report.passed_count(), 0);
+}
+
+#[tokio::test]
+async fn busy_loop_is_stopped_by_cpu_limit() {
+    if !rustc_available() {
+        return;
+    }
+    let runner = CodeRunnerService::with_limits(CodeRunnerLimits {
+        cpu_seconds: 1,
+        wall_timeout: Duration::from_secs(10),
+        ..CodeRunnerLimits::default()
+    });
+    let started = Instant::now();
+    let report = runner
+        .run_rust(
+            "fn main() { let mut x: u64 = 0; loop { x = x.wrapping_add(1); std::hint::black_box(x); } }",
+            &[case("", "")],
+        )
+        .await
+        .expect("run");
+
+    let result = &report.results[0];
+    assert!(!result.passed);
+    assert!(result.timed_out);
+    assert!(started.elapsed() < Duration::from_secs(8));
+}
+
+#[tokio::test]
+async fn default_limits_match_sandbox_policy() {
+    let limits = CodeRunnerLimits::default();
+    assert_eq!(limits.cpu_seconds, 5);
+    assert_eq!(limits.max_output_bytes, 64 * 1024 * 1024);
+}
+
+#[tokio::test]
+async fn output_is_capped() {
+    if !rustc_available() {
+        return;
+    }
+    let runner = CodeRunnerService::with_limits(CodeRunnerLimits {
+        max_output_bytes: 1024,
+        ..CodeRunnerLimits::default()
+    });
+    let report = runner
+        .run_rust(
+            "fn main() { loop { println!(\"spam spam spam spam spam spam\"); } }",
+            &[case("", "")],
+        )
+        .await
+        .expect("run");
+
+    let result = &report.results[0];
+    assert!(result.output_truncated);
+    assert!(!result.passed);
+    assert!(!result.timed_out);
+    assert!(result.stdout.len() <= 1024);
+}
+
+#[tokio::test]
+async fn network_is_unavailable() {
+    if !rustc_available() {
+        return;
+    }
+    let source = r#"
+        use std::net::{TcpStream, SocketAddr};
+        use std::time::Duration;
+        fn main() {
+            let addr: SocketAddr = "1.1.1.1:80".parse().unwrap();
+            match TcpStream::connect_timeout(&addr, Duration::from_secs(2)) {
+                Ok(_) => println!("connected"),
+                Err(_) => println!("blocked"),
+            }
+        }
+    "#;
+    let report = CodeRunnerService::new()
+        .run_rust(source, &[case("", "blocked")])
+        .await
+        .expect("run");
+    assert!(report.results[0].passed, "{:?}", report.results[0]);
+}
+
+#[tokio::test]
+async fn files_outside_the_binary_are_unreadable() {
+    if !rustc_available() {
+        return;
+    }
+    let source = r#"
+        fn main() {
+            let reads = ["/etc/passwd", "/proc/self/status", "main.rs", "../main.rs"];
+            for path in reads {
+                if std::fs::read(path).is_ok() {
+                    println!("read {}", path);
+                    return;
+                }
+            }
+            println!("blocked");
+        }
+    "#;
+    let report = CodeRunnerService::new()
+        .run_rust(source, &[case("", "blocked")])
+        .await
+        .expect("run");
+    assert!(report.results[0].passed, "{:?}", report.results[0]);
+}
+
+#[tokio::test]
+async fn files_cannot_be_included_at_compile_time() {
+    if !rustc_available() {
+        return;
+    }
+    let report = CodeRunnerService::new()
+        .run_rust(
+            r#"fn main() { print!("{}", include_str!("/etc/passwd")); }"#,
+            &[case("", "")],
+        )
+        .await
+        .expect("run");
+    assert!(report.compile_error.unwrap().contains("include_str"));
+    assert!(report.results.is_empty());
+}
+
+#[tokio::test]
+async fn processes_and_threads_cannot_be_started() {
+    if !rustc_available() {
+        return;
+    }
+    let source = r#"
+        fn main() {
+            if std::env::args().count() > 1 {
+                return;
+            }
+            let process = std::process::Command::new("/main").arg("child").status();
+            let thread = std::thread::Builder::new().spawn(|| ());
+            match (process, thread) {
+                (Err(_), Err(_)) => println!("blocked"),
+                (process, thread) => println!("process {:?}, thread {}", process, thread.is_ok()),
+            }
+        }
+    "#;
+    let report = CodeRunnerService::new()
+        .run_rust(source, &[case("", "blocked")])
+        .await
+        .expect("run");
+    assert!(report.results[0].passed, "{:?}", report.results[0]);
+}
@@ -0,0 +1,155 @@
+use std::env;
+
+use recruitment_backend::services::attempt_service::{at_capacity, AttemptService};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[test]
+fn capacity_is_reached_at_the_cap() {
+    assert!(!at_capacity(0, 1));
+    assert!(!at_capacity(4, 5));
+    assert!(at_capacity(5, 5));
+    assert!(at_capacity(6, 5));
+}
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+async fn pending_attempt(pool: &PgPool, test_id: Uuid) -> String {
+    let token = Uuid::new_v4().simple().to_string();
+    sqlx::query(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status)
+           VALUES ($1, 'Cap Test', 'cap@example.com', $2, NOW() + INTERVAL '1 day', '[]', 'pending')"#,
+    )
+    .bind(test_id)
+    .bind(&token)
+    .execute(pool)
+    .await
+    .unwrap();
+    token
+}
+
+#[tokio::test]
+async fn full_tests_turn_away_new_takers_but_not_resumes() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes, max_concurrent_takers) VALUES ('Cap test', '[]', 'question_based', 0, 30, 1) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let first = pending_attempt(&pool, test_id).await;
+    let second = pending_attempt(&pool, test_id).await;
+    let service = AttemptService::new(pool.clone());
+
+    let started = service
+        .start_attempt_by_token(&first, true, None, None)
+        .await
+        .unwrap();
+    assert_eq!(started.status, "in_progress");
+
+    let err = service
+        .start_attempt_by_token(&second, true, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "service_unavailable");
+
+    // Reloading the page while taking the test is still allowed.
+    let resumed = service
+        .start_attempt_by_token(&first, true, None, None)
+        .await
+        .unwrap();
+    assert_eq!(resumed.id, started.id);
+
+    sqlx::query("UPDATE tests SET max_concurrent_takers = NULL WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let uncapped = service
+        .start_attempt_by_token(&second, true, None, None)
+        .await
+        .unwrap();
+    assert_eq!(uncapped.status, "in_progress");
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn simultaneous_starts_never_exceed_the_cap() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes, max_concurrent_takers) VALUES ('Cap race', '[]', 'question_based', 0, 30, 2) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let mut tokens = Vec::new();
+    for _ in 0..8 {
+        tokens.push(pending_attempt(&pool, test_id).await);
+    }
+    let service = AttemptService::new(pool.clone());
+    let starts = tokens.iter().map(|token| {
+        let service = service.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            service
+                .start_attempt_by_token(&token, true, None, None)
+                .await
+        })
+    });
+    let results = futures_util::future::join_all(starts).await;
+    let started = results
+        .into_iter()
+        .filter(|r| r.as_ref().unwrap().is_ok())
+        .count();
+    assert_eq!(started, 2);
+
+    let taking: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM test_attempts WHERE test_id = $1 AND status = 'in_progress'",
+    )
+    .bind(test_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(taking, 2);
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,84 @@
+use std::env;
+
+use recruitment_backend::config::{validate_config, Config};
+
+fn load_config() -> Config {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:8000");
+    env::set_var("DATABASE_URL", "postgres://localhost/recruitment_test");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    env::set_var("INTEGRATION_RPS", "10");
+    env::set_var("PUBLIC_RPS", "20");
+    env::set_var("MAX_AI_QUESTIONS", "50");
+    env::set_var(
+        "TELEGRAM_BOT_TOKEN",
+        "123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_",
+    );
+    env::set_var("WEBAPP_URL", "https://hr.example.com");
+    Config::from_env().expect("config")
+}
+
+#[test]
+fn valid_config_passes() {
+    assert_eq!(validate_config(&load_config()), Ok(()));
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let mut config = load_config();
+    config.openai_api_key = "pk-live".into();
+    config.telegram_bot_token = "123456789:too-short".into();
+    config.server_address = "localhost".into();
+    config.integration_rps = 0;
+    config.public_rps = 0;
+    config.webapp_url = "hr.example.com".into();
+    config.ai_generation_timeout_secs = 0;
+    config.telegram_init_data_max_age_secs = 0;
+
+    let problems = validate_config(&config).unwrap_err();
+    assert_eq!(problems.len(), 8, "{:?}", problems);
+    for var in [
+        "OPENAI_API_KEY",
+        "TELEGRAM_BOT_TOKEN",
+        "SERVER_ADDRESS",
+        "INTEGRATION_RPS",
+        "PUBLIC_RPS",
+        "WEBAPP_URL",
+        "AI_GENERATION_TIMEOUT_SECS",
+        "TELEGRAM_INIT_DATA_MAX_AGE_SECS",
+    ] {
+        assert!(
+            problems.iter().any(|p| p.starts_with(var)),
+            "{} missing from {:?}",
+            var,
+            problems
+        );
+    }
+
+    config.openai_api_key = "  ".into();
+    let problems = validate_config(&config).unwrap_err();
+    assert!(problems.contains(&"OPENAI_API_KEY is empty".to_string()));
+}
+
+#[test]
+fn bot_token_must_match_the_botfather_format() {
+    let mut config = load_config();
+    for bad in [
+        "",
+        "abc:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_",
+        "123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_x",
+        "123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsa!_",
+    ] {
+        config.telegram_bot_token = bad.into();
+        assert!(validate_config(&config).is_err(), "{:?} accepted", bad);
+    }
+}
+
+#[test]
+fn ai_generation_timeout_defaults_to_five_minutes() {
+    env::remove_var("AI_GENERATION_TIMEOUT_SECS");
+    assert_eq!(load_config().ai_generation_timeout_secs, 300);
+}
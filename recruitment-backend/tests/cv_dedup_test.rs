@@ -0,0 +1,26 @@
+use recruitment_backend::services::candidate_service::CvDedupReport;
+use recruitment_backend::utils::crypto::sha256_hex;
+
+#[test]
+fn sha256_is_lowercase_hex() {
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(sha256_hex(b"%PDF-1.7 same"), sha256_hex(b"%PDF-1.7 same"));
+    assert_ne!(sha256_hex(b"%PDF-1.7 a"), sha256_hex(b"%PDF-1.7 b"));
+}
+
+#[test]
+fn report_counts_shared_files() {
+    let report = CvDedupReport::from_groups(&[(1, 0), (3, 1_000), (2, 250)]);
+    assert_eq!(
+        report,
+        CvDedupReport {
+            unique_files: 3,
+            deduplicated: 3,
+            saved_bytes: 2_250,
+        }
+    );
+    assert_eq!(CvDedupReport::from_groups(&[]), CvDedupReport::default());
+}
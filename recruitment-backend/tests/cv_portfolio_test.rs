@@ -0,0 +1,226 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use recruitment_backend::services::export_service::ExportService;
+use recruitment_backend::utils::signed_url::verify_signed_path;
+use serde_json::Value as JsonValue;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const BOUNDARY: &str = "portfolio-boundary";
+
+#[test]
+fn portfolio_summary_counts_and_names_the_files() {
+    assert_eq!(ExportService::portfolio_summary(&[]), "—");
+    assert_eq!(
+        ExportService::portfolio_summary(&[
+            "portfolio/a.pdf".to_string(),
+            "portfolio/b.png".to_string(),
+        ]),
+        "2: a.pdf, b.png"
+    );
+}
+
+/// Multipart registration form: text fields first, then `(field, filename, bytes)` files.
+fn registration(fields: &[(&str, String)], files: &[(String, &str, &[u8])]) -> Request<Body> {
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                BOUNDARY, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    for (name, filename, data) in files {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+                BOUNDARY, name, filename
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+    Request::builder()
+        .method("POST")
+        .uri("/api/candidate/register")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn applicant() -> Vec<(&'static str, String)> {
+    let id = Uuid::new_v4();
+    vec![
+        ("name", "Portfolio Candidate".to_string()),
+        ("email", format!("portfolio_{}@example.com", id)),
+        ("phone", format!("+992{}", id.as_u128() % 1_000_000_000)),
+        (
+            "telegram_id",
+            (800_000_000 + (id.as_u128() % 100_000_000) as i64).to_string(),
+        ),
+        ("dob", "1995-04-12".to_string()),
+        ("vacancy_id", "4242".to_string()),
+    ]
+}
+
+async fn call(app: &Router, req: Request<Body>) -> (StatusCode, JsonValue) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(JsonValue::Null),
+    )
+}
+
+#[tokio::test]
+async fn registration_stores_portfolio_files_behind_signed_links() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    let root = env::temp_dir().join(format!("portfolio-{}", Uuid::new_v4()));
+    env::set_var("UPLOADS_DIR", &root);
+
+    let app = Router::new()
+        .route(
+            "/api/candidate/register",
+            post(recruitment_backend::routes::candidate_routes::register_candidate),
+        )
+        .route(
+            "/api/integration/candidates/:id/portfolio",
+            get(recruitment_backend::routes::candidate_routes::get_candidate_portfolio),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let cv: &[u8] = b"%PDF-1.4 cv";
+    let png: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A];
+    let (status, body) = call(
+        &app,
+        registration(
+            &applicant(),
+            &[
+                ("cv".to_string(), "cv.pdf", cv),
+                ("portfolio_file_1".to_string(), "case.pdf", b"%PDF-1.4 case"),
+                ("portfolio_file_2".to_string(), "mockup.png", png),
+            ],
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let id: Uuid = serde_json::from_value(body["id"].clone()).unwrap();
+
+    let stored: Option<JsonValue> =
+        sqlx::query_scalar("SELECT cv_portfolio FROM candidates WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let paths: Vec<String> = serde_json::from_value(stored.expect("portfolio stored")).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].starts_with("portfolio/") && paths[0].ends_with(".pdf"));
+    assert!(paths[1].ends_with(".png"));
+    assert_eq!(std::fs::read(root.join(&paths[0])).unwrap(), b"%PDF-1.4 case");
+
+    let (status, body) = call(
+        &app,
+        Request::builder()
+            .uri(format!("/api/integration/candidates/{}/portfolio", id))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let files = body["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    let secret = recruitment_backend::config::get_config()
+        .upload_signing_secret
+        .as_bytes();
+    for (file, path) in files.iter().zip(&paths) {
+        let url = file["url"].as_str().unwrap();
+        let (signed_path, query) = url.split_once('?').unwrap();
+        assert_eq!(signed_path, format!("uploads/{}", path));
+        assert!(verify_signed_path(
+            path,
+            Some(query),
+            secret,
+            chrono::Utc::now().timestamp()
+        ));
+        assert!(path.ends_with(file["name"].as_str().unwrap()));
+    }
+
+    let six: Vec<(String, &str, &[u8])> = (1..=6)
+        .map(|n| (format!("portfolio_file_{}", n), "doc.pdf", b"%PDF-1.4 x" as &[u8]))
+        .collect();
+    let mut too_many = vec![("cv".to_string(), "cv.pdf", cv)];
+    too_many.extend(six);
+    let (status, body) = call(&app, registration(&applicant(), &too_many)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "too_many_portfolio_files");
+    assert_eq!(body["error"]["details"]["max"], 5);
+
+    let (status, _) = call(
+        &app,
+        registration(
+            &applicant(),
+            &[
+                ("cv".to_string(), "cv.pdf", cv),
+                ("portfolio_file_1".to_string(), "fake.pdf", b"not a pdf"),
+            ],
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "portfolio files get the CV checks");
+    let (status, _) = call(
+        &app,
+        registration(
+            &applicant(),
+            &[
+                ("cv".to_string(), "cv.pdf", cv),
+                ("portfolio_file_1".to_string(), "script.exe", b"MZ"),
+            ],
+        ),
+    )
+    .await;
+    assert!(status.is_client_error());
+
+    let (status, _) = call(
+        &app,
+        Request::builder()
+            .uri(format!("/api/integration/candidates/{}/portfolio", Uuid::new_v4()))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&root);
+}
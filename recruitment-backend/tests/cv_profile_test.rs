@@ -0,0 +1,132 @@
+use std::env;
+
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::ai_service::{
+    normalize_cv_profile, CvProfile, MAX_PROFILE_LANGUAGES,
+};
+use recruitment_backend::services::candidate_service::CandidateService;
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn normalizes_extracted_cv_profile() {
+    let profile = normalize_cv_profile(&json!({
+        "skills": ["Rust", "rust", " SQL "],
+        "years_experience": 6.7,
+        "last_position": "  Backend developer, Alif  ",
+        "education": "",
+        "languages": ["Russian", "English (B2)", "russian", 7]
+    }));
+    assert_eq!(
+        profile,
+        CvProfile {
+            skills: vec!["Rust".into(), "SQL".into()],
+            years_experience: Some(6),
+            last_position: Some("Backend developer, Alif".into()),
+            education: None,
+            languages: vec!["Russian".into(), "English (B2)".into()],
+        }
+    );
+}
+
+#[test]
+fn drops_implausible_profile_values() {
+    let many: Vec<String> = (0..20).map(|i| format!("Language {}", i)).collect();
+    let profile = normalize_cv_profile(&json!({
+        "years_experience": -3,
+        "last_position": "x".repeat(500),
+        "education": null,
+        "languages": many
+    }));
+    assert!(profile.skills.is_empty());
+    assert_eq!(profile.years_experience, None);
+    assert_eq!(profile.last_position, None);
+    assert_eq!(profile.languages.len(), MAX_PROFILE_LANGUAGES);
+
+    assert_eq!(
+        normalize_cv_profile(&json!({ "years_experience": 120 })).years_experience,
+        None
+    );
+    assert_eq!(
+        normalize_cv_profile(&json!("not an object")),
+        CvProfile::default()
+    );
+}
+
+#[tokio::test]
+async fn filters_candidates_by_extracted_skill() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let tag = format!("profile-skill-{}", Uuid::new_v4().simple());
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO candidates (id, name, email) VALUES ($1, 'Parsed', $2)")
+        .bind(id)
+        .bind(format!("{}@example.com", tag))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let service = CandidateService::new(pool.clone());
+    assert!(service
+        .list_filtered(&[], std::slice::from_ref(&tag), DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let profile = CvProfile {
+        skills: vec![tag.clone(), "Kotlin".into()],
+        years_experience: Some(4),
+        last_position: Some("Android developer".into()),
+        education: None,
+        languages: vec!["Tajik".into()],
+    };
+    service
+        .update_extracted_profile(id, &profile)
+        .await
+        .unwrap();
+
+    let found = service
+        .list_filtered(&[], &[tag.clone(), "Cobol".into()], DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, id);
+    let stored: CvProfile =
+        serde_json::from_value(found[0].extracted_profile.clone().unwrap()).unwrap();
+    assert_eq!(stored, profile);
+    assert_eq!(found[0].skills.as_deref(), Some(&profile.skills[..]));
+
+    assert_eq!(
+        service
+            .list_filtered(std::slice::from_ref(&tag), std::slice::from_ref(&tag), DEFAULT_ORGANIZATION_ID)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(service
+        .list_filtered(&["Cobol".into()], std::slice::from_ref(&tag), DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap()
+        .is_empty());
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
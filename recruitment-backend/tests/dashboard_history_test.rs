@@ -0,0 +1,121 @@
+use chrono::{DateTime, TimeZone, Utc};
+use recruitment_backend::services::dashboard_snapshot_service::{
+    aggregate, next_hourly_run, DashboardHistoryQuery, Granularity,
+};
+use serde_json::json;
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+}
+
+fn stats(total: i64, new: i64, completed: i64) -> serde_json::Value {
+    json!({
+        "total_candidates": total,
+        "unread_messages": 1,
+        "active_tests": 2,
+        "active_vacancies": 3,
+        "candidates_by_status": { "new": new },
+        "candidates_history": [["2026-03-01", 4]],
+        "attempts_status": { "completed": completed },
+    })
+}
+
+#[test]
+fn hourly_keeps_one_point_per_hour() {
+    let rows = vec![
+        (at(1, 9, 0), stats(10, 4, 1)),
+        (at(1, 10, 0), stats(12, 5, 2)),
+    ];
+    let points = aggregate(&rows, Granularity::Hourly);
+
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0].captured_at, at(1, 9, 0));
+    assert_eq!(points[0].samples, 1);
+    assert_eq!(points[1].total_candidates, 12.0);
+    assert_eq!(points[1].attempts_status["completed"], 2.0);
+}
+
+#[test]
+fn hourly_merges_extra_snapshots_within_the_same_hour() {
+    let rows = vec![
+        (at(1, 9, 0), stats(10, 4, 1)),
+        (at(1, 9, 40), stats(11, 4, 1)),
+    ];
+    let points = aggregate(&rows, Granularity::Hourly);
+
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].samples, 2);
+    assert_eq!(points[0].total_candidates, 10.5);
+}
+
+#[test]
+fn daily_averages_every_counter() {
+    let rows = vec![
+        (at(1, 0, 0), stats(10, 4, 0)),
+        (at(1, 12, 0), stats(11, 5, 1)),
+        (at(1, 23, 0), stats(12, 6, 1)),
+        (at(2, 0, 0), stats(20, 8, 3)),
+    ];
+    let points = aggregate(&rows, Granularity::Daily);
+
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0].captured_at, at(1, 0, 0));
+    assert_eq!(points[0].samples, 3);
+    assert_eq!(points[0].total_candidates, 11.0);
+    assert_eq!(points[0].candidates_by_status["new"], 5.0);
+    assert_eq!(points[0].attempts_status["completed"], 0.67);
+    assert_eq!(points[0].active_vacancies, 3.0);
+    assert_eq!(points[1].total_candidates, 20.0);
+}
+
+#[test]
+fn status_missing_from_a_snapshot_counts_as_zero() {
+    let rows = vec![
+        (
+            at(1, 9, 0),
+            json!({ "candidates_by_status": { "hired": 2 } }),
+        ),
+        (at(1, 15, 0), json!({ "candidates_by_status": {} })),
+    ];
+    let points = aggregate(&rows, Granularity::Daily);
+
+    assert_eq!(points[0].candidates_by_status["hired"], 1.0);
+    assert_eq!(points[0].total_candidates, 0.0);
+}
+
+#[test]
+fn unreadable_snapshots_are_skipped() {
+    let rows = vec![
+        (at(1, 9, 0), json!("garbage")),
+        (at(1, 10, 0), stats(7, 1, 0)),
+    ];
+    let points = aggregate(&rows, Granularity::Hourly);
+
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].total_candidates, 7.0);
+}
+
+#[test]
+fn next_run_is_the_top_of_the_following_hour() {
+    assert_eq!(next_hourly_run(at(1, 9, 15)), at(1, 10, 0));
+    assert_eq!(next_hourly_run(at(1, 9, 0)), at(1, 10, 0));
+    assert_eq!(next_hourly_run(at(1, 23, 59)), at(2, 0, 0));
+}
+
+#[test]
+fn history_query_parses_granularity() {
+    let query: DashboardHistoryQuery = serde_json::from_value(
+        json!({ "from": "2026-03-01", "to": "2026-03-07", "granularity": "daily" }),
+    )
+    .unwrap();
+    assert_eq!(query.granularity, Some(Granularity::Daily));
+    assert!(query.from.is_some());
+
+    let query: DashboardHistoryQuery = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(query.granularity.unwrap_or_default(), Granularity::Hourly);
+
+    assert!(
+        serde_json::from_value::<DashboardHistoryQuery>(json!({ "granularity": "weekly" }))
+            .is_err()
+    );
+}
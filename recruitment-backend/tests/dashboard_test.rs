@@ -0,0 +1,83 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use recruitment_backend::services::dashboard_service::{DashboardRange, DEFAULT_HISTORY_DAYS};
+use recruitment_backend::utils::ttl_cache::TtlCache;
+use std::time::Duration;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn range_bounds_cover_whole_days() {
+    let range = DashboardRange {
+        from: Some(date(2026, 3, 1)),
+        to: Some(date(2026, 3, 7)),
+    };
+    assert_eq!(
+        range.start(),
+        Some(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        range.end(),
+        Some(Utc.with_ymd_and_hms(2026, 3, 8, 0, 0, 0).unwrap())
+    );
+
+    let single_day = DashboardRange {
+        from: Some(date(2026, 2, 28)),
+        to: Some(date(2026, 2, 28)),
+    };
+    assert_eq!(
+        single_day.end(),
+        Some(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert!(single_day.validate().is_ok());
+}
+
+#[test]
+fn history_window_defaults_to_last_week() {
+    let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+
+    let (start, end) = DashboardRange::default().history_window(now);
+    assert_eq!(start, now - chrono::Duration::days(DEFAULT_HISTORY_DAYS));
+    assert_eq!(end, None);
+    assert_eq!(DashboardRange::default().start(), None);
+
+    let only_to = DashboardRange {
+        from: None,
+        to: Some(date(2026, 1, 31)),
+    };
+    let (start, end) = only_to.history_window(now);
+    assert_eq!(
+        end,
+        Some(Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(start, Utc.with_ymd_and_hms(2026, 1, 25, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn inverted_range_is_rejected() {
+    let range = DashboardRange {
+        from: Some(date(2026, 3, 8)),
+        to: Some(date(2026, 3, 1)),
+    };
+    assert!(range.validate().is_err());
+}
+
+#[test]
+fn ttl_cache_expires_entries() {
+    let cache: TtlCache<DashboardRange, i64> = TtlCache::new(Duration::from_millis(50));
+    let all_time = DashboardRange::default();
+    let march = DashboardRange {
+        from: Some(date(2026, 3, 1)),
+        to: None,
+    };
+
+    cache.insert(all_time, 1);
+    cache.insert(march, 2);
+    assert_eq!(cache.get(&all_time), Some(1));
+    assert_eq!(cache.get(&march), Some(2));
+
+    std::thread::sleep(Duration::from_millis(80));
+    assert_eq!(cache.get(&all_time), None);
+    assert_eq!(cache.get(&march), None);
+}
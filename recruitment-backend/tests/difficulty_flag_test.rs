@@ -0,0 +1,300 @@
+use std::env;
+
+use recruitment_backend::dto::integration_dto::{CreateQuestion, CreateTestPayload};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::models::question::{
+    Difficulty, MultipleChoiceDetails, Question, QuestionDetails, QuestionType,
+};
+use recruitment_backend::services::attempt_service::{
+    AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::grading_service::GradingService;
+use recruitment_backend::services::test_service::{
+    apply_replacements, difficulty_flag, questions_to_replace, DifficultyAdjustment,
+    DifficultyFlag, QuestionAnswerRate, QuestionReplacement, TestService,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+fn mcq(id: i32, text: &str, difficulty: Difficulty) -> Question {
+    Question {
+        id,
+        question_type: QuestionType::MultipleChoice,
+        question: text.into(),
+        points: 5,
+        difficulty,
+        details: QuestionDetails::MultipleChoice(MultipleChoiceDetails {
+            options: vec!["A".into(), "B".into(), "C".into()],
+            correct_answer: 0,
+            explanation: None,
+        }),
+    }
+}
+
+fn rate(question_id: i32, answered: i64, correct: i64) -> QuestionAnswerRate {
+    QuestionAnswerRate {
+        question_id,
+        answered,
+        correct,
+    }
+}
+
+#[test]
+fn flags_follow_pass_rate_thresholds() {
+    assert_eq!(difficulty_flag(95.0), Some(DifficultyFlag::TooEasy));
+    assert_eq!(difficulty_flag(90.0), None);
+    assert_eq!(difficulty_flag(55.0), None);
+    assert_eq!(difficulty_flag(30.0), None);
+    assert_eq!(difficulty_flag(10.0), Some(DifficultyFlag::TooHard));
+
+    assert_eq!(
+        DifficultyFlag::parse("too_easy"),
+        Some(DifficultyFlag::TooEasy)
+    );
+    assert_eq!(DifficultyFlag::parse("fine"), None);
+    assert_eq!(
+        DifficultyFlag::TooEasy.target_difficulty(),
+        Difficulty::Hard
+    );
+    assert_eq!(
+        DifficultyFlag::TooHard.target_difficulty(),
+        Difficulty::Easy
+    );
+}
+
+#[test]
+fn picks_questions_everyone_or_no_one_got_right() {
+    let rates = [
+        rate(1, 4, 4),
+        rate(2, 6, 6),
+        rate(3, 6, 3),
+        rate(4, 5, 0),
+        rate(5, 0, 0),
+    ];
+    let easy: Vec<i32> = questions_to_replace(DifficultyFlag::TooEasy, &rates, 5)
+        .iter()
+        .map(|r| r.question_id)
+        .collect();
+    assert_eq!(easy, [2, 1]);
+    let hard: Vec<i32> = questions_to_replace(DifficultyFlag::TooHard, &rates, 5)
+        .iter()
+        .map(|r| r.question_id)
+        .collect();
+    assert_eq!(hard, [4]);
+    assert_eq!(
+        questions_to_replace(DifficultyFlag::TooEasy, &rates, 1).len(),
+        1
+    );
+}
+
+#[test]
+fn replacements_keep_position_and_detect_edits() {
+    let questions = vec![
+        mcq(1, "Q1", Difficulty::Easy),
+        mcq(2, "Q2", Difficulty::Medium),
+    ];
+    let replacement = QuestionReplacement {
+        question_id: 1,
+        answered: 5,
+        correct: 5,
+        previous: questions[0].clone(),
+        replacement: mcq(1, "Harder Q1", Difficulty::Hard),
+    };
+
+    let applied =
+        apply_replacements(questions.clone(), std::slice::from_ref(&replacement)).unwrap();
+    assert_eq!(applied[0].question, "Harder Q1");
+    assert_eq!(applied[0].difficulty, Difficulty::Hard);
+    assert_eq!(applied[1].question, "Q2");
+
+    let mut edited = questions;
+    edited[0].question = "Q1 (edited)".into();
+    assert!(apply_replacements(edited, &[replacement]).is_none());
+}
+
+#[tokio::test]
+async fn flags_too_easy_test_and_applies_previewed_proposal() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Difficulty User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("difficulty_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let question = |text: &str| CreateQuestion {
+        question_type: QuestionType::MultipleChoice,
+        question: text.into(),
+        points: 5,
+        difficulty: Difficulty::Easy,
+        details: QuestionDetails::MultipleChoice(MultipleChoiceDetails {
+            options: vec!["A".into(), "B".into(), "C".into()],
+            correct_answer: 0,
+            explanation: None,
+        }),
+    };
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(
+            CreateTestPayload {
+                title: "Difficulty".into(),
+                external_id: None,
+                description: None,
+                instructions: None,
+                questions: Some(vec![question("Q1"), question("Q2")]),
+                duration_minutes: 10,
+                passing_score: 50.0,
+                shuffle_questions: None,
+                shuffle_options: None,
+                show_results_immediately: None,
+                test_type: None,
+                presentation_themes: None,
+                presentation_extra_info: None,
+                show_honesty_declaration: None,
+                declaration_text: None,
+                prerequisite_test_id: None,
+                default_invite_expiry_hours: None,
+                reminder_hours_before: None,
+                passing_score_mcq: None,
+                passing_score_open: None,
+                share_results_details: None,
+                max_grace_seconds: None,
+                max_concurrent_takers: None,
+                language: None,
+                reveal_correct_answers: None,
+            },
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create test");
+    let questions: Vec<Question> = serde_json::from_value(test.questions.clone()).unwrap();
+
+    assert_eq!(
+        tests.get_pass_rate(test.id).await.unwrap_err().code(),
+        "no_completed_attempts"
+    );
+
+    // Five passing attempts: Q1 always right, Q2 right only twice.
+    let attempts = AttemptService::new(pool.clone());
+    for i in 0..5 {
+        let invite = attempts
+            .create_invite(
+                test.id,
+                InviteCandidate {
+                    external_id: None,
+                    name: format!("Cand {}", i),
+                    email: format!("difficulty_{}_{}@example.com", i, Uuid::new_v4()),
+                    telegram_id: None,
+                    phone: None,
+                },
+                2,
+                None,
+                None,
+                InviteSource::Integration,
+            )
+            .await
+            .expect("invite");
+        let answers = vec![
+            json!({ "question_id": 1, "answer": 0 }),
+            json!({ "question_id": 2, "answer": if i < 2 { 0 } else { 1 } }),
+        ];
+        let (_, _, graded, _) = GradingService::grade_mcq_only(&questions, &answers);
+        sqlx::query(
+            r#"
+            UPDATE test_attempts
+            SET status = 'completed', answers = $2, graded_answers = $3,
+                passed = TRUE, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(invite.attempt_id)
+        .bind(json!(answers))
+        .bind(json!(graded))
+        .execute(&pool)
+        .await
+        .expect("seed attempt");
+    }
+
+    assert_eq!(tests.get_pass_rate(test.id).await.unwrap(), 100.0);
+    assert_eq!(
+        tests.refresh_difficulty_flag(test.id).await.unwrap(),
+        Some(DifficultyFlag::TooEasy)
+    );
+    assert_eq!(
+        tests.get_difficulty_flag(test.id).await.unwrap(),
+        Some(DifficultyFlag::TooEasy)
+    );
+    assert_eq!(
+        tests.question_answer_rates(test.id).await.unwrap(),
+        [rate(1, 5, 5), rate(2, 5, 2)]
+    );
+
+    assert_eq!(
+        tests
+            .apply_difficulty_adjustment(test.id)
+            .await
+            .unwrap_err()
+            .code(),
+        "adjustment_not_previewed"
+    );
+
+    // Stand-in for the dry run, which needs the AI to generate the replacement.
+    let proposal = DifficultyAdjustment {
+        test_id: test.id,
+        difficulty_flag: DifficultyFlag::TooEasy,
+        pass_rate: 100.0,
+        replacements: vec![QuestionReplacement {
+            question_id: 1,
+            answered: 5,
+            correct: 5,
+            previous: questions[0].clone(),
+            replacement: mcq(1, "Harder Q1", Difficulty::Hard),
+        }],
+        proposed_at: chrono::Utc::now(),
+    };
+    sqlx::query("UPDATE tests SET difficulty_proposal = $2 WHERE id = $1")
+        .bind(test.id)
+        .bind(json!(proposal))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let applied = tests.apply_difficulty_adjustment(test.id).await.unwrap();
+    assert_eq!(applied.replacements.len(), 1);
+    let updated: Vec<Question> =
+        serde_json::from_value(tests.get_test_by_id(test.id).await.unwrap().questions).unwrap();
+    assert_eq!(updated[0].question, "Harder Q1");
+    assert_eq!(updated[1].question, "Q2");
+    assert_eq!(tests.get_difficulty_flag(test.id).await.unwrap(), None);
+    // Attempts on the old questions no longer count.
+    assert!(tests.get_pass_rate(test.id).await.is_err());
+    assert_eq!(tests.refresh_difficulty_flag(test.id).await.unwrap(), None);
+
+    tests.delete_test(test.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
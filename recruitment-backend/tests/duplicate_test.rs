@@ -0,0 +1,237 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use axum::{routing::post, Json, Router};
+use recruitment_backend::dto::integration_dto::{CreateQuestion, CreateTestPayload};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::models::question::{
+    Difficulty, MultipleChoiceDetails, QuestionDetails, QuestionType,
+};
+use recruitment_backend::services::ai_service::AIService;
+use recruitment_backend::services::test_service::{
+    next_variant_number, profession_from_title, variant_base_title, TestService,
+};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+#[test]
+fn variant_titles_share_the_original_base() {
+    assert_eq!(variant_base_title("Rust Basics (Variant 3)"), "Rust Basics");
+    assert_eq!(variant_base_title("Rust Basics"), "Rust Basics");
+    assert_eq!(
+        variant_base_title("Rust Basics (Variant x)"),
+        "Rust Basics (Variant x)"
+    );
+
+    let titles = vec![
+        "Rust Basics".to_string(),
+        "Rust Basics (Variant 1)".to_string(),
+        "Rust Basics (Variant 4)".to_string(),
+        "Rust Basics Advanced (Variant 9)".to_string(),
+    ];
+    assert_eq!(next_variant_number("Rust Basics", &titles), 5);
+    assert_eq!(next_variant_number("Go Basics", &titles), 1);
+}
+
+#[test]
+fn profession_is_recovered_from_generated_titles() {
+    assert_eq!(
+        profession_from_title("AI Backend Developer Test"),
+        "Backend Developer"
+    );
+    assert_eq!(
+        profession_from_title("Accountant Assessment (Variant 2)"),
+        "Accountant"
+    );
+    assert_eq!(profession_from_title("Sales Manager"), "Sales Manager");
+    assert_eq!(profession_from_title("Test"), "Test");
+}
+
+fn original_payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: Some("Original description".into()),
+        instructions: Some("Read carefully".into()),
+        questions: Some(vec![CreateQuestion {
+            question_type: QuestionType::MultipleChoice,
+            question: "Original question?".into(),
+            points: 5,
+            difficulty: Difficulty::Easy,
+            details: QuestionDetails::MultipleChoice(MultipleChoiceDetails {
+                options: vec!["A".into(), "B".into()],
+                correct_answer: 0,
+                explanation: None,
+            }),
+        }]),
+        duration_minutes: 25,
+        passing_score: 60.0,
+        shuffle_questions: Some(true),
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: Some(24),
+        reminder_hours_before: Some(vec![2]),
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: Some("en".into()),
+        reveal_correct_answers: None,
+    }
+}
+
+async fn mock_openai(requests: Arc<Mutex<Vec<JsonValue>>>) -> String {
+    let questions: Vec<JsonValue> = (0..3)
+        .map(|i| {
+            json!({
+                "type": "multiple_choice",
+                "difficulty": "medium",
+                "question": format!("Generated question {}?", i),
+                "options": ["One", "Two", "Three", "Four"],
+                "correct_answer": 1,
+            })
+        })
+        .collect();
+    let api = Router::new().route(
+        "/chat/completions",
+        post(move |Json(body): Json<JsonValue>| {
+            let requests = requests.clone();
+            let questions = questions.clone();
+            async move {
+                requests.lock().unwrap().push(body);
+                Json(json!({
+                    "model": "gpt-4o",
+                    "usage": { "prompt_tokens": 10, "completion_tokens": 20 },
+                    "choices": [{
+                        "message": {
+                            "content": json!({ "questions": questions }).to_string()
+                        }
+                    }]
+                }))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, api).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn duplicate_copies_metadata_with_new_questions() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Duplicate User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("duplicate_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let ai = AIService::new(
+        "sk-test".into(),
+        mock_openai(requests.clone()).await,
+        reqwest::Client::new(),
+    );
+
+    let tests = TestService::new(pool.clone());
+    let suffix = Uuid::new_v4().simple().to_string();
+    let original = tests
+        .create_test(
+            original_payload(&format!("AI Dev{} Test", suffix)),
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create original");
+
+    let first = tests
+        .duplicate_test(&ai, original.id, DEFAULT_ORGANIZATION_ID, 3, None)
+        .await
+        .expect("first variant");
+    assert_eq!(first.title, format!("{} (Variant 1)", original.title));
+    assert_ne!(first.id, original.id);
+    assert_eq!(first.description, original.description);
+    assert_eq!(first.instructions, original.instructions);
+    assert_eq!(first.duration_minutes, original.duration_minutes);
+    assert_eq!(first.passing_score, original.passing_score);
+    assert_eq!(first.shuffle_questions, original.shuffle_questions);
+    assert_eq!(first.default_invite_expiry_hours, Some(24));
+    assert_eq!(first.reminder_hours_before, vec![2]);
+    assert_eq!(first.created_by, original.created_by);
+    assert_eq!(first.language, "en");
+
+    let questions = first.questions.as_array().unwrap();
+    assert_eq!(questions.len(), 3);
+    assert!(questions
+        .iter()
+        .all(|q| q["question"].as_str().unwrap().starts_with("Generated")));
+    let ids: Vec<i64> = questions
+        .iter()
+        .map(|q| q["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    let user_message = requests.lock().unwrap()[0]["messages"][1]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(user_message.contains(&format!("Dev{}", suffix)));
+
+    // Variants of variants keep counting against the original title.
+    let second = tests
+        .duplicate_test(
+            &ai,
+            first.id,
+            DEFAULT_ORGANIZATION_ID,
+            3,
+            Some("Data Analyst".into()),
+        )
+        .await
+        .expect("second variant");
+    assert_eq!(second.title, format!("{} (Variant 2)", original.title));
+    let user_message = requests.lock().unwrap()[1]["messages"][1]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(user_message.contains("Data Analyst"));
+
+    let untouched = tests.get_test_by_id(original.id).await.unwrap();
+    assert_eq!(untouched.questions.as_array().unwrap().len(), 1);
+
+    for id in [second.id, first.id, original.id] {
+        tests.delete_test(id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    }
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
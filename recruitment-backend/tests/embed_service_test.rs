@@ -0,0 +1,26 @@
+use recruitment_backend::services::embed_service::EmbedService;
+
+#[test]
+fn similarity_matrix_matches_pairwise_cosine() {
+    let embs = vec![
+        vec![1.0, 0.0, 0.0],
+        vec![2.0, 2.0, 0.0],
+        vec![0.0, 0.0, 0.0],
+        vec![-1.0, 0.5, 3.0],
+    ];
+    let matrix = EmbedService::cosine_sim_matrix(&embs);
+    assert_eq!(matrix.len(), embs.len());
+    for (i, a) in embs.iter().enumerate() {
+        for (j, b) in embs.iter().enumerate() {
+            let expected = if i == j && a.iter().any(|x| *x != 0.0) {
+                1.0
+            } else {
+                EmbedService::cosine_sim(a, b)
+            };
+            assert!((matrix[i][j] - expected).abs() < 1e-6, "({i}, {j})");
+            assert_eq!(matrix[i][j], matrix[j][i]);
+        }
+    }
+    assert_eq!(matrix[2][2], 0.0);
+    assert!(EmbedService::cosine_sim_matrix(&[]).is_empty());
+}
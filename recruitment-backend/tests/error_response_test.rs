@@ -0,0 +1,96 @@
+use axum::{
+    body::to_bytes,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use recruitment_backend::error::Error;
+use serde_json::Value as JsonValue;
+use validator::Validate;
+
+async fn render(err: Error) -> (StatusCode, JsonValue) {
+    let resp = err.into_response();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn business_errors_carry_stable_codes() {
+    let (status, body) = render(Error::test_expired()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "test_expired");
+    assert!(body["error"]["message"].is_string());
+    assert!(body["error"]["details"].is_null());
+
+    let (status, body) = render(Error::already_completed("Test has already been submitted")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "already_completed");
+    assert_eq!(body["error"]["message"], "Test has already been submitted");
+
+    let (status, body) = render(Error::pending_invite_exists()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "pending_invite_exists");
+
+    let (status, body) = render(Error::candidate_not_found()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "candidate_not_found");
+}
+
+#[tokio::test]
+async fn generic_variants_map_to_snake_case_codes() {
+    let (status, body) = render(Error::BadRequest("nope".into())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "bad_request");
+    assert_eq!(body["error"]["message"], "nope");
+
+    let (status, body) = render(Error::from(sqlx::Error::RowNotFound)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "not_found");
+
+    let (status, body) = render(Error::Internal("boom".into())).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"]["code"], "internal_error");
+}
+
+#[tokio::test]
+async fn service_unavailable_asks_clients_to_retry_later() {
+    let resp = Error::ServiceUnavailable("Test is at capacity".into()).into_response();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(resp.headers()["retry-after"], "300");
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let body: JsonValue = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"]["code"], "service_unavailable");
+    assert_eq!(body["error"]["message"], "Test is at capacity");
+
+    let resp = Error::Internal("boom".into()).into_response();
+    assert!(resp.headers().get("retry-after").is_none());
+}
+
+#[tokio::test]
+async fn invalid_status_transition_includes_details() {
+    let err = recruitment_backend::services::status_pipeline_service::StatusPipelineService::validate_transition(
+        "new", "accepted",
+    )
+    .unwrap_err();
+    let (status, body) = render(err).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "invalid_status_transition");
+    assert_eq!(body["error"]["details"]["from"], "new");
+    assert_eq!(body["error"]["details"]["to"], "accepted");
+    assert_eq!(
+        body["error"]["details"]["allowed_next_statuses"],
+        serde_json::json!(["reviewing", "rejected"])
+    );
+}
+
+#[tokio::test]
+async fn validation_errors_list_failing_fields() {
+    let payload = recruitment_backend::dto::integration_dto::ExtendAttemptPayload { extra_minutes: 0 };
+    let err = Error::from(payload.validate().unwrap_err());
+    let (status, body) = render(err).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "validation_failed");
+    let field = &body["error"]["details"]["fields"]["extra_minutes"][0];
+    assert_eq!(field["code"], "range");
+    assert_eq!(field["message"], "extra_minutes must be between 1 and 120");
+}
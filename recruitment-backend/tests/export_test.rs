@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use chrono::{TimeZone, Utc};
+use recruitment_backend::models::candidate::{Candidate, HistoryItem};
+use recruitment_backend::services::export_service::{
+    rating_histogram, status_distribution, CandidateDataExport, ExportOptions, ExportService,
+};
+use uuid::Uuid;
+
+fn candidate(name: &str, status: &str, ai_rating: Option<i32>) -> Candidate {
+    Candidate {
+        id: Uuid::new_v4(),
+        telegram_id: None,
+        telegram_thread_id: None,
+        name: Some(name.into()),
+        email: Some(format!("{}@example.com", name.to_lowercase())),
+        phone: None,
+        cv_url: None,
+        photo_url: None,
+        cv_portfolio: None,
+        dob: None,
+        vacancy_id: Some(42),
+        profile_data: None,
+        ai_rating,
+        ai_comment: None,
+        skills: None,
+        extracted_profile: None,
+        timezone: None,
+        telegram_unreachable: false,
+        status: status.into(),
+        unread_messages: None,
+        last_activity_at: None,
+        deleted_at: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+fn event(event_type: &str, day: u32, status: Option<&str>) -> HistoryItem {
+    HistoryItem {
+        event_type: event_type.into(),
+        title: event_type.into(),
+        description: Some(format!("{} #{}", event_type, day)),
+        timestamp: Utc.with_ymd_and_hms(2025, 2, day, 10, 0, 0).unwrap(),
+        status: status.map(str::to_string),
+        metadata: None,
+    }
+}
+
+/// Sheet names in workbook order.
+fn sheet_names(xlsx: &[u8]) -> Vec<String> {
+    let workbook = read_part(xlsx, "xl/workbook.xml");
+    workbook
+        .split("<sheet name=\"")
+        .skip(1)
+        .map(|rest| rest[..rest.find('"').unwrap()].to_string())
+        .collect()
+}
+
+/// Number of `<row>` elements in the n-th (1-based) worksheet.
+fn row_count(xlsx: &[u8], sheet: usize) -> usize {
+    read_part(xlsx, &format!("xl/worksheets/sheet{}.xml", sheet))
+        .matches("<row ")
+        .count()
+}
+
+fn read_part(xlsx: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(xlsx)).expect("xlsx is a zip");
+    let mut part = archive.by_name(name).expect("part exists");
+    let mut xml = String::new();
+    part.read_to_string(&mut xml).unwrap();
+    xml
+}
+
+fn fixture() -> (Vec<Candidate>, HashMap<Uuid, Vec<HistoryItem>>) {
+    let mut candidates = vec![
+        candidate("Алишер", "accepted", Some(88)),
+        candidate("Мадина", "new", Some(42)),
+        candidate("Фарход", "test_assigned", None),
+    ];
+    candidates[0].cv_portfolio = Some(serde_json::json!(["portfolio/case.pdf"]));
+    let mut history = HashMap::new();
+    history.insert(
+        candidates[0].id,
+        (1..=8)
+            .map(|d| event("application", d, Some("candidate_profile.status_passed")))
+            .collect(),
+    );
+    history.insert(candidates[1].id, vec![event("registration", 3, None)]);
+    (candidates, history)
+}
+
+#[test]
+fn main_sheet_only_by_default() {
+    let (candidates, history) = fixture();
+    let xlsx = ExportService::generate_candidates_xlsx(
+        &candidates,
+        &HashMap::new(),
+        &history,
+        ExportOptions::default(),
+    )
+    .expect("xlsx");
+
+    assert_eq!(sheet_names(&xlsx), ["Candidates"]);
+    // Title, subtitle, header, one row per candidate and the summary row.
+    assert_eq!(row_count(&xlsx, 1), 3 + candidates.len() + 1);
+    let strings = read_part(&xlsx, "xl/sharedStrings.xml");
+    assert!(strings.contains("Файлы портфолио"));
+    assert!(strings.contains("1: case.pdf"));
+}
+
+#[test]
+fn history_sheet_lists_every_event() {
+    let (candidates, history) = fixture();
+    let xlsx = ExportService::generate_candidates_xlsx(
+        &candidates,
+        &HashMap::new(),
+        &history,
+        ExportOptions {
+            include_history: true,
+            include_stats: false,
+        },
+    )
+    .expect("xlsx");
+
+    assert_eq!(sheet_names(&xlsx), ["Candidates", "История"]);
+    assert_eq!(row_count(&xlsx, 2), 1 + 9);
+    let strings = read_part(&xlsx, "xl/sharedStrings.xml");
+    assert!(strings.contains("application #8"));
+    assert!(strings.contains("Пройден"));
+}
+
+#[test]
+fn stats_sheet_has_distribution_and_histogram() {
+    let (candidates, history) = fixture();
+    let xlsx = ExportService::generate_candidates_xlsx(
+        &candidates,
+        &HashMap::new(),
+        &history,
+        ExportOptions {
+            include_history: true,
+            include_stats: true,
+        },
+    )
+    .expect("xlsx");
+
+    assert_eq!(sheet_names(&xlsx), ["Candidates", "История", "Статистика"]);
+    let statuses = status_distribution(&candidates).len();
+    let buckets = rating_histogram(&candidates).len();
+    // Each table: section title and header, then its rows; a blank row between.
+    assert_eq!(row_count(&xlsx, 3), 2 + statuses + 2 + buckets);
+}
+
+#[test]
+fn breakdowns_count_candidates() {
+    let (candidates, _) = fixture();
+    let statuses = status_distribution(&candidates);
+    assert_eq!(
+        statuses,
+        [
+            ("new".to_string(), 1),
+            ("reviewing".to_string(), 0),
+            ("contacted".to_string(), 0),
+            ("accepted".to_string(), 1),
+            ("rejected".to_string(), 0),
+            ("test_assigned".to_string(), 1),
+        ]
+    );
+
+    let histogram = rating_histogram(&candidates);
+    assert_eq!(histogram.len(), 11);
+    assert_eq!(histogram[4], ("40–49".to_string(), 1));
+    assert_eq!(histogram[8], ("80–89".to_string(), 1));
+    assert_eq!(histogram[9].0, "90–100");
+    assert_eq!(histogram[10], ("Без оценки".to_string(), 1));
+
+    let perfect = [candidate("Top", "accepted", Some(100))];
+    assert_eq!(rating_histogram(&perfect)[9].1, 1);
+}
+
+#[test]
+fn data_archive_holds_candidate_json_messages_and_cv() {
+    let mut alisher = candidate("Алишер", "accepted", Some(88));
+    alisher.ai_comment = Some("Strong logistics background".into());
+    let history = vec![event("registration", 3, None)];
+    let archive = ExportService::generate_candidate_data_archive(
+        &alisher,
+        &CandidateDataExport {
+            history: &history,
+            cv: Some(("uploads/cv/resume.pdf", b"%PDF-1.4 test")),
+            ..Default::default()
+        },
+    )
+    .expect("zip");
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(&archive)).expect("zip");
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort_unstable();
+    assert_eq!(names, ["candidate.json", "cv/resume.pdf", "messages.json"]);
+
+    let document: serde_json::Value =
+        serde_json::from_str(&read_part(&archive, "candidate.json")).unwrap();
+    assert_eq!(document["candidate"]["ai_rating"], 88);
+    assert_eq!(
+        document["candidate"]["ai_comment"],
+        "Strong logistics background"
+    );
+    assert_eq!(document["history"].as_array().unwrap().len(), 1);
+    assert_eq!(document["test_attempts"], serde_json::json!([]));
+    assert_eq!(read_part(&archive, "messages.json"), "[]");
+
+    let mut cv = Vec::new();
+    zip.by_name("cv/resume.pdf")
+        .unwrap()
+        .read_to_end(&mut cv)
+        .unwrap();
+    assert_eq!(cv, b"%PDF-1.4 test");
+}
@@ -0,0 +1,200 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::post,
+    Router,
+};
+use recruitment_backend::routes::integration::extend_test_attempt;
+use recruitment_backend::AppState;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn app(pool: &PgPool) -> Router {
+    Router::new()
+        .route(
+            "/api/integration/test-attempts/:id/extend",
+            post(extend_test_attempt),
+        )
+        .with_state(AppState::new(pool.clone()))
+}
+
+async fn extend(app: &Router, id: Uuid, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/integration/test-attempts/{}/extend", id))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn attempt(pool: &PgPool, test_id: Uuid, status: &str) -> Uuid {
+    sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, started_at)
+           VALUES ($1, 'Extend Test', 'extend@example.com', $2, NOW() + INTERVAL '5 minutes',
+                   '[]', $3, NOW() - INTERVAL '25 minutes')
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(status)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn audit_entries(pool: &PgPool, id: Uuid) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_logs WHERE action = 'extend_attempt' AND entity_id = $1",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn extending_an_attempt_moves_its_deadline_and_is_audited() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Extend endpoint', '[]', 'question_based', 0, 30) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let running = attempt(&pool, test_id, "in_progress").await;
+    let finished = attempt(&pool, test_id, "completed").await;
+    let app = app(&pool);
+
+    let (status, body) = extend(&app, running, json!({ "extra_minutes": 20 })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["extension_seconds"], 20 * 60);
+    let changes: Value = sqlx::query_scalar(
+        "SELECT changes FROM audit_logs WHERE action = 'extend_attempt' AND entity_id = $1",
+    )
+    .bind(running)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(changes["extra_minutes"], 20);
+    assert_eq!(changes["new_expires_at"], body["expires_at"]);
+
+    let (status, _) = extend(&app, running, json!({ "extra_minutes": 0 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = extend(&app, finished, json!({ "extra_minutes": 10 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = extend(&app, Uuid::new_v4(), json!({ "extra_minutes": 10 })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(audit_entries(&pool, running).await, 1);
+    assert_eq!(audit_entries(&pool, finished).await, 0);
+
+    sqlx::query("DELETE FROM audit_logs WHERE entity_id = $1")
+        .bind(running)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_failed_audit_write_does_not_fail_the_extension() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Extend audit down', '[]', 'question_based', 0, 30) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let running = attempt(&pool, test_id, "in_progress").await;
+
+    // Audit inserts for this attempt only fail, so other tests are unaffected.
+    let trigger = format!("fail_audit_{}", running.simple());
+    sqlx::query(&format!(
+        r#"CREATE FUNCTION {trigger}() RETURNS trigger AS $$
+           BEGIN
+               IF NEW.entity_id = '{running}' THEN RAISE EXCEPTION 'audit unavailable'; END IF;
+               RETURN NEW;
+           END $$ LANGUAGE plpgsql"#
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(&format!(
+        "CREATE TRIGGER {trigger} BEFORE INSERT ON audit_logs FOR EACH ROW EXECUTE FUNCTION {trigger}()"
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = extend(&app(&pool), running, json!({ "extra_minutes": 15 })).await;
+
+    sqlx::query(&format!("DROP TRIGGER {trigger} ON audit_logs"))
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(&format!("DROP FUNCTION {trigger}()"))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["extension_seconds"], 15 * 60);
+    assert_eq!(audit_entries(&pool, running).await, 0);
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use recruitment_backend::services::ai_service::{dedup_questions, GenerationOutput, ModelUsage};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn dedup_drops_repeated_question_text() {
+    let raw = json!({ "questions": [
+        { "question": "Что такое  SQL-инъекция?" },
+        { "question": "Объясните индексы" },
+        { "question": "что такое sql-инъекция?" },
+        { "question": "" },
+        { "question": "" },
+    ]});
+    let (unique, removed) = dedup_questions(&raw);
+    assert_eq!(removed, 1);
+    let texts: Vec<&str> = unique["questions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|q| q["question"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        texts,
+        ["Что такое  SQL-инъекция?", "Объясните индексы", "", ""]
+    );
+}
+
+async fn get_log(app: &Router, test_id: Uuid) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/integration/tests/{}/generation-log", test_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn generation_log_lists_stored_runs() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score) VALUES ('Generated test', '[]', 'question_based', 70) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let state = recruitment_backend::AppState::new(pool.clone());
+    let output = GenerationOutput {
+        logs: vec![
+            "Starting generation".into(),
+            "Removed 2 duplicate questions.".into(),
+        ],
+        total_prompt_tokens: 1200,
+        total_completion_tokens: 800,
+        calls_made: 1,
+        usage_by_model: HashMap::from([(
+            "gpt-4o".to_string(),
+            ModelUsage {
+                prompt_tokens: 1200,
+                completion_tokens: 800,
+                calls: 1,
+            },
+        )]),
+        blueprint: json!({ "profession": "QA", "required_count": 8 }),
+        returned_questions: 9,
+        duplicates_removed: 2,
+        ..Default::default()
+    };
+    state
+        .test_service
+        .record_generation_run(test_id, None, &output, 8, 7, 1)
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/integration/tests/:id/generation-log",
+            get(recruitment_backend::routes::integration::get_generation_log),
+        )
+        .with_state(state);
+
+    let (status, body) = get_log(&app, test_id).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let runs = body["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run["blueprint"]["profession"], "QA");
+    assert_eq!(run["logs"][1], "Removed 2 duplicate questions.");
+    assert_eq!(run["models"], json!(["gpt-4o"]));
+    assert_eq!(run["requested_questions"], 8);
+    assert_eq!(run["returned_questions"], 9);
+    assert_eq!(run["duplicates_removed"], 2);
+    assert_eq!(run["topup_rounds"], 1);
+    assert_eq!(run["delivered_questions"], 7);
+    assert_eq!(run["prompt_tokens"], 1200);
+
+    let (status, _) = get_log(&app, Uuid::new_v4()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
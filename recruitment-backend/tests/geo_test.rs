@@ -0,0 +1,147 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Path,
+    http::Request,
+    routing::get,
+    Json, Router,
+};
+use recruitment_backend::routes::public::ClientIp;
+use recruitment_backend::services::geo_service::{
+    is_public_ip, is_suspicious_geo, parse_ip_api, GeoLocation, GeoService,
+};
+use serde_json::json;
+use std::net::IpAddr;
+use tower::ServiceExt;
+
+fn dushanbe() -> GeoLocation {
+    GeoLocation {
+        country_code: "TJ".into(),
+        country: "Tajikistan".into(),
+        city: Some("Dushanbe".into()),
+    }
+}
+
+#[test]
+fn parses_successful_lookup() {
+    let location = parse_ip_api(&json!({
+        "status": "success",
+        "country": "Tajikistan",
+        "countryCode": "TJ",
+        "city": "Dushanbe"
+    }))
+    .expect("location");
+    assert_eq!(location, dushanbe());
+}
+
+#[test]
+fn failed_lookup_has_no_location() {
+    assert!(parse_ip_api(&json!({ "status": "fail", "message": "private range" })).is_none());
+    assert!(parse_ip_api(&json!({ "status": "success", "countryCode": "" })).is_none());
+    assert!(parse_ip_api(&json!("nonsense")).is_none());
+}
+
+#[test]
+fn flags_only_countries_other_than_expected() {
+    let location = dushanbe();
+    assert!(!is_suspicious_geo(Some("TJ"), &location));
+    assert!(!is_suspicious_geo(Some(" tj "), &location));
+    assert!(!is_suspicious_geo(Some("tajikistan"), &location));
+    assert!(is_suspicious_geo(Some("UZ"), &location));
+    assert!(!is_suspicious_geo(None, &location));
+    assert!(!is_suspicious_geo(Some(""), &location));
+}
+
+#[test]
+fn private_addresses_are_not_looked_up() {
+    let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+    assert!(is_public_ip(&ip("8.8.8.8")));
+    assert!(is_public_ip(&ip("2a00:1450:4001::1")));
+    assert!(!is_public_ip(&ip("10.1.2.3")));
+    assert!(!is_public_ip(&ip("192.168.0.10")));
+    assert!(!is_public_ip(&ip("127.0.0.1")));
+    assert!(!is_public_ip(&ip("::1")));
+    assert!(!is_public_ip(&ip("fd00::5")));
+    assert!(!is_public_ip(&ip("fe80::1")));
+}
+
+#[tokio::test]
+async fn lookup_queries_the_configured_api() {
+    let api = Router::new().route(
+        "/json/:ip",
+        get(|Path(ip): Path<String>| async move {
+            Json(json!({
+                "status": "success",
+                "country": "Uzbekistan",
+                "countryCode": "UZ",
+                "city": if ip == "8.8.8.8" { "Tashkent" } else { "" },
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, api).await.unwrap() });
+
+    let geo = GeoService::new(format!("http://{}/json", addr));
+    let location = geo
+        .lookup("8.8.8.8".parse().unwrap())
+        .await
+        .expect("lookup")
+        .expect("location");
+    assert_eq!(location.country_code, "UZ");
+    assert_eq!(location.city.as_deref(), Some("Tashkent"));
+    assert!(is_suspicious_geo(Some("TJ"), &location));
+
+    let private = geo
+        .lookup("192.168.1.1".parse().unwrap())
+        .await
+        .expect("lookup");
+    assert!(private.is_none());
+}
+
+#[tokio::test]
+async fn client_ip_prefers_forwarded_header() {
+    dotenvy::dotenv().ok();
+    std::env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    std::env::set_var("JWT_SECRET", "test_secret_key");
+    std::env::set_var("WEBHOOK_SECRET", "whsec_test");
+    std::env::set_var("OPENAI_API_KEY", "sk-test");
+    std::env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+
+    let app = Router::new().route(
+        "/",
+        get(
+            |ClientIp(ip): ClientIp| async move { ip.map(|ip| ip.to_string()).unwrap_or_default() },
+        ),
+    );
+    let call = |req: Request<Body>| {
+        let app = app.clone();
+        async move {
+            let resp = app.oneshot(req).await.unwrap();
+            let bytes = to_bytes(resp.into_body(), 1024).await.unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        }
+    };
+
+    let forwarded = Request::builder()
+        .uri("/")
+        .header("x-forwarded-for", "95.142.90.1, 10.0.0.2")
+        .header("x-real-ip", "10.0.0.2")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(call(forwarded).await, "95.142.90.1");
+
+    let real_ip = Request::builder()
+        .uri("/")
+        .header("x-real-ip", "95.142.90.7")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(call(real_ip).await, "95.142.90.7");
+
+    let garbage = Request::builder()
+        .uri("/")
+        .header("x-forwarded-for", "unknown")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(call(garbage).await, "");
+}
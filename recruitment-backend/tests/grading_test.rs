@@ -1 +1,229 @@
+use recruitment_backend::models::question::Question;
+use recruitment_backend::services::ai_service::AIService;
+use recruitment_backend::services::code_runner_service::CodeRunnerService;
+use recruitment_backend::services::grading_service::{
+    GradingService, PassingThresholds, SubScores,
+};
+use serde_json::json;
 
+fn code_question() -> Vec<Question> {
+    serde_json::from_value(json!([{
+        "id": 1,
+        "type": "code",
+        "question": "Double the number read from stdin",
+        "points": 4,
+        "language": "rust",
+        "starter_code": null,
+        "test_cases": [
+            { "input": "2", "expected": "4" },
+            { "input": "3", "expected": "6" }
+        ]
+    }]))
+    .unwrap()
+}
+
+#[test]
+fn code_questions_need_review_without_execution() {
+    let questions = code_question();
+    let answers = vec![json!({ "question_id": 1, "answer": "fn main() {}" })];
+    let (earned, max, graded, needs_review) = GradingService::grade_mcq_only(&questions, &answers);
+    assert_eq!((earned, max), (0, 4));
+    assert!(needs_review);
+    assert_eq!(graded[0]["type"], "code");
+}
+
+#[tokio::test]
+async fn code_answers_score_proportionally_to_passed_cases() {
+    let rustc = std::process::Command::new("rustc")
+        .arg("--version")
+        .output();
+    if !rustc.map(|o| o.status.success()).unwrap_or(false) {
+        return;
+    }
+    let questions = code_question();
+    let source = r#"
+        use std::io::Read;
+        fn main() {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s).unwrap();
+            let n: i64 = s.trim().parse().unwrap();
+            println!("{}", if n == 2 { 4 } else { 0 });
+        }
+    "#;
+    let answers = vec![json!({ "question_id": 1, "answer": { "code": source } })];
+    let (_, _, mut graded, _) = GradingService::grade_mcq_only(&questions, &answers);
+
+    let earned = GradingService::grade_code_answers(
+        &questions,
+        &answers,
+        &mut graded,
+        &CodeRunnerService::new(),
+    )
+    .await;
+
+    assert_eq!(earned, 2);
+    assert!(!GradingService::needs_review(&graded));
+    assert_eq!(graded[0]["tests_passed"], 1);
+    assert_eq!(graded[0]["test_results"][1]["stdout"], "0\n");
+}
+
+#[tokio::test]
+async fn failed_ai_calls_keep_partial_results() {
+    let questions: Vec<Question> = serde_json::from_value(json!([
+        {
+            "id": 1,
+            "type": "multiple_choice",
+            "question": "2 + 2?",
+            "points": 3,
+            "options": ["3", "4"],
+            "correct_answer": 1,
+            "explanation": null
+        },
+        {
+            "id": 2,
+            "type": "short_answer",
+            "question": "Explain ownership",
+            "points": 5,
+            "expected_keywords": ["borrow"],
+            "min_words": 10,
+            "ai_grading": true
+        },
+        {
+            "id": 3,
+            "type": "short_answer",
+            "question": "Explain lifetimes",
+            "points": 5,
+            "expected_keywords": null,
+            "min_words": 10,
+            "ai_grading": true
+        }
+    ]))
+    .unwrap();
+    let answers = vec![
+        json!({ "question_id": 1, "answer": 1 }),
+        json!({ "question_id": 2, "answer": "Each value has a single owner" }),
+        json!({ "question_id": 3, "answer": "   " }),
+    ];
+    // Nothing listens on the discard port, so every LLM call fails fast.
+    let ai = AIService::new(
+        "test-key".to_string(),
+        "http://127.0.0.1:9".to_string(),
+        reqwest::Client::new(),
+    );
+
+    let result = GradingService::grade_parallel(questions, answers, &ai)
+        .await
+        .expect("grading never fails as a whole");
+
+    assert_eq!((result.earned_points, result.total_max_points), (3, 13));
+    assert!(result.needs_review);
+    assert_eq!(result.graded[0]["is_correct"], true);
+    assert_eq!(result.graded[1]["needs_review"], true);
+    assert!(result.graded[1]["grading_error"].is_string());
+    assert_eq!(result.graded[2]["needs_review"], false);
+    assert_eq!(result.graded[2]["points_earned"], 0);
+}
+
+#[test]
+fn sub_scores_split_mcq_from_open_answers() {
+    let graded = vec![
+        json!({ "type": "multiple_choice", "points_earned": 1, "max_points": 1 }),
+        json!({ "type": "multiple_choice", "points_earned": 0, "max_points": 2 }),
+        json!({ "type": "short_answer", "points_earned": 3, "max_points": 4 }),
+        json!({ "type": "code", "points_earned": 0, "max_points": 4 }),
+    ];
+    assert_eq!(
+        GradingService::sub_scores(&graded),
+        SubScores {
+            mcq: Some(33.33),
+            open: Some(37.5)
+        }
+    );
+    assert_eq!(GradingService::sub_scores(&graded[..2]).open, None);
+}
+
+#[test]
+fn both_sub_thresholds_must_be_met() {
+    let thresholds = PassingThresholds {
+        total: 70.0,
+        mcq: Some(80.0),
+        open: Some(50.0),
+    };
+    let scores = |mcq, open| SubScores {
+        mcq: Some(mcq),
+        open: Some(open),
+    };
+    // The overall mark no longer applies once both kinds have their own.
+    assert!(thresholds.passes(60.0, scores(80.0, 50.0)));
+    assert!(!thresholds.passes(95.0, scores(100.0, 40.0)));
+    assert!(!thresholds.passes(60.0, scores(70.0, 90.0)));
+    // A test with no open questions is judged on its MCQs alone.
+    assert!(thresholds.passes(
+        85.0,
+        SubScores {
+            mcq: Some(85.0),
+            open: None
+        }
+    ));
+
+    let mcq_only = PassingThresholds {
+        mcq: Some(80.0),
+        open: None,
+        ..thresholds
+    };
+    assert!(!mcq_only.passes(65.0, scores(90.0, 40.0)));
+    assert!(mcq_only.passes(75.0, scores(90.0, 60.0)));
+
+    let total_only = PassingThresholds {
+        total: 70.0,
+        ..Default::default()
+    };
+    assert!(total_only.passes(70.0, scores(0.0, 100.0)));
+    assert!(!total_only.passes(69.9, SubScores::default()));
+}
+
+fn fill_in_blank_questions() -> Vec<Question> {
+    serde_json::from_value(json!([
+        {
+            "id": 1,
+            "type": "fill_in_blank",
+            "question": "Complete the legal term",
+            "points": 2,
+            "blanked_sentence": "A ___ is a binding ___.",
+            "correct_words": ["contract", "agreement"],
+            "case_sensitive": false
+        },
+        {
+            "id": 2,
+            "type": "fill_in_blank",
+            "question": "Latin abbreviation",
+            "points": 3,
+            "blanked_sentence": "Write ___ for 'that is'.",
+            "correct_words": ["i.e."],
+            "case_sensitive": true
+        }
+    ]))
+    .unwrap()
+}
+
+#[test]
+fn fill_in_blank_answers_need_every_word_to_match() {
+    let questions = fill_in_blank_questions();
+    let answers = vec![
+        json!({ "question_id": 1, "answer": [" Contract ", "AGREEMENT"] }),
+        json!({ "question_id": 2, "answer": "i.e." }),
+    ];
+    let (earned, max, graded, needs_review) = GradingService::grade_mcq_only(&questions, &answers);
+    assert_eq!((earned, max), (5, 5));
+    assert!(!needs_review);
+    assert_eq!(graded[0]["type"], "fill_in_blank");
+    assert_eq!(graded[0]["correct_answer"], "contract, agreement");
+
+    let answers = vec![
+        json!({ "question_id": 1, "answer": ["contract"] }),
+        json!({ "question_id": 2, "answer": "I.E." }),
+    ];
+    let (earned, _, graded, _) = GradingService::grade_mcq_only(&questions, &answers);
+    assert_eq!(earned, 0, "a missing blank and a case mismatch both fail");
+    assert_eq!(graded[1]["is_correct"], false);
+}
@@ -0,0 +1,176 @@
+use std::env;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use recruitment_backend::services::health_service::{
+    check_readiness, queue_status, CheckStatus, HealthReport, HealthService, OverallStatus,
+    Readiness, AI_QUEUE_BACKLOG_LIMIT,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+#[test]
+fn only_the_database_is_critical() {
+    use CheckStatus::{Error, Ok};
+    assert_eq!(HealthReport::overall(Ok, Ok, Ok, Ok), OverallStatus::Ok);
+    assert_eq!(
+        HealthReport::overall(Ok, Error, Ok, Error),
+        OverallStatus::Degraded
+    );
+    assert_eq!(
+        HealthReport::overall(Error, Ok, Ok, Ok),
+        OverallStatus::Error
+    );
+    assert_eq!(
+        serde_json::to_value(OverallStatus::Degraded).unwrap(),
+        "degraded"
+    );
+}
+
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn queue_backlog_degrades_readiness() {
+    assert_eq!(queue_status(0, AI_QUEUE_BACKLOG_LIMIT), CheckStatus::Ok);
+    assert_eq!(
+        queue_status(AI_QUEUE_BACKLOG_LIMIT, AI_QUEUE_BACKLOG_LIMIT),
+        CheckStatus::Ok
+    );
+    assert_eq!(
+        queue_status(AI_QUEUE_BACKLOG_LIMIT + 1, AI_QUEUE_BACKLOG_LIMIT),
+        CheckStatus::Degraded
+    );
+    assert_eq!(serde_json::to_value(Readiness::Down).unwrap(), "down");
+}
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+#[tokio::test]
+async fn reports_each_dependency_within_the_timeout() {
+    let pool = setup_pool().await;
+
+    let provider = serve(
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route(
+                "/bottest/getMe",
+                get(|| async { Json(json!({ "ok": true, "result": { "id": 1 } })) }),
+            )
+            .route(
+                "/slow/getMe",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    Json(json!({ "ok": true }))
+                }),
+            ),
+    )
+    .await;
+
+    let healthy = HealthService::new(
+        provider.clone(),
+        format!("{}/bottest/getMe", provider),
+        vec![provider.clone()],
+    );
+    let report = healthy.check(&pool).await;
+    assert_eq!(report.status, OverallStatus::Ok);
+    assert_eq!(report.database, CheckStatus::Ok);
+    assert_eq!(report.telegram, CheckStatus::Ok);
+    assert!(report.queue_depth >= 0);
+    assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+
+    let started = Instant::now();
+    let degraded = HealthService::new(
+        "http://127.0.0.1:9".into(),
+        format!("{}/slow/getMe", provider),
+        vec![],
+    )
+    .check(&pool)
+    .await;
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert_eq!(degraded.status, OverallStatus::Degraded);
+    assert_eq!(degraded.ai_provider, CheckStatus::Error);
+    assert_eq!(degraded.telegram, CheckStatus::Error);
+    assert_eq!(degraded.onef, CheckStatus::Ok);
+}
+
+#[tokio::test]
+async fn readiness_checks_database_uploads_and_queues() {
+    let pool = setup_pool().await;
+    let uploads = env::temp_dir();
+
+    let report = check_readiness(&pool, &uploads).await;
+    assert_eq!(report.checks.database.status, CheckStatus::Ok);
+    assert_eq!(report.checks.uploads.status, CheckStatus::Ok);
+    assert!(report.checks.ai_queue.depth.unwrap() >= 0);
+    assert!(report.checks.webhook_queue.depth.unwrap() >= 0);
+    assert_ne!(report.status, Readiness::Down);
+
+    let missing = uploads.join(format!("missing-{}", uuid::Uuid::new_v4()));
+    let report = check_readiness(&pool, &missing).await;
+    assert_eq!(report.status, Readiness::Degraded);
+    assert_eq!(report.checks.uploads.status, CheckStatus::Error);
+    assert!(report.checks.uploads.error.is_some());
+}
+
+#[tokio::test]
+async fn readiness_is_unavailable_once_the_pool_is_closed() {
+    let pool = setup_pool().await;
+    let app = Router::new()
+        .route("/health", get(recruitment_backend::routes::health::health))
+        .route(
+            "/health/ready",
+            get(recruitment_backend::routes::health::health_ready),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    pool.close().await;
+
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let resp = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = resp.status();
+            let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&bytes).unwrap())
+        }
+    };
+
+    let (status, body) = get_json("/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "down");
+    assert_eq!(body["checks"]["database"]["status"], "error");
+    assert!(body["checks"]["database"]["error"].is_string());
+    assert_eq!(body["checks"]["ai_queue"]["status"], "error");
+
+    let (status, _) = get_json("/health").await;
+    assert_eq!(status, StatusCode::OK);
+}
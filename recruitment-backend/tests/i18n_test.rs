@@ -0,0 +1,161 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::test_service::TestService;
+use recruitment_backend::utils::strings::{
+    render, resolve_lang, supported_lang, text, ui_strings, DEFAULT_LANG, UI_KEYS,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn language_tags_resolve_to_supported_languages() {
+    assert_eq!(supported_lang("tg"), Some("tg"));
+    assert_eq!(supported_lang(" TG-tj "), Some("tg"));
+    assert_eq!(supported_lang("ru_RU"), Some("ru"));
+    assert_eq!(supported_lang("en"), None);
+    assert_eq!(resolve_lang(Some("en")), DEFAULT_LANG);
+    assert_eq!(resolve_lang(None), DEFAULT_LANG);
+}
+
+#[test]
+fn lookups_fall_back_to_russian_then_the_key() {
+    assert_ne!(text("tg", "submit_success"), text("ru", "submit_success"));
+    assert_eq!(text("en", "submit_success"), text("ru", "submit_success"));
+    assert_eq!(text("tg", "no_such_key"), "no_such_key");
+
+    let tg = ui_strings("tg");
+    assert_eq!(tg.len(), UI_KEYS.len());
+    assert!(tg.values().all(|s| !s.is_empty()));
+    assert_eq!(ui_strings("de"), ui_strings("ru"));
+}
+
+#[test]
+fn render_fills_placeholders() {
+    let message = render("ru", "invite_test", &[("title", "Rust Basics")]);
+    assert!(message.contains("Rust Basics"));
+    assert!(!message.contains("{title}"));
+    let message = render(
+        "tg",
+        "test_graded",
+        &[("title", "SQL"), ("percentage", "80")],
+    );
+    assert!(message.contains("\"SQL\"") && message.contains("80%"));
+}
+
+async fn get_json(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn attempt_language_is_stored_and_served() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'I18n User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("i18n_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Localized",
+        "duration_minutes": 10,
+        "passing_score": 50.0,
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+
+    let invite = AttemptService::new(pool.clone())
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Farrukh".into(),
+                email: format!("tg_{}@example.com", Uuid::new_v4()),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            Some("tg"),
+            InviteSource::Integration,
+        )
+        .await
+        .expect("invite");
+
+    let app = Router::new()
+        .route(
+            "/api/public/tests/:token",
+            get(recruitment_backend::routes::public::get_test_by_token),
+        )
+        .route(
+            "/api/public/i18n/:lang",
+            get(recruitment_backend::routes::public::get_i18n_strings),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let (status, body) =
+        get_json(&app, &format!("/api/public/tests/{}", invite.access_token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["attempt"]["language"], "tg");
+
+    let (status, body) = get_json(&app, "/api/public/i18n/tg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["lang"], "tg");
+    assert_eq!(
+        body["strings"]["submit_success"],
+        text("tg", "submit_success")
+    );
+
+    let (_, body) = get_json(&app, "/api/public/i18n/en").await;
+    assert_eq!(body["lang"], "ru");
+    assert_eq!(body["strings"]["test_expired"], text("ru", "test_expired"));
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,207 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, Request, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use chrono::{Duration, TimeZone, Utc};
+use recruitment_backend::services::calendar_service::{
+    add_to_calendar_button, generate_ics, google_calendar_link, CalendarEvent,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+type Calls = Arc<Mutex<Vec<(String, Value)>>>;
+
+fn event(location: &str) -> CalendarEvent {
+    CalendarEvent {
+        uid: "interview-1@recruitment".into(),
+        title: "Собеседование".into(),
+        description: "Кандидат: Иван Петров\nИнтервьюер: Анна".into(),
+        location: location.into(),
+        start: Utc.with_ymd_and_hms(2026, 11, 3, 9, 30, 0).unwrap(),
+        duration_minutes: 45,
+        created_at: Utc.with_ymd_and_hms(2026, 10, 16, 8, 0, 0).unwrap(),
+    }
+}
+
+#[test]
+fn ics_has_one_event_with_escaped_text() {
+    let ics = generate_ics(event("Офис, каб. 3; 2 этаж"));
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    assert!(ics.contains("\r\nUID:interview-1@recruitment\r\n"));
+    assert!(ics.contains("\r\nDTSTAMP:20261016T080000Z\r\n"));
+    assert!(ics.contains("\r\nDTSTART:20261103T093000Z\r\n"));
+    assert!(ics.contains("\r\nDTEND:20261103T101500Z\r\n"));
+    assert!(ics.contains("\r\nLOCATION:Офис\\, каб. 3\\; 2 этаж\r\n"));
+    assert!(ics
+        .replace("\r\n ", "")
+        .contains("\r\nDESCRIPTION:Кандидат: Иван Петров\\nИнтервьюер: Анна\r\n"));
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+}
+
+#[test]
+fn long_lines_fold_on_character_boundaries() {
+    let location = "Душанбе, проспект Рудаки 137, бизнес-центр, конференц-зал на пятом этаже";
+    let ics = generate_ics(event(location));
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75, "{} octets: {}", line.len(), line);
+    }
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("LOCATION:{}", location.replace(',', "\\,"))));
+}
+
+#[test]
+fn google_link_prefills_the_event() {
+    let link = google_calendar_link(&event("Офис"));
+    let parsed = url::Url::parse(&link).unwrap();
+    assert_eq!(parsed.host_str(), Some("calendar.google.com"));
+    let query: std::collections::HashMap<_, _> = parsed.query_pairs().into_owned().collect();
+    assert_eq!(query["action"], "TEMPLATE");
+    assert_eq!(query["text"], "Собеседование");
+    assert_eq!(query["dates"], "20261103T093000Z/20261103T101500Z");
+    assert_eq!(query["location"], "Офис");
+
+    let markup = add_to_calendar_button(&link);
+    assert_eq!(markup["inline_keyboard"][0][0]["url"], link);
+}
+
+async fn bot_api(
+    State(calls): State<Calls>,
+    Path((_bot, method)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    calls.lock().unwrap().push((method, body));
+    Json(json!({ "ok": true, "result": { "message_id": 7 } }))
+}
+
+#[tokio::test]
+async fn schedules_interview_and_invites_candidate() {
+    let calls: Calls = Arc::default();
+    let bot = Router::new()
+        .route("/:bot/:method", post(bot_api))
+        .with_state(calls.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, bot).await.unwrap() });
+
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    env::set_var("TELEGRAM_API_URL", format!("http://{}", addr));
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let candidate_id = Uuid::new_v4();
+    let telegram_id = 700_000_000 + (candidate_id.as_u128() % 100_000_000) as i64;
+    sqlx::query(
+        "INSERT INTO candidates (id, telegram_id, name, email) VALUES ($1, $2, 'Interviewee', $3)",
+    )
+    .bind(candidate_id)
+    .bind(telegram_id)
+    .bind(format!("interview_{}@example.com", candidate_id))
+    .execute(&pool)
+    .await
+    .expect("seed candidate");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/candidates/:id/schedule-interview",
+            post(recruitment_backend::routes::interviews::schedule_interview),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let schedule = |body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/integration/candidates/{}/schedule-interview",
+                candidate_id
+            ))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let start = (Utc::now() + Duration::days(2))
+        .date_naive()
+        .and_hms_opt(10, 0, 0)
+        .unwrap()
+        .and_utc();
+    let resp = app
+        .clone()
+        .oneshot(schedule(json!({
+            "datetime": start,
+            "duration_minutes": 60,
+            "location": "Офис, 3 этаж",
+            "interviewer_name": "Анна",
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()[header::CONTENT_TYPE],
+        "text/calendar; charset=utf-8"
+    );
+    assert!(resp.headers()[header::CONTENT_DISPOSITION]
+        .to_str()
+        .unwrap()
+        .starts_with("attachment; filename=\"interview_"));
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let ics = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(ics.contains(&format!("DTSTART:{}", start.format("%Y%m%dT%H%M%SZ"))));
+
+    let mut sent = None;
+    for _ in 0..50 {
+        if let Some(call) = calls.lock().unwrap().first().cloned() {
+            sent = Some(call);
+            break;
+        }
+        tokio::time::sleep(StdDuration::from_millis(20)).await;
+    }
+    let (method, body) = sent.expect("telegram invitation");
+    assert_eq!(method, "sendMessage");
+    assert_eq!(body["chat_id"], telegram_id);
+    assert!(body["text"]
+        .as_str()
+        .unwrap()
+        .contains(&start.format("%d.%m.%Y %H:%M").to_string()));
+    assert!(body["reply_markup"]["inline_keyboard"][0][0]["url"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://calendar.google.com/calendar/render?"));
+
+    let past = app
+        .oneshot(schedule(json!({
+            "datetime": Utc::now() - Duration::hours(1),
+            "duration_minutes": 60,
+            "location": "Офис",
+            "interviewer_name": "Анна",
+        })))
+        .await
+        .unwrap();
+    assert_eq!(past.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
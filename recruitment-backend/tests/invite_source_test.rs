@@ -0,0 +1,245 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{parse_source_filter, InviteSource};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn source_filter_accepts_known_sources_only() {
+    for source in InviteSource::ALL {
+        assert_eq!(InviteSource::parse(source.as_str()), Some(source));
+    }
+    assert_eq!(
+        parse_source_filter(Some(" onef ")).unwrap(),
+        Some(InviteSource::Onef)
+    );
+    assert_eq!(parse_source_filter(Some("")).unwrap(), None);
+    assert_eq!(parse_source_filter(None).unwrap(), None);
+    let err = parse_source_filter(Some("1f")).unwrap_err();
+    assert_eq!(err.code(), "unknown_invite_source");
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 30,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+#[tokio::test]
+async fn invites_record_the_router_they_came_from() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Source User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("source_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(
+            payload("Invite source test"),
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create test");
+    let onef_candidate = Uuid::new_v4();
+    let onef_email = format!("source_onef_{}@example.com", onef_candidate);
+    sqlx::query("INSERT INTO candidates (id, name, email, status) VALUES ($1, $2, $3, 'new')")
+        .bind(onef_candidate)
+        .bind("OneF Invitee")
+        .bind(&onef_email)
+        .execute(&pool)
+        .await
+        .expect("seed candidate");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/test-invites",
+            post(recruitment_backend::routes::integration::create_test_invite),
+        )
+        .route(
+            "/api/integration/test-attempts",
+            get(recruitment_backend::routes::integration::list_test_attempts),
+        )
+        .route(
+            "/api/onef/invites",
+            post(recruitment_backend::routes::onef::create_test_invite),
+        )
+        .route(
+            "/api/onef/attempts_filter",
+            get(recruitment_backend::routes::onef::list_attempts_filter),
+        )
+        .route(
+            "/api/onef/dashboard",
+            get(recruitment_backend::routes::onef::get_dashboard_stats),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let integration_email = format!("source_integration_{}@example.com", Uuid::new_v4());
+    let (status, integration_invite) = send(
+        &app,
+        "POST",
+        "/api/integration/test-invites",
+        json!({
+            "test_id": test.id,
+            "candidate": { "name": "Integration Invitee", "email": integration_email },
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, onef_invite) = send(
+        &app,
+        "POST",
+        "/api/onef/invites",
+        json!({ "candidate_id": onef_candidate, "test_id": test.id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", onef_invite);
+
+    for (source, expected) in [("integration", &integration_invite), ("onef", &onef_invite)] {
+        let (status, page) = send(
+            &app,
+            "GET",
+            &format!(
+                "/api/integration/test-attempts?test_id={}&source={}",
+                test.id, source
+            ),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["items"][0]["id"], expected["attempt_id"]);
+        assert_eq!(page["items"][0]["source"], source);
+    }
+
+    let (_, page) = send(
+        &app,
+        "GET",
+        &format!("/api/onef/attempts_filter?email={}&source=onef", onef_email),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(page["total"], 1);
+    let (_, page) = send(
+        &app,
+        "GET",
+        &format!(
+            "/api/onef/attempts_filter?email={}&source=integration",
+            onef_email
+        ),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(page["total"], 0);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/api/integration/test-attempts?source=telegram",
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "unknown_invite_source");
+
+    let (status, stats) = send(&app, "GET", "/api/onef/dashboard", Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(stats["test_attempts_by_source"]["onef"].as_i64().unwrap() >= 1);
+    assert!(
+        stats["test_attempts_by_source"]["integration"]
+            .as_i64()
+            .unwrap()
+            >= 1
+    );
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(onef_candidate)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,74 @@
+use axum::{extract::Query, http::HeaderMap, routing::get, Json, Router};
+use recruitment_backend::services::koinotinav_service::{parse_vacancy_page, KoinotinavService};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn vacancy(id: i64) -> Value {
+    json!({
+        "id": id,
+        "title": format!("<b>Vacancy {}</b>", id),
+        "content": "",
+        "hot": 0,
+        "city": "Dushanbe",
+        "direction": "IT",
+        "company_id": null,
+        "created_at": "2026-01-05T09:00:00Z"
+    })
+}
+
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn plain_array_is_paged_locally() {
+    let body = Value::Array((130..150).map(vacancy).collect());
+    let (items, total) = parse_vacancy_page(body, None, 2, 5).unwrap();
+
+    assert_eq!(total, 13);
+    let ids: Vec<i64> = items.iter().map(|v| v.id).collect();
+    assert_eq!(ids, vec![142, 143, 144, 145, 146]);
+    assert_eq!(items[0].title, "Vacancy 142");
+}
+
+#[test]
+fn paginated_object_reports_upstream_total() {
+    let body = json!({ "data": [vacancy(140), vacancy(141)], "total": 57 });
+    let (items, total) = parse_vacancy_page(body, None, 3, 2).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(total, 57);
+
+    let body = json!({ "items": [vacancy(140)], "meta": { "total": 9 } });
+    assert_eq!(parse_vacancy_page(body, None, 1, 1).unwrap().1, 9);
+
+    let body = Value::Array(vec![vacancy(140)]);
+    let (items, total) = parse_vacancy_page(body, Some(31), 4, 1).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(total, 31);
+
+    assert!(parse_vacancy_page(json!("oops"), None, 1, 1).is_err());
+}
+
+#[tokio::test]
+async fn fetch_vacancies_page_sends_pagination_params() {
+    let app = Router::new().route(
+        "/api/vacancies",
+        get(|Query(q): Query<HashMap<String, String>>| async move {
+            let mut headers = HeaderMap::new();
+            headers.insert("X-Total-Count", "42".parse().unwrap());
+            let id = 200
+                + q["page"].parse::<i64>().unwrap() * 10
+                + q["per_page"].parse::<i64>().unwrap();
+            (headers, Json(json!([vacancy(id)])))
+        }),
+    );
+    let service = KoinotinavService::new().with_base_url(serve(app).await);
+
+    let (items, total) = service.fetch_vacancies_page(3, 1).await.unwrap();
+    assert_eq!(total, 42);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, 231);
+}
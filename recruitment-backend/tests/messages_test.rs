@@ -0,0 +1,252 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::error::Error;
+use recruitment_backend::routes::telegram::{attachment_extension, TelegramUpdate};
+use recruitment_backend::services::message_service::{
+    MessageQuery, MessageService, DEFAULT_MESSAGE_PAGE_SIZE, MAX_MESSAGE_PAGE_SIZE,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+fn query(limit: Option<i64>, before_id: Option<Uuid>, search: Option<&str>) -> MessageQuery {
+    MessageQuery {
+        limit,
+        before_id,
+        search: search.map(str::to_string),
+    }
+}
+
+#[test]
+fn page_size_is_clamped() {
+    assert_eq!(
+        query(None, None, None).page_size(),
+        DEFAULT_MESSAGE_PAGE_SIZE
+    );
+    assert_eq!(query(Some(0), None, None).page_size(), 1);
+    assert_eq!(query(Some(-5), None, None).page_size(), 1);
+    assert_eq!(
+        query(Some(10_000), None, None).page_size(),
+        MAX_MESSAGE_PAGE_SIZE
+    );
+}
+
+#[test]
+fn search_pattern_escapes_wildcards() {
+    assert_eq!(query(None, None, None).search_pattern(), None);
+    assert_eq!(query(None, None, Some("   ")).search_pattern(), None);
+    assert_eq!(
+        query(None, None, Some(" резюме "))
+            .search_pattern()
+            .as_deref(),
+        Some("%резюме%")
+    );
+    assert_eq!(
+        query(None, None, Some("100%_a\\b"))
+            .search_pattern()
+            .as_deref(),
+        Some("%100\\%\\_a\\\\b%")
+    );
+}
+
+#[test]
+fn inbound_document_update_exposes_attachment() {
+    let update: TelegramUpdate = serde_json::from_value(json!({
+        "update_id": 900001,
+        "message": {
+            "message_id": 42,
+            "from": { "id": 555, "is_bot": false, "first_name": "Aziz" },
+            "chat": { "id": 555, "type": "private" },
+            "date": 1760000000,
+            "caption": "Моё резюме",
+            "document": {
+                "file_name": "Resume.PDF",
+                "mime_type": "application/pdf",
+                "file_id": "BQACAgIAAxkBAAIBQ2Z",
+                "file_unique_id": "AgADQ2Z",
+                "file_size": 48213
+            }
+        }
+    }))
+    .expect("document update");
+
+    let message = update.message.expect("message");
+    assert!(message.text.is_none());
+    assert_eq!(message.caption.as_deref(), Some("Моё резюме"));
+
+    let attachment = message.attachment().expect("attachment");
+    assert_eq!(attachment.kind, "document");
+    assert_eq!(attachment.file_id, "BQACAgIAAxkBAAIBQ2Z");
+    assert_eq!(
+        attachment_extension(attachment.file_name.as_deref(), "documents/file_7.pdf"),
+        "pdf"
+    );
+}
+
+#[test]
+fn photo_update_uses_the_largest_size() {
+    let update: TelegramUpdate = serde_json::from_value(json!({
+        "update_id": 900002,
+        "message": {
+            "message_id": 43,
+            "from": { "id": 555, "is_bot": false, "first_name": "Aziz" },
+            "chat": { "id": 555, "type": "private" },
+            "photo": [
+                { "file_id": "small", "file_unique_id": "s", "width": 90, "height": 60 },
+                { "file_id": "large", "file_unique_id": "l", "width": 1280, "height": 853 },
+                { "file_id": "medium", "file_unique_id": "m", "width": 320, "height": 213 }
+            ]
+        }
+    }))
+    .expect("photo update");
+
+    let attachment = update.message.unwrap().attachment().expect("attachment");
+    assert_eq!(attachment.kind, "photo");
+    assert_eq!(attachment.file_id, "large");
+    assert_eq!(attachment_extension(None, "photos/file_3.jpg"), "jpg");
+}
+
+#[test]
+fn plain_text_update_has_no_attachment() {
+    let update: TelegramUpdate = serde_json::from_value(json!({
+        "update_id": 900003,
+        "message": {
+            "message_id": 44,
+            "from": { "id": 555, "is_bot": false, "first_name": "Aziz" },
+            "chat": { "id": 555, "type": "private" },
+            "text": "/start"
+        }
+    }))
+    .expect("text update");
+    assert!(update.message.unwrap().attachment().is_none());
+}
+
+#[test]
+fn unknown_extensions_are_stored_as_bin() {
+    assert_eq!(
+        attachment_extension(Some("payload.html"), "documents/file_1.html"),
+        "bin"
+    );
+    assert_eq!(
+        attachment_extension(Some("noext"), "documents/file_1"),
+        "bin"
+    );
+    assert_eq!(
+        attachment_extension(Some("archive.exe"), "documents/file_1.zip"),
+        "zip"
+    );
+}
+
+#[tokio::test]
+async fn history_pages_backwards_from_before_id() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let candidate_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO candidates (id, name, email, status) VALUES ($1, 'Paging Candidate', $2, 'new')")
+        .bind(candidate_id)
+        .bind(format!("paging_{}@example.com", candidate_id))
+        .execute(&pool)
+        .await
+        .expect("seed candidate");
+
+    let base = Utc::now() - Duration::hours(1);
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let id = Uuid::new_v4();
+        let (text, attachment) = match i {
+            2 => ("see attached CV", Some("uploads/chat/cv.pdf")),
+            _ => ("hello", None),
+        };
+        sqlx::query(
+            r#"INSERT INTO messages (id, candidate_id, telegram_id, direction, text, created_at, attachment_path, attachment_type)
+               VALUES ($1, $2, 777, 'inbound', $3, $4, $5, $6)"#,
+        )
+        .bind(id)
+        .bind(candidate_id)
+        .bind(format!("{} {}", text, i))
+        .bind(base + Duration::minutes(i))
+        .bind(attachment)
+        .bind(attachment.map(|_| "document"))
+        .execute(&pool)
+        .await
+        .expect("seed message");
+        ids.push(id);
+    }
+
+    let service = MessageService::new(pool.clone());
+    let page_ids = |page: &[recruitment_backend::models::message::Message]| {
+        page.iter().map(|m| m.id).collect::<Vec<_>>()
+    };
+
+    let all = service
+        .get_by_candidate(candidate_id, &MessageQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(page_ids(&all), ids);
+
+    let latest = service
+        .get_by_candidate(candidate_id, &query(Some(2), None, None))
+        .await
+        .unwrap();
+    assert_eq!(page_ids(&latest), ids[3..].to_vec());
+
+    let older = service
+        .get_by_candidate(candidate_id, &query(Some(2), Some(ids[3]), None))
+        .await
+        .unwrap();
+    assert_eq!(page_ids(&older), ids[1..3].to_vec());
+
+    let last = service
+        .get_by_candidate(candidate_id, &query(Some(2), Some(ids[1]), None))
+        .await
+        .unwrap();
+    assert_eq!(page_ids(&last), vec![ids[0]]);
+
+    let beyond = service
+        .get_by_candidate(candidate_id, &query(Some(2), Some(ids[0]), None))
+        .await
+        .unwrap();
+    assert!(beyond.is_empty());
+
+    let found = service
+        .get_by_candidate(candidate_id, &query(None, None, Some("ATTACHED")))
+        .await
+        .unwrap();
+    assert_eq!(page_ids(&found), vec![ids[2]]);
+    assert_eq!(
+        found[0].attachment_path.as_deref(),
+        Some("uploads/chat/cv.pdf")
+    );
+    assert_eq!(found[0].attachment_type.as_deref(), Some("document"));
+
+    let searched_before = service
+        .get_by_candidate(candidate_id, &query(None, Some(ids[2]), Some("attached")))
+        .await
+        .unwrap();
+    assert!(searched_before.is_empty());
+
+    let missing = service
+        .get_by_candidate(candidate_id, &query(None, Some(Uuid::new_v4()), None))
+        .await;
+    assert!(matches!(missing, Err(Error::NotFound(_))));
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,126 @@
+use std::env;
+use std::net::SocketAddr;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use recruitment_backend::middleware::metrics::track_http_metrics;
+use recruitment_backend::routes;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    env::set_var("METRICS_ALLOWED_IPS", "127.0.0.1,10.1.0.0/16");
+    let _ = recruitment_backend::config::init_config();
+    recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool")
+}
+
+async fn app(probe_route: &str) -> Router {
+    let state = recruitment_backend::AppState::new(setup_pool().await);
+    Router::new()
+        .route("/metrics", get(routes::metrics::metrics))
+        .route(probe_route, get(|| async { "ok" }))
+        .layer(middleware::from_fn(track_http_metrics))
+        .with_state(state)
+}
+
+fn request(uri: &str, peer: Option<&str>) -> Request<Body> {
+    let mut req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    if let Some(peer) = peer {
+        let addr: SocketAddr = peer.parse().unwrap();
+        req.extensions_mut().insert(ConnectInfo(addr));
+    }
+    req
+}
+
+async fn scrape(app: &Router) -> String {
+    let res = app
+        .clone()
+        .oneshot(request("/metrics", Some("127.0.0.1:40000")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn metrics_are_only_served_to_allowed_networks() {
+    let app = app("/probe").await;
+
+    for peer in ["127.0.0.1:40000", "10.1.200.7:40000"] {
+        let res = app
+            .clone()
+            .oneshot(request("/metrics", Some(peer)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{peer} is allowed");
+    }
+    for peer in ["10.2.0.1:40000", "203.0.113.5:40000", "[::1]:40000"] {
+        let res = app
+            .clone()
+            .oneshot(request("/metrics", Some(peer)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN, "{peer} is not allowed");
+    }
+
+    // A forwarded header does not stand in for the peer address.
+    let mut spoofed = request("/metrics", Some("203.0.113.5:40000"));
+    spoofed
+        .headers_mut()
+        .insert("x-forwarded-for", "127.0.0.1".parse().unwrap());
+    let res = app.clone().oneshot(spoofed).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"]["code"], "metrics_forbidden");
+
+    let res = app
+        .clone()
+        .oneshot(request("/metrics", None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn request_counters_show_up_in_the_scrape() {
+    let route = format!("/probe-{}", Uuid::new_v4().simple());
+    let app = app(&route).await;
+
+    for _ in 0..3 {
+        let res = app.clone().oneshot(request(&route, None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let body = scrape(&app).await;
+    let counter = body
+        .lines()
+        .find(|line| {
+            line.starts_with("http_requests_total{")
+                && line.contains(&format!("route=\"{route}\""))
+                && line.contains("status=\"200\"")
+                && line.contains("method=\"GET\"")
+        })
+        .unwrap_or_else(|| panic!("no counter for {route} in:\n{body}"));
+    assert_eq!(counter.rsplit(' ').next(), Some("3"));
+    assert!(body.contains("# TYPE http_requests_total counter"));
+    assert!(body.contains(&format!(
+        "http_request_duration_seconds_count{{method=\"GET\",route=\"{route}\"}} 3"
+    )));
+}
@@ -0,0 +1,260 @@
+use std::env;
+use std::str::FromStr;
+
+use clap::Parser;
+use recruitment_backend::cli::{exit_code, Cli, Command, DEFAULT_STALE_JOB_MINUTES};
+use recruitment_backend::database::migrations::{
+    migration_problems, repair_migrations, MigrationProblem, MIGRATOR,
+};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::queue_service::AiQueueService;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use uuid::Uuid;
+
+#[test]
+fn no_subcommand_means_serve() {
+    let cli = Cli::try_parse_from(["recruitment-backend"]).unwrap();
+    assert_eq!(cli.command(), Command::Serve);
+    let cli = Cli::try_parse_from(["recruitment-backend", "migrate"]).unwrap();
+    assert_eq!(cli.command(), Command::Migrate);
+}
+
+#[test]
+fn maintenance_commands_take_their_arguments() {
+    let cli = Cli::try_parse_from([
+        "recruitment-backend",
+        "repair-migrations",
+        "--from",
+        "20260120150000",
+        "--to",
+        "20260120160000",
+    ])
+    .unwrap();
+    assert_eq!(
+        cli.command(),
+        Command::RepairMigrations {
+            from: 20260120150000,
+            to: 20260120160000
+        }
+    );
+
+    let err = Cli::try_parse_from(["recruitment-backend", "repair-migrations", "--from", "1"])
+        .unwrap_err();
+    assert_eq!(err.exit_code(), exit_code::USAGE);
+    let err = Cli::try_parse_from(["recruitment-backend", "drop-everything"]).unwrap_err();
+    assert_eq!(err.exit_code(), exit_code::USAGE);
+
+    let cli = Cli::try_parse_from(["recruitment-backend", "requeue-stale-jobs"]).unwrap();
+    assert_eq!(
+        cli.command(),
+        Command::RequeueStaleJobs {
+            older_than_minutes: DEFAULT_STALE_JOB_MINUTES
+        }
+    );
+    let cli = Cli::try_parse_from([
+        "recruitment-backend",
+        "requeue-stale-jobs",
+        "--older-than-minutes",
+        "5",
+    ])
+    .unwrap();
+    assert_eq!(
+        cli.command(),
+        Command::RequeueStaleJobs {
+            older_than_minutes: 5
+        }
+    );
+}
+
+#[tokio::test]
+async fn repair_clears_the_reported_history_rows_in_a_temp_schema() {
+    dotenvy::dotenv().ok();
+    let url = env::var("DATABASE_URL").expect("DATABASE_URL");
+    let admin = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&url)
+        .await
+        .expect("pool");
+    let schema = format!("migration_repair_{}", Uuid::new_v4().simple());
+    sqlx::query(&format!("CREATE SCHEMA {}", schema))
+        .execute(&admin)
+        .await
+        .unwrap();
+
+    let options = PgConnectOptions::from_str(&url)
+        .unwrap()
+        .options([("search_path", schema.as_str())]);
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("schema pool");
+
+    assert!(
+        migration_problems(&pool, &MIGRATOR)
+            .await
+            .unwrap()
+            .is_empty(),
+        "a database without history has nothing to repair"
+    );
+
+    sqlx::query(
+        r#"CREATE TABLE _sqlx_migrations (
+               version BIGINT PRIMARY KEY,
+               description TEXT NOT NULL,
+               installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
+               success BOOLEAN NOT NULL,
+               checksum BYTEA NOT NULL,
+               execution_time BIGINT NOT NULL
+           )"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let shipped: Vec<_> = MIGRATOR.iter().take(3).collect();
+    let record = |version: i64, description: &str, success: bool, checksum: Vec<u8>| {
+        let pool = pool.clone();
+        let description = description.to_string();
+        async move {
+            sqlx::query(
+                r#"INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+                   VALUES ($1, $2, $3, $4, 0)"#,
+            )
+            .bind(version)
+            .bind(description)
+            .bind(success)
+            .bind(checksum)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+    };
+    record(
+        shipped[0].version,
+        &shipped[0].description,
+        true,
+        shipped[0].checksum.to_vec(),
+    )
+    .await;
+    record(
+        shipped[1].version,
+        &shipped[1].description,
+        true,
+        vec![0; 48],
+    )
+    .await;
+    record(
+        shipped[2].version,
+        &shipped[2].description,
+        false,
+        shipped[2].checksum.to_vec(),
+    )
+    .await;
+    record(20260120150500, "removed grading fields", true, vec![1; 48]).await;
+
+    let problems = migration_problems(&pool, &MIGRATOR).await.unwrap();
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+    assert!(matches!(
+        &problems[0],
+        MigrationProblem::ChecksumMismatch { version, .. } if *version == shipped[1].version
+    ));
+    assert!(matches!(
+        &problems[1],
+        MigrationProblem::Failed { version, .. } if *version == shipped[2].version
+    ));
+    assert_eq!(
+        problems[2],
+        MigrationProblem::Unknown {
+            version: 20260120150500,
+            description: "removed grading fields".into()
+        }
+    );
+    assert!(problems[2].to_string().contains("not part of this build"));
+
+    let err = repair_migrations(&pool, 20260120160000, 20260120150000)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "invalid_migration_range");
+
+    let removed = repair_migrations(&pool, 20260120150000, 20260120160000)
+        .await
+        .unwrap();
+    assert_eq!(removed, 1);
+    let problems = migration_problems(&pool, &MIGRATOR).await.unwrap();
+    assert_eq!(problems.len(), 2);
+    assert!(problems
+        .iter()
+        .all(|p| !matches!(p, MigrationProblem::Unknown { .. })));
+
+    pool.close().await;
+    sqlx::query(&format!("DROP SCHEMA {} CASCADE", schema))
+        .execute(&admin)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stale_running_jobs_go_back_to_pending() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    MIGRATOR.run(&pool).await.expect("migrations");
+
+    let queue = AiQueueService::new(pool.clone());
+    let mut jobs = Vec::new();
+    for started_minutes_ago in [120, 5] {
+        let id = queue
+            .enqueue(
+                serde_json::json!({ "profession": "Stale" }),
+                false,
+                None,
+                None,
+                None,
+                None,
+                DEFAULT_ORGANIZATION_ID,
+            )
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE ai_jobs SET status = 'running', started_at = NOW() - make_interval(mins => $2) WHERE id = $1",
+        )
+        .bind(id)
+        .bind(started_minutes_ago)
+        .execute(&pool)
+        .await
+        .unwrap();
+        jobs.push(id);
+    }
+
+    assert!(queue.requeue_stale(30).await.unwrap() >= 1);
+    let status = |id: Uuid| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, String>("SELECT status FROM ai_jobs WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        }
+    };
+    assert_eq!(status(jobs[0]).await, "pending");
+    assert_eq!(
+        status(jobs[1]).await,
+        "running",
+        "a recent job is left alone"
+    );
+
+    sqlx::query("DELETE FROM ai_jobs WHERE id = ANY($1)")
+        .bind(&jobs)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,76 @@
+use std::env;
+
+use recruitment_backend::services::notification_service::{
+    notification_dedup_key, NotificationService,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn dedup_key_is_sha256_of_event_and_key() {
+    let attempt = "3f0c4a1e-9a2b-4c1d-8e7f-0a1b2c3d4e5f";
+    let key = notification_dedup_key("test_completed", attempt);
+    assert_eq!(key.len(), 64);
+    assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(key, notification_dedup_key("test_completed", attempt));
+    assert_ne!(key, notification_dedup_key("test_assigned", attempt));
+}
+
+#[tokio::test]
+async fn repeated_notification_is_queued_once() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    let attempt_id = Uuid::new_v4().to_string();
+    let payload = json!({ "attempt_id": attempt_id });
+
+    let first = notif
+        .enqueue_webhook("test_completed", &payload, Some(&attempt_id))
+        .await
+        .unwrap();
+    assert!(first.iter().any(|l| l.subscription_id.is_none()));
+    let repeat = notif
+        .enqueue_webhook("test_completed", &payload, Some(&attempt_id))
+        .await
+        .unwrap();
+    assert!(repeat.is_empty());
+
+    let other_event = notif
+        .enqueue_webhook("test_assigned", &payload, Some(&attempt_id))
+        .await
+        .unwrap();
+    assert!(!other_event.is_empty());
+    let unkeyed = notif
+        .enqueue_webhook("test_completed", &payload, None)
+        .await
+        .unwrap();
+    assert!(!unkeyed.is_empty());
+
+    let queued: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM webhook_logs WHERE subscription_id IS NULL AND payload->>'attempt_id' = $1",
+    )
+    .bind(&attempt_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(queued, 3);
+
+    sqlx::query("DELETE FROM webhook_logs WHERE payload->>'attempt_id' = $1")
+        .bind(&attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
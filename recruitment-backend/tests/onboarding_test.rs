@@ -0,0 +1,187 @@
+use std::env;
+
+use axum::{routing::post, Json, Router};
+use recruitment_backend::models::candidate::CandidateOnboarding;
+use recruitment_backend::routes::candidate_routes::onboarding_message;
+use recruitment_backend::services::ai_service::AIService;
+use recruitment_backend::services::candidate_service::{
+    normalize_completed_items, CandidateService,
+};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+#[test]
+fn completed_items_must_exist_and_are_deduplicated() {
+    assert_eq!(
+        normalize_completed_items(&[3, 0, 3, 1], 4).unwrap(),
+        vec![0, 1, 3]
+    );
+    assert!(normalize_completed_items(&[], 0).unwrap().is_empty());
+    let err = normalize_completed_items(&[1, 4], 4).unwrap_err();
+    assert_eq!(err.code(), "invalid_checklist_item");
+}
+
+#[test]
+fn checklist_view_marks_done_items() {
+    let onboarding = CandidateOnboarding {
+        candidate_id: Uuid::new_v4(),
+        onboarding_checklist: Some(json!([
+            "Получить доступы",
+            "Встреча с командой",
+            "Изучить CRM"
+        ])),
+        checklist_completed_items: Some(json!([2])),
+    };
+    let checklist = onboarding.checklist().unwrap();
+    assert_eq!(checklist.total, 3);
+    assert_eq!(checklist.completed, 1);
+    assert_eq!(
+        checklist.items.iter().map(|i| i.done).collect::<Vec<_>>(),
+        vec![false, false, true]
+    );
+    assert_eq!(checklist.items[1].text, "Встреча с командой");
+
+    let pending = CandidateOnboarding {
+        onboarding_checklist: None,
+        checklist_completed_items: None,
+        ..onboarding
+    };
+    assert!(pending.checklist().is_none());
+}
+
+#[test]
+fn telegram_message_is_a_numbered_list() {
+    let text = onboarding_message(
+        "Алия",
+        "Бухгалтер",
+        &[
+            "Получить доступы".to_string(),
+            "Встреча с командой".to_string(),
+        ],
+    );
+    assert!(text.starts_with("Алия"));
+    assert!(text.contains("«Бухгалтер»"));
+    assert!(text.ends_with("1. Получить доступы\n2. Встреча с командой"));
+}
+
+async fn mock_openai(content: JsonValue) -> String {
+    let api = Router::new().route(
+        "/chat/completions",
+        post(move |Json(body): Json<JsonValue>| {
+            let content = content.clone();
+            async move {
+                let user = body["messages"][1]["content"].as_str().unwrap().to_string();
+                assert!(user.contains("Бухгалтер"));
+                assert!(user.contains("Koinoti Nav"));
+                Json(json!({
+                    "model": "gpt-4o",
+                    "choices": [{ "message": { "content": content.to_string() } }]
+                }))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, api).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn generates_checklist_from_the_model() {
+    let ai = AIService::new(
+        "sk-test".into(),
+        mock_openai(json!({ "items": ["1. Получить доступы", "Встреча с командой"] })).await,
+        reqwest::Client::new(),
+    );
+    let items = ai
+        .generate_onboarding_checklist("Бухгалтер", "Koinoti Nav")
+        .await
+        .unwrap();
+    assert_eq!(items, vec!["Получить доступы", "Встреча с командой"]);
+
+    let empty = AIService::new(
+        "sk-test".into(),
+        mock_openai(json!({ "items": [] })).await,
+        reqwest::Client::new(),
+    );
+    assert!(empty
+        .generate_onboarding_checklist("Бухгалтер", "Koinoti Nav")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn candidate_marks_checklist_items_done() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO candidates (id, name, email, status) VALUES ($1, 'Onboarding Candidate', $2, 'accepted')")
+        .bind(id)
+        .bind(format!("onboarding_{}@example.com", id))
+        .execute(&pool)
+        .await
+        .expect("seed candidate");
+
+    let svc = CandidateService::new(pool.clone());
+    assert!(svc.get_onboarding(id).await.unwrap().checklist().is_none());
+    assert_eq!(
+        svc.mark_onboarding_items(id, &[0])
+            .await
+            .unwrap_err()
+            .code(),
+        "onboarding_not_ready"
+    );
+
+    let items = vec![
+        "Получить доступы".to_string(),
+        "Встреча с командой".to_string(),
+    ];
+    svc.set_onboarding_checklist(id, &items).await.unwrap();
+    let updated = svc.mark_onboarding_items(id, &[1, 1]).await.unwrap();
+    assert_eq!(updated.completed_items(), vec![1]);
+    assert_eq!(
+        svc.mark_onboarding_items(id, &[2])
+            .await
+            .unwrap_err()
+            .code(),
+        "invalid_checklist_item"
+    );
+
+    let stored = svc.get_onboarding(id).await.unwrap().checklist().unwrap();
+    assert_eq!(stored.items.len(), 2);
+    assert_eq!(stored.completed, 1);
+
+    // Regenerating starts the progress over.
+    svc.set_onboarding_checklist(id, &items).await.unwrap();
+    assert!(svc
+        .get_onboarding(id)
+        .await
+        .unwrap()
+        .completed_items()
+        .is_empty());
+
+    assert_eq!(
+        svc.get_onboarding(Uuid::new_v4()).await.unwrap_err().code(),
+        "candidate_not_found"
+    );
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
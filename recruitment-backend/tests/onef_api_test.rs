@@ -155,6 +155,7 @@ async fn onef_api_end_to_end() {
 
 
     // --- Test 4: Update Candidate Status ---
+    // new -> accepted skips the workflow, so it needs an admin override.
     let status_body = json!({ "status": "accepted" });
     let req = Request::builder()
         .method("POST")
@@ -163,6 +164,30 @@ async fn onef_api_end_to_end() {
         .body(Body::from(status_body.to_string()))
         .unwrap();
     let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let rejected: JsonValue = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        rejected["error"]["details"]["allowed_next_statuses"],
+        json!(["reviewing", "rejected"])
+    );
+
+    let admin_token = recruitment_backend::middleware::auth::mint_token(
+        &Uuid::new_v4().to_string(),
+        "admin",
+        recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID,
+        1,
+    )
+    .expect("token");
+    let status_body = json!({ "status": "accepted", "allow_override": true });
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/onef/candidates/{}/status", candidate_id))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(status_body.to_string()))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
     if resp.status() != StatusCode::OK {
          let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
          let body = String::from_utf8(bytes.to_vec()).unwrap();
@@ -0,0 +1,396 @@
+use std::env;
+
+use chrono::Utc;
+use recruitment_backend::dto::public_dto::SubmitTestRequest;
+use recruitment_backend::models::test_attempt::TestAttempt;
+use recruitment_backend::services::attempt_service::{onef_event_data, AttemptService};
+use recruitment_backend::services::notification_service::{
+    NotificationService, ONEF_TARGET, ONEF_TEST_STATUS_EVENT,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const ONEF_BASE_URL: &str = "http://onef.test/api";
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    env::set_var("ONEF_BASE_URLS", ONEF_BASE_URL);
+    env::set_var("WEBAPP_URL", "https://app.example.com");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+struct Seed {
+    pool: PgPool,
+    test_id: Uuid,
+    candidate_id: Uuid,
+    email: String,
+}
+
+impl Seed {
+    async fn new(pool: &PgPool, test_type: &str) -> Self {
+        let email = format!("onef_{}@example.com", Uuid::new_v4().simple());
+        let candidate_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO candidates (name, email, vacancy_id) VALUES ('Push Test', $1, 4242) RETURNING id",
+        )
+        .bind(&email)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let test_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO tests (title, questions, test_type, passing_score) VALUES ('1F push test', '[]', $1, 0) RETURNING id",
+        )
+        .bind(test_type)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        Self {
+            pool: pool.clone(),
+            test_id,
+            candidate_id,
+            email,
+        }
+    }
+
+    /// Inserts an attempt and returns its access token.
+    async fn attempt(&self, status: &str, expires_in_minutes: i64) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        sqlx::query(
+            r#"INSERT INTO test_attempts
+                   (test_id, candidate_name, candidate_email, access_token, expires_at,
+                    questions_snapshot, status, started_at)
+               VALUES ($1, 'Push Test', $2, $3, NOW() + make_interval(mins => $4::int), '[]', $5,
+                       CASE WHEN $5 = 'pending' THEN NULL ELSE NOW() END)"#,
+        )
+        .bind(self.test_id)
+        .bind(&self.email)
+        .bind(&token)
+        .bind(expires_in_minutes as i32)
+        .bind(status)
+        .execute(&self.pool)
+        .await
+        .unwrap();
+        token
+    }
+
+    async fn attempt_id(&self, token: &str) -> Uuid {
+        sqlx::query_scalar("SELECT id FROM test_attempts WHERE access_token = $1")
+            .bind(token)
+            .fetch_one(&self.pool)
+            .await
+            .unwrap()
+    }
+
+    /// Queued 1F pushes for an attempt, oldest first.
+    async fn pushes(&self, attempt_id: Uuid) -> Vec<serde_json::Value> {
+        sqlx::query_scalar(
+            r#"SELECT payload FROM webhook_logs
+               WHERE target = $1 AND event_type = $2
+                 AND payload->'requestBody'->'event_data'->>'attempt_id' = $3
+               ORDER BY created_at"#,
+        )
+        .bind(ONEF_TARGET)
+        .bind(ONEF_TEST_STATUS_EVENT)
+        .bind(attempt_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+    }
+
+    async fn statuses(&self, attempt_id: Uuid) -> Vec<String> {
+        self.pushes(attempt_id)
+            .await
+            .iter()
+            .map(|p| {
+                p["requestBody"]["test_status"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    async fn cleanup(self) {
+        sqlx::query(
+            r#"DELETE FROM webhook_logs
+               WHERE target = $1 AND payload->'requestBody'->>'candidate_id' = $2"#,
+        )
+        .bind(ONEF_TARGET)
+        .bind(self.candidate_id.to_string())
+        .execute(&self.pool)
+        .await
+        .unwrap();
+        sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+            .bind(self.test_id)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM tests WHERE id = $1")
+            .bind(self.test_id)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM candidates WHERE id = $1")
+            .bind(self.candidate_id)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+    }
+}
+
+fn attempt_fixture() -> TestAttempt {
+    serde_json::from_value(json!({
+        "id": Uuid::new_v4(),
+        "test_id": Uuid::new_v4(),
+        "candidate_name": "Fixture",
+        "candidate_email": "fixture@example.com",
+        "access_token": "token",
+        "expires_at": Utc::now(),
+        "questions_snapshot": [],
+        "status": "in_progress",
+        "honesty_declaration_accepted": false,
+        "answer_changes": 0,
+        "interruption_seconds": 0,
+        "extension_seconds": 0,
+        "suspicious_geo": false,
+        "reminders_sent": [],
+        "language": "ru",
+        "source": "onef",
+        "is_preview": false,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn event_data_reports_scores_only_when_finished() {
+    let mut attempt = attempt_fixture();
+    attempt.score = Some(Decimal::new(8, 0));
+    attempt.max_score = Some(Decimal::new(10, 0));
+    attempt.percentage = Some(Decimal::new(80, 0));
+    attempt.passed = Some(true);
+
+    let data = onef_event_data(&attempt, "https://app.example.com");
+    assert_eq!(data.attempt_id, Some(attempt.id));
+    assert!(data.user_score.is_none() && data.passed.is_none());
+
+    attempt.status = "completed".into();
+    attempt.completed_at = Some(Utc::now());
+    attempt.graded_answers = Some(json!([]));
+    let data = onef_event_data(&attempt, "https://app.example.com");
+    assert_eq!(data.user_score, Some(8.0));
+    assert_eq!(data.max_score, Some(10.0));
+    assert_eq!(data.percentage, Some(80.0));
+    assert_eq!(data.passed, Some(true));
+    assert_eq!(
+        data.result_url,
+        Some(format!(
+            "https://app.example.com/uploads/results/{}.txt",
+            attempt.id
+        ))
+    );
+}
+
+#[test]
+fn event_data_links_presentation_submission() {
+    let mut attempt = attempt_fixture();
+    attempt.presentation_submission_link = Some("https://slides.example.com/deck".into());
+    attempt.presentation_submission_file_path = Some("uploads/presentations/deck.pdf".into());
+
+    let data = onef_event_data(&attempt, "https://app.example.com");
+    assert_eq!(
+        data.presentation_file_url.as_deref(),
+        Some("https://app.example.com/uploads/presentations/deck.pdf")
+    );
+    assert_eq!(
+        data.result_url.as_deref(),
+        Some("Link: https://slides.example.com/deck | File: https://app.example.com/uploads/presentations/deck.pdf")
+    );
+}
+
+#[tokio::test]
+async fn enqueues_push_per_base_url() {
+    let pool = setup_pool().await;
+    let seed = Seed::new(&pool, "question_based").await;
+    let token = seed.attempt("in_progress", 60).await;
+    let attempt_id = seed.attempt_id(&token).await;
+    let svc = AttemptService::new(pool.clone());
+    let attempt = svc.get_attempt_by_id(attempt_id).await.unwrap();
+
+    let payload = svc.onef_status_payload(&attempt).await.unwrap().unwrap();
+    assert_eq!(payload.candidate_id, seed.candidate_id);
+    assert_eq!(payload.vacancy_id, Some(4242));
+
+    let urls = vec![
+        ONEF_BASE_URL.to_string(),
+        "http://onef-backup.test".to_string(),
+    ];
+    let logs = NotificationService::enqueue_onef_test_status(&pool, &urls, &payload)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 2);
+    assert!(logs.iter().all(|l| l.target == ONEF_TARGET));
+    assert_eq!(
+        logs[1].target_url,
+        "http://onef-backup.test/action/postTestStatus"
+    );
+    assert_eq!(
+        logs[0].payload["requestBody"]["candidate_id"],
+        json!(seed.candidate_id)
+    );
+
+    let unknown = TestAttempt {
+        candidate_email: "nobody@example.com".into(),
+        ..attempt
+    };
+    assert!(svc.onef_status_payload(&unknown).await.unwrap().is_none());
+
+    seed.cleanup().await;
+}
+
+#[tokio::test]
+async fn pushes_on_start_submit_and_violation() {
+    let pool = setup_pool().await;
+    let seed = Seed::new(&pool, "question_based").await;
+    let svc = AttemptService::new(pool.clone());
+
+    let token = seed.attempt("pending", 60).await;
+    let attempt_id = seed.attempt_id(&token).await;
+    svc.start_attempt_by_token(&token, false, None, None)
+        .await
+        .unwrap();
+    // Restarting an in-progress attempt is not a status change.
+    svc.start_attempt_by_token(&token, false, None, None)
+        .await
+        .unwrap();
+    assert_eq!(seed.statuses(attempt_id).await, vec!["in_progress"]);
+
+    svc.submit_attempt_by_token(
+        &token,
+        SubmitTestRequest {
+            answers: vec![],
+            status: None,
+        },
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let pushes = seed.pushes(attempt_id).await;
+    assert_eq!(pushes.len(), 2);
+    let body = &pushes[1]["requestBody"];
+    assert_eq!(body["test_status"], "completed");
+    assert_eq!(body["candidate_id"], json!(seed.candidate_id));
+    assert_eq!(body["event_data"]["passed"], json!(true));
+
+    let token = seed.attempt("in_progress", 60).await;
+    let attempt_id = seed.attempt_id(&token).await;
+    svc.report_violation(&token, "tab_switch").await.unwrap();
+    assert!(seed.statuses(attempt_id).await.is_empty());
+    let (_, terminated) = svc.report_violation(&token, "tab_switch").await.unwrap();
+    assert!(terminated);
+    assert_eq!(seed.statuses(attempt_id).await, vec!["escaped"]);
+
+    seed.cleanup().await;
+}
+
+#[tokio::test]
+async fn pushes_on_presentation_submit_and_grading() {
+    let pool = setup_pool().await;
+    let seed = Seed::new(&pool, "presentation").await;
+    let svc = AttemptService::new(pool.clone());
+
+    let token = seed.attempt("in_progress", 60).await;
+    let attempt_id = seed.attempt_id(&token).await;
+    svc.submit_presentation_by_token(&token, Some("https://slides.example.com/deck".into()), None)
+        .await
+        .unwrap();
+    svc.grade_presentation(attempt_id, 90.0, None, Uuid::new_v4())
+        .await
+        .unwrap();
+    let pushes = seed.pushes(attempt_id).await;
+    assert_eq!(
+        pushes
+            .iter()
+            .map(|p| p["requestBody"]["test_status"].as_str().unwrap())
+            .collect::<Vec<_>>(),
+        vec!["needs_review", "completed"]
+    );
+    assert_eq!(
+        pushes[0]["requestBody"]["event_data"]["presentation_link"],
+        "https://slides.example.com/deck"
+    );
+    assert_eq!(
+        pushes[1]["requestBody"]["event_data"]["user_score"],
+        json!(90.0)
+    );
+
+    seed.cleanup().await;
+}
+
+#[tokio::test]
+async fn pushes_when_manual_grading_finishes_review() {
+    let pool = setup_pool().await;
+    let seed = Seed::new(&pool, "question_based").await;
+    let svc = AttemptService::new(pool.clone());
+
+    let token = seed.attempt("needs_review", 60).await;
+    let attempt_id = seed.attempt_id(&token).await;
+    sqlx::query("UPDATE test_attempts SET completed_at = NOW(), graded_answers = $2 WHERE id = $1")
+        .bind(attempt_id)
+        .bind(json!([
+            { "question_id": 1, "max_points": 5, "points_earned": 0, "needs_review": true },
+            { "question_id": 2, "max_points": 5, "points_earned": 0, "needs_review": true }
+        ]))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    svc.grade_answer(attempt_id, 1, true, None).await.unwrap();
+    assert!(seed.statuses(attempt_id).await.is_empty());
+    svc.grade_answer(attempt_id, 2, false, None).await.unwrap();
+    assert_eq!(seed.statuses(attempt_id).await, vec!["completed"]);
+
+    seed.cleanup().await;
+}
+
+#[tokio::test]
+async fn pushes_for_timed_out_and_abandoned_attempts() {
+    let pool = setup_pool().await;
+    let seed = Seed::new(&pool, "question_based").await;
+    let svc = AttemptService::new(pool.clone());
+
+    let expired = seed.attempt("pending", -5).await;
+    let expired_id = seed.attempt_id(&expired).await;
+    let abandoned = seed.attempt("in_progress", 60).await;
+    let abandoned_id = seed.attempt_id(&abandoned).await;
+    sqlx::query(
+        "UPDATE test_attempts SET last_heartbeat_at = NOW() - INTERVAL '10 minutes' WHERE id = $1",
+    )
+    .bind(abandoned_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    svc.check_deadlines(&notif).await.unwrap();
+
+    assert_eq!(seed.statuses(expired_id).await, vec!["timeout"]);
+    assert_eq!(seed.statuses(abandoned_id).await, vec!["escaped"]);
+
+    seed.cleanup().await;
+}
@@ -0,0 +1,795 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{delete, get, patch, post},
+    Router,
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use recruitment_backend::AppState;
+use tower::ServiceExt;
+
+use recruitment_backend::dto::integration_dto::{CandidateStatusSyncQuery, CreateTestPayload};
+use recruitment_backend::middleware::auth::Claims;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::routes::webhook_subscriptions as hooks;
+use recruitment_backend::routes::{
+    candidate_routes, export, integration, onef, vacancy as vacancies,
+};
+use recruitment_backend::services::attempt_service::{
+    AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::dashboard_service::DashboardRange;
+use recruitment_backend::services::organization_service::{is_valid_slug, OrganizationService};
+use recruitment_backend::services::test_service::{TestFilter, TestService};
+use recruitment_backend::services::webhook_subscription_service::WebhookSubscriptionService;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[test]
+fn slugs_are_lowercase_words_joined_by_hyphens() {
+    for ok in ["acme", "acme-hr", "team-42", "a1"] {
+        assert!(is_valid_slug(ok), "{} rejected", ok);
+    }
+    for bad in [
+        "", "a", "Acme", "acme_hr", "-acme", "acme-", "ac--me", "acme hr",
+    ] {
+        assert!(!is_valid_slug(bad), "{:?} accepted", bad);
+    }
+    assert!(!is_valid_slug(&"a".repeat(64)));
+}
+
+#[test]
+fn tokens_without_org_act_for_the_default_organization() {
+    let legacy: Claims =
+        serde_json::from_value(json!({ "sub": "u1", "exp": 1, "role": "admin" })).unwrap();
+    assert_eq!(legacy.organization_id(), DEFAULT_ORGANIZATION_ID);
+    assert!(!legacy.is_super_admin());
+
+    let org = Uuid::new_v4();
+    let scoped: Claims = serde_json::from_value(
+        json!({ "sub": "u2", "exp": 1, "role": "super_admin", "org_id": org }),
+    )
+    .unwrap();
+    assert_eq!(scoped.organization_id(), org);
+    assert!(scoped.is_super_admin());
+}
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn payload(title: &str) -> CreateTestPayload {
+    serde_json::from_value(json!({
+        "title": title,
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [],
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn tests_and_attempts_stay_inside_their_organization() {
+    let pool = setup_pool().await;
+    let organizations = OrganizationService::new(pool.clone());
+    let suffix = Uuid::new_v4().simple().to_string();
+    let acme = organizations
+        .create("Acme", &format!("acme-{}", &suffix[..8]))
+        .await
+        .unwrap();
+    let globex = organizations
+        .create("Globex", &format!("globex-{}", &suffix[..8]))
+        .await
+        .unwrap();
+
+    let taken = organizations.create("Acme again", &acme.slug).await;
+    assert_eq!(taken.unwrap_err().code(), "organization_slug_taken");
+    let invalid = organizations.create("Bad", "Not A Slug").await;
+    assert_eq!(invalid.unwrap_err().code(), "invalid_organization_slug");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Org Test User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("org_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let title = format!("Org scoped {}", suffix);
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(payload(&title), creator, acme.id)
+        .await
+        .unwrap();
+    let search = || {
+        Some(TestFilter {
+            is_active: None,
+            created_by: None,
+            search: Some(title.clone()),
+        })
+    };
+    let own = tests
+        .list_tests(1, 10, search(), None, acme.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        own.tests.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![test.id]
+    );
+    let other = tests
+        .list_tests(1, 10, search(), None, globex.id)
+        .await
+        .unwrap();
+    assert_eq!(other.total, 0);
+
+    let attempts = AttemptService::new(pool.clone());
+    let invite = attempts
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Org Candidate".into(),
+                email: format!("org_candidate_{}@example.com", suffix),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .unwrap();
+    let attempt_org: Uuid =
+        sqlx::query_scalar("SELECT organization_id FROM test_attempts WHERE id = $1")
+            .bind(invite.attempt_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attempt_org, acme.id);
+    let (visible, _) = attempts
+        .list_attempts(Some(test.id), None, None, None, 1, 10, acme.id)
+        .await
+        .unwrap();
+    assert_eq!(visible.len(), 1);
+    let (hidden, total) = attempts
+        .list_attempts(Some(test.id), None, None, None, 1, 10, globex.id)
+        .await
+        .unwrap();
+    assert!(hidden.is_empty());
+    assert_eq!(total, 0);
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tests.delete_test(test.id, acme.id).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM organizations WHERE id = ANY($1)")
+        .bind(vec![acme.id, globex.id])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+fn bearer(org_id: Uuid) -> String {
+    let claims = Claims {
+        sub: Uuid::new_v4().to_string(),
+        exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp() as usize,
+        role: Some("admin".into()),
+        org_id: Some(org_id),
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(
+            recruitment_backend::config::get_config()
+                .jwt_secret
+                .as_bytes(),
+        ),
+    )
+    .unwrap();
+    format!("Bearer {}", token)
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: String,
+    org_id: Uuid,
+    body: Option<serde_json::Value>,
+) -> StatusCode {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", bearer(org_id))
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+        .unwrap();
+    app.clone().oneshot(req).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn single_records_of_another_organization_are_not_found() {
+    let pool = setup_pool().await;
+    let organizations = OrganizationService::new(pool.clone());
+    let suffix = Uuid::new_v4().simple().to_string();
+    let acme = organizations
+        .create("Acme", &format!("acme-one-{}", &suffix[..8]))
+        .await
+        .unwrap();
+    let globex = organizations
+        .create("Globex", &format!("globex-one-{}", &suffix[..8]))
+        .await
+        .unwrap();
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Org Test User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("org_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload(&format!("Org single {}", suffix)), creator, acme.id)
+        .await
+        .unwrap();
+    let attempt = AttemptService::new(pool.clone())
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Org Candidate".into(),
+                email: format!("org_single_{}@example.com", suffix),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .unwrap()
+        .attempt_id;
+    let candidate: Uuid = sqlx::query_scalar(
+        "INSERT INTO candidates (name, email, organization_id) VALUES ('Org Candidate', $1, $2) RETURNING id",
+    )
+    .bind(format!("org_single_{}@example.com", suffix))
+    .bind(acme.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/integration/tests/:id",
+            get(recruitment_backend::routes::integration::get_test_by_id)
+                .patch(recruitment_backend::routes::integration::update_test)
+                .delete(recruitment_backend::routes::integration::delete_test),
+        )
+        .route(
+            "/api/integration/test-attempts/:id",
+            get(recruitment_backend::routes::integration::get_test_attempt_by_id),
+        )
+        .route(
+            "/api/candidate/:id",
+            get(recruitment_backend::routes::candidate_routes::get_candidate),
+        )
+        .route(
+            "/api/integration/candidates/:id/status",
+            post(recruitment_backend::routes::candidate_routes::update_candidate_status),
+        )
+        .route(
+            "/api/onef/candidates/:id",
+            get(recruitment_backend::routes::onef::get_candidate),
+        )
+        .route(
+            "/api/onef/candidates/:id/status",
+            post(recruitment_backend::routes::onef::update_candidate_status),
+        )
+        .with_state(AppState::new(pool.clone()));
+
+    let test_uri = format!("/api/integration/tests/{}", test.id);
+    let rename = json!({ "title": "Renamed by Globex" });
+    let screening = json!({ "status": "screening" });
+    let reads = [
+        test_uri.clone(),
+        format!("/api/integration/test-attempts/{}", attempt),
+        format!("/api/candidate/{}", candidate),
+        format!("/api/onef/candidates/{}", candidate),
+    ];
+    for uri in &reads {
+        assert_eq!(
+            send(&app, "GET", uri.clone(), globex.id, None).await,
+            StatusCode::NOT_FOUND,
+            "{}",
+            uri
+        );
+        assert_eq!(
+            send(&app, "GET", uri.clone(), acme.id, None).await,
+            StatusCode::OK,
+            "{}",
+            uri
+        );
+    }
+    assert_eq!(
+        send(
+            &app,
+            "PATCH",
+            test_uri.clone(),
+            globex.id,
+            Some(rename.clone())
+        )
+        .await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        send(&app, "DELETE", test_uri.clone(), globex.id, None).await,
+        StatusCode::NOT_FOUND
+    );
+    for uri in [
+        format!("/api/integration/candidates/{}/status", candidate),
+        format!("/api/onef/candidates/{}/status", candidate),
+    ] {
+        assert_eq!(
+            send(
+                &app,
+                "POST",
+                uri.clone(),
+                globex.id,
+                Some(screening.clone())
+            )
+            .await,
+            StatusCode::NOT_FOUND,
+            "{}",
+            uri
+        );
+    }
+
+    let (title, status): (String, String) = sqlx::query_as(
+        "SELECT t.title, c.status FROM tests t, candidates c WHERE t.id = $1 AND c.id = $2",
+    )
+    .bind(test.id)
+    .bind(candidate)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_ne!(title, "Renamed by Globex");
+    assert_ne!(status, "screening");
+
+    assert_eq!(
+        send(&app, "PATCH", test_uri.clone(), acme.id, Some(rename)).await,
+        StatusCode::OK
+    );
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        send(&app, "DELETE", test_uri.clone(), acme.id, None).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        send(&app, "DELETE", test_uri, acme.id, None).await,
+        StatusCode::NOT_FOUND
+    );
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM organizations WHERE id = ANY($1)")
+        .bind(vec![acme.id, globex.id])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn other_organizations_cannot_act_on_records_they_do_not_own() {
+    let pool = setup_pool().await;
+    let organizations = OrganizationService::new(pool.clone());
+    let suffix = Uuid::new_v4().simple().to_string();
+    let acme = organizations
+        .create("Acme", &format!("acme-act-{}", &suffix[..8]))
+        .await
+        .unwrap();
+    let globex = organizations
+        .create("Globex", &format!("globex-act-{}", &suffix[..8]))
+        .await
+        .unwrap();
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Org Test User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("org_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload(&format!("Org act {}", suffix)), creator, acme.id)
+        .await
+        .unwrap();
+    let attempt = AttemptService::new(pool.clone())
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Org Candidate".into(),
+                email: format!("org_act_{}@example.com", suffix),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .unwrap()
+        .attempt_id;
+    let candidate: Uuid = sqlx::query_scalar(
+        "INSERT INTO candidates (name, email, status, organization_id) VALUES ('Org Candidate', $1, 'new', $2) RETURNING id",
+    )
+    .bind(format!("org_act_{}@example.com", suffix))
+    .bind(acme.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let vacancy: Uuid = sqlx::query_scalar(
+        "INSERT INTO vacancies (title, company, location, status, organization_id) VALUES ('Org vacancy', 'Acme', 'Dushanbe', 'published', $1) RETURNING id",
+    )
+    .bind(acme.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let subscriptions = WebhookSubscriptionService::new(pool.clone());
+    let acme_hook = subscriptions
+        .create(
+            acme.id,
+            "https://acme.example.com/hook",
+            &["*".into()],
+            None,
+        )
+        .await
+        .unwrap();
+    let globex_hook = subscriptions
+        .create(
+            globex.id,
+            "https://globex.example.com/hook",
+            &["*".into()],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/integration/test-invites",
+            post(integration::create_test_invite),
+        )
+        .route("/api/onef/invites", post(onef::create_test_invite))
+        .route(
+            "/api/integration/tests/:id/owners",
+            post(integration::add_test_owner),
+        )
+        .route(
+            "/api/integration/tests/:id/owners/:user_id",
+            delete(integration::remove_test_owner),
+        )
+        .route(
+            "/api/integration/tests/:id/regrade",
+            post(integration::regrade_test),
+        )
+        .route(
+            "/api/integration/tests/:id/generation-log",
+            get(integration::get_generation_log),
+        )
+        .route(
+            "/api/integration/tests/:id/preview",
+            post(integration::preview_test),
+        )
+        .route(
+            "/api/integration/tests/:id/duplicate",
+            post(integration::duplicate_test),
+        )
+        .route(
+            "/api/integration/test-attempts/:id",
+            delete(integration::delete_test_invite),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/grade",
+            post(integration::grade_presentation),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/grade-answer",
+            post(integration::grade_test_answer),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/claim",
+            post(integration::claim_attempt_for_review),
+        )
+        .route(
+            "/api/integration/test-attempts/:id/release",
+            post(integration::release_attempt_review),
+        )
+        .route(
+            "/api/integration/candidates/:id",
+            delete(candidate_routes::delete_candidate),
+        )
+        .route(
+            "/api/integration/candidates/bulk-status",
+            post(candidate_routes::bulk_update_candidate_status),
+        )
+        .route(
+            "/api/integration/candidates/:id/export",
+            get(export::export_candidate),
+        )
+        .route(
+            "/api/integration/vacancies/:id",
+            get(vacancies::get_vacancy)
+                .patch(vacancies::update_vacancy)
+                .delete(vacancies::delete_vacancy),
+        )
+        .route(
+            "/api/integration/vacancies/:id/analytics",
+            get(vacancies::get_vacancy_analytics),
+        )
+        .route(
+            "/api/integration/vacancies/:id/links",
+            patch(vacancies::update_vacancy_links),
+        )
+        .route(
+            "/api/integration/vacancies/:id/publish-telegram",
+            post(vacancies::publish_vacancy_telegram),
+        )
+        .route("/api/onef/vacancies/:id", get(onef::get_vacancy))
+        .route(
+            "/api/integration/webhook-subscriptions/:id",
+            get(hooks::get_webhook_subscription)
+                .patch(hooks::update_webhook_subscription)
+                .delete(hooks::delete_webhook_subscription),
+        )
+        .with_state(AppState::new(pool.clone()));
+
+    let test_uri = format!("/api/integration/tests/{}", test.id);
+    let attempt_uri = format!("/api/integration/test-attempts/{}", attempt);
+    let candidate_uri = format!("/api/integration/candidates/{}", candidate);
+    let vacancy_uri = format!("/api/integration/vacancies/{}", vacancy);
+    let hook_uri = format!("/api/integration/webhook-subscriptions/{}", acme_hook.id);
+    let requests = [
+        (
+            "POST",
+            "/api/integration/test-invites".to_string(),
+            Some(json!({
+                "test_id": test.id,
+                "candidate": { "name": "Intruder", "email": format!("intruder_{}@example.com", suffix) },
+            })),
+        ),
+        (
+            "POST",
+            "/api/onef/invites".to_string(),
+            Some(json!({ "candidate_id": candidate, "test_id": test.id })),
+        ),
+        (
+            "POST",
+            format!("{}/owners", test_uri),
+            Some(json!({ "user_id": creator })),
+        ),
+        ("DELETE", format!("{}/owners/{}", test_uri, creator), None),
+        (
+            "POST",
+            format!("{}/regrade", test_uri),
+            Some(json!({ "corrections": { "1": { "options": ["a", "b"], "correct_answer": 0 } } })),
+        ),
+        ("GET", format!("{}/generation-log", test_uri), None),
+        ("POST", format!("{}/preview", test_uri), None),
+        (
+            "POST",
+            format!("{}/duplicate", test_uri),
+            Some(json!({ "num_new_questions": 1 })),
+        ),
+        (
+            "POST",
+            format!("{}/grade", attempt_uri),
+            Some(json!({ "grade": 50.0 })),
+        ),
+        (
+            "POST",
+            format!("{}/grade-answer", attempt_uri),
+            Some(json!({ "question_id": 1, "is_correct": true })),
+        ),
+        ("POST", format!("{}/claim", attempt_uri), None),
+        ("POST", format!("{}/release", attempt_uri), None),
+        ("DELETE", attempt_uri.clone(), None),
+        ("GET", format!("{}/export", candidate_uri), None),
+        ("DELETE", candidate_uri.clone(), None),
+        ("DELETE", format!("{}?erase=true", candidate_uri), None),
+        ("GET", vacancy_uri.clone(), None),
+        (
+            "PATCH",
+            vacancy_uri.clone(),
+            Some(json!({ "title": "Renamed by Globex" })),
+        ),
+        ("GET", format!("{}/analytics", vacancy_uri), None),
+        (
+            "PATCH",
+            format!("{}/links", vacancy_uri),
+            Some(json!({ "onef_id": "globex-1" })),
+        ),
+        ("POST", format!("{}/publish-telegram", vacancy_uri), None),
+        ("GET", format!("/api/onef/vacancies/{}", vacancy), None),
+        ("DELETE", vacancy_uri.clone(), None),
+        ("GET", hook_uri.clone(), None),
+        ("PATCH", hook_uri.clone(), Some(json!({ "active": false }))),
+        ("DELETE", hook_uri.clone(), None),
+    ];
+    for (method, uri, body) in requests {
+        assert_eq!(
+            send(&app, method, uri.clone(), globex.id, body).await,
+            StatusCode::NOT_FOUND,
+            "{} {}",
+            method,
+            uri
+        );
+    }
+    assert_eq!(
+        send(
+            &app,
+            "POST",
+            "/api/integration/candidates/bulk-status".to_string(),
+            globex.id,
+            Some(json!({ "candidate_ids": [candidate], "status": "rejected" })),
+        )
+        .await,
+        StatusCode::OK
+    );
+
+    let (title, status, deleted_at): (String, String, Option<chrono::DateTime<chrono::Utc>>) =
+        sqlx::query_as(
+            "SELECT v.title, c.status, c.deleted_at FROM vacancies v, candidates c WHERE v.id = $1 AND c.id = $2",
+        )
+        .bind(vacancy)
+        .bind(candidate)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(title, "Org vacancy");
+    assert_eq!(status, "new");
+    assert!(deleted_at.is_none());
+    let attempts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attempts, 1, "no invite or preview was created for Globex");
+    assert!(
+        subscriptions
+            .get(acme_hook.id, acme.id)
+            .await
+            .unwrap()
+            .is_active
+    );
+
+    let state = AppState::new(pool.clone());
+    let globex_stats = state
+        .dashboard_service
+        .get_stats(globex.id, DashboardRange::default())
+        .await
+        .unwrap();
+    assert_eq!(globex_stats.total_candidates, 0);
+    assert_eq!(globex_stats.active_tests, 0);
+    let acme_stats = state
+        .dashboard_service
+        .get_stats(acme.id, DashboardRange::default())
+        .await
+        .unwrap();
+    assert_eq!(acme_stats.total_candidates, 1);
+    let sync = state
+        .candidate_service
+        .sync_statuses(&CandidateStatusSyncQuery::default(), globex.id)
+        .await
+        .unwrap();
+    assert!(sync.items.iter().all(|c| c.id != candidate));
+    assert_eq!(
+        subscriptions
+            .list(globex.id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.id)
+            .collect::<Vec<_>>(),
+        vec![globex_hook.id]
+    );
+
+    let logs = state
+        .notification_service
+        .enqueue_webhook("test_assigned", &json!({ "attempt_id": attempt }), None)
+        .await
+        .unwrap();
+    let subscribers: Vec<Option<Uuid>> = logs.iter().map(|l| l.subscription_id).collect();
+    assert_eq!(subscribers, vec![None, Some(acme_hook.id)]);
+
+    sqlx::query("DELETE FROM webhook_logs WHERE id = ANY($1)")
+        .bind(logs.iter().map(|l| l.id).collect::<Vec<_>>())
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM webhook_subscriptions WHERE id = ANY($1)")
+        .bind(vec![acme_hook.id, globex_hook.id])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM vacancies WHERE id = $1")
+        .bind(vacancy)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    TestService::new(pool.clone())
+        .delete_test(test.id, acme.id)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM organizations WHERE id = ANY($1)")
+        .bind(vec![acme.id, globex.id])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,146 @@
+use std::env;
+
+use recruitment_backend::dto::integration_dto::{CreateTestPayload, UpdateTestPayload};
+use recruitment_backend::error::Error;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::test_service::TestService;
+use uuid::Uuid;
+
+fn payload(title: &str, prerequisite_test_id: Option<Uuid>) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 10,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+fn candidate(email: &str) -> InviteCandidate {
+    InviteCandidate {
+        external_id: None,
+        name: "Bob".into(),
+        email: email.into(),
+        telegram_id: None,
+        phone: None,
+    }
+}
+
+#[tokio::test]
+async fn prerequisite_must_be_passed_and_acyclic() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    recruitment_backend::config::init_config().expect("init config");
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Prereq User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("prereq_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let tests = TestService::new(pool.clone());
+    let basics = tests
+        .create_test(payload("Basics", None), creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create basics");
+    let advanced = tests
+        .create_test(
+            payload("Advanced", Some(basics.id)),
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create advanced");
+    assert_eq!(advanced.prerequisite_test_id, Some(basics.id));
+
+    let missing = tests
+        .create_test(
+            payload("Orphan", Some(Uuid::new_v4())),
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await;
+    assert!(matches!(missing, Err(Error::BadRequest(_))));
+
+    let circular = tests
+        .update_test(
+            basics.id,
+            DEFAULT_ORGANIZATION_ID,
+            serde_json::from_value::<UpdateTestPayload>(
+                serde_json::json!({ "prerequisite_test_id": advanced.id }),
+            )
+            .unwrap(),
+        )
+        .await;
+    assert!(matches!(circular, Err(Error::BadRequest(_))));
+
+    let attempts = AttemptService::new(pool.clone());
+    let email = format!("bob_{}@example.com", Uuid::new_v4());
+    let blocked = attempts
+        .create_invite(advanced.id, candidate(&email), 2, None, None, InviteSource::Integration)
+        .await;
+    match blocked {
+        Err(Error::BadRequest(msg)) => {
+            assert_eq!(msg, "Candidate has not passed the prerequisite test")
+        }
+        other => panic!("expected prerequisite error, got {:?}", other.map(|r| r.attempt_id)),
+    }
+
+    let first = attempts
+        .create_invite(basics.id, candidate(&email), 2, None, None, InviteSource::Integration)
+        .await
+        .expect("invite to basics");
+    sqlx::query(
+        "UPDATE test_attempts SET status = 'passed', passed = TRUE, completed_at = NOW() WHERE id = $1",
+    )
+    .bind(first.attempt_id)
+    .execute(&pool)
+    .await
+    .expect("mark passed");
+
+    attempts
+        .create_invite(advanced.id, candidate(&email), 2, None, None, InviteSource::Integration)
+        .await
+        .expect("invite after passing prerequisite");
+}
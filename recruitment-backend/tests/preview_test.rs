@@ -0,0 +1,196 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    AttemptService, ReviewAssignment, PREVIEW_CANDIDATE_EMAIL, PREVIEW_EXPIRY_HOURS,
+    PREVIEW_RETENTION_HOURS,
+};
+use recruitment_backend::services::test_service::TestService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+#[tokio::test]
+async fn previews_never_show_up_in_listings_or_stats() {
+    let pool = setup_pool().await;
+    let since = Utc::now();
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Preview test', '[]', 'question_based', 50, 30) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let real: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status)
+           VALUES ($1, 'Real', 'real_preview@example.com', $2, NOW() + INTERVAL '1 day', '[]', 'pending')
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let svc = AttemptService::new(pool.clone());
+    let preview = svc
+        .create_preview(test_id, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(preview.status, "pending");
+    let lifetime = preview.expires_at - Utc::now();
+    assert!(lifetime <= Duration::hours(PREVIEW_EXPIRY_HOURS));
+    assert!(lifetime > Duration::hours(PREVIEW_EXPIRY_HOURS) - Duration::minutes(1));
+
+    // The public flow works on the preview as on any attempt.
+    let started = svc
+        .start_attempt_by_token(&preview.access_token, true, None, None)
+        .await
+        .unwrap();
+    assert!(started.is_preview);
+    assert_eq!(started.status, "in_progress");
+    assert_eq!(started.candidate_email, PREVIEW_CANDIDATE_EMAIL);
+    let candidates: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM candidates WHERE email = $1")
+        .bind(PREVIEW_CANDIDATE_EMAIL)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(candidates, 0);
+
+    sqlx::query(
+        "UPDATE test_attempts SET status = 'completed', completed_at = NOW(), passed = TRUE, graded_answers = '[]' WHERE id = $1",
+    )
+    .bind(preview.attempt_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (items, total) = svc
+        .list_attempts(
+            Some(test_id),
+            None,
+            None,
+            None,
+            1,
+            50,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(items.iter().map(|a| a.id).collect::<Vec<_>>(), vec![real]);
+
+    let statuses = svc
+        .get_status_distribution_between(DEFAULT_ORGANIZATION_ID, Some(since), None)
+        .await
+        .unwrap();
+    assert_eq!(statuses.get("completed"), None, "{:?}", statuses);
+    let sources = svc
+        .get_source_distribution_between(DEFAULT_ORGANIZATION_ID, Some(since), None)
+        .await
+        .unwrap();
+    assert_eq!(sources.values().sum::<i64>(), 1, "{:?}", sources);
+
+    assert_eq!(
+        TestService::new(pool.clone())
+            .get_pass_rate(test_id)
+            .await
+            .unwrap_err()
+            .code(),
+        "no_completed_attempts"
+    );
+
+    sqlx::query("UPDATE test_attempts SET status = 'needs_review' WHERE id = $1")
+        .bind(preview.attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (queue, _) = svc
+        .list_review_queue(ReviewAssignment::All, DEFAULT_ORGANIZATION_ID, 1, 1000)
+        .await
+        .unwrap();
+    assert!(queue.iter().all(|a| a.id != preview.attempt_id));
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn old_previews_are_deleted() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Preview cleanup', '[]', 'question_based', 0, 30) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let svc = AttemptService::new(pool.clone());
+    let old = svc
+        .create_preview(test_id, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    let fresh = svc
+        .create_preview(test_id, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE test_attempts SET created_at = $2 WHERE id = $1")
+        .bind(old.attempt_id)
+        .bind(Utc::now() - Duration::hours(PREVIEW_RETENTION_HOURS + 1))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(svc.delete_old_previews(Utc::now()).await.unwrap() >= 1);
+    let left: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(left, vec![fresh.attempt_id]);
+
+    assert_eq!(
+        svc.create_preview(Uuid::new_v4(), DEFAULT_ORGANIZATION_ID)
+            .await
+            .unwrap_err()
+            .code(),
+        "not_found"
+    );
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,80 @@
+use std::env;
+
+use recruitment_backend::error::Error;
+use recruitment_backend::services::prompt_service::{prompt_text, PromptService};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn prompt_text_accepts_strings_and_line_arrays() {
+    assert_eq!(
+        prompt_text(&json!("Be concise.")).as_deref(),
+        Some("Be concise.")
+    );
+    assert_eq!(
+        prompt_text(&json!(["Rule one.", "Rule two."])).as_deref(),
+        Some("Rule one.\nRule two.")
+    );
+    assert!(prompt_text(&json!("  ")).is_none());
+    assert!(prompt_text(&json!([])).is_none());
+    assert!(prompt_text(&json!(["ok", 5])).is_none());
+    assert!(prompt_text(&json!({ "text": "nested" })).is_none());
+}
+
+#[tokio::test]
+async fn refresh_picks_up_database_prompts() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let key = format!("test.{}", Uuid::new_v4());
+    let prompts = PromptService::new(pool.clone());
+    prompts.refresh().await.unwrap();
+    assert!(matches!(prompts.get(&key).await, Err(Error::NotFound(_))));
+    assert_eq!(prompts.text_or(&key, "embedded").await, "embedded");
+
+    sqlx::query("INSERT INTO ai_prompts (key, content) VALUES ($1, $2)")
+        .bind(&key)
+        .bind(json!(["Line A", "Line B"]))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Cached until the next refresh.
+    assert_eq!(prompts.text_or(&key, "embedded").await, "embedded");
+    prompts.refresh().await.unwrap();
+    assert_eq!(
+        prompts.get(&key).await.unwrap(),
+        json!(["Line A", "Line B"])
+    );
+    assert_eq!(prompts.text_or(&key, "embedded").await, "Line A\nLine B");
+
+    sqlx::query("UPDATE ai_prompts SET content = $2, updated_at = NOW() WHERE key = $1")
+        .bind(&key)
+        .bind(json!({ "not": "text" }))
+        .execute(&pool)
+        .await
+        .unwrap();
+    prompts.refresh().await.unwrap();
+    assert_eq!(prompts.text_or(&key, "embedded").await, "embedded");
+
+    sqlx::query("DELETE FROM ai_prompts WHERE key = $1")
+        .bind(&key)
+        .execute(&pool)
+        .await
+        .unwrap();
+    prompts.refresh().await.unwrap();
+    assert!(prompts.get(&key).await.is_err());
+}
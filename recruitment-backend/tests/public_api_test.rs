@@ -6,6 +6,7 @@ use axum::{
     routing::{get, patch, post},
     Router,
 };
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
 use serde_json::{json, Value as JsonValue};
 use tower::ServiceExt;
 use uuid::Uuid;
@@ -58,6 +59,7 @@ async fn public_flow_end_to_end() {
                         recruitment_backend::models::question::QuestionType::MultipleChoice,
                     question: "2+2?".into(),
                     points: 1,
+                    difficulty: Default::default(),
                     details: recruitment_backend::models::question::QuestionDetails::MultipleChoice(
                         recruitment_backend::models::question::MultipleChoiceDetails {
                             options: vec!["1".into(), "2".into(), "3".into(), "4".into()],
@@ -74,8 +76,21 @@ async fn public_flow_end_to_end() {
                 test_type: Some("question_based".to_string()),
                 presentation_themes: None,
                 presentation_extra_info: None,
+                show_honesty_declaration: None,
+                declaration_text: None,
+                prerequisite_test_id: None,
+                default_invite_expiry_hours: None,
+                reminder_hours_before: None,
+                passing_score_mcq: None,
+                passing_score_open: None,
+                share_results_details: None,
+                max_grace_seconds: None,
+                max_concurrent_takers: None,
+                language: None,
+                reveal_correct_answers: None,
             },
             creator,
+            DEFAULT_ORGANIZATION_ID,
         )
         .await
         .expect("create test");
@@ -95,6 +110,8 @@ async fn public_flow_end_to_end() {
             },
             2,
             None,
+            None,
+            recruitment_backend::services::attempt_service::InviteSource::Integration,
         )
         .await
         .expect("invite");
@@ -135,13 +152,32 @@ async fn public_flow_end_to_end() {
         .unwrap();
     let resp = app.clone().oneshot(req).await.unwrap();
     assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let body: JsonValue = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["requires_declaration"], true);
+
     let req = Request::builder()
         .method("POST")
         .uri(format!("/api/public/tests/{}/start", token))
-        .body(Body::empty())
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "accept_declaration": false }).to_string()))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/public/tests/{}/start", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "accept_declaration": true }).to_string()))
         .unwrap();
     let resp = app.clone().oneshot(req).await.unwrap();
     assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let started = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(!started.contains("correct_answer"), "answer key leaked: {}", started);
+    let body: JsonValue = serde_json::from_str(&started).unwrap();
+    assert_eq!(body["questions"][0]["options"], json!(["1", "2", "3", "4"]));
     let save_body = json!({
         "question_id": 1,
         "answer": json!({"selected": 3}),
@@ -170,7 +206,9 @@ async fn public_flow_end_to_end() {
     assert_eq!(resp.status(), StatusCode::OK);
     let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
     let body: JsonValue = serde_json::from_slice(&bytes).unwrap();
-    assert!(body["passed"].is_boolean());
+    // The test doesn't show results immediately, so the outcome stays with HR.
+    assert_eq!(body["result_state"], "hidden");
+    assert!(body.get("passed").is_none());
 
     let req = Request::builder()
         .method("GET")
@@ -0,0 +1,77 @@
+use recruitment_backend::dto::public_dto::{PublicQuestionView, StartTestResponse};
+use serde_json::{json, Value as JsonValue};
+
+fn has_key(value: &JsonValue, key: &str) -> bool {
+    match value {
+        JsonValue::Object(map) => map.contains_key(key) || map.values().any(|v| has_key(v, key)),
+        JsonValue::Array(items) => items.iter().any(|v| has_key(v, key)),
+        _ => false,
+    }
+}
+
+fn start_response(questions: JsonValue) -> JsonValue {
+    serde_json::to_value(StartTestResponse {
+        attempt_id: uuid::Uuid::new_v4(),
+        access_token: "rotated".into(),
+        status: "in_progress".into(),
+        started_at: chrono::Utc::now(),
+        expires_at: chrono::Utc::now(),
+        questions: PublicQuestionView::sanitize_snapshot(&questions),
+    })
+    .unwrap()
+}
+
+#[test]
+fn start_response_carries_no_answer_key() {
+    let snapshot = json!([
+        {
+            "id": 1, "type": "multiple_choice", "question": "2+2?", "points": 2,
+            "difficulty": "easy", "options": ["3", "4"], "correct_answer": 1,
+            "explanation": "Arithmetic"
+        },
+        {
+            "id": 2, "type": "short_answer", "question": "Describe ownership",
+            "expected_keywords": ["borrow", "move"], "min_words": 30
+        },
+        {
+            "id": 3, "type": "code", "question": "Sum two numbers", "language": "python",
+            "starter_code": "def add(a, b):", "test_cases": [{ "input": "1 2", "expected": "3" }]
+        },
+        {
+            "id": 4, "type": "fill_in_blank", "question": "Complete the term",
+            "blanked_sentence": "Habeas ___", "correct_words": ["corpus"]
+        }
+    ]);
+
+    let body = start_response(snapshot);
+    for key in ["correct_answer", "explanation", "expected_keywords", "correct_words"] {
+        assert!(!has_key(&body, key), "{} leaked: {}", key, body);
+    }
+    let questions = &body["questions"];
+    assert_eq!(questions[0]["options"], json!(["3", "4"]));
+    assert_eq!(questions[0]["points"], 2);
+    assert_eq!(questions[1]["type"], "short_answer");
+    assert_eq!(questions[1]["min_words"], 30);
+    assert_eq!(questions[2]["starter_code"], "def add(a, b):");
+    assert_eq!(questions[3]["type"], "fill_in_blank");
+    assert_eq!(questions[3]["blanked_sentence"], "Habeas ___");
+}
+
+#[test]
+fn unknown_question_shapes_are_still_stripped() {
+    let body = start_response(json!([
+        { "id": 1, "type": "matching", "question": "?", "correct_answer": [1, 0] }
+    ]));
+    assert!(!has_key(&body, "correct_answer"));
+    assert_eq!(body["questions"][0]["type"], "matching");
+}
+
+#[test]
+fn presentation_snapshot_passes_through() {
+    let snapshot = json!({
+        "test_type": "presentation",
+        "themes": [{ "title": "Go-to-market plan" }],
+        "extra_info": "10 slides max"
+    });
+    assert_eq!(PublicQuestionView::sanitize_snapshot(&snapshot), snapshot);
+}
@@ -0,0 +1,155 @@
+use recruitment_backend::models::question::{
+    Difficulty, Question, QuestionDetails, QuestionType, ShortAnswerDetails,
+};
+use recruitment_backend::services::ai_service::{
+    allocate_points, assemble_test, DifficultyDistribution, DEFAULT_HARD_POINTS_MULTIPLIER,
+};
+use std::collections::HashMap;
+
+fn question(label: &str, difficulty: Difficulty) -> Question {
+    Question {
+        id: 0,
+        question_type: QuestionType::ShortAnswer,
+        question: label.to_string(),
+        points: 10,
+        difficulty,
+        details: QuestionDetails::ShortAnswer(ShortAnswerDetails {
+            expected_keywords: None,
+            min_words: Some(40),
+            ai_grading: true,
+        }),
+    }
+}
+
+/// Interleaves difficulties as e0, m0, h0, e1, m1, h1, ... until each runs out.
+fn pool(easy: usize, medium: usize, hard: usize) -> Vec<Question> {
+    let mut out = Vec::new();
+    for i in 0..easy.max(medium).max(hard) {
+        if i < easy {
+            out.push(question(&format!("e{}", i), Difficulty::Easy));
+        }
+        if i < medium {
+            out.push(question(&format!("m{}", i), Difficulty::Medium));
+        }
+        if i < hard {
+            out.push(question(&format!("h{}", i), Difficulty::Hard));
+        }
+    }
+    out
+}
+
+fn count(questions: &[Question], difficulty: Difficulty) -> usize {
+    questions.iter().filter(|q| q.difficulty == difficulty).count()
+}
+
+fn dist(pairs: &[(&str, f64)]) -> DifficultyDistribution {
+    let map: HashMap<String, f64> = pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+    DifficultyDistribution::from_map(&map).expect("valid distribution")
+}
+
+#[test]
+fn targets_always_sum_to_total() {
+    let default = DifficultyDistribution::default();
+    for total in 0..30 {
+        let targets = default.targets(total);
+        assert_eq!(targets.values().sum::<usize>(), total, "total {}", total);
+    }
+    let ten = default.targets(10);
+    assert_eq!(ten[&Difficulty::Easy], 3);
+    assert_eq!(ten[&Difficulty::Medium], 5);
+    assert_eq!(ten[&Difficulty::Hard], 2);
+}
+
+#[test]
+fn assemble_enforces_distribution_when_truncating() {
+    let selected = assemble_test(
+        pool(8, 8, 8),
+        10,
+        &dist(&[("easy", 20.0), ("medium", 40.0), ("hard", 40.0)]),
+        DEFAULT_HARD_POINTS_MULTIPLIER,
+    );
+
+    assert_eq!(selected.len(), 10);
+    assert_eq!(count(&selected, Difficulty::Easy), 2);
+    assert_eq!(count(&selected, Difficulty::Medium), 4);
+    assert_eq!(count(&selected, Difficulty::Hard), 4);
+    let ids: Vec<i32> = selected.iter().map(|q| q.id).collect();
+    assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+    assert_eq!(selected[0].question, "e0");
+}
+
+#[test]
+fn short_bucket_is_filled_from_other_difficulties() {
+    let selected = assemble_test(
+        pool(6, 6, 1),
+        6,
+        &dist(&[("hard", 1.0)]),
+        DEFAULT_HARD_POINTS_MULTIPLIER,
+    );
+
+    assert_eq!(selected.len(), 6);
+    assert_eq!(count(&selected, Difficulty::Hard), 1);
+    assert_eq!(selected.iter().map(|q| q.points).sum::<i32>(), 100);
+}
+
+#[test]
+fn fewer_candidates_than_requested_keeps_everything() {
+    let selected = assemble_test(
+        pool(1, 1, 0),
+        5,
+        &DifficultyDistribution::default(),
+        DEFAULT_HARD_POINTS_MULTIPLIER,
+    );
+    assert_eq!(selected.len(), 2);
+}
+
+#[test]
+fn hard_questions_are_worth_more() {
+    let mut questions = vec![
+        question("e", Difficulty::Easy),
+        question("m", Difficulty::Medium),
+        question("h", Difficulty::Hard),
+        question("h2", Difficulty::Hard),
+    ];
+    allocate_points(&mut questions, 3.0);
+
+    let points: Vec<i32> = questions.iter().map(|q| q.points).collect();
+    assert_eq!(points.iter().sum::<i32>(), 100);
+    assert_eq!(points, vec![11, 22, 34, 33]);
+
+    allocate_points(&mut questions, 1.0);
+    assert_eq!(
+        questions.iter().map(|q| q.points).collect::<Vec<_>>(),
+        vec![25, 25, 25, 25]
+    );
+}
+
+#[test]
+fn invalid_distributions_are_rejected() {
+    let bad = |pairs: &[(&str, f64)]| {
+        let map: HashMap<String, f64> = pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+        DifficultyDistribution::from_map(&map).is_err()
+    };
+    assert!(bad(&[("extreme", 1.0)]));
+    assert!(bad(&[("easy", -1.0)]));
+    assert!(bad(&[("easy", 0.0), ("hard", 0.0)]));
+    assert_eq!(
+        DifficultyDistribution::from_optional_map(None).unwrap(),
+        DifficultyDistribution::default()
+    );
+}
+
+#[test]
+fn questions_without_difficulty_default_to_medium() {
+    let q: Question = serde_json::from_value(serde_json::json!({
+        "id": 1,
+        "type": "short_answer",
+        "question": "Old snapshot",
+        "points": 10,
+        "expected_keywords": null,
+        "min_words": 40
+    }))
+    .expect("old snapshot deserializes");
+    assert_eq!(q.difficulty, Difficulty::Medium);
+    assert_eq!(Difficulty::parse(" Advanced "), Some(Difficulty::Hard));
+}
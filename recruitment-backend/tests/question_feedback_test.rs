@@ -0,0 +1,191 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::{get, patch, post},
+    Router,
+};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::question_feedback_service::{hash_email, snapshot_has_question};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn emails_hash_case_insensitively() {
+    let hashed = hash_email("Candidate@Example.com ");
+    assert_eq!(hashed, hash_email("candidate@example.com"));
+    assert_eq!(hashed.len(), 64);
+    assert!(!hashed.contains('@'));
+    assert_ne!(hashed, hash_email("other@example.com"));
+}
+
+#[test]
+fn feedback_must_target_a_snapshot_question() {
+    let snapshot = json!([{ "id": 1, "question": "2 + 2?" }, { "id": 7 }]);
+    assert!(snapshot_has_question(&snapshot, 7));
+    assert!(!snapshot_has_question(&snapshot, 3));
+    assert!(!snapshot_has_question(
+        &json!({ "test_type": "presentation", "themes": [] }),
+        1
+    ));
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn candidates_flag_questions_and_hr_reviews_them() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Feedback User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("feedback_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Feedback",
+        "duration_minutes": 10,
+        "passing_score": 50.0,
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+
+    let email = format!("Feedback_{}@Example.com", Uuid::new_v4());
+    let token = Uuid::new_v4().simple().to_string();
+    sqlx::query(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status)
+           VALUES ($1, 'Feedback Candidate', $2, $3, NOW() + INTERVAL '1 day', $4, 'in_progress')"#,
+    )
+    .bind(test.id)
+    .bind(&email)
+    .bind(&token)
+    .bind(json!([{ "id": 1, "type": "multiple_choice", "question": "2 + 2?" }]))
+    .execute(&pool)
+    .await
+    .expect("seed attempt");
+
+    let app = Router::new()
+        .route(
+            "/api/public/tests/:token/feedback",
+            post(recruitment_backend::routes::public::submit_question_feedback),
+        )
+        .route(
+            "/api/integration/tests/:id/feedback",
+            get(recruitment_backend::routes::integration::list_question_feedback),
+        )
+        .route(
+            "/api/integration/tests/:id/feedback/:feedback_id",
+            patch(recruitment_backend::routes::integration::review_question_feedback),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let feedback_uri = format!("/api/public/tests/{}/feedback", token);
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        &feedback_uri,
+        json!({ "question_id": 1, "text": "Both 4 and IV look correct" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &feedback_uri,
+        json!({ "question_id": 99, "text": "?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "question_not_found");
+    let (status, _) = send(
+        &app,
+        "POST",
+        &feedback_uri,
+        json!({ "question_id": 1, "text": "" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let list_uri = format!("/api/integration/tests/{}/feedback", test.id);
+    let (status, items) = send(&app, "GET", &list_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = items.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], created["id"]);
+    assert_eq!(items[0]["question_id"], 1);
+    assert_eq!(items[0]["feedback_text"], "Both 4 and IV look correct");
+    assert_eq!(items[0]["candidate_email"], hash_email(&email));
+
+    let review_uri = format!("{}/{}", list_uri, created["id"].as_str().unwrap());
+    let (status, reviewed) = send(&app, "PATCH", &review_uri, json!({ "reviewed": true })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reviewed["reviewed"], true);
+    let (_, items) = send(&app, "GET", &list_uri, Value::Null).await;
+    assert!(items.as_array().unwrap().is_empty());
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!(
+            "/api/integration/tests/{}/feedback/{}",
+            Uuid::new_v4(),
+            created["id"].as_str().unwrap()
+        ),
+        json!({ "reviewed": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
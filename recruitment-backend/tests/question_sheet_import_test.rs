@@ -0,0 +1,343 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use calamine::{open_workbook_from_rs, Reader, Xlsx};
+use recruitment_backend::models::question::QuestionDetails;
+use recruitment_backend::services::question_import_service::{
+    parse_question_sheet, question_template, QUESTION_SHEET_COLUMNS,
+};
+use rust_xlsxwriter::Workbook;
+use serde_json::{json, Value as JsonValue};
+use tower::ServiceExt;
+
+/// Builds an XLSX file with the template header and `rows` below it; numbers
+/// are written as numeric cells the way Excel stores them.
+fn sheet(rows: &[&[&str]]) -> Vec<u8> {
+    let mut workbook = Workbook::new();
+    let ws = workbook.add_worksheet();
+    for (col, name) in QUESTION_SHEET_COLUMNS.iter().enumerate() {
+        ws.write_string(0, col as u16, *name).unwrap();
+    }
+    for (r, row) in rows.iter().enumerate() {
+        for (c, value) in row.iter().enumerate() {
+            let (r, c) = (r as u32 + 1, c as u16);
+            if value.is_empty() {
+                continue;
+            }
+            match value.parse::<f64>() {
+                Ok(n) => ws.write_number(r, c, n).unwrap(),
+                Err(_) => ws.write_string(r, c, *value).unwrap(),
+            };
+        }
+    }
+    workbook.save_to_buffer().unwrap()
+}
+
+const VALID_ROWS: &[&[&str]] = &[
+    &[
+        "multiple_choice",
+        "Какой документ сопровождает отгрузку?",
+        "Накладная",
+        "Счёт",
+        "Договор",
+        "",
+        "1",
+        "Накладная сопровождает товар",
+        "",
+        "",
+        "2",
+    ],
+    &[],
+    &[
+        "short_answer",
+        "Опишите порядок инвентаризации",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "пересчёт, акт; сверка",
+        "30",
+        "",
+    ],
+];
+
+#[test]
+fn parses_multiple_choice_and_short_answer_rows() {
+    let questions = parse_question_sheet(&sheet(VALID_ROWS)).unwrap();
+    assert_eq!(questions.len(), 2, "the blank row is skipped");
+
+    assert_eq!(questions[0].points, 2);
+    match &questions[0].details {
+        QuestionDetails::MultipleChoice(mc) => {
+            assert_eq!(mc.options, vec!["Накладная", "Счёт", "Договор"]);
+            assert_eq!(mc.correct_answer, 0, "correct_option is 1-based");
+            assert_eq!(
+                mc.explanation.as_deref(),
+                Some("Накладная сопровождает товар")
+            );
+        }
+        other => panic!("expected multiple choice, got {:?}", other),
+    }
+
+    assert_eq!(questions[1].points, 1, "empty points default to 1");
+    match &questions[1].details {
+        QuestionDetails::ShortAnswer(sa) => {
+            assert_eq!(
+                sa.expected_keywords.as_deref(),
+                Some(
+                    &[
+                        "пересчёт".to_string(),
+                        "акт".to_string(),
+                        "сверка".to_string()
+                    ][..]
+                )
+            );
+            assert_eq!(sa.min_words, Some(30));
+        }
+        other => panic!("expected short answer, got {:?}", other),
+    }
+}
+
+#[test]
+fn malformed_rows_are_reported_together_by_row_number() {
+    let rows: &[&[&str]] = &[
+        VALID_ROWS[0],
+        &["essay", "Расскажите о себе"],
+        &[
+            "multiple_choice",
+            "",
+            "Да",
+            "",
+            "Нет",
+            "",
+            "5",
+            "",
+            "",
+            "",
+            "0",
+        ],
+        &[
+            "short_answer",
+            "Что такое FIFO?",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "много",
+        ],
+    ];
+    let err = parse_question_sheet(&sheet(rows)).unwrap_err();
+    assert_eq!(err.code(), "invalid_question_rows");
+    let resp = axum::response::IntoResponse::into_response(err);
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let err = parse_question_sheet(&sheet(rows)).unwrap_err();
+    let recruitment_backend::error::Error::Coded {
+        details: Some(details),
+        ..
+    } = err
+    else {
+        panic!("expected coded error with details");
+    };
+    let reported: Vec<(u64, usize)> = details["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["row"].as_u64().unwrap(),
+                r["problems"].as_array().unwrap().len(),
+            )
+        })
+        .collect();
+    assert_eq!(reported, vec![(3, 1), (4, 4), (5, 1)]);
+    assert!(details["rows"][0]["problems"][0]
+        .as_str()
+        .unwrap()
+        .contains("essay"));
+}
+
+#[test]
+fn rejects_files_that_are_not_question_sheets() {
+    let err = parse_question_sheet(b"type,question\nmultiple_choice,x").unwrap_err();
+    assert_eq!(err.code(), "invalid_question_sheet");
+
+    let err = parse_question_sheet(&sheet(&[])).unwrap_err();
+    assert_eq!(err.code(), "invalid_question_sheet");
+}
+
+#[test]
+fn template_has_the_import_columns_and_no_questions() {
+    let template = question_template().unwrap();
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(std::io::Cursor::new(&template)).unwrap();
+    let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+    let headers: Vec<String> = range
+        .rows()
+        .next()
+        .unwrap()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(headers, QUESTION_SHEET_COLUMNS);
+
+    let err = parse_question_sheet(&template).unwrap_err();
+    assert_eq!(err.code(), "invalid_question_sheet");
+}
+
+fn upload(uri: &str, data: &[u8]) -> Request<Body> {
+    let boundary = "sheet-boundary";
+    let mut body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"questions.xlsx\"\r\nContent-Type: application/vnd.openxmlformats-officedocument.spreadsheetml.sheet\r\n\r\n",
+        b = boundary
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+async fn call(app: &Router, req: Request<Body>) -> (StatusCode, JsonValue) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn import_endpoint_appends_questions_unless_dry_run() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/tests/question-template.xlsx",
+            get(recruitment_backend::routes::integration::question_template),
+        )
+        .route(
+            "/api/integration/tests/:id/questions/import",
+            post(recruitment_backend::routes::integration::import_questions),
+        )
+        .route(
+            "/api/integration/tests/import",
+            post(recruitment_backend::routes::integration::import_test),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let spec = serde_json::to_string(&json!({
+        "title": "Sheet Import",
+        "duration_minutes": 10,
+        "passing_score": 50.0,
+        "questions": [{
+            "type": "short_answer",
+            "question": "Existing question",
+            "points": 1,
+            "min_words": 5
+        }]
+    }))
+    .unwrap();
+    let boundary = "spec-boundary";
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/integration/tests/import")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"spec.json\"\r\n\r\n{s}\r\n--{b}--\r\n",
+            b = boundary,
+            s = spec
+        )))
+        .unwrap();
+    let (status, body) = call(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let id: uuid::Uuid = serde_json::from_value(body["id"].clone()).unwrap();
+    let uri = format!("/api/integration/tests/{}/questions/import", id);
+
+    let (status, body) = call(
+        &app,
+        upload(&format!("{}?dry_run=true", uri), &sheet(VALID_ROWS)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["dry_run"], true);
+    assert_eq!(body["questions"].as_array().unwrap().len(), 2);
+    let stored = recruitment_backend::services::test_service::TestService::new(pool.clone())
+        .get_test_by_id(id)
+        .await
+        .unwrap();
+    assert_eq!(
+        stored.questions.as_array().unwrap().len(),
+        1,
+        "dry run saved questions"
+    );
+
+    let (status, body) = call(&app, upload(&uri, &sheet(VALID_ROWS))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["imported"], 2);
+    let questions = body["test"]["questions"].as_array().unwrap();
+    assert_eq!(questions.len(), 3);
+    let ids: Vec<i64> = questions
+        .iter()
+        .map(|q| q["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(questions[0]["question"], "Existing question");
+
+    let (status, body) = call(&app, upload(&uri, &sheet(&[&["essay", "x"]]))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "invalid_question_rows");
+    assert_eq!(body["error"]["details"]["rows"][0]["row"], 2);
+
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/integration/tests/question-template.xlsx")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers()[header::CONTENT_DISPOSITION]
+        .to_str()
+        .unwrap()
+        .contains("question-template.xlsx"));
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use recruitment_backend::dto::integration_dto::{CreateQuestion, CreateTestPayload};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::models::question::{
+    MultipleChoiceDetails, Question, QuestionDetails, QuestionType,
+};
+use recruitment_backend::services::attempt_service::{
+    append_regrade_event, AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::grading_service::GradingService;
+use recruitment_backend::services::test_service::TestService;
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+
+fn mcq(correct_answer: i32) -> MultipleChoiceDetails {
+    MultipleChoiceDetails {
+        options: vec!["A".into(), "B".into(), "C".into()],
+        correct_answer,
+        explanation: None,
+    }
+}
+
+fn questions() -> Vec<Question> {
+    serde_json::from_value(json!([
+        { "id": 1, "type": "multiple_choice", "question": "Q1", "points": 5,
+          "options": ["A", "B", "C"], "correct_answer": 0, "explanation": null },
+        { "id": 2, "type": "short_answer", "question": "Q2", "points": 5,
+          "expected_keywords": null, "min_words": null }
+    ]))
+    .unwrap()
+}
+
+fn corrections(question_id: i32, correct_answer: i32) -> BTreeMap<i32, MultipleChoiceDetails> {
+    BTreeMap::from([(question_id, mcq(correct_answer))])
+}
+
+#[test]
+fn patch_skips_unknown_and_non_mcq_questions() {
+    let mut qs = questions();
+    let mut fixes = corrections(1, 2);
+    fixes.insert(2, mcq(1));
+    fixes.insert(9, mcq(1));
+
+    let patched = GradingService::patch_answer_key(&mut qs, &fixes);
+    assert_eq!(patched.into_iter().collect::<Vec<_>>(), vec![1]);
+    match &qs[0].details {
+        QuestionDetails::MultipleChoice(mc) => assert_eq!(mc.correct_answer, 2),
+        other => panic!("unexpected details {:?}", other),
+    }
+    assert!(matches!(qs[1].question_type, QuestionType::ShortAnswer));
+}
+
+#[test]
+fn regrade_keeps_manual_grades_of_other_questions() {
+    let mut qs = questions();
+    let answers = vec![
+        json!({ "question_id": 1, "answer": 2 }),
+        json!({ "question_id": 2, "answer": "text" }),
+    ];
+    let (_, _, mut graded, _) = GradingService::grade_mcq_only(&qs, &answers);
+    graded[1]["points_earned"] = json!(4);
+    graded[1]["needs_review"] = json!(false);
+    assert_eq!(GradingService::totals(&graded), (4, 10));
+
+    let fixes = corrections(1, 2);
+    let affected = GradingService::patch_answer_key(&mut qs, &fixes);
+    GradingService::regrade_questions(&qs, &answers, &mut graded, &affected);
+
+    assert_eq!(GradingService::totals(&graded), (9, 10));
+    assert_eq!(graded[0]["is_correct"], true);
+    assert_eq!(graded[1]["points_earned"], 4);
+    assert!(!GradingService::needs_review(&graded));
+}
+
+#[test]
+fn regrade_events_accumulate_in_metadata() {
+    let first = append_regrade_event(Some(json!({ "vacancy_id": 7 })), json!({ "n": 1 }));
+    let second = append_regrade_event(Some(first), json!({ "n": 2 }));
+    assert_eq!(second["vacancy_id"], 7);
+    assert_eq!(second["regrades"], json!([{ "n": 1 }, { "n": 2 }]));
+
+    let wrapped = append_regrade_event(Some(json!("legacy")), json!({ "n": 1 }));
+    assert_eq!(wrapped["previous"], "legacy");
+    assert_eq!(append_regrade_event(None, json!({}))["regrades"], json!([{}]));
+}
+
+#[tokio::test]
+async fn regrade_updates_only_graded_attempts_and_reports_flips() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Regrade User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("regrade_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let question = |text: &str| CreateQuestion {
+        question_type: QuestionType::MultipleChoice,
+        question: text.into(),
+        points: 5,
+        difficulty: Default::default(),
+        details: QuestionDetails::MultipleChoice(mcq(0)),
+    };
+    let test = TestService::new(pool.clone())
+        .create_test(
+            CreateTestPayload {
+                title: "Regrade".into(),
+                external_id: None,
+                description: None,
+                instructions: None,
+                questions: Some(vec![question("Q1"), question("Q2")]),
+                duration_minutes: 10,
+                passing_score: 60.0,
+                shuffle_questions: None,
+                shuffle_options: None,
+                show_results_immediately: None,
+                test_type: None,
+                presentation_themes: None,
+                presentation_extra_info: None,
+                show_honesty_declaration: None,
+                declaration_text: None,
+                prerequisite_test_id: None,
+                default_invite_expiry_hours: None,
+                reminder_hours_before: None,
+                passing_score_mcq: None,
+                passing_score_open: None,
+                share_results_details: None,
+                max_grace_seconds: None,
+                max_concurrent_takers: None,
+                language: None,
+                reveal_correct_answers: None,
+            },
+            creator,
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .expect("create test");
+    let snapshot_questions: Vec<Question> = serde_json::from_value(test.questions.clone()).unwrap();
+
+    let attempts = AttemptService::new(pool.clone());
+    // Everyone picked option 2 for Q1 (the real answer) and 0 for Q2.
+    let answers = vec![
+        json!({ "question_id": 1, "answer": 2 }),
+        json!({ "question_id": 2, "answer": 0 }),
+    ];
+    let mut ids = Vec::new();
+    for status in [
+        "completed",
+        "needs_review",
+        "timeout",
+        "in_progress",
+        "pending",
+    ] {
+        let invite = attempts
+            .create_invite(
+                test.id,
+                InviteCandidate {
+                    external_id: None,
+                    name: format!("Cand {}", status),
+                    email: format!("regrade_{}_{}@example.com", status, Uuid::new_v4()),
+                    telegram_id: None,
+                    phone: None,
+                },
+                2,
+                None,
+                None,
+                InviteSource::Integration,
+            )
+            .await
+            .expect("invite");
+        if status != "pending" {
+            let (earned, max, graded, _) =
+                GradingService::grade_mcq_only(&snapshot_questions, &answers);
+            sqlx::query(
+                r#"
+                UPDATE test_attempts
+                SET status = $2, answers = $3, graded_answers = $4, score = $5, max_score = $6,
+                    percentage = 50, passed = FALSE, completed_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(invite.attempt_id)
+            .bind(status)
+            .bind(json!(answers))
+            .bind(json!(graded))
+            .bind(Decimal::from(earned))
+            .bind(Decimal::from(max))
+            .execute(&pool)
+            .await
+            .expect("seed attempt");
+        }
+        ids.push(invite.attempt_id);
+    }
+
+    let invalid = attempts
+        .regrade_test(test.id, DEFAULT_ORGANIZATION_ID, &corrections(1, 5))
+        .await;
+    assert!(invalid.is_err());
+    let unknown = attempts
+        .regrade_test(test.id, DEFAULT_ORGANIZATION_ID, &corrections(42, 1))
+        .await;
+    assert!(unknown.is_err());
+
+    let summary = attempts
+        .regrade_test(test.id, DEFAULT_ORGANIZATION_ID, &corrections(1, 2))
+        .await
+        .expect("regrade");
+    assert_eq!(summary.attempts_regraded, 3);
+    let flipped: Vec<Uuid> = summary.pass_changes.iter().map(|c| c.attempt_id).collect();
+    assert_eq!(flipped.len(), 3);
+    assert!(ids[..3].iter().all(|id| flipped.contains(id)));
+    assert!(summary.pass_changes.iter().all(|c| c.before == Some(false) && c.after));
+
+    let completed = attempts.get_attempt_by_id(ids[0]).await.unwrap();
+    assert_eq!(completed.percentage, Some(Decimal::from(100)));
+    assert_eq!(completed.passed, Some(true));
+    assert_eq!(completed.status, "completed");
+    assert_eq!(completed.metadata.unwrap()["regrades"][0]["questions"], json!([1]));
+
+    let timed_out = attempts.get_attempt_by_id(ids[2]).await.unwrap();
+    assert_eq!(timed_out.passed, Some(true));
+    assert_eq!(timed_out.status, "timeout");
+
+    let in_progress = attempts.get_attempt_by_id(ids[3]).await.unwrap();
+    assert_eq!(in_progress.passed, Some(false));
+    assert!(in_progress.metadata.unwrap().get("regrades").is_none());
+
+    let webhooks: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM webhook_logs WHERE event_type = 'grade_revised' AND payload->>'attempt_id' = ANY($1) AND subscription_id IS NULL AND notification_dedup_key IS NOT NULL",
+    )
+    .bind(ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(webhooks, 3);
+
+    let repeat = attempts
+        .regrade_test(test.id, DEFAULT_ORGANIZATION_ID, &corrections(1, 2))
+        .await
+        .expect("idempotent regrade");
+    assert_eq!(repeat.attempts_regraded, 3);
+    assert!(repeat.pass_changes.is_empty());
+}
@@ -0,0 +1,121 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::post,
+    Router,
+};
+use recruitment_backend::services::ai_service::normalize_rejection_message;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn model_output_without_message_is_rejected() {
+    let raw = json!({ "message": "\"Здравствуйте, Фарида! Спасибо за интерес.\"" });
+    assert_eq!(
+        normalize_rejection_message(&raw).as_deref(),
+        Some("Здравствуйте, Фарида! Спасибо за интерес.")
+    );
+    assert!(normalize_rejection_message(&json!({ "message": 42 })).is_none());
+    assert!(normalize_rejection_message(&json!({ "message": "\"\"" })).is_none());
+}
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn cached_rejection_message_is_reused() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, status) VALUES ($1, 'Rejected', $2, 'rejected')",
+    )
+    .bind(id)
+    .bind(format!("reject_{}@example.com", id))
+    .execute(&pool)
+    .await
+    .expect("seed candidate");
+
+    let state = recruitment_backend::AppState::new(pool.clone());
+    assert_eq!(
+        state.candidate_service.rejection_message(id).await.unwrap(),
+        None
+    );
+    state
+        .candidate_service
+        .set_rejection_message(id, "Спасибо за ваше время.")
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/integration/candidates/:id/rejection-message",
+            post(recruitment_backend::routes::candidate_routes::generate_rejection_message),
+        )
+        .with_state(state);
+    let uri = format!("/api/integration/candidates/{}/rejection-message", id);
+
+    let (status, body) = post_json(&app, &uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["message"], "Спасибо за ваше время.");
+    assert_eq!(body["cached"], true);
+    assert_eq!(body["sent"], false);
+
+    let (status, body) = post_json(&app, &uri, json!({ "lang": "fr" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "unsupported_language");
+
+    // No Telegram chat to deliver to.
+    let (status, _) = post_json(&app, &uri, json!({ "send": true })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let missing = format!(
+        "/api/integration/candidates/{}/rejection-message",
+        Uuid::new_v4()
+    );
+    let (status, body) = post_json(&app, &missing, json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "candidate_not_found");
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,157 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use recruitment_backend::error::{server_error_alert, Error};
+use recruitment_backend::middleware::request_id::{
+    assign_request_id, current_request_id, with_request_id, REQUEST_ID_HEADER,
+};
+use recruitment_backend::services::audit_service::AuditService;
+use serde_json::Value as JsonValue;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/echo",
+            get(|| async {
+                current_request_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default()
+            }),
+        )
+        .route(
+            "/boom",
+            get(|| async { Err::<(), _>(Error::Internal("boom".into())) }),
+        )
+        .route(
+            "/missing",
+            get(|| async { Err::<(), _>(Error::candidate_not_found()) }),
+        )
+        .layer(axum::middleware::from_fn(assign_request_id))
+}
+
+async fn call(uri: &str, incoming: Option<&str>) -> (StatusCode, String, Vec<u8>) {
+    let mut req = Request::builder().uri(uri);
+    if let Some(id) = incoming {
+        req = req.header(REQUEST_ID_HEADER, id);
+    }
+    let resp = app()
+        .oneshot(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = resp.status();
+    let header = resp
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .expect("X-Request-Id header")
+        .to_str()
+        .unwrap()
+        .to_string();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, header, bytes.to_vec())
+}
+
+#[tokio::test]
+async fn every_response_carries_the_request_id() {
+    let (status, header, body) = call("/echo", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(Uuid::parse_str(&header).is_ok());
+    assert_eq!(String::from_utf8(body).unwrap(), header);
+
+    let (_, other, _) = call("/echo", None).await;
+    assert_ne!(header, other);
+}
+
+#[tokio::test]
+async fn valid_incoming_request_id_is_reused() {
+    let incoming = Uuid::new_v4().to_string();
+    let (_, header, _) = call("/echo", Some(&incoming)).await;
+    assert_eq!(header, incoming);
+
+    let (_, header, _) = call("/echo", Some("not-a-uuid")).await;
+    assert_ne!(header, "not-a-uuid");
+    assert!(Uuid::parse_str(&header).is_ok());
+}
+
+#[tokio::test]
+async fn error_bodies_include_the_request_id() {
+    for (uri, expected) in [
+        ("/boom", StatusCode::INTERNAL_SERVER_ERROR),
+        ("/missing", StatusCode::NOT_FOUND),
+    ] {
+        let (status, header, body) = call(uri, None).await;
+        assert_eq!(status, expected);
+        let body: JsonValue = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["request_id"], header);
+    }
+}
+
+#[test]
+fn server_error_alert_names_the_request() {
+    let id = Uuid::new_v4();
+    let text = server_error_alert(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "database_error",
+        "connection reset",
+        Some(id),
+    );
+    assert!(text.contains("500"));
+    assert!(text.contains("database_error"));
+    assert!(text.contains(&format!("Request ID: {}", id)));
+    assert!(text.ends_with("connection reset"));
+
+    let long = "x".repeat(5000);
+    let text = server_error_alert(StatusCode::BAD_GATEWAY, "upstream_error", &long, None);
+    assert!(text.contains("Request ID: -"));
+    assert!(text.chars().count() < 1100);
+    assert!(text.ends_with('…'));
+}
+
+#[tokio::test]
+async fn audit_entries_record_the_correlation_id() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let audit = AuditService::new(pool.clone());
+    let entity_id = Uuid::new_v4();
+    let request_id = Uuid::new_v4();
+
+    let tagged = with_request_id(
+        request_id,
+        audit.log(None, "request_id_test", "test", entity_id, None, None, None),
+    )
+    .await
+    .unwrap();
+    assert_eq!(tagged.correlation_id, Some(request_id));
+
+    let untagged = audit
+        .log(None, "request_id_test", "test", entity_id, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(untagged.correlation_id, None);
+
+    sqlx::query("DELETE FROM audit_logs WHERE entity_id = $1")
+        .bind(entity_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
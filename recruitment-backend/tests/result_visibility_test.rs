@@ -0,0 +1,268 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use recruitment_backend::AppState;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn app(pool: &PgPool) -> Router {
+    Router::new()
+        .route(
+            "/api/public/tests/:token/submit",
+            post(recruitment_backend::routes::public::submit_test),
+        )
+        .route(
+            "/api/public/tests/:token/result",
+            get(recruitment_backend::routes::public::get_result),
+        )
+        .with_state(AppState::new(pool.clone()))
+}
+
+fn mcq(id: i32) -> Value {
+    json!({
+        "id": id,
+        "type": "multiple_choice",
+        "question": format!("Question {}", id),
+        "points": 1,
+        "options": ["a", "b", "c"],
+        "correct_answer": 1
+    })
+}
+
+fn short_answer(id: i32) -> Value {
+    json!({
+        "id": id,
+        "type": "short_answer",
+        "question": "Explain ownership",
+        "points": 2,
+        "expected_keywords": ["borrow"]
+    })
+}
+
+/// A started attempt on a new test; returns the test id and access token.
+async fn started_attempt(
+    pool: &PgPool,
+    questions: Value,
+    show_results: bool,
+    reveal: bool,
+    language: &str,
+) -> (Uuid, String) {
+    let test_id: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes,
+                              show_results_immediately, reveal_correct_answers)
+           VALUES ('Result visibility', $1, 'question_based', 50, 30, $2, $3) RETURNING id"#,
+    )
+    .bind(&questions)
+    .bind(show_results)
+    .bind(reveal)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let token = Uuid::new_v4().simple().to_string();
+    sqlx::query(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, started_at, language)
+           VALUES ($1, 'Result Test', 'result@example.com', $2, NOW() + INTERVAL '1 hour',
+                   $3, 'in_progress', NOW(), $4)"#,
+    )
+    .bind(test_id)
+    .bind(&token)
+    .bind(&questions)
+    .bind(language)
+    .execute(pool)
+    .await
+    .unwrap();
+    (test_id, token)
+}
+
+async fn call(app: &Router, method: &str, uri: String, body: Option<Value>) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned())),
+    )
+}
+
+/// Answers question 1 right and question 2 wrong.
+async fn submit(app: &Router, token: &str) -> Value {
+    let (status, body) = call(
+        app,
+        "POST",
+        format!("/api/public/tests/{}/submit", token),
+        Some(json!({ "answers": [
+            { "question_id": 1, "answer": 1, "time_spent_seconds": 5 },
+            { "question_id": 2, "answer": 0, "time_spent_seconds": 5 },
+            { "question_id": 3, "answer": "Through borrowing", "time_spent_seconds": 5 }
+        ] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body
+}
+
+async fn result(app: &Router, token: &str) -> (StatusCode, Value) {
+    call(app, "GET", format!("/api/public/tests/{}/result", token), None).await
+}
+
+async fn cleanup(pool: &PgPool, test_id: Uuid) {
+    sqlx::query("DELETE FROM webhook_logs WHERE payload->>'attempt_id' IN (SELECT id::text FROM test_attempts WHERE test_id = $1)")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+fn assert_no_scores(body: &Value) {
+    for key in ["score", "max_score", "percentage", "passed", "questions"] {
+        assert!(body.get(key).is_none(), "{} leaked: {}", key, body);
+    }
+}
+
+#[tokio::test]
+async fn hidden_results_stay_opaque_in_the_attempt_language() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+    let questions = json!([mcq(1), mcq(2)]);
+
+    let (ru_test, ru_token) = started_attempt(&pool, questions.clone(), false, true, "ru").await;
+    let body = submit(&app, &ru_token).await;
+    assert_eq!(body["result_state"], "hidden");
+    assert_eq!(body["show_results"], false);
+    assert_eq!(body["message"], "Тест отправлен. Результаты переданы HR.");
+    assert_no_scores(&body);
+    let (status, fetched) = result(&app, &ru_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["result_state"], "hidden");
+    assert_no_scores(&fetched);
+
+    let (tg_test, tg_token) = started_attempt(&pool, questions, false, false, "tg").await;
+    let body = submit(&app, &tg_token).await;
+    assert_eq!(body["message"], "Тест фиристода шуд. Натиҷаҳо ба HR фиристода шуданд.");
+    assert_no_scores(&body);
+
+    cleanup(&pool, ru_test).await;
+    cleanup(&pool, tg_test).await;
+}
+
+#[tokio::test]
+async fn shown_results_hide_correct_answers_unless_revealed() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+    let questions = json!([mcq(1), mcq(2)]);
+
+    let (test_id, token) = started_attempt(&pool, questions.clone(), true, false, "ru").await;
+    let body = submit(&app, &token).await;
+    assert_eq!(body["result_state"], "shown");
+    assert_eq!(body["show_results"], true);
+    assert_eq!(body["score"], 1.0);
+    assert_eq!(body["max_score"], 2.0);
+    assert_eq!(body["percentage"], 50.0);
+    assert_eq!(body["passed"], true);
+    let per_question = body["questions"].as_array().unwrap();
+    assert_eq!(per_question.len(), 2);
+    assert_eq!(per_question[0]["is_correct"], true);
+    assert_eq!(per_question[1]["is_correct"], false);
+    assert!(per_question.iter().all(|q| q.get("correct_answer").is_none()));
+
+    let (status, fetched) = result(&app, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["questions"], body["questions"]);
+    assert_eq!(fetched["percentage"], 50.0);
+    cleanup(&pool, test_id).await;
+
+    let (test_id, token) = started_attempt(&pool, questions, true, true, "ru").await;
+    let body = submit(&app, &token).await;
+    let per_question = body["questions"].as_array().unwrap();
+    assert!(per_question[0].get("correct_answer").is_none(), "right answers need no reveal");
+    assert_eq!(per_question[1]["correct_answer"], "b");
+    let (_, fetched) = result(&app, &token).await;
+    assert_eq!(fetched["questions"][1]["correct_answer"], "b");
+    cleanup(&pool, test_id).await;
+}
+
+#[tokio::test]
+async fn results_wait_for_manual_review() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+    let questions = json!([mcq(1), mcq(2), short_answer(3)]);
+
+    let (test_id, token) = started_attempt(&pool, questions, true, true, "ru").await;
+    let body = submit(&app, &token).await;
+    assert_eq!(body["status"], "needs_review");
+    assert_eq!(body["result_state"], "pending_review");
+    assert_eq!(body["show_results"], false);
+    assert_no_scores(&body);
+
+    let (status, fetched) = result(&app, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["result_state"], "pending_review");
+    assert_no_scores(&fetched);
+
+    // Once HR has graded the open answer the result is released.
+    sqlx::query("UPDATE test_attempts SET status = 'completed' WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, fetched) = result(&app, &token).await;
+    assert_eq!(fetched["result_state"], "shown");
+    assert_eq!(fetched["questions"].as_array().unwrap().len(), 3);
+    cleanup(&pool, test_id).await;
+}
+
+#[tokio::test]
+async fn result_is_refused_before_submission() {
+    let pool = setup_pool().await;
+    let app = app(&pool);
+
+    let (test_id, token) = started_attempt(&pool, json!([mcq(1)]), true, true, "ru").await;
+    let (status, body) = result(&app, &token).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "attempt_not_finished");
+    cleanup(&pool, test_id).await;
+}
@@ -0,0 +1,234 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use recruitment_backend::services::attempt_service::AttemptService;
+use recruitment_backend::services::grading_service::GradingService;
+use recruitment_backend::services::telegram_channel_service::{
+    split_message, TELEGRAM_MESSAGE_LIMIT,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn snapshot() -> Value {
+    json!([
+        {
+            "id": 1,
+            "type": "multiple_choice",
+            "question": "Какой HTTP-метод идемпотентен?",
+            "points": 2,
+            "options": ["POST", "PUT", "PATCH"],
+            "correct_answer": 1,
+            "explanation": "PUT заменяет ресурс целиком, повтор даёт тот же результат."
+        },
+        {
+            "id": 2,
+            "type": "multiple_choice",
+            "question": "Что возвращает   SELECT 1?",
+            "points": 1,
+            "options": ["1", "0"],
+            "correct_answer": 0,
+            "explanation": "Литерал возвращается как есть."
+        },
+        {
+            "id": 3,
+            "type": "short_answer",
+            "question": "Опишите, зачем нужны индексы",
+            "points": 4,
+            "expected_keywords": ["поиск"]
+        }
+    ])
+}
+
+fn graded() -> Vec<Value> {
+    vec![
+        json!({
+            "question_id": 1, "question_text": "Какой HTTP-метод идемпотентен?",
+            "type": "multiple_choice", "candidate_answer": "POST", "correct_answer": "PUT",
+            "points_earned": 0, "max_points": 2, "is_correct": false
+        }),
+        json!({
+            "question_id": 2, "question_text": "Что возвращает   SELECT 1?",
+            "type": "multiple_choice", "candidate_answer": "1", "correct_answer": "1",
+            "points_earned": 1, "max_points": 1, "is_correct": true
+        }),
+        json!({
+            "question_id": 3, "question_text": "Опишите, зачем нужны индексы",
+            "type": "short_answer", "candidate_answer": "Для скорости",
+            "correct_answer": null, "points_earned": 2, "max_points": 4, "is_correct": true,
+            "ai_score": 50, "ai_comment": "Не раскрыта цена записи."
+        }),
+    ]
+}
+
+#[test]
+fn summary_lists_every_question_with_verdict_and_points() {
+    let text = GradingService::results_summary("Backend", &graded(), &snapshot(), false);
+
+    assert!(text.starts_with("Разбор теста \"Backend\"\nИтог: 3 из 7 баллов (42.86%)"));
+    assert!(text.contains("1. Какой HTTP-метод идемпотентен?\n❌ неверно, 0/2"));
+    assert!(text.contains("2. Что возвращает SELECT 1?\n✅ верно, 1/1"));
+    assert!(text.contains(
+        "3. Опишите, зачем нужны индексы\n✅ верно, 2/4\nКомментарий: Не раскрыта цена записи."
+    ));
+    assert!(!text.contains("Правильный ответ"));
+    assert!(!text.contains("Пояснение"));
+}
+
+#[test]
+fn explanations_only_for_wrong_answers_when_requested() {
+    let text = GradingService::results_summary("Backend", &graded(), &snapshot(), true);
+
+    assert!(text.contains(
+        "❌ неверно, 0/2\nПравильный ответ: PUT\nПояснение: PUT заменяет ресурс целиком, повтор даёт тот же результат."
+    ));
+    assert!(!text.contains("Литерал возвращается как есть."));
+}
+
+#[test]
+fn reviewer_comment_wins_over_ai_comment() {
+    let mut graded = graded();
+    graded[2]["reviewer_comment"] = json!("Хороший ответ");
+    let text = GradingService::results_summary("Backend", &graded, &snapshot(), false);
+    assert!(text.contains("Комментарий: Хороший ответ"));
+    assert!(!text.contains("Не раскрыта"));
+}
+
+#[test]
+fn long_summaries_split_on_line_breaks() {
+    let line = "x".repeat(1000);
+    let text = [line.as_str(); 9].join("\n");
+    let chunks = split_message(&text);
+
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks
+        .iter()
+        .all(|c| c.chars().count() <= TELEGRAM_MESSAGE_LIMIT));
+    assert_eq!(chunks.join("\n"), text);
+
+    let single = "я".repeat(TELEGRAM_MESSAGE_LIMIT + 10);
+    let chunks = split_message(&single);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[1].chars().count(), 10);
+
+    assert_eq!(split_message("коротко"), vec!["коротко".to_string()]);
+}
+
+async fn get_summary(app: &Router, attempt_id: Uuid) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/integration/test-attempts/{}/summary",
+                    attempt_id
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn hr_summary_includes_reviewer_comments_and_explanations() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, share_results_details) VALUES ('Summary test', $1, 'question_based', 50, TRUE) RETURNING id",
+    )
+    .bind(snapshot())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let mut pending = graded();
+    pending[2]["points_earned"] = json!(0);
+    pending[2]["is_correct"] = json!(false);
+    pending[2]["needs_review"] = json!(true);
+    pending[2]["ai_comment"] = Value::Null;
+    let seed = |graded: Option<Value>| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, Uuid>(
+                r#"INSERT INTO test_attempts
+                       (test_id, candidate_name, candidate_email, access_token, expires_at,
+                        questions_snapshot, graded_answers, status)
+                   VALUES ($1, 'Summary Candidate', $2, $3, NOW() + INTERVAL '1 day', $4, $5, 'needs_review')
+                   RETURNING id"#,
+            )
+            .bind(test_id)
+            .bind(format!("summary_{}@example.com", Uuid::new_v4()))
+            .bind(Uuid::new_v4().simple().to_string())
+            .bind(snapshot())
+            .bind(graded)
+            .fetch_one(&pool)
+            .await
+            .expect("seed attempt")
+        }
+    };
+    let graded_id = seed(Some(Value::Array(pending))).await;
+    let ungraded_id = seed(None).await;
+
+    let attempt = AttemptService::new(pool.clone())
+        .grade_answer(graded_id, 3, true, Some("  Хороший ответ "))
+        .await
+        .unwrap();
+    assert_eq!(attempt.status, "completed");
+
+    let state = recruitment_backend::AppState::new(pool.clone());
+    let app = Router::new()
+        .route(
+            "/api/integration/test-attempts/:id/summary",
+            get(recruitment_backend::routes::integration::get_test_attempt_summary),
+        )
+        .with_state(state);
+
+    let (status, body) = get_summary(&app, graded_id).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let summary = body["summary"].as_str().unwrap();
+    assert!(summary.contains("Итог: 5 из 7 баллов"));
+    assert!(summary.contains("Комментарий: Хороший ответ"));
+    assert!(summary.contains("Правильный ответ: PUT"));
+
+    let (status, body) = get_summary(&app, ungraded_id).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "attempt_not_graded");
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,229 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{AttemptService, ReviewAssignment};
+use recruitment_backend::services::test_service::TestService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 30,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+async fn seed_review_attempt(pool: &PgPool, test_id: Uuid, completed_minutes_ago: i64) -> Uuid {
+    sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, completed_at)
+           VALUES ($1, 'Review Candidate', $2, $3, NOW() + INTERVAL '1 day', '[]', 'needs_review', $4)
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(format!("review_{}@example.com", Uuid::new_v4()))
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(Utc::now() - Duration::minutes(completed_minutes_ago))
+    .fetch_one(pool)
+    .await
+    .expect("seed attempt")
+}
+
+#[tokio::test]
+async fn reviewers_claim_release_and_expire_attempts() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Review User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("review_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(payload("Review Queue"), creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+    let older = seed_review_attempt(&pool, test.id, 120).await;
+    let newer = seed_review_attempt(&pool, test.id, 5).await;
+
+    let svc = AttemptService::new(pool.clone());
+    let alice = Uuid::new_v4();
+    let bob = Uuid::new_v4();
+
+    // Two reviewers race for the same attempt: exactly one wins.
+    let (a, b) = tokio::join!(
+        svc.claim_for_review(older, DEFAULT_ORGANIZATION_ID, alice),
+        svc.claim_for_review(older, DEFAULT_ORGANIZATION_ID, bob)
+    );
+    let (winner, loser) = match (a, b) {
+        (Ok(attempt), Err(err)) => (attempt, err),
+        (Err(err), Ok(attempt)) => (attempt, err),
+        (a, b) => panic!(
+            "expected exactly one claim to succeed, got {:?} / {:?}",
+            a.map(|x| x.id),
+            b.map(|x| x.id)
+        ),
+    };
+    assert_eq!(loser.code(), "attempt_claimed");
+    let owner = winner.reviewed_by.expect("claimed");
+    let other = if owner == alice { bob } else { alice };
+    assert!(winner.claimed_at.is_some());
+
+    // Re-claiming your own attempt is fine.
+    svc.claim_for_review(older, DEFAULT_ORGANIZATION_ID, owner)
+        .await
+        .unwrap();
+
+    let (mine, _) = svc
+        .list_review_queue(
+            ReviewAssignment::Mine(owner),
+            DEFAULT_ORGANIZATION_ID,
+            1,
+            100,
+        )
+        .await
+        .unwrap();
+    assert_eq!(mine.iter().map(|a| a.id).collect::<Vec<_>>(), vec![older]);
+
+    let (unassigned, _) = svc
+        .list_review_queue(
+            ReviewAssignment::Unassigned,
+            DEFAULT_ORGANIZATION_ID,
+            1,
+            100,
+        )
+        .await
+        .unwrap();
+    assert!(unassigned.iter().any(|a| a.id == newer));
+    assert!(!unassigned.iter().any(|a| a.id == older));
+
+    let (all, _) = svc
+        .list_review_queue(ReviewAssignment::All, DEFAULT_ORGANIZATION_ID, 1, 100)
+        .await
+        .unwrap();
+    let older_pos = all.iter().position(|a| a.id == older).unwrap();
+    let newer_pos = all.iter().position(|a| a.id == newer).unwrap();
+    assert!(older_pos < newer_pos, "oldest submission comes first");
+
+    // Grading someone else's claim needs force.
+    assert_eq!(
+        svc.ensure_can_grade(older, DEFAULT_ORGANIZATION_ID, Some(other), false)
+            .await
+            .unwrap_err()
+            .code(),
+        "attempt_claimed"
+    );
+    assert!(svc
+        .ensure_can_grade(older, DEFAULT_ORGANIZATION_ID, None, false)
+        .await
+        .is_err());
+    svc.ensure_can_grade(older, DEFAULT_ORGANIZATION_ID, Some(other), true)
+        .await
+        .unwrap();
+    svc.ensure_can_grade(older, DEFAULT_ORGANIZATION_ID, Some(owner), false)
+        .await
+        .unwrap();
+    svc.ensure_can_grade(newer, DEFAULT_ORGANIZATION_ID, Some(other), false)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        svc.release_review(older, DEFAULT_ORGANIZATION_ID, other)
+            .await
+            .unwrap_err()
+            .code(),
+        "attempt_claimed"
+    );
+    let released = svc
+        .release_review(older, DEFAULT_ORGANIZATION_ID, owner)
+        .await
+        .unwrap();
+    assert_eq!(released.reviewed_by, None);
+    svc.claim_for_review(older, DEFAULT_ORGANIZATION_ID, other)
+        .await
+        .unwrap();
+
+    // Claims lapse once the TTL passes.
+    svc.release_expired_claims(4, Utc::now()).await.unwrap();
+    assert_eq!(
+        svc.get_attempt_by_id(older).await.unwrap().reviewed_by,
+        Some(other)
+    );
+    let later = Utc::now() + Duration::hours(5);
+    assert!(svc.release_expired_claims(4, later).await.unwrap() >= 1);
+    let expired = svc.get_attempt_by_id(older).await.unwrap();
+    assert_eq!(expired.reviewed_by, None);
+    assert_eq!(expired.claimed_at, None);
+
+    sqlx::query("UPDATE test_attempts SET status = 'completed' WHERE id = $1")
+        .bind(newer)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        svc.claim_for_review(newer, DEFAULT_ORGANIZATION_ID, owner)
+            .await
+            .unwrap_err()
+            .code(),
+        "attempt_not_in_review"
+    );
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tests.delete_test(test.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,141 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use recruitment_backend::middleware::signed_upload::require_signed_upload;
+use recruitment_backend::utils::signed_url::{
+    sign_token, sign_url_until, signed_download_url, signed_upload_url, storage_path, CV_LINK_TTL,
+};
+use std::env;
+use tower::ServiceExt;
+
+fn setup() -> std::path::PathBuf {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test");
+    env::set_var("WEBHOOK_SECRET", "test");
+    env::set_var("OPENAI_API_KEY", "test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost");
+    env::set_var("UPLOAD_SIGNING_SECRET", "upload-test-secret");
+    let _ = recruitment_backend::config::init_config();
+
+    let root = env::temp_dir().join(format!("uploads-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("cv")).unwrap();
+    std::fs::create_dir_all(root.join("photos")).unwrap();
+    std::fs::write(root.join("cv/a.pdf"), b"%PDF-1.4 cv").unwrap();
+    std::fs::write(root.join("photos/p.jpg"), b"jpeg").unwrap();
+    root
+}
+
+fn app(root: &std::path::Path) -> Router {
+    Router::new().nest_service(
+        "/uploads",
+        tower::ServiceBuilder::new()
+            .layer(axum::middleware::from_fn(require_signed_upload))
+            .service(tower_http::services::ServeDir::new(root)),
+    )
+}
+
+async fn get(root: &std::path::Path, uri: &str) -> (StatusCode, String) {
+    let resp = app(root)
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, String::from_utf8_lossy(&body).into_owned())
+}
+
+#[tokio::test]
+async fn cv_downloads_require_a_valid_signature() {
+    let root = setup();
+
+    let (status, body) = get(&root, "/uploads/cv/a.pdf").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body.contains("invalid_file_signature"));
+
+    // Stored paths omit the upload root; legacy `uploads/...` values sign the same file.
+    for stored in ["cv/a.pdf", "uploads/cv/a.pdf"] {
+        let link = signed_upload_url(stored, CV_LINK_TTL);
+        assert!(link.starts_with("uploads/cv/a.pdf?expires="));
+        let (status, body) = get(&root, &format!("/{}", link)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "%PDF-1.4 cv");
+    }
+
+    for sneaky in [
+        "/uploads/%63v/a.pdf",
+        "/uploads//cv/a.pdf",
+        "/uploads/./cv/a.pdf",
+    ] {
+        let (status, _) = get(&root, sneaky).await;
+        assert_ne!(status, StatusCode::OK, "{} was served unsigned", sneaky);
+    }
+
+    let link = signed_upload_url("cv/a.pdf", CV_LINK_TTL);
+    let (_, query) = link.split_once('?').unwrap();
+    let (status, _) = get(&root, &format!("/uploads/cv/other.pdf?{}", query)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let secret = recruitment_backend::config::get_config()
+        .upload_signing_secret
+        .as_bytes();
+    let expired = sign_url_until("cv/a.pdf", chrono::Utc::now().timestamp() - 1, secret);
+    let (status, _) = get(&root, &format!("/uploads/{}", expired)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[tokio::test]
+async fn other_uploads_are_served_unsigned() {
+    let root = setup();
+    let (status, body) = get(&root, "/uploads/photos/p.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "jpeg");
+    assert_eq!(storage_path("/uploads/photos/p.jpg"), "photos/p.jpg");
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[tokio::test]
+async fn signed_tokens_download_the_named_file() {
+    let root = setup();
+    env::set_var("UPLOADS_DIR", &root);
+    let app = Router::new().route(
+        "/api/files/signed/:token",
+        axum::routing::get(recruitment_backend::routes::candidate_routes::download_signed_file),
+    );
+    let fetch = |uri: String| {
+        let app = app.clone();
+        async move {
+            let resp = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = resp.status();
+            let content_type = resp
+                .headers()
+                .get("content-type")
+                .map(|v| v.to_str().unwrap().to_string());
+            let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+            (status, content_type, body)
+        }
+    };
+
+    let link = signed_download_url("uploads/cv/a.pdf", CV_LINK_TTL);
+    assert!(link.starts_with("api/files/signed/"));
+    assert!(!link.contains("cv/a.pdf"));
+    let (status, content_type, body) = fetch(format!("/{}", link)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(&body[..], b"%PDF-1.4 cv");
+
+    let expired = sign_token("cv/a.pdf", 1, b"upload-test-secret");
+    let (status, _, _) = fetch(format!("/api/files/signed/{}", expired)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let missing = signed_download_url("cv/missing.pdf", CV_LINK_TTL);
+    let (status, _, _) = fetch(format!("/{}", missing)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
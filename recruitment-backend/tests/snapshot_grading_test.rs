@@ -0,0 +1,156 @@
+use std::env;
+
+use recruitment_backend::dto::integration_dto::{CreateTestPayload, UpdateTestPayload};
+use recruitment_backend::dto::public_dto::{SaveAnswerRequest, SubmitTestRequest};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    grading_questions, snapshot_hash, with_snapshot_hash, AttemptService, InviteCandidate,
+    InviteSource,
+};
+use recruitment_backend::services::test_service::TestService;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn mcq_questions(correct_answer: i32) -> Value {
+    json!([
+        { "type": "multiple_choice", "question": "Q1", "points": 5,
+          "options": ["A", "B", "C"], "correct_answer": correct_answer },
+        { "type": "multiple_choice", "question": "Q2", "points": 5,
+          "options": ["A", "B", "C"], "correct_answer": correct_answer }
+    ])
+}
+
+#[test]
+fn legacy_attempts_fall_back_to_the_live_test() {
+    let live = json!([{ "id": 1, "type": "multiple_choice", "question": "Live", "points": 1,
+                        "options": ["A", "B"], "correct_answer": 1, "explanation": null }]);
+    let mut snapshot = live.clone();
+    snapshot[0]["question"] = json!("Snapshot");
+
+    assert_eq!(grading_questions(&snapshot, &live)[0].question, "Snapshot");
+    assert_eq!(grading_questions(&json!([]), &live)[0].question, "Live");
+    assert_eq!(grading_questions(&Value::Null, &live)[0].question, "Live");
+}
+
+#[test]
+fn snapshot_hash_is_added_to_metadata() {
+    let snapshot = json!([{ "b": 1, "a": 2 }]);
+    let hash = snapshot_hash(&snapshot);
+    assert_eq!(hash.len(), 64);
+    assert_eq!(hash, snapshot_hash(&json!([{ "a": 2, "b": 1 }])));
+
+    let metadata = with_snapshot_hash(Some(json!({ "vacancy_id": 7 })), &snapshot);
+    assert_eq!(metadata["vacancy_id"], 7);
+    assert_eq!(metadata["snapshot_hash"], hash);
+    assert_eq!(with_snapshot_hash(None, &snapshot)["snapshot_hash"], hash);
+}
+
+#[tokio::test]
+async fn submit_grades_against_the_snapshot_after_test_edit() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Snapshot User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("snapshot_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Snapshot Grading",
+        "questions": mcq_questions(0),
+        "duration_minutes": 10,
+        "passing_score": 60.0,
+    }))
+    .unwrap();
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+
+    let attempts = AttemptService::new(pool.clone());
+    let invite = attempts
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Snapshot Candidate".into(),
+                email: format!("snapshot_{}@example.com", Uuid::new_v4()),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .expect("invite");
+    let invited = attempts.get_attempt_by_id(invite.attempt_id).await.unwrap();
+    assert_eq!(
+        invited.metadata.unwrap()["snapshot_hash"],
+        snapshot_hash(&invited.questions_snapshot)
+    );
+
+    let edit: UpdateTestPayload =
+        serde_json::from_value(json!({ "questions": mcq_questions(1) })).unwrap();
+    tests.update_test(test.id, DEFAULT_ORGANIZATION_ID, edit).await.expect("update test");
+
+    let answers = invited
+        .questions_snapshot
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|q| SaveAnswerRequest {
+            question_id: q["id"].as_i64().unwrap() as i32,
+            answer: json!(0),
+            time_spent_seconds: 5,
+            marked_for_review: None,
+        })
+        .collect();
+    attempts
+        .start_attempt_by_token(&invite.access_token, false, None, None)
+        .await
+        .expect("start");
+    let (submitted, score, max_score, percentage, passed) = attempts
+        .submit_attempt_by_token(
+            &invite.access_token,
+            SubmitTestRequest {
+                answers,
+                status: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .expect("submit");
+    assert_eq!((score, max_score), (10.0, 10.0));
+    assert_eq!(percentage, 100.0);
+    assert!(passed);
+    assert_eq!(submitted.percentage, Some(Decimal::from(100)));
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
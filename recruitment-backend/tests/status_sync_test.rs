@@ -0,0 +1,181 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use recruitment_backend::dto::integration_dto::{CandidateStatusSyncQuery, CreateTestPayload};
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::candidate_service::CandidateService;
+use recruitment_backend::services::test_service::TestService;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn seed_candidate_with_attempt(pool: &PgPool, test_id: Uuid) -> (Uuid, Uuid) {
+    let candidate_id = Uuid::new_v4();
+    let email = format!("sync_{}@example.com", candidate_id);
+    let day_ago = Utc::now() - Duration::days(1);
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, created_at, updated_at) VALUES ($1, 'Sync Candidate', $2, $3, $3)",
+    )
+    .bind(candidate_id)
+    .bind(&email)
+    .bind(day_ago)
+    .execute(pool)
+    .await
+    .expect("seed candidate");
+    let attempt_id = sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, created_at, updated_at)
+           VALUES ($1, 'Sync Candidate', $2, $3, NOW() + INTERVAL '1 day', '[]', 'pending', $4, $4)
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(&email)
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(day_ago)
+    .fetch_one(pool)
+    .await
+    .expect("seed attempt");
+    (candidate_id, attempt_id)
+}
+
+#[test]
+fn page_size_is_clamped() {
+    let query = CandidateStatusSyncQuery::default();
+    assert_eq!((query.page(), query.per_page()), (1, 100));
+    let query = CandidateStatusSyncQuery {
+        updated_since: None,
+        page: Some(0),
+        per_page: Some(10_000),
+    };
+    assert_eq!((query.page(), query.per_page()), (1, 500));
+}
+
+#[tokio::test]
+async fn returns_only_candidates_changed_after_cursor() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Sync User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("sync_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Status Sync",
+        "duration_minutes": 10,
+        "passing_score": 50.0,
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+
+    let (touched, touched_attempt) = seed_candidate_with_attempt(&pool, test.id).await;
+    let (untouched, _) = seed_candidate_with_attempt(&pool, test.id).await;
+    let cursor = Utc::now() - Duration::hours(1);
+
+    sqlx::query(
+        "UPDATE test_attempts SET status = 'completed', percentage = 80, passed = TRUE WHERE id = $1",
+    )
+    .bind(touched_attempt)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let svc = CandidateService::new(pool.clone());
+    let page = svc
+        .sync_statuses(
+            &CandidateStatusSyncQuery {
+                updated_since: Some(cursor),
+                page: Some(1),
+                per_page: Some(500),
+            },
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    let row = page
+        .items
+        .iter()
+        .find(|row| row.id == touched)
+        .expect("touched candidate is synced");
+    assert_eq!(row.status, "completed");
+    assert_eq!(row.latest_attempt_id, Some(touched_attempt));
+    assert_eq!(row.percentage, Some(80.0));
+    assert_eq!(row.passed, Some(true));
+    assert!(row.last_updated > cursor);
+    assert!(page.items.iter().all(|row| row.id != untouched));
+    assert!(page.next_cursor.unwrap() >= row.last_updated);
+
+    let later = svc
+        .sync_statuses(
+            &CandidateStatusSyncQuery {
+                updated_since: page.next_cursor,
+                page: Some(1),
+                per_page: Some(500),
+            },
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    assert!(later.items.iter().all(|row| row.id != touched));
+
+    let first = svc
+        .sync_statuses(
+            &CandidateStatusSyncQuery {
+                updated_since: None,
+                page: Some(1),
+                per_page: Some(1),
+            },
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.items.len(), 1);
+    assert!(first.total >= 2);
+    assert_eq!(first.total_pages, first.total);
+    let past_end = svc
+        .sync_statuses(
+            &CandidateStatusSyncQuery {
+                updated_since: None,
+                page: Some(first.total + 1),
+                per_page: Some(1),
+            },
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await
+        .unwrap();
+    assert!(past_end.items.is_empty());
+    assert_eq!(past_end.total, first.total);
+
+    sqlx::query("DELETE FROM candidates WHERE id = ANY($1)")
+        .bind(vec![touched, untouched])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,147 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use recruitment_backend::services::status_pipeline_service::{
+    StatusPipelineService as Workflow, STATUS_WORKFLOW,
+};
+use serde_json::{json, Value as JsonValue};
+
+const LEGAL: &[(&str, &str)] = &[
+    ("new", "reviewing"),
+    ("reviewing", "test_assigned"),
+    ("test_assigned", "test_completed"),
+    ("test_completed", "interview"),
+    ("interview", "accepted"),
+    ("new", "rejected"),
+    ("reviewing", "rejected"),
+    ("test_assigned", "rejected"),
+    ("test_completed", "rejected"),
+    ("interview", "rejected"),
+    ("accepted", "rejected"),
+];
+
+async fn error_body(err: recruitment_backend::error::Error) -> (StatusCode, JsonValue) {
+    let resp = err.into_response();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), 64 * 1024)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[test]
+fn transition_matrix_matches_the_workflow() {
+    let statuses: Vec<&str> = Workflow::statuses().collect();
+    assert_eq!(
+        statuses,
+        [
+            "new",
+            "reviewing",
+            "test_assigned",
+            "test_completed",
+            "interview",
+            "accepted",
+            "rejected"
+        ]
+    );
+
+    for from in &statuses {
+        for to in &statuses {
+            let expected = from == to || LEGAL.contains(&(*from, *to));
+            assert_eq!(
+                Workflow::can_transition(from, to),
+                expected,
+                "{} -> {}",
+                from,
+                to
+            );
+            assert_eq!(
+                Workflow::validate_transition(from, to).is_ok(),
+                expected,
+                "{} -> {}",
+                from,
+                to
+            );
+        }
+    }
+}
+
+#[test]
+fn rejected_is_terminal_and_reachable_from_everywhere() {
+    assert!(Workflow::next_statuses("rejected").is_empty());
+    for status in Workflow::statuses() {
+        assert!(Workflow::is_reachable(status, "rejected"), "{}", status);
+    }
+    assert!(!Workflow::is_reachable("rejected", "new"));
+}
+
+#[test]
+fn previous_statuses_are_the_direct_sources() {
+    for to in Workflow::statuses() {
+        let expected: Vec<&str> = LEGAL
+            .iter()
+            .filter(|(_, t)| *t == to)
+            .map(|(f, _)| *f)
+            .collect();
+        let mut actual = Workflow::previous_statuses(to);
+        actual.sort_unstable();
+        let mut expected = expected;
+        expected.sort_unstable();
+        assert_eq!(actual, expected, "{}", to);
+    }
+    assert!(Workflow::previous_statuses("new").is_empty());
+}
+
+#[test]
+fn system_transitions_may_skip_stages_forward_only() {
+    assert!(Workflow::is_reachable("new", "test_assigned"));
+    assert!(Workflow::is_reachable("reviewing", "test_completed"));
+    assert!(!Workflow::is_reachable("interview", "test_assigned"));
+    assert!(!Workflow::is_reachable("accepted", "test_completed"));
+}
+
+#[tokio::test]
+async fn illegal_transition_is_422_with_next_statuses() {
+    let (status, body) =
+        error_body(Workflow::validate_transition("reviewing", "interview").unwrap_err()).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "invalid_status_transition");
+    assert_eq!(
+        body["error"]["details"]["allowed_next_statuses"],
+        json!(["test_assigned", "rejected"])
+    );
+}
+
+#[tokio::test]
+async fn typo_is_rejected_even_with_override() {
+    let (status, body) =
+        error_body(Workflow::validate_transition("interview", "acceptd").unwrap_err()).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "unknown_candidate_status");
+    assert_eq!(
+        body["error"]["details"]["allowed_next_statuses"],
+        json!(["accepted", "rejected"])
+    );
+
+    assert!(Workflow::validate_status("accepted").is_ok());
+    let (status, body) = error_body(Workflow::validate_status("acceptd").unwrap_err()).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["error"]["details"]["known_statuses"]
+            .as_array()
+            .unwrap()
+            .len(),
+        7
+    );
+}
+
+#[test]
+fn dictionary_is_built_from_the_workflow() {
+    let dictionary = serde_json::to_value(STATUS_WORKFLOW).unwrap();
+    assert_eq!(dictionary.as_array().unwrap().len(), 7);
+    assert_eq!(
+        dictionary[0],
+        json!({ "id": "new", "label": "New", "allowed_next_statuses": ["reviewing", "rejected"] })
+    );
+    assert_eq!(dictionary[6]["id"], "rejected");
+    assert_eq!(dictionary[6]["allowed_next_statuses"], json!([]));
+}
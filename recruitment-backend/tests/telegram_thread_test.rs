@@ -0,0 +1,77 @@
+use std::env;
+
+use recruitment_backend::dto::webhook_dto::WebhookCandidate;
+use recruitment_backend::routes::telegram::with_message_thread;
+use recruitment_backend::services::candidate_service::CandidateService;
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn thread_id_is_added_only_when_set() {
+    let body = json!({ "chat_id": 42, "text": "hi" });
+    assert_eq!(with_message_thread(body.clone(), None), body);
+    let threaded = with_message_thread(body, Some(7));
+    assert_eq!(threaded["message_thread_id"], 7);
+    assert_eq!(threaded["chat_id"], 42);
+
+    let candidate = WebhookCandidate {
+        name: "A".into(),
+        telegram_id: Some(42),
+        telegram_thread_id: None,
+    };
+    assert!(serde_json::to_value(&candidate)
+        .unwrap()
+        .get("telegram_thread_id")
+        .is_none());
+}
+
+#[tokio::test]
+async fn thread_id_is_stored_and_looked_up_by_chat() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let id = Uuid::new_v4();
+    let telegram_id = (id.as_u128() % 1_000_000_000_000) as i64 + 1;
+    sqlx::query(
+        "INSERT INTO candidates (id, telegram_id, name, email) VALUES ($1, $2, 'Thread Candidate', $3)",
+    )
+    .bind(id)
+    .bind(telegram_id)
+    .bind(format!("thread_{}@example.com", id))
+    .execute(&pool)
+    .await
+    .expect("seed candidate");
+
+    let svc = CandidateService::new(pool.clone());
+    assert_eq!(svc.telegram_thread_id(Some(telegram_id)).await, None);
+
+    let updated = svc.set_telegram_thread_id(id, Some(55)).await.unwrap();
+    assert_eq!(updated.telegram_thread_id, Some(55));
+    assert_eq!(svc.telegram_thread_id(Some(telegram_id)).await, Some(55));
+    assert_eq!(svc.telegram_thread_id(None).await, None);
+
+    let cleared = svc.set_telegram_thread_id(id, None).await.unwrap();
+    assert_eq!(cleared.telegram_thread_id, None);
+    assert!(svc
+        .set_telegram_thread_id(Uuid::new_v4(), Some(1))
+        .await
+        .is_err());
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,201 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use recruitment_backend::error::Error;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::candidate_service::CandidateService;
+use recruitment_backend::services::telegram_channel_service::{
+    is_blocked_by_user, TelegramChannelService,
+};
+use recruitment_backend::services::telegram_delivery_service::{
+    TelegramDeliveryService, UNREACHABLE_AFTER_FAILURES,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+type Calls = Arc<Mutex<Vec<Value>>>;
+type Blocked = Arc<Mutex<bool>>;
+
+#[test]
+fn only_403_from_telegram_counts_as_blocked() {
+    let blocked = Error::coded(StatusCode::BAD_GATEWAY, "telegram_error", "Forbidden")
+        .with_details(json!({ "method": "sendMessage", "error_code": 403 }));
+    assert!(is_blocked_by_user(&blocked));
+
+    let rate_limited = Error::coded(StatusCode::BAD_GATEWAY, "telegram_error", "Too Many")
+        .with_details(json!({ "method": "sendMessage", "error_code": 429 }));
+    assert!(!is_blocked_by_user(&rate_limited));
+    let unreachable = Error::coded(StatusCode::BAD_GATEWAY, "telegram_error", "timeout")
+        .with_details(json!({ "method": "sendMessage", "error_code": null }));
+    assert!(!is_blocked_by_user(&unreachable));
+    let other = Error::coded(StatusCode::FORBIDDEN, "forbidden", "Forbidden")
+        .with_details(json!({ "error_code": 403 }));
+    assert!(!is_blocked_by_user(&other));
+}
+
+/// Fake Bot API that answers 403 while `blocked` is set.
+async fn bot_api(
+    State((calls, blocked)): State<(Calls, Blocked)>,
+    Path((_bot, _method)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> impl IntoResponse {
+    calls.lock().unwrap().push(body);
+    if *blocked.lock().unwrap() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "ok": false,
+                "error_code": 403,
+                "description": "Forbidden: bot was blocked by the user"
+            })),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(json!({ "ok": true, "result": { "message_id": 1 } })),
+    )
+}
+
+async fn mock_telegram() -> (TelegramChannelService, Calls, Blocked) {
+    let calls: Calls = Arc::default();
+    let blocked: Blocked = Arc::new(Mutex::new(true));
+    let app = Router::new()
+        .route("/:bot/:method", post(bot_api))
+        .with_state((calls.clone(), blocked.clone()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (
+        TelegramChannelService::new(format!("http://{}", addr), "123:abc"),
+        calls,
+        blocked,
+    )
+}
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+async fn failures(pool: &PgPool, id: Uuid) -> (i32, bool) {
+    sqlx::query_as(
+        "SELECT telegram_send_failures, telegram_unreachable FROM candidates WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn repeated_blocks_pause_messages_until_the_candidate_writes_again() {
+    let pool = setup_pool().await;
+    let telegram_id = (Uuid::new_v4().as_u128() % 1_000_000_000_000) as i64 + 1_000;
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO candidates (id, telegram_id, name, email, status)
+           VALUES ($1, $2, 'Blocked Candidate', $3, 'new')"#,
+    )
+    .bind(id)
+    .bind(telegram_id)
+    .bind(format!("blocked_{}@example.com", id))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (telegram, calls, blocked) = mock_telegram().await;
+    let delivery = TelegramDeliveryService::new(pool.clone(), telegram);
+    let candidates = CandidateService::new(pool.clone());
+
+    for attempt in 1..UNREACHABLE_AFTER_FAILURES {
+        let err = delivery
+            .send(telegram_id, None, "Hi", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "telegram_error");
+        assert_eq!(failures(&pool, id).await, (attempt, false));
+    }
+    let err = delivery
+        .send(telegram_id, None, "Hi", None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "telegram_unreachable");
+    assert_eq!(
+        failures(&pool, id).await,
+        (UNREACHABLE_AFTER_FAILURES, true)
+    );
+    let candidate = candidates.get_candidate(id).await.unwrap().unwrap();
+    assert!(candidate.telegram_unreachable);
+    assert!(
+        candidates
+            .count_telegram_unreachable(DEFAULT_ORGANIZATION_ID)
+            .await
+            .unwrap()
+            >= 1
+    );
+
+    let sent_before = calls.lock().unwrap().len();
+    let err = delivery
+        .send(telegram_id, None, "Hi", None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "telegram_unreachable");
+    assert_eq!(
+        calls.lock().unwrap().len(),
+        sent_before,
+        "Telegram was called"
+    );
+
+    candidates
+        .mark_telegram_reachable(telegram_id)
+        .await
+        .unwrap();
+    assert_eq!(failures(&pool, id).await, (0, false));
+
+    delivery
+        .send(telegram_id, None, "Hi", None)
+        .await
+        .unwrap_err();
+    assert_eq!(failures(&pool, id).await, (1, false));
+    *blocked.lock().unwrap() = false;
+    delivery
+        .send(
+            telegram_id,
+            None,
+            "Welcome back",
+            Some(json!({ "inline_keyboard": [] })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(failures(&pool, id).await, (0, false));
+    let last = calls.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(last["text"], "Welcome back");
+    assert_eq!(last["reply_markup"], json!({ "inline_keyboard": [] }));
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,177 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::post,
+    Router,
+};
+use recruitment_backend::services::test_service::{parse_test_spec, TestSpecFormat};
+use serde_json::Value as JsonValue;
+use tower::ServiceExt;
+
+const YAML_SPEC: &str = r#"
+title: Warehouse Operator Basics
+external_id: wh-basics
+duration_minutes: 20
+passing_score: 60
+questions:
+  - type: multiple_choice
+    question: Какой документ сопровождает отгрузку?
+    points: 2
+    difficulty: easy
+    options: [Накладная, Счёт, Договор]
+    correct_answer: 0
+    explanation: Накладная сопровождает товар
+  - type: short_answer
+    question: Опишите порядок инвентаризации
+    points: 3
+    min_words: 30
+    expected_keywords: [пересчёт, акт]
+"#;
+
+#[test]
+fn detects_format_from_extension_then_content_type() {
+    let detect = TestSpecFormat::detect;
+    assert_eq!(detect(Some("spec.YML"), None), Some(TestSpecFormat::Yaml));
+    assert_eq!(
+        detect(Some("spec.json"), Some("text/yaml")),
+        Some(TestSpecFormat::Json)
+    );
+    assert_eq!(
+        detect(Some("export"), Some("application/x-yaml; charset=utf-8")),
+        Some(TestSpecFormat::Yaml)
+    );
+    assert_eq!(
+        detect(None, Some("application/json")),
+        Some(TestSpecFormat::Json)
+    );
+    assert_eq!(detect(Some("spec.txt"), Some("text/plain")), None);
+}
+
+#[test]
+fn parses_yaml_and_json_into_the_create_payload() {
+    let payload = parse_test_spec(YAML_SPEC.as_bytes(), TestSpecFormat::Yaml).unwrap();
+    assert_eq!(payload.title, "Warehouse Operator Basics");
+    assert_eq!(payload.passing_score, 60.0);
+    let questions = payload.questions.unwrap();
+    assert_eq!(questions.len(), 2);
+    assert_eq!(questions[0].points, 2);
+
+    let json = serde_json::to_vec(&serde_json::json!({
+        "title": "JSON Spec",
+        "duration_minutes": 10,
+        "passing_score": 50.0
+    }))
+    .unwrap();
+    let payload = parse_test_spec(&json, TestSpecFormat::Json).unwrap();
+    assert_eq!(payload.title, "JSON Spec");
+    assert!(payload.questions.is_none());
+}
+
+#[test]
+fn syntax_errors_report_their_position() {
+    let err = parse_test_spec(
+        b"title: [unclosed\nduration_minutes: 5",
+        TestSpecFormat::Yaml,
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "invalid_test_spec");
+
+    let err = parse_test_spec(b"{\n  \"title\": \"x\",\n}", TestSpecFormat::Json).unwrap_err();
+    assert_eq!(err.code(), "invalid_test_spec");
+    let resp = axum::response::IntoResponse::into_response(err);
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+fn multipart(filename: &str, content_type: &str, data: &str) -> Request<Body> {
+    let boundary = "spec-boundary";
+    let body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{f}\"\r\nContent-Type: {ct}\r\n\r\n{d}\r\n--{b}--\r\n",
+        b = boundary,
+        f = filename,
+        ct = content_type,
+        d = data
+    );
+    Request::builder()
+        .method("POST")
+        .uri("/api/integration/tests/import")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+async fn call(app: &Router, req: Request<Body>) -> (StatusCode, JsonValue) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn import_endpoint_creates_or_reports_errors() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/tests/import",
+            post(recruitment_backend::routes::integration::import_test),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let (status, body) = call(
+        &app,
+        multipart("basics.yaml", "application/octet-stream", YAML_SPEC),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    assert_eq!(body["title"], "Warehouse Operator Basics");
+    assert_eq!(body["questions"].as_array().unwrap().len(), 2);
+    assert_eq!(body["questions"][1]["id"], 2);
+    let id: uuid::Uuid = serde_json::from_value(body["id"].clone()).unwrap();
+
+    let invalid =
+        "title: ''\nduration_minutes: 5\npassing_score: 50\ndefault_invite_expiry_hours: 0\n";
+    let (status, body) = call(&app, multipart("bad.yml", "text/yaml", invalid)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "validation_failed");
+    let fields = &body["error"]["details"]["fields"];
+    assert!(fields.get("title").is_some(), "{}", body);
+    assert!(fields.get("default_invite_expiry_hours").is_some());
+
+    let (status, body) = call(
+        &app,
+        multipart("broken.json", "application/json", "{\"title\": "),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "invalid_test_spec");
+    assert_eq!(body["error"]["details"]["format"], "json");
+    assert!(body["error"]["details"]["line"].is_number());
+
+    let (status, body) = call(&app, multipart("spec.txt", "text/plain", YAML_SPEC)).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(body["error"]["code"], "unsupported_test_spec_format");
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,222 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{delete, get, post},
+    Router,
+};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::middleware::auth::mint_token;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::test_service::{TestFilter, TestService};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 30,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: None,
+        reminder_hours_before: None,
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+async fn seed_user(pool: &PgPool, role: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Owner Test User', $3, $4, TRUE)"#,
+    )
+    .bind(id)
+    .bind(format!("ext-{}", id))
+    .bind(format!("owner_{}@example.com", id))
+    .bind(role)
+    .execute(pool)
+    .await
+    .expect("seed user");
+    id
+}
+
+fn search(title: &str) -> Option<TestFilter> {
+    Some(TestFilter {
+        is_active: None,
+        created_by: None,
+        search: Some(title.to_string()),
+    })
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", format!("Bearer {}", token));
+    let body = match body {
+        Some(body) => {
+            req = req.header("content-type", "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let resp = app.clone().oneshot(req.body(body).unwrap()).await.unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn hr_users_only_list_tests_they_own() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let owner = seed_user(&pool, "hr").await;
+    let other = seed_user(&pool, "hr").await;
+    let admin = seed_user(&pool, "admin").await;
+    let title = format!("Owned test {}", Uuid::new_v4());
+
+    let service = TestService::new(pool.clone());
+    let test = service
+        .create_test(payload(&title), owner, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(service.list_owners(test.id).await.unwrap(), vec![owner]);
+
+    let visible = |caller: Option<Uuid>| {
+        let service = service.clone();
+        let title = title.clone();
+        async move {
+            service
+                .list_tests(1, 10, search(&title), caller, DEFAULT_ORGANIZATION_ID)
+                .await
+                .unwrap()
+                .total
+        }
+    };
+    assert_eq!(visible(Some(owner)).await, 1);
+    assert_eq!(visible(Some(other)).await, 0);
+    assert_eq!(visible(None).await, 1);
+
+    let err = service
+        .add_owner(test.id, Uuid::new_v4())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "user_not_found");
+    let err = service.add_owner(Uuid::new_v4(), other).await.unwrap_err();
+    assert_eq!(err.code(), "not_found");
+
+    let state = recruitment_backend::AppState::new(pool.clone());
+    let app = Router::new()
+        .route(
+            "/api/integration/tests",
+            get(recruitment_backend::routes::integration::list_tests),
+        )
+        .route(
+            "/api/integration/tests/:id/owners",
+            post(recruitment_backend::routes::integration::add_test_owner),
+        )
+        .route(
+            "/api/integration/tests/:id/owners/:user_id",
+            delete(recruitment_backend::routes::integration::remove_test_owner),
+        )
+        .with_state(state);
+    let token = |id: Uuid, role: &str| {
+        mint_token(&id.to_string(), role, DEFAULT_ORGANIZATION_ID, 1).unwrap()
+    };
+    let list_uri = format!(
+        "/api/integration/tests?search={}",
+        title.replace(' ', "%20")
+    );
+    let owners_uri = format!("/api/integration/tests/{}/owners", test.id);
+
+    let (status, body) = send(&app, "GET", &list_uri, &token(other, "hr"), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 0);
+    let (_, body) = send(&app, "GET", &list_uri, &token(admin, "admin"), None).await;
+    assert_eq!(body["total"], 1);
+
+    // Someone without access can't grant it to themselves.
+    let (status, body) = send(
+        &app,
+        "POST",
+        &owners_uri,
+        &token(other, "hr"),
+        Some(json!({ "user_id": other })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "not_test_owner");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &owners_uri,
+        &token(owner, "hr"),
+        Some(json!({ "user_id": other })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    assert_eq!(body["owners"], json!([owner, other]));
+    let (_, body) = send(&app, "GET", &list_uri, &token(other, "hr"), None).await;
+    assert_eq!(body["total"], 1);
+
+    let remove_uri = format!("{}/{}", owners_uri, other);
+    let (status, _) = send(&app, "DELETE", &remove_uri, &token(admin, "admin"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = send(&app, "DELETE", &remove_uri, &token(admin, "admin"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "test_owner_not_found");
+    assert_eq!(visible(Some(other)).await, 0);
+
+    service.delete_test(test.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = ANY($1)")
+        .bind(vec![owner, other, admin])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
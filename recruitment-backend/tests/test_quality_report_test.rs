@@ -0,0 +1,183 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::ai_service::{normalize_test_critique, TestCritique};
+use recruitment_backend::services::test_quality_service::{
+    low_quality_digest, CritiquedTest, TestQualityService,
+};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::{json, Value as JsonValue};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn critique(score: i32, diagnosis: &str) -> TestCritique {
+    TestCritique {
+        score,
+        diagnosis: diagnosis.to_string(),
+        estimated_difficulty: "easy".to_string(),
+        coverage_gaps: vec![],
+        suggested_improvements: vec!["Добавить вопросы по складскому учёту".to_string()],
+    }
+}
+
+#[test]
+fn model_critique_is_normalised() {
+    let raw = json!({
+        "score": 140.4,
+        "diagnosis": "  Вопросы 2 и 3 дублируют друг друга. ",
+        "estimated_difficulty": "Very hard",
+        "coverage_gaps": ["Excel", "excel", " ", 7, "1C"],
+        "suggested_improvements": (0..15).map(|i| format!("Правка {}", i)).collect::<Vec<_>>(),
+    });
+    let critique = normalize_test_critique(&raw).unwrap();
+    assert_eq!(critique.score, 100);
+    assert_eq!(critique.diagnosis, "Вопросы 2 и 3 дублируют друг друга.");
+    assert_eq!(critique.estimated_difficulty, "medium");
+    assert_eq!(critique.coverage_gaps, vec!["Excel", "1C"]);
+    assert_eq!(critique.suggested_improvements.len(), 10);
+
+    assert!(normalize_test_critique(&json!({ "score": 50, "diagnosis": "" })).is_none());
+    assert!(normalize_test_critique(&json!({ "score": 50 })).is_none());
+}
+
+#[test]
+fn digest_lists_only_low_scoring_tests_worst_first() {
+    let tests = vec![
+        CritiquedTest {
+            test_id: Uuid::new_v4(),
+            title: "Бухгалтер".into(),
+            critique: critique(55, "Мало практических задач."),
+        },
+        CritiquedTest {
+            test_id: Uuid::new_v4(),
+            title: "Кладовщик".into(),
+            critique: critique(90, "Хороший тест."),
+        },
+        CritiquedTest {
+            test_id: Uuid::new_v4(),
+            title: "Кассир".into(),
+            critique: critique(20, "Вопросы не по профессии."),
+        },
+    ];
+    let text = low_quality_digest(&tests).unwrap();
+    assert!(text.contains("(2)"));
+    assert!(!text.contains("Кладовщик"));
+    assert!(text.find("Кассир").unwrap() < text.find("Бухгалтер").unwrap());
+    assert!(text.contains("Добавить вопросы по складскому учёту"));
+
+    assert!(low_quality_digest(&tests[1..2]).is_none());
+    assert!(low_quality_digest(&[]).is_none());
+}
+
+async fn call(app: &Router, method: &str, uri: &str) -> (StatusCode, JsonValue) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn latest_report_endpoint_returns_the_newest_critique() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Quality User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("quality_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Quality Test",
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [],
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/integration/tests/:id/quality-report",
+            post(recruitment_backend::routes::integration::generate_quality_report),
+        )
+        .route(
+            "/api/integration/tests/:id/quality-report/latest",
+            get(recruitment_backend::routes::integration::get_latest_quality_report),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let latest = format!("/api/integration/tests/{}/quality-report/latest", test.id);
+
+    let (status, body) = call(&app, "GET", &latest).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "quality_report_not_found");
+
+    let (status, body) = call(
+        &app,
+        "POST",
+        &format!("/api/integration/tests/{}/quality-report", test.id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "no_questions");
+
+    let service = TestQualityService::new(pool.clone());
+    service
+        .store(test.id, &critique(40, "Старый отчёт."))
+        .await
+        .unwrap();
+    let newest = service
+        .store(test.id, &critique(75, "Новый отчёт."))
+        .await
+        .unwrap();
+
+    let (status, body) = call(&app, "GET", &latest).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], json!(newest.id));
+    assert_eq!(body["report"]["score"], 75);
+    assert_eq!(body["report"]["diagnosis"], "Новый отчёт.");
+    assert!(body["report"]["coverage_gaps"].is_array());
+
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
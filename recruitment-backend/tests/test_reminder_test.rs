@@ -0,0 +1,228 @@
+use std::env;
+
+use chrono::{Duration, TimeZone, Utc};
+use recruitment_backend::dto::integration_dto::{CreateTestPayload, UpdateTestPayload};
+use recruitment_backend::error::Error;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    due_reminder_offsets, hours_until, reminder_message, AttemptService,
+};
+use recruitment_backend::services::notification_service::NotificationService;
+use recruitment_backend::services::test_service::{normalize_reminder_hours, TestService};
+use uuid::Uuid;
+
+#[test]
+fn reminder_offsets_are_validated_and_sorted() {
+    assert_eq!(
+        normalize_reminder_hours(&[2, 24, 2, 6]).unwrap(),
+        vec![24, 6, 2]
+    );
+    assert!(normalize_reminder_hours(&[]).unwrap().is_empty());
+    assert!(matches!(
+        normalize_reminder_hours(&[24, 0]),
+        Err(Error::BadRequest(_))
+    ));
+}
+
+#[test]
+fn due_offsets_skip_sent_and_future_reminders() {
+    let expires_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    let offsets = [24, 6, 1];
+
+    let day_before = expires_at - Duration::hours(30);
+    assert!(due_reminder_offsets(&offsets, &[], expires_at, day_before).is_empty());
+
+    let five_hours_left = expires_at - Duration::hours(5);
+    assert_eq!(
+        due_reminder_offsets(&offsets, &[], expires_at, five_hours_left),
+        vec![24, 6]
+    );
+    assert!(due_reminder_offsets(&offsets, &[24, 6], expires_at, five_hours_left).is_empty());
+
+    assert!(due_reminder_offsets(&offsets, &[], expires_at, expires_at).is_empty());
+}
+
+#[test]
+fn reminder_text_rounds_hours_up() {
+    let expires_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    assert_eq!(
+        hours_until(expires_at, expires_at - Duration::minutes(90)),
+        2
+    );
+    assert_eq!(
+        hours_until(expires_at, expires_at - Duration::seconds(5)),
+        1
+    );
+    assert_eq!(reminder_message(3), "Ваш тест истекает через 3 часов");
+}
+
+fn payload(title: &str) -> CreateTestPayload {
+    CreateTestPayload {
+        title: title.into(),
+        external_id: None,
+        description: None,
+        instructions: None,
+        questions: None,
+        duration_minutes: 30,
+        passing_score: 50.0,
+        shuffle_questions: None,
+        shuffle_options: None,
+        show_results_immediately: None,
+        test_type: None,
+        presentation_themes: None,
+        presentation_extra_info: None,
+        show_honesty_declaration: None,
+        declaration_text: None,
+        prerequisite_test_id: None,
+        default_invite_expiry_hours: Some(72),
+        reminder_hours_before: Some(vec![2, 24, 24]),
+        passing_score_mcq: None,
+        passing_score_open: None,
+        share_results_details: None,
+        max_grace_seconds: None,
+        max_concurrent_takers: None,
+        language: None,
+        reveal_correct_answers: None,
+    }
+}
+
+#[tokio::test]
+async fn reminders_are_sent_once_per_offset() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Reminder User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("reminder_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .expect("seed user");
+
+    let tests = TestService::new(pool.clone());
+    let test = tests
+        .create_test(payload("Reminders"), creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("create test");
+    assert_eq!(test.default_invite_expiry_hours, Some(72));
+    assert_eq!(test.invite_expiry_hours(), 72);
+    assert_eq!(test.reminder_hours_before, vec![24, 2]);
+
+    let unchanged = tests
+        .update_test(
+            test.id,
+            DEFAULT_ORGANIZATION_ID,
+            serde_json::from_value::<UpdateTestPayload>(
+                serde_json::json!({ "title": "Reminders" }),
+            )
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(unchanged.reminder_hours_before, vec![24, 2]);
+
+    let telegram_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 9_000_000_000;
+    let expires_at = Utc::now() + Duration::hours(30);
+    let attempt_id: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, candidate_telegram_id, access_token,
+                expires_at, questions_snapshot, status)
+           VALUES ($1, 'Reminder Candidate', $2, $3, $4, $5, '[]', 'pending')
+           RETURNING id"#,
+    )
+    .bind(test.id)
+    .bind(format!("reminder_{}@example.com", Uuid::new_v4()))
+    .bind(telegram_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(expires_at)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let reminder_logs = || {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, serde_json::Value>(
+                r#"SELECT payload FROM webhook_logs
+                   WHERE event_type = 'test_reminder' AND payload->>'attempt_id' = $1
+                   ORDER BY created_at"#,
+            )
+            .bind(attempt_id.to_string())
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        }
+    };
+
+    let svc = AttemptService::new(pool.clone());
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+
+    // Fake clock: step through the attempt's lifetime.
+    svc.send_due_reminders(&notif, Utc::now()).await.unwrap();
+    assert!(reminder_logs().await.is_empty());
+
+    let at_23h_left = expires_at - Duration::hours(23);
+    svc.send_due_reminders(&notif, at_23h_left).await.unwrap();
+    svc.send_due_reminders(&notif, at_23h_left).await.unwrap();
+    let logs = reminder_logs().await;
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["hours_left"], 23);
+    assert_eq!(logs[0]["message"], "Ваш тест истекает через 23 часов");
+    assert_eq!(logs[0]["candidate"]["telegram_id"], telegram_id);
+
+    let at_90m_left = expires_at - Duration::minutes(90);
+    svc.send_due_reminders(&notif, at_90m_left).await.unwrap();
+    svc.send_due_reminders(&notif, at_90m_left + Duration::minutes(30))
+        .await
+        .unwrap();
+    let logs = reminder_logs().await;
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[1]["hours_left"], 2);
+
+    let sent: serde_json::Value =
+        sqlx::query_scalar("SELECT reminders_sent FROM test_attempts WHERE id = $1")
+            .bind(attempt_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(sent, serde_json::json!([24, 2]));
+
+    svc.send_due_reminders(&notif, expires_at + Duration::minutes(1))
+        .await
+        .unwrap();
+    assert_eq!(reminder_logs().await.len(), 2);
+
+    sqlx::query("DELETE FROM webhook_logs WHERE payload->>'attempt_id' = $1")
+        .bind(attempt_id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM test_attempts WHERE id = $1")
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tests.delete_test(test.id, DEFAULT_ORGANIZATION_ID).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(creator)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
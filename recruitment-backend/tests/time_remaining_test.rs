@@ -0,0 +1,532 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{patch, post},
+    Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use recruitment_backend::dto::public_dto::SubmitTestRequest;
+use recruitment_backend::models::test_attempt::TestAttempt;
+use recruitment_backend::services::attempt_service::{
+    accumulate_interruption, attempt_deadline, connection_loss_flags, heartbeat_gap, offline_gap,
+    time_remaining_at, AttemptService, CONNECTION_GAP_THRESHOLD_SECS,
+    ESCAPE_ACTIVITY_WINDOW_MINUTES, HEARTBEAT_GRACE_PERIOD_SECS,
+};
+use recruitment_backend::services::notification_service::NotificationService;
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn attempt(
+    started_at: Option<DateTime<Utc>>,
+    expires_at: DateTime<Utc>,
+    last_heartbeat_at: Option<DateTime<Utc>>,
+) -> TestAttempt {
+    serde_json::from_value(json!({
+        "id": Uuid::new_v4(),
+        "test_id": Uuid::new_v4(),
+        "candidate_name": "Fixture",
+        "candidate_email": "fixture@example.com",
+        "access_token": "token",
+        "expires_at": expires_at,
+        "started_at": started_at,
+        "last_heartbeat_at": last_heartbeat_at,
+        "questions_snapshot": [],
+        "status": "in_progress",
+        "honesty_declaration_accepted": false,
+        "answer_changes": 0,
+        "interruption_seconds": 0,
+        "extension_seconds": 0,
+        "suspicious_geo": false,
+        "reminders_sent": [],
+        "language": "ru",
+        "source": "integration",
+        "is_preview": false,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn offline_time_counts_against_the_candidate() {
+    let now = Utc::now();
+    let started = now - Duration::minutes(10);
+    let far = now + Duration::days(1);
+
+    // Connected the whole time: 30 min test, 10 min in.
+    let connected = attempt(Some(started), far, Some(now - Duration::seconds(5)));
+    assert_eq!(time_remaining_at(&connected, 30, now), 20 * 60);
+    assert_eq!(offline_gap(&connected, now), Duration::zero());
+
+    // Closed the browser 8 minutes ago: the same 20 minutes are left, not 28.
+    let resumed = attempt(Some(started), far, Some(now - Duration::minutes(8)));
+    assert_eq!(
+        offline_gap(&resumed, now),
+        Duration::minutes(8) - Duration::seconds(HEARTBEAT_GRACE_PERIOD_SECS)
+    );
+    assert_eq!(time_remaining_at(&resumed, 30, now), 20 * 60);
+
+    // Gone past the end of the window.
+    assert_eq!(time_remaining_at(&resumed, 5, now), 0);
+}
+
+#[test]
+fn remaining_time_is_capped_by_invite_expiry() {
+    let now = Utc::now();
+    let started = now - Duration::minutes(10);
+    let expires = now + Duration::minutes(3);
+
+    let capped = attempt(Some(started), expires, None);
+    assert_eq!(attempt_deadline(&capped, 30), expires);
+    assert_eq!(time_remaining_at(&capped, 30, now), 3 * 60);
+
+    let pending = attempt(None, now + Duration::days(2), None);
+    assert_eq!(time_remaining_at(&pending, 45, now), 45 * 60);
+}
+
+#[test]
+fn only_long_heartbeat_gaps_count_as_connection_loss() {
+    let now = Utc::now();
+    let threshold = Duration::seconds(CONNECTION_GAP_THRESHOLD_SECS);
+    assert_eq!(heartbeat_gap(now - Duration::seconds(10), now), None);
+    assert_eq!(heartbeat_gap(now - threshold, now), None);
+    assert_eq!(heartbeat_gap(now - Duration::seconds(95), now), Some(95));
+}
+
+#[test]
+fn interruptions_accumulate_up_to_the_cap() {
+    assert_eq!(accumulate_interruption(0, 45, 300), 45);
+    assert_eq!(accumulate_interruption(45, 90, 300), 135);
+    assert_eq!(accumulate_interruption(280, 45, 300), 300);
+    assert_eq!(accumulate_interruption(300, 120, 300), 300);
+    assert_eq!(accumulate_interruption(0, 45, 0), 0);
+    // Lowering the cap later doesn't take back time already granted.
+    assert_eq!(accumulate_interruption(240, 60, 120), 240);
+}
+
+#[test]
+fn interruption_time_extends_the_deadline() {
+    let now = Utc::now();
+    let started = now - Duration::minutes(10);
+    let mut interrupted = attempt(Some(started), now + Duration::days(1), Some(now));
+    interrupted.interruption_seconds = 120;
+    assert_eq!(
+        attempt_deadline(&interrupted, 30),
+        started + Duration::minutes(30) + Duration::seconds(120)
+    );
+    assert_eq!(time_remaining_at(&interrupted, 30, now), 20 * 60 + 120);
+}
+
+#[test]
+fn extensions_lengthen_the_window_past_the_duration() {
+    let now = Utc::now();
+    let started = now - Duration::minutes(25);
+    let mut extended = attempt(Some(started), started + Duration::minutes(40), Some(now));
+    extended.extension_seconds = 10 * 60;
+    assert_eq!(attempt_deadline(&extended, 30), started + Duration::minutes(40));
+    assert_eq!(time_remaining_at(&extended, 30, now), 15 * 60);
+}
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+async fn started_attempt(pool: &PgPool, test_id: Uuid, started_secs_ago: i32) -> Uuid {
+    sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, started_at, last_heartbeat_at)
+           VALUES ($1, 'Clock Test', 'clock@example.com', $2, NOW() + INTERVAL '1 hour', '[]',
+                   'in_progress', NOW() - make_interval(secs => $3), NOW())
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(started_secs_ago as f64)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn check_deadlines_times_out_attempts_past_their_duration() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Clock test', '[]', 'question_based', 0, 10) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let overdue = started_attempt(&pool, test_id, 15 * 60).await;
+    let in_grace = started_attempt(&pool, test_id, 10 * 60 + 30).await;
+    let running = started_attempt(&pool, test_id, 2 * 60).await;
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    AttemptService::new(pool.clone())
+        .check_deadlines(&notif)
+        .await
+        .unwrap();
+
+    for (id, expected) in [
+        (overdue, "timeout"),
+        (in_grace, "in_progress"),
+        (running, "in_progress"),
+    ] {
+        let status: String = sqlx::query_scalar("SELECT status FROM test_attempts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, expected);
+    }
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn extended_attempts_survive_the_deadline_sweep() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Extension test', '[]', 'question_based', 0, 10) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // Both started 12 minutes into a 10 minute test, expiring with the
+    // duration as `start_attempt_by_token` leaves them.
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let id = started_attempt(&pool, test_id, 12 * 60).await;
+        sqlx::query("UPDATE test_attempts SET expires_at = started_at + INTERVAL '10 minutes' WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        ids.push(id);
+    }
+    let (extended, overdue) = (ids[0], ids[1]);
+
+    let svc = AttemptService::new(pool.clone());
+    let attempt = svc.extend_expiry(extended, 15).await.unwrap();
+    assert_eq!(attempt.extension_seconds, 15 * 60);
+    // 13 minutes of the extended window are left.
+    let remaining = AttemptService::get_current_time_remaining(&attempt, 10);
+    assert!((12 * 60..=13 * 60).contains(&remaining), "{}", remaining);
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    svc.check_deadlines(&notif).await.unwrap();
+    for (id, expected) in [(extended, "in_progress"), (overdue, "timeout")] {
+        let status: String = sqlx::query_scalar("SELECT status FROM test_attempts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, expected);
+    }
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn status_check_credits_heartbeat_gaps_once() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes, max_grace_seconds) VALUES ('Gap test', '[]', 'question_based', 0, 10, 60) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let id = started_attempt(&pool, test_id, 5 * 60).await;
+    sqlx::query(
+        "UPDATE test_attempts SET previous_heartbeat_at = last_heartbeat_at - INTERVAL '90 seconds' WHERE id = $1",
+    )
+    .bind(id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let svc = AttemptService::new(pool.clone());
+    let attempt = svc.get_attempt_by_id(id).await.unwrap();
+    let attempt = svc.record_connection_gap(attempt, 60).await.unwrap();
+    assert_eq!(attempt.interruption_seconds, 60);
+    let losses = connection_loss_flags(attempt.suspicious_activity.as_ref());
+    assert_eq!(losses.len(), 1);
+    assert_eq!(losses[0]["gap_seconds"], 90);
+    assert_eq!(losses[0]["credited_seconds"], 60);
+
+    let again = svc.record_connection_gap(attempt, 60).await.unwrap();
+    assert_eq!(again.interruption_seconds, 60);
+    assert_eq!(
+        connection_loss_flags(again.suspicious_activity.as_ref()).len(),
+        1
+    );
+
+    // The 10 minutes are used up, but two credited minutes extend the deadline.
+    let late = started_attempt(&pool, test_id, 10 * 60 + 90).await;
+    sqlx::query("UPDATE test_attempts SET interruption_seconds = 120 WHERE id = $1")
+        .bind(late)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    svc.check_deadlines(&notif).await.unwrap();
+    let status: String = sqlx::query_scalar("SELECT status FROM test_attempts WHERE id = $1")
+        .bind(late)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "in_progress");
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+async fn silent_attempt(pool: &PgPool, test_id: Uuid, answered_mins_ago: Option<i32>) -> Uuid {
+    let id: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, started_at, last_heartbeat_at)
+           VALUES ($1, 'Silent Test', 'silent@example.com', $2, NOW() + INTERVAL '1 hour', '[]',
+                   'in_progress', NOW() - INTERVAL '20 minutes', NOW() - INTERVAL '3 minutes')
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    if let Some(mins) = answered_mins_ago {
+        sqlx::query(
+            r#"INSERT INTO answer_logs (attempt_id, question_id, answer_value, created_at)
+               VALUES ($1, 1, '"a"', NOW() - make_interval(mins => $2))"#,
+        )
+        .bind(id)
+        .bind(mins)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    id
+}
+
+#[tokio::test]
+async fn lost_heartbeat_does_not_escape_candidates_still_answering() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Escape test', '[]', 'question_based', 0, 60) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let answering = silent_attempt(&pool, test_id, Some(1)).await;
+    let stale_answer = silent_attempt(
+        &pool,
+        test_id,
+        Some(ESCAPE_ACTIVITY_WINDOW_MINUTES as i32 + 5),
+    )
+    .await;
+    let never_answered = silent_attempt(&pool, test_id, None).await;
+
+    let svc = AttemptService::new(pool.clone());
+    assert!(svc
+        .was_recently_active(answering, ESCAPE_ACTIVITY_WINDOW_MINUTES)
+        .await
+        .unwrap());
+    assert!(!svc
+        .was_recently_active(stale_answer, ESCAPE_ACTIVITY_WINDOW_MINUTES)
+        .await
+        .unwrap());
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    svc.check_deadlines(&notif).await.unwrap();
+
+    for (id, expected) in [
+        (answering, "in_progress"),
+        (stale_answer, "escaped"),
+        (never_answered, "escaped"),
+    ] {
+        let status: String = sqlx::query_scalar("SELECT status FROM test_attempts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, expected);
+    }
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn credited_interruptions_keep_answers_and_submission_open_past_expiry() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Credited test', '[]', 'question_based', 0, 10) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // `expires_at` passed a minute ago, but two credited minutes move the
+    // deadline a minute ahead.
+    let id = started_attempt(&pool, test_id, 11 * 60).await;
+    let token: String = sqlx::query_scalar(
+        "UPDATE test_attempts SET expires_at = started_at + INTERVAL '10 minutes', interruption_seconds = 120 WHERE id = $1 RETURNING access_token",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/public/tests/:token/answer",
+            patch(recruitment_backend::routes::public::save_answer),
+        )
+        .route(
+            "/api/public/tests/:token/submit",
+            post(recruitment_backend::routes::public::submit_test),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+    let send = |method: &str, path: &str, body: serde_json::Value| {
+        Request::builder()
+            .method(method)
+            .uri(format!("/api/public/tests/{}/{}", token, path))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let saved = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            "answer",
+            json!({ "question_id": 1, "answer": { "selected": 0 }, "time_spent_seconds": 5 }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(saved.status(), StatusCode::OK);
+    let submitted = app
+        .clone()
+        .oneshot(send("POST", "submit", json!({ "answers": [] })))
+        .await
+        .unwrap();
+    assert_eq!(submitted.status(), StatusCode::OK);
+    let status: String = sqlx::query_scalar("SELECT status FROM test_attempts WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "completed");
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_late_submit_does_not_overwrite_a_timeout() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Late submit test', '[]', 'question_based', 0, 10) RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let id = started_attempt(&pool, test_id, 15 * 60).await;
+    let svc = AttemptService::new(pool.clone());
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    svc.check_deadlines(&notif).await.unwrap();
+    let timed_out = svc.get_attempt_by_id(id).await.unwrap();
+    assert_eq!(timed_out.status, "timeout");
+
+    let late = svc
+        .submit_attempt_by_token(
+            &timed_out.access_token,
+            SubmitTestRequest {
+                answers: vec![],
+                status: None,
+            },
+            None,
+            None,
+        )
+        .await;
+    assert_eq!(late.unwrap_err().code(), "already_completed");
+    let after = svc.get_attempt_by_id(id).await.unwrap();
+    assert_eq!(after.status, "timeout");
+    assert_eq!(after.completed_at, timed_out.completed_at);
+    assert_eq!(after.answers, timed_out.answers);
+
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
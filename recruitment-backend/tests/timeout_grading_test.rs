@@ -0,0 +1,152 @@
+use std::env;
+
+use recruitment_backend::models::test_attempt::TestAttempt;
+use recruitment_backend::services::attempt_service::AttemptService;
+use recruitment_backend::services::notification_service::NotificationService;
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+fn mcq(id: i32) -> Value {
+    json!({
+        "id": id,
+        "type": "multiple_choice",
+        "question": format!("Question {}", id),
+        "points": 1,
+        "options": ["a", "b", "c"],
+        "correct_answer": 1
+    })
+}
+
+async fn expired_attempt(pool: &PgPool, test_id: Uuid, status: &str, answers: Value) -> Uuid {
+    sqlx::query_scalar(
+        r#"INSERT INTO test_attempts
+               (test_id, candidate_name, candidate_email, access_token, expires_at,
+                questions_snapshot, status, started_at, last_heartbeat_at, answers)
+           VALUES ($1, 'Timeout Test', 'timeout@example.com', $2, NOW() - INTERVAL '1 minute',
+                   $3, $4, NOW() - INTERVAL '20 minutes', NOW() - INTERVAL '1 minute', $5)
+           RETURNING id"#,
+    )
+    .bind(test_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .bind(json!([mcq(1), mcq(2), mcq(3), mcq(4)]))
+    .bind(status)
+    .bind(answers)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn load(pool: &PgPool, id: Uuid) -> TestAttempt {
+    sqlx::query_as("SELECT * FROM test_attempts WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn timed_out_attempts_are_graded_on_their_saved_answers() {
+    let pool = setup_pool().await;
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions, test_type, passing_score, duration_minutes) VALUES ('Timeout grading', $1, 'question_based', 40, 30) RETURNING id",
+    )
+    .bind(json!([mcq(1), mcq(2), mcq(3), mcq(4)]))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // Two right, one wrong, the last question never reached.
+    let answered = expired_attempt(
+        &pool,
+        test_id,
+        "in_progress",
+        json!([
+            { "question_id": 1, "answer": 1 },
+            { "question_id": 2, "answer": 1 },
+            { "question_id": 3, "answer": 0 },
+        ]),
+    )
+    .await;
+    let blank = expired_attempt(&pool, test_id, "in_progress", json!([])).await;
+    let never_started = expired_attempt(&pool, test_id, "pending", json!([])).await;
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    AttemptService::new(pool.clone())
+        .check_deadlines(&notif)
+        .await
+        .unwrap();
+
+    let graded = load(&pool, answered).await;
+    assert_eq!(graded.status, "timeout");
+    assert_eq!(graded.score.and_then(|s| s.to_f64()), Some(2.0));
+    assert_eq!(graded.max_score.and_then(|s| s.to_f64()), Some(4.0));
+    assert_eq!(graded.percentage.and_then(|p| p.to_f64()), Some(50.0));
+    assert_eq!(graded.passed, Some(true));
+    assert_eq!(graded.metadata.as_ref().unwrap()["timed_out"], true);
+    let per_question: Vec<Value> = serde_json::from_value(graded.graded_answers.unwrap()).unwrap();
+    assert_eq!(per_question.len(), 4);
+    assert_eq!(per_question[3]["points_earned"], 0);
+
+    let zero = load(&pool, blank).await;
+    assert_eq!(zero.status, "timeout");
+    assert_eq!(zero.percentage.and_then(|p| p.to_f64()), Some(0.0));
+    assert_eq!(zero.passed, Some(false));
+
+    assert_eq!(load(&pool, never_started).await.status, "timeout");
+
+    let payloads: Vec<Value> = sqlx::query_scalar(
+        "SELECT payload FROM webhook_logs WHERE event_type = 'test_completed' AND payload->>'attempt_id' = ANY($1)",
+    )
+    .bind(vec![
+        answered.to_string(),
+        blank.to_string(),
+        never_started.to_string(),
+    ])
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(payloads.len(), 2, "only started attempts report a result");
+    let payload = payloads
+        .iter()
+        .find(|p| p["attempt_id"] == json!(answered))
+        .unwrap();
+    assert_eq!(payload["timed_out"], true);
+    assert_eq!(payload["percentage"], 50.0);
+    assert_eq!(payload["passed"], true);
+
+    sqlx::query("DELETE FROM webhook_logs WHERE payload->>'attempt_id' = ANY($1)")
+        .bind(vec![answered.to_string(), blank.to_string()])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM test_attempts WHERE test_id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,121 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::{TimeZone, Utc};
+use recruitment_backend::routes::candidate_routes::parse_timezone_field;
+use recruitment_backend::services::candidate_service::CandidateService;
+use recruitment_backend::utils::time::{format_deadline, supported_timezones, DEFAULT_TIMEZONE};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn deadlines_are_shown_in_local_time() {
+    let expires_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    assert_eq!(
+        format_deadline(expires_at, Some("Asia/Dushanbe")),
+        "10.03.2026 17:00 (Asia/Dushanbe)"
+    );
+    assert_eq!(
+        format_deadline(expires_at, Some("Asia/Kolkata")),
+        "10.03.2026 17:30 (Asia/Kolkata)"
+    );
+    assert_eq!(format_deadline(expires_at, None), "10.03.2026 12:00 (UTC)");
+    assert_eq!(
+        format_deadline(expires_at, Some("Mars/Olympus")),
+        "10.03.2026 12:00 (UTC)"
+    );
+}
+
+#[test]
+fn timezone_field_accepts_iana_names_only() {
+    assert_eq!(
+        parse_timezone_field(" Asia/Dushanbe ").unwrap(),
+        Some("Asia/Dushanbe".to_string())
+    );
+    assert_eq!(parse_timezone_field("").unwrap(), None);
+    let err = parse_timezone_field("UTC+5").unwrap_err();
+    assert_eq!(err.code(), "invalid_timezone");
+
+    let zones = supported_timezones();
+    assert!(zones.contains(&"Asia/Dushanbe"));
+    assert!(zones.contains(&DEFAULT_TIMEZONE));
+    assert!(zones.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[tokio::test]
+async fn timezones_are_listed_and_looked_up_by_telegram_id() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let app = Router::new().route(
+        "/api/public/timezones",
+        get(recruitment_backend::routes::public::list_timezones),
+    );
+    let resp = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/public/timezones")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value =
+        serde_json::from_slice(&to_bytes(resp.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!(body["default"], "UTC");
+    assert!(body["timezones"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|z| z == "Asia/Dushanbe"));
+
+    let telegram_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 8_000_000_000;
+    let service = CandidateService::new(pool.clone());
+    let candidate = service
+        .create_candidate(
+            Some(telegram_id),
+            "Local Time".into(),
+            format!("tz_{}@example.com", telegram_id),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("Asia/Dushanbe".into()),
+        )
+        .await
+        .expect("create candidate");
+    assert_eq!(candidate.timezone.as_deref(), Some("Asia/Dushanbe"));
+    assert_eq!(
+        service.timezone_for_telegram(Some(telegram_id)).await,
+        Some("Asia/Dushanbe".to_string())
+    );
+    assert_eq!(service.timezone_for_telegram(None).await, None);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
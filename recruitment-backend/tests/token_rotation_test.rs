@@ -0,0 +1,133 @@
+use std::env;
+
+use recruitment_backend::dto::integration_dto::CreateTestPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::attempt_service::{
+    AttemptService, InviteCandidate, InviteSource,
+};
+use recruitment_backend::services::test_service::TestService;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn starting_a_test_rotates_its_token_with_a_grace_period() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let creator = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO users (id, external_id, name, email, role, is_active)
+           VALUES ($1, $2, 'Rotation User', $3, 'hr', TRUE)"#,
+    )
+    .bind(creator)
+    .bind(format!("ext-{}", creator))
+    .bind(format!("rotation_{}@example.com", creator))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let payload: CreateTestPayload = serde_json::from_value(json!({
+        "title": "Rotation Test",
+        "duration_minutes": 30,
+        "passing_score": 50.0,
+        "questions": [],
+    }))
+    .unwrap();
+    let test = TestService::new(pool.clone())
+        .create_test(payload, creator, DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+
+    let svc = AttemptService::new(pool.clone());
+    let invite = svc
+        .create_invite(
+            test.id,
+            InviteCandidate {
+                external_id: None,
+                name: "Rotated".into(),
+                email: format!("rotated_{}@example.com", Uuid::new_v4()),
+                telegram_id: None,
+                phone: None,
+            },
+            2,
+            None,
+            None,
+            InviteSource::Integration,
+        )
+        .await
+        .expect("invite");
+    let link_token = invite.access_token;
+
+    let started = svc
+        .start_attempt_by_token(&link_token, true, None, None)
+        .await
+        .expect("start");
+    let rotated = started.access_token.clone();
+    assert_ne!(rotated, link_token);
+    assert_eq!(rotated.len(), 32);
+
+    let (by_new, _) = svc.get_attempt_and_test_by_token(&rotated).await.unwrap();
+    assert_eq!(by_new.id, invite.attempt_id);
+    let (by_old, _) = svc
+        .get_attempt_and_test_by_token(&link_token)
+        .await
+        .expect("the link token works during the grace period");
+    assert_eq!(by_old.id, invite.attempt_id);
+    svc.heartbeat(&link_token).await.unwrap();
+    let heartbeat: Option<chrono::DateTime<chrono::Utc>> =
+        sqlx::query_scalar("SELECT last_heartbeat_at FROM test_attempts WHERE id = $1")
+            .bind(invite.attempt_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(heartbeat.is_some(), "heartbeat on the old token lands");
+
+    let retried = svc
+        .start_attempt_by_token(&link_token, true, None, None)
+        .await
+        .expect("a retried start on the old token");
+    assert_eq!(retried.access_token, rotated, "a retry does not rotate again");
+    assert!(svc.get_attempt_and_test_by_token(&link_token).await.is_ok());
+
+    sqlx::query(
+        "UPDATE test_attempts SET access_token_rotated_at = NOW() - INTERVAL '2 minutes' WHERE id = $1",
+    )
+    .bind(invite.attempt_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert!(svc
+        .get_attempt_and_test_by_token(&link_token)
+        .await
+        .is_err());
+    assert!(svc.get_attempt_and_test_by_token(&rotated).await.is_ok());
+
+    let resumed = svc
+        .start_attempt_by_token(&rotated, true, None, None)
+        .await
+        .expect("resume");
+    assert_ne!(resumed.access_token, rotated, "every start rotates");
+    assert!(svc.get_attempt_and_test_by_token(&rotated).await.is_ok());
+
+    sqlx::query("DELETE FROM test_attempts WHERE id = $1")
+        .bind(invite.attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
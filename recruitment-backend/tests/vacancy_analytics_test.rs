@@ -0,0 +1,271 @@
+use std::env;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use recruitment_backend::error::Error;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::services::vacancy_service::{
+    fill_weekly_series, VacancyRef, VacancyService, WeeklyApplications,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn parses_internal_and_external_ids() {
+    let id = Uuid::new_v4();
+    assert_eq!(
+        VacancyRef::parse(&id.to_string()).unwrap(),
+        VacancyRef::Internal(id)
+    );
+    assert_eq!(
+        VacancyRef::parse("1042").unwrap(),
+        VacancyRef::External(1042)
+    );
+    assert!(matches!(
+        VacancyRef::parse("backend-dev"),
+        Err(Error::BadRequest(_))
+    ));
+}
+
+#[test]
+fn weekly_series_fills_gaps() {
+    assert!(fill_weekly_series(&[]).is_empty());
+    let series = fill_weekly_series(&[(date(2025, 2, 10), 2), (date(2025, 3, 3), 1)]);
+    assert_eq!(
+        series,
+        vec![
+            WeeklyApplications {
+                week_start: date(2025, 2, 10),
+                applications: 2
+            },
+            WeeklyApplications {
+                week_start: date(2025, 2, 17),
+                applications: 0
+            },
+            WeeklyApplications {
+                week_start: date(2025, 2, 24),
+                applications: 0
+            },
+            WeeklyApplications {
+                week_start: date(2025, 3, 3),
+                applications: 1
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn aggregates_funnel_for_vacancy() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let vacancy_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 7_000_000_000;
+    let tag = Uuid::new_v4().simple().to_string();
+    let email = |name: &str| format!("{}_{}@example.com", name, tag);
+
+    let seed_candidate = |name: &'static str,
+                          vacancy: i64,
+                          status: &'static str,
+                          rating: Option<i32>,
+                          created: chrono::DateTime<Utc>,
+                          deleted: bool| {
+        let pool = pool.clone();
+        let email = email(name);
+        async move {
+            sqlx::query(
+                r#"INSERT INTO candidates (id, name, email, status, vacancy_id, ai_rating, created_at, deleted_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $8 THEN NOW() END)"#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(name)
+            .bind(email)
+            .bind(status)
+            .bind(vacancy)
+            .bind(rating)
+            .bind(created)
+            .bind(deleted)
+            .execute(&pool)
+            .await
+            .expect("seed candidate");
+        }
+    };
+    let at = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 10, 0, 0).unwrap();
+
+    seed_candidate(
+        "alisher",
+        vacancy_id,
+        "test_completed",
+        Some(80),
+        at(2025, 2, 10),
+        false,
+    )
+    .await;
+    seed_candidate(
+        "bahrom",
+        vacancy_id,
+        "test_assigned",
+        Some(61),
+        at(2025, 2, 12),
+        false,
+    )
+    .await;
+    seed_candidate("cyrus", vacancy_id, "new", None, at(2025, 2, 26), false).await;
+    seed_candidate(
+        "dilnoza",
+        vacancy_id,
+        "rejected",
+        Some(10),
+        at(2025, 2, 11),
+        true,
+    )
+    .await;
+    seed_candidate(
+        "elbek",
+        vacancy_id + 1,
+        "new",
+        Some(99),
+        at(2025, 2, 11),
+        false,
+    )
+    .await;
+
+    let test_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO tests (title, questions) VALUES ('Analytics test', '[]') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let seed_attempt = |candidate_email: String, percentage: Option<f64>, passed: Option<bool>| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query(
+                r#"INSERT INTO test_attempts
+                       (test_id, candidate_name, candidate_email, access_token, expires_at,
+                        questions_snapshot, status, percentage, passed, completed_at)
+                   VALUES ($1, 'Seed', $2, $3, NOW() + INTERVAL '1 day', '[]',
+                           CASE WHEN $4::float8 IS NULL THEN 'pending' ELSE 'completed' END,
+                           $4::float8, $5, CASE WHEN $4::float8 IS NULL THEN NULL ELSE NOW() END)"#,
+            )
+            .bind(test_id)
+            .bind(candidate_email)
+            .bind(Uuid::new_v4().to_string())
+            .bind(percentage)
+            .bind(passed)
+            .execute(&pool)
+            .await
+            .expect("seed attempt");
+        }
+    };
+
+    seed_attempt(email("alisher"), Some(80.0), Some(true)).await;
+    seed_attempt(email("alisher").to_uppercase(), Some(50.0), Some(false)).await;
+    seed_attempt(email("bahrom"), None, None).await;
+    seed_attempt(email("dilnoza"), Some(100.0), Some(true)).await;
+    seed_attempt(email("elbek"), Some(100.0), Some(true)).await;
+
+    let service = VacancyService::new(pool.clone());
+    let analytics = service
+        .analytics(VacancyRef::External(vacancy_id), DEFAULT_ORGANIZATION_ID)
+        .await
+        .expect("analytics");
+
+    assert_eq!(analytics.vacancy_id, Some(vacancy_id));
+    assert_eq!(analytics.applicants, 3);
+    assert_eq!(
+        serde_json::to_value(&analytics.by_status).unwrap(),
+        json!({ "new": 1, "test_assigned": 1, "test_completed": 1 })
+    );
+    assert_eq!(analytics.invites_sent, 3);
+    assert_eq!(analytics.attempts_completed, 2);
+    assert_eq!(analytics.attempts_passed, 1);
+    assert_eq!(analytics.average_percentage, Some(65.0));
+    assert_eq!(analytics.pass_rate, Some(50.0));
+    assert_eq!(analytics.average_ai_rating, Some(70.5));
+    assert_eq!(
+        analytics.applications_per_week,
+        vec![
+            WeeklyApplications {
+                week_start: date(2025, 2, 10),
+                applications: 2
+            },
+            WeeklyApplications {
+                week_start: date(2025, 2, 17),
+                applications: 0
+            },
+            WeeklyApplications {
+                week_start: date(2025, 2, 24),
+                applications: 1
+            },
+        ]
+    );
+
+    let internal_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO vacancies (external_id, title, company, location) VALUES ($1, 'Backend', 'Koinoti Nav', 'Dushanbe') RETURNING id",
+    )
+    .bind(vacancy_id.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let via_internal = service
+        .analytics(VacancyRef::Internal(internal_id), DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(via_internal.vacancy_id, Some(vacancy_id));
+    assert_eq!(via_internal.applicants, 3);
+
+    let unlinked_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO vacancies (title, company, location) VALUES ('Unlinked', 'Koinoti Nav', 'Dushanbe') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let unlinked = service
+        .analytics(VacancyRef::Internal(unlinked_id), DEFAULT_ORGANIZATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(unlinked.vacancy_id, None);
+    assert_eq!(unlinked.applicants, 0);
+    assert!(unlinked.pass_rate.is_none());
+
+    let missing = service
+        .analytics(
+            VacancyRef::Internal(Uuid::new_v4()),
+            DEFAULT_ORGANIZATION_ID,
+        )
+        .await;
+    assert!(matches!(missing, Err(Error::NotFound(_))));
+
+    sqlx::query("DELETE FROM vacancies WHERE id = ANY($1)")
+        .bind(vec![internal_id, unlinked_id])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM tests WHERE id = $1")
+        .bind(test_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM candidates WHERE vacancy_id IN ($1, $2)")
+        .bind(vacancy_id)
+        .bind(vacancy_id + 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,219 @@
+use std::env;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    routing::{get, patch},
+    Router,
+};
+use chrono::Utc;
+use recruitment_backend::dto::vacancy_dto::CreateVacancyPayload;
+use recruitment_backend::models::organization::DEFAULT_ORGANIZATION_ID;
+use recruitment_backend::models::vacancy_link::VacancyLink;
+use recruitment_backend::services::vacancy_link_service::{match_link, VacancyLinkService};
+use recruitment_backend::services::vacancy_service::VacancyService;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn link(koinotinav_id: Option<i64>, onef_id: Option<&str>) -> VacancyLink {
+    VacancyLink {
+        vacancy_id: Uuid::new_v4(),
+        koinotinav_id,
+        onef_id: onef_id.map(Into::into),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn resolves_internal_koinotinav_and_onef_ids() {
+    let linked = link(Some(501), Some("onef-9"));
+    let numeric_onef = link(None, Some("777"));
+    let shadowed = link(None, Some("501"));
+    let links = vec![linked.clone(), numeric_onef.clone(), shadowed];
+
+    let uuid = linked.vacancy_id.to_string().to_uppercase();
+    assert_eq!(match_link(&links, &uuid), Some(&linked));
+    assert_eq!(match_link(&links, " 501 "), Some(&linked));
+    assert_eq!(match_link(&links, "onef-9"), Some(&linked));
+    assert_eq!(match_link(&links, "777"), Some(&numeric_onef));
+    assert_eq!(match_link(&links, &Uuid::new_v4().to_string()), None);
+    assert_eq!(match_link(&links, "42"), None);
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn links_resolve_to_the_internal_vacancy() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+
+    let vacancies = VacancyService::new(pool.clone());
+    let mut ids = Vec::new();
+    for title in ["Linked Vacancy", "Other Vacancy"] {
+        let payload: CreateVacancyPayload = serde_json::from_value(json!({
+            "title": title,
+            "company": "Koinoti Nav",
+            "location": "Dushanbe",
+        }))
+        .unwrap();
+        ids.push(
+            vacancies
+                .create(payload, DEFAULT_ORGANIZATION_ID)
+                .await
+                .expect("create vacancy")
+                .id,
+        );
+    }
+    let (vacancy_id, other_id) = (ids[0], ids[1]);
+    let koinotinav_id = (Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 1;
+    let onef_id = format!("onef-{}", Uuid::new_v4().simple());
+
+    let candidate_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO candidates (id, name, email, vacancy_id) VALUES ($1, 'Linked Candidate', $2, $3)",
+    )
+    .bind(candidate_id)
+    .bind(format!("link_{}@example.com", candidate_id))
+    .bind(koinotinav_id)
+    .execute(&pool)
+    .await
+    .expect("seed candidate");
+
+    let app = Router::new()
+        .route(
+            "/api/integration/vacancies/:id/links",
+            patch(recruitment_backend::routes::vacancy::update_vacancy_links),
+        )
+        .route(
+            "/api/onef/vacancies/:id",
+            get(recruitment_backend::routes::onef::get_vacancy),
+        )
+        .route(
+            "/api/integration/candidates/:id",
+            get(recruitment_backend::routes::candidate_routes::get_candidate),
+        )
+        .with_state(recruitment_backend::AppState::new(pool.clone()));
+
+    let links_uri = format!("/api/integration/vacancies/{}/links", vacancy_id);
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &links_uri,
+        json!({ "koinotinav_id": koinotinav_id, "onef_id": onef_id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["koinotinav_id"], koinotinav_id);
+
+    let service = VacancyLinkService::new(pool.clone());
+    for raw in [
+        vacancy_id.to_string(),
+        koinotinav_id.to_string(),
+        onef_id.clone(),
+    ] {
+        let resolved = service.resolve(&raw).await.unwrap().expect("resolved");
+        assert_eq!(resolved.vacancy_id, vacancy_id);
+    }
+    assert_eq!(
+        service.koinotinav_id_for(&onef_id).await.unwrap(),
+        Some(koinotinav_id)
+    );
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/api/onef/vacancies/{}", onef_id),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], vacancy_id.to_string());
+    assert_eq!(body["title"], "Linked Vacancy");
+
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/api/integration/candidates/{}", candidate_id),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(body["vacancy_id"], koinotinav_id);
+    assert_eq!(body["canonical_vacancy"]["id"], vacancy_id.to_string());
+    assert_eq!(body["canonical_vacancy"]["title"], "Linked Vacancy");
+
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/api/integration/vacancies/{}/links", other_id),
+        json!({ "onef_id": onef_id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "vacancy_link_conflict");
+
+    let (status, body) = send(&app, "PATCH", &links_uri, json!({ "onef_id": null })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["koinotinav_id"], koinotinav_id);
+    assert!(body["onef_id"].is_null());
+    assert!(service.resolve(&onef_id).await.unwrap().is_none());
+
+    service.unlink_koinotinav(koinotinav_id).await.unwrap();
+    assert!(service
+        .canonical_for_koinotinav(koinotinav_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/api/integration/vacancies/{}/links", Uuid::new_v4()),
+        json!({ "koinotinav_id": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM candidates WHERE id = $1")
+        .bind(candidate_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM vacancies WHERE id = ANY($1)")
+        .bind(vec![vacancy_id, other_id])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
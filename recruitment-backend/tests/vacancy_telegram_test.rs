@@ -0,0 +1,271 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use recruitment_backend::models::vacancy::Vacancy;
+use recruitment_backend::services::telegram_channel_service::{
+    apply_button, registration_link, render_vacancy_post, ChannelPost, TelegramChannelService,
+    MAX_POST_DESCRIPTION_CHARS, TELEGRAM_MESSAGE_LIMIT,
+};
+use recruitment_backend::services::vacancy_service::VacancyService;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+type Calls = Arc<Mutex<Vec<(String, Value)>>>;
+
+fn vacancy(description: &str) -> Vacancy {
+    Vacancy {
+        id: Uuid::new_v4(),
+        external_id: Some("1042".into()),
+        title: "Backend-разработчик".into(),
+        company: "Koinoti Nav".into(),
+        location: "Душанбе".into(),
+        employment_type: Some("Полная занятость".into()),
+        salary_from: Some(Decimal::new(8000, 0)),
+        salary_to: Some(Decimal::new(12000, 0)),
+        currency: Some("TJS".into()),
+        negotiated_salary: false,
+        description: Some(description.into()),
+        requirements: None,
+        responsibilities: None,
+        benefits: None,
+        apply_url: None,
+        contact_email: None,
+        contact_phone: None,
+        status: "published".into(),
+        published_at: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+/// Fake Bot API: records every call and answers like Telegram does for
+/// the few situations the service cares about.
+async fn bot_api(
+    State(calls): State<Calls>,
+    Path((_bot, method)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> impl IntoResponse {
+    calls.lock().unwrap().push((method.clone(), body.clone()));
+    let fail = |code: StatusCode, description: &str| {
+        (
+            code,
+            Json(json!({ "ok": false, "error_code": code.as_u16(), "description": description })),
+        )
+    };
+    if body["chat_id"] == "@forbidden" {
+        return fail(
+            StatusCode::FORBIDDEN,
+            "Forbidden: bot is not a member of the channel chat",
+        );
+    }
+    match method.as_str() {
+        "sendMessage" => (
+            StatusCode::OK,
+            Json(json!({ "ok": true, "result": { "message_id": 42 } })),
+        ),
+        "editMessageText" if body["text"] == "unchanged" => fail(
+            StatusCode::BAD_REQUEST,
+            "Bad Request: message is not modified: specified new message content and reply markup are exactly the same",
+        ),
+        "deleteMessage" if body["message_id"] == 404 => fail(
+            StatusCode::BAD_REQUEST,
+            "Bad Request: message to delete not found",
+        ),
+        _ => (StatusCode::OK, Json(json!({ "ok": true, "result": true }))),
+    }
+}
+
+async fn mock_telegram() -> (TelegramChannelService, Calls) {
+    let calls: Calls = Arc::default();
+    let app = Router::new()
+        .route("/:bot/:method", post(bot_api))
+        .with_state(calls.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let service = TelegramChannelService::new(format!("http://{}/", addr), "123:abc");
+    (service, calls)
+}
+
+#[test]
+fn link_prefills_numeric_vacancy_id() {
+    let mut v = vacancy("");
+    assert_eq!(
+        registration_link("https://hr.example.com/", &v),
+        "https://hr.example.com/candidate/register?vacancy_id=1042"
+    );
+    v.external_id = None;
+    assert_eq!(
+        registration_link("https://hr.example.com", &v),
+        format!(
+            "https://hr.example.com/candidate/register?vacancy_id={}",
+            v.id
+        )
+    );
+    let markup = apply_button("https://hr.example.com/x");
+    assert_eq!(markup["inline_keyboard"][0][0]["text"], "Откликнуться");
+    assert_eq!(
+        markup["inline_keyboard"][0][0]["url"],
+        "https://hr.example.com/x"
+    );
+}
+
+#[test]
+fn renders_plain_text_post() {
+    let v = vacancy("<p>Пишем сервисы на <b>Rust</b>.</p><ul><li>axum</li><li>sqlx</li></ul>");
+    let text = render_vacancy_post(&v, "https://hr.example.com/x");
+    assert!(text.starts_with("Backend-разработчик\n📍 Душанбе · Koinoti Nav"));
+    assert!(text.contains("💰 8000 – 12000 TJS"));
+    assert!(text.contains("Пишем сервисы на Rust."));
+    assert!(text.contains("• axum"));
+    assert!(!text.contains('<'));
+}
+
+#[test]
+fn long_descriptions_fit_telegram_limit() {
+    let v = vacancy(&"я".repeat(10_000));
+    let text = render_vacancy_post(&v, "https://hr.example.com/x");
+    assert!(text.chars().count() <= TELEGRAM_MESSAGE_LIMIT);
+    assert!(text.contains(&format!("{}…", "я".repeat(MAX_POST_DESCRIPTION_CHARS - 1))));
+
+    let mut v = vacancy("");
+    v.title = "Т".repeat(5_000);
+    let text = render_vacancy_post(&v, "https://hr.example.com/x");
+    assert_eq!(text.chars().count(), TELEGRAM_MESSAGE_LIMIT);
+    assert!(text.ends_with("…\n\nПодробнее: https://hr.example.com/x"));
+}
+
+#[tokio::test]
+async fn posts_edits_and_deletes_channel_message() {
+    let (telegram, calls) = mock_telegram().await;
+    let markup = apply_button("https://hr.example.com/x");
+
+    let post = telegram
+        .post("@koinotinav_jobs", "Вакансия", markup.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        post,
+        ChannelPost {
+            chat_id: "@koinotinav_jobs".into(),
+            message_id: 42
+        }
+    );
+
+    telegram
+        .edit(&post, "Вакансия (обновлено)", markup.clone())
+        .await
+        .unwrap();
+    telegram.edit(&post, "unchanged", markup).await.unwrap();
+    telegram.delete(&post).await.unwrap();
+    let gone = ChannelPost {
+        message_id: 404,
+        ..post.clone()
+    };
+    telegram.delete(&gone).await.unwrap();
+
+    let calls = calls.lock().unwrap();
+    let methods: Vec<&str> = calls.iter().map(|(m, _)| m.as_str()).collect();
+    assert_eq!(
+        methods,
+        [
+            "sendMessage",
+            "editMessageText",
+            "editMessageText",
+            "deleteMessage",
+            "deleteMessage"
+        ]
+    );
+    assert_eq!(calls[0].1["chat_id"], "@koinotinav_jobs");
+    assert_eq!(
+        calls[0].1["reply_markup"]["inline_keyboard"][0][0]["text"],
+        "Откликнуться"
+    );
+    assert_eq!(calls[1].1["message_id"], 42);
+    assert_eq!(calls[1].1["text"], "Вакансия (обновлено)");
+}
+
+#[tokio::test]
+async fn telegram_errors_become_bad_gateway() {
+    let (telegram, _) = mock_telegram().await;
+    let err = telegram
+        .post("@forbidden", "Вакансия", json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "telegram_error");
+    assert!(err
+        .to_string()
+        .contains("Forbidden: bot is not a member of the channel chat"));
+
+    let resp = err.into_response();
+    assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["details"]["method"], "sendMessage");
+    assert_eq!(body["error"]["details"]["error_code"], 403);
+
+    let unreachable = TelegramChannelService::new("http://127.0.0.1:9", "123:abc");
+    let err = unreachable
+        .delete(&ChannelPost {
+            chat_id: "@koinotinav_jobs".into(),
+            message_id: 1,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "telegram_error");
+    assert!(!err.to_string().contains("123:abc"));
+}
+
+#[tokio::test]
+async fn stores_and_clears_channel_post() {
+    dotenvy::dotenv().ok();
+    env::set_var("SERVER_ADDRESS", "127.0.0.1:0");
+    env::set_var("JWT_SECRET", "test_secret_key");
+    env::set_var("WEBHOOK_SECRET", "whsec_test");
+    env::set_var("OPENAI_API_KEY", "sk-test");
+    env::set_var("TELEGRAM_BOT_WEBHOOK_URL", "http://localhost/webhook");
+
+    let _ = recruitment_backend::config::init_config();
+    let pool = recruitment_backend::database::pool::create_pool()
+        .await
+        .expect("pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    let service = VacancyService::new(pool.clone());
+
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO vacancies (title, company, location, status) VALUES ('Channel post', 'Koinoti Nav', 'Dushanbe', 'published') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(service.telegram_post(id).await.unwrap(), None);
+    let post = ChannelPost {
+        chat_id: "-1001234567890".into(),
+        message_id: 77,
+    };
+    service.set_telegram_post(id, Some(&post)).await.unwrap();
+    assert_eq!(service.telegram_post(id).await.unwrap(), Some(post));
+    service.set_telegram_post(id, None).await.unwrap();
+    assert_eq!(service.telegram_post(id).await.unwrap(), None);
+    assert!(service.telegram_post(Uuid::new_v4()).await.is_err());
+
+    sqlx::query("DELETE FROM vacancies WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -1,12 +1,17 @@
 use std::env;
 
 use axum::{
-    body::Body,
-    http::{Request, StatusCode},
-    routing::post,
+    body::{to_bytes, Body},
+    http::{HeaderMap, Request, StatusCode},
+    routing::{delete, get, post},
     Router,
 };
-use serde_json::json;
+use recruitment_backend::models::webhook_subscription::WebhookSubscription;
+use recruitment_backend::services::notification_service::{webhook_signature, NotificationService};
+use recruitment_backend::services::webhook_subscription_service::{
+    matching_subscriptions, normalize_event_types, subscription_wants,
+};
+use serde_json::{json, Value as JsonValue};
 use tower::ServiceExt;
 use uuid::Uuid;
 
@@ -24,7 +29,7 @@ async fn setup_app() -> (Router, sqlx::PgPool) {
     env::set_var("PUBLIC_RPS", "100");
     env::set_var("INTEGRATION_RPS", "100");
 
-    recruitment_backend::config::init_config().expect("init config");
+    let _ = recruitment_backend::config::init_config();
     let pool = recruitment_backend::database::pool::create_pool()
         .await
         .expect("pool");
@@ -43,6 +48,31 @@ async fn setup_app() -> (Router, sqlx::PgPool) {
             "/webhook/test-completed",
             post(recruitment_backend::routes::webhook::handle_test_completed),
         )
+        .route(
+            "/api/integration/webhooks",
+            post(recruitment_backend::routes::webhook_subscriptions::create_webhook_subscription)
+                .get(
+                    recruitment_backend::routes::webhook_subscriptions::list_webhook_subscriptions,
+                ),
+        )
+        .route(
+            "/api/integration/webhooks/:id",
+            delete(recruitment_backend::routes::webhook_subscriptions::delete_webhook_subscription),
+        )
+        .route(
+            "/api/integration/webhook-subscriptions",
+            post(recruitment_backend::routes::webhook_subscriptions::create_webhook_subscription),
+        )
+        .route(
+            "/api/integration/webhook-subscriptions/:id",
+            get(recruitment_backend::routes::webhook_subscriptions::get_webhook_subscription)
+                .patch(
+                    recruitment_backend::routes::webhook_subscriptions::update_webhook_subscription,
+                )
+                .delete(
+                    recruitment_backend::routes::webhook_subscriptions::delete_webhook_subscription,
+                ),
+        )
         .with_state(state);
 
     (app, pool)
@@ -99,3 +129,339 @@ async fn webhook_routes_require_secret_and_enqueue() {
     );
     assert_eq!(payload_json["candidate"]["name"].as_str(), Some("Alice"));
 }
+
+async fn send_json(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: JsonValue,
+) -> (StatusCode, JsonValue) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(JsonValue::Null),
+    )
+}
+
+#[test]
+fn event_types_are_normalized() {
+    let events = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        normalize_event_types(&events(&[
+            " test_completed ",
+            "test_completed",
+            "grade_revised"
+        ]))
+        .unwrap(),
+        events(&["test_completed", "grade_revised"])
+    );
+    assert_eq!(
+        normalize_event_types(&events(&["test_assigned", "*"])).unwrap(),
+        events(&["*"])
+    );
+    assert!(normalize_event_types(&events(&["test_complete"])).is_err());
+    assert!(normalize_event_types(&events(&["", " "])).is_err());
+}
+
+fn subscription(event_types: &[&str], is_active: bool) -> WebhookSubscription {
+    WebhookSubscription {
+        id: Uuid::new_v4(),
+        url: "http://consumer.example/hook".to_string(),
+        event_types: event_types.iter().map(|e| e.to_string()).collect(),
+        secret: "secret".to_string(),
+        is_active,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+#[test]
+fn subscriptions_are_filtered_by_event_and_active_flag() {
+    let events = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert!(subscription_wants(
+        &events(&["test_completed"]),
+        "test_completed"
+    ));
+    assert!(subscription_wants(&events(&["*"]), "candidate_registered"));
+    assert!(!subscription_wants(
+        &events(&["test_completed"]),
+        "test_assigned"
+    ));
+    assert!(!subscription_wants(&[], "test_completed"));
+
+    let onef = subscription(&["test_completed"], true);
+    let analytics = subscription(&["candidate_registered", "candidate_status_changed"], true);
+    let everything = subscription(&["*"], true);
+    let paused = subscription(&["*"], false);
+    let all = [onef.clone(), analytics.clone(), everything.clone(), paused];
+
+    let ids = |event: &str| {
+        matching_subscriptions(&all, event)
+            .iter()
+            .map(|s| s.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids("test_completed"), vec![onef.id, everything.id]);
+    assert_eq!(
+        ids("candidate_registered"),
+        vec![analytics.id, everything.id]
+    );
+    assert_eq!(ids("grade_revised"), vec![everything.id]);
+}
+
+#[test]
+fn signature_is_hmac_sha256_of_the_body() {
+    assert_eq!(
+        webhook_signature("completed-secret-123", br#"{"event":"ping"}"#),
+        "sha256=76353f1b6d0425d256591ec004cbf8411c7a0f891773bf1beb022bd98b07a02c"
+    );
+    assert_ne!(
+        webhook_signature("another-secret-456", br#"{"event":"ping"}"#),
+        webhook_signature("completed-secret-123", br#"{"event":"ping"}"#)
+    );
+}
+
+#[tokio::test]
+async fn subscription_updates_change_fan_out_and_deliveries_are_signed() {
+    let (app, pool) = setup_app().await;
+
+    let (received_tx, mut received_rx) =
+        tokio::sync::mpsc::unbounded_channel::<(String, axum::body::Bytes)>();
+    let receiver = Router::new().route(
+        "/analytics",
+        post(move |headers: HeaderMap, body: axum::body::Bytes| {
+            let received_tx = received_tx.clone();
+            async move {
+                let signature = headers
+                    .get("x-webhook-signature")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                received_tx.send((signature, body)).unwrap();
+                StatusCode::OK
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/analytics", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    let (status, created) = send_json(
+        &app,
+        "POST",
+        "/api/integration/webhook-subscriptions",
+        json!({ "url": hook_url, "event_types": ["candidate_registered"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    let id = Uuid::parse_str(created["id"].as_str().unwrap()).unwrap();
+    let uri = format!("/api/integration/webhook-subscriptions/{}", id);
+
+    let (status, updated) = send_json(
+        &app,
+        "PATCH",
+        &uri,
+        json!({
+            "event_types": ["candidate_registered", "candidate_status_changed"],
+            "secret": "analytics-secret-0001"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", updated);
+    assert!(updated.get("secret").is_none());
+    let (status, fetched) = send_json(&app, "GET", &uri, JsonValue::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        fetched["event_types"],
+        json!(["candidate_registered", "candidate_status_changed"])
+    );
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    let candidate_id = Uuid::new_v4();
+    let logs = notif
+        .enqueue_webhook(
+            "candidate_status_changed",
+            &json!({ "event": "candidate_status_changed", "candidate_id": candidate_id }),
+            None,
+        )
+        .await
+        .unwrap();
+    // The legacy bot webhook always gets a row of its own.
+    assert_eq!(
+        logs.iter().filter(|l| l.subscription_id.is_none()).count(),
+        1
+    );
+    let delivery = logs
+        .iter()
+        .find(|l| l.subscription_id == Some(id))
+        .expect("queued for the subscription");
+    assert_eq!(delivery.target_url, hook_url);
+
+    notif.deliver_once(delivery.id).await.unwrap();
+    let (signature, body) = received_rx.recv().await.unwrap();
+    assert_eq!(signature, webhook_signature("analytics-secret-0001", &body));
+    let body: JsonValue = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["candidate_id"], json!(candidate_id));
+    let status: String = sqlx::query_scalar("SELECT status FROM webhook_logs WHERE id = $1")
+        .bind(delivery.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "success");
+
+    let (status, _) = send_json(&app, "PATCH", &uri, json!({ "active": false })).await;
+    assert_eq!(status, StatusCode::OK);
+    let logs = notif
+        .enqueue_webhook(
+            "candidate_registered",
+            &json!({ "candidate_id": candidate_id }),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(logs.iter().all(|l| l.subscription_id != Some(id)));
+
+    let (status, _) = send_json(&app, "DELETE", &uri, JsonValue::Null).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send_json(&app, "GET", &uri, JsonValue::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    sqlx::query("DELETE FROM webhook_logs WHERE payload->>'candidate_id' = $1")
+        .bind(candidate_id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn subscriptions_only_receive_their_events() {
+    let (app, pool) = setup_app().await;
+
+    let (received_tx, mut received_rx) =
+        tokio::sync::mpsc::unbounded_channel::<(HeaderMap, JsonValue)>();
+    let receiver = Router::new().route(
+        "/hook",
+        post(
+            move |headers: HeaderMap, axum::Json(body): axum::Json<JsonValue>| {
+                let received_tx = received_tx.clone();
+                async move {
+                    received_tx.send((headers, body)).unwrap();
+                    StatusCode::OK
+                }
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    let (status, body) = send_json(
+        &app,
+        "POST",
+        "/api/integration/webhooks",
+        json!({ "url": hook_url, "event_types": ["tset_completed"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "unknown_webhook_event");
+
+    let (status, completed_only) = send_json(
+        &app,
+        "POST",
+        "/api/integration/webhooks",
+        json!({ "url": hook_url, "event_types": ["test_completed"], "secret": "completed-secret-123" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(completed_only["secret"], "completed-secret-123");
+    assert_eq!(completed_only["event_types"], json!(["test_completed"]));
+
+    let (status, everything) = send_json(
+        &app,
+        "POST",
+        "/api/integration/webhooks",
+        json!({ "url": hook_url, "event_types": ["*"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(everything["secret"].as_str().is_some_and(|s| !s.is_empty()));
+
+    let (status, listed) =
+        send_json(&app, "GET", "/api/integration/webhooks", JsonValue::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = listed.as_array().unwrap();
+    assert!(listed.iter().any(|s| s["id"] == completed_only["id"]));
+    assert!(listed.iter().all(|s| s.get("secret").is_none()));
+
+    let notif = NotificationService::new(pool.clone(), "http://localhost/webhook".to_string());
+    let marker = Uuid::new_v4();
+    let assigned = notif
+        .enqueue_webhook("test_assigned", &json!({ "marker": marker }), None)
+        .await
+        .unwrap();
+    let everything_id = Uuid::parse_str(everything["id"].as_str().unwrap()).unwrap();
+    let completed_id = Uuid::parse_str(completed_only["id"].as_str().unwrap()).unwrap();
+    let subscribers = |logs: &[recruitment_backend::models::webhook_log::WebhookLog]| {
+        logs.iter()
+            .filter_map(|l| l.subscription_id)
+            .collect::<Vec<_>>()
+    };
+    assert!(subscribers(&assigned).contains(&everything_id));
+    assert!(!subscribers(&assigned).contains(&completed_id));
+    assert!(assigned.iter().any(|l| l.subscription_id.is_none()));
+
+    let completed = notif
+        .enqueue_webhook("test_completed", &json!({ "marker": marker }), None)
+        .await
+        .unwrap();
+    assert!(subscribers(&completed).contains(&everything_id));
+    assert!(subscribers(&completed).contains(&completed_id));
+
+    let delivery = completed
+        .iter()
+        .find(|l| l.subscription_id == Some(completed_id))
+        .unwrap();
+    notif.deliver_once(delivery.id).await.unwrap();
+    let (headers, body) = received_rx.recv().await.unwrap();
+    // Subscribers prove the sender with the signature; the secret stays here.
+    assert!(headers.get("x-webhook-secret").is_none());
+    assert_eq!(
+        headers["x-webhook-signature"],
+        webhook_signature("completed-secret-123", &serde_json::to_vec(&body).unwrap()).as_str()
+    );
+    assert_eq!(body["marker"], json!(marker));
+
+    for id in [completed_id, everything_id] {
+        let (status, _) = send_json(
+            &app,
+            "DELETE",
+            &format!("/api/integration/webhooks/{}", id),
+            JsonValue::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+    let (status, _) = send_json(
+        &app,
+        "DELETE",
+        &format!("/api/integration/webhooks/{}", completed_id),
+        JsonValue::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM webhook_logs WHERE subscription_id = ANY($1)")
+            .bind(vec![completed_id, everything_id])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 0);
+}
@@ -0,0 +1,127 @@
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use recruitment_backend::config::parse_utc_offset;
+use recruitment_backend::services::export_service::ExportService;
+use recruitment_backend::services::report_service::{
+    next_weekly_run, pass_rate, ReportWeek, TopCandidate, VacancyApplications, WeeklyReport,
+};
+use uuid::Uuid;
+
+fn dushanbe() -> FixedOffset {
+    FixedOffset::east_opt(5 * 3600).unwrap()
+}
+
+#[test]
+fn parses_iso_week_labels() {
+    let week = ReportWeek::parse("2025-W07").expect("valid week");
+    assert_eq!(week, ReportWeek { year: 2025, week: 7 });
+    assert_eq!(week.monday(), NaiveDate::from_ymd_opt(2025, 2, 10).unwrap());
+    assert_eq!(week.sunday(), NaiveDate::from_ymd_opt(2025, 2, 16).unwrap());
+    assert_eq!(week.label(), "2025-W07");
+
+    assert!(ReportWeek::parse("2026-W53").is_ok());
+    assert!(ReportWeek::parse("2025-W53").is_err());
+    assert!(ReportWeek::parse("2025-07").is_err());
+    assert!(ReportWeek::parse("last week").is_err());
+}
+
+#[test]
+fn week_bounds_follow_report_timezone() {
+    let week = ReportWeek::parse("2025-W07").unwrap();
+    let (start, end) = week.bounds(dushanbe());
+    assert_eq!(start, Utc.with_ymd_and_hms(2025, 2, 9, 19, 0, 0).unwrap());
+    assert_eq!(end, Utc.with_ymd_and_hms(2025, 2, 16, 19, 0, 0).unwrap());
+
+    let (utc_start, _) = week.bounds(FixedOffset::east_opt(0).unwrap());
+    assert_eq!(utc_start, Utc.with_ymd_and_hms(2025, 2, 10, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn previous_week_uses_local_date() {
+    // Sunday evening in UTC is already Monday in Dushanbe.
+    let now = Utc.with_ymd_and_hms(2025, 2, 16, 20, 0, 0).unwrap();
+    assert_eq!(ReportWeek::previous(now, dushanbe()).week, 7);
+    assert_eq!(ReportWeek::previous(now, FixedOffset::east_opt(0).unwrap()).week, 6);
+
+    let new_year = Utc.with_ymd_and_hms(2026, 1, 5, 4, 0, 0).unwrap();
+    assert_eq!(
+        ReportWeek::previous(new_year, dushanbe()),
+        ReportWeek { year: 2026, week: 1 }
+    );
+}
+
+#[test]
+fn next_run_is_monday_nine_local() {
+    let tz = dushanbe();
+    let wednesday = Utc.with_ymd_and_hms(2025, 2, 12, 10, 0, 0).unwrap();
+    assert_eq!(
+        next_weekly_run(wednesday, tz),
+        Utc.with_ymd_and_hms(2025, 2, 17, 4, 0, 0).unwrap()
+    );
+
+    let monday_early = Utc.with_ymd_and_hms(2025, 2, 17, 3, 59, 0).unwrap();
+    assert_eq!(
+        next_weekly_run(monday_early, tz),
+        Utc.with_ymd_and_hms(2025, 2, 17, 4, 0, 0).unwrap()
+    );
+
+    let monday_at_nine = Utc.with_ymd_and_hms(2025, 2, 17, 4, 0, 0).unwrap();
+    assert_eq!(
+        next_weekly_run(monday_at_nine, tz),
+        Utc.with_ymd_and_hms(2025, 2, 24, 4, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn parses_timezone_offsets() {
+    assert_eq!(parse_utc_offset("+05:00"), FixedOffset::east_opt(5 * 3600));
+    assert_eq!(parse_utc_offset("UTC+5"), FixedOffset::east_opt(5 * 3600));
+    assert_eq!(parse_utc_offset("-0330"), FixedOffset::west_opt(3 * 3600 + 1800));
+    assert_eq!(parse_utc_offset("UTC"), FixedOffset::east_opt(0));
+    assert_eq!(parse_utc_offset("Asia/Dushanbe"), None);
+    assert_eq!(parse_utc_offset("+25:00"), None);
+}
+
+#[test]
+fn empty_week_renders_without_stats() {
+    let report = WeeklyReport::empty(ReportWeek::parse("2025-W01").unwrap());
+    assert!(report.is_empty());
+    assert_eq!(pass_rate(0, 0), None);
+
+    let text = report.to_telegram_text();
+    assert!(text.contains("30.12.2024 — 05.01.2025"));
+    assert!(text.contains("активности не было"));
+    assert!(!text.contains("🏆"));
+
+    let xlsx = ExportService::generate_weekly_report_xlsx(&report).expect("xlsx");
+    assert!(xlsx.starts_with(b"PK"));
+}
+
+#[test]
+fn busy_week_lists_sections() {
+    let mut report = WeeklyReport::empty(ReportWeek::parse("2025-W07").unwrap());
+    report.new_candidates = 3;
+    report.applications = vec![VacancyApplications {
+        vacancy_id: 42,
+        title: "Backend разработчик".into(),
+        applications: 2,
+    }];
+    report.tests_completed = 3;
+    report.tests_passed = 2;
+    report.pass_rate = pass_rate(3, 2);
+    report.average_ai_rating = Some(71.5);
+    report.top_candidates = vec![TopCandidate {
+        id: Uuid::new_v4(),
+        name: "Алишер".into(),
+        ai_rating: 88,
+        vacancy_id: Some(42),
+    }];
+
+    assert_eq!(report.pass_rate, Some(66.7));
+    let text = report.to_telegram_text();
+    assert!(text.contains("👥 Новые кандидаты: 3"));
+    assert!(text.contains("• Backend разработчик — 2"));
+    assert!(text.contains("• Сдано: 2 (66.7%)"));
+    assert!(text.contains("1. Алишер — 88%"));
+
+    assert!(ExportService::generate_weekly_report_xlsx(&report).is_ok());
+}